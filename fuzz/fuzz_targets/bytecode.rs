@@ -0,0 +1,20 @@
+#![no_main]
+use jvm_rs::class_file::bytecode;
+use libfuzzer_sys::fuzz_target;
+
+// The input is a code array. Whatever decodes has to encode and decode again to the same
+// instructions, padding and reserved bytes are the only thing allowed to change.
+fuzz_target!(|data: &[u8]| {
+    let instructions = match bytecode::decode(data) {
+        Ok(instructions) => instructions,
+        Err(err) => {
+            let _ = err.to_string();
+            return;
+        },
+    };
+    let mut code = Vec::new();
+    for instruction in &instructions {
+        bytecode::encode(instruction, &mut code).unwrap();
+    }
+    assert_eq!(bytecode::decode(&code).unwrap(), instructions);
+});
@@ -0,0 +1,37 @@
+#![no_main]
+use jvm_rs::class_file::bytecode;
+use jvm_rs::class_file::class_file::ClassFile;
+use jvm_rs::class_file::from_bytes::FromBytes;
+use jvm_rs::class_file::pos_slice::PoSlice;
+use jvm_rs::class_file::view::ClassView;
+use libfuzzer_sys::fuzz_target;
+
+// Any input has to come back as Ok or Err, never as a panic or an abort on a huge allocation
+fuzz_target!(|data: &[u8]| {
+    let eager = ClassFile::from_bytes(&PoSlice::new(data));
+    // Slices and streams share the decoders, they can't disagree
+    let stream = ClassFile::read_from(data);
+    assert_eq!(eager.is_ok(), stream.is_ok());
+    match eager {
+        Ok(class_file) => {
+            for method in class_file.methods() {
+                if let Some(code) = method.get_code_attribute() {
+                    let _ = bytecode::decode(code.code());
+                }
+            }
+        },
+        Err(err) => {
+            let _ = err.to_string();
+        },
+    }
+
+    if let Ok(view) = ClassView::parse(data) {
+        let pool = view.constant_pool();
+        for method in view.methods() {
+            let _ = method.code(pool);
+            for attribute in method.attributes().flatten() {
+                let _ = attribute.decode(pool);
+            }
+        }
+    }
+});
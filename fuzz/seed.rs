@@ -0,0 +1,27 @@
+// `cargo run --example seed` from fuzz/ fills corpus/class_file with the test classes and
+// corpus/bytecode with the code array of each of their methods
+use std::fs;
+use std::path::Path;
+
+use jvm_rs::class_file::view::ClassView;
+
+fn main() {
+    let sources = Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/tests/source_files");
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    fs::create_dir_all(corpus.join("class_file")).unwrap();
+    fs::create_dir_all(corpus.join("bytecode")).unwrap();
+    let mut entries: Vec<_> = fs::read_dir(&sources).unwrap().map(|entry| entry.unwrap().path()).collect();
+    entries.sort();
+    for path in entries.iter().filter(|path| path.extension().is_some_and(|extension| extension == "class")) {
+        let bytes = fs::read(path).unwrap();
+        let file_name = path.file_name().unwrap();
+        fs::write(corpus.join("class_file").join(file_name), &bytes).unwrap();
+        let view = ClassView::parse(&bytes).unwrap();
+        let stem = path.file_stem().unwrap().to_str().unwrap();
+        for (i, method) in view.methods().iter().enumerate() {
+            if let Some(code) = method.code(view.constant_pool()).unwrap() {
+                fs::write(corpus.join("bytecode").join(format!("{}.{}", stem, i)), code.code()).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+/*
+* `jvm_rs bench [<lib/modules>]` parses every class in a jimage with the eager ClassFile parser
+* and with ClassView, once only parsing and once reading the code of every method too. Classes
+* are read into memory first so only parsing is timed, each run is the best of three.
+*/
+use std::hint::black_box;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use class_file::class_file::ClassFile;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use class_file::view::ClassView;
+use class_path::jimage::JImage;
+use result::Result;
+use types::u1;
+
+const RUNS: usize = 3;
+
+pub fn run<W: Write>(out: &mut W, image: &JImage) -> Result<()> {
+    let mut classes = Vec::new();
+    for entry in image.entries()? {
+        if entry.extension == "class" && entry.module != "modules" && entry.module != "packages" {
+            classes.push(image.read(&entry)?);
+        }
+    }
+    let size: usize = classes.iter().map(Vec::len).sum();
+    writeln!(out, "{} classes, {:.1} MB", classes.len(), size as f64 / 1e6)?;
+
+    let eager = time(&classes, |bytes| Ok(ClassFile::from_bytes(&PoSlice::new(bytes))?.methods().len()))?;
+    let lazy = time(&classes, |bytes| Ok(ClassView::parse(bytes)?.methods().len()))?;
+    let eager_code = time(&classes, |bytes| {
+        let class_file = ClassFile::from_bytes(&PoSlice::new(bytes))?;
+        Ok(class_file.methods().iter()
+            .filter_map(|method| method.get_code_attribute())
+            .map(|code| code.code().len())
+            .sum())
+    })?;
+    let lazy_code = time(&classes, |bytes| {
+        let view = ClassView::parse(bytes)?;
+        let mut length = 0;
+        for method in view.methods() {
+            if let Some(code) = method.code(view.constant_pool())? {
+                length += code.code().len();
+            }
+        }
+        Ok(length)
+    })?;
+    for &(name, duration) in &[("eager", eager), ("lazy", lazy), ("eager code", eager_code), ("lazy code", lazy_code)] {
+        let seconds = duration.as_secs_f64();
+        writeln!(out, "{:<12} {:>9.1} ms {:>9.1} MB/s {:>5.2}x",
+            name, seconds * 1e3, size as f64 / 1e6 / seconds, eager.as_secs_f64() / seconds)?;
+    }
+    Ok(())
+}
+
+fn time<F>(classes: &[Vec<u1>], parse: F) -> Result<Duration>
+where F: Fn(&[u1]) -> Result<usize> {
+    let mut best = None;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        for bytes in classes {
+            black_box(parse(bytes)?);
+        }
+        let elapsed = start.elapsed();
+        if best.is_none_or(|best| elapsed < best) {
+            best = Some(elapsed);
+        }
+    }
+    Ok(best.unwrap_or_default())
+}
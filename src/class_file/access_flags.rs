@@ -0,0 +1,120 @@
+use types::u2;
+
+// JVMS §4.1, §4.5, §4.6. Some bits mean different things for classes, fields and methods
+pub const ACC_PUBLIC: u2 = 0x0001;
+pub const ACC_PRIVATE: u2 = 0x0002;
+pub const ACC_PROTECTED: u2 = 0x0004;
+pub const ACC_STATIC: u2 = 0x0008;
+pub const ACC_FINAL: u2 = 0x0010;
+pub const ACC_SUPER: u2 = 0x0020;
+pub const ACC_SYNCHRONIZED: u2 = 0x0020;
+pub const ACC_VOLATILE: u2 = 0x0040;
+pub const ACC_BRIDGE: u2 = 0x0040;
+pub const ACC_TRANSIENT: u2 = 0x0080;
+pub const ACC_VARARGS: u2 = 0x0080;
+pub const ACC_NATIVE: u2 = 0x0100;
+pub const ACC_INTERFACE: u2 = 0x0200;
+pub const ACC_ABSTRACT: u2 = 0x0400;
+pub const ACC_STRICT: u2 = 0x0800;
+pub const ACC_SYNTHETIC: u2 = 0x1000;
+pub const ACC_ANNOTATION: u2 = 0x2000;
+pub const ACC_ENUM: u2 = 0x4000;
+pub const ACC_MODULE: u2 = 0x8000;
+
+pub const CLASS_FLAGS: &[(u2, &str)] = &[
+    (ACC_PUBLIC, "ACC_PUBLIC"), (ACC_FINAL, "ACC_FINAL"), (ACC_SUPER, "ACC_SUPER"),
+    (ACC_INTERFACE, "ACC_INTERFACE"), (ACC_ABSTRACT, "ACC_ABSTRACT"), (ACC_SYNTHETIC, "ACC_SYNTHETIC"),
+    (ACC_ANNOTATION, "ACC_ANNOTATION"), (ACC_ENUM, "ACC_ENUM"), (ACC_MODULE, "ACC_MODULE"),
+];
+
+pub const FIELD_FLAGS: &[(u2, &str)] = &[
+    (ACC_PUBLIC, "ACC_PUBLIC"), (ACC_PRIVATE, "ACC_PRIVATE"), (ACC_PROTECTED, "ACC_PROTECTED"),
+    (ACC_STATIC, "ACC_STATIC"), (ACC_FINAL, "ACC_FINAL"), (ACC_VOLATILE, "ACC_VOLATILE"),
+    (ACC_TRANSIENT, "ACC_TRANSIENT"), (ACC_SYNTHETIC, "ACC_SYNTHETIC"), (ACC_ENUM, "ACC_ENUM"),
+];
+
+pub const METHOD_FLAGS: &[(u2, &str)] = &[
+    (ACC_PUBLIC, "ACC_PUBLIC"), (ACC_PRIVATE, "ACC_PRIVATE"), (ACC_PROTECTED, "ACC_PROTECTED"),
+    (ACC_STATIC, "ACC_STATIC"), (ACC_FINAL, "ACC_FINAL"), (ACC_SYNCHRONIZED, "ACC_SYNCHRONIZED"),
+    (ACC_BRIDGE, "ACC_BRIDGE"), (ACC_VARARGS, "ACC_VARARGS"), (ACC_NATIVE, "ACC_NATIVE"),
+    (ACC_ABSTRACT, "ACC_ABSTRACT"), (ACC_STRICT, "ACC_STRICT"), (ACC_SYNTHETIC, "ACC_SYNTHETIC"),
+];
+
+// A u2 of flags with a predicate for each flag that means something for that kind
+macro_rules! flags {
+    ($name:ident { $($is:ident => $flag:ident),* }) => {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub struct $name(u2);
+
+        impl $name {
+            pub fn new(bits: u2) -> Self {
+                $name(bits)
+            }
+
+            pub fn bits(self) -> u2 { self.0 }
+
+            #[inline]
+            pub fn contains(self, flag: u2) -> bool {
+                self.0 & flag == flag
+            }
+
+            $(pub fn $is(self) -> bool { self.contains($flag) })*
+        }
+    };
+}
+
+// The bits whose meaning doesn't depend on what they are the flags of, what MemberInfo has
+flags!(AccessFlags {
+    is_public => ACC_PUBLIC, is_private => ACC_PRIVATE, is_protected => ACC_PROTECTED,
+    is_static => ACC_STATIC, is_final => ACC_FINAL, is_synthetic => ACC_SYNTHETIC
+});
+
+flags!(ClassFlags {
+    is_public => ACC_PUBLIC, is_final => ACC_FINAL, is_super => ACC_SUPER, is_interface => ACC_INTERFACE,
+    is_abstract => ACC_ABSTRACT, is_synthetic => ACC_SYNTHETIC, is_annotation => ACC_ANNOTATION,
+    is_enum => ACC_ENUM, is_module => ACC_MODULE
+});
+
+flags!(FieldFlags {
+    is_public => ACC_PUBLIC, is_private => ACC_PRIVATE, is_protected => ACC_PROTECTED,
+    is_static => ACC_STATIC, is_final => ACC_FINAL, is_volatile => ACC_VOLATILE,
+    is_transient => ACC_TRANSIENT, is_synthetic => ACC_SYNTHETIC, is_enum => ACC_ENUM
+});
+
+flags!(MethodFlags {
+    is_public => ACC_PUBLIC, is_private => ACC_PRIVATE, is_protected => ACC_PROTECTED,
+    is_static => ACC_STATIC, is_final => ACC_FINAL, is_synchronized => ACC_SYNCHRONIZED,
+    is_bridge => ACC_BRIDGE, is_varargs => ACC_VARARGS, is_native => ACC_NATIVE,
+    is_abstract => ACC_ABSTRACT, is_strict => ACC_STRICT, is_synthetic => ACC_SYNTHETIC
+});
+
+impl ClassFlags {
+    // Names of the set flags as javap prints them, e.g. ["ACC_PUBLIC", "ACC_SUPER"]
+    pub fn names(self) -> Vec<&'static str> { names(self.0, CLASS_FLAGS) }
+
+    pub fn unknown_bits(self) -> u2 { unknown_bits(self.0, CLASS_FLAGS) }
+}
+
+impl FieldFlags {
+    pub fn names(self) -> Vec<&'static str> { names(self.0, FIELD_FLAGS) }
+
+    pub fn unknown_bits(self) -> u2 { unknown_bits(self.0, FIELD_FLAGS) }
+}
+
+impl MethodFlags {
+    pub fn names(self) -> Vec<&'static str> { names(self.0, METHOD_FLAGS) }
+
+    pub fn unknown_bits(self) -> u2 { unknown_bits(self.0, METHOD_FLAGS) }
+}
+
+// The set bits that have no name for this kind of flags
+fn unknown_bits(bits: u2, table: &[(u2, &'static str)]) -> u2 {
+    table.iter().fold(bits, |bits, &(flag, _)| bits & !flag)
+}
+
+fn names(bits: u2, table: &[(u2, &'static str)]) -> Vec<&'static str> {
+    table.iter()
+        .filter(|&&(flag, _)| bits & flag == flag)
+        .map(|&(_, name)| name)
+        .collect()
+}
@@ -1,145 +1,329 @@
-use std::rc::Rc;
-use class_file::constant_pool::{ConstantPoolRep};
-use class_file::from_bytes::FromBytes;
-use class_file::pos_slice::PoSlice;
-use result::Result;
-use types::{u1, u2, u4};
-
-const CONSTANT_VALUE: &str = "ConstantValue";
-const CODE: &str = "Code";
-const SOURCE_FILE: &str = "SourceFile";
-
-#[derive(Clone)]
-pub enum AttributeInfo {
-    Raw(RawAttribute),
-    Constant(ConstantValueAttribute),
-    Code(CodeAttribute),
-    Source(SourceFileAttribute),
-}
-
-impl<'a> AttributeInfo {
-    // TODO: do something to to_strings
-    pub fn new(slice: &'a PoSlice<'a>, constant_pool: Rc<ConstantPoolRep>) -> Result<Self> {
-        let attribute_name_index = slice.read_u2()?;
-        let info = constant_pool.get(attribute_name_index as usize)?;
-        let attribute_length = slice.read_u4()?;
-        let name = info.as_str()?;
-        Ok(match name {
-            CONSTANT_VALUE => AttributeInfo::Constant(ConstantValueAttribute::new(slice, 
-                name.to_string())?),
-            CODE => AttributeInfo::Code(CodeAttribute::new(slice, 
-                name.to_string(), 
-                constant_pool.clone())?),
-            SOURCE_FILE => AttributeInfo::Source(SourceFileAttribute::new(slice, 
-                name.to_string(), 
-                constant_pool.clone())?),
-            _ => AttributeInfo::Raw(RawAttribute::new(slice, 
-                name.to_string(), 
-                attribute_length)?),
-        })
-    }
-
-    pub fn read_attributes(slice: &'a PoSlice<'a>, constant_pool: Rc<ConstantPoolRep>) -> Result<Box<[Self]>> {
-        let attributes_length = slice.read_u2()?;
-        println!("count: {}", attributes_length);
-        let mut attribute_infos = Vec::with_capacity(attributes_length as usize);
-        for _ in 0..attributes_length {
-            let info = AttributeInfo::new(slice, constant_pool.clone())?;
-            attribute_infos.push(info);
-        }
-        Ok(attribute_infos.into_boxed_slice())
-    }
-
-    pub fn is_code(&self) -> bool {
-        match *self {
-            AttributeInfo::Code(_) => true,
-            _ => false,
-        }
-    }
-}
-
-#[derive(Clone)]
-pub struct RawAttribute {
-    pub(super) name: String,
-    pub(super) info: Box<[u1]>,
-}
-
-impl<'a> RawAttribute {
-    pub(super) fn new(slice: &PoSlice<'a>, name: String, length: u4) -> Result<Self> {
-        Ok(RawAttribute {
-            name,
-            info: slice.read_slice_vec(length as usize)?.into_boxed_slice(),
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantValueAttribute {
-    pub(super) name: String, 
-    pub(super) constant_value_index: u2,
-}
-
-impl<'a> ConstantValueAttribute {
-    pub(super) fn new(slice: &PoSlice, name: String) -> Result<Self> {
-        Ok(ConstantValueAttribute {
-            name,
-            constant_value_index: slice.read_u2()?,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct CodeAttribute {
-    pub(super) name: String,
-    pub(super) constant_pool: Rc<ConstantPoolRep>,
-    pub(super) max_stack: u2,
-    pub(super) max_locals: u2,
-    pub(super) code:  Box<[u1]>,
-    pub(super) exception_table: Box<[Exception]>,
-    pub(super) attributes: Box<[AttributeInfo]>,
-}
-
-impl<'a> CodeAttribute {
-    pub(super) fn new(slice: &'a PoSlice, name: String, constant_pool: Rc<ConstantPoolRep>) -> Result<Self> {
-        let max_stack = slice.read_u2()?;
-        let max_locals = slice.read_u2()?;
-        let code_length = slice.read_u4()?;
-        let code = slice.read_slice(code_length as usize)?.to_vec().into_boxed_slice();
-        let exception_table = <Box<[Exception]>>::from_bytes(slice)?;
-        let attributes = AttributeInfo::read_attributes(slice, constant_pool.clone())?;
-        Ok(CodeAttribute {
-            name,
-            constant_pool,
-            max_stack,
-            max_locals,
-            code,
-            exception_table,
-            attributes,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct SourceFileAttribute {
-    constant_pool: Rc<ConstantPoolRep>,
-    name: String,
-    sourcefile_index: u2,
-}
-
-impl SourceFileAttribute {
-    pub(super) fn new(slice: &PoSlice, name: String, constant_pool: Rc<ConstantPoolRep>) -> Result<Self> {
-        Ok(SourceFileAttribute {
-            constant_pool,
-            name,
-            sourcefile_index: slice.read_u2()?,
-        })
-    }
-}
-
-#[derive(Copy, Clone)]
-pub(crate) struct Exception {
-    pub(super) start_pc: u2,
-    pub(super) end_pc: u2,
-    pub(super) handler_pc: u2,
-    pub(super) catch_type: u2,
+use std::sync::Arc;
+use class_file::constant_pool::{ConstantPoolRep};
+use class_file::from_bytes::FromBytes;
+use class_file::reader::Reader;
+use result::{Result, Error};
+use trace::Category;
+use types::{u1, u2, u4};
+
+const CONSTANT_VALUE: &str = "ConstantValue";
+const CODE: &str = "Code";
+const SOURCE_FILE: &str = "SourceFile";
+const LINE_NUMBER_TABLE: &str = "LineNumberTable";
+const BOOTSTRAP_METHODS: &str = "BootstrapMethods";
+
+#[derive(Clone)]
+pub enum AttributeInfo {
+    Raw(RawAttribute),
+    Constant(ConstantValueAttribute),
+    Code(CodeAttribute),
+    Source(SourceFileAttribute),
+    LineNumberTable(LineNumberTableAttribute),
+    BootstrapMethods(BootstrapMethodsAttribute),
+}
+
+impl AttributeInfo {
+    // TODO: do something to to_strings
+    pub fn new<R: Reader>(slice: &R, constant_pool: Arc<ConstantPoolRep>) -> Result<Self> {
+        let attribute_name_index = slice.read_u2()?;
+        let name = constant_pool.utf8(attribute_name_index as usize)
+            .map_err(|err| err.within(slice.pos(), "attribute_name_index"))?;
+        let attribute_length = slice.read_u4()?;
+        trace!(Category::Attributes, "{} ({} bytes) at {}", name, attribute_length, slice.pos() - 6);
+        // Nothing inside can read past attribute_length, what it leaves unread is an error too
+        let start = slice.pos();
+        let (attribute, unread) = slice.bounded(attribute_length as usize, |slice| {
+            AttributeInfo::read_info(slice, name, attribute_length, &constant_pool)
+                .map_err(|err| match *err.cause() {
+                    Error::PastEnd(end) => Error::AttributeLength(attribute_length, end - start),
+                    _ => err,
+                })
+        }).map_err(|err| err.within(slice.pos(), name))?;
+        if unread != 0 {
+            let decoded = attribute_length as usize - unread;
+            return Err(Error::AttributeLength(attribute_length, decoded).within(slice.pos(), name));
+        }
+        Ok(attribute)
+    }
+
+    fn read_info<R: Reader>(slice: &R, name: &str, attribute_length: u4, constant_pool: &Arc<ConstantPoolRep>) -> Result<Self> {
+        Ok(match name {
+            CONSTANT_VALUE => AttributeInfo::Constant(ConstantValueAttribute::new(slice, 
+                name.to_string())?),
+            CODE => AttributeInfo::Code(CodeAttribute::new(slice, 
+                name.to_string(), 
+                constant_pool.clone())?),
+            SOURCE_FILE => AttributeInfo::Source(SourceFileAttribute::new(slice, 
+                name.to_string(), 
+                constant_pool.clone())?),
+            LINE_NUMBER_TABLE => AttributeInfo::LineNumberTable(LineNumberTableAttribute::new(slice, 
+                name.to_string())?),
+            BOOTSTRAP_METHODS => AttributeInfo::BootstrapMethods(BootstrapMethodsAttribute::new(slice, 
+                name.to_string())?),
+            _ => AttributeInfo::Raw(RawAttribute::new(slice, 
+                name.to_string(), 
+                attribute_length)?),
+        })
+    }
+
+    pub fn read_attributes<R: Reader>(slice: &R, constant_pool: Arc<ConstantPoolRep>) -> Result<Box<[Self]>> {
+        let attributes_length = slice.read_u2()?;
+        trace!(Category::Counts, "{} attributes at {}", attributes_length, slice.pos() - 2);
+        let mut attribute_infos = Vec::with_capacity(attributes_length as usize);
+        for _ in 0..attributes_length {
+            let info = AttributeInfo::new(slice, constant_pool.clone())?;
+            attribute_infos.push(info);
+        }
+        Ok(attribute_infos.into_boxed_slice())
+    }
+
+    pub fn name(&self) -> &str {
+        match *self {
+            AttributeInfo::Raw(ref attr) => &attr.name,
+            AttributeInfo::Constant(ref attr) => &attr.name,
+            AttributeInfo::Code(ref attr) => &attr.name,
+            AttributeInfo::Source(ref attr) => &attr.name,
+            AttributeInfo::LineNumberTable(ref attr) => &attr.name,
+            AttributeInfo::BootstrapMethods(ref attr) => &attr.name,
+        }
+    }
+
+    pub fn is_code(&self) -> bool {
+        matches!(*self, AttributeInfo::Code(_))
+    }
+}
+
+#[derive(Clone)]
+pub struct RawAttribute {
+    pub(super) name: String,
+    pub(super) info: Box<[u1]>,
+}
+
+impl RawAttribute {
+    pub(super) fn new<R: Reader>(slice: &R, name: String, length: u4) -> Result<Self> {
+        Ok(RawAttribute {
+            name,
+            info: slice.read_slice_vec(length as usize)?.into_boxed_slice(),
+        })
+    }
+
+    pub fn info(&self) -> &[u1] {
+        self.info.as_ref()
+    }
+}
+
+#[derive(Clone)]
+pub struct ConstantValueAttribute {
+    pub(super) name: String, 
+    pub(super) constant_value_index: u2,
+}
+
+impl ConstantValueAttribute {
+    pub(super) fn new<R: Reader>(slice: &R, name: String) -> Result<Self> {
+        Ok(ConstantValueAttribute {
+            name,
+            constant_value_index: slice.read_u2()?,
+        })
+    }
+
+    pub fn constant_value_index(&self) -> u2 { self.constant_value_index }
+}
+
+#[derive(Clone)]
+pub struct CodeAttribute {
+    pub(super) name: String,
+    pub(super) constant_pool: Arc<ConstantPoolRep>,
+    pub(super) max_stack: u2,
+    pub(super) max_locals: u2,
+    pub(super) code:  Box<[u1]>,
+    pub(super) exception_table: Box<[Exception]>,
+    pub(super) attributes: Box<[AttributeInfo]>,
+}
+
+impl CodeAttribute {
+    pub(super) fn new<R: Reader>(slice: &R, name: String, constant_pool: Arc<ConstantPoolRep>) -> Result<Self> {
+        let max_stack = slice.read_u2()?;
+        let max_locals = slice.read_u2()?;
+        let code_length = slice.read_u4()?;
+        let code = slice.read_slice_vec(code_length as usize)
+            .map_err(|err| err.within(slice.pos(), "code"))?
+            .into_boxed_slice();
+        let exception_table = <Box<[Exception]>>::from_bytes(slice)
+            .map_err(|err| err.within(slice.pos(), "exception_table"))?;
+        let attributes = AttributeInfo::read_attributes(slice, constant_pool.clone())?;
+        Ok(CodeAttribute {
+            name,
+            constant_pool,
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            attributes,
+        })
+    }
+
+    pub fn max_stack(&self) -> u2 { self.max_stack }
+
+    pub fn max_locals(&self) -> u2 { self.max_locals }
+
+    pub fn code(&self) -> &[u1] {
+        self.code.as_ref()
+    }
+
+    pub fn exception_table(&self) -> &[Exception] {
+        self.exception_table.as_ref()
+    }
+
+    pub fn attributes(&self) -> &[AttributeInfo] {
+        self.attributes.as_ref()
+    }
+
+    // Handlers covering `pc` in the order they have to be tried, see JVMS §2.10
+    pub fn handlers_at(&self, pc: u2) -> impl Iterator<Item = &Exception> {
+        self.exception_table.iter()
+            .filter(move |exception| exception.covers(pc))
+    }
+
+    // None means the handler catches everything, which is how javac compiles `finally`
+    pub fn catch_type_name(&self, exception: &Exception) -> Result<Option<&str>> {
+        match exception.catch_type {
+            0 => Ok(None),
+            index => self.constant_pool.class_name(index as usize).map(Some),
+        }
+    }
+
+    // Source line of the instruction at `pc`, if the method was compiled with line numbers
+    pub fn line_number(&self, pc: u2) -> Option<u2> {
+        self.attributes.iter()
+            .filter_map(|attr_info| {
+                match attr_info {
+                    AttributeInfo::LineNumberTable(ref table) => table.line_number(pc),
+                    _ => None,
+                }
+            })
+            .max_by_key(|&(start_pc, _)| start_pc)
+            .map(|(_, line_number)| line_number)
+    }
+}
+
+#[derive(Clone)]
+pub struct LineNumberTableAttribute {
+    pub(super) name: String,
+    pub(super) line_number_table: Box<[LineNumber]>,
+}
+
+impl LineNumberTableAttribute {
+    pub(super) fn new<R: Reader>(slice: &R, name: String) -> Result<Self> {
+        Ok(LineNumberTableAttribute {
+            name,
+            line_number_table: <Box<[LineNumber]>>::from_bytes(slice)
+                .map_err(|err| err.within(slice.pos(), "line_number_table"))?,
+        })
+    }
+
+    pub fn line_number_table(&self) -> &[LineNumber] {
+        self.line_number_table.as_ref()
+    }
+
+    // Entries are not sorted by pc, returns the closest (start_pc, line_number) before `pc`
+    fn line_number(&self, pc: u2) -> Option<(u2, u2)> {
+        self.line_number_table.iter()
+            .filter(|entry| entry.start_pc <= pc)
+            .max_by_key(|entry| entry.start_pc)
+            .map(|entry| (entry.start_pc, entry.line_number))
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct LineNumber {
+    pub(super) start_pc: u2,
+    pub(super) line_number: u2,
+}
+
+impl LineNumber {
+    pub fn start_pc(&self) -> u2 { self.start_pc }
+
+    pub fn line_number(&self) -> u2 { self.line_number }
+}
+
+#[derive(Clone)]
+pub struct BootstrapMethodsAttribute {
+    pub(super) name: String,
+    pub(super) bootstrap_methods: Box<[BootstrapMethod]>,
+}
+
+impl BootstrapMethodsAttribute {
+    pub(super) fn new<R: Reader>(slice: &R, name: String) -> Result<Self> {
+        Ok(BootstrapMethodsAttribute {
+            name,
+            bootstrap_methods: <Box<[BootstrapMethod]>>::from_bytes(slice)
+                .map_err(|err| err.within(slice.pos(), "bootstrap_methods"))?,
+        })
+    }
+
+    pub fn bootstrap_methods(&self) -> &[BootstrapMethod] {
+        self.bootstrap_methods.as_ref()
+    }
+}
+
+#[derive(Clone)]
+pub struct BootstrapMethod {
+    pub(super) bootstrap_method_ref: u2,
+    pub(super) bootstrap_arguments: Box<[u2]>,
+}
+
+impl BootstrapMethod {
+    // Index of a MethodHandle, usually LambdaMetafactory.metafactory or StringConcatFactory
+    pub fn bootstrap_method_ref(&self) -> u2 { self.bootstrap_method_ref }
+
+    // Indexes of loadable constants, see ConstantPoolRep::loadable
+    pub fn bootstrap_arguments(&self) -> &[u2] {
+        self.bootstrap_arguments.as_ref()
+    }
+}
+
+#[derive(Clone)]
+pub struct SourceFileAttribute {
+    constant_pool: Arc<ConstantPoolRep>,
+    name: String,
+    sourcefile_index: u2,
+}
+
+impl SourceFileAttribute {
+    pub(super) fn new<R: Reader>(slice: &R, name: String, constant_pool: Arc<ConstantPoolRep>) -> Result<Self> {
+        Ok(SourceFileAttribute {
+            constant_pool,
+            name,
+            sourcefile_index: slice.read_u2()?,
+        })
+    }
+
+    pub fn sourcefile_index(&self) -> u2 { self.sourcefile_index }
+
+    pub fn source_file(&self) -> Result<&str> {
+        self.constant_pool.utf8(self.sourcefile_index as usize)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Exception {
+    pub(super) start_pc: u2,
+    pub(super) end_pc: u2,
+    pub(super) handler_pc: u2,
+    pub(super) catch_type: u2,
+}
+
+impl Exception {
+    pub fn start_pc(&self) -> u2 { self.start_pc }
+
+    pub fn end_pc(&self) -> u2 { self.end_pc }
+
+    pub fn handler_pc(&self) -> u2 { self.handler_pc }
+
+    pub fn catch_type(&self) -> u2 { self.catch_type }
+
+    // end_pc is exclusive
+    pub fn covers(&self, pc: u2) -> bool {
+        self.start_pc <= pc && pc < self.end_pc
+    }
 }
\ No newline at end of file
@@ -0,0 +1,374 @@
+/*
+* Decoder and encoder for the code array of the Code attribute, JVMS chapter 6
+*/
+use std::convert::TryFrom;
+use std::iter;
+
+use class_file::pos_slice::PoSlice;
+use result::{Result, Error};
+use types::{u1, u2, u4};
+
+pub const WIDE: u1 = 0xc4;
+pub const TABLESWITCH: u1 = 0xaa;
+pub const LOOKUPSWITCH: u1 = 0xab;
+
+static MNEMONICS: [&str; 256] = [
+    "nop", "aconst_null", "iconst_m1", "iconst_0", "iconst_1", "iconst_2", "iconst_3", "iconst_4",
+    "iconst_5", "lconst_0", "lconst_1", "fconst_0", "fconst_1", "fconst_2", "dconst_0", "dconst_1",
+    "bipush", "sipush", "ldc", "ldc_w", "ldc2_w", "iload", "lload", "fload",
+    "dload", "aload", "iload_0", "iload_1", "iload_2", "iload_3", "lload_0", "lload_1",
+    "lload_2", "lload_3", "fload_0", "fload_1", "fload_2", "fload_3", "dload_0", "dload_1",
+    "dload_2", "dload_3", "aload_0", "aload_1", "aload_2", "aload_3", "iaload", "laload",
+    "faload", "daload", "aaload", "baload", "caload", "saload", "istore", "lstore",
+    "fstore", "dstore", "astore", "istore_0", "istore_1", "istore_2", "istore_3", "lstore_0",
+    "lstore_1", "lstore_2", "lstore_3", "fstore_0", "fstore_1", "fstore_2", "fstore_3", "dstore_0",
+    "dstore_1", "dstore_2", "dstore_3", "astore_0", "astore_1", "astore_2", "astore_3", "iastore",
+    "lastore", "fastore", "dastore", "aastore", "bastore", "castore", "sastore", "pop",
+    "pop2", "dup", "dup_x1", "dup_x2", "dup2", "dup2_x1", "dup2_x2", "swap",
+    "iadd", "ladd", "fadd", "dadd", "isub", "lsub", "fsub", "dsub",
+    "imul", "lmul", "fmul", "dmul", "idiv", "ldiv", "fdiv", "ddiv",
+    "irem", "lrem", "frem", "drem", "ineg", "lneg", "fneg", "dneg",
+    "ishl", "lshl", "ishr", "lshr", "iushr", "lushr", "iand", "land",
+    "ior", "lor", "ixor", "lxor", "iinc", "i2l", "i2f", "i2d",
+    "l2i", "l2f", "l2d", "f2i", "f2l", "f2d", "d2i", "d2l",
+    "d2f", "i2b", "i2c", "i2s", "lcmp", "fcmpl", "fcmpg", "dcmpl",
+    "dcmpg", "ifeq", "ifne", "iflt", "ifge", "ifgt", "ifle", "if_icmpeq",
+    "if_icmpne", "if_icmplt", "if_icmpge", "if_icmpgt", "if_icmple", "if_acmpeq", "if_acmpne", "goto",
+    "jsr", "ret", "tableswitch", "lookupswitch", "ireturn", "lreturn", "freturn", "dreturn",
+    "areturn", "return", "getstatic", "putstatic", "getfield", "putfield", "invokevirtual", "invokespecial",
+    "invokestatic", "invokeinterface", "invokedynamic", "new", "newarray", "anewarray", "arraylength", "athrow",
+    "checkcast", "instanceof", "monitorenter", "monitorexit", "wide", "multianewarray", "ifnull", "ifnonnull",
+    "goto_w", "jsr_w", "breakpoint", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "impdep1", "impdep2",
+];
+
+pub fn mnemonic(opcode: u1) -> Option<&'static str> {
+    match MNEMONICS[opcode as usize] {
+        "" => None,
+        mnemonic => Some(mnemonic),
+    }
+}
+
+const ARRAY_TYPES: [&str; 8] = ["boolean", "char", "float", "double", "byte", "short", "int", "long"];
+
+// Operand of newarray, T_BOOLEAN = 4 ... T_LONG = 11
+pub fn array_type_name(atype: u1) -> Option<&'static str> {
+    ARRAY_TYPES.get((atype as usize).wrapping_sub(4)).cloned()
+}
+
+pub fn array_type(name: &str) -> Option<u1> {
+    ARRAY_TYPES.iter()
+        .position(|&array_type| array_type == name)
+        .map(|index| index as u1 + 4)
+}
+
+pub fn opcode(mnemonic: &str) -> Option<u1> {
+    if mnemonic.is_empty() {
+        return None;
+    }
+    MNEMONICS.iter()
+        .position(|&name| name == mnemonic)
+        .map(|opcode| opcode as u1)
+}
+
+// Layout of the bytes that follow an opcode, shared by the decoder and the encoder
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OperandKind {
+    None,
+    // bipush
+    Byte,
+    // sipush
+    Short,
+    // u1, u2 after wide
+    Local,
+    Iinc,
+    // ldc
+    Constant1,
+    Constant,
+    // new, anewarray, checkcast and instanceof, a Class constant
+    Class,
+    // invokedynamic, followed by two zero bytes
+    ConstantDynamic,
+    Branch,
+    BranchWide,
+    InvokeInterface,
+    MultiANewArray,
+    NewArray,
+    TableSwitch,
+    LookupSwitch,
+}
+
+pub fn operand_kind(opcode: u1) -> OperandKind {
+    match opcode {
+        0x10 => OperandKind::Byte,
+        0x11 => OperandKind::Short,
+        0x12 => OperandKind::Constant1,
+        0x13 | 0x14 | 0xb2..=0xb8 => OperandKind::Constant,
+        0xbb | 0xbd | 0xc0 | 0xc1 => OperandKind::Class,
+        0x15..=0x19 | 0x36..=0x3a | 0xa9 => OperandKind::Local,
+        0x84 => OperandKind::Iinc,
+        0x99..=0xa8 | 0xc6 | 0xc7 => OperandKind::Branch,
+        0xc8 | 0xc9 => OperandKind::BranchWide,
+        0xb9 => OperandKind::InvokeInterface,
+        0xba => OperandKind::ConstantDynamic,
+        0xc5 => OperandKind::MultiANewArray,
+        0xbc => OperandKind::NewArray,
+        TABLESWITCH => OperandKind::TableSwitch,
+        LOOKUPSWITCH => OperandKind::LookupSwitch,
+        _ => OperandKind::None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    None,
+    // bipush and sipush
+    Int(i32),
+    // Local variable index, u1 or u2 after wide
+    Local(u2),
+    // Constant pool index, u1 for ldc
+    Constant(u2),
+    // Absolute pc of the branch target
+    Branch(u4),
+    Iinc { index: u2, delta: i16 },
+    InvokeInterface { index: u2, count: u1 },
+    MultiANewArray { index: u2, dimensions: u1 },
+    // T_BOOLEAN = 4 ... T_LONG = 11
+    NewArray(u1),
+    TableSwitch { default: u4, low: i32, high: i32, targets: Box<[u4]> },
+    LookupSwitch { default: u4, pairs: Box<[(i32, u4)]> },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+    pub pc: u4,
+    pub opcode: u1,
+    // Set when the instruction is prefixed by `wide`, pc is the pc of the prefix
+    pub wide: bool,
+    pub operand: Operand,
+}
+
+impl Instruction {
+    pub fn mnemonic(&self) -> &'static str {
+        MNEMONICS[self.opcode as usize]
+    }
+}
+
+pub struct Instructions<'a> {
+    slice: PoSlice<'a>,
+    len: usize,
+    failed: bool,
+}
+
+impl<'a> Instructions<'a> {
+    pub fn new(code: &'a [u1]) -> Self {
+        Instructions {
+            slice: PoSlice::new(code),
+            len: code.len(),
+            failed: false,
+        }
+    }
+
+    fn decode(&self) -> Result<Instruction> {
+        let slice = &self.slice;
+        let pc = slice.pos() as u4;
+        let mut opcode = slice.read_u1()?;
+        let wide = opcode == WIDE;
+        if wide {
+            opcode = slice.read_u1()?;
+        }
+        if mnemonic(opcode).is_none() {
+            return Err(Error::InvalidOpcode(opcode));
+        }
+        let branch = |offset: i32| pc.wrapping_add(offset as u4);
+        let local = || if wide { slice.read_u2() } else { slice.read_u1().map(u2::from) };
+        let operand = match operand_kind(opcode) {
+            OperandKind::Local => Operand::Local(local()?),
+            OperandKind::Iinc => Operand::Iinc {
+                index: local()?,
+                delta: if wide { slice.read_u2()? as i16 } else { i16::from(slice.read_u1()? as i8) },
+            },
+            _ if wide => return Err(Error::InvalidOpcode(opcode)),
+            OperandKind::None => Operand::None,
+            OperandKind::Byte => Operand::Int(i32::from(slice.read_u1()? as i8)),
+            OperandKind::Short => Operand::Int(i32::from(slice.read_u2()? as i16)),
+            OperandKind::Constant1 => Operand::Constant(u2::from(slice.read_u1()?)),
+            OperandKind::Constant | OperandKind::Class => Operand::Constant(slice.read_u2()?),
+            OperandKind::Branch => Operand::Branch(branch(i32::from(slice.read_u2()? as i16))),
+            OperandKind::BranchWide => Operand::Branch(branch(slice.read_u4()? as i32)),
+            OperandKind::InvokeInterface => {
+                let index = slice.read_u2()?;
+                let count = slice.read_u1()?;
+                slice.read_u1()?;
+                Operand::InvokeInterface { index, count }
+            },
+            OperandKind::ConstantDynamic => {
+                let index = slice.read_u2()?;
+                slice.read_u2()?;
+                Operand::Constant(index)
+            },
+            OperandKind::MultiANewArray => Operand::MultiANewArray {
+                index: slice.read_u2()?,
+                dimensions: slice.read_u1()?,
+            },
+            OperandKind::NewArray => Operand::NewArray(slice.read_u1()?),
+            OperandKind::TableSwitch => {
+                self.skip_padding()?;
+                let default = branch(slice.read_u4()? as i32);
+                let low = slice.read_u4()? as i32;
+                let high = slice.read_u4()? as i32;
+                if high < low {
+                    return Err(Error::InvalidOpcode(opcode));
+                }
+                let count = (i64::from(high) - i64::from(low) + 1) as usize;
+                if count > (self.len - slice.pos()) / 4 {
+                    return Err(Error::OutOfBounds(self.len));
+                }
+                let targets = (0..count)
+                    .map(|_| Ok(branch(slice.read_u4()? as i32)))
+                    .collect::<Result<Vec<_>>>()?;
+                Operand::TableSwitch { default, low, high, targets: targets.into_boxed_slice() }
+            },
+            OperandKind::LookupSwitch => {
+                self.skip_padding()?;
+                let default = branch(slice.read_u4()? as i32);
+                let count = slice.read_u4()? as usize;
+                if count > (self.len - slice.pos()) / 8 {
+                    return Err(Error::OutOfBounds(self.len));
+                }
+                let pairs = (0..count)
+                    .map(|_| Ok((slice.read_u4()? as i32, branch(slice.read_u4()? as i32))))
+                    .collect::<Result<Vec<_>>>()?;
+                Operand::LookupSwitch { default, pairs: pairs.into_boxed_slice() }
+            },
+        };
+        Ok(Instruction {
+            pc,
+            opcode,
+            wide,
+            operand,
+        })
+    }
+
+    // Switch operands start at a multiple of four from the start of the code
+    fn skip_padding(&self) -> Result<()> {
+        while !self.slice.pos().is_multiple_of(4) {
+            self.slice.read_u1()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = Result<Instruction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.slice.pos() >= self.len {
+            return None;
+        }
+        let instruction = self.decode();
+        self.failed = instruction.is_err();
+        Some(instruction)
+    }
+}
+
+pub fn decode(code: &[u1]) -> Result<Vec<Instruction>> {
+    Instructions::new(code).collect()
+}
+
+// Inverse of decode, appends the instruction to `code`. `instruction.pc` has to be the offset it
+// ends up at, switch padding and branch offsets are relative to it.
+pub fn encode(instruction: &Instruction, code: &mut Vec<u1>) -> Result<()> {
+    let &Instruction { pc, opcode, wide, ref operand } = instruction;
+    let kind = operand_kind(opcode);
+    if mnemonic(opcode).is_none() || wide && kind != OperandKind::Local && kind != OperandKind::Iinc {
+        return Err(Error::InvalidOpcode(opcode));
+    }
+    if wide {
+        code.push(WIDE);
+    }
+    code.push(opcode);
+    let offset = |target: u4| target.wrapping_sub(pc) as i32;
+    match (kind, operand) {
+        (OperandKind::None, Operand::None) => (),
+        (OperandKind::Byte, &Operand::Int(value)) => code.push(narrow::<i8>(value)? as u1),
+        (OperandKind::Short, &Operand::Int(value)) => push_u2(code, narrow::<i16>(value)? as u2),
+        (OperandKind::Local, &Operand::Local(index)) if wide => push_u2(code, index),
+        (OperandKind::Local, &Operand::Local(index)) => code.push(narrow::<u1>(index)?),
+        (OperandKind::Iinc, &Operand::Iinc { index, delta }) if wide => {
+            push_u2(code, index);
+            push_u2(code, delta as u2);
+        },
+        (OperandKind::Iinc, &Operand::Iinc { index, delta }) => {
+            code.push(narrow::<u1>(index)?);
+            code.push(narrow::<i8>(delta)? as u1);
+        },
+        (OperandKind::Constant1, &Operand::Constant(index)) => code.push(narrow::<u1>(index)?),
+        (OperandKind::Constant, &Operand::Constant(index)) |
+        (OperandKind::Class, &Operand::Constant(index)) => push_u2(code, index),
+        (OperandKind::ConstantDynamic, &Operand::Constant(index)) => {
+            push_u2(code, index);
+            push_u2(code, 0);
+        },
+        (OperandKind::Branch, &Operand::Branch(target)) => 
+            push_u2(code, narrow::<i16>(offset(target))? as u2),
+        (OperandKind::BranchWide, &Operand::Branch(target)) => push_u4(code, offset(target) as u4),
+        (OperandKind::InvokeInterface, &Operand::InvokeInterface { index, count }) => {
+            push_u2(code, index);
+            code.push(count);
+            code.push(0);
+        },
+        (OperandKind::MultiANewArray, &Operand::MultiANewArray { index, dimensions }) => {
+            push_u2(code, index);
+            code.push(dimensions);
+        },
+        (OperandKind::NewArray, &Operand::NewArray(atype)) => code.push(atype),
+        (OperandKind::TableSwitch, &Operand::TableSwitch { default, low, high, ref targets }) => {
+            if i64::from(high) - i64::from(low) + 1 != targets.len() as i64 {
+                return Err(Error::InvalidOpcode(opcode));
+            }
+            pad(code, pc);
+            push_u4(code, offset(default) as u4);
+            push_u4(code, low as u4);
+            push_u4(code, high as u4);
+            for &target in targets.iter() {
+                push_u4(code, offset(target) as u4);
+            }
+        },
+        (OperandKind::LookupSwitch, &Operand::LookupSwitch { default, ref pairs }) => {
+            pad(code, pc);
+            push_u4(code, offset(default) as u4);
+            push_u4(code, pairs.len() as u4);
+            for &(key, target) in pairs.iter() {
+                push_u4(code, key as u4);
+                push_u4(code, offset(target) as u4);
+            }
+        },
+        _ => return Err(Error::InvalidOpcode(opcode)),
+    }
+    Ok(())
+}
+
+fn narrow<T: TryFrom<i64>>(value: impl Into<i64>) -> Result<T> {
+    let value = value.into();
+    T::try_from(value).map_err(|_| Error::OutOfBounds(value as usize))
+}
+
+// The padding is counted from the start of the code array, `code` may have other bytes before it
+fn pad(code: &mut Vec<u1>, pc: u4) {
+    let padding = (4 - (pc as usize + 1) % 4) % 4;
+    code.extend(iter::repeat_n(0, padding));
+}
+
+fn push_u2(code: &mut Vec<u1>, value: u2) {
+    code.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_u4(code: &mut Vec<u1>, value: u4) {
+    code.extend_from_slice(&value.to_be_bytes());
+}
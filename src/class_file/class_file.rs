@@ -1,137 +1,349 @@
-use std::rc::Rc;
-use class_file::attribute_info::{AttributeInfo, CodeAttribute};
-use class_file::constant_pool::{ConstantPoolRep, CPInfoRep};
-use class_file::pos_slice::PoSlice;
-use result::{Result, Error};
-use types::{u2, u4};
-
-#[allow(dead_code)]
-pub struct ClassFile {
-    pub(super) magic:          u4,
-    pub(super) minor_version:  u2,
-    pub(super) major_version:  u2,
-    pub(super) constant_pool:  Rc<ConstantPoolRep>,
-    pub(super) access_flags:   u2,
-    pub(super) this_class:     u2,
-    pub(super) super_class:    u2,
-    pub(super) interfaces:     Box<[u2]>,
-    pub(super) fields:         Box<[MemberInfo]>,
-    pub(super) methods:        Box<[MemberInfo]>,
-    pub(super) attributes:     Box<[AttributeInfo]>,  
-}
-
-impl<'a> ClassFile {
-    pub fn minor_version(&self) -> u2 { self.minor_version }
-
-    pub fn major_version(&self) -> u2 { self.major_version }
-    
-    pub fn constant_pool(&self) -> Rc<ConstantPoolRep> {
-        self.constant_pool.clone()
-    }
-
-    pub fn interfaces(&self) -> &[u2] {
-        self.interfaces.as_ref()
-    }
-
-    pub fn fields(&self) -> &[MemberInfo] {
-        self.fields.as_ref()
-    } 
-
-    pub fn methods(&self) -> &[MemberInfo] {
-        self.methods.as_ref()
-    }
-
-    pub fn name(&self) -> Result<&str> {
-        let class_info = match self.constant_pool.get(self.this_class as usize)? {
-            CPInfoRep::Class(class_info) => Ok(class_info),
-            cp_info => Err(Error::WrongTag(cp_info.tag())),
-        };
-        match self.constant_pool.get(class_info?.name_index as usize)? {
-            CPInfoRep::Utf8(utf8_info) => Ok(&utf8_info.0),
-            cp_info => Err(Error::WrongTag(cp_info.tag())),
-        }
-    }
-
-    pub fn super_name(&self) -> Result<&str> {
-        let class_info = match self.constant_pool.get(self.super_class as usize)? {
-            CPInfoRep::Class(class_info) => Ok(class_info),
-            cp_info => Err(Error::WrongTag(cp_info.tag())),
-        };
-        match self.constant_pool.get(class_info?.name_index as usize)? {
-            CPInfoRep::Utf8(utf8_info) => Ok(&utf8_info.0),
-            cp_info => Err(Error::WrongTag(cp_info.tag())),
-        }
-    } 
-
-    pub fn get_main_method(&self) -> Result<&MemberInfo> {
-        for member_info in self.methods.iter() {
-            if member_info.get_name()? == "main" && member_info.get_descriptor()? == "([Ljava/lang/String;)V" {
-                return Ok(member_info);
-            }
-        }
-        Err(Error::MainNotFound)
-    }
-}
-
-pub struct MemberInfo {
-    pub(super) constant_pool: Rc<ConstantPoolRep>,
-    pub(super) access_flags: u2,
-    pub(super) name_index: u2,
-    pub(super) descriptor_index: u2,
-    pub(super) attributes: Box<[AttributeInfo]>,
-}
-
-impl<'a> MemberInfo {
-    pub fn new(slice: &'a PoSlice<'a>, constant_pool: Rc<ConstantPoolRep>) -> Result<Self> {
-        let access_flags = slice.read_u2()?;
-        let name_index = slice.read_u2()?;
-        let descriptor_index = slice.read_u2()?;
-        let attributes = AttributeInfo::read_attributes(slice, constant_pool.clone())?;
-        Ok(MemberInfo {
-            constant_pool,
-            access_flags,
-            name_index,
-            descriptor_index,
-            attributes,
-        })
-    }
-
-    pub fn read_members(slice: &'a PoSlice<'a>, constant_pool: Rc<ConstantPoolRep>) -> Result<Box<[Self]>> {
-        let member_count = slice.read_u2()?;
-        let mut vec = Vec::with_capacity(member_count as usize);
-        for _ in 0..member_count {
-            let member_info = MemberInfo::new(slice, constant_pool.clone())?;
-            vec.push(member_info);
-        }
-        Ok(vec.into_boxed_slice())
-    }
-
-    pub fn access_flags(&self) -> u2 { 
-        self.access_flags 
-    }
-
-    pub fn get_name(&self) -> Result<&str> {
-        self.get_str(self.name_index as usize)
-    }
-
-    pub fn get_descriptor(&self) -> Result<&str> {
-        self.get_str(self.descriptor_index as usize)
-    }
-
-    // use find_map when it gets stable -> https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.find_map
-    pub fn get_code_attribute(&self) -> Option<&CodeAttribute> {
-        self.attributes.iter()
-            .filter_map(|attr_info| {
-                match attr_info {
-                    AttributeInfo::Code(ref code_info) => Some(code_info),
-                    _ => None,
-                }
-            })
-            .next()
-    }
-
-    #[inline]
-    fn get_str(&self, index: usize) -> Result<&str> {
-        self.constant_pool.get(index)?.as_str()
-    }
-}
\ No newline at end of file
+use std::io;
+use std::sync::Arc;
+use class_file::access_flags::{AccessFlags, ClassFlags, FieldFlags, MethodFlags};
+use class_file::attribute_info::{AttributeInfo, BootstrapMethod, CodeAttribute, ConstantValueAttribute};
+use class_file::constant_pool::ConstantPoolRep;
+use class_file::descriptor::{FieldType, MethodDescriptor};
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use class_file::reader::{Reader, Stream};
+use result::{Result, Error};
+use trace::Category;
+use types::{u1, u2, u4};
+
+pub const MAGIC: u4 = 0xCAFE_BABE;
+
+// What InnerClasses says of a nested class: the class it is a member of and its simple name,
+// None for local and anonymous classes
+pub struct InnerClass<'a> {
+    pub outer_class: Option<&'a str>,
+    pub inner_name:  Option<&'a str>,
+    pub flags:       u2,
+}
+
+// Where a local or anonymous class is: the class and the name and descriptor of the method, None
+// for one in an initializer
+pub struct EnclosingMethod<'a> {
+    pub class:  &'a str,
+    pub method: Option<(&'a str, &'a str)>,
+}
+
+pub struct ClassFile {
+    pub(super) magic:          u4,
+    pub(super) minor_version:  u2,
+    pub(super) major_version:  u2,
+    pub(super) constant_pool:  Arc<ConstantPoolRep>,
+    pub(super) access_flags:   u2,
+    pub(super) this_class:     u2,
+    pub(super) super_class:    u2,
+    pub(super) interfaces:     Box<[u2]>,
+    pub(super) fields:         Box<[MemberInfo]>,
+    pub(super) methods:        Box<[MemberInfo]>,
+    pub(super) attributes:     Box<[AttributeInfo]>,  
+}
+
+impl ClassFile {
+    // Reads exactly one class from `reader`, errors carry the offset where parsing stopped
+    pub fn read_from<R: io::Read>(reader: R) -> Result<Self> {
+        ClassFile::from_bytes(&Stream::new(reader))
+    }
+
+    pub fn magic(&self) -> u4 { self.magic }
+
+    pub fn minor_version(&self) -> u2 { self.minor_version }
+
+    pub fn major_version(&self) -> u2 { self.major_version }
+
+    pub fn access_flags(&self) -> ClassFlags {
+        ClassFlags::new(self.access_flags)
+    }
+
+    pub fn this_class(&self) -> u2 { self.this_class }
+
+    // 0 only for java/lang/Object and module-info
+    pub fn super_class(&self) -> u2 { self.super_class }
+    
+    pub fn constant_pool(&self) -> &ConstantPoolRep {
+        &self.constant_pool
+    }
+
+    // Shared with the members and attributes, clone this only to keep the pool past the class
+    pub fn shared_constant_pool(&self) -> &Arc<ConstantPoolRep> {
+        &self.constant_pool
+    }
+
+    pub fn interfaces(&self) -> &[u2] {
+        self.interfaces.as_ref()
+    }
+
+    pub fn interface_names(&self) -> Result<Vec<&str>> {
+        self.interfaces.iter()
+            .map(|&index| self.constant_pool.class_name(index as usize))
+            .collect()
+    }
+
+    pub fn fields(&self) -> &[MemberInfo] {
+        self.fields.as_ref()
+    } 
+
+    pub fn methods(&self) -> &[MemberInfo] {
+        self.methods.as_ref()
+    }
+
+    pub fn attributes(&self) -> &[AttributeInfo] {
+        self.attributes.as_ref()
+    }
+
+    // Name of the source file for stack traces, None if compiled without -g:source
+    pub fn source_file(&self) -> Result<Option<&str>> {
+        for attr_info in self.attributes.iter() {
+            if let AttributeInfo::Source(ref source_file) = *attr_info {
+                return source_file.source_file().map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn name(&self) -> Result<&str> {
+        self.constant_pool.class_name(self.this_class as usize)
+    }
+
+    pub fn super_name(&self) -> Result<&str> {
+        self.constant_pool.class_name(self.super_class as usize)
+    } 
+
+    // The NestHost attribute, a class without one is the host of its own nest
+    pub fn nest_host(&self) -> Result<Option<&str>> {
+        match self.raw_attribute("NestHost") {
+            Some(info) => Ok(Some(self.constant_pool.class_name(PoSlice::new(info).read_u2()? as usize)?)),
+            None => Ok(None),
+        }
+    }
+
+    // The classes the NestMembers attribute of a nest host lists
+    pub fn nest_members(&self) -> Result<Vec<&str>> {
+        let mut members = Vec::new();
+        if let Some(info) = self.raw_attribute("NestMembers") {
+            let slice = PoSlice::new(info);
+            for _ in 0..slice.read_u2()? {
+                members.push(self.constant_pool.class_name(slice.read_u2()? as usize)?);
+            }
+        }
+        Ok(members)
+    }
+
+    // The inner_class_access_flags the InnerClasses attribute gives the class, None when it
+    // doesn't list it
+    pub fn inner_class_flags(&self, class_name: &str) -> Result<Option<u2>> {
+        Ok(self.inner_class(class_name)?.map(|inner| inner.flags))
+    }
+
+    // The InnerClasses entry of the class, None when the attribute doesn't list it
+    pub fn inner_class(&self, class_name: &str) -> Result<Option<InnerClass<'_>>> {
+        if let Some(info) = self.raw_attribute("InnerClasses") {
+            let slice = PoSlice::new(info);
+            for _ in 0..slice.read_u2()? {
+                let inner_class = slice.read_u2()?;
+                let outer_class = slice.read_u2()?;
+                let inner_name = slice.read_u2()?;
+                let flags = slice.read_u2()?;
+                if self.constant_pool.class_name(inner_class as usize)? == class_name {
+                    return Ok(Some(InnerClass {
+                        outer_class: if outer_class == 0 { None } else { Some(self.constant_pool.class_name(outer_class as usize)?) },
+                        inner_name: if inner_name == 0 { None } else { Some(self.constant_pool.utf8(inner_name as usize)?) },
+                        flags,
+                    }));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // The EnclosingMethod attribute of a local or anonymous class
+    pub fn enclosing_method(&self) -> Result<Option<EnclosingMethod<'_>>> {
+        match self.raw_attribute("EnclosingMethod") {
+            Some(info) => {
+                let slice = PoSlice::new(info);
+                let class = self.constant_pool.class_name(slice.read_u2()? as usize)?;
+                let method = slice.read_u2()?;
+                let method = if method == 0 { None } else { Some(self.constant_pool.name_and_type(method as usize)?) };
+                Ok(Some(EnclosingMethod { class, method }))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn raw_attribute(&self, name: &str) -> Option<&[u1]> {
+        raw_attribute(&self.attributes, name)
+    }
+
+    // Referenced by the bootstrap_method_attr_index of Dynamic and InvokeDynamic constants
+    pub fn bootstrap_methods(&self) -> &[BootstrapMethod] {
+        self.attributes.iter()
+            .filter_map(|attr_info| {
+                match attr_info {
+                    AttributeInfo::BootstrapMethods(ref bootstrap_methods) => 
+                        Some(bootstrap_methods.bootstrap_methods()),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or(&[])
+    }
+
+    // Looks only at the methods declared by this class, superclasses are resolver's job
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Result<Option<&MemberInfo>> {
+        MemberInfo::find(&self.methods, name, descriptor)
+    }
+
+    // Same as find_method, superclasses and superinterfaces are searched by the resolver
+    pub fn find_field(&self, name: &str, descriptor: &str) -> Result<Option<&MemberInfo>> {
+        MemberInfo::find(&self.fields, name, descriptor)
+    }
+
+    pub fn get_main_method(&self) -> Result<&MemberInfo> {
+        self.find_method("main", "([Ljava/lang/String;)V")?
+            .ok_or(Error::MainNotFound)
+    }
+}
+
+pub struct MemberInfo {
+    pub(super) constant_pool: Arc<ConstantPoolRep>,
+    pub(super) access_flags: u2,
+    pub(super) name_index: u2,
+    pub(super) descriptor_index: u2,
+    pub(super) attributes: Box<[AttributeInfo]>,
+}
+
+impl MemberInfo {
+    // `kind` is "field" or "method", errors are reported inside e.g. `method main([Ljava/lang/String;)V`
+    pub fn new<R: Reader>(slice: &R, constant_pool: Arc<ConstantPoolRep>, kind: &str) -> Result<Self> {
+        let access_flags = slice.read_u2()?;
+        let name_index = slice.read_u2()?;
+        let descriptor_index = slice.read_u2()?;
+        trace!(Category::Members, "{} at {}", member_label(&constant_pool, kind, name_index, descriptor_index), slice.pos() - 6);
+        let attributes = AttributeInfo::read_attributes(slice, constant_pool.clone())
+            .map_err(|err| err.within(slice.pos(), member_label(&constant_pool, kind, name_index, descriptor_index)))?;
+        Ok(MemberInfo {
+            constant_pool,
+            access_flags,
+            name_index,
+            descriptor_index,
+            attributes,
+        })
+    }
+
+    pub fn read_members<R: Reader>(slice: &R, constant_pool: Arc<ConstantPoolRep>, kind: &str) -> Result<Box<[Self]>> {
+        let member_count = slice.read_u2()?;
+        trace!(Category::Counts, "{} {}s at {}", member_count, kind, slice.pos() - 2);
+        let mut vec = Vec::with_capacity(member_count as usize);
+        for _ in 0..member_count {
+            let member_info = MemberInfo::new(slice, constant_pool.clone(), kind)?;
+            vec.push(member_info);
+        }
+        Ok(vec.into_boxed_slice())
+    }
+
+    pub fn access_flags(&self) -> AccessFlags {
+        AccessFlags::new(self.access_flags)
+    }
+
+    // For a field, the volatile and transient bits are bridge and varargs on a method
+    pub fn field_flags(&self) -> FieldFlags {
+        FieldFlags::new(self.access_flags)
+    }
+
+    pub fn method_flags(&self) -> MethodFlags {
+        MethodFlags::new(self.access_flags)
+    }
+
+    pub fn name_index(&self) -> u2 { self.name_index }
+
+    pub fn descriptor_index(&self) -> u2 { self.descriptor_index }
+
+    pub fn attributes(&self) -> &[AttributeInfo] {
+        self.attributes.as_ref()
+    }
+
+    pub fn get_name(&self) -> Result<&str> {
+        self.get_str(self.name_index as usize)
+    }
+
+    pub fn get_descriptor(&self) -> Result<&str> {
+        self.get_str(self.descriptor_index as usize)
+    }
+
+    pub fn get_method_descriptor(&self) -> Result<MethodDescriptor> {
+        MethodDescriptor::parse(self.get_descriptor()?)
+    }
+
+    pub fn get_field_type(&self) -> Result<FieldType> {
+        FieldType::parse(self.get_descriptor()?)
+    }
+
+    // Initial value of a static field, only meaningful when the field is static
+    pub fn get_constant_value(&self) -> Option<&ConstantValueAttribute> {
+        self.attributes.iter()
+            .filter_map(|attr_info| {
+                match attr_info {
+                    AttributeInfo::Constant(ref constant_value) => Some(constant_value),
+                    _ => None,
+                }
+            })
+            .next()
+    }
+
+    // The classes the Exceptions attribute of a method lists
+    pub fn exceptions(&self) -> Result<Vec<&str>> {
+        let mut exceptions = Vec::new();
+        if let Some(info) = raw_attribute(&self.attributes, "Exceptions") {
+            let slice = PoSlice::new(info);
+            for _ in 0..slice.read_u2()? {
+                exceptions.push(self.constant_pool.class_name(slice.read_u2()? as usize)?);
+            }
+        }
+        Ok(exceptions)
+    }
+
+    // use find_map when it gets stable -> https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.find_map
+    pub fn get_code_attribute(&self) -> Option<&CodeAttribute> {
+        self.attributes.iter()
+            .filter_map(|attr_info| {
+                match attr_info {
+                    AttributeInfo::Code(ref code_info) => Some(code_info),
+                    _ => None,
+                }
+            })
+            .next()
+    }
+
+    fn find<'m>(members: &'m [MemberInfo], name: &str, descriptor: &str) -> Result<Option<&'m MemberInfo>> {
+        for member_info in members {
+            if member_info.get_name()? == name && member_info.get_descriptor()? == descriptor {
+                return Ok(Some(member_info));
+            }
+        }
+        Ok(None)
+    }
+
+    #[inline]
+    fn get_str(&self, index: usize) -> Result<&str> {
+        self.constant_pool.utf8(index)
+    }
+}
+
+// `method main([Ljava/lang/String;)V` or `field count I`, indexes are kept when they don't resolve
+fn member_label(constant_pool: &ConstantPoolRep, kind: &str, name_index: u2, descriptor_index: u2) -> String {
+    let utf8 = |index: u2| constant_pool.utf8(index as usize).map(str::to_string)
+        .unwrap_or_else(|_| format!("#{}", index));
+    let descriptor = utf8(descriptor_index);
+    let separator = if descriptor.starts_with('(') { "" } else { " " };
+    format!("{} {}{}{}", kind, utf8(name_index), separator, descriptor)
+}
+
+fn raw_attribute<'a>(attributes: &'a [AttributeInfo], name: &str) -> Option<&'a [u1]> {
+    attributes.iter().find_map(|attr_info| match *attr_info {
+        AttributeInfo::Raw(ref raw) if attr_info.name() == name => Some(raw.info()),
+        _ => None,
+    })
+}
@@ -1,412 +1,849 @@
-use num_traits::FromPrimitive;
-
-use class_file::pos_slice::PoSlice;
-use types::{u1, u2, u4};
-use result::{Result, Error};
-
-/* Working on progress */
-
-pub struct ConstantPoolRep(Vec<Option<CPInfoRep>>);
-
-impl ConstantPoolRep {
-    pub fn read<'a>(slice: &'a PoSlice) -> Result<Self> {
-        let cp_count = slice.read_u2()? as usize;
-        let mut constant_pool = vec![None; cp_count];
-        {
-            let mut jump = true;
-            for cp_info in constant_pool.iter_mut() {
-                *cp_info = if !jump {
-                    let cp_info = CPInfoRep::new(slice)?;
-                    jump = match cp_info {
-                        CPInfoRep::Double(_) | CPInfoRep::Long(_) => true,
-                        _ => false, 
-                    };
-                    Some(cp_info)
-                } else {
-                    jump = false;
-                    None
-                }
-            }
-        }
-        Ok(ConstantPoolRep(constant_pool))
-    }
-
-    pub fn get(&self, index: usize) -> Result<&CPInfoRep> {
-        match self.0.get(index) {
-            Some(cp_info) => match cp_info {
-                Some(cp_info) => Ok(cp_info),
-                None => Err(Error::EmptyCPInfo(index as u2)),  
-            },
-            None => Err(Error::OutOfBounds(index))
-        }
-    }
-}
-
-// replacement of CPInfo
-#[derive(Clone)]
-pub enum CPInfoRep {
-    Class(ConstantClass),
-    Fieldref(ConstantFieldref),
-    Methodref(ConstantMethodref),
-    InterfaceMethodref(ConstantInterfaceMethodref),
-    String(ConstantString),
-    Integer(ConstantInteger),
-    Float(ConstantFloat),
-    Long(ConstantLong),
-    Double(ConstantDouble),
-    NameAndType(ConstantNameAndType),
-    Utf8(ConstantUtf8),
-    MethodHandle(ConstantMethodHandle),
-    MethodType(ConstantMethodType),
-    InvokeDynamic(ConstantInvokeDynamic),
-}
-
-impl CPInfoRep {
-    pub fn new(slice: &PoSlice) -> Result<Self> {
-        let tag = slice.read_u1()?;
-        Ok(match Tag::new(tag)? {
-            Tag::CLASS                  => CPInfoRep::Class(ConstantClass::read(slice)?),
-            Tag::FIELD_REF              => CPInfoRep::Fieldref(ConstantFieldref::read(slice)?),
-            Tag::METHOD_REF             => CPInfoRep::Methodref(ConstantMethodref::read(slice)?),
-            Tag::INTERFACE_METHOD_REF   => CPInfoRep::InterfaceMethodref(ConstantInterfaceMethodref::read(slice)?),
-            Tag::STRING                 => CPInfoRep::String(ConstantString::read(slice)?),
-            Tag::INTEGER                => CPInfoRep::Integer(ConstantInteger::read(slice)?),
-            Tag::FLOAT                  => CPInfoRep::Float(ConstantFloat::read(slice)?),
-            Tag::LONG                   => CPInfoRep::Long(ConstantLong::read(slice)?),
-            Tag::DOUBLE                 => CPInfoRep::Double(ConstantDouble::read(slice)?),
-            Tag::NAME_AND_TYPE          => CPInfoRep::NameAndType(ConstantNameAndType::read(slice)?),
-            Tag::UTF8                   => CPInfoRep::Utf8(ConstantUtf8::read(slice)?),
-            Tag::METHOD_HANDLE          => CPInfoRep::MethodHandle(ConstantMethodHandle::read(slice)?),
-            Tag::METHOD_TYPE            => CPInfoRep::MethodType(ConstantMethodType::read(slice)?),
-            Tag::INVOKE_DYNAMIC         => CPInfoRep::InvokeDynamic(ConstantInvokeDynamic::read(slice)?),
-        })
-    }
-
-    pub fn tag(&self) -> Tag {
-        match self {
-            CPInfoRep::Class(_) => Tag::CLASS,
-            CPInfoRep::Fieldref(_) => Tag::FIELD_REF,
-            CPInfoRep::Methodref(_) => Tag::METHOD_REF,
-            CPInfoRep::InterfaceMethodref(_) => Tag::INTERFACE_METHOD_REF,
-            CPInfoRep::String(_) => Tag::STRING,
-            CPInfoRep::Integer(_) => Tag::INTEGER,
-            CPInfoRep::Float(_) => Tag::FLOAT,
-            CPInfoRep::Long(_) => Tag::LONG,
-            CPInfoRep::Double(_) => Tag::DOUBLE,
-            CPInfoRep::NameAndType(_) => Tag::NAME_AND_TYPE,
-            CPInfoRep::Utf8(_) => Tag::UTF8,
-            CPInfoRep::MethodHandle(_) => Tag::METHOD_HANDLE,
-            CPInfoRep::MethodType(_) => Tag::METHOD_TYPE,
-            CPInfoRep::InvokeDynamic(_) => Tag::INVOKE_DYNAMIC,
-        }
-    }
-
-    pub fn as_str(&self) -> Result<&str> {
-        match self {
-            CPInfoRep::Utf8(utf8) => Ok(&utf8.0),
-            _ => Err(Error::NotUtf8),
-        }
-    }
-}
-
-trait CPElem {
-    fn tag() -> Tag; 
-}
-
-#[derive(Clone, Copy)]
-pub struct ConstantClass {
-    pub(super) name_index: u2,
-}
-
-impl CPElem for ConstantClass {
-    fn tag() -> Tag { Tag::CLASS }
-}
-
-impl ConstantClass {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let name_index = slice.read_u2()?;
-        Ok(ConstantClass {
-            name_index,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantFieldref {
-    class_index: u2,
-    name_and_type_index: u2,
-}
-
-impl CPElem for ConstantFieldref {
-    fn tag() -> Tag { Tag::FIELD_REF }
-}
-
-impl ConstantFieldref {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let class_index = slice.read_u2()?;
-        let name_and_type_index = slice.read_u2()?;
-        Ok(ConstantFieldref {
-            class_index,
-            name_and_type_index,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantMethodref {
-    class_index: u2,
-    name_and_type_index: u2,
-}
-
-impl CPElem for ConstantMethodref {
-    fn tag() -> Tag { Tag::METHOD_REF }
-}
-
-impl ConstantMethodref {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let class_index = slice.read_u2()?;
-        let name_and_type_index = slice.read_u2()?;
-        Ok(ConstantMethodref {
-            class_index,
-            name_and_type_index,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantInterfaceMethodref {
-    class_index: u2,
-    name_and_type_index: u2,
-}
-
-impl CPElem for ConstantInterfaceMethodref {
-    fn tag() -> Tag { Tag::INTERFACE_METHOD_REF }
-}
-
-impl ConstantInterfaceMethodref {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let class_index = slice.read_u2()?;
-        let name_and_type_index = slice.read_u2()?;
-        Ok(ConstantInterfaceMethodref {
-            class_index,
-            name_and_type_index,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantString {
-    string_index: u2,
-}
-
-impl CPElem for ConstantString {
-    fn tag() -> Tag { Tag::STRING }
-}
-
-impl ConstantString {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        Ok(ConstantString {
-            string_index: slice.read_u2()?,
-        })
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct ConstantInteger(u4);
-
-impl CPElem for ConstantInteger {
-    fn tag() -> Tag { Tag::INTEGER }
-}
-
-impl ConstantInteger {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        Ok(ConstantInteger(slice.read_u4()?))
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct ConstantFloat(u4);
-
-impl CPElem for ConstantFloat {
-    fn tag() -> Tag { Tag::FLOAT }
-}
-
-impl ConstantFloat {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        Ok(ConstantFloat(slice.read_u4()?))
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct ConstantLong(u4, u4);
-
-impl CPElem for ConstantLong {
-    fn tag() -> Tag { Tag::LONG }
-}
-
-impl ConstantLong {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let low_bytes = slice.read_u4()?;
-        let hig_bytes = slice.read_u4()?;
-        Ok(ConstantLong(low_bytes, hig_bytes))
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct ConstantDouble(u4, u4);
-
-impl CPElem for ConstantDouble {
-    fn tag() -> Tag { Tag::DOUBLE }
-}
-
-impl ConstantDouble {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let low_bytes = slice.read_u4()?;
-        let hig_bytes = slice.read_u4()?;
-        Ok(ConstantDouble(low_bytes, hig_bytes))
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantNameAndType {
-    name_index: u2,
-    descriptor_index: u2,
-}
-
-impl CPElem for ConstantNameAndType {
-    fn tag() -> Tag { Tag::NAME_AND_TYPE }
-}
-
-impl ConstantNameAndType {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let name_index = slice.read_u2()?;
-        let descriptor_index = slice.read_u2()?;
-        Ok(ConstantNameAndType {
-            name_index,
-            descriptor_index,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantUtf8(pub(super) String);
-
-impl CPElem for ConstantUtf8 {
-    fn tag() -> Tag { Tag::UTF8 }
-}
-
-impl ConstantUtf8 {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let length = slice.read_u2()? as usize;
-        let text = String::from_utf8(slice.read_slice_vec(length)?)
-            .map_err(|_| Error::InvalidUtf8);
-        Ok(ConstantUtf8(text?))
-    }
-}
-
-#[repr(u8)]
-#[derive(Primitive, Clone, Copy)]
-pub enum RefKind {
-    GetField = 1,
-    GetStatic = 2,
-    PutField = 3,
-    PutStatic = 4,
-    InvokeVirtual = 5,
-    InvokeStatic = 6,
-    InvokeSpecial = 7,
-    NewInvokeSpecial = 8,
-    InvokeInterface = 9,
-}
-
-impl RefKind {
-    pub fn read(val: u1) -> Result<Self> {
-        RefKind::from_u8(val).ok_or(Error::WrongKind(val))
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantMethodHandle {
-    reference_kind: RefKind,
-    reference_index: u2,
-}
-
-impl CPElem for ConstantMethodHandle {
-    fn tag() -> Tag { Tag::METHOD_HANDLE }
-}
-
-impl ConstantMethodHandle {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let ref_kind = slice.read_u1()?;
-        let reference_index = slice.read_u2()?;
-        Ok(ConstantMethodHandle {
-            reference_kind: RefKind::read(ref_kind)?,
-            reference_index,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantMethodType {
-    descriptor_index: u2,
-}
-
-impl CPElem for ConstantMethodType {
-    fn tag() -> Tag { Tag::METHOD_TYPE }
-}
-
-impl ConstantMethodType {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        Ok(ConstantMethodType {
-            descriptor_index: slice.read_u2()?,
-        })
-    }
-}
-
-#[derive(Clone)]
-pub struct ConstantInvokeDynamic {
-    bootstrap_method_attr_index: u2,
-    name_and_type_index: u2,
-}
-
-impl CPElem for ConstantInvokeDynamic {
-    fn tag() -> Tag { Tag::INVOKE_DYNAMIC }
-}
-
-impl ConstantInvokeDynamic {
-    pub fn read(slice: &PoSlice) -> Result<Self> {
-        let bootstrap_method_attr_index = slice.read_u2()?;
-        let name_and_type_index = slice.read_u2()?;
-        Ok(ConstantInvokeDynamic {
-            bootstrap_method_attr_index,
-            name_and_type_index,
-        })
-    }
-}
-
-/* End of working on progress */
-
-#[allow(non_camel_case_types)]
-#[derive(Primitive, Copy, Clone, Debug, PartialEq)]
-#[repr(u8)]
-pub enum Tag {
-    INTEGER =  3,
-    FLOAT =  4,
-    LONG =  5,
-    DOUBLE =  6,
-    UTF8 =  1,
-    STRING =  8,
-    CLASS =  7,
-    FIELD_REF =  9,
-    METHOD_REF =  10,
-    INTERFACE_METHOD_REF =  11,
-    NAME_AND_TYPE =  12,
-    METHOD_HANDLE =  15,
-    METHOD_TYPE =  16,
-    INVOKE_DYNAMIC =  18,
-}
-
-impl Tag {
-    pub fn new(val: u8) -> Result<Self> {
-        Self::from_u8(val).ok_or(Error::CPTag(val))
-    }
+use num_traits::FromPrimitive;
+
+use class_file::descriptor::MethodDescriptor;
+use class_file::reader::Reader;
+use types::{u1, u2, u4};
+use result::{Result, Error};
+use trace::Category;
+
+/* Working on progress */
+
+// Tags ldc can load, JVMS §4.4 table 4.4-C
+const LOADABLE: [Tag; 9] = [Tag::INTEGER, Tag::FLOAT, Tag::LONG, Tag::DOUBLE, Tag::STRING, Tag::CLASS,
+    Tag::METHOD_TYPE, Tag::METHOD_HANDLE, Tag::DYNAMIC];
+
+pub struct ConstantPoolRep(Vec<Option<CPInfoRep>>);
+
+impl ConstantPoolRep {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let cp_count = slice.read_u2()? as usize;
+        trace!(Category::Counts, "{} constants at {}", cp_count, slice.pos() - 2);
+        let mut constant_pool = vec![None; cp_count];
+        {
+            let mut jump = true;
+            for (index, cp_info) in constant_pool.iter_mut().enumerate() {
+                *cp_info = if !jump {
+                    let offset = slice.pos();
+                    let cp_info = CPInfoRep::new(slice)
+                        .map_err(|err| err.within(slice.pos(), format!("constant_pool[{}]", index)))?;
+                    trace!(Category::Pool, "#{} {} at {}", index, cp_info.tag().name(), offset);
+                    jump = matches!(cp_info, CPInfoRep::Double(_) | CPInfoRep::Long(_));
+                    Some(cp_info)
+                } else {
+                    jump = false;
+                    None
+                }
+            }
+        }
+        Ok(ConstantPoolRep(constant_pool))
+    }
+
+    // constant_pool_count, valid indexes are 1 to len() - 1
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.len() <= 1
+    }
+
+    // Skips the unusable slots: index 0 and the one after each Long and Double
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &CPInfoRep)> {
+        self.0.iter()
+            .enumerate()
+            .filter_map(|(index, cp_info)| cp_info.as_ref().map(|cp_info| (index, cp_info)))
+    }
+
+    pub fn get(&self, index: usize) -> Result<&CPInfoRep> {
+        match self.0.get(index) {
+            Some(cp_info) => match cp_info {
+                Some(cp_info) => Ok(cp_info),
+                None => Err(Error::EmptyCPInfo(index as u2)),  
+            },
+            None => Err(Error::Index(index))
+        }
+    }
+
+    pub fn utf8(&self, index: usize) -> Result<&str> {
+        match self.get(index)? {
+            CPInfoRep::Utf8(utf8) => Ok(utf8.as_str()),
+            cp_info => Err(Error::WrongTag(index, &[Tag::UTF8], cp_info.tag())),
+        }
+    }
+
+    pub fn class_name(&self, index: usize) -> Result<&str> {
+        match self.get(index)? {
+            CPInfoRep::Class(class_info) => self.utf8(class_info.name_index as usize),
+            cp_info => Err(Error::WrongTag(index, &[Tag::CLASS], cp_info.tag())),
+        }
+    }
+
+    pub fn module_name(&self, index: usize) -> Result<&str> {
+        match self.get(index)? {
+            CPInfoRep::Module(module) => self.utf8(module.name_index as usize),
+            cp_info => Err(Error::WrongTag(index, &[Tag::MODULE], cp_info.tag())),
+        }
+    }
+
+    pub fn package_name(&self, index: usize) -> Result<&str> {
+        match self.get(index)? {
+            CPInfoRep::Package(package) => self.utf8(package.name_index as usize),
+            cp_info => Err(Error::WrongTag(index, &[Tag::PACKAGE], cp_info.tag())),
+        }
+    }
+
+    pub fn name_and_type(&self, index: usize) -> Result<(&str, &str)> {
+        match self.get(index)? {
+            CPInfoRep::NameAndType(name_and_type) => Ok((
+                self.utf8(name_and_type.name_index as usize)?,
+                self.utf8(name_and_type.descriptor_index as usize)?,
+            )),
+            cp_info => Err(Error::WrongTag(index, &[Tag::NAME_AND_TYPE], cp_info.tag())),
+        }
+    }
+
+    pub fn field_ref(&self, index: usize) -> Result<MemberRef<'_>> {
+        match self.get(index)? {
+            CPInfoRep::Fieldref(field_ref) => 
+                self.member_ref(field_ref.class_index, field_ref.name_and_type_index),
+            cp_info => Err(Error::WrongTag(index, &[Tag::FIELD_REF], cp_info.tag())),
+        }
+    }
+
+    // Resolves both Methodref and InterfaceMethodref entries, the tag tells which one it was
+    pub fn method_ref(&self, index: usize) -> Result<(Tag, MemberRef<'_>)> {
+        let (class_index, name_and_type_index) = match self.get(index)? {
+            CPInfoRep::Methodref(method_ref) => 
+                (method_ref.class_index, method_ref.name_and_type_index),
+            CPInfoRep::InterfaceMethodref(method_ref) => 
+                (method_ref.class_index, method_ref.name_and_type_index),
+            cp_info => return Err(Error::WrongTag(index, &[Tag::METHOD_REF, Tag::INTERFACE_METHOD_REF], cp_info.tag())),
+        };
+        Ok((self.get(index)?.tag(), self.member_ref(class_index, name_and_type_index)?))
+    }
+
+    pub fn string(&self, index: usize) -> Result<&str> {
+        match self.get(index)? {
+            CPInfoRep::String(string) => self.utf8(string.string_index as usize),
+            cp_info => Err(Error::WrongTag(index, &[Tag::STRING], cp_info.tag())),
+        }
+    }
+
+    // Resolves the operand of ldc, ldc_w and ldc2_w (JVMS §4.4, table 4.4-C)
+    pub fn loadable(&self, index: usize) -> Result<LoadableConstant<'_>> {
+        Ok(match self.get(index)? {
+            CPInfoRep::Integer(integer) => LoadableConstant::Integer(integer.value()),
+            CPInfoRep::Float(float) => LoadableConstant::Float(float.value()),
+            CPInfoRep::Long(long) => LoadableConstant::Long(long.value()),
+            CPInfoRep::Double(double) => LoadableConstant::Double(double.value()),
+            CPInfoRep::String(_) => LoadableConstant::String(self.string(index)?),
+            CPInfoRep::Class(_) => LoadableConstant::Class(self.class_name(index)?),
+            CPInfoRep::MethodType(method_type) => LoadableConstant::MethodType(
+                self.utf8(method_type.descriptor_index as usize)?),
+            CPInfoRep::MethodHandle(_) => {
+                let (reference_kind, reference) = self.method_handle(index)?;
+                LoadableConstant::MethodHandle(reference_kind, reference)
+            },
+            CPInfoRep::Dynamic(_) => LoadableConstant::Dynamic(self.dynamic(index)?),
+            cp_info => return Err(Error::WrongTag(index, &LOADABLE, cp_info.tag())),
+        })
+    }
+
+    // Checks the constraints of JVMS §4.4.8 on the referenced member
+    pub fn method_handle(&self, index: usize) -> Result<(RefKind, MemberRef<'_>)> {
+        let (reference_kind, reference_index) = match self.get(index)? {
+            CPInfoRep::MethodHandle(method_handle) => 
+                (method_handle.reference_kind, method_handle.reference_index as usize),
+            cp_info => return Err(Error::WrongTag(index, &[Tag::METHOD_HANDLE], cp_info.tag())),
+        };
+        let reference_tag = self.get(reference_index)?.tag();
+        let reference = match reference_kind {
+            RefKind::GetField | RefKind::GetStatic | 
+            RefKind::PutField | RefKind::PutStatic => self.field_ref(reference_index)?,
+            RefKind::InvokeVirtual | RefKind::NewInvokeSpecial if reference_tag != Tag::METHOD_REF => 
+                return Err(Error::WrongTag(reference_index, &[Tag::METHOD_REF], reference_tag)),
+            RefKind::InvokeInterface if reference_tag != Tag::INTERFACE_METHOD_REF => 
+                return Err(Error::WrongTag(reference_index, &[Tag::INTERFACE_METHOD_REF], reference_tag)),
+            _ => self.method_ref(reference_index)?.1,
+        };
+        let valid = match reference_kind {
+            RefKind::NewInvokeSpecial => reference.name == "<init>",
+            RefKind::InvokeVirtual | RefKind::InvokeStatic | 
+            RefKind::InvokeSpecial | RefKind::InvokeInterface => 
+                reference.name != "<init>" && reference.name != "<clinit>",
+            _ => true,
+        };
+        if valid {
+            Ok((reference_kind, reference))
+        } else {
+            Err(Error::InvalidMethodHandle(reference_kind))
+        }
+    }
+
+    pub fn method_type(&self, index: usize) -> Result<MethodDescriptor> {
+        match self.get(index)? {
+            CPInfoRep::MethodType(method_type) => 
+                MethodDescriptor::parse(self.utf8(method_type.descriptor_index as usize)?),
+            cp_info => Err(Error::WrongTag(index, &[Tag::METHOD_TYPE], cp_info.tag())),
+        }
+    }
+
+    // Resolves both Dynamic and InvokeDynamic entries, the call site or constant is linked by
+    // running the bootstrap method
+    pub fn dynamic(&self, index: usize) -> Result<DynamicRef<'_>> {
+        let (bootstrap_method_attr_index, name_and_type_index) = match self.get(index)? {
+            CPInfoRep::Dynamic(dynamic) => 
+                (dynamic.bootstrap_method_attr_index, dynamic.name_and_type_index),
+            CPInfoRep::InvokeDynamic(dynamic) => 
+                (dynamic.bootstrap_method_attr_index, dynamic.name_and_type_index),
+            cp_info => return Err(Error::WrongTag(index, &[Tag::DYNAMIC, Tag::INVOKE_DYNAMIC], cp_info.tag())),
+        };
+        let (name, descriptor) = self.name_and_type(name_and_type_index as usize)?;
+        Ok(DynamicRef {
+            bootstrap_method_attr_index,
+            name,
+            descriptor,
+        })
+    }
+
+    fn member_ref(&self, class_index: u2, name_and_type_index: u2) -> Result<MemberRef<'_>> {
+        let class_name = self.class_name(class_index as usize)?;
+        let (name, descriptor) = self.name_and_type(name_and_type_index as usize)?;
+        Ok(MemberRef {
+            class_name,
+            name,
+            descriptor,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoadableConstant<'a> {
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(&'a str),
+    // Internal binary name, array classes are in descriptor form like `[I`
+    Class(&'a str),
+    MethodType(&'a str),
+    MethodHandle(RefKind, MemberRef<'a>),
+    Dynamic(DynamicRef<'a>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DynamicRef<'a> {
+    // Index into ClassFile::bootstrap_methods
+    pub bootstrap_method_attr_index: u2,
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
+
+// Symbolic reference to a field or method, with every index resolved to its string
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MemberRef<'a> {
+    pub class_name: &'a str,
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
+
+// replacement of CPInfo
+#[derive(Clone)]
+pub enum CPInfoRep {
+    Class(ConstantClass),
+    Fieldref(ConstantFieldref),
+    Methodref(ConstantMethodref),
+    InterfaceMethodref(ConstantInterfaceMethodref),
+    String(ConstantString),
+    Integer(ConstantInteger),
+    Float(ConstantFloat),
+    Long(ConstantLong),
+    Double(ConstantDouble),
+    NameAndType(ConstantNameAndType),
+    Utf8(ConstantUtf8),
+    MethodHandle(ConstantMethodHandle),
+    MethodType(ConstantMethodType),
+    Dynamic(ConstantDynamic),
+    InvokeDynamic(ConstantInvokeDynamic),
+    Module(ConstantModule),
+    Package(ConstantPackage),
+}
+
+impl CPInfoRep {
+    pub fn new<R: Reader>(slice: &R) -> Result<Self> {
+        let tag = slice.read_u1()?;
+        Ok(match Tag::new(tag)? {
+            Tag::CLASS                  => CPInfoRep::Class(ConstantClass::read(slice)?),
+            Tag::FIELD_REF              => CPInfoRep::Fieldref(ConstantFieldref::read(slice)?),
+            Tag::METHOD_REF             => CPInfoRep::Methodref(ConstantMethodref::read(slice)?),
+            Tag::INTERFACE_METHOD_REF   => CPInfoRep::InterfaceMethodref(ConstantInterfaceMethodref::read(slice)?),
+            Tag::STRING                 => CPInfoRep::String(ConstantString::read(slice)?),
+            Tag::INTEGER                => CPInfoRep::Integer(ConstantInteger::read(slice)?),
+            Tag::FLOAT                  => CPInfoRep::Float(ConstantFloat::read(slice)?),
+            Tag::LONG                   => CPInfoRep::Long(ConstantLong::read(slice)?),
+            Tag::DOUBLE                 => CPInfoRep::Double(ConstantDouble::read(slice)?),
+            Tag::NAME_AND_TYPE          => CPInfoRep::NameAndType(ConstantNameAndType::read(slice)?),
+            Tag::UTF8                   => CPInfoRep::Utf8(ConstantUtf8::read(slice)?),
+            Tag::METHOD_HANDLE          => CPInfoRep::MethodHandle(ConstantMethodHandle::read(slice)?),
+            Tag::METHOD_TYPE            => CPInfoRep::MethodType(ConstantMethodType::read(slice)?),
+            Tag::DYNAMIC                => CPInfoRep::Dynamic(ConstantDynamic::read(slice)?),
+            Tag::INVOKE_DYNAMIC         => CPInfoRep::InvokeDynamic(ConstantInvokeDynamic::read(slice)?),
+            Tag::MODULE                 => CPInfoRep::Module(ConstantModule::read(slice)?),
+            Tag::PACKAGE                => CPInfoRep::Package(ConstantPackage::read(slice)?),
+        })
+    }
+
+    pub fn tag(&self) -> Tag {
+        match self {
+            CPInfoRep::Class(_) => Tag::CLASS,
+            CPInfoRep::Fieldref(_) => Tag::FIELD_REF,
+            CPInfoRep::Methodref(_) => Tag::METHOD_REF,
+            CPInfoRep::InterfaceMethodref(_) => Tag::INTERFACE_METHOD_REF,
+            CPInfoRep::String(_) => Tag::STRING,
+            CPInfoRep::Integer(_) => Tag::INTEGER,
+            CPInfoRep::Float(_) => Tag::FLOAT,
+            CPInfoRep::Long(_) => Tag::LONG,
+            CPInfoRep::Double(_) => Tag::DOUBLE,
+            CPInfoRep::NameAndType(_) => Tag::NAME_AND_TYPE,
+            CPInfoRep::Utf8(_) => Tag::UTF8,
+            CPInfoRep::MethodHandle(_) => Tag::METHOD_HANDLE,
+            CPInfoRep::MethodType(_) => Tag::METHOD_TYPE,
+            CPInfoRep::Dynamic(_) => Tag::DYNAMIC,
+            CPInfoRep::InvokeDynamic(_) => Tag::INVOKE_DYNAMIC,
+            CPInfoRep::Module(_) => Tag::MODULE,
+            CPInfoRep::Package(_) => Tag::PACKAGE,
+        }
+    }
+}
+
+pub trait CPElem {
+    fn tag() -> Tag; 
+}
+
+#[derive(Clone, Copy)]
+pub struct ConstantClass {
+    pub(super) name_index: u2,
+}
+
+impl CPElem for ConstantClass {
+    fn tag() -> Tag { Tag::CLASS }
+}
+
+impl ConstantClass {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let name_index = slice.read_u2()?;
+        Ok(ConstantClass {
+            name_index,
+        })
+    }
+
+    pub fn name_index(&self) -> u2 { self.name_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantFieldref {
+    class_index: u2,
+    name_and_type_index: u2,
+}
+
+impl CPElem for ConstantFieldref {
+    fn tag() -> Tag { Tag::FIELD_REF }
+}
+
+impl ConstantFieldref {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let class_index = slice.read_u2()?;
+        let name_and_type_index = slice.read_u2()?;
+        Ok(ConstantFieldref {
+            class_index,
+            name_and_type_index,
+        })
+    }
+
+    pub fn class_index(&self) -> u2 { self.class_index }
+
+    pub fn name_and_type_index(&self) -> u2 { self.name_and_type_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantMethodref {
+    class_index: u2,
+    name_and_type_index: u2,
+}
+
+impl CPElem for ConstantMethodref {
+    fn tag() -> Tag { Tag::METHOD_REF }
+}
+
+impl ConstantMethodref {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let class_index = slice.read_u2()?;
+        let name_and_type_index = slice.read_u2()?;
+        Ok(ConstantMethodref {
+            class_index,
+            name_and_type_index,
+        })
+    }
+
+    pub fn class_index(&self) -> u2 { self.class_index }
+
+    pub fn name_and_type_index(&self) -> u2 { self.name_and_type_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantInterfaceMethodref {
+    class_index: u2,
+    name_and_type_index: u2,
+}
+
+impl CPElem for ConstantInterfaceMethodref {
+    fn tag() -> Tag { Tag::INTERFACE_METHOD_REF }
+}
+
+impl ConstantInterfaceMethodref {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let class_index = slice.read_u2()?;
+        let name_and_type_index = slice.read_u2()?;
+        Ok(ConstantInterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        })
+    }
+
+    pub fn class_index(&self) -> u2 { self.class_index }
+
+    pub fn name_and_type_index(&self) -> u2 { self.name_and_type_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantString {
+    string_index: u2,
+}
+
+impl CPElem for ConstantString {
+    fn tag() -> Tag { Tag::STRING }
+}
+
+impl ConstantString {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        Ok(ConstantString {
+            string_index: slice.read_u2()?,
+        })
+    }
+
+    pub fn string_index(&self) -> u2 { self.string_index }
+}
+
+#[derive(Clone, Copy)]
+pub struct ConstantInteger(u4);
+
+impl CPElem for ConstantInteger {
+    fn tag() -> Tag { Tag::INTEGER }
+}
+
+impl ConstantInteger {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        Ok(ConstantInteger(slice.read_u4()?))
+    }
+
+    pub fn value(&self) -> i32 { self.0 as i32 }
+}
+
+#[derive(Clone, Copy)]
+pub struct ConstantFloat(u4);
+
+impl CPElem for ConstantFloat {
+    fn tag() -> Tag { Tag::FLOAT }
+}
+
+impl ConstantFloat {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        Ok(ConstantFloat(slice.read_u4()?))
+    }
+
+    pub fn value(&self) -> f32 { f32::from_bits(self.0) }
+}
+
+#[derive(Clone, Copy)]
+pub struct ConstantLong(u4, u4);
+
+impl CPElem for ConstantLong {
+    fn tag() -> Tag { Tag::LONG }
+}
+
+impl ConstantLong {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let high_bytes = slice.read_u4()?;
+        let low_bytes = slice.read_u4()?;
+        Ok(ConstantLong(high_bytes, low_bytes))
+    }
+
+    #[inline]
+    fn bits(&self) -> u64 {
+        (u64::from(self.0) << 32) | u64::from(self.1)
+    }
+
+    pub fn value(&self) -> i64 { self.bits() as i64 }
+}
+
+#[derive(Clone, Copy)]
+pub struct ConstantDouble(u4, u4);
+
+impl CPElem for ConstantDouble {
+    fn tag() -> Tag { Tag::DOUBLE }
+}
+
+impl ConstantDouble {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let high_bytes = slice.read_u4()?;
+        let low_bytes = slice.read_u4()?;
+        Ok(ConstantDouble(high_bytes, low_bytes))
+    }
+
+    #[inline]
+    fn bits(&self) -> u64 {
+        (u64::from(self.0) << 32) | u64::from(self.1)
+    }
+
+    pub fn value(&self) -> f64 { f64::from_bits(self.bits()) }
+}
+
+#[derive(Clone)]
+pub struct ConstantNameAndType {
+    name_index: u2,
+    descriptor_index: u2,
+}
+
+impl CPElem for ConstantNameAndType {
+    fn tag() -> Tag { Tag::NAME_AND_TYPE }
+}
+
+impl ConstantNameAndType {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let name_index = slice.read_u2()?;
+        let descriptor_index = slice.read_u2()?;
+        Ok(ConstantNameAndType {
+            name_index,
+            descriptor_index,
+        })
+    }
+
+    pub fn name_index(&self) -> u2 { self.name_index }
+
+    pub fn descriptor_index(&self) -> u2 { self.descriptor_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantUtf8 {
+    text: String,
+    // What was read when `text` doesn't encode back to it, like an unpaired surrogate
+    bytes: Option<Box<[u1]>>,
+}
+
+impl CPElem for ConstantUtf8 {
+    fn tag() -> Tag { Tag::UTF8 }
+}
+
+impl ConstantUtf8 {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let length = slice.read_u2()? as usize;
+        let bytes = slice.read_slice_vec(length)?;
+        match String::from_utf8(bytes) {
+            // Standard UTF-8 is accepted too, it only differs in NUL and supplementary characters
+            Ok(text) => {
+                let same = !text.chars().any(|c| c == '\0' || c > '\u{FFFF}');
+                let bytes = if same { None } else { Some(text.clone().into_bytes().into_boxed_slice()) };
+                Ok(ConstantUtf8 { text, bytes })
+            },
+            Err(err) => {
+                let bytes = err.into_bytes();
+                let text = decode_modified_utf8(&bytes)?;
+                let same = encode_modified_utf8(&text) == bytes;
+                Ok(ConstantUtf8 { text, bytes: if same { None } else { Some(bytes.into_boxed_slice()) } })
+            },
+        }
+    }
+
+    // With U+FFFD for what has no place in a Rust string
+    pub fn as_str(&self) -> &str { &self.text }
+
+    // The bytes of the class file when as_str() lost something
+    pub fn lossy_bytes(&self) -> Option<&[u1]> { self.bytes.as_deref() }
+}
+
+// Class files store strings in modified UTF-8 (JVMS §4.4.7): NUL is encoded in two bytes and
+// supplementary characters as a surrogate pair of three bytes each. Unpaired surrogates can't be
+// represented in a Rust string so they become U+FFFD, ConstantUtf8 keeps the bytes for those.
+pub(super) fn decode_modified_utf8(bytes: &[u1]) -> Result<String> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter().cloned();
+    let continuation = |byte: Option<u1>| match byte {
+        Some(byte) if byte & 0xC0 == 0x80 => Ok(u2::from(byte & 0x3F)),
+        _ => Err(Error::InvalidUtf8),
+    };
+    while let Some(byte) = iter.next() {
+        let unit = match byte {
+            0x01..=0x7F => u2::from(byte),
+            0xC0..=0xDF => (u2::from(byte & 0x1F) << 6) | continuation(iter.next())?,
+            0xE0..=0xEF => {
+                let second = continuation(iter.next())?;
+                let third = continuation(iter.next())?;
+                (u2::from(byte & 0x0F) << 12) | (second << 6) | third
+            },
+            _ => return Err(Error::InvalidUtf8),
+        };
+        units.push(unit);
+    }
+    Ok(String::from_utf16_lossy(&units))
+}
+
+// Inverse of decode_modified_utf8, used when writing class files
+pub fn encode_modified_utf8(text: &str) -> Vec<u1> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for unit in text.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => bytes.push(unit as u1),
+            0x0000..=0x07FF => {
+                bytes.push(0xC0 | (unit >> 6) as u1);
+                bytes.push(0x80 | (unit & 0x3F) as u1);
+            },
+            _ => {
+                bytes.push(0xE0 | (unit >> 12) as u1);
+                bytes.push(0x80 | ((unit >> 6) & 0x3F) as u1);
+                bytes.push(0x80 | (unit & 0x3F) as u1);
+            },
+        }
+    }
+    bytes
+}
+
+#[repr(u8)]
+#[derive(Primitive, Clone, Copy, Debug, PartialEq)]
+pub enum RefKind {
+    GetField = 1,
+    GetStatic = 2,
+    PutField = 3,
+    PutStatic = 4,
+    InvokeVirtual = 5,
+    InvokeStatic = 6,
+    InvokeSpecial = 7,
+    NewInvokeSpecial = 8,
+    InvokeInterface = 9,
+}
+
+impl RefKind {
+    pub fn read(val: u1) -> Result<Self> {
+        RefKind::from_u8(val).ok_or(Error::WrongKind(val))
+    }
+
+    // Name used by javap and MethodHandleInfo.referenceKindToString
+    pub fn name(self) -> &'static str {
+        match self {
+            RefKind::GetField => "REF_getField",
+            RefKind::GetStatic => "REF_getStatic",
+            RefKind::PutField => "REF_putField",
+            RefKind::PutStatic => "REF_putStatic",
+            RefKind::InvokeVirtual => "REF_invokeVirtual",
+            RefKind::InvokeStatic => "REF_invokeStatic",
+            RefKind::InvokeSpecial => "REF_invokeSpecial",
+            RefKind::NewInvokeSpecial => "REF_newInvokeSpecial",
+            RefKind::InvokeInterface => "REF_invokeInterface",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        (1..=9).filter_map(RefKind::from_u8).find(|kind| kind.name() == name)
+    }
+
+    pub fn is_field(self) -> bool {
+        (self as u1) <= (RefKind::PutStatic as u1)
+    }
+}
+
+#[derive(Clone)]
+pub struct ConstantMethodHandle {
+    reference_kind: RefKind,
+    reference_index: u2,
+}
+
+impl CPElem for ConstantMethodHandle {
+    fn tag() -> Tag { Tag::METHOD_HANDLE }
+}
+
+impl ConstantMethodHandle {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let ref_kind = slice.read_u1()?;
+        let reference_index = slice.read_u2()?;
+        Ok(ConstantMethodHandle {
+            reference_kind: RefKind::read(ref_kind)?,
+            reference_index,
+        })
+    }
+
+    pub fn reference_kind(&self) -> RefKind { self.reference_kind }
+
+    pub fn reference_index(&self) -> u2 { self.reference_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantMethodType {
+    descriptor_index: u2,
+}
+
+impl CPElem for ConstantMethodType {
+    fn tag() -> Tag { Tag::METHOD_TYPE }
+}
+
+impl ConstantMethodType {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        Ok(ConstantMethodType {
+            descriptor_index: slice.read_u2()?,
+        })
+    }
+
+    pub fn descriptor_index(&self) -> u2 { self.descriptor_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantInvokeDynamic {
+    bootstrap_method_attr_index: u2,
+    name_and_type_index: u2,
+}
+
+impl CPElem for ConstantInvokeDynamic {
+    fn tag() -> Tag { Tag::INVOKE_DYNAMIC }
+}
+
+impl ConstantInvokeDynamic {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let bootstrap_method_attr_index = slice.read_u2()?;
+        let name_and_type_index = slice.read_u2()?;
+        Ok(ConstantInvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        })
+    }
+
+    pub fn bootstrap_method_attr_index(&self) -> u2 { self.bootstrap_method_attr_index }
+
+    pub fn name_and_type_index(&self) -> u2 { self.name_and_type_index }
+}
+
+#[derive(Clone)]
+pub struct ConstantDynamic {
+    bootstrap_method_attr_index: u2,
+    name_and_type_index: u2,
+}
+
+impl CPElem for ConstantDynamic {
+    fn tag() -> Tag { Tag::DYNAMIC }
+}
+
+impl ConstantDynamic {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        let bootstrap_method_attr_index = slice.read_u2()?;
+        let name_and_type_index = slice.read_u2()?;
+        Ok(ConstantDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        })
+    }
+
+    pub fn bootstrap_method_attr_index(&self) -> u2 { self.bootstrap_method_attr_index }
+
+    pub fn name_and_type_index(&self) -> u2 { self.name_and_type_index }
+}
+
+// Only allowed in module-info.class
+#[derive(Clone, Copy)]
+pub struct ConstantModule {
+    name_index: u2,
+}
+
+impl CPElem for ConstantModule {
+    fn tag() -> Tag { Tag::MODULE }
+}
+
+impl ConstantModule {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        Ok(ConstantModule {
+            name_index: slice.read_u2()?,
+        })
+    }
+
+    pub fn name_index(&self) -> u2 { self.name_index }
+}
+
+// Only allowed in module-info.class
+#[derive(Clone, Copy)]
+pub struct ConstantPackage {
+    name_index: u2,
+}
+
+impl CPElem for ConstantPackage {
+    fn tag() -> Tag { Tag::PACKAGE }
+}
+
+impl ConstantPackage {
+    pub fn read<R: Reader>(slice: &R) -> Result<Self> {
+        Ok(ConstantPackage {
+            name_index: slice.read_u2()?,
+        })
+    }
+
+    pub fn name_index(&self) -> u2 { self.name_index }
+}
+
+/* End of working on progress */
+
+#[allow(non_camel_case_types)]
+#[derive(Primitive, Copy, Clone, Debug, PartialEq)]
+#[repr(u8)]
+pub enum Tag {
+    INTEGER =  3,
+    FLOAT =  4,
+    LONG =  5,
+    DOUBLE =  6,
+    UTF8 =  1,
+    STRING =  8,
+    CLASS =  7,
+    FIELD_REF =  9,
+    METHOD_REF =  10,
+    INTERFACE_METHOD_REF =  11,
+    NAME_AND_TYPE =  12,
+    METHOD_HANDLE =  15,
+    METHOD_TYPE =  16,
+    DYNAMIC =  17,
+    INVOKE_DYNAMIC =  18,
+    MODULE =  19,
+    PACKAGE =  20,
+}
+
+impl Tag {
+    pub fn new(val: u8) -> Result<Self> {
+        Self::from_u8(val).ok_or(Error::CPTag(val))
+    }
+
+    // Name used by JVMS table 4.4-B and javap
+    pub fn name(self) -> &'static str {
+        match self {
+            Tag::INTEGER => "Integer",
+            Tag::FLOAT => "Float",
+            Tag::LONG => "Long",
+            Tag::DOUBLE => "Double",
+            Tag::UTF8 => "Utf8",
+            Tag::STRING => "String",
+            Tag::CLASS => "Class",
+            Tag::FIELD_REF => "Fieldref",
+            Tag::METHOD_REF => "Methodref",
+            Tag::INTERFACE_METHOD_REF => "InterfaceMethodref",
+            Tag::NAME_AND_TYPE => "NameAndType",
+            Tag::METHOD_HANDLE => "MethodHandle",
+            Tag::METHOD_TYPE => "MethodType",
+            Tag::DYNAMIC => "Dynamic",
+            Tag::INVOKE_DYNAMIC => "InvokeDynamic",
+            Tag::MODULE => "Module",
+            Tag::PACKAGE => "Package",
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+use std::str::Chars;
+use std::iter::Peekable;
+
+use result::{Result, Error};
+
+// JVMS §4.3.2
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    Object(String),
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    pub fn parse(descriptor: &str) -> Result<Self> {
+        let mut chars = descriptor.chars().peekable();
+        let field_type = FieldType::read(&mut chars)?;
+        match chars.next() {
+            None => Ok(field_type),
+            Some(_) => Err(Error::InvalidDescriptor(descriptor.to_string())),
+        }
+    }
+
+    fn read(chars: &mut Peekable<Chars>) -> Result<Self> {
+        Ok(match chars.next() {
+            Some('B') => FieldType::Byte,
+            Some('C') => FieldType::Char,
+            Some('D') => FieldType::Double,
+            Some('F') => FieldType::Float,
+            Some('I') => FieldType::Int,
+            Some('J') => FieldType::Long,
+            Some('S') => FieldType::Short,
+            Some('Z') => FieldType::Boolean,
+            Some('L') => {
+                let mut class_name = String::new();
+                loop {
+                    match chars.next() {
+                        Some(';') if !class_name.is_empty() => break,
+                        Some(';') | None => return Err(Error::InvalidDescriptor(class_name)),
+                        Some(c) => class_name.push(c),
+                    }
+                }
+                FieldType::Object(class_name)
+            },
+            Some('[') => FieldType::Array(Box::new(FieldType::read(chars)?)),
+            Some(c) => return Err(Error::InvalidDescriptor(c.to_string())),
+            None => return Err(Error::InvalidDescriptor(String::new())),
+        })
+    }
+
+    // Number of local variable / operand stack slots a value of this type occupies
+    pub fn slots(&self) -> usize {
+        match *self {
+            FieldType::Long | FieldType::Double => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn is_reference(&self) -> bool {
+        matches!(*self, FieldType::Object(_) | FieldType::Array(_))
+    }
+
+    // Source form as returned by Class.getTypeName, e.g. "int", "java.lang.String[]"
+    pub fn type_name(&self) -> String {
+        match *self {
+            FieldType::Byte => "byte".to_string(),
+            FieldType::Char => "char".to_string(),
+            FieldType::Double => "double".to_string(),
+            FieldType::Float => "float".to_string(),
+            FieldType::Int => "int".to_string(),
+            FieldType::Long => "long".to_string(),
+            FieldType::Short => "short".to_string(),
+            FieldType::Boolean => "boolean".to_string(),
+            FieldType::Object(ref class_name) => java_name(class_name),
+            FieldType::Array(ref component) => format!("{}[]", component.type_name()),
+        }
+    }
+}
+
+// Internal binary name to the form Class.getName uses, "java/lang/Object" -> "java.lang.Object".
+// Array classes keep their descriptor form, "[Ljava/lang/String;" -> "[Ljava.lang.String;"
+pub fn java_name(class_name: &str) -> String {
+    class_name.replace('/', ".")
+}
+
+// JVMS §4.3.3
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodDescriptor {
+    params: Box<[FieldType]>,
+    return_type: Option<FieldType>,
+}
+
+impl MethodDescriptor {
+    pub fn parse(descriptor: &str) -> Result<Self> {
+        let error = || Error::InvalidDescriptor(descriptor.to_string());
+        let mut chars = descriptor.chars().peekable();
+        if chars.next() != Some('(') {
+            return Err(error());
+        }
+        let mut params = Vec::new();
+        loop {
+            match chars.peek() {
+                Some(')') => {
+                    chars.next();
+                    break;
+                },
+                Some(_) => params.push(FieldType::read(&mut chars).map_err(|_| error())?),
+                None => return Err(error()),
+            }
+        }
+        let return_type = match chars.peek() {
+            Some('V') => {
+                chars.next();
+                None
+            },
+            _ => Some(FieldType::read(&mut chars).map_err(|_| error())?),
+        };
+        if chars.next().is_some() {
+            return Err(error());
+        }
+        Ok(MethodDescriptor {
+            params: params.into_boxed_slice(),
+            return_type,
+        })
+    }
+
+    pub fn params(&self) -> &[FieldType] {
+        self.params.as_ref()
+    }
+
+    // None means void
+    pub fn return_type(&self) -> Option<&FieldType> {
+        self.return_type.as_ref()
+    }
+
+    // Slots taken by the arguments, not counting `this` for instance methods
+    pub fn arg_slots(&self) -> usize {
+        self.params.iter().map(FieldType::slots).sum()
+    }
+
+    pub fn return_slots(&self) -> usize {
+        self.return_type.as_ref().map_or(0, FieldType::slots)
+    }
+}
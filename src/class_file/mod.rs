@@ -1,6 +1,14 @@
-pub mod attribute_info;
-pub mod class_file;
-pub mod constant_pool;
-pub mod from_bytes;
-pub mod pos_slice;
-pub mod read;
\ No newline at end of file
+pub mod access_flags;
+pub mod attribute_info;
+pub mod bytecode;
+#[allow(clippy::module_inception)]
+pub mod class_file;
+#[allow(clippy::upper_case_acronyms)]
+pub mod constant_pool;
+pub mod descriptor;
+pub mod from_bytes;
+pub mod pos_slice;
+pub mod read;
+pub mod reader;
+pub mod signature;
+pub mod view;
\ No newline at end of file
@@ -1,94 +1,118 @@
-use std::cell::Cell;
-use byteorder::{ByteOrder, BigEndian};
-
-use result::{Result, Error};
-use types::{u1, u2, u4};
-
-#[derive(Clone)]
-pub struct PoSlice<'a> {
-    bytes: &'a [u1],
-    pos: Cell<usize>,
-}
-
-impl<'a> PoSlice<'a> {
-    pub fn new(bytes: &'a [u8]) -> Self {
-        PoSlice { bytes, pos: Cell::new(0) }
-    }
-
-    #[inline]
-    fn ensure_index(&self, pos: usize) -> Result<()> {
-        println!("pos: {}", pos);
-        if pos >= self.bytes.len() {
-            Err(Error::OutOfBounds(pos))
-        } else {
-            Ok(())
-        }
-    }
-
-    pub fn read_u1(&self) -> Result<u1> {
-        self.ensure_index(self.pos.get())?;
-        let byte = self.bytes[self.pos.get()];
-        self.pos.set(self.pos.get() + 1);
-        Ok(byte)
-    }
-
-    pub fn read_u2(&self) -> Result<u2> {
-        self.ensure_index(self.pos.get() + 1)?;
-        let byte = BigEndian::read_u16(&self.bytes[self.pos.get()..]);
-        self.pos.set(self.pos.get() + 2);
-        Ok(byte)
-    }
-
-    pub fn read_u4(&self) -> Result<u4> {
-        self.ensure_index(self.pos.get() + 3)?;
-        let byte = BigEndian::read_u32(&self.bytes[self.pos.get()..]);
-        self.pos.set(self.pos.get() + 4);
-        Ok(byte)
-    }
-
-    pub fn read_slice(&self, len: usize) -> Result<&[u1]> {
-        self.ensure_index(self.pos.get() + len - 1)?;
-        let slice = &self.bytes[self.pos.get()..self.pos.get() + len];
-        self.pos.set(self.pos.get() + len);
-        Ok(slice)
-    }
-
-    pub fn read_slice_vec(&self, len: usize) -> Result<Vec<u1>> {
-        let pos = self.pos.get();
-        self.ensure_index(pos + len - 1)?;
-        let slice = &self.bytes[pos..pos + len];
-        self.pos.set(pos + len);
-        Ok(slice.to_vec())
-    }
-
-    #[inline]
-    pub fn peek_u1(&self) -> Result<u1> {
-        self.ensure_index(self.pos.get())?;
-        Ok(self.bytes[self.pos.get()])
-    }
-
-    #[inline]
-    pub fn peek_u2(&self) -> Result<u2> {
-        self.ensure_index(self.pos.get() + 1)?;
-        Ok(BigEndian::read_u16(&self.bytes[self.pos.get()..]))
-    }
-
-    #[inline]
-    pub fn peek_u4(&self) -> Result<u4> {
-        self.ensure_index(self.pos.get() + 3)?;
-        Ok(BigEndian::read_u32(&self.bytes[self.pos.get()..]))
-    }
-
-    pub fn peek_slice(&self, pos: usize) -> Result<&[u1]> {
-        self.ensure_index(self.pos.get() + pos)?;
-        Ok(&self.bytes[self.pos.get() + pos..])
-    }
-
-    pub fn skip(&self, len: usize) -> Result<usize> {
-        self.ensure_index(self.pos.get() + len)?;
-        self.pos.set(self.pos.get() + len);
-        Ok(len)
-    }
-
-    pub fn pos(&self) -> usize { self.pos.get() }
-}
\ No newline at end of file
+use std::cell::Cell;
+use byteorder::{ByteOrder, BigEndian};
+
+use result::{Result, Error};
+use trace::Category;
+use types::{u1, u2, u4};
+
+// Offsets are always into the whole input, children made by sub_slice included
+#[derive(Clone)]
+pub struct PoSlice<'a> {
+    bytes: &'a [u1],
+    pos: Cell<usize>,
+    // end of the structure being decoded, see sub_slice and Reader::bounded
+    limit: Cell<Option<usize>>,
+}
+
+impl<'a> PoSlice<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        PoSlice { bytes, pos: Cell::new(0), limit: Cell::new(None) }
+    }
+
+    // Start and end of the next `len` bytes, checked without overflowing
+    #[inline]
+    fn range(&self, len: usize) -> Result<(usize, usize)> {
+        let pos = self.pos.get();
+        let end = pos.checked_add(len).ok_or(Error::OutOfBounds(pos))?;
+        match self.limit.get() {
+            Some(limit) if end > limit => Err(Error::PastEnd(end)),
+            _ if end > self.bytes.len() => Err(Error::OutOfBounds(end - 1)),
+            _ => Ok((pos, end)),
+        }
+    }
+
+    #[inline]
+    fn take(&self, len: usize) -> Result<&'a [u1]> {
+        let (pos, end) = self.range(len)?;
+        self.pos.set(end);
+        Ok(&self.bytes[pos..end])
+    }
+
+    pub fn read_u1(&self) -> Result<u1> {
+        trace!(Category::Reads, "u1 at {}", self.pos.get());
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_u2(&self) -> Result<u2> {
+        trace!(Category::Reads, "u2 at {}", self.pos.get());
+        Ok(BigEndian::read_u16(self.take(2)?))
+    }
+
+    pub fn read_u4(&self) -> Result<u4> {
+        trace!(Category::Reads, "u4 at {}", self.pos.get());
+        Ok(BigEndian::read_u32(self.take(4)?))
+    }
+
+    pub fn read_slice(&self, len: usize) -> Result<&'a [u1]> {
+        trace!(Category::Reads, "{} bytes at {}", len, self.pos.get());
+        self.take(len)
+    }
+
+    pub fn read_slice_vec(&self, len: usize) -> Result<Vec<u1>> {
+        trace!(Category::Reads, "{} bytes at {}", len, self.pos.get());
+        self.take(len).map(<[u1]>::to_vec)
+    }
+
+    #[inline]
+    pub fn peek_u1(&self) -> Result<u1> {
+        let (pos, _) = self.range(1)?;
+        Ok(self.bytes[pos])
+    }
+
+    #[inline]
+    pub fn peek_u2(&self) -> Result<u2> {
+        let (pos, end) = self.range(2)?;
+        Ok(BigEndian::read_u16(&self.bytes[pos..end]))
+    }
+
+    #[inline]
+    pub fn peek_u4(&self) -> Result<u4> {
+        let (pos, end) = self.range(4)?;
+        Ok(BigEndian::read_u32(&self.bytes[pos..end]))
+    }
+
+    // What is left after skipping `pos` bytes, empty at the end
+    pub fn peek_slice(&self, pos: usize) -> Result<&'a [u1]> {
+        let (_, start) = self.range(pos)?;
+        Ok(&self.bytes[start..self.end()])
+    }
+
+    pub fn skip(&self, len: usize) -> Result<usize> {
+        self.take(len).map(|_| len)
+    }
+
+    // Reader over the next `len` bytes only, this one moves past them
+    pub fn sub_slice(&self, len: usize) -> Result<PoSlice<'a>> {
+        let (pos, end) = self.range(len)?;
+        self.pos.set(end);
+        Ok(PoSlice { bytes: self.bytes, pos: Cell::new(pos), limit: Cell::new(Some(end)) })
+    }
+
+    pub fn pos(&self) -> usize { self.pos.get() }
+
+    // Bytes left before the end of the input or of the structure being decoded
+    pub fn remaining(&self) -> usize {
+        self.end().saturating_sub(self.pos.get())
+    }
+
+    fn end(&self) -> usize {
+        self.limit.get().map_or(self.bytes.len(), |limit| limit.min(self.bytes.len()))
+    }
+
+    pub(super) fn limit(&self) -> Option<usize> { self.limit.get() }
+
+    pub(super) fn set_limit(&self, limit: Option<usize>) { self.limit.set(limit) }
+
+    // The whole input, not only what is left
+    pub fn bytes(&self) -> &'a [u1] { self.bytes }
+}
@@ -0,0 +1,135 @@
+/*
+* Where the decoders in from_bytes, read, constant_pool and attribute_info take their bytes from.
+* PoSlice reads a class that is already in memory, Stream reads from any io::Read so a class can
+* be parsed straight from a zip entry or a socket. Stream never reads past the end of the class,
+* which also means it reads a few bytes at a time: wrap files and sockets in a BufReader.
+*/
+use std::cell::{Cell, RefCell};
+use std::io::{self, Read};
+use byteorder::{ByteOrder, BigEndian};
+
+use class_file::pos_slice::PoSlice;
+use result::{Result, Error};
+use trace::Category;
+use types::{u1, u2, u4};
+
+pub trait Reader {
+    fn read_u1(&self) -> Result<u1>;
+
+    fn read_u2(&self) -> Result<u2>;
+
+    fn read_u4(&self) -> Result<u4>;
+
+    fn read_slice_vec(&self, len: usize) -> Result<Vec<u1>>;
+
+    // Bytes read so far
+    fn pos(&self) -> usize;
+
+    // End of the structure being decoded, None outside of bounded
+    fn limit(&self) -> Option<usize>;
+
+    fn set_limit(&self, limit: Option<usize>);
+
+    // Decodes the next `len` bytes with `decode`, which fails with Error::PastEnd instead of
+    // reading past them. Returns the value and how many of the bytes it left unread.
+    fn bounded<T, F>(&self, len: usize, decode: F) -> Result<(T, usize)>
+    where Self: Sized, F: FnOnce(&Self) -> Result<T> {
+        let end = self.pos().checked_add(len).ok_or(Error::OutOfBounds(self.pos()))?;
+        if self.limit().is_some_and(|limit| end > limit) {
+            return Err(Error::PastEnd(end));
+        }
+        let outer = self.limit();
+        self.set_limit(Some(end));
+        let value = decode(self);
+        self.set_limit(outer);
+        Ok((value?, end - self.pos()))
+    }
+}
+
+impl<'a> Reader for PoSlice<'a> {
+    fn read_u1(&self) -> Result<u1> { PoSlice::read_u1(self) }
+
+    fn read_u2(&self) -> Result<u2> { PoSlice::read_u2(self) }
+
+    fn read_u4(&self) -> Result<u4> { PoSlice::read_u4(self) }
+
+    fn read_slice_vec(&self, len: usize) -> Result<Vec<u1>> { PoSlice::read_slice_vec(self, len) }
+
+    fn pos(&self) -> usize { PoSlice::pos(self) }
+
+    fn limit(&self) -> Option<usize> { PoSlice::limit(self) }
+
+    fn set_limit(&self, limit: Option<usize>) { PoSlice::set_limit(self, limit) }
+}
+
+pub struct Stream<R> {
+    inner: RefCell<R>,
+    pos: Cell<usize>,
+    limit: Cell<Option<usize>>,
+}
+
+impl<R: Read> Stream<R> {
+    pub fn new(inner: R) -> Self {
+        Stream { inner: RefCell::new(inner), pos: Cell::new(0), limit: Cell::new(None) }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+
+    // Nothing is read from `inner` when the bytes would go past the limit
+    fn check_limit(&self, len: usize) -> Result<()> {
+        let end = self.pos.get().checked_add(len).ok_or(Error::OutOfBounds(self.pos.get()))?;
+        match self.limit.get() {
+            Some(limit) if end > limit => Err(Error::PastEnd(end)),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_exact(&self, buf: &mut [u1]) -> Result<()> {
+        trace!(Category::Reads, "{} bytes at {}", buf.len(), self.pos.get());
+        self.check_limit(buf.len())?;
+        self.inner.borrow_mut().read_exact(buf)?;
+        self.pos.set(self.pos.get() + buf.len());
+        Ok(())
+    }
+}
+
+impl<R: Read> Reader for Stream<R> {
+    fn read_u1(&self) -> Result<u1> {
+        let mut buf = [0; 1];
+        self.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    fn read_u2(&self) -> Result<u2> {
+        let mut buf = [0; 2];
+        self.read_exact(&mut buf)?;
+        Ok(BigEndian::read_u16(&buf))
+    }
+
+    fn read_u4(&self) -> Result<u4> {
+        let mut buf = [0; 4];
+        self.read_exact(&mut buf)?;
+        Ok(BigEndian::read_u32(&buf))
+    }
+
+    // A corrupt length must not allocate up front what the stream doesn't have
+    fn read_slice_vec(&self, len: usize) -> Result<Vec<u1>> {
+        trace!(Category::Reads, "{} bytes at {}", len, self.pos.get());
+        self.check_limit(len)?;
+        let mut bytes = Vec::new();
+        self.inner.borrow_mut().by_ref().take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() < len {
+            return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        self.pos.set(self.pos.get() + len);
+        Ok(bytes)
+    }
+
+    fn pos(&self) -> usize { self.pos.get() }
+
+    fn limit(&self) -> Option<usize> { self.limit.get() }
+
+    fn set_limit(&self, limit: Option<usize>) { self.limit.set(limit) }
+}
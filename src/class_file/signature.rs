@@ -0,0 +1,324 @@
+use std::str::Chars;
+use std::iter::Peekable;
+
+use class_file::descriptor::{java_name, FieldType};
+use result::{Result, Error};
+
+// JVMS §4.7.9.1, what the Signature attribute holds for generic classes, fields and methods
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeSignature {
+    Base(FieldType),
+    Class(ClassTypeSignature),
+    Variable(String),
+    Array(Box<TypeSignature>),
+}
+
+impl TypeSignature {
+    pub fn parse(signature: &str) -> Result<Self> {
+        let mut parser = Parser::new(signature);
+        let type_signature = parser.type_signature()?;
+        parser.end()?;
+        Ok(type_signature)
+    }
+
+    // Source form, e.g. "java.util.List<? extends T>[]"
+    pub fn type_name(&self) -> String {
+        match *self {
+            TypeSignature::Base(ref field_type) => field_type.type_name(),
+            TypeSignature::Class(ref class) => class.type_name(),
+            TypeSignature::Variable(ref name) => name.clone(),
+            TypeSignature::Array(ref component) => format!("{}[]", component.type_name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassTypeSignature {
+    // The binary name with its type arguments, then every inner class with its own,
+    // java/util/Map<TK;TV;>.Entry is ("java/util/Map", [K, V]), ("Entry", [])
+    pub(super) parts: Box<[(String, Box<[TypeArgument]>)]>,
+}
+
+impl ClassTypeSignature {
+    pub fn parts(&self) -> &[(String, Box<[TypeArgument]>)] {
+        self.parts.as_ref()
+    }
+
+    pub fn is_object(&self) -> bool {
+        self.parts.len() == 1 && self.parts[0].0 == "java/lang/Object" && self.parts[0].1.is_empty()
+    }
+
+    pub fn type_name(&self) -> String {
+        self.parts.iter()
+            .map(|(name, arguments)| {
+                if arguments.is_empty() {
+                    java_name(name)
+                } else {
+                    let arguments = arguments.iter().map(TypeArgument::type_name).collect::<Vec<_>>();
+                    format!("{}<{}>", java_name(name), arguments.join(", "))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeArgument {
+    // *
+    Any,
+    Exact(TypeSignature),
+    // +
+    Extends(TypeSignature),
+    // -
+    Super(TypeSignature),
+}
+
+impl TypeArgument {
+    pub fn type_name(&self) -> String {
+        match *self {
+            TypeArgument::Any => "?".to_string(),
+            TypeArgument::Exact(ref bound) => bound.type_name(),
+            TypeArgument::Extends(ref bound) => format!("? extends {}", bound.type_name()),
+            TypeArgument::Super(ref bound) => format!("? super {}", bound.type_name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeParameter {
+    pub(super) name: String,
+    // Left out when the parameter is only bounded by interfaces, <T::Ljava/lang/Runnable;>
+    pub(super) class_bound: Option<TypeSignature>,
+    pub(super) interface_bounds: Box<[TypeSignature]>,
+}
+
+impl TypeParameter {
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn class_bound(&self) -> Option<&TypeSignature> { self.class_bound.as_ref() }
+
+    pub fn interface_bounds(&self) -> &[TypeSignature] { self.interface_bounds.as_ref() }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassSignature {
+    pub(super) type_params: Box<[TypeParameter]>,
+    pub(super) super_class: ClassTypeSignature,
+    pub(super) interfaces: Box<[ClassTypeSignature]>,
+}
+
+impl ClassSignature {
+    pub fn parse(signature: &str) -> Result<Self> {
+        let mut parser = Parser::new(signature);
+        let type_params = parser.type_params()?;
+        let super_class = parser.class_type()?;
+        let mut interfaces = Vec::new();
+        while !parser.at_end() {
+            interfaces.push(parser.class_type()?);
+        }
+        Ok(ClassSignature {
+            type_params,
+            super_class,
+            interfaces: interfaces.into_boxed_slice(),
+        })
+    }
+
+    pub fn type_params(&self) -> &[TypeParameter] { self.type_params.as_ref() }
+
+    pub fn super_class(&self) -> &ClassTypeSignature { &self.super_class }
+
+    pub fn interfaces(&self) -> &[ClassTypeSignature] { self.interfaces.as_ref() }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodSignature {
+    pub(super) type_params: Box<[TypeParameter]>,
+    pub(super) params: Box<[TypeSignature]>,
+    pub(super) return_type: Option<TypeSignature>,
+    pub(super) throws: Box<[TypeSignature]>,
+}
+
+impl MethodSignature {
+    pub fn parse(signature: &str) -> Result<Self> {
+        let mut parser = Parser::new(signature);
+        let type_params = parser.type_params()?;
+        parser.expect('(')?;
+        let mut params = Vec::new();
+        while !parser.eat(')') {
+            params.push(parser.type_signature()?);
+        }
+        let return_type = if parser.eat('V') { None } else { Some(parser.type_signature()?) };
+        let mut throws = Vec::new();
+        while parser.eat('^') {
+            throws.push(parser.reference_type()?);
+        }
+        parser.end()?;
+        Ok(MethodSignature {
+            type_params,
+            params: params.into_boxed_slice(),
+            return_type,
+            throws: throws.into_boxed_slice(),
+        })
+    }
+
+    pub fn type_params(&self) -> &[TypeParameter] { self.type_params.as_ref() }
+
+    pub fn params(&self) -> &[TypeSignature] { self.params.as_ref() }
+
+    // None means void
+    pub fn return_type(&self) -> Option<&TypeSignature> { self.return_type.as_ref() }
+
+    pub fn throws(&self) -> &[TypeSignature] { self.throws.as_ref() }
+}
+
+struct Parser<'a> {
+    signature: &'a str,
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(signature: &'a str) -> Self {
+        Parser { signature, chars: signature.chars().peekable() }
+    }
+
+    fn error(&self) -> Error {
+        Error::InvalidSignature(self.signature.to_string())
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.chars.peek().is_none()
+    }
+
+    fn end(&mut self) -> Result<()> {
+        if self.at_end() { Ok(()) } else { Err(self.error()) }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.chars.peek() == Some(&expected) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        if self.eat(expected) { Ok(()) } else { Err(self.error()) }
+    }
+
+    fn identifier(&mut self) -> Result<String> {
+        let mut identifier = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if let '.' | ';' | '[' | '/' | '<' | '>' | ':' = c {
+                break;
+            }
+            identifier.push(c);
+            self.chars.next();
+        }
+        if identifier.is_empty() { Err(self.error()) } else { Ok(identifier) }
+    }
+
+    fn type_params(&mut self) -> Result<Box<[TypeParameter]>> {
+        let mut type_params = Vec::new();
+        if self.eat('<') {
+            while !self.eat('>') {
+                let name = self.identifier()?;
+                self.expect(':')?;
+                let class_bound = match self.chars.peek() {
+                    Some(':') => None,
+                    _ => Some(self.reference_type()?),
+                };
+                let mut interface_bounds = Vec::new();
+                while self.eat(':') {
+                    interface_bounds.push(self.reference_type()?);
+                }
+                type_params.push(TypeParameter {
+                    name,
+                    class_bound,
+                    interface_bounds: interface_bounds.into_boxed_slice(),
+                });
+            }
+            if type_params.is_empty() {
+                return Err(self.error());
+            }
+        }
+        Ok(type_params.into_boxed_slice())
+    }
+
+    fn type_signature(&mut self) -> Result<TypeSignature> {
+        let base = match self.chars.peek() {
+            Some('B') => FieldType::Byte,
+            Some('C') => FieldType::Char,
+            Some('D') => FieldType::Double,
+            Some('F') => FieldType::Float,
+            Some('I') => FieldType::Int,
+            Some('J') => FieldType::Long,
+            Some('S') => FieldType::Short,
+            Some('Z') => FieldType::Boolean,
+            _ => return self.reference_type(),
+        };
+        self.chars.next();
+        Ok(TypeSignature::Base(base))
+    }
+
+    fn reference_type(&mut self) -> Result<TypeSignature> {
+        match self.chars.peek() {
+            Some('L') => Ok(TypeSignature::Class(self.class_type()?)),
+            Some('T') => {
+                self.chars.next();
+                let name = self.identifier()?;
+                self.expect(';')?;
+                Ok(TypeSignature::Variable(name))
+            },
+            Some('[') => {
+                self.chars.next();
+                Ok(TypeSignature::Array(Box::new(self.type_signature()?)))
+            },
+            _ => Err(self.error()),
+        }
+    }
+
+    fn class_type(&mut self) -> Result<ClassTypeSignature> {
+        self.expect('L')?;
+        let mut name = self.identifier()?;
+        while self.eat('/') {
+            name.push('/');
+            name.push_str(&self.identifier()?);
+        }
+        let mut parts = vec![(name, self.type_arguments()?)];
+        while self.eat('.') {
+            let name = self.identifier()?;
+            parts.push((name, self.type_arguments()?));
+        }
+        self.expect(';')?;
+        Ok(ClassTypeSignature { parts: parts.into_boxed_slice() })
+    }
+
+    fn type_arguments(&mut self) -> Result<Box<[TypeArgument]>> {
+        let mut arguments = Vec::new();
+        if self.eat('<') {
+            while !self.eat('>') {
+                arguments.push(match self.chars.peek() {
+                    Some('*') => {
+                        self.chars.next();
+                        TypeArgument::Any
+                    },
+                    Some('+') => {
+                        self.chars.next();
+                        TypeArgument::Extends(self.reference_type()?)
+                    },
+                    Some('-') => {
+                        self.chars.next();
+                        TypeArgument::Super(self.reference_type()?)
+                    },
+                    _ => TypeArgument::Exact(self.reference_type()?),
+                });
+            }
+            if arguments.is_empty() {
+                return Err(self.error());
+            }
+        }
+        Ok(arguments.into_boxed_slice())
+    }
+}
@@ -0,0 +1,437 @@
+/*
+* Borrowing, lazy counterpart of ClassFile for scanning many classes. Parsing only records where
+* constant pool entries and members start, strings and code are slices of the input and
+* attributes are decoded when asked for. The only copies are Utf8 constants that are modified
+* UTF-8 but not UTF-8, and the offset and member tables.
+*/
+use std::borrow::Cow;
+use std::slice;
+use std::str;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use class_file::access_flags::{AccessFlags, ClassFlags, FieldFlags, MethodFlags};
+use class_file::attribute_info::{Exception, LineNumber};
+use class_file::class_file::MAGIC;
+use class_file::constant_pool::{decode_modified_utf8, CPInfoRep, Tag};
+use class_file::pos_slice::PoSlice;
+use class_file::reader::Reader;
+use result::{Result, Error};
+use types::{u1, u2, u4};
+
+pub struct ClassView<'a> {
+    minor_version: u2,
+    major_version: u2,
+    constant_pool: PoolView<'a>,
+    access_flags: u2,
+    this_class: u2,
+    super_class: u2,
+    interfaces: U2s<'a>,
+    fields: Box<[MemberView<'a>]>,
+    methods: Box<[MemberView<'a>]>,
+    attributes: Attributes<'a>,
+}
+
+impl<'a> ClassView<'a> {
+    pub fn parse(bytes: &'a [u1]) -> Result<Self> {
+        let slice = PoSlice::new(bytes);
+        let magic = slice.read_u4()?;
+        if magic != MAGIC {
+            return Err(Error::Magic(magic).at(0));
+        }
+        let minor_version = slice.read_u2()?;
+        let major_version = slice.read_u2()?;
+        let constant_pool = PoolView::read(&slice)?;
+        let access_flags = slice.read_u2()?;
+        let this_class = slice.read_u2()?;
+        let super_class = slice.read_u2()?;
+        let interfaces = U2s::read(&slice)?;
+        let fields = MemberView::read_members(&slice)?;
+        let methods = MemberView::read_members(&slice)?;
+        let attributes = Attributes::read(&slice)?;
+        Ok(ClassView {
+            minor_version,
+            major_version,
+            constant_pool,
+            access_flags,
+            this_class,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            attributes,
+        })
+    }
+
+    pub fn minor_version(&self) -> u2 { self.minor_version }
+
+    pub fn major_version(&self) -> u2 { self.major_version }
+
+    pub fn access_flags(&self) -> ClassFlags {
+        ClassFlags::new(self.access_flags)
+    }
+
+    pub fn this_class(&self) -> u2 { self.this_class }
+
+    pub fn super_class(&self) -> u2 { self.super_class }
+
+    pub fn constant_pool(&self) -> &PoolView<'a> {
+        &self.constant_pool
+    }
+
+    pub fn interfaces(&self) -> U2s<'a> {
+        self.interfaces.clone()
+    }
+
+    pub fn fields(&self) -> &[MemberView<'a>] {
+        self.fields.as_ref()
+    }
+
+    pub fn methods(&self) -> &[MemberView<'a>] {
+        self.methods.as_ref()
+    }
+
+    pub fn attributes(&self) -> Attributes<'a> {
+        self.attributes.clone()
+    }
+
+    pub fn name(&self) -> Result<Cow<'a, str>> {
+        self.constant_pool.class_name(self.this_class)
+    }
+
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Result<Option<&MemberView<'a>>> {
+        for method in self.methods.iter() {
+            if self.constant_pool.utf8(method.name_index)? == name &&
+                self.constant_pool.utf8(method.descriptor_index)? == descriptor {
+                return Ok(Some(method));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// Offsets of the entries, each starting with its tag. 0 for the unusable slots
+pub struct PoolView<'a> {
+    bytes: &'a [u1],
+    offsets: Box<[u4]>,
+}
+
+impl<'a> PoolView<'a> {
+    fn read(slice: &PoSlice<'a>) -> Result<Self> {
+        let count = slice.read_u2()? as usize;
+        let mut offsets = vec![0; count.max(1)];
+        let mut index = 1;
+        while index < count {
+            offsets[index] = slice.pos() as u4;
+            let tag = Tag::new(slice.read_u1()?)?;
+            let length = match tag {
+                Tag::UTF8 => slice.read_u2()? as usize,
+                Tag::CLASS | Tag::STRING | Tag::METHOD_TYPE | Tag::MODULE | Tag::PACKAGE => 2,
+                Tag::METHOD_HANDLE => 3,
+                Tag::LONG | Tag::DOUBLE => 8,
+                _ => 4,
+            };
+            slice.read_slice(length)?;
+            index += if tag == Tag::LONG || tag == Tag::DOUBLE { 2 } else { 1 };
+        }
+        Ok(PoolView { bytes: slice.bytes(), offsets: offsets.into_boxed_slice() })
+    }
+
+    // constant_pool_count, like ConstantPoolRep::len
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.len() <= 1
+    }
+
+    // Slice starting at the tag of the entry
+    fn entry_slice(&self, index: u2) -> Result<PoSlice<'a>> {
+        match self.offsets.get(index as usize) {
+            Some(&0) => Err(Error::EmptyCPInfo(index)),
+            Some(&offset) => Ok(PoSlice::new(&self.bytes[offset as usize..])),
+            None => Err(Error::Index(index as usize)),
+        }
+    }
+
+    pub fn tag(&self, index: u2) -> Result<Tag> {
+        Tag::new(self.entry_slice(index)?.read_u1()?)
+    }
+
+    // Decodes one entry the way the eager parser does
+    pub fn get(&self, index: u2) -> Result<CPInfoRep> {
+        CPInfoRep::new(&self.entry_slice(index)?)
+    }
+
+    // Borrowed unless the bytes are modified UTF-8 that UTF-8 doesn't accept
+    pub fn utf8(&self, index: u2) -> Result<Cow<'a, str>> {
+        let slice = self.entry_slice(index)?;
+        match Tag::new(slice.read_u1()?)? {
+            Tag::UTF8 => {
+                let length = slice.read_u2()? as usize;
+                let bytes = slice.read_slice(length)?;
+                Ok(match str::from_utf8(bytes) {
+                    Ok(text) => Cow::Borrowed(text),
+                    Err(_) => Cow::Owned(decode_modified_utf8(bytes)?),
+                })
+            },
+            tag => Err(Error::WrongTag(index as usize, &[Tag::UTF8], tag)),
+        }
+    }
+
+    // Both u2 of a Class, String, NameAndType, Fieldref... entry with the expected tag
+    fn references(&self, index: u2, expected: &'static Tag) -> Result<(u2, u2)> {
+        let slice = self.entry_slice(index)?;
+        let tag = Tag::new(slice.read_u1()?)?;
+        if tag != *expected {
+            return Err(Error::WrongTag(index as usize, slice::from_ref(expected), tag));
+        }
+        let first = slice.read_u2()?;
+        let second = if *expected == Tag::CLASS { 0 } else { slice.read_u2()? };
+        Ok((first, second))
+    }
+
+    pub fn class_name(&self, index: u2) -> Result<Cow<'a, str>> {
+        let (name_index, _) = self.references(index, &Tag::CLASS)?;
+        self.utf8(name_index)
+    }
+
+    pub fn name_and_type(&self, index: u2) -> Result<(Cow<'a, str>, Cow<'a, str>)> {
+        let (name_index, descriptor_index) = self.references(index, &Tag::NAME_AND_TYPE)?;
+        Ok((self.utf8(name_index)?, self.utf8(descriptor_index)?))
+    }
+}
+
+pub struct MemberView<'a> {
+    access_flags: u2,
+    name_index: u2,
+    descriptor_index: u2,
+    attributes: Attributes<'a>,
+}
+
+impl<'a> MemberView<'a> {
+    fn read_members(slice: &PoSlice<'a>) -> Result<Box<[Self]>> {
+        let count = slice.read_u2()?;
+        let mut members = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            members.push(MemberView {
+                access_flags: slice.read_u2()?,
+                name_index: slice.read_u2()?,
+                descriptor_index: slice.read_u2()?,
+                attributes: Attributes::read(slice)?,
+            });
+        }
+        Ok(members.into_boxed_slice())
+    }
+
+    pub fn access_flags(&self) -> AccessFlags {
+        AccessFlags::new(self.access_flags)
+    }
+
+    pub fn field_flags(&self) -> FieldFlags {
+        FieldFlags::new(self.access_flags)
+    }
+
+    pub fn method_flags(&self) -> MethodFlags {
+        MethodFlags::new(self.access_flags)
+    }
+
+    pub fn name_index(&self) -> u2 { self.name_index }
+
+    pub fn descriptor_index(&self) -> u2 { self.descriptor_index }
+
+    pub fn attributes(&self) -> Attributes<'a> {
+        self.attributes.clone()
+    }
+
+    pub fn code(&self, pool: &PoolView<'a>) -> Result<Option<CodeView<'a>>> {
+        for attribute in self.attributes() {
+            if let Attribute::Code(code) = attribute?.decode(pool)? {
+                return Ok(Some(code));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// attributes_count and the attributes after it, walked on every iteration
+#[derive(Clone)]
+pub struct Attributes<'a> {
+    count: u2,
+    slice: PoSlice<'a>,
+}
+
+impl<'a> Attributes<'a> {
+    // Skips over the attributes, only their lengths are read
+    fn read(slice: &PoSlice<'a>) -> Result<Self> {
+        let count = slice.read_u2()?;
+        let start = slice.pos();
+        for _ in 0..count {
+            slice.read_u2()?;
+            let length = slice.read_u4()?;
+            slice.read_slice(length as usize)?;
+        }
+        Ok(Attributes { count, slice: PoSlice::new(&slice.bytes()[start..slice.pos()]) })
+    }
+
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = Result<AttributeView<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        let read = || -> Result<AttributeView<'a>> {
+            let name_index = self.slice.read_u2()?;
+            let length = self.slice.read_u4()?;
+            Ok(AttributeView { name_index, info: self.slice.read_slice(length as usize)? })
+        };
+        Some(read())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct AttributeView<'a> {
+    name_index: u2,
+    info: &'a [u1],
+}
+
+impl<'a> AttributeView<'a> {
+    pub fn name_index(&self) -> u2 { self.name_index }
+
+    pub fn info(&self) -> &'a [u1] { self.info }
+
+    pub fn name(&self, pool: &PoolView<'a>) -> Result<Cow<'a, str>> {
+        pool.utf8(self.name_index)
+    }
+
+    // Attributes the runtime needs, everything else, BootstrapMethods included, stays Other
+    pub fn decode(&self, pool: &PoolView<'a>) -> Result<Attribute<'a>> {
+        let name = self.name(pool)?;
+        let slice = PoSlice::new(self.info);
+        let length = self.info.len() as u4;
+        // Like AttributeInfo::new, the info is decoded to its last byte and not past it
+        let (attribute, unread) = slice.bounded(self.info.len(), |slice| self.decode_info(slice, &name))
+            .map_err(|err| match *err.cause() {
+                Error::PastEnd(end) => Error::AttributeLength(length, end),
+                _ => err,
+            })?;
+        if unread != 0 {
+            return Err(Error::AttributeLength(length, self.info.len() - unread));
+        }
+        Ok(attribute)
+    }
+
+    fn decode_info(&self, slice: &PoSlice<'a>, name: &str) -> Result<Attribute<'a>> {
+        Ok(match name {
+            "ConstantValue" => Attribute::ConstantValue(slice.read_u2()?),
+            "SourceFile" => Attribute::SourceFile(slice.read_u2()?),
+            "Code" => {
+                let max_stack = slice.read_u2()?;
+                let max_locals = slice.read_u2()?;
+                let code_length = slice.read_u4()?;
+                let code = slice.read_slice(code_length as usize)?;
+                let exception_count = slice.read_u2()?;
+                let exception_table = slice.read_slice(exception_count as usize * 8)?;
+                let attributes = Attributes::read(slice)?;
+                Attribute::Code(CodeView { max_stack, max_locals, code, exception_table, attributes })
+            },
+            "LineNumberTable" => {
+                let count = slice.read_u2()?;
+                Attribute::LineNumberTable(LineNumbers(slice.read_slice(count as usize * 4)?))
+            },
+            _ => Attribute::Other(slice.read_slice(self.info.len())?),
+        })
+    }
+}
+
+pub enum Attribute<'a> {
+    ConstantValue(u2),
+    SourceFile(u2),
+    Code(CodeView<'a>),
+    LineNumberTable(LineNumbers<'a>),
+    Other(&'a [u1]),
+}
+
+pub struct CodeView<'a> {
+    max_stack: u2,
+    max_locals: u2,
+    code: &'a [u1],
+    exception_table: &'a [u1],
+    attributes: Attributes<'a>,
+}
+
+impl<'a> CodeView<'a> {
+    pub fn max_stack(&self) -> u2 { self.max_stack }
+
+    pub fn max_locals(&self) -> u2 { self.max_locals }
+
+    pub fn code(&self) -> &'a [u1] { self.code }
+
+    pub fn exception_table(&self) -> impl Iterator<Item = Exception> + 'a {
+        self.exception_table.chunks(8).map(|entry| Exception {
+            start_pc: BigEndian::read_u16(&entry[0..]),
+            end_pc: BigEndian::read_u16(&entry[2..]),
+            handler_pc: BigEndian::read_u16(&entry[4..]),
+            catch_type: BigEndian::read_u16(&entry[6..]),
+        })
+    }
+
+    pub fn attributes(&self) -> Attributes<'a> {
+        self.attributes.clone()
+    }
+}
+
+#[derive(Clone)]
+pub struct LineNumbers<'a>(&'a [u1]);
+
+impl<'a> Iterator for LineNumbers<'a> {
+    type Item = LineNumber;
+
+    fn next(&mut self) -> Option<LineNumber> {
+        if self.0.len() < 4 {
+            return None;
+        }
+        let entry = LineNumber {
+            start_pc: BigEndian::read_u16(self.0),
+            line_number: BigEndian::read_u16(&self.0[2..]),
+        };
+        self.0 = &self.0[4..];
+        Some(entry)
+    }
+}
+
+// A u2 array like interfaces, read as it is iterated
+#[derive(Clone)]
+pub struct U2s<'a>(&'a [u1]);
+
+impl<'a> U2s<'a> {
+    fn read(slice: &PoSlice<'a>) -> Result<Self> {
+        let count = slice.read_u2()?;
+        Ok(U2s(slice.read_slice(count as usize * 2)?))
+    }
+}
+
+impl<'a> Iterator for U2s<'a> {
+    type Item = u2;
+
+    fn next(&mut self) -> Option<u2> {
+        if self.0.len() < 2 {
+            return None;
+        }
+        let value = BigEndian::read_u16(self.0);
+        self.0 = &self.0[2..];
+        Some(value)
+    }
+}
@@ -0,0 +1,277 @@
+/*
+* Reader for the jimage container (lib/modules) JDK 9+ ships its class library in.
+* The format is not specified anywhere, this follows jdk.internal.jimage.BasicImageReader.
+*/
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+use byteorder::{ByteOrder, BigEndian, LittleEndian};
+
+use class_path::ClassSource;
+use result::{Result, Error};
+use types::{u1, u4};
+
+const MAGIC: u4 = 0xCAFE_DADA;
+const MAJOR_VERSION: u4 = 1;
+const HEADER_SIZE: usize = 7 * 4;
+const HASH_MULTIPLIER: u4 = 0x0100_0193;
+
+const ATTRIBUTE_END: u1 = 0;
+const ATTRIBUTE_MODULE: usize = 1;
+const ATTRIBUTE_PARENT: usize = 2;
+const ATTRIBUTE_BASE: usize = 3;
+const ATTRIBUTE_EXTENSION: usize = 4;
+const ATTRIBUTE_OFFSET: usize = 5;
+const ATTRIBUTE_COMPRESSED: usize = 6;
+const ATTRIBUTE_UNCOMPRESSED: usize = 7;
+const ATTRIBUTE_COUNT: usize = 8;
+
+pub struct JImage {
+    file: Mutex<File>,
+    // The image is written in the byte order of the platform that built it
+    big_endian: bool,
+    table_length: usize,
+    // redirect table, offsets table, locations and strings, everything but the resources
+    index: Box<[u1]>,
+    locations_start: usize,
+    strings_start: usize,
+    resources_start: u64,
+    file_length: u64,
+    // package ("java/lang") to module ("java.base"), built on first class lookup
+    packages: Mutex<Option<HashMap<String, String>>>,
+}
+
+// Location of a resource inside the image, full name is /module/parent/base.extension
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub module: String,
+    pub parent: String,
+    pub base: String,
+    pub extension: String,
+    offset: u64,
+    compressed_size: u64,
+    uncompressed_size: u64,
+}
+
+impl Entry {
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        if !self.module.is_empty() {
+            name.push('/');
+            name.push_str(&self.module);
+            name.push('/');
+        }
+        if !self.parent.is_empty() {
+            name.push_str(&self.parent);
+            name.push('/');
+        }
+        name.push_str(&self.base);
+        if !self.extension.is_empty() {
+            name.push('.');
+            name.push_str(&self.extension);
+        }
+        name
+    }
+
+    pub fn size(&self) -> u64 { self.uncompressed_size }
+
+    pub fn is_compressed(&self) -> bool { self.compressed_size != 0 }
+}
+
+impl JImage {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut file = File::open(path)?;
+        let mut header = [0; HEADER_SIZE];
+        file.read_exact(&mut header)?;
+        let big_endian = match LittleEndian::read_u32(&header) {
+            MAGIC => false,
+            _ if BigEndian::read_u32(&header) == MAGIC => true,
+            magic => return Err(Error::Magic(magic)),
+        };
+        let read_u4 = |i: usize| if big_endian {
+            BigEndian::read_u32(&header[i * 4..])
+        } else {
+            LittleEndian::read_u32(&header[i * 4..])
+        };
+        let major_version = read_u4(1) >> 16;
+        if major_version != MAJOR_VERSION {
+            return Err(Error::Unsupported("jimage version"));
+        }
+        let table_length = read_u4(4) as usize;
+        let locations_size = read_u4(5) as u64;
+        let strings_size = read_u4(6) as u64;
+
+        // The sizes come from the file, check them before allocating
+        let file_length = file.metadata()?.len();
+        let index_size = table_length as u64 * 4 * 2 + locations_size + strings_size;
+        if HEADER_SIZE as u64 + index_size > file_length {
+            return Err(Error::OutOfBounds((HEADER_SIZE as u64 + index_size) as usize));
+        }
+        let locations_start = table_length * 4 * 2;
+        let strings_start = locations_start + locations_size as usize;
+        let mut index = vec![0; index_size as usize];
+        file.read_exact(&mut index)?;
+        Ok(JImage {
+            file: Mutex::new(file),
+            big_endian,
+            table_length,
+            resources_start: (HEADER_SIZE + index.len()) as u64,
+            file_length,
+            index: index.into_boxed_slice(),
+            locations_start,
+            strings_start,
+            packages: Mutex::new(None),
+        })
+    }
+
+    // Every resource in the image, including the /modules and /packages directory entries
+    pub fn entries(&self) -> Result<Vec<Entry>> {
+        (0..self.table_length)
+            .map(|index| self.entry_at(self.offset(index)))
+            .collect()
+    }
+
+    // `name` is the full name, like "/java.base/java/lang/Object.class"
+    pub fn find(&self, name: &str) -> Result<Option<Entry>> {
+        if self.table_length == 0 {
+            return Ok(None);
+        }
+        let bytes = name.as_bytes();
+        let redirect = self.redirect(hash_code(bytes, HASH_MULTIPLIER) as usize % self.table_length);
+        let index = if redirect < 0 {
+            // -1 - index, as u32 so that i32::MIN doesn't overflow
+            redirect.unsigned_abs() as usize - 1
+        } else if redirect > 0 {
+            hash_code(bytes, redirect as u4) as usize % self.table_length
+        } else {
+            return Ok(None);
+        };
+        // Where the offset of `index` would be read
+        if index >= self.table_length {
+            return Err(Error::OutOfBounds((self.table_length + index) * 4));
+        }
+        // The perfect hash only tells where the name would be, it can still be somebody else
+        let entry = self.entry_at(self.offset(index))?;
+        Ok(if entry.name() == name { Some(entry) } else { None })
+    }
+
+    pub fn find_in_module(&self, module: &str, name: &str) -> Result<Option<Entry>> {
+        self.find(&format!("/{}/{}", module, name))
+    }
+
+    pub fn read(&self, entry: &Entry) -> Result<Vec<u1>> {
+        if entry.is_compressed() {
+            return Err(Error::Unsupported("compressed jimage resource"));
+        }
+        let start = self.resources_start.saturating_add(entry.offset);
+        if start.saturating_add(entry.uncompressed_size) > self.file_length {
+            return Err(Error::OutOfBounds(start.saturating_add(entry.uncompressed_size) as usize));
+        }
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start(start))?;
+        let mut bytes = vec![0; entry.uncompressed_size as usize];
+        file.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    // Module that contains the package of `class_name`, e.g. "java.base" for "java/lang/Object"
+    pub fn module_of(&self, class_name: &str) -> Result<Option<String>> {
+        let package = match class_name.rfind('/') {
+            Some(end) => &class_name[..end],
+            None => "",
+        };
+        let mut packages = self.packages.lock().unwrap();
+        if packages.is_none() {
+            let mut map = HashMap::new();
+            for entry in self.entries()? {
+                if entry.extension == "class" && entry.module != "modules" && entry.module != "packages" {
+                    map.entry(entry.parent).or_insert(entry.module);
+                }
+            }
+            *packages = Some(map);
+        }
+        Ok(packages.as_ref().and_then(|map| map.get(package).cloned()))
+    }
+
+    fn redirect(&self, index: usize) -> i32 {
+        self.read_index_u4(index * 4) as i32
+    }
+
+    fn offset(&self, index: usize) -> usize {
+        self.read_index_u4((self.table_length + index) * 4) as usize
+    }
+
+    fn read_index_u4(&self, pos: usize) -> u4 {
+        if self.big_endian {
+            BigEndian::read_u32(&self.index[pos..])
+        } else {
+            LittleEndian::read_u32(&self.index[pos..])
+        }
+    }
+
+    // Attributes are stored as a kind/length byte followed by a big endian value
+    fn entry_at(&self, offset: usize) -> Result<Entry> {
+        let mut attributes = [0u64; ATTRIBUTE_COUNT];
+        let locations = &self.index[self.locations_start..self.strings_start];
+        let mut pos = offset;
+        loop {
+            let byte = *locations.get(pos).ok_or(Error::OutOfBounds(pos))?;
+            let kind = (byte >> 3) as usize;
+            if kind == ATTRIBUTE_END as usize {
+                break;
+            }
+            if kind >= ATTRIBUTE_COUNT {
+                return Err(Error::Unsupported("jimage location attribute"));
+            }
+            let length = (byte & 0x7) as usize + 1;
+            let value = locations.get(pos + 1..pos + 1 + length)
+                .ok_or(Error::OutOfBounds(pos + length))?
+                .iter()
+                .fold(0u64, |value, &byte| (value << 8) | u64::from(byte));
+            attributes[kind] = value;
+            pos += 1 + length;
+        }
+        Ok(Entry {
+            module: self.string(attributes[ATTRIBUTE_MODULE])?,
+            parent: self.string(attributes[ATTRIBUTE_PARENT])?,
+            base: self.string(attributes[ATTRIBUTE_BASE])?,
+            extension: self.string(attributes[ATTRIBUTE_EXTENSION])?,
+            offset: attributes[ATTRIBUTE_OFFSET],
+            compressed_size: attributes[ATTRIBUTE_COMPRESSED],
+            uncompressed_size: attributes[ATTRIBUTE_UNCOMPRESSED],
+        })
+    }
+
+    // Strings are NUL terminated, offset 0 is the empty string
+    fn string(&self, offset: u64) -> Result<String> {
+        let strings = &self.index[self.strings_start..];
+        let start = offset as usize;
+        let len = strings.get(start..)
+            .and_then(|rest| rest.iter().position(|&byte| byte == 0))
+            .ok_or(Error::OutOfBounds(start))?;
+        String::from_utf8(strings[start..start + len].to_vec())
+            .map_err(|_| Error::InvalidUtf8)
+    }
+}
+
+impl ClassSource for JImage {
+    fn find_class(&self, name: &str) -> Result<Option<Vec<u1>>> {
+        let module = match self.module_of(name)? {
+            Some(module) => module,
+            None => return Ok(None),
+        };
+        match self.find_in_module(&module, &format!("{}.class", name))? {
+            Some(entry) => self.read(&entry).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+// jdk.internal.jimage.ImageStringsReader.unmaskedHashCode over the UTF-8 bytes
+fn hash_code(bytes: &[u1], seed: u4) -> u4 {
+    bytes.iter()
+        .fold(seed, |hash, &byte| hash.wrapping_mul(HASH_MULTIPLIER) ^ u4::from(byte))
+        & 0x7FFF_FFFF
+}
@@ -0,0 +1,62 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use result::Result;
+use types::u1;
+
+pub mod jimage;
+
+// Anything class bytes can be loaded from. `name` is the internal binary name, e.g. "java/lang/Object"
+pub trait ClassSource: Send + Sync {
+    fn find_class(&self, name: &str) -> Result<Option<Vec<u1>>>;
+}
+
+// Classes laid out as package directories, the same as a -cp directory entry
+pub struct Directory(PathBuf);
+
+impl Directory {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Directory(path.into())
+    }
+}
+
+impl ClassSource for Directory {
+    fn find_class(&self, name: &str) -> Result<Option<Vec<u1>>> {
+        let mut file = match File::open(self.0.join(format!("{}.class", name))) {
+            Ok(file) => file,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Ok(Some(bytes))
+    }
+}
+
+// Searched in order, the first source that has the class wins
+#[derive(Default)]
+pub struct ClassPath {
+    sources: Vec<Box<dyn ClassSource>>,
+}
+
+impl ClassPath {
+    pub fn new() -> Self {
+        ClassPath::default()
+    }
+
+    pub fn push<S: ClassSource + 'static>(&mut self, source: S) {
+        self.sources.push(Box::new(source));
+    }
+}
+
+impl ClassSource for ClassPath {
+    fn find_class(&self, name: &str) -> Result<Option<Vec<u1>>> {
+        for source in self.sources.iter() {
+            if let Some(bytes) = source.find_class(name)? {
+                return Ok(Some(bytes));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,821 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use class_file::access_flags::{CLASS_FLAGS, FIELD_FLAGS, METHOD_FLAGS};
+use class_file::bytecode::{self, Instruction, Operand, OperandKind};
+use class_file::constant_pool::RefKind;
+use class_file::descriptor::MethodDescriptor;
+use jasm::{AtLine, Parsed};
+use jasm::lexer::{self, Line, Token};
+use jasm::pool::{push_u2, push_u4, Constant, Pool, Ref};
+use result::{Result, Error};
+use types::{u1, u2, u4};
+
+const MAGIC: u4 = 0xCAFE_BABE;
+
+pub fn assemble(source: &str) -> Result<Vec<u1>> {
+    let lines = lexer::tokenize(source)?;
+    let class = Parser { lines: &lines, pos: 0 }.class()?;
+    let last_line = lines.last().map_or(1, |line| line.number);
+    Assembler { pool: Pool::new(), last_line }.class(&class)
+}
+
+/* Syntax tree, references are resolved only after every `.const` is placed */
+
+struct Class {
+    minor_version: u2,
+    major_version: u2,
+    access_flags: u2,
+    this_class: (usize, Ref),
+    super_class: Option<(usize, Ref)>,
+    interfaces: Vec<(usize, Ref)>,
+    constants: Vec<(usize, u2, Constant<Ref>)>,
+    fields: Vec<Member>,
+    methods: Vec<Member>,
+    attributes: Vec<Attribute>,
+}
+
+struct Member {
+    line: usize,
+    access_flags: u2,
+    name: Ref,
+    descriptor: Ref,
+    attributes: Vec<Attribute>,
+}
+
+struct Attribute {
+    line: usize,
+    kind: AttributeKind,
+}
+
+enum AttributeKind {
+    Raw(Ref, Vec<u1>),
+    ConstantValue(Ref),
+    SourceFile(Ref),
+    Code(Code),
+    LineNumberTable(Vec<(Target, u2)>),
+    // Method handle and its arguments
+    BootstrapMethods(Vec<(Ref, Vec<Ref>)>),
+}
+
+struct Code {
+    max_stack: u2,
+    max_locals: u2,
+    items: Vec<(usize, Item)>,
+    catches: Vec<Catch>,
+    attributes: Vec<Attribute>,
+}
+
+enum Item {
+    Label(String),
+    Instruction { wide: bool, opcode: u1, operand: AsmOperand },
+}
+
+// bytecode::Operand before references and labels are resolved
+enum AsmOperand {
+    None,
+    Int(i32),
+    Local(u2),
+    Iinc { index: u2, delta: i16 },
+    Constant(Ref),
+    InvokeInterface(Ref, Option<u1>),
+    MultiANewArray(Ref, u1),
+    NewArray(u1),
+    Branch(Target),
+    TableSwitch { low: i32, targets: Vec<Target>, default: Target },
+    LookupSwitch { pairs: Vec<(i32, Target)>, default: Target },
+}
+
+struct Catch {
+    line: usize,
+    // None catches everything
+    catch_type: Option<Ref>,
+    start: Target,
+    end: Target,
+    handler: Target,
+}
+
+enum Target {
+    Label(String),
+    Pc(u4),
+}
+
+/* Parser */
+
+struct Parser<'a> {
+    lines: &'a [Line],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<&'a Line> {
+        let line = self.lines.get(self.pos)?;
+        self.pos += 1;
+        Some(line)
+    }
+
+    // Next line of the block opened at `start`, None once `.end <name>` is reached
+    fn block_line(&mut self, name: &str, start: usize) -> Result<Option<(usize, Tokens<'a>)>> {
+        let line = self.next()
+            .ok_or_else(|| Error::Syntax(start, format!("missing .end {}", name)))?;
+        let mut tokens = Tokens { tokens: &line.tokens, pos: 0 };
+        if tokens.peek_word() != Some(".end") {
+            return Ok(Some((line.number, tokens)));
+        }
+        tokens.next().at(line.number)?;
+        let end = tokens.word().at(line.number)?;
+        if end != name {
+            return Err(Error::Syntax(line.number, format!("expected .end {}, found .end {}", name, end)));
+        }
+        tokens.end().at(line.number)?;
+        Ok(None)
+    }
+
+    fn class(&mut self) -> Result<Class> {
+        let mut version = (52, 0);
+        let mut class_line = None;
+        let mut super_class = None;
+        let mut interfaces = Vec::new();
+        let mut constants = Vec::new();
+        let mut fields = Vec::new();
+        let mut methods = Vec::new();
+        let mut attributes = Vec::new();
+        while let Some(line) = self.next() {
+            let number = line.number;
+            let mut tokens = Tokens { tokens: &line.tokens, pos: 0 };
+            let directive = tokens.word().at(number)?;
+            match directive {
+                ".version" => {
+                    version = (tokens.number().at(number)?, tokens.number().at(number)?);
+                    tokens.end().at(number)?;
+                },
+                ".class" => {
+                    if class_line.is_some() {
+                        return Err(Error::Syntax(number, "more than one .class".to_string()));
+                    }
+                    let access_flags = tokens.flags(CLASS_FLAGS, 1).at(number)?;
+                    let this_class = tokens.class().at(number)?;
+                    class_line = Some((number, access_flags, this_class));
+                },
+                ".super" => super_class = Some((number, tokens.class().at(number)?)),
+                ".implements" => interfaces.push((number, tokens.class().at(number)?)),
+                ".const" => {
+                    let index = tokens.index().at(number)?;
+                    tokens.expect("=").at(number)?;
+                    constants.push((number, index, tokens.constant().at(number)?));
+                },
+                ".field" => {
+                    fields.push(self.member(tokens, number, "field", FIELD_FLAGS)?);
+                    continue;
+                },
+                ".method" => {
+                    methods.push(self.member(tokens, number, "method", METHOD_FLAGS)?);
+                    continue;
+                },
+                _ => {
+                    attributes.push(self.attribute(directive, tokens, number)?);
+                    continue;
+                },
+            }
+            tokens.end().at(number)?;
+        }
+        let (line, access_flags, this_class) = class_line
+            .ok_or_else(|| Error::Syntax(1, "missing .class".to_string()))?;
+        Ok(Class {
+            major_version: version.0,
+            minor_version: version.1,
+            access_flags,
+            this_class: (line, this_class),
+            super_class,
+            interfaces,
+            constants,
+            fields,
+            methods,
+            attributes,
+        })
+    }
+
+    fn member(&mut self, mut tokens: Tokens<'a>, line: usize, kind: &str, flags: &[(u2, &str)]) -> Result<Member> {
+        let access_flags = tokens.flags(flags, 2).at(line)?;
+        let name = tokens.utf8().at(line)?;
+        let descriptor = tokens.utf8().at(line)?;
+        tokens.end().at(line)?;
+        let mut attributes = Vec::new();
+        while let Some((number, mut tokens)) = self.block_line(kind, line)? {
+            let directive = tokens.word().at(number)?;
+            attributes.push(self.attribute(directive, tokens, number)?);
+        }
+        Ok(Member {
+            line,
+            access_flags,
+            name,
+            descriptor,
+            attributes,
+        })
+    }
+
+    // The directive is already taken from `tokens`
+    fn attribute(&mut self, directive: &str, mut tokens: Tokens<'a>, line: usize) -> Result<Attribute> {
+        let kind = match directive {
+            ".sourcefile" => AttributeKind::SourceFile(tokens.utf8().at(line)?),
+            ".constantvalue" => AttributeKind::ConstantValue(tokens.reference().at(line)?),
+            ".attribute" => AttributeKind::Raw(tokens.utf8().at(line)?, tokens.hex().at(line)?),
+            ".code" => {
+                tokens.expect("stack").at(line)?;
+                let max_stack = tokens.number().at(line)?;
+                tokens.expect("locals").at(line)?;
+                let max_locals = tokens.number().at(line)?;
+                tokens.end().at(line)?;
+                AttributeKind::Code(self.code(max_stack, max_locals, line)?)
+            },
+            ".linenumbertable" => {
+                tokens.end().at(line)?;
+                let mut line_numbers = Vec::new();
+                while let Some((number, mut tokens)) = self.block_line("linenumbertable", line)? {
+                    line_numbers.push((tokens.target().at(number)?, tokens.number().at(number)?));
+                    tokens.end().at(number)?;
+                }
+                AttributeKind::LineNumberTable(line_numbers)
+            },
+            ".bootstrapmethods" => {
+                tokens.end().at(line)?;
+                let mut bootstrap_methods = Vec::new();
+                while let Some((number, mut tokens)) = self.block_line("bootstrapmethods", line)? {
+                    let method_handle = tokens.reference().at(number)?;
+                    let mut arguments = Vec::new();
+                    while tokens.peek().is_some() {
+                        arguments.push(tokens.reference().at(number)?);
+                    }
+                    bootstrap_methods.push((method_handle, arguments));
+                }
+                AttributeKind::BootstrapMethods(bootstrap_methods)
+            },
+            _ => return Err(Error::Syntax(line, format!("unknown directive {}", directive))),
+        };
+        // Blocks end at their .end line, everything else has to end here
+        if let AttributeKind::SourceFile(_) | AttributeKind::ConstantValue(_) | AttributeKind::Raw(..) = kind {
+            tokens.end().at(line)?;
+        }
+        Ok(Attribute { line, kind })
+    }
+
+    fn code(&mut self, max_stack: u2, max_locals: u2, line: usize) -> Result<Code> {
+        let mut items = Vec::new();
+        let mut catches = Vec::new();
+        let mut attributes = Vec::new();
+        while let Some((number, mut tokens)) = self.block_line("code", line)? {
+            let first = tokens.word().at(number)?;
+            if first.len() > 1 && first.ends_with(':') {
+                let label = &first[..first.len() - 1];
+                if label.parse::<u4>().is_ok() {
+                    return Err(Error::Syntax(number, format!("label {} is a number", label)));
+                }
+                items.push((number, Item::Label(label.to_string())));
+            } else if first == ".catch" {
+                let catch_type = if tokens.peek_word() == Some("any") {
+                    tokens.next().at(number)?;
+                    None
+                } else {
+                    Some(tokens.class().at(number)?)
+                };
+                tokens.expect("from").at(number)?;
+                let start = tokens.target().at(number)?;
+                tokens.expect("to").at(number)?;
+                let end = tokens.target().at(number)?;
+                tokens.expect("using").at(number)?;
+                let handler = tokens.target().at(number)?;
+                catches.push(Catch { line: number, catch_type, start, end, handler });
+            } else if first.starts_with('.') {
+                attributes.push(self.attribute(first, tokens, number)?);
+                continue;
+            } else {
+                items.push((number, tokens.instruction(first).at(number)?));
+            }
+            tokens.end().at(number)?;
+        }
+        Ok(Code {
+            max_stack,
+            max_locals,
+            items,
+            catches,
+            attributes,
+        })
+    }
+}
+
+struct Tokens<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_word(&self) -> Option<&'a str> {
+        match self.peek() {
+            Some(Token::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn next(&mut self) -> Parsed<&'a Token> {
+        let token = self.peek().ok_or("unexpected end of line")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn end(&self) -> Parsed<()> {
+        match self.peek() {
+            Some(token) => Err(format!("unexpected {}", token.text())),
+            None => Ok(()),
+        }
+    }
+
+    fn word(&mut self) -> Parsed<&'a str> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            Token::Quoted(text) => Err(format!("unexpected string {}", lexer::quote(text))),
+        }
+    }
+
+    // A word or a string
+    fn text(&mut self) -> Parsed<&'a str> {
+        self.next().map(Token::text)
+    }
+
+    fn expect(&mut self, expected: &str) -> Parsed<()> {
+        match self.word()? {
+            word if word == expected => Ok(()),
+            word => Err(format!("expected {}, found {}", expected, word)),
+        }
+    }
+
+    fn number<T: FromStr>(&mut self) -> Parsed<T> {
+        let word = self.word()?;
+        word.parse().map_err(|_| format!("invalid number {}", word))
+    }
+
+    fn index(&mut self) -> Parsed<u2> {
+        let word = self.word()?;
+        if !word.starts_with('#') {
+            return Err(format!("expected a constant pool index, found {}", word));
+        }
+        word[1..].parse().map_err(|_| format!("invalid constant pool index {}", word))
+    }
+
+    fn is_index(&self) -> bool {
+        self.peek_word().is_some_and(|word| word.starts_with('#'))
+    }
+
+    // Raw bytes written as 0x followed by two digits per byte
+    fn hex(&mut self) -> Parsed<Vec<u1>> {
+        let word = self.word()?;
+        let digits = word.strip_prefix("0x")
+            .filter(|digits| digits.len() % 2 == 0)
+            .ok_or_else(|| format!("invalid hex bytes {}", word))?;
+        (0..digits.len())
+            .step_by(2)
+            .map(|i| u1::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("invalid hex bytes {}", word)))
+            .collect()
+    }
+
+    // Every token but the last `names` ones is a flag, known bits by name and the rest as 0x
+    fn flags(&mut self, table: &[(u2, &str)], names: usize) -> Parsed<u2> {
+        let mut access_flags = 0;
+        while self.tokens.len().saturating_sub(self.pos) > names {
+            let word = self.word()?;
+            access_flags |= match word.strip_prefix("0x") {
+                Some(digits) => u2::from_str_radix(digits, 16).map_err(|_| format!("invalid flags {}", word))?,
+                None => table.iter()
+                    .find(|&&(_, name)| name["ACC_".len()..].eq_ignore_ascii_case(word))
+                    .map(|&(flag, _)| flag)
+                    .ok_or_else(|| format!("unknown flag {}", word))?,
+            };
+        }
+        Ok(access_flags)
+    }
+
+    // #n or the text of a Utf8 constant
+    fn utf8(&mut self) -> Parsed<Ref> {
+        if self.is_index() {
+            return self.index().map(Ref::Index);
+        }
+        self.text().map(Ref::utf8)
+    }
+
+    // #n or a class name
+    fn class(&mut self) -> Parsed<Ref> {
+        if self.is_index() {
+            return self.index().map(Ref::Index);
+        }
+        self.text().map(Ref::class)
+    }
+
+    // #n or a name and a descriptor
+    fn name_and_type(&mut self) -> Parsed<Ref> {
+        if self.is_index() {
+            return self.index().map(Ref::Index);
+        }
+        let name = self.utf8()?;
+        let descriptor = self.utf8()?;
+        Ok(Ref::Symbol(Box::new(Constant::NameAndType(name, descriptor))))
+    }
+
+    // #n or any constant
+    fn reference(&mut self) -> Parsed<Ref> {
+        if self.is_index() {
+            return self.index().map(Ref::Index);
+        }
+        self.constant().map(|constant| Ref::Symbol(Box::new(constant)))
+    }
+
+    fn constant(&mut self) -> Parsed<Constant<Ref>> {
+        let tag = self.word()?;
+        Ok(match tag {
+            "Utf8" if self.peek_word().is_some_and(|word| word.starts_with("0x")) => Constant::Utf8Bytes(self.hex()?),
+            "Utf8" => Constant::Utf8(self.text()?.to_string()),
+            "Integer" => Constant::Integer(self.number()?),
+            "Float" => {
+                let word = self.word()?;
+                match word.strip_prefix("0x") {
+                    Some(bits) => Constant::Float(u4::from_str_radix(bits, 16)
+                        .map_err(|_| format!("invalid float {}", word))?),
+                    None => Constant::Float(word.parse::<f32>()
+                        .map_err(|_| format!("invalid float {}", word))?
+                        .to_bits()),
+                }
+            },
+            "Long" => Constant::Long(self.number()?),
+            "Double" => {
+                let word = self.word()?;
+                match word.strip_prefix("0x") {
+                    Some(bits) => Constant::Double(u64::from_str_radix(bits, 16)
+                        .map_err(|_| format!("invalid double {}", word))?),
+                    None => Constant::Double(word.parse::<f64>()
+                        .map_err(|_| format!("invalid double {}", word))?
+                        .to_bits()),
+                }
+            },
+            "Class" => Constant::Class(self.utf8()?),
+            "String" => Constant::String(self.utf8()?),
+            "Field" | "Fieldref" => Constant::Fieldref(self.class()?, self.name_and_type()?),
+            "Method" | "Methodref" => Constant::Methodref(self.class()?, self.name_and_type()?),
+            "InterfaceMethod" | "InterfaceMethodref" =>
+                Constant::InterfaceMethodref(self.class()?, self.name_and_type()?),
+            "NameAndType" => Constant::NameAndType(self.utf8()?, self.utf8()?),
+            "MethodHandle" => {
+                let kind = self.word()?;
+                let kind = RefKind::from_name(kind)
+                    .ok_or_else(|| format!("unknown reference kind {}", kind))?;
+                Constant::MethodHandle(kind, self.reference()?)
+            },
+            "MethodType" => Constant::MethodType(self.utf8()?),
+            "Dynamic" => Constant::Dynamic(self.number()?, self.name_and_type()?),
+            "InvokeDynamic" => Constant::InvokeDynamic(self.number()?, self.name_and_type()?),
+            "Module" => Constant::Module(self.utf8()?),
+            "Package" => Constant::Package(self.utf8()?),
+            _ => return Err(format!("unknown constant {}", tag)),
+        })
+    }
+
+    // A label or an absolute pc
+    fn target(&mut self) -> Parsed<Target> {
+        let word = self.word()?;
+        Ok(match word.parse() {
+            Ok(pc) => Target::Pc(pc),
+            Err(_) => Target::Label(word.to_string()),
+        })
+    }
+
+    fn instruction(&mut self, mnemonic: &str) -> Parsed<Item> {
+        let wide = mnemonic == "wide";
+        let mnemonic = if wide { self.word()? } else { mnemonic };
+        let opcode = bytecode::opcode(mnemonic)
+            .ok_or_else(|| format!("unknown instruction {}", mnemonic))?;
+        let operand = match bytecode::operand_kind(opcode) {
+            OperandKind::None => AsmOperand::None,
+            OperandKind::Byte | OperandKind::Short => AsmOperand::Int(self.number()?),
+            OperandKind::Local => AsmOperand::Local(self.number()?),
+            OperandKind::Iinc => AsmOperand::Iinc {
+                index: self.number()?,
+                delta: self.number()?,
+            },
+            OperandKind::Constant1 | OperandKind::Constant | OperandKind::ConstantDynamic =>
+                AsmOperand::Constant(self.reference()?),
+            OperandKind::Class => AsmOperand::Constant(self.class()?),
+            OperandKind::Branch | OperandKind::BranchWide => AsmOperand::Branch(self.target()?),
+            OperandKind::InvokeInterface => {
+                let reference = self.reference()?;
+                let count = if self.peek().is_some() { Some(self.number()?) } else { None };
+                AsmOperand::InvokeInterface(reference, count)
+            },
+            OperandKind::MultiANewArray => AsmOperand::MultiANewArray(self.class()?, self.number()?),
+            OperandKind::NewArray => {
+                let name = self.word()?;
+                AsmOperand::NewArray(bytecode::array_type(name)
+                    .or_else(|| name.parse().ok())
+                    .ok_or_else(|| format!("unknown array type {}", name))?)
+            },
+            OperandKind::TableSwitch => {
+                let low = self.number()?;
+                let mut targets = Vec::new();
+                while self.peek_word() != Some("default") {
+                    targets.push(self.target()?);
+                }
+                self.expect("default")?;
+                AsmOperand::TableSwitch { low, targets, default: self.target()? }
+            },
+            OperandKind::LookupSwitch => {
+                let mut pairs = Vec::new();
+                while self.peek_word() != Some("default") {
+                    pairs.push((self.number()?, self.target()?));
+                }
+                self.expect("default")?;
+                AsmOperand::LookupSwitch { pairs, default: self.target()? }
+            },
+        };
+        Ok(Item::Instruction { wide, opcode, operand })
+    }
+}
+
+/* Class file writer */
+
+struct Assembler {
+    pool: Pool,
+    // Errors that belong to no line in particular, like an undefined pool slot
+    last_line: usize,
+}
+
+type Labels = HashMap<String, u4>;
+
+impl Assembler {
+    fn class(&mut self, class: &Class) -> Result<Vec<u1>> {
+        self.constants(&class.constants)?;
+        let mut body = Vec::new();
+        push_u2(&mut body, class.access_flags);
+        let (line, ref this_class) = class.this_class;
+        push_u2(&mut body, self.pool.resolve(this_class).at(line)?);
+        match class.super_class {
+            Some((line, ref super_class)) => push_u2(&mut body, self.pool.resolve(super_class).at(line)?),
+            None => push_u2(&mut body, 0),
+        }
+        push_u2(&mut body, class.interfaces.len() as u2);
+        for &(line, ref interface) in class.interfaces.iter() {
+            push_u2(&mut body, self.pool.resolve(interface).at(line)?);
+        }
+        self.members(&class.fields, &mut body)?;
+        self.members(&class.methods, &mut body)?;
+        self.attributes(&class.attributes, &Labels::new(), &mut body)?;
+
+        let mut bytes = Vec::with_capacity(body.len() + 1024);
+        push_u4(&mut bytes, MAGIC);
+        push_u2(&mut bytes, class.minor_version);
+        push_u2(&mut bytes, class.major_version);
+        self.pool.write(&mut bytes).at(self.last_line)?;
+        bytes.extend_from_slice(&body);
+        Ok(bytes)
+    }
+
+    fn constants(&mut self, constants: &[(usize, u2, Constant<Ref>)]) -> Result<()> {
+        for &(line, index, ref constant) in constants {
+            self.pool.reserve(index, constant.is_wide()).at(line)?;
+        }
+        // Entries that only use indexes go first, so that symbolic references in the others
+        // find them instead of adding duplicates
+        let (plain, symbolic): (Vec<_>, Vec<_>) = constants.iter()
+            .partition(|&(_, _, constant)| constant.map(|reference| match *reference {
+                Ref::Index(_) => Ok(()),
+                Ref::Symbol(_) => Err(()),
+            }).is_ok());
+        for &&(line, index, ref constant) in plain.iter().chain(symbolic.iter()) {
+            let pool = &mut self.pool;
+            let entry = constant.map(|reference| pool.resolve(reference)).at(line)?;
+            pool.define(index, entry).at(line)?;
+        }
+        Ok(())
+    }
+
+    fn members(&mut self, members: &[Member], out: &mut Vec<u1>) -> Result<()> {
+        push_u2(out, members.len() as u2);
+        for member in members {
+            push_u2(out, member.access_flags);
+            push_u2(out, self.pool.resolve(&member.name).at(member.line)?);
+            push_u2(out, self.pool.resolve(&member.descriptor).at(member.line)?);
+            self.attributes(&member.attributes, &Labels::new(), out)?;
+        }
+        Ok(())
+    }
+
+    // `labels` are the ones of the enclosing code, for LineNumberTable
+    fn attributes(&mut self, attributes: &[Attribute], labels: &Labels, out: &mut Vec<u1>) -> Result<()> {
+        push_u2(out, attributes.len() as u2);
+        for attribute in attributes {
+            let line = attribute.line;
+            let mut info = Vec::new();
+            let name = match attribute.kind {
+                AttributeKind::Raw(ref name, ref bytes) => {
+                    info.extend_from_slice(bytes);
+                    name.clone()
+                },
+                AttributeKind::ConstantValue(ref value) => {
+                    push_u2(&mut info, self.pool.resolve(value).at(line)?);
+                    Ref::utf8("ConstantValue")
+                },
+                AttributeKind::SourceFile(ref source_file) => {
+                    push_u2(&mut info, self.pool.resolve(source_file).at(line)?);
+                    Ref::utf8("SourceFile")
+                },
+                AttributeKind::Code(ref code) => {
+                    self.code(code, &mut info)?;
+                    Ref::utf8("Code")
+                },
+                AttributeKind::LineNumberTable(ref line_numbers) => {
+                    push_u2(&mut info, line_numbers.len() as u2);
+                    for &(ref target, line_number) in line_numbers {
+                        push_u2(&mut info, pc_u2(target, labels).at(line)?);
+                        push_u2(&mut info, line_number);
+                    }
+                    Ref::utf8("LineNumberTable")
+                },
+                AttributeKind::BootstrapMethods(ref bootstrap_methods) => {
+                    push_u2(&mut info, bootstrap_methods.len() as u2);
+                    for (method_handle, arguments) in bootstrap_methods {
+                        push_u2(&mut info, self.pool.resolve(method_handle).at(line)?);
+                        push_u2(&mut info, arguments.len() as u2);
+                        for argument in arguments {
+                            push_u2(&mut info, self.pool.resolve(argument).at(line)?);
+                        }
+                    }
+                    Ref::utf8("BootstrapMethods")
+                },
+            };
+            push_u2(out, self.pool.resolve(&name).at(line)?);
+            push_u4(out, info.len() as u4);
+            out.extend_from_slice(&info);
+        }
+        Ok(())
+    }
+
+    // Two passes: the first places the labels, the second encodes with the real branch targets
+    fn code(&mut self, code: &Code, out: &mut Vec<u1>) -> Result<()> {
+        let mut labels = Labels::new();
+        let mut instructions = Vec::new();
+        let mut pc: u4 = 0;
+        let mut scratch = Vec::new();
+        for &(line, ref item) in code.items.iter() {
+            match *item {
+                Item::Label(ref label) => {
+                    if labels.insert(label.clone(), pc).is_some() {
+                        return Err(Error::Syntax(line, format!("label {} is defined twice", label)));
+                    }
+                },
+                Item::Instruction { wide, opcode, ref operand } => {
+                    let (operand, targets) = self.operand(operand, pc).at(line)?;
+                    let instruction = Instruction { pc, opcode, wide, operand };
+                    scratch.clear();
+                    encode(&instruction, &mut scratch).at(line)?;
+                    pc += scratch.len() as u4;
+                    instructions.push((line, instruction, targets));
+                },
+            }
+        }
+        let mut bytes = Vec::with_capacity(pc as usize);
+        for (line, mut instruction, targets) in instructions {
+            let targets = targets.iter()
+                .map(|target| resolve_target(target, &labels))
+                .collect::<Parsed<Vec<_>>>()
+                .at(line)?;
+            match instruction.operand {
+                Operand::Branch(ref mut target) => *target = targets[0],
+                Operand::TableSwitch { ref mut default, targets: ref mut table, .. } => {
+                    *default = targets[0];
+                    table.copy_from_slice(&targets[1..]);
+                },
+                Operand::LookupSwitch { ref mut default, ref mut pairs } => {
+                    *default = targets[0];
+                    for (pair, &target) in pairs.iter_mut().zip(targets[1..].iter()) {
+                        pair.1 = target;
+                    }
+                },
+                _ => (),
+            }
+            encode(&instruction, &mut bytes).at(line)?;
+        }
+
+        push_u2(out, code.max_stack);
+        push_u2(out, code.max_locals);
+        push_u4(out, bytes.len() as u4);
+        out.extend_from_slice(&bytes);
+        push_u2(out, code.catches.len() as u2);
+        for catch in code.catches.iter() {
+            for target in &[&catch.start, &catch.end, &catch.handler] {
+                push_u2(out, pc_u2(target, &labels).at(catch.line)?);
+            }
+            let catch_type = match catch.catch_type {
+                Some(ref catch_type) => self.pool.resolve(catch_type).at(catch.line)?,
+                None => 0,
+            };
+            push_u2(out, catch_type);
+        }
+        self.attributes(&code.attributes, &labels, out)
+    }
+
+    // Branch targets are left at `pc` and returned in order, default first for switches
+    fn operand<'c>(&mut self, operand: &'c AsmOperand, pc: u4) -> Parsed<(Operand, Vec<&'c Target>)> {
+        let mut targets = Vec::new();
+        let operand = match *operand {
+            AsmOperand::None => Operand::None,
+            AsmOperand::Int(value) => Operand::Int(value),
+            AsmOperand::Local(index) => Operand::Local(index),
+            AsmOperand::Iinc { index, delta } => Operand::Iinc { index, delta },
+            AsmOperand::Constant(ref reference) => Operand::Constant(self.pool.resolve(reference)?),
+            AsmOperand::InvokeInterface(ref reference, count) => {
+                let index = self.pool.resolve(reference)?;
+                let count = match count {
+                    Some(count) => count,
+                    None => self.invoke_interface_count(index)?,
+                };
+                Operand::InvokeInterface { index, count }
+            },
+            AsmOperand::MultiANewArray(ref class, dimensions) => Operand::MultiANewArray {
+                index: self.pool.resolve(class)?,
+                dimensions,
+            },
+            AsmOperand::NewArray(atype) => Operand::NewArray(atype),
+            AsmOperand::Branch(ref target) => {
+                targets.push(target);
+                Operand::Branch(pc)
+            },
+            AsmOperand::TableSwitch { low, targets: ref table, ref default } => {
+                if table.is_empty() {
+                    return Err("tableswitch needs at least one target".to_string());
+                }
+                let high = i64::from(low) + table.len() as i64 - 1;
+                if high > i64::from(i32::MAX) {
+                    return Err("tableswitch has too many targets".to_string());
+                }
+                targets.push(default);
+                targets.extend(table.iter());
+                Operand::TableSwitch {
+                    default: pc,
+                    low,
+                    high: high as i32,
+                    targets: vec![pc; table.len()].into_boxed_slice(),
+                }
+            },
+            AsmOperand::LookupSwitch { ref pairs, ref default } => {
+                targets.push(default);
+                targets.extend(pairs.iter().map(|(_, target)| target));
+                Operand::LookupSwitch {
+                    default: pc,
+                    pairs: pairs.iter().map(|&(key, _)| (key, pc)).collect::<Vec<_>>().into_boxed_slice(),
+                }
+            },
+        };
+        Ok((operand, targets))
+    }
+
+    // The count operand is redundant, it's the argument slots of the method plus one
+    fn invoke_interface_count(&self, index: u2) -> Parsed<u1> {
+        let descriptor = match self.pool.get(index) {
+            Some(&Constant::InterfaceMethodref(_, name_and_type)) => match self.pool.get(name_and_type) {
+                Some(&Constant::NameAndType(_, descriptor)) => self.pool.get(descriptor),
+                _ => None,
+            },
+            _ => None,
+        };
+        match descriptor {
+            Some(Constant::Utf8(descriptor)) => MethodDescriptor::parse(descriptor)
+                .map(|descriptor| descriptor.arg_slots() as u1 + 1)
+                .map_err(|_| format!("invalid method descriptor {}", descriptor)),
+            _ => Err("the count of invokeinterface is needed when its method is not known yet".to_string()),
+        }
+    }
+}
+
+fn resolve_target(target: &Target, labels: &Labels) -> Parsed<u4> {
+    match *target {
+        Target::Label(ref label) => labels.get(label)
+            .cloned()
+            .ok_or_else(|| format!("undefined label {}", label)),
+        Target::Pc(pc) => Ok(pc),
+    }
+}
+
+fn pc_u2(target: &Target, labels: &Labels) -> Parsed<u2> {
+    let pc = resolve_target(target, labels)?;
+    if pc > u4::from(u2::MAX) {
+        return Err(format!("pc {} does not fit in 16 bits", pc));
+    }
+    Ok(pc as u2)
+}
+
+fn encode(instruction: &Instruction, out: &mut Vec<u1>) -> Parsed<()> {
+    bytecode::encode(instruction, out).map_err(|err| match err {
+        Error::OutOfBounds(_) => format!("operand of {} is out of range", instruction.mnemonic()),
+        _ => format!("invalid operand for {}", instruction.mnemonic()),
+    })
+}
@@ -0,0 +1,355 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+use class_file::access_flags::{CLASS_FLAGS, FIELD_FLAGS, METHOD_FLAGS};
+use class_file::attribute_info::{AttributeInfo, CodeAttribute};
+use class_file::bytecode::{self, Instruction, Operand, OperandKind};
+use class_file::class_file::{ClassFile, MemberInfo};
+use jasm::lexer::{quote, word};
+use jasm::pool::{Constant, Entry, Pool, Ref};
+use result::Result;
+use types::{u1, u2, u4};
+
+pub fn disassemble<W: Write>(out: &mut W, class_file: &ClassFile) -> Result<()> {
+    let pool = Pool::from_constant_pool(class_file.constant_pool());
+    Disassembler { out, pool }.class(class_file)
+}
+
+// pc to label, only for the pcs something refers to
+type Labels = BTreeMap<u4, String>;
+
+struct Disassembler<'a, W: Write + 'a> {
+    out: &'a mut W,
+    pool: Pool,
+}
+
+impl<'a, W: Write> Disassembler<'a, W> {
+    fn class(&mut self, class_file: &ClassFile) -> Result<()> {
+        writeln!(self.out, ".version {} {}", class_file.major_version(), class_file.minor_version())?;
+        writeln!(self.out, ".class {}{}",
+            flags(class_file.access_flags().bits(), CLASS_FLAGS),
+            self.class_name(class_file.this_class()))?;
+        if class_file.super_class() != 0 {
+            writeln!(self.out, ".super {}", self.class_name(class_file.super_class()))?;
+        }
+        for &interface in class_file.interfaces() {
+            writeln!(self.out, ".implements {}", self.class_name(interface))?;
+        }
+        writeln!(self.out)?;
+        for (index, entry) in self.pool.entries() {
+            writeln!(self.out, ".const #{} = {}", index, entry_text(entry))?;
+        }
+        for field in class_file.fields() {
+            writeln!(self.out)?;
+            self.member("field", FIELD_FLAGS, field)?;
+        }
+        for method in class_file.methods() {
+            writeln!(self.out)?;
+            self.member("method", METHOD_FLAGS, method)?;
+        }
+        if !class_file.attributes().is_empty() {
+            writeln!(self.out)?;
+        }
+        self.attributes(class_file.attributes(), "", &Labels::new())
+    }
+
+    fn member(&mut self, kind: &str, table: &[(u2, &str)], member: &MemberInfo) -> Result<()> {
+        writeln!(self.out, ".{} {}{} {}", kind,
+            flags(member.access_flags().bits(), table),
+            self.utf8(member.name_index()),
+            self.utf8(member.descriptor_index()))?;
+        self.attributes(member.attributes(), "    ", &Labels::new())?;
+        writeln!(self.out, ".end {}", kind)?;
+        Ok(())
+    }
+
+    fn attributes(&mut self, attributes: &[AttributeInfo], indent: &str, labels: &Labels) -> Result<()> {
+        for attribute in attributes {
+            match *attribute {
+                AttributeInfo::Raw(ref raw) => {
+                    writeln!(self.out, "{}.attribute {} {}", indent, word(attribute.name()), hex(raw.info()))?;
+                },
+                AttributeInfo::Constant(ref constant_value) => writeln!(self.out, "{}.constantvalue {}",
+                    indent, self.reference(constant_value.constant_value_index()))?,
+                AttributeInfo::Source(ref source_file) => writeln!(self.out, "{}.sourcefile {}",
+                    indent, self.utf8(source_file.sourcefile_index()))?,
+                AttributeInfo::Code(ref code) => self.code(code, indent)?,
+                AttributeInfo::LineNumberTable(ref table) => {
+                    writeln!(self.out, "{}.linenumbertable", indent)?;
+                    for entry in table.line_number_table() {
+                        writeln!(self.out, "{}    {} {}",
+                            indent, target(u4::from(entry.start_pc()), labels), entry.line_number())?;
+                    }
+                    writeln!(self.out, "{}.end linenumbertable", indent)?;
+                },
+                AttributeInfo::BootstrapMethods(ref bootstrap_methods) => {
+                    writeln!(self.out, "{}.bootstrapmethods", indent)?;
+                    for bootstrap_method in bootstrap_methods.bootstrap_methods() {
+                        let mut line = self.reference(bootstrap_method.bootstrap_method_ref());
+                        for &argument in bootstrap_method.bootstrap_arguments() {
+                            line.push(' ');
+                            line.push_str(&self.reference(argument));
+                        }
+                        writeln!(self.out, "{}    {}", indent, line)?;
+                    }
+                    writeln!(self.out, "{}.end bootstrapmethods", indent)?;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn code(&mut self, code: &CodeAttribute, indent: &str) -> Result<()> {
+        let instructions = bytecode::decode(code.code())?;
+        let labels = code_labels(code, &instructions);
+        let inner = format!("{}    ", indent);
+        writeln!(self.out, "{}.code stack {} locals {}", indent, code.max_stack(), code.max_locals())?;
+        for instruction in instructions.iter() {
+            if let Some(label) = labels.get(&instruction.pc) {
+                writeln!(self.out, "{}{}:", indent, label)?;
+            }
+            writeln!(self.out, "{}{}", inner, self.instruction(instruction, &labels))?;
+        }
+        if let Some(label) = labels.get(&(code.code().len() as u4)) {
+            writeln!(self.out, "{}{}:", indent, label)?;
+        }
+        for exception in code.exception_table() {
+            let catch_type = match exception.catch_type() {
+                0 => "any".to_string(),
+                index => self.class_name(index),
+            };
+            writeln!(self.out, "{}.catch {} from {} to {} using {}", inner, catch_type,
+                target(u4::from(exception.start_pc()), &labels),
+                target(u4::from(exception.end_pc()), &labels),
+                target(u4::from(exception.handler_pc()), &labels))?;
+        }
+        self.attributes(code.attributes(), &inner, &labels)?;
+        writeln!(self.out, "{}.end code", indent)?;
+        Ok(())
+    }
+
+    fn instruction(&self, instruction: &Instruction, labels: &Labels) -> String {
+        let mut text = String::new();
+        if instruction.wide {
+            text.push_str("wide ");
+        }
+        text.push_str(instruction.mnemonic());
+        let operand = match instruction.operand {
+            Operand::None => return text,
+            Operand::Int(value) => value.to_string(),
+            Operand::Local(index) => index.to_string(),
+            Operand::Constant(index) if bytecode::operand_kind(instruction.opcode) == OperandKind::Class =>
+                self.class_name(index),
+            Operand::Constant(index) => self.reference(index),
+            Operand::Branch(pc) => target(pc, labels),
+            Operand::Iinc { index, delta } => format!("{} {}", index, delta),
+            Operand::InvokeInterface { index, count } => format!("{} {}", self.reference(index), count),
+            Operand::MultiANewArray { index, dimensions } => format!("{} {}", self.class_name(index), dimensions),
+            Operand::NewArray(atype) => match bytecode::array_type_name(atype) {
+                Some(name) => name.to_string(),
+                None => atype.to_string(),
+            },
+            Operand::TableSwitch { default, low, ref targets, .. } => {
+                let mut operand = low.to_string();
+                for &pc in targets.iter() {
+                    operand.push(' ');
+                    operand.push_str(&target(pc, labels));
+                }
+                format!("{} default {}", operand, target(default, labels))
+            },
+            Operand::LookupSwitch { default, ref pairs } => {
+                let mut operand = String::new();
+                for &(key, pc) in pairs.iter() {
+                    operand.push_str(&format!("{} {} ", key, target(pc, labels)));
+                }
+                format!("{}default {}", operand, target(default, labels))
+            },
+        };
+        format!("{} {}", text, operand)
+    }
+
+    // Any constant, symbolic when that reads back as the same index
+    fn reference(&self, index: u2) -> String {
+        match self.pool.symbol(index) {
+            Ref::Symbol(ref constant) => constant_text(constant),
+            Ref::Index(_) => None,
+        }.unwrap_or_else(|| format!("#{}", index))
+    }
+
+    fn class_name(&self, index: u2) -> String {
+        class_text(&self.pool.symbol(index)).unwrap_or_else(|| format!("#{}", index))
+    }
+
+    fn utf8(&self, index: u2) -> String {
+        utf8_text(&self.pool.symbol(index)).unwrap_or_else(|| format!("#{}", index))
+    }
+}
+
+// Every pc something refers to that is also an instruction boundary. The others are written as
+// numbers, which keeps broken code round tripping.
+fn code_labels(code: &CodeAttribute, instructions: &[Instruction]) -> Labels {
+    let mut boundaries: BTreeSet<u4> = instructions.iter().map(|instruction| instruction.pc).collect();
+    boundaries.insert(code.code().len() as u4);
+    let mut targets = Vec::new();
+    for instruction in instructions {
+        match instruction.operand {
+            Operand::Branch(pc) => targets.push(pc),
+            Operand::TableSwitch { default, targets: ref table, .. } => {
+                targets.push(default);
+                targets.extend(table.iter());
+            },
+            Operand::LookupSwitch { default, ref pairs } => {
+                targets.push(default);
+                targets.extend(pairs.iter().map(|&(_, pc)| pc));
+            },
+            _ => (),
+        }
+    }
+    for exception in code.exception_table() {
+        targets.extend([exception.start_pc(), exception.end_pc(), exception.handler_pc()]
+            .iter().map(|&pc| u4::from(pc)));
+    }
+    for attribute in code.attributes() {
+        if let AttributeInfo::LineNumberTable(ref table) = *attribute {
+            targets.extend(table.line_number_table().iter().map(|entry| u4::from(entry.start_pc())));
+        }
+    }
+    targets.into_iter()
+        .filter(|pc| boundaries.contains(pc))
+        .map(|pc| (pc, format!("L{}", pc)))
+        .collect()
+}
+
+fn target(pc: u4, labels: &Labels) -> String {
+    match labels.get(&pc) {
+        Some(label) => label.clone(),
+        None => pc.to_string(),
+    }
+}
+
+// Names of the known flags followed by a space, bits without a name as 0x
+fn flags(bits: u2, table: &[(u2, &str)]) -> String {
+    let mut text = String::new();
+    let mut rest = bits;
+    for &(flag, name) in table {
+        if bits & flag == flag {
+            text.push_str(&name["ACC_".len()..].to_lowercase());
+            text.push(' ');
+            rest &= !flag;
+        }
+    }
+    if rest != 0 {
+        text.push_str(&format!("0x{:04x} ", rest));
+    }
+    text
+}
+
+fn hex(bytes: &[u1]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{}", digits)
+}
+
+fn float_text(bits: u4) -> String {
+    let value = f32::from_bits(bits);
+    if value.is_nan() { format!("0x{:08x}", bits) } else { format!("{:?}", value) }
+}
+
+fn double_text(bits: u64) -> String {
+    let value = f64::from_bits(bits);
+    if value.is_nan() { format!("0x{:016x}", bits) } else { format!("{:?}", value) }
+}
+
+// `.const` lines, everything by index
+fn entry_text(entry: &Entry) -> String {
+    match *entry {
+        Constant::Utf8(ref text) => format!("Utf8 {}", word(text)),
+        Constant::Utf8Bytes(ref bytes) => format!("Utf8 {}", hex(bytes)),
+        Constant::Integer(value) => format!("Integer {}", value),
+        Constant::Float(bits) => format!("Float {}", float_text(bits)),
+        Constant::Long(value) => format!("Long {}", value),
+        Constant::Double(bits) => format!("Double {}", double_text(bits)),
+        Constant::Class(name) => format!("Class #{}", name),
+        Constant::String(string) => format!("String #{}", string),
+        Constant::Fieldref(class, name_and_type) => format!("Fieldref #{} #{}", class, name_and_type),
+        Constant::Methodref(class, name_and_type) => format!("Methodref #{} #{}", class, name_and_type),
+        Constant::InterfaceMethodref(class, name_and_type) =>
+            format!("InterfaceMethodref #{} #{}", class, name_and_type),
+        Constant::NameAndType(name, descriptor) => format!("NameAndType #{} #{}", name, descriptor),
+        Constant::MethodHandle(kind, reference) => format!("MethodHandle {} #{}", kind.name(), reference),
+        Constant::MethodType(descriptor) => format!("MethodType #{}", descriptor),
+        Constant::Dynamic(bootstrap, name_and_type) => format!("Dynamic {} #{}", bootstrap, name_and_type),
+        Constant::InvokeDynamic(bootstrap, name_and_type) =>
+            format!("InvokeDynamic {} #{}", bootstrap, name_and_type),
+        Constant::Module(name) => format!("Module #{}", name),
+        Constant::Package(name) => format!("Package #{}", name),
+    }
+}
+
+// None when some part has no syntax, like a Class whose name is an Integer in a broken pool
+fn constant_text(constant: &Constant<Ref>) -> Option<String> {
+    Some(match *constant {
+        Constant::Utf8(ref text) => format!("Utf8 {}", word(text)),
+        Constant::Utf8Bytes(ref bytes) => format!("Utf8 {}", hex(bytes)),
+        Constant::Integer(value) => format!("Integer {}", value),
+        Constant::Float(bits) => format!("Float {}", float_text(bits)),
+        Constant::Long(value) => format!("Long {}", value),
+        Constant::Double(bits) => format!("Double {}", double_text(bits)),
+        Constant::Class(ref name) => format!("Class {}", utf8_text(name)?),
+        Constant::String(Ref::Symbol(ref string)) => match **string {
+            Constant::Utf8(ref text) => format!("String {}", quote(text)),
+            _ => return None,
+        },
+        Constant::String(ref string) => format!("String {}", utf8_text(string)?),
+        Constant::Fieldref(ref class, ref name_and_type) =>
+            format!("Field {} {}", class_text(class)?, name_and_type_text(name_and_type)?),
+        Constant::Methodref(ref class, ref name_and_type) =>
+            format!("Method {} {}", class_text(class)?, name_and_type_text(name_and_type)?),
+        Constant::InterfaceMethodref(ref class, ref name_and_type) =>
+            format!("InterfaceMethod {} {}", class_text(class)?, name_and_type_text(name_and_type)?),
+        Constant::NameAndType(ref name, ref descriptor) =>
+            format!("NameAndType {} {}", utf8_text(name)?, utf8_text(descriptor)?),
+        Constant::MethodHandle(kind, ref reference) => format!("MethodHandle {} {}", kind.name(), match *reference {
+            Ref::Index(index) => format!("#{}", index),
+            Ref::Symbol(ref constant) => constant_text(constant)?,
+        }),
+        Constant::MethodType(ref descriptor) => format!("MethodType {}", utf8_text(descriptor)?),
+        Constant::Dynamic(bootstrap, ref name_and_type) =>
+            format!("Dynamic {} {}", bootstrap, name_and_type_text(name_and_type)?),
+        Constant::InvokeDynamic(bootstrap, ref name_and_type) =>
+            format!("InvokeDynamic {} {}", bootstrap, name_and_type_text(name_and_type)?),
+        Constant::Module(ref name) => format!("Module {}", utf8_text(name)?),
+        Constant::Package(ref name) => format!("Package {}", utf8_text(name)?),
+    })
+}
+
+fn utf8_text(reference: &Ref) -> Option<String> {
+    match *reference {
+        Ref::Index(index) => Some(format!("#{}", index)),
+        Ref::Symbol(ref constant) => match **constant {
+            Constant::Utf8(ref text) => Some(word(text)),
+            _ => None,
+        },
+    }
+}
+
+fn class_text(reference: &Ref) -> Option<String> {
+    match *reference {
+        Ref::Index(index) => Some(format!("#{}", index)),
+        Ref::Symbol(ref constant) => match **constant {
+            Constant::Class(ref name @ Ref::Symbol(_)) => utf8_text(name),
+            _ => None,
+        },
+    }
+}
+
+// A name starting with # would read back as the index of a NameAndType
+fn name_and_type_text(reference: &Ref) -> Option<String> {
+    match *reference {
+        Ref::Index(index) => Some(format!("#{}", index)),
+        Ref::Symbol(ref constant) => match **constant {
+            Constant::NameAndType(ref name @ Ref::Symbol(_), ref descriptor) =>
+                Some(format!("{} {}", utf8_text(name)?, utf8_text(descriptor)?)),
+            _ => None,
+        },
+    }
+}
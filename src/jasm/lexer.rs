@@ -0,0 +1,136 @@
+use result::{Result, Error};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    // Anything up to the next whitespace, descriptors included
+    Word(String),
+    // "..." with \t \n \r \" \\ and \uXXXX escapes
+    Quoted(String),
+}
+
+impl Token {
+    pub fn text(&self) -> &str {
+        match *self {
+            Token::Word(ref text) | Token::Quoted(ref text) => text,
+        }
+    }
+}
+
+pub struct Line {
+    // 1 based, for error messages
+    pub number: usize,
+    pub tokens: Vec<Token>,
+}
+
+// Splits the source into lines of tokens, blank lines and `//` comments are dropped
+pub fn tokenize(source: &str) -> Result<Vec<Line>> {
+    let mut lines = Vec::new();
+    for (number, text) in source.lines().enumerate() {
+        let number = number + 1;
+        let tokens = tokenize_line(text).map_err(|message| Error::Syntax(number, message))?;
+        if !tokens.is_empty() {
+            lines.push(Line { number, tokens });
+        }
+    }
+    Ok(lines)
+}
+
+fn tokenize_line(text: &str) -> ::std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        match chars.peek() {
+            None => break,
+            Some('"') => {
+                chars.next();
+                tokens.push(Token::Quoted(quoted(&mut chars)?));
+            },
+            Some(_) => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if word.starts_with("//") {
+                    break;
+                }
+                tokens.push(Token::Word(word));
+            },
+        }
+    }
+    Ok(tokens)
+}
+
+fn quoted<I: Iterator<Item = char>>(chars: &mut I) -> ::std::result::Result<String, String> {
+    // Escapes are UTF-16 units so a surrogate pair can be written as two \u escapes
+    let mut units = Vec::new();
+    loop {
+        let c = chars.next().ok_or("unterminated string")?;
+        match c {
+            '"' => break,
+            '\\' => {
+                let unit = match chars.next().ok_or("unterminated string")? {
+                    't' => 0x09,
+                    'n' => 0x0A,
+                    'r' => 0x0D,
+                    '"' => 0x22,
+                    '\\' => 0x5C,
+                    'u' => {
+                        let hex: String = chars.by_ref().take(4).collect();
+                        u16::from_str_radix(&hex, 16)
+                            .ok()
+                            .filter(|_| hex.len() == 4)
+                            .ok_or_else(|| format!("invalid escape \\u{}", hex))?
+                    },
+                    other => return Err(format!("invalid escape \\{}", other)),
+                };
+                units.push(unit);
+            },
+            c => {
+                let mut buffer = [0; 2];
+                units.extend_from_slice(c.encode_utf16(&mut buffer));
+            },
+        }
+    }
+    String::from_utf16(&units).map_err(|_| "unpaired surrogate in string".to_string())
+}
+
+// Inverse of the string syntax above, only printable ASCII is written as is
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for unit in text.encode_utf16() {
+        match unit {
+            0x09 => quoted.push_str("\\t"),
+            0x0A => quoted.push_str("\\n"),
+            0x0D => quoted.push_str("\\r"),
+            0x22 => quoted.push_str("\\\""),
+            0x5C => quoted.push_str("\\\\"),
+            0x20..=0x7E => quoted.push(unit as u8 as char),
+            _ => quoted.push_str(&format!("\\u{:04x}", unit)),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// `text` as a bare word when it reads back the same, quoted otherwise
+pub fn word(text: &str) -> String {
+    let bare = !text.is_empty() &&
+        text.chars().all(|c| c.is_ascii_graphic() && c != '"' && c != '\\') &&
+        !text.starts_with('#') &&
+        !text.starts_with("//") &&
+        !text.starts_with("0x") &&
+        !text.ends_with(':') &&
+        !KEYWORDS.contains(&text);
+    if bare { text.to_string() } else { quote(text) }
+}
+
+// Words that mean something in the places a name can appear
+const KEYWORDS: &[&str] = &["any", "default", "from", "to", "using", "stack", "locals", "="];
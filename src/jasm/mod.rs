@@ -0,0 +1,85 @@
+/*
+* Text assembly language for whole class files. The disassembler writes every constant pool
+* entry with its index, so assembling its output gives back the same class file byte for byte.
+* Handwritten sources can leave the pool out, symbolic references are added as they are used.
+*
+*   .version 52 0
+*   .class public super Hello
+*   .super java/lang/Object
+*
+*   .method public static main ([Ljava/lang/String;)V
+*       .code stack 2 locals 1
+*           getstatic Field java/lang/System out Ljava/io/PrintStream;
+*           ldc String "Hello"
+*           invokevirtual Method java/io/PrintStream println (Ljava/lang/String;)V
+*           return
+*       .end code
+*   .end method
+*
+* One directive or instruction per line, `//` starts a comment. Flags come before the name of
+* a class, field or method. Wherever the class file has a constant pool index, `#n` can be used
+* instead of the symbolic form.
+*
+*   Constants   Utf8 text (0xhex for bytes that aren't text), Integer 1, Float 1.5, Long 1, Double 1.5 (0x bits for NaN),
+*               Class name, String text, Field class name descriptor, Method ...,
+*               InterfaceMethod ..., NameAndType name descriptor, MethodType descriptor,
+*               MethodHandle REF_invokeStatic Method ..., Dynamic bootstrap name descriptor,
+*               InvokeDynamic ..., Module name, Package name. Fieldref, Methodref and
+*               InterfaceMethodref are accepted too, that is how `.const` lines are written.
+*   Class       .version major minor, .class, .super, .implements, .const #n = constant,
+*               .field flags name descriptor ... .end field,
+*               .method flags name descriptor ... .end method
+*   Attributes  .sourcefile name, .constantvalue constant, .code stack n locals n ... .end code,
+*               .linenumbertable (label line per line) .end linenumbertable,
+*               .bootstrapmethods (handle arguments... per line) .end bootstrapmethods,
+*               .attribute name 0xhex for everything else
+*   Code        label:, mnemonic operands, wide iinc 300 1,
+*               tableswitch low target... default target, lookupswitch key target... default target,
+*               .catch class|any from label to label using label
+*
+* A label is any word, a number is taken as an absolute pc. max_stack and max_locals are not
+* computed and StackMapTable is kept as raw bytes, edited code needs both fixed by hand.
+* Utf8 constants with unpaired surrogates (some JDK charset tables have them) are written as the
+* 0x bytes of the class file, a text starting with 0x is quoted.
+*/
+use std::io::Write;
+
+use class_file::class_file::ClassFile;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use result::{Result, Error};
+use types::u1;
+
+mod assembler;
+mod disassembler;
+mod lexer;
+pub mod pool;
+
+pub use self::lexer::{quote, word};
+
+// Errors without a line yet, attached with `at`
+type Parsed<T> = ::std::result::Result<T, String>;
+
+trait AtLine<T> {
+    fn at(self, line: usize) -> Result<T>;
+}
+
+impl<T> AtLine<T> for Parsed<T> {
+    fn at(self, line: usize) -> Result<T> {
+        self.map_err(|message| Error::Syntax(line, message))
+    }
+}
+
+// Bytes of the .class file
+pub fn assemble(source: &str) -> Result<Vec<u1>> {
+    assembler::assemble(source)
+}
+
+pub fn assemble_class(source: &str) -> Result<ClassFile> {
+    let bytes = assemble(source)?;
+    ClassFile::from_bytes(&PoSlice::new(&bytes))
+}
+
+pub fn disassemble<W: Write>(out: &mut W, class_file: &ClassFile) -> Result<()> {
+    disassembler::disassemble(out, class_file)
+}
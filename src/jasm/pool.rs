@@ -0,0 +1,347 @@
+/*
+* Constant pool of the assembler. Entries are either placed at a fixed index with `.const` or
+* interned from a symbolic reference, interning reuses the first equal entry before adding one.
+*/
+use class_file::constant_pool::{encode_modified_utf8, ConstantPoolRep, CPInfoRep, RefKind, Tag};
+use jasm::Parsed;
+use types::{u1, u2, u4};
+
+// References are u2 indexes once resolved and `Ref`s while they can still be symbolic
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constant<R> {
+    Utf8(String),
+    // Modified UTF-8 that doesn't read back as a string, like an unpaired surrogate
+    Utf8Bytes(Vec<u1>),
+    Integer(i32),
+    // Raw bits so that NaNs compare equal and keep their payload
+    Float(u4),
+    Long(i64),
+    Double(u64),
+    Class(R),
+    String(R),
+    Fieldref(R, R),
+    Methodref(R, R),
+    InterfaceMethodref(R, R),
+    NameAndType(R, R),
+    MethodHandle(RefKind, R),
+    MethodType(R),
+    Dynamic(u2, R),
+    InvokeDynamic(u2, R),
+    Module(R),
+    Package(R),
+}
+
+pub type Entry = Constant<u2>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ref {
+    Index(u2),
+    Symbol(Box<Constant<Ref>>),
+}
+
+impl Ref {
+    pub fn utf8(text: &str) -> Self {
+        Ref::Symbol(Box::new(Constant::Utf8(text.to_string())))
+    }
+
+    pub fn class(name: &str) -> Self {
+        Ref::Symbol(Box::new(Constant::Class(Ref::utf8(name))))
+    }
+}
+
+impl<R> Constant<R> {
+    pub fn map<S, E, F>(&self, mut f: F) -> Result<Constant<S>, E>
+    where F: FnMut(&R) -> Result<S, E> {
+        Ok(match *self {
+            Constant::Utf8(ref text) => Constant::Utf8(text.clone()),
+            Constant::Utf8Bytes(ref bytes) => Constant::Utf8Bytes(bytes.clone()),
+            Constant::Integer(value) => Constant::Integer(value),
+            Constant::Float(bits) => Constant::Float(bits),
+            Constant::Long(value) => Constant::Long(value),
+            Constant::Double(bits) => Constant::Double(bits),
+            Constant::Class(ref name) => Constant::Class(f(name)?),
+            Constant::String(ref string) => Constant::String(f(string)?),
+            Constant::Fieldref(ref class, ref name_and_type) =>
+                Constant::Fieldref(f(class)?, f(name_and_type)?),
+            Constant::Methodref(ref class, ref name_and_type) =>
+                Constant::Methodref(f(class)?, f(name_and_type)?),
+            Constant::InterfaceMethodref(ref class, ref name_and_type) =>
+                Constant::InterfaceMethodref(f(class)?, f(name_and_type)?),
+            Constant::NameAndType(ref name, ref descriptor) =>
+                Constant::NameAndType(f(name)?, f(descriptor)?),
+            Constant::MethodHandle(kind, ref reference) => Constant::MethodHandle(kind, f(reference)?),
+            Constant::MethodType(ref descriptor) => Constant::MethodType(f(descriptor)?),
+            Constant::Dynamic(bootstrap, ref name_and_type) =>
+                Constant::Dynamic(bootstrap, f(name_and_type)?),
+            Constant::InvokeDynamic(bootstrap, ref name_and_type) =>
+                Constant::InvokeDynamic(bootstrap, f(name_and_type)?),
+            Constant::Module(ref name) => Constant::Module(f(name)?),
+            Constant::Package(ref name) => Constant::Package(f(name)?),
+        })
+    }
+
+    // Long and Double take two slots
+    pub fn is_wide(&self) -> bool {
+        matches!(*self, Constant::Long(_) | Constant::Double(_))
+    }
+
+    pub fn tag(&self) -> Tag {
+        match *self {
+            Constant::Utf8(_) | Constant::Utf8Bytes(_) => Tag::UTF8,
+            Constant::Integer(_) => Tag::INTEGER,
+            Constant::Float(_) => Tag::FLOAT,
+            Constant::Long(_) => Tag::LONG,
+            Constant::Double(_) => Tag::DOUBLE,
+            Constant::Class(_) => Tag::CLASS,
+            Constant::String(_) => Tag::STRING,
+            Constant::Fieldref(..) => Tag::FIELD_REF,
+            Constant::Methodref(..) => Tag::METHOD_REF,
+            Constant::InterfaceMethodref(..) => Tag::INTERFACE_METHOD_REF,
+            Constant::NameAndType(..) => Tag::NAME_AND_TYPE,
+            Constant::MethodHandle(..) => Tag::METHOD_HANDLE,
+            Constant::MethodType(_) => Tag::METHOD_TYPE,
+            Constant::Dynamic(..) => Tag::DYNAMIC,
+            Constant::InvokeDynamic(..) => Tag::INVOKE_DYNAMIC,
+            Constant::Module(_) => Tag::MODULE,
+            Constant::Package(_) => Tag::PACKAGE,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Slot {
+    Free,
+    // Claimed by a `.const` whose entry is not resolved yet
+    Reserved,
+    Used(Entry),
+    // Index 0 and the slot after a Long or Double
+    Unusable,
+}
+
+pub struct Pool {
+    slots: Vec<Slot>,
+}
+
+impl Default for Pool {
+    fn default() -> Self {
+        Pool::new()
+    }
+}
+
+impl Pool {
+    pub fn new() -> Self {
+        Pool { slots: vec![Slot::Unusable] }
+    }
+
+    pub fn from_constant_pool(constant_pool: &ConstantPoolRep) -> Self {
+        let mut slots = vec![Slot::Unusable; constant_pool.len().max(1)];
+        for (index, cp_info) in constant_pool.iter() {
+            slots[index] = Slot::Used(entry(cp_info));
+        }
+        Pool { slots }
+    }
+
+    pub fn get(&self, index: u2) -> Option<&Entry> {
+        match self.slots.get(index as usize) {
+            Some(Slot::Used(entry)) => Some(entry),
+            _ => None,
+        }
+    }
+
+    // Defined entries in index order
+    pub fn entries(&self) -> impl Iterator<Item = (u2, &Entry)> {
+        self.slots.iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match *slot {
+                Slot::Used(ref entry) => Some((index as u2, entry)),
+                _ => None,
+            })
+    }
+
+    pub fn reserve(&mut self, index: u2, wide: bool) -> Parsed<()> {
+        let index = index as usize;
+        let last = if wide { index + 1 } else { index };
+        if index == 0 || last > u2::MAX as usize - 1 {
+            return Err(format!("#{} is not a usable constant pool index", index));
+        }
+        if self.slots.len() <= last {
+            self.slots.resize(last + 1, Slot::Free);
+        }
+        if self.slots[index..=last].iter().any(|slot| *slot != Slot::Free) {
+            return Err(format!("constant pool slot #{} is already taken", index));
+        }
+        self.slots[index] = Slot::Reserved;
+        if wide {
+            self.slots[last] = Slot::Unusable;
+        }
+        Ok(())
+    }
+
+    // Fills a slot claimed by reserve
+    pub fn define(&mut self, index: u2, entry: Entry) -> Parsed<()> {
+        match self.slots.get_mut(index as usize) {
+            Some(slot) if *slot == Slot::Reserved => {
+                *slot = Slot::Used(entry);
+                Ok(())
+            },
+            _ => Err(format!("constant pool slot #{} is not reserved", index)),
+        }
+    }
+
+    // Index of the constant, adding it and everything it refers to when missing
+    pub fn resolve(&mut self, reference: &Ref) -> Parsed<u2> {
+        match *reference {
+            Ref::Index(index) => Ok(index),
+            Ref::Symbol(ref constant) => {
+                let entry = constant.map(|reference| self.resolve(reference))?;
+                self.intern(entry)
+            },
+        }
+    }
+
+    // Same as resolve without adding anything, None if some part is missing
+    pub fn lookup(&self, reference: &Ref) -> Option<u2> {
+        match *reference {
+            Ref::Index(index) => Some(index),
+            Ref::Symbol(ref constant) => {
+                let entry = constant.map(|reference| self.lookup(reference).ok_or(()))
+                    .ok()?;
+                self.find(&entry)
+            },
+        }
+    }
+
+    // `index` written as a symbol when resolving the symbol gives `index` back
+    pub fn symbol(&self, index: u2) -> Ref {
+        match self.symbolic(index, 0) {
+            Some(constant) => {
+                let symbol = Ref::Symbol(Box::new(constant));
+                if self.lookup(&symbol) == Some(index) { symbol } else { Ref::Index(index) }
+            },
+            None => Ref::Index(index),
+        }
+    }
+
+    pub fn write(&self, out: &mut Vec<u1>) -> Parsed<()> {
+        push_u2(out, self.slots.len() as u2);
+        for (index, slot) in self.slots.iter().enumerate().skip(1) {
+            match *slot {
+                Slot::Used(ref entry) => write_entry(entry, out)?,
+                Slot::Unusable => (),
+                Slot::Free | Slot::Reserved =>
+                    return Err(format!("constant pool slot #{} is not defined", index)),
+            }
+        }
+        Ok(())
+    }
+
+    fn find(&self, entry: &Entry) -> Option<u2> {
+        self.slots.iter()
+            .position(|slot| match *slot {
+                Slot::Used(ref used) => used == entry,
+                _ => false,
+            })
+            .map(|index| index as u2)
+    }
+
+    fn intern(&mut self, entry: Entry) -> Parsed<u2> {
+        if let Some(index) = self.find(&entry) {
+            return Ok(index);
+        }
+        let wide = entry.is_wide();
+        let index = (1..self.slots.len())
+            .find(|&index| {
+                self.slots[index] == Slot::Free &&
+                    (!wide || self.slots.get(index + 1).is_none_or(|slot| *slot == Slot::Free))
+            })
+            .unwrap_or(self.slots.len());
+        self.reserve(index as u2, wide)?;
+        self.define(index as u2, entry)?;
+        Ok(index as u2)
+    }
+
+    // Nothing in a well formed pool is nested deeper than MethodHandle -> Methodref ->
+    // NameAndType -> Utf8, the limit only stops cycles in broken ones
+    fn symbolic(&self, index: u2, depth: usize) -> Option<Constant<Ref>> {
+        if depth > 4 {
+            return None;
+        }
+        self.get(index)?
+            .map(|&index| self.symbolic(index, depth + 1).map(|constant| Ref::Symbol(Box::new(constant))).ok_or(()))
+            .ok()
+    }
+}
+
+fn entry(cp_info: &CPInfoRep) -> Entry {
+    match *cp_info {
+        CPInfoRep::Utf8(ref utf8) => match utf8.lossy_bytes() {
+            Some(bytes) => Constant::Utf8Bytes(bytes.to_vec()),
+            None => Constant::Utf8(utf8.as_str().to_string()),
+        },
+        CPInfoRep::Integer(integer) => Constant::Integer(integer.value()),
+        CPInfoRep::Float(float) => Constant::Float(float.value().to_bits()),
+        CPInfoRep::Long(long) => Constant::Long(long.value()),
+        CPInfoRep::Double(double) => Constant::Double(double.value().to_bits()),
+        CPInfoRep::Class(class) => Constant::Class(class.name_index()),
+        CPInfoRep::String(ref string) => Constant::String(string.string_index()),
+        CPInfoRep::Fieldref(ref field_ref) =>
+            Constant::Fieldref(field_ref.class_index(), field_ref.name_and_type_index()),
+        CPInfoRep::Methodref(ref method_ref) =>
+            Constant::Methodref(method_ref.class_index(), method_ref.name_and_type_index()),
+        CPInfoRep::InterfaceMethodref(ref method_ref) =>
+            Constant::InterfaceMethodref(method_ref.class_index(), method_ref.name_and_type_index()),
+        CPInfoRep::NameAndType(ref name_and_type) =>
+            Constant::NameAndType(name_and_type.name_index(), name_and_type.descriptor_index()),
+        CPInfoRep::MethodHandle(ref method_handle) =>
+            Constant::MethodHandle(method_handle.reference_kind(), method_handle.reference_index()),
+        CPInfoRep::MethodType(ref method_type) => Constant::MethodType(method_type.descriptor_index()),
+        CPInfoRep::Dynamic(ref dynamic) =>
+            Constant::Dynamic(dynamic.bootstrap_method_attr_index(), dynamic.name_and_type_index()),
+        CPInfoRep::InvokeDynamic(ref dynamic) =>
+            Constant::InvokeDynamic(dynamic.bootstrap_method_attr_index(), dynamic.name_and_type_index()),
+        CPInfoRep::Module(module) => Constant::Module(module.name_index()),
+        CPInfoRep::Package(package) => Constant::Package(package.name_index()),
+    }
+}
+
+fn write_utf8(bytes: &[u1], out: &mut Vec<u1>) -> Parsed<()> {
+    if bytes.len() > u2::MAX as usize {
+        return Err(format!("Utf8 constant of {} bytes is too long", bytes.len()));
+    }
+    push_u2(out, bytes.len() as u2);
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+fn write_entry(entry: &Entry, out: &mut Vec<u1>) -> Parsed<()> {
+    out.push(entry.tag() as u1);
+    match *entry {
+        Constant::Utf8(ref text) => write_utf8(&encode_modified_utf8(text), out)?,
+        Constant::Utf8Bytes(ref bytes) => write_utf8(bytes, out)?,
+        Constant::Integer(value) => push_u4(out, value as u4),
+        Constant::Float(bits) => push_u4(out, bits),
+        Constant::Long(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Constant::Double(bits) => out.extend_from_slice(&bits.to_be_bytes()),
+        Constant::Class(index) | Constant::String(index) | Constant::MethodType(index) |
+        Constant::Module(index) | Constant::Package(index) => push_u2(out, index),
+        Constant::Fieldref(first, second) | Constant::Methodref(first, second) |
+        Constant::InterfaceMethodref(first, second) | Constant::NameAndType(first, second) |
+        Constant::Dynamic(first, second) | Constant::InvokeDynamic(first, second) => {
+            push_u2(out, first);
+            push_u2(out, second);
+        },
+        Constant::MethodHandle(kind, index) => {
+            out.push(kind as u1);
+            push_u2(out, index);
+        },
+    }
+    Ok(())
+}
+
+pub fn push_u2(out: &mut Vec<u1>, value: u2) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub fn push_u4(out: &mut Vec<u1>, value: u4) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
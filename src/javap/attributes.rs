@@ -0,0 +1,593 @@
+/*
+* The attributes the parser keeps as raw bytes, decoded here the way javap prints them. One that
+* doesn't decode to its last byte is dumped as hex like an attribute javap doesn't know.
+*/
+use std::io::Write;
+
+use class_file::access_flags::{ACC_FINAL, ACC_INTERFACE, ACC_ABSTRACT, ACC_SYNTHETIC};
+use class_file::attribute_info::AttributeInfo;
+use class_file::constant_pool::CPInfoRep;
+use class_file::descriptor::{java_name, FieldType};
+use class_file::pos_slice::PoSlice;
+use class_file::signature::TypeSignature;
+use result::{Error, Result};
+use types::{u1, u2};
+
+use super::{escape, modifiers, quote, Javap, INNER_CLASS_MODIFIERS};
+
+// JVMS §4.7.24, §4.7.25
+const ACC_MANDATED: u2 = 0x8000;
+const ACC_OPEN: u2 = 0x0020;
+const ACC_TRANSITIVE: u2 = 0x0020;
+const ACC_STATIC_PHASE: u2 = 0x0040;
+
+// JVMS §4.7.16.1
+enum ElementValue {
+    // B C D F I J S Z s
+    Constant(u1, u2),
+    Enum(u2, u2),
+    Class(u2),
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
+
+struct Annotation {
+    type_index: u2,
+    pairs: Vec<(u2, ElementValue)>,
+}
+
+impl<'a, W: Write> Javap<'a, W> {
+    pub(super) fn raw_attribute(&mut self, name: &str, info: &[u1], indent: &str) -> Result<()> {
+        let mut decoded = Vec::new();
+        let complete = {
+            let mut javap = Javap {
+                out: &mut decoded,
+                class_file: self.class_file,
+                constant_pool: self.constant_pool,
+                options: self.options,
+            };
+            let slice = PoSlice::new(info);
+            matches!(javap.decode(name, &slice, indent), Ok(true)) && slice.remaining() == 0
+        };
+        if complete {
+            self.out.write_all(&decoded)?;
+        } else {
+            writeln!(self.out, "{}{}: length = 0x{:X}", indent, name, info.len())?;
+            for line in info.chunks(16) {
+                let bytes = line.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>();
+                writeln!(self.out, "{}   {}", indent, bytes.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+
+    // False for the attributes javap only dumps
+    fn decode(&mut self, name: &str, slice: &PoSlice, indent: &str) -> Result<bool> {
+        let more = format!("{}  ", indent);
+        match name {
+            "Signature" => {
+                let index = slice.read_u2()?;
+                let signature = self.constant_pool.utf8(index as usize)?;
+                self.commented(indent.len(), &format!("{}Signature: #{}", indent, index), signature)?;
+            },
+            "Deprecated" | "Synthetic" => writeln!(self.out, "{}{}: true", indent, name)?,
+            "Exceptions" => {
+                writeln!(self.out, "{}Exceptions:", indent)?;
+                let mut exceptions = Vec::new();
+                for _ in 0..slice.read_u2()? {
+                    exceptions.push(java_name(self.constant_pool.class_name(slice.read_u2()? as usize)?));
+                }
+                writeln!(self.out, "{}throws {}", more, exceptions.join(", "))?;
+            },
+            "InnerClasses" => {
+                writeln!(self.out, "{}InnerClasses:", indent)?;
+                for _ in 0..slice.read_u2()? {
+                    let (inner, outer, name, flags) = (slice.read_u2()?, slice.read_u2()?, slice.read_u2()?, slice.read_u2()?);
+                    let flags = if flags & ACC_INTERFACE != 0 { flags & !ACC_ABSTRACT } else { flags };
+                    let mut text = format!("{}{}", more, modifiers(flags, INNER_CLASS_MODIFIERS));
+                    let mut comment = String::new();
+                    if name != 0 {
+                        text.push_str(&format!("#{}= ", name));
+                        comment.push_str(&format!("{}=", self.constant_pool.utf8(name as usize)?));
+                    }
+                    text.push_str(&format!("#{}", inner));
+                    comment.push_str(&self.operand_comment(inner)?);
+                    if outer != 0 {
+                        text.push_str(&format!(" of #{}", outer));
+                        comment.push_str(&format!(" of {}", self.operand_comment(outer)?));
+                    }
+                    text.push(';');
+                    self.commented(more.len(), &text, &comment)?;
+                }
+            },
+            "EnclosingMethod" => {
+                let (class, method) = (slice.read_u2()?, slice.read_u2()?);
+                let mut comment = java_name(self.constant_pool.class_name(class as usize)?);
+                if method != 0 {
+                    comment.push('.');
+                    comment.push_str(self.constant_pool.name_and_type(method as usize)?.0);
+                }
+                self.commented(indent.len(), &format!("{}EnclosingMethod: #{}.#{}", indent, class, method), &comment)?;
+            },
+            "NestHost" => writeln!(self.out, "{}NestHost: {}", indent, self.operand_comment(slice.read_u2()?)?)?,
+            "NestMembers" | "PermittedSubclasses" => {
+                writeln!(self.out, "{}{}:", indent, name)?;
+                for _ in 0..slice.read_u2()? {
+                    writeln!(self.out, "{}{}", more, quote(self.constant_pool.class_name(slice.read_u2()? as usize)?))?;
+                }
+            },
+            "SourceDebugExtension" => {
+                writeln!(self.out, "{}SourceDebugExtension:", indent)?;
+                let text = String::from_utf8_lossy(slice.read_slice(slice.remaining())?);
+                for line in text.split(['\r', '\n']).filter(|line| !line.is_empty()) {
+                    writeln!(self.out, "{}{}", more, line)?;
+                }
+            },
+            "MethodParameters" => {
+                writeln!(self.out, "{}MethodParameters:", indent)?;
+                writeln!(self.out, "{}{:<30} Flags", more, "Name")?;
+                for _ in 0..slice.read_u1()? {
+                    let (name, flags) = (slice.read_u2()?, slice.read_u2()?);
+                    let name = match name {
+                        0 => "<no name>",
+                        index => self.constant_pool.utf8(index as usize)?,
+                    };
+                    let flags = [(ACC_FINAL, "final"), (ACC_MANDATED, "mandated"), (ACC_SYNTHETIC, "synthetic")].iter()
+                        .filter(|&&(flag, _)| flags & flag != 0)
+                        .map(|&(_, name)| name)
+                        .collect::<Vec<_>>();
+                    let line = format!("{}{:<30} {}", more, name, flags.join(" "));
+                    writeln!(self.out, "{}", line.trim_end())?;
+                }
+            },
+            "LocalVariableTable" | "LocalVariableTypeTable" => {
+                writeln!(self.out, "{}{}:", indent, name)?;
+                writeln!(self.out, "{}Start  Length  Slot  Name   Signature", more)?;
+                for _ in 0..slice.read_u2()? {
+                    let (start, length) = (slice.read_u2()?, slice.read_u2()?);
+                    let (name, descriptor, slot) = (slice.read_u2()?, slice.read_u2()?, slice.read_u2()?);
+                    writeln!(self.out, "{}{:>5} {:>7} {:>5} {:>5}   {}", more, start, length, slot,
+                        self.constant_pool.utf8(name as usize)?, self.constant_pool.utf8(descriptor as usize)?)?;
+                }
+            },
+            "StackMapTable" => self.stack_map_table(slice, indent)?,
+            "RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations" => {
+                writeln!(self.out, "{}{}:", indent, name)?;
+                for index in 0..slice.read_u2()? {
+                    let annotation = read_annotation(slice)?;
+                    self.annotation(&annotation, &format!("{}{}: ", more, index), &format!("{}  ", more))?;
+                }
+            },
+            "RuntimeVisibleParameterAnnotations" | "RuntimeInvisibleParameterAnnotations" => {
+                writeln!(self.out, "{}{}:", indent, name)?;
+                for parameter in 0..slice.read_u1()? {
+                    writeln!(self.out, "{}parameter {}:", more, parameter)?;
+                    for index in 0..slice.read_u2()? {
+                        let annotation = read_annotation(slice)?;
+                        self.annotation(&annotation, &format!("{}  {}: ", more, index), &format!("{}    ", more))?;
+                    }
+                }
+            },
+            "RuntimeVisibleTypeAnnotations" | "RuntimeInvisibleTypeAnnotations" => {
+                writeln!(self.out, "{}{}:", indent, name)?;
+                for index in 0..slice.read_u2()? {
+                    let position = type_annotation_position(slice)?;
+                    let annotation = read_annotation(slice)?;
+                    writeln!(self.out, "{}{}: {}: {}", more, index, self.raw_annotation(&annotation), position)?;
+                    writeln!(self.out, "{}  {}", more, self.resolved_annotation(&annotation, &format!("{}  ", more))?)?;
+                }
+            },
+            "AnnotationDefault" => {
+                writeln!(self.out, "{}AnnotationDefault:", indent)?;
+                let value = read_element_value(slice)?;
+                writeln!(self.out, "{}default_value: {}", more, self.raw_value(&value))?;
+                writeln!(self.out, "{}  {}", more, self.resolved_value(&value, &format!("{}  ", more))?)?;
+            },
+            "Record" => {
+                writeln!(self.out, "{}Record:", indent)?;
+                for _ in 0..slice.read_u2()? {
+                    let (name, descriptor) = (slice.read_u2()?, slice.read_u2()?);
+                    let descriptor = self.constant_pool.utf8(descriptor as usize)?;
+                    let attributes = read_attributes(slice)?;
+                    let signature = attributes.iter()
+                        .find(|&&(name, _)| self.constant_pool.utf8(name as usize).is_ok_and(|name| name == "Signature"))
+                        .map(|&(_, info)| PoSlice::new(info).read_u2())
+                        .transpose()?;
+                    let component_type = match signature {
+                        Some(index) => TypeSignature::parse(self.constant_pool.utf8(index as usize)?)?.type_name(),
+                        None => FieldType::parse(descriptor)?.type_name(),
+                    };
+                    writeln!(self.out, "{}{} {};", more, component_type, self.constant_pool.utf8(name as usize)?)?;
+                    writeln!(self.out, "{}  descriptor: {}", more, descriptor)?;
+                    for (name, info) in attributes {
+                        self.raw_attribute(self.constant_pool.utf8(name as usize)?, info, &format!("{}  ", more))?;
+                    }
+                    writeln!(self.out)?;
+                }
+            },
+            "Module" => self.module(slice, indent)?,
+            "ModulePackages" => {
+                writeln!(self.out, "{}ModulePackages:", indent)?;
+                for _ in 0..slice.read_u2()? {
+                    let index = slice.read_u2()?;
+                    let package = java_name(self.constant_pool.package_name(index as usize)?);
+                    self.commented(more.len(), &format!("{}#{}", more, index), &package)?;
+                }
+            },
+            "ModuleMainClass" => {
+                let index = slice.read_u2()?;
+                let main_class = java_name(self.constant_pool.class_name(index as usize)?);
+                self.commented(indent.len(), &format!("{}ModuleMainClass: #{}", indent, index), &main_class)?;
+            },
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    // JVMS §4.7.4
+    fn stack_map_table(&mut self, slice: &PoSlice, indent: &str) -> Result<()> {
+        let frames = slice.read_u2()?;
+        writeln!(self.out, "{}StackMapTable: number_of_entries = {}", indent, frames)?;
+        let (frame_indent, more) = (format!("{}  ", indent), format!("{}    ", indent));
+        for _ in 0..frames {
+            let frame_type = slice.read_u1()?;
+            let kind = match frame_type {
+                0..=63 => "same",
+                64..=127 => "same_locals_1_stack_item",
+                247 => "same_locals_1_stack_item_frame_extended",
+                248..=250 => "chop",
+                251 => "same_frame_extended",
+                252..=254 => "append",
+                255 => "full_frame",
+                _ => return Err(Error::Unsupported("reserved stack map frame type")),
+            };
+            writeln!(self.out, "{}frame_type = {} /* {} */", frame_indent, frame_type, kind)?;
+            match frame_type {
+                0..=63 => (),
+                64..=127 => {
+                    let stack = self.verification_types(slice, 1)?;
+                    writeln!(self.out, "{}stack = {}", more, stack)?;
+                },
+                _ => {
+                    writeln!(self.out, "{}offset_delta = {}", more, slice.read_u2()?)?;
+                    match frame_type {
+                        247 => {
+                            let stack = self.verification_types(slice, 1)?;
+                            writeln!(self.out, "{}stack = {}", more, stack)?;
+                        },
+                        252..=254 => {
+                            let locals = self.verification_types(slice, frame_type as usize - 251)?;
+                            writeln!(self.out, "{}locals = {}", more, locals)?;
+                        },
+                        255 => {
+                            let count = slice.read_u2()? as usize;
+                            let locals = self.verification_types(slice, count)?;
+                            writeln!(self.out, "{}locals = {}", more, locals)?;
+                            let count = slice.read_u2()? as usize;
+                            let stack = self.verification_types(slice, count)?;
+                            writeln!(self.out, "{}stack = {}", more, stack)?;
+                        },
+                        _ => (),
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    // "[ int, class java/lang/String ]", "[]" when there are none
+    fn verification_types(&self, slice: &PoSlice, count: usize) -> Result<String> {
+        let mut types = Vec::with_capacity(count);
+        for _ in 0..count {
+            types.push(match slice.read_u1()? {
+                0 => "top".to_string(),
+                1 => "int".to_string(),
+                2 => "float".to_string(),
+                3 => "double".to_string(),
+                4 => "long".to_string(),
+                5 => "null".to_string(),
+                6 => "this".to_string(),
+                7 => self.operand_comment(slice.read_u2()?)?,
+                8 => format!("uninitialized {}", slice.read_u2()?),
+                _ => return Err(Error::Unsupported("unknown verification type")),
+            });
+        }
+        Ok(if types.is_empty() { "[]".to_string() } else { format!("[ {} ]", types.join(", ")) })
+    }
+
+    // The indexes on the first line, what they resolve to below
+    fn annotation(&mut self, annotation: &Annotation, first: &str, indent: &str) -> Result<()> {
+        writeln!(self.out, "{}{}", first, self.raw_annotation(annotation))?;
+        writeln!(self.out, "{}{}", indent, self.resolved_annotation(annotation, indent)?)?;
+        Ok(())
+    }
+
+    fn raw_annotation(&self, annotation: &Annotation) -> String {
+        let pairs = annotation.pairs.iter()
+            .map(|(name, value)| format!("#{}={}", name, self.raw_value(value)))
+            .collect::<Vec<_>>();
+        format!("#{}({})", annotation.type_index, pairs.join(","))
+    }
+
+    fn raw_value(&self, value: &ElementValue) -> String {
+        match *value {
+            ElementValue::Constant(tag, index) => format!("{}#{}", tag as char, index),
+            ElementValue::Enum(type_name, const_name) => format!("e#{}.#{}", type_name, const_name),
+            ElementValue::Class(index) => format!("c#{}", index),
+            ElementValue::Annotation(ref annotation) => format!("@{}", self.raw_annotation(annotation)),
+            ElementValue::Array(ref values) =>
+                format!("[{}]", values.iter().map(|value| self.raw_value(value)).collect::<Vec<_>>().join(",")),
+        }
+    }
+
+    // Element values one per line below the annotation type, `indent` is where the type starts
+    fn resolved_annotation(&self, annotation: &Annotation, indent: &str) -> Result<String> {
+        let mut text = FieldType::parse(self.constant_pool.utf8(annotation.type_index as usize)?)?.type_name();
+        if !annotation.pairs.is_empty() {
+            text.push_str("(\n");
+            let more = format!("{}  ", indent);
+            for (name, value) in &annotation.pairs {
+                let name = self.constant_pool.utf8(*name as usize)?;
+                text.push_str(&format!("{}{}={}\n", more, name, self.resolved_value(value, &more)?));
+            }
+            text.push_str(indent);
+            text.push(')');
+        }
+        Ok(text)
+    }
+
+    fn resolved_value(&self, value: &ElementValue, indent: &str) -> Result<String> {
+        let constant_pool = self.constant_pool;
+        Ok(match *value {
+            ElementValue::Constant(tag, index) => {
+                let cp_info = constant_pool.get(index as usize)?;
+                let integer = match *cp_info {
+                    CPInfoRep::Integer(ref integer) => Some(integer.value()),
+                    _ => None,
+                };
+                match (tag, integer) {
+                    (b'B', _) => format!("(byte) {}", self.constant_value(cp_info)),
+                    (b'S', _) => format!("(short) {}", self.constant_value(cp_info)),
+                    (b'C', Some(value)) =>
+                        format!("'{}'", escape(&String::from_utf16_lossy(&[value as u16]))),
+                    (b'Z', Some(value)) => (value != 0).to_string(),
+                    (b's', _) => format!("\"{}\"", self.constant_value(cp_info)),
+                    _ => self.constant_value(cp_info),
+                }
+            },
+            ElementValue::Enum(type_name, const_name) =>
+                format!("{}.{}", constant_pool.utf8(type_name as usize)?, constant_pool.utf8(const_name as usize)?),
+            ElementValue::Class(index) => format!("class {}", constant_pool.utf8(index as usize)?),
+            ElementValue::Annotation(ref annotation) => self.resolved_annotation(annotation, indent)?,
+            ElementValue::Array(ref values) => {
+                let values = values.iter()
+                    .map(|value| self.resolved_value(value, indent))
+                    .collect::<Result<Vec<_>>>()?;
+                format!("[{}]", values.join(","))
+            },
+        })
+    }
+
+    // JVMS §4.7.25
+    fn module(&mut self, slice: &PoSlice, indent: &str) -> Result<()> {
+        let (more, table) = (format!("{}  ", indent), format!("{}    ", indent));
+        writeln!(self.out, "{}Module:", indent)?;
+        let (name, flags, version) = (slice.read_u2()?, slice.read_u2()?, slice.read_u2()?);
+        let comment = quote(self.constant_pool.module_name(name as usize)?) +
+            &flag_names(flags, &[(ACC_OPEN, "ACC_OPEN"), (ACC_MANDATED, "ACC_MANDATED"), (ACC_SYNTHETIC, "ACC_SYNTHETIC")]);
+        self.commented(more.len(), &format!("{}#{},{:x}", more, name, flags), &comment)?;
+        self.version(&more, version)?;
+
+        let requires = slice.read_u2()?;
+        self.commented(more.len(), &format!("{}{}", more, requires), "requires")?;
+        for _ in 0..requires {
+            let (index, flags, version) = (slice.read_u2()?, slice.read_u2()?, slice.read_u2()?);
+            let comment = quote(self.constant_pool.module_name(index as usize)?) + &flag_names(flags, &[(ACC_TRANSITIVE, "ACC_TRANSITIVE"),
+                (ACC_STATIC_PHASE, "ACC_STATIC_PHASE"), (ACC_SYNTHETIC, "ACC_SYNTHETIC"), (ACC_MANDATED, "ACC_MANDATED")]);
+            self.commented(table.len(), &format!("{}#{},{:x}", table, index, flags), &comment)?;
+            self.version(&table, version)?;
+        }
+
+        for kind in &["exports", "opens"] {
+            let count = slice.read_u2()?;
+            self.commented(more.len(), &format!("{}{}", more, count), kind)?;
+            for _ in 0..count {
+                let (index, flags) = (slice.read_u2()?, slice.read_u2()?);
+                let mut comment = quote(self.constant_pool.package_name(index as usize)?) +
+                    &flag_names(flags, &[(ACC_MANDATED, "ACC_MANDATED"), (ACC_SYNTHETIC, "ACC_SYNTHETIC")]);
+                let to = slice.read_u2()?;
+                if to != 0 {
+                    comment.push_str(&format!(" to ... {}", to));
+                }
+                self.commented(table.len(), &format!("{}#{},{:x}", table, index, flags), &comment)?;
+                for _ in 0..to {
+                    let index = slice.read_u2()?;
+                    let comment = format!("... to {}", quote(self.constant_pool.module_name(index as usize)?));
+                    self.commented(table.len() + 2, &format!("{}  #{}", table, index), &comment)?;
+                }
+            }
+        }
+
+        let uses = slice.read_u2()?;
+        self.commented(more.len(), &format!("{}{}", more, uses), "uses")?;
+        for _ in 0..uses {
+            let index = slice.read_u2()?;
+            self.commented(table.len(), &format!("{}#{}", table, index), &quote(self.constant_pool.class_name(index as usize)?))?;
+        }
+
+        let provides = slice.read_u2()?;
+        self.commented(more.len(), &format!("{}{}", more, provides), "provides")?;
+        for _ in 0..provides {
+            let (index, with) = (slice.read_u2()?, slice.read_u2()?);
+            let comment = format!("{} with ... {}", quote(self.constant_pool.class_name(index as usize)?), with);
+            self.commented(table.len(), &format!("{}#{}", table, index), &comment)?;
+            for _ in 0..with {
+                let index = slice.read_u2()?;
+                let comment = format!("... with {}", quote(self.constant_pool.class_name(index as usize)?));
+                self.commented(table.len() + 2, &format!("{}  #{}", table, index), &comment)?;
+            }
+        }
+        Ok(())
+    }
+
+    // The module declaration's body, what javap shows for module-info without -v
+    pub(super) fn module_directives(&mut self) -> Result<()> {
+        let module = self.class_file.attributes().iter().find_map(|attribute| match *attribute {
+            AttributeInfo::Raw(ref raw) if attribute.name() == "Module" => Some(raw.info()),
+            _ => None,
+        });
+        let slice = match module {
+            Some(info) => PoSlice::new(info),
+            None => return Ok(()),
+        };
+        let constant_pool = self.constant_pool;
+        let module_name = |index: u2| constant_pool.module_name(index as usize);
+        let class_name = |index: u2| constant_pool.class_name(index as usize).map(java_name);
+        slice.read_slice(6)?;
+        for _ in 0..slice.read_u2()? {
+            let (index, flags) = (slice.read_u2()?, slice.read_u2()?);
+            slice.read_u2()?;
+            let static_phase = if flags & ACC_STATIC_PHASE != 0 { "static " } else { "" };
+            let transitive = if flags & ACC_TRANSITIVE != 0 { "transitive " } else { "" };
+            writeln!(self.out, "  requires {}{}{};", static_phase, transitive, module_name(index)?)?;
+        }
+        for kind in &["exports", "opens"] {
+            for _ in 0..slice.read_u2()? {
+                let index = slice.read_u2()?;
+                slice.read_u2()?;
+                let package = java_name(constant_pool.package_name(index as usize)?);
+                let to = slice.read_u2()?;
+                if to == 0 {
+                    writeln!(self.out, "  {} {};", kind, package)?;
+                } else {
+                    writeln!(self.out, "  {} {} to", kind, package)?;
+                    for i in 0..to {
+                        writeln!(self.out, "    {}{}", module_name(slice.read_u2()?)?, if i + 1 < to { "," } else { ";" })?;
+                    }
+                }
+            }
+        }
+        for _ in 0..slice.read_u2()? {
+            writeln!(self.out, "  uses {};", class_name(slice.read_u2()?)?)?;
+        }
+        for _ in 0..slice.read_u2()? {
+            // javap prints two spaces here
+            writeln!(self.out, "  provides  {} with", class_name(slice.read_u2()?)?)?;
+            let with = slice.read_u2()?;
+            for i in 0..with {
+                writeln!(self.out, "    {}{}", class_name(slice.read_u2()?)?, if i + 1 < with { "," } else { ";" })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn version(&mut self, indent: &str, index: u2) -> Result<()> {
+        match index {
+            0 => writeln!(self.out, "{}#0", indent)?,
+            index => self.commented(indent.len(), &format!("{}#{}", indent, index), self.constant_pool.utf8(index as usize)?)?,
+        }
+        Ok(())
+    }
+}
+
+// " ACC_OPEN ACC_SYNTHETIC", each flag that is set with a space before it
+fn flag_names(flags: u2, names: &[(u2, &str)]) -> String {
+    names.iter()
+        .filter(|&&(flag, _)| flags & flag != 0)
+        .map(|&(_, name)| format!(" {}", name))
+        .collect()
+}
+
+// attribute_name_index and info of each attribute, what Record components have
+fn read_attributes<'b>(slice: &PoSlice<'b>) -> Result<Vec<(u2, &'b [u1])>> {
+    let mut attributes = Vec::new();
+    for _ in 0..slice.read_u2()? {
+        let name = slice.read_u2()?;
+        let length = slice.read_u4()?;
+        attributes.push((name, slice.read_slice(length as usize)?));
+    }
+    Ok(attributes)
+}
+
+fn read_annotation(slice: &PoSlice) -> Result<Annotation> {
+    let type_index = slice.read_u2()?;
+    let mut pairs = Vec::new();
+    for _ in 0..slice.read_u2()? {
+        pairs.push((slice.read_u2()?, read_element_value(slice)?));
+    }
+    Ok(Annotation { type_index, pairs })
+}
+
+fn read_element_value(slice: &PoSlice) -> Result<ElementValue> {
+    Ok(match slice.read_u1()? {
+        tag @ (b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's') => ElementValue::Constant(tag, slice.read_u2()?),
+        b'e' => ElementValue::Enum(slice.read_u2()?, slice.read_u2()?),
+        b'c' => ElementValue::Class(slice.read_u2()?),
+        b'@' => ElementValue::Annotation(read_annotation(slice)?),
+        b'[' => {
+            let mut values = Vec::new();
+            for _ in 0..slice.read_u2()? {
+                values.push(read_element_value(slice)?);
+            }
+            ElementValue::Array(values)
+        },
+        _ => return Err(Error::Unsupported("unknown element value tag")),
+    })
+}
+
+// JVMS §4.7.20, the target and the type path as javap prints them, "CAST, offset=1, type_index=0"
+fn type_annotation_position(slice: &PoSlice) -> Result<String> {
+    let target_type = slice.read_u1()?;
+    let mut position = match target_type {
+        0x00 => format!("CLASS_TYPE_PARAMETER, param_index={}", slice.read_u1()?),
+        0x01 => format!("METHOD_TYPE_PARAMETER, param_index={}", slice.read_u1()?),
+        0x10 => format!("CLASS_EXTENDS, type_index={}", supertype_index(slice.read_u2()?)),
+        0x11 => format!("CLASS_TYPE_PARAMETER_BOUND, param_index={}, bound_index={}", slice.read_u1()?, slice.read_u1()?),
+        0x12 => format!("METHOD_TYPE_PARAMETER_BOUND, param_index={}, bound_index={}", slice.read_u1()?, slice.read_u1()?),
+        0x13 => "FIELD".to_string(),
+        0x14 => "METHOD_RETURN".to_string(),
+        0x15 => "METHOD_RECEIVER".to_string(),
+        0x16 => format!("METHOD_FORMAL_PARAMETER, param_index={}", slice.read_u1()?),
+        0x17 => format!("THROWS, type_index={}", slice.read_u2()?),
+        0x40 | 0x41 => {
+            let mut ranges = Vec::new();
+            for _ in 0..slice.read_u2()? {
+                let (start, length, index) = (slice.read_u2()?, slice.read_u2()?, slice.read_u2()?);
+                ranges.push(format!("start_pc={}, length={}, index={}", start, length, index));
+            }
+            let kind = if target_type == 0x40 { "LOCAL_VARIABLE" } else { "RESOURCE_VARIABLE" };
+            format!("{}, {{{}}}", kind, ranges.join("; "))
+        },
+        0x42 => format!("EXCEPTION_PARAMETER, exception_index={}", slice.read_u2()?),
+        0x43..=0x46 => {
+            let kind = ["INSTANCEOF", "NEW", "CONSTRUCTOR_REFERENCE", "METHOD_REFERENCE"][target_type as usize - 0x43];
+            format!("{}, offset={}", kind, slice.read_u2()?)
+        },
+        0x47..=0x4B => {
+            let kind = ["CAST", "CONSTRUCTOR_INVOCATION_TYPE_ARGUMENT", "METHOD_INVOCATION_TYPE_ARGUMENT",
+                "CONSTRUCTOR_REFERENCE_TYPE_ARGUMENT", "METHOD_REFERENCE_TYPE_ARGUMENT"][target_type as usize - 0x47];
+            format!("{}, offset={}, type_index={}", kind, slice.read_u2()?, slice.read_u1()?)
+        },
+        _ => return Err(Error::Unsupported("unknown type annotation target")),
+    };
+    let mut path = Vec::new();
+    for _ in 0..slice.read_u1()? {
+        let (kind, argument) = (slice.read_u1()?, slice.read_u1()?);
+        path.push(match kind {
+            0 => "ARRAY".to_string(),
+            1 => "INNER_TYPE".to_string(),
+            2 => "WILDCARD".to_string(),
+            3 => format!("TYPE_ARGUMENT({})", argument),
+            _ => return Err(Error::Unsupported("unknown type path kind")),
+        });
+    }
+    if !path.is_empty() {
+        position.push_str(&format!(", location=[{}]", path.join(", ")));
+    }
+    Ok(position)
+}
+
+// 65535 is the superclass, javap shows it as -1
+fn supertype_index(index: u2) -> i32 {
+    if index == u2::MAX { -1 } else { i32::from(index) }
+}
@@ -0,0 +1,707 @@
+/*
+* Prints a parsed ClassFile the way javap does. `javap -v -p` from JDK 17 prints the classes in
+* src/tests/corpus exactly like this, attributes the parser keeps raw are decoded in attributes.rs.
+*/
+use std::io::Write;
+
+use class_file::access_flags::*;
+use class_file::attribute_info::{AttributeInfo, CodeAttribute};
+use class_file::bytecode::{array_type_name, Instruction, Instructions, Operand};
+use class_file::class_file::{ClassFile, MemberInfo};
+use class_file::constant_pool::{ConstantPoolRep, CPInfoRep};
+use class_file::descriptor::{java_name, FieldType, MethodDescriptor};
+use class_file::pos_slice::PoSlice;
+use class_file::signature::{ClassSignature, MethodSignature, TypeParameter, TypeSignature};
+use result::Result;
+use types::{u1, u2};
+
+mod attributes;
+
+const CLASS_MODIFIERS: &[(u2, &str)] = &[(ACC_PUBLIC, "public"), (ACC_FINAL, "final"), (ACC_ABSTRACT, "abstract")];
+
+const INNER_CLASS_MODIFIERS: &[(u2, &str)] = &[
+    (ACC_PUBLIC, "public"), (ACC_PRIVATE, "private"), (ACC_PROTECTED, "protected"), (ACC_STATIC, "static"),
+    (ACC_FINAL, "final"), (ACC_ABSTRACT, "abstract"),
+];
+
+const FIELD_MODIFIERS: &[(u2, &str)] = &[
+    (ACC_PUBLIC, "public"), (ACC_PRIVATE, "private"), (ACC_PROTECTED, "protected"), (ACC_STATIC, "static"),
+    (ACC_FINAL, "final"), (ACC_VOLATILE, "volatile"), (ACC_TRANSIENT, "transient"),
+];
+
+const METHOD_MODIFIERS: &[(u2, &str)] = &[
+    (ACC_PUBLIC, "public"), (ACC_PRIVATE, "private"), (ACC_PROTECTED, "protected"), (ACC_STATIC, "static"),
+    (ACC_FINAL, "final"), (ACC_SYNCHRONIZED, "synchronized"), (ACC_NATIVE, "native"),
+    (ACC_ABSTRACT, "abstract"), (ACC_STRICT, "strictfp"),
+];
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    // -c
+    pub code: bool,
+    // -v, implies everything else
+    pub verbose: bool,
+    // -p
+    pub private: bool,
+    // -l
+    pub lines: bool,
+}
+
+impl Options {
+    fn code(&self) -> bool { self.code || self.verbose }
+
+    fn lines(&self) -> bool { self.lines || self.verbose }
+
+    // javap hides private members unless -p is given
+    fn shows(&self, member: &MemberInfo) -> bool {
+        self.private || !member.access_flags().is_private()
+    }
+}
+
+pub fn disassemble<W: Write>(out: &mut W, class_file: &ClassFile, options: &Options) -> Result<()> {
+    Javap { out, class_file, constant_pool: class_file.constant_pool(), options }.class()
+}
+
+struct Javap<'a, W: Write + 'a> {
+    out: &'a mut W,
+    class_file: &'a ClassFile,
+    constant_pool: &'a ConstantPoolRep,
+    options: &'a Options,
+}
+
+impl<'a, W: Write> Javap<'a, W> {
+    fn class(&mut self) -> Result<()> {
+        let class_file = self.class_file;
+        let options = self.options;
+        if let Some(source_file) = class_file.source_file()? {
+            let indent = if options.verbose { "  " } else { "" };
+            writeln!(self.out, "{}Compiled from \"{}\"", indent, source_file)?;
+        }
+        let declaration = self.class_declaration()?;
+        if !options.verbose {
+            writeln!(self.out, "{} {{", declaration)?;
+        } else {
+            let flags = class_file.access_flags();
+            writeln!(self.out, "{}", declaration)?;
+            writeln!(self.out, "  minor version: {}", class_file.minor_version())?;
+            writeln!(self.out, "  major version: {}", class_file.major_version())?;
+            writeln!(self.out, "  flags: {}", format_flags(flags.bits(), flags.names(), flags.unknown_bits()))?;
+            let this_class = format!("  this_class: #{}", class_file.this_class());
+            self.commented(2, &this_class, &self.constant(class_file.this_class() as usize)?)?;
+            match class_file.super_class() {
+                0 => writeln!(self.out, "  super_class: #0")?,
+                index => self.commented(2, &format!("  super_class: #{}", index), &self.constant(index as usize)?)?,
+            }
+            writeln!(self.out, "  interfaces: {}, fields: {}, methods: {}, attributes: {}",
+                class_file.interfaces().len(),
+                class_file.fields().len(),
+                class_file.methods().len(),
+                class_file.attributes().len())?;
+            self.constant_pool()?;
+            writeln!(self.out, "{{")?;
+        }
+
+        if class_file.access_flags().is_module() && !options.verbose {
+            self.module_directives()?;
+        }
+        // Like javap, a blank line follows every member but the last method
+        let spaced = options.verbose || options.code() || options.lines();
+        let mut blank_line = false;
+        for field in class_file.fields().iter().filter(|member| options.shows(member)) {
+            if blank_line {
+                writeln!(self.out)?;
+            }
+            self.field(field)?;
+            blank_line = spaced;
+        }
+        for method in class_file.methods().iter().filter(|member| options.shows(member)) {
+            if blank_line {
+                writeln!(self.out)?;
+            }
+            self.method(method)?;
+            blank_line = spaced;
+        }
+        if blank_line && !class_file.methods().iter().any(|member| options.shows(member)) {
+            writeln!(self.out)?;
+        }
+        writeln!(self.out, "}}")?;
+
+        if options.verbose {
+            for attribute in class_file.attributes() {
+                self.attribute(attribute, "")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn class_declaration(&self) -> Result<String> {
+        let class_file = self.class_file;
+        let flags = class_file.access_flags();
+        if flags.is_module() {
+            let mut declaration = modifiers(flags.bits(), CLASS_MODIFIERS);
+            declaration.push_str("module ");
+            declaration.push_str(&self.module_declaration_name()?);
+            return Ok(declaration);
+        }
+        // Interfaces are abstract anyway, javap leaves it out
+        let bits = if flags.is_interface() { flags.bits() & !ACC_ABSTRACT } else { flags.bits() };
+        let mut declaration = modifiers(bits, CLASS_MODIFIERS);
+        declaration.push_str(if flags.is_interface() { "interface " } else { "class " });
+        declaration.push_str(&java_name(class_file.name()?));
+
+        if let Some(signature) = self.signature(class_file.attributes())?.and_then(|text| ClassSignature::parse(text).ok()) {
+            let interfaces = signature.interfaces().iter()
+                .map(|interface| interface.type_name())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let super_class = signature.super_class();
+            declaration.push_str(&self.type_params(signature.type_params()));
+            // Without type parameters or interfaces javap can't tell the signature from a field
+            // type and prints it as the superclass, whatever kind of class it is
+            if flags.is_interface() && !(signature.type_params().is_empty() && interfaces.is_empty()) {
+                if !interfaces.is_empty() {
+                    declaration.push_str(" extends ");
+                    declaration.push_str(&interfaces);
+                }
+            } else {
+                if self.options.verbose || !super_class.is_object() {
+                    declaration.push_str(" extends ");
+                    declaration.push_str(&super_class.type_name());
+                }
+                if !interfaces.is_empty() {
+                    declaration.push_str(" implements ");
+                    declaration.push_str(&interfaces);
+                }
+            }
+            return Ok(declaration);
+        }
+
+        let interfaces = class_file.interface_names()?
+            .into_iter()
+            .map(java_name)
+            .collect::<Vec<_>>()
+            .join(",");
+        if flags.is_interface() {
+            if !interfaces.is_empty() {
+                declaration.push_str(" extends ");
+                declaration.push_str(&interfaces);
+            }
+        } else {
+            if class_file.super_class() != 0 {
+                let super_name = class_file.super_name()?;
+                if super_name != "java/lang/Object" {
+                    declaration.push_str(" extends ");
+                    declaration.push_str(&java_name(super_name));
+                }
+            }
+            if !interfaces.is_empty() {
+                declaration.push_str(" implements ");
+                declaration.push_str(&interfaces);
+            }
+        }
+        Ok(declaration)
+    }
+
+    // "<K, V extends java.lang.Comparable<V>>", an Object bound only shows with -v
+    fn type_params(&self, type_params: &[TypeParameter]) -> String {
+        if type_params.is_empty() {
+            return String::new();
+        }
+        let type_params = type_params.iter()
+            .map(|type_param| {
+                let mut bounds = Vec::new();
+                if let Some(class_bound) = type_param.class_bound() {
+                    let object = matches!(*class_bound, TypeSignature::Class(ref class) if class.is_object());
+                    if self.options.verbose || !object {
+                        bounds.push(class_bound.type_name());
+                    }
+                }
+                bounds.extend(type_param.interface_bounds().iter().map(TypeSignature::type_name));
+                if bounds.is_empty() {
+                    type_param.name().to_string()
+                } else {
+                    format!("{} extends {}", type_param.name(), bounds.join(" & "))
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("<{}>", type_params.join(", "))
+    }
+
+    // The Signature attribute among these, generic declarations are printed from it
+    fn signature(&self, attributes: &[AttributeInfo]) -> Result<Option<&'a str>> {
+        for attribute in attributes {
+            if let AttributeInfo::Raw(ref raw) = *attribute {
+                if attribute.name() == "Signature" {
+                    let index = PoSlice::new(raw.info()).read_u2()?;
+                    return Ok(Some(self.constant_pool.utf8(index as usize)?));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // module-info names its module in the Module attribute, not this_class
+    fn module_declaration_name(&self) -> Result<String> {
+        for attribute in self.class_file.attributes() {
+            if let AttributeInfo::Raw(ref raw) = *attribute {
+                if attribute.name() == "Module" {
+                    let index = PoSlice::new(raw.info()).read_u2()?;
+                    return Ok(java_name(self.constant_pool.module_name(index as usize)?));
+                }
+            }
+        }
+        Ok(java_name(self.class_file.name()?))
+    }
+
+    // Class names in the Exceptions attribute, if there is one
+    fn exceptions(&self, attributes: &[AttributeInfo]) -> Result<Option<Vec<String>>> {
+        for attribute in attributes {
+            if let AttributeInfo::Raw(ref raw) = *attribute {
+                if attribute.name() == "Exceptions" {
+                    let slice = PoSlice::new(raw.info());
+                    let mut exceptions = Vec::new();
+                    for _ in 0..slice.read_u2()? {
+                        exceptions.push(java_name(self.constant_pool.class_name(slice.read_u2()? as usize)?));
+                    }
+                    return Ok(Some(exceptions));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn constant_pool(&mut self) -> Result<()> {
+        writeln!(self.out, "Constant pool:")?;
+        let constant_pool = self.constant_pool;
+        // Wide enough for the largest index
+        let width = constant_pool.len().to_string().len() + 1;
+        for (index, cp_info) in constant_pool.iter() {
+            let (kind, arguments) = match *cp_info {
+                CPInfoRep::Class(ref class) => ("Class", format!("#{}", class.name_index())),
+                CPInfoRep::Fieldref(ref field) =>
+                    ("Fieldref", format!("#{}.#{}", field.class_index(), field.name_and_type_index())),
+                CPInfoRep::Methodref(ref method) =>
+                    ("Methodref", format!("#{}.#{}", method.class_index(), method.name_and_type_index())),
+                CPInfoRep::InterfaceMethodref(ref method) =>
+                    ("InterfaceMethodref", format!("#{}.#{}", method.class_index(), method.name_and_type_index())),
+                CPInfoRep::String(ref string) => ("String", format!("#{}", string.string_index())),
+                CPInfoRep::Integer(ref integer) => ("Integer", integer.value().to_string()),
+                CPInfoRep::Float(ref float) => ("Float", format_float(float.value())),
+                CPInfoRep::Long(ref long) => ("Long", format!("{}l", long.value())),
+                CPInfoRep::Double(ref double) => ("Double", format_double(double.value())),
+                CPInfoRep::NameAndType(ref name_and_type) =>
+                    ("NameAndType", format!("#{}:#{}", name_and_type.name_index(), name_and_type.descriptor_index())),
+                CPInfoRep::Utf8(ref utf8) => ("Utf8", escape(utf8.as_str())),
+                CPInfoRep::MethodHandle(ref method_handle) =>
+                    ("MethodHandle", format!("{}:#{}", method_handle.reference_kind() as u1, method_handle.reference_index())),
+                CPInfoRep::MethodType(ref method_type) => ("MethodType", format!("#{}", method_type.descriptor_index())),
+                CPInfoRep::Dynamic(ref dynamic) =>
+                    ("Dynamic", format!("#{}:#{}", dynamic.bootstrap_method_attr_index(), dynamic.name_and_type_index())),
+                CPInfoRep::InvokeDynamic(ref dynamic) =>
+                    ("InvokeDynamic", format!("#{}:#{}", dynamic.bootstrap_method_attr_index(), dynamic.name_and_type_index())),
+                CPInfoRep::Module(ref module) => ("Module", format!("#{}", module.name_index())),
+                CPInfoRep::Package(ref package) => ("Package", format!("#{}", package.name_index())),
+            };
+            let line = format!("  {:>width$} = {:<18} {}", format!("#{}", index), kind, arguments, width = width);
+            match *cp_info {
+                CPInfoRep::Utf8(_) | CPInfoRep::Integer(_) | CPInfoRep::Float(_) |
+                CPInfoRep::Long(_) | CPInfoRep::Double(_) => writeln!(self.out, "{}", line.trim_end())?,
+                // javap puts an extra space before these
+                CPInfoRep::MethodType(_) => self.commented(2, &line, &format!(" {}", self.constant_comment(cp_info)?))?,
+                _ => self.commented(2, &line, &self.constant_comment(cp_info)?)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn constant(&self, index: usize) -> Result<String> {
+        self.constant_comment(self.constant_pool.get(index)?)
+    }
+
+    // Comment for the constant pool listing, symbolic references resolved all the way down
+    fn constant_comment(&self, cp_info: &CPInfoRep) -> Result<String> {
+        let constant_pool = self.constant_pool;
+        let utf8 = |index: u2| constant_pool.utf8(index as usize);
+        Ok(match *cp_info {
+            CPInfoRep::Class(ref class) => quote(utf8(class.name_index())?),
+            CPInfoRep::Fieldref(ref member) => self.member(member.class_index(), member.name_and_type_index(), false)?,
+            CPInfoRep::Methodref(ref member) => self.member(member.class_index(), member.name_and_type_index(), false)?,
+            CPInfoRep::InterfaceMethodref(ref member) =>
+                self.member(member.class_index(), member.name_and_type_index(), false)?,
+            CPInfoRep::String(ref string) => escape(utf8(string.string_index())?),
+            CPInfoRep::NameAndType(ref name_and_type) =>
+                format!("{}:{}", quote(utf8(name_and_type.name_index())?), utf8(name_and_type.descriptor_index())?),
+            CPInfoRep::MethodHandle(ref method_handle) => {
+                let reference = self.constant(method_handle.reference_index() as usize)?;
+                format!("{} {}", method_handle.reference_kind().name(), reference)
+            },
+            CPInfoRep::MethodType(ref method_type) => utf8(method_type.descriptor_index())?.to_string(),
+            CPInfoRep::Dynamic(ref dynamic) =>
+                format!("#{}:{}", dynamic.bootstrap_method_attr_index(), self.name_and_type(dynamic.name_and_type_index())?),
+            CPInfoRep::InvokeDynamic(ref dynamic) =>
+                format!("#{}:{}", dynamic.bootstrap_method_attr_index(), self.name_and_type(dynamic.name_and_type_index())?),
+            CPInfoRep::Module(ref module) => quote(utf8(module.name_index())?),
+            CPInfoRep::Package(ref package) => quote(utf8(package.name_index())?),
+            CPInfoRep::Integer(_) | CPInfoRep::Float(_) | CPInfoRep::Long(_) |
+            CPInfoRep::Double(_) | CPInfoRep::Utf8(_) => self.constant_value(cp_info),
+        })
+    }
+
+    fn constant_value(&self, cp_info: &CPInfoRep) -> String {
+        match *cp_info {
+            CPInfoRep::Integer(ref integer) => integer.value().to_string(),
+            CPInfoRep::Float(ref float) => format_float(float.value()),
+            CPInfoRep::Long(ref long) => format!("{}l", long.value()),
+            CPInfoRep::Double(ref double) => format_double(double.value()),
+            CPInfoRep::Utf8(ref utf8) => escape(utf8.as_str()),
+            _ => String::new(),
+        }
+    }
+
+    fn name_and_type(&self, index: u2) -> Result<String> {
+        let (name, descriptor) = self.constant_pool.name_and_type(index as usize)?;
+        Ok(format!("{}:{}", quote(name), descriptor))
+    }
+
+    // owner.name:descriptor, owner is left out inside instructions when it's this class
+    fn member(&self, class_index: u2, name_and_type_index: u2, in_code: bool) -> Result<String> {
+        let class_name = self.constant_pool.class_name(class_index as usize)?;
+        let name_and_type = self.name_and_type(name_and_type_index)?;
+        if in_code && class_index == self.class_file.this_class() {
+            Ok(name_and_type)
+        } else {
+            Ok(format!("{}.{}", quote(class_name), name_and_type))
+        }
+    }
+
+    // The comment javap puts after an instruction that references the constant pool
+    fn operand_comment(&self, index: u2) -> Result<String> {
+        let cp_info = self.constant_pool.get(index as usize)?;
+        Ok(match *cp_info {
+            CPInfoRep::Class(ref class) =>
+                format!("class {}", quote(self.constant_pool.utf8(class.name_index() as usize)?)),
+            CPInfoRep::Fieldref(ref member) =>
+                format!("Field {}", self.member(member.class_index(), member.name_and_type_index(), true)?),
+            CPInfoRep::Methodref(ref member) =>
+                format!("Method {}", self.member(member.class_index(), member.name_and_type_index(), true)?),
+            CPInfoRep::InterfaceMethodref(ref member) =>
+                format!("InterfaceMethod {}", self.member(member.class_index(), member.name_and_type_index(), true)?),
+            CPInfoRep::String(_) => format!("String {}", self.constant_comment(cp_info)?),
+            CPInfoRep::Integer(_) => format!("int {}", self.constant_value(cp_info)),
+            CPInfoRep::Float(_) => format!("float {}", self.constant_value(cp_info)),
+            CPInfoRep::Long(_) => format!("long {}", self.constant_value(cp_info)),
+            CPInfoRep::Double(_) => format!("double {}", self.constant_value(cp_info)),
+            CPInfoRep::MethodHandle(_) => format!("MethodHandle {}", self.constant_comment(cp_info)?),
+            CPInfoRep::MethodType(_) => format!("MethodType {}", self.constant_comment(cp_info)?),
+            CPInfoRep::Dynamic(_) => format!("Dynamic {}", self.constant_comment(cp_info)?),
+            CPInfoRep::InvokeDynamic(_) => format!("InvokeDynamic {}", self.constant_comment(cp_info)?),
+            _ => self.constant_comment(cp_info)?,
+        })
+    }
+
+    fn field(&mut self, field: &MemberInfo) -> Result<()> {
+        let flags = field.field_flags();
+        let field_type = match self.signature(field.attributes())?.and_then(|text| TypeSignature::parse(text).ok()) {
+            Some(signature) => signature.type_name(),
+            None => field.get_field_type()?.type_name(),
+        };
+        let mut declaration = modifiers(flags.bits(), FIELD_MODIFIERS);
+        declaration.push_str(&field_type);
+        declaration.push(' ');
+        declaration.push_str(field.get_name()?);
+        writeln!(self.out, "  {};", declaration)?;
+        if self.options.verbose {
+            writeln!(self.out, "    descriptor: {}", field.get_descriptor()?)?;
+            writeln!(self.out, "    flags: {}", format_flags(flags.bits(), flags.names(), flags.unknown_bits()))?;
+            for attribute in field.attributes() {
+                self.attribute(attribute, "    ")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn method(&mut self, method: &MemberInfo) -> Result<()> {
+        let flags = method.method_flags();
+        let descriptor = method.get_method_descriptor()?;
+        let name = method.get_name()?;
+        let mut declaration = modifiers(flags.bits(), METHOD_MODIFIERS);
+        // Interface methods with a body since Java 8
+        let class_file = self.class_file;
+        if class_file.access_flags().is_interface() && !flags.is_abstract() && name != "<clinit>" &&
+            class_file.major_version() >= 52 && !flags.is_static() && !flags.is_private() {
+            declaration.push_str("default ");
+        }
+
+        let (params, return_type, throws) = match self.signature(method.attributes())?.and_then(|text| MethodSignature::parse(text).ok()) {
+            Some(signature) => {
+                let type_params = self.type_params(signature.type_params());
+                if !type_params.is_empty() {
+                    declaration.push_str(&type_params);
+                    declaration.push(' ');
+                }
+                (signature.params().iter().map(TypeSignature::type_name).collect::<Vec<_>>(),
+                    signature.return_type().map_or("void".to_string(), TypeSignature::type_name),
+                    signature.throws().iter().map(thrown_type_name).collect::<Vec<_>>())
+            },
+            None => (descriptor.params().iter().map(FieldType::type_name).collect(),
+                descriptor.return_type().map_or("void".to_string(), FieldType::type_name),
+                Vec::new()),
+        };
+        let mut params = format!("({})", params.join(", "));
+        if flags.is_varargs() {
+            if let Some(index) = params.rfind("[]") {
+                params.replace_range(index..index + 2, "...");
+            }
+        }
+        match name {
+            "<clinit>" => declaration.push_str("{}"),
+            "<init>" => declaration.push_str(&format!("{}{}", java_name(class_file.name()?), params)),
+            _ => declaration.push_str(&format!("{} {}{}", return_type, name, params)),
+        }
+        // Generic exceptions from the signature, but only when there is an Exceptions attribute
+        if let Some(exceptions) = self.exceptions(method.attributes())? {
+            declaration.push_str(" throws ");
+            declaration.push_str(&if throws.is_empty() { exceptions } else { throws }.join(", "));
+        }
+        writeln!(self.out, "  {};", declaration)?;
+        if self.options.verbose {
+            writeln!(self.out, "    descriptor: {}", method.get_descriptor()?)?;
+            writeln!(self.out, "    flags: {}", format_flags(flags.bits(), flags.names(), flags.unknown_bits()))?;
+        }
+        for attribute in method.attributes() {
+            match *attribute {
+                AttributeInfo::Code(ref code) => self.code(code, flags, &descriptor)?,
+                _ if self.options.verbose => self.attribute(attribute, "    ")?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn code(&mut self, code: &CodeAttribute, flags: MethodFlags, descriptor: &MethodDescriptor) -> Result<()> {
+        let options = self.options;
+        // javap nests the code one level deeper with -v
+        let indent = if options.verbose { "      " } else { "    " };
+        if options.code() {
+            writeln!(self.out, "    Code:")?;
+            if options.verbose {
+                // javap counts parameters, not the slots they take
+                let args_size = descriptor.params().len() + if flags.is_static() { 0 } else { 1 };
+                writeln!(self.out, "      stack={}, locals={}, args_size={}", code.max_stack(), code.max_locals(), args_size)?;
+            }
+            for instruction in Instructions::new(code.code()) {
+                self.instruction(&instruction?, indent)?;
+            }
+            if !code.exception_table().is_empty() {
+                writeln!(self.out, "{}Exception table:", indent)?;
+                writeln!(self.out, "{}   from    to  target type", indent)?;
+                for exception in code.exception_table() {
+                    let catch_type = match code.catch_type_name(exception)? {
+                        Some(class_name) => format!("Class {}", class_name),
+                        None => "any".to_string(),
+                    };
+                    writeln!(self.out, "{}   {:>5} {:>5} {:>5}   {}", indent,
+                        exception.start_pc(), exception.end_pc(), exception.handler_pc(), catch_type)?;
+                }
+            }
+        }
+        for attribute in code.attributes() {
+            match *attribute {
+                AttributeInfo::LineNumberTable(ref table) if options.lines() => {
+                    writeln!(self.out, "{}LineNumberTable:", indent)?;
+                    for entry in table.line_number_table() {
+                        writeln!(self.out, "{}  line {}: {}", indent, entry.line_number(), entry.start_pc())?;
+                    }
+                },
+                AttributeInfo::LineNumberTable(_) => (),
+                // -l shows the local variables too
+                _ if options.verbose || (options.lines() && attribute.name() == "LocalVariableTable") =>
+                    self.attribute(attribute, indent)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn instruction(&mut self, instruction: &Instruction, indent: &str) -> Result<()> {
+        // javap names the wide forms like iinc_w
+        let mnemonic = if instruction.wide { format!("{}_w", instruction.mnemonic()) } else { instruction.mnemonic().to_string() };
+        let prefix = format!("{}{:>4}: {:<13} ", indent, instruction.pc, mnemonic);
+        let column = indent.len() + 18;
+        match instruction.operand {
+            Operand::None => writeln!(self.out, "{}", prefix.trim_end())?,
+            Operand::Int(value) => writeln!(self.out, "{}{}", prefix, value)?,
+            Operand::Local(index) => writeln!(self.out, "{}{}", prefix, index)?,
+            Operand::Branch(target) => writeln!(self.out, "{}{}", prefix, target)?,
+            Operand::Iinc { index, delta } => writeln!(self.out, "{}{}, {}", prefix, index, delta)?,
+            Operand::NewArray(atype) => writeln!(self.out, "{} {}", prefix, array_type_name(atype).unwrap_or("?"))?,
+            Operand::Constant(index) => {
+                // The two zero bytes after invokedynamic's index are printed like invokeinterface's count
+                let operand = if mnemonic == "invokedynamic" { format!("#{},  0", index) } else { format!("#{}", index) };
+                self.commented(indent.len(), &format!("{}{}", prefix, operand), &self.operand_comment(index)?)?
+            },
+            Operand::InvokeInterface { index, count } =>
+                self.commented(indent.len(), &format!("{}#{},  {}", prefix, index, count), &self.operand_comment(index)?)?,
+            Operand::MultiANewArray { index, dimensions } =>
+                self.commented(indent.len(), &format!("{}#{},  {}", prefix, index, dimensions), &self.operand_comment(index)?)?,
+            Operand::TableSwitch { default, low, high, ref targets } => {
+                writeln!(self.out, "{}{{ // {} to {}", prefix, low, high)?;
+                for (key, target) in (i64::from(low)..).zip(targets.iter()) {
+                    writeln!(self.out, "{:>column$}: {}", key, target, column = column)?;
+                }
+                writeln!(self.out, "{:>column$}: {}", "default", default, column = column)?;
+                writeln!(self.out, "{}      }}", indent)?;
+            },
+            Operand::LookupSwitch { default, ref pairs } => {
+                writeln!(self.out, "{}{{ // {}", prefix, pairs.len())?;
+                for &(key, target) in pairs.iter() {
+                    writeln!(self.out, "{:>column$}: {}", key, target, column = column)?;
+                }
+                writeln!(self.out, "{:>column$}: {}", "default", default, column = column)?;
+                writeln!(self.out, "{}      }}", indent)?;
+            },
+        }
+        Ok(())
+    }
+
+    // javap's tab stops: comments start at column 40 plus the indentation, or a space after the text
+    fn commented(&mut self, indent: usize, text: &str, comment: &str) -> Result<()> {
+        let padding = (40 + indent).saturating_sub(text.chars().count()).max(1);
+        let line = format!("{}{:padding$}// {}", text, "", comment, padding = padding);
+        writeln!(self.out, "{}", line.trim_end())?;
+        Ok(())
+    }
+
+    fn attribute(&mut self, attribute: &AttributeInfo, indent: &str) -> Result<()> {
+        match *attribute {
+            AttributeInfo::Source(ref source_file) =>
+                writeln!(self.out, "{}SourceFile: \"{}\"", indent, source_file.source_file()?)?,
+            AttributeInfo::Constant(ref constant_value) => {
+                let index = constant_value.constant_value_index();
+                let line = format!("{}ConstantValue: {}", indent, self.operand_comment(index)?);
+                writeln!(self.out, "{}", line.trim_end())?
+            },
+            AttributeInfo::BootstrapMethods(ref bootstrap_methods) => {
+                writeln!(self.out, "{}BootstrapMethods:", indent)?;
+                for (index, bootstrap_method) in bootstrap_methods.bootstrap_methods().iter().enumerate() {
+                    let method_ref = bootstrap_method.bootstrap_method_ref();
+                    let method_handle = self.constant(method_ref as usize)?;
+                    writeln!(self.out, "{}  {}: #{} {}", indent, index, method_ref, method_handle)?;
+                    writeln!(self.out, "{}    Method arguments:", indent)?;
+                    for &argument in bootstrap_method.bootstrap_arguments() {
+                        let comment = self.constant(argument as usize)?;
+                        let line = format!("{}      #{} {}", indent, argument, comment);
+                        writeln!(self.out, "{}", line.trim_end())?;
+                    }
+                }
+            },
+            AttributeInfo::LineNumberTable(ref table) => {
+                writeln!(self.out, "{}LineNumberTable:", indent)?;
+                for entry in table.line_number_table() {
+                    writeln!(self.out, "{}  line {}: {}", indent, entry.line_number(), entry.start_pc())?;
+                }
+            },
+            AttributeInfo::Code(_) => (),
+            AttributeInfo::Raw(ref raw) => self.raw_attribute(attribute.name(), raw.info(), indent)?,
+        }
+        Ok(())
+    }
+}
+
+// The modifiers among the flags in javap's order, each followed by a space
+fn modifiers(bits: u2, modifiers: &[(u2, &str)]) -> String {
+    modifiers.iter()
+        .filter(|&&(flag, _)| bits & flag != 0)
+        .map(|&(_, modifier)| format!("{} ", modifier))
+        .collect()
+}
+
+// javap leaves the class names of generic throws clauses as they are in the signature
+fn thrown_type_name(thrown: &TypeSignature) -> String {
+    match *thrown {
+        TypeSignature::Class(ref class) =>
+            class.parts().iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join("."),
+        _ => thrown.type_name(),
+    }
+}
+
+fn format_flags(bits: u2, names: Vec<&str>, unknown_bits: u2) -> String {
+    // javap lists bits it has no name for in hex after the names
+    let mut names = names.into_iter().map(str::to_string).collect::<Vec<_>>();
+    names.extend((0..16).map(|bit| 1 << bit).filter(|flag| unknown_bits & flag != 0).map(|flag| format!("0x{:x}", flag)));
+    let bits = format!("(0x{:04x})", bits);
+    if names.is_empty() {
+        bits
+    } else {
+        format!("{} {}", bits, names.join(", "))
+    }
+}
+
+// javap quotes names that aren't plain identifiers like "<init>", "[I" and "module-info",
+// package separators are fine
+fn quote(name: &str) -> String {
+    let mut previous = '/';
+    let plain = !name.is_empty() && name.chars().all(|c| {
+        let start = c.is_alphabetic() || c == '$' || c == '_';
+        let plain = if previous == '/' { start } else { c == '/' || start || c.is_numeric() };
+        previous = c;
+        plain
+    });
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", escape(name))
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '"' => escaped.push_str("\\\""),
+            '\'' => escaped.push_str("\\'"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_float(value: f32) -> String {
+    if value.is_nan() {
+        "NaNf".to_string()
+    } else if value.is_infinite() {
+        format!("{}Infinityf", if value < 0.0 { "-" } else { "" })
+    } else {
+        format!("{}f", java_decimal(f64::from(value.abs()), format!("{:?}", value), format!("{:e}", value)))
+    }
+}
+
+fn format_double(value: f64) -> String {
+    if value.is_nan() {
+        "NaNd".to_string()
+    } else if value.is_infinite() {
+        format!("{}Infinityd", if value < 0.0 { "-" } else { "" })
+    } else {
+        format!("{}d", java_decimal(value.abs(), format!("{:?}", value), format!("{:e}", value)))
+    }
+}
+
+// Like Java's toString, plain from 10^-3 up to 10^7 and 1.0E10 style outside of that
+fn java_decimal(magnitude: f64, plain: String, scientific: String) -> String {
+    if magnitude == 0.0 || (1e-3..1e7).contains(&magnitude) {
+        return plain;
+    }
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+    if mantissa.contains('.') {
+        format!("{}E{}", mantissa, &exponent[1..])
+    } else {
+        format!("{}.0E{}", mantissa, &exponent[1..])
+    }
+}
@@ -0,0 +1,92 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// Where the JDK keeps java.base and the rest of the class library
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClassLibrary {
+    // JDK 9+, jimage container
+    Modules(PathBuf),
+    // JDK 8 and older
+    RtJar(PathBuf),
+}
+
+#[derive(Clone, Debug)]
+pub struct Jdk {
+    home: PathBuf,
+    version: Option<String>,
+    class_library: ClassLibrary,
+}
+
+impl Jdk {
+    pub fn from_home<P: AsRef<Path>>(home: P) -> Option<Self> {
+        let home = home.as_ref().to_path_buf();
+        let modules = home.join("lib").join("modules");
+        let rt_jar = home.join("jre").join("lib").join("rt.jar");
+        let class_library = if modules.is_file() {
+            ClassLibrary::Modules(modules)
+        } else if rt_jar.is_file() {
+            ClassLibrary::RtJar(rt_jar)
+        } else if home.join("lib").join("rt.jar").is_file() {
+            // home points to the jre directory itself
+            ClassLibrary::RtJar(home.join("lib").join("rt.jar"))
+        } else {
+            return None;
+        };
+        let version = read_java_version(&home.join("release"));
+        Some(Jdk {
+            home,
+            version,
+            class_library,
+        })
+    }
+
+    // Uses JAVA_HOME, the same way the java launcher scripts do
+    pub fn from_env() -> Option<Self> {
+        env::var_os("JAVA_HOME").and_then(Jdk::from_home)
+    }
+
+    // JAVA_HOME first, then the JDK the `java` on PATH belongs to
+    pub fn locate() -> Option<Self> {
+        Jdk::from_env().or_else(|| {
+            env::split_paths(&env::var_os("PATH")?)
+                .map(|dir| dir.join("java"))
+                .find(|java| java.is_file())
+                .and_then(|java| fs::canonicalize(java).ok())
+                .and_then(|java| Jdk::from_home(java.parent()?.parent()?))
+        })
+    }
+
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    pub fn class_library(&self) -> &ClassLibrary {
+        &self.class_library
+    }
+
+    // JAVA_VERSION from the release file, e.g. "17.0.15" or "1.8.0_292"
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    // 8 for "1.8.0_292", 17 for "17.0.15"
+    pub fn feature_version(&self) -> Option<u32> {
+        let version = self.version()?;
+        let mut parts = version.split(&['.', '_', '-', '+'][..]);
+        match parts.next()?.parse().ok()? {
+            1 => parts.next()?.parse().ok(),
+            feature => Some(feature),
+        }
+    }
+}
+
+fn read_java_version(release: &Path) -> Option<String> {
+    let mut content = String::new();
+    File::open(release).ok()?.read_to_string(&mut content).ok()?;
+    content.lines()
+        .filter_map(|line| line.strip_prefix("JAVA_VERSION="))
+        .map(|version| version.trim_matches('"').to_string())
+        .next()
+}
@@ -0,0 +1,286 @@
+use class_file::attribute_info::{AttributeInfo, CodeAttribute};
+use class_file::bytecode::{self, Instruction, Operand};
+use class_file::class_file::{ClassFile, MemberInfo};
+use jasm::pool::{Constant, Entry, Pool, Ref};
+use json::value::{number, string, Json, Object};
+use json::SCHEMA;
+use result::Result;
+use types::{u1, u2, u4};
+
+pub fn class(class_file: &ClassFile) -> Result<Json> {
+    let exporter = Exporter { pool: Pool::from_constant_pool(class_file.constant_pool()) };
+    let constant_pool = exporter.pool.entries()
+        .map(|(index, entry)| Object::new()
+            .with("index", number(index))
+            .extend(exporter.constant(entry))
+            .into())
+        .collect();
+    let flags = class_file.access_flags();
+    Ok(Object::new()
+        .with("schema", number(SCHEMA))
+        .with("minor_version", number(class_file.minor_version()))
+        .with("major_version", number(class_file.major_version()))
+        .with("constant_pool", Json::Array(constant_pool))
+        .with("access_flags", number(flags.bits()))
+        .with("flags", flag_names(flags.names()))
+        .with("this_class", exporter.class(class_file.this_class()))
+        .with("super_class", exporter.class(class_file.super_class()))
+        .with("interfaces", Json::Array(class_file.interfaces().iter()
+            .map(|&interface| exporter.class(interface))
+            .collect()))
+        .with("fields", Json::Array(class_file.fields().iter()
+            .map(|field| exporter.member(field, field.field_flags().names()))
+            .collect::<Result<_>>()?))
+        .with("methods", Json::Array(class_file.methods().iter()
+            .map(|method| exporter.member(method, method.method_flags().names()))
+            .collect::<Result<_>>()?))
+        .with("attributes", exporter.attributes(class_file.attributes())?)
+        .into())
+}
+
+struct Exporter {
+    pool: Pool,
+}
+
+impl Exporter {
+    // Tag, the fields the import reads and then the resolved strings
+    fn constant(&self, entry: &Entry) -> Object {
+        let object = Object::new().with("tag", string(tag_name(entry)));
+        match *entry {
+            Constant::Utf8(ref text) => object.with("value", string(text)),
+            Constant::Utf8Bytes(ref bytes) => object.with("bytes", hex(bytes)),
+            Constant::Integer(value) => object.with("value", number(value)),
+            Constant::Float(bits) =>
+                object.with("value", float(format!("{:?}", f32::from_bits(bits)), format!("0x{:08x}", bits))),
+            Constant::Long(value) => object.with("value", number(value)),
+            Constant::Double(bits) =>
+                object.with("value", float(format!("{:?}", f64::from_bits(bits)), format!("0x{:016x}", bits))),
+            Constant::Class(name) => object
+                .with("name_index", number(name))
+                .with_option("name", self.utf8(name)),
+            Constant::String(string) => object
+                .with("string_index", number(string))
+                .with_option("value", self.utf8(string)),
+            Constant::Fieldref(class, name_and_type) | Constant::Methodref(class, name_and_type) |
+            Constant::InterfaceMethodref(class, name_and_type) => object
+                .with("class_index", number(class))
+                .with("name_and_type_index", number(name_and_type))
+                .extend(self.member_ref(class, name_and_type)),
+            Constant::NameAndType(name, descriptor) => object
+                .with("name_index", number(name))
+                .with("descriptor_index", number(descriptor))
+                .with_option("name", self.utf8(name))
+                .with_option("descriptor", self.utf8(descriptor)),
+            Constant::MethodHandle(kind, reference) => {
+                let object = object
+                    .with("reference_kind", string(kind.name()))
+                    .with("reference_index", number(reference));
+                match self.pool.get(reference) {
+                    Some(&Constant::Fieldref(class, name_and_type)) |
+                    Some(&Constant::Methodref(class, name_and_type)) |
+                    Some(&Constant::InterfaceMethodref(class, name_and_type)) =>
+                        object.extend(self.member_ref(class, name_and_type)),
+                    _ => object,
+                }
+            },
+            Constant::MethodType(descriptor) => object
+                .with("descriptor_index", number(descriptor))
+                .with_option("descriptor", self.utf8(descriptor)),
+            Constant::Dynamic(bootstrap, name_and_type) | Constant::InvokeDynamic(bootstrap, name_and_type) => object
+                .with("bootstrap_method_attr_index", number(bootstrap))
+                .with("name_and_type_index", number(name_and_type))
+                .extend(self.name_and_type(name_and_type)),
+            Constant::Module(name) | Constant::Package(name) => object
+                .with("name_index", number(name))
+                .with_option("name", self.utf8(name)),
+        }
+    }
+
+    fn utf8(&self, index: u2) -> Option<Json> {
+        match self.pool.get(index) {
+            Some(Constant::Utf8(text)) => Some(string(text)),
+            _ => None,
+        }
+    }
+
+    fn class_name(&self, index: u2) -> Option<Json> {
+        match self.pool.get(index) {
+            Some(&Constant::Class(name)) => self.utf8(name),
+            _ => None,
+        }
+    }
+
+    fn name_and_type(&self, index: u2) -> Object {
+        match self.pool.get(index) {
+            Some(&Constant::NameAndType(name, descriptor)) => Object::new()
+                .with_option("name", self.utf8(name))
+                .with_option("descriptor", self.utf8(descriptor)),
+            _ => Object::new(),
+        }
+    }
+
+    fn member_ref(&self, class: u2, name_and_type: u2) -> Object {
+        Object::new()
+            .with_option("class", self.class_name(class))
+            .extend(self.name_and_type(name_and_type))
+    }
+
+    fn class(&self, index: u2) -> Json {
+        Object::new()
+            .with("index", number(index))
+            .with_option("name", self.class_name(index))
+            .into()
+    }
+
+    fn member(&self, member: &MemberInfo, flags: Vec<&str>) -> Result<Json> {
+        Ok(Object::new()
+            .with("access_flags", number(member.access_flags().bits()))
+            .with("flags", flag_names(flags))
+            .with("name_index", number(member.name_index()))
+            .with_option("name", self.utf8(member.name_index()))
+            .with("descriptor_index", number(member.descriptor_index()))
+            .with_option("descriptor", self.utf8(member.descriptor_index()))
+            .with("attributes", self.attributes(member.attributes())?)
+            .into())
+    }
+
+    fn attributes(&self, attributes: &[AttributeInfo]) -> Result<Json> {
+        let mut array = Vec::with_capacity(attributes.len());
+        for attribute in attributes {
+            let object = Object::new()
+                // The first Utf8 with the name, the parsed attribute doesn't keep its index
+                .with_option("attribute_name_index", self.pool.lookup(&Ref::utf8(attribute.name())).map(number))
+                .with("name", string(attribute.name()));
+            array.push(match *attribute {
+                AttributeInfo::Raw(ref raw) => object
+                    .with("info", hex(raw.info())),
+                AttributeInfo::Constant(ref constant_value) => object
+                    .with("constantvalue_index", number(constant_value.constant_value_index())),
+                AttributeInfo::Source(ref source_file) => object
+                    .with("sourcefile_index", number(source_file.sourcefile_index()))
+                    .with_option("sourcefile", self.utf8(source_file.sourcefile_index())),
+                AttributeInfo::Code(ref code) => object.extend(self.code(code)?),
+                AttributeInfo::LineNumberTable(ref table) => object
+                    .with("line_number_table", Json::Array(table.line_number_table().iter()
+                        .map(|entry| Object::new()
+                            .with("start_pc", number(entry.start_pc()))
+                            .with("line_number", number(entry.line_number()))
+                            .into())
+                        .collect())),
+                AttributeInfo::BootstrapMethods(ref bootstrap_methods) => object
+                    .with("bootstrap_methods", Json::Array(bootstrap_methods.bootstrap_methods().iter()
+                        .map(|bootstrap_method| Object::new()
+                            .with("bootstrap_method_ref", number(bootstrap_method.bootstrap_method_ref()))
+                            .with("bootstrap_arguments", Json::Array(bootstrap_method.bootstrap_arguments().iter()
+                                .map(|&argument| number(argument))
+                                .collect()))
+                            .into())
+                        .collect())),
+            }.into());
+        }
+        Ok(Json::Array(array))
+    }
+
+    fn code(&self, code: &CodeAttribute) -> Result<Object> {
+        let instructions = bytecode::decode(code.code())?;
+        Ok(Object::new()
+            .with("max_stack", number(code.max_stack()))
+            .with("max_locals", number(code.max_locals()))
+            .with("code", Json::Array(instructions.iter()
+                .map(|instruction| self.instruction(instruction))
+                .collect()))
+            .with("exception_table", Json::Array(code.exception_table().iter()
+                .map(|exception| Object::new()
+                    .with("start_pc", number(exception.start_pc()))
+                    .with("end_pc", number(exception.end_pc()))
+                    .with("handler_pc", number(exception.handler_pc()))
+                    .with("catch_type", self.class(exception.catch_type()))
+                    .into())
+                .collect()))
+            .with("attributes", self.attributes(code.attributes())?))
+    }
+
+    fn instruction(&self, instruction: &Instruction) -> Json {
+        let mut object = Object::new()
+            .with("pc", number(instruction.pc))
+            .with("opcode", string(instruction.mnemonic()));
+        if instruction.wide {
+            object = object.with("wide", Json::Bool(true));
+        }
+        let constant = |index: u2| self.pool.get(index).map(|entry| self.constant(entry).into());
+        match instruction.operand {
+            Operand::None => object,
+            Operand::Int(value) => object.with("value", number(value)),
+            Operand::Local(index) => object.with("index", number(index)),
+            Operand::Constant(index) => object
+                .with("index", number(index))
+                .with_option("constant", constant(index)),
+            Operand::Branch(target) => object.with("target", number(target)),
+            Operand::Iinc { index, delta } => object
+                .with("index", number(index))
+                .with("delta", number(delta)),
+            Operand::InvokeInterface { index, count } => object
+                .with("index", number(index))
+                .with_option("constant", constant(index))
+                .with("count", number(count)),
+            Operand::MultiANewArray { index, dimensions } => object
+                .with("index", number(index))
+                .with_option("constant", constant(index))
+                .with("dimensions", number(dimensions)),
+            Operand::NewArray(atype) => object.with("atype", match bytecode::array_type_name(atype) {
+                Some(name) => string(name),
+                None => number(atype),
+            }),
+            Operand::TableSwitch { default, low, high, ref targets } => object
+                .with("default", number(default))
+                .with("low", number(low))
+                .with("high", number(high))
+                .with("targets", Json::Array(targets.iter().map(|&target| number(target)).collect())),
+            Operand::LookupSwitch { default, ref pairs } => object
+                .with("default", number(default))
+                .with("pairs", Json::Array(pairs.iter()
+                    .map(|&(key, target)| Json::Array(vec![number(key), number(target as u4)]))
+                    .collect())),
+        }.into()
+    }
+}
+
+fn tag_name(entry: &Entry) -> &'static str {
+    match *entry {
+        Constant::Utf8(_) | Constant::Utf8Bytes(_) => "Utf8",
+        Constant::Integer(_) => "Integer",
+        Constant::Float(_) => "Float",
+        Constant::Long(_) => "Long",
+        Constant::Double(_) => "Double",
+        Constant::Class(_) => "Class",
+        Constant::String(_) => "String",
+        Constant::Fieldref(..) => "Fieldref",
+        Constant::Methodref(..) => "Methodref",
+        Constant::InterfaceMethodref(..) => "InterfaceMethodref",
+        Constant::NameAndType(..) => "NameAndType",
+        Constant::MethodHandle(..) => "MethodHandle",
+        Constant::MethodType(_) => "MethodType",
+        Constant::Dynamic(..) => "Dynamic",
+        Constant::InvokeDynamic(..) => "InvokeDynamic",
+        Constant::Module(_) => "Module",
+        Constant::Package(_) => "Package",
+    }
+}
+
+// JSON has no NaN or infinities, NaNs keep their payload as bits
+fn hex(bytes: &[u1]) -> Json {
+    Json::String(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn float(text: String, bits: String) -> Json {
+    match text.as_str() {
+        "NaN" => Json::String(bits),
+        "inf" => string("Infinity"),
+        "-inf" => string("-Infinity"),
+        _ => Json::Number(text),
+    }
+}
+
+fn flag_names(names: Vec<&str>) -> Json {
+    Json::Array(names.into_iter().map(string).collect())
+}
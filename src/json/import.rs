@@ -0,0 +1,314 @@
+use std::convert::TryFrom;
+
+use class_file::bytecode::{self, Instruction, Operand, OperandKind};
+use class_file::constant_pool::RefKind;
+use jasm::pool::{push_u2, push_u4, Constant, Entry, Pool};
+use json::value::Json;
+use json::SCHEMA;
+use result::{Result, Error};
+use types::{u1, u2, u4};
+
+const MAGIC: u4 = 0xCAFE_BABE;
+
+pub fn class(json: &Json) -> Result<Vec<u1>> {
+    let root = Node { json, path: "$".to_string() };
+    let schema: u4 = root.integer("schema")?;
+    if u64::from(schema) != SCHEMA {
+        return root.field("schema")?.error(format!("schema {} is not supported", schema));
+    }
+    let mut pool = Pool::new();
+    let constant_pool = root.field("constant_pool")?;
+    for node in constant_pool.items()? {
+        let index = node.integer("index")?;
+        let entry = constant(&node)?;
+        pool.reserve(index, entry.is_wide())
+            .and_then(|()| pool.define(index, entry))
+            .or_else(|message| node.error(message))?;
+    }
+
+    let mut out = Vec::new();
+    push_u4(&mut out, MAGIC);
+    push_u2(&mut out, root.integer("minor_version")?);
+    push_u2(&mut out, root.integer("major_version")?);
+    pool.write(&mut out).or_else(|message| constant_pool.error(message))?;
+    push_u2(&mut out, root.integer("access_flags")?);
+    push_u2(&mut out, root.field("this_class")?.integer("index")?);
+    push_u2(&mut out, root.field("super_class")?.integer("index")?);
+    let interfaces = root.field("interfaces")?.items()?;
+    push_u2(&mut out, count(&root.field("interfaces")?, interfaces.len())?);
+    for interface in interfaces {
+        push_u2(&mut out, interface.integer("index")?);
+    }
+    members(&root.field("fields")?, &mut out)?;
+    members(&root.field("methods")?, &mut out)?;
+    attributes(&root.field("attributes")?, &mut out)?;
+    Ok(out)
+}
+
+// A value and where it is in the document, for the errors
+struct Node<'a> {
+    json: &'a Json,
+    path: String,
+}
+
+impl<'a> Node<'a> {
+    fn error<T>(&self, message: String) -> Result<T> {
+        Err(Error::Json(self.path.clone(), message))
+    }
+
+    fn optional(&self, key: &str) -> Option<Node<'a>> {
+        self.json.get(key).map(|json| Node { json, path: format!("{}.{}", self.path, key) })
+    }
+
+    fn field(&self, key: &str) -> Result<Node<'a>> {
+        match self.optional(key) {
+            Some(node) => Ok(node),
+            None => self.error(format!("missing {}", key)),
+        }
+    }
+
+    fn items(&self) -> Result<Vec<Node<'a>>> {
+        match *self.json {
+            Json::Array(ref items) => Ok(items.iter()
+                .enumerate()
+                .map(|(i, json)| Node { json, path: format!("{}[{}]", self.path, i) })
+                .collect()),
+            _ => self.error("expected an array".to_string()),
+        }
+    }
+
+    fn text(&self) -> Result<&'a str> {
+        match *self.json {
+            Json::String(ref text) => Ok(text),
+            _ => self.error("expected a string".to_string()),
+        }
+    }
+
+    fn as_integer<T: TryFrom<i64>>(&self) -> Result<T> {
+        let value = match *self.json {
+            Json::Number(ref text) => text.parse::<i64>().ok(),
+            _ => None,
+        };
+        match value {
+            Some(value) => T::try_from(value).or_else(|_| self.error(format!("{} is out of range", value))),
+            None => self.error("expected an integer".to_string()),
+        }
+    }
+
+    fn integer<T: TryFrom<i64>>(&self, key: &str) -> Result<T> {
+        self.field(key)?.as_integer()
+    }
+}
+
+// Length of an array as the u2 count in front of it
+fn count(node: &Node, len: usize) -> Result<u2> {
+    u2::try_from(len).or_else(|_| node.error(format!("{} items are too many", len)))
+}
+
+fn constant(node: &Node) -> Result<Entry> {
+    let tag = node.field("tag")?;
+    Ok(match tag.text()? {
+        "Utf8" => match node.optional("bytes") {
+            Some(bytes) => Constant::Utf8Bytes(hex(&bytes)?),
+            None => Constant::Utf8(node.field("value")?.text()?.to_string()),
+        },
+        "Integer" => Constant::Integer(node.integer("value")?),
+        "Float" => Constant::Float(float(&node.field("value")?, |text| text.parse::<f32>().ok().map(f32::to_bits),
+            |bits| u4::from_str_radix(bits, 16).ok())?),
+        "Long" => Constant::Long(node.integer("value")?),
+        "Double" => Constant::Double(float(&node.field("value")?, |text| text.parse::<f64>().ok().map(f64::to_bits),
+            |bits| u64::from_str_radix(bits, 16).ok())?),
+        "Class" => Constant::Class(node.integer("name_index")?),
+        "String" => Constant::String(node.integer("string_index")?),
+        "Fieldref" => Constant::Fieldref(node.integer("class_index")?, node.integer("name_and_type_index")?),
+        "Methodref" => Constant::Methodref(node.integer("class_index")?, node.integer("name_and_type_index")?),
+        "InterfaceMethodref" =>
+            Constant::InterfaceMethodref(node.integer("class_index")?, node.integer("name_and_type_index")?),
+        "NameAndType" => Constant::NameAndType(node.integer("name_index")?, node.integer("descriptor_index")?),
+        "MethodHandle" => {
+            let kind = node.field("reference_kind")?;
+            match RefKind::from_name(kind.text()?) {
+                Some(kind) => Constant::MethodHandle(kind, node.integer("reference_index")?),
+                None => return kind.error(format!("unknown reference kind {}", kind.text()?)),
+            }
+        },
+        "MethodType" => Constant::MethodType(node.integer("descriptor_index")?),
+        "Dynamic" =>
+            Constant::Dynamic(node.integer("bootstrap_method_attr_index")?, node.integer("name_and_type_index")?),
+        "InvokeDynamic" =>
+            Constant::InvokeDynamic(node.integer("bootstrap_method_attr_index")?, node.integer("name_and_type_index")?),
+        "Module" => Constant::Module(node.integer("name_index")?),
+        "Package" => Constant::Package(node.integer("name_index")?),
+        other => return tag.error(format!("unknown tag {}", other)),
+    })
+}
+
+// Bits of a Float or Double value: a number, "Infinity", "-Infinity" or the bits of a NaN in hex
+fn float<T, P, H>(node: &Node, parse: P, hex: H) -> Result<T>
+where P: Fn(&str) -> Option<T>, H: Fn(&str) -> Option<T> {
+    let bits = match *node.json {
+        Json::Number(ref text) => parse(text),
+        Json::String(ref text) => match text.as_str() {
+            "Infinity" => parse("inf"),
+            "-Infinity" => parse("-inf"),
+            text if text.starts_with("0x") => hex(&text[2..]),
+            _ => None,
+        },
+        _ => None,
+    };
+    match bits {
+        Some(bits) => Ok(bits),
+        None => node.error("expected a number, Infinity, -Infinity or 0x bits".to_string()),
+    }
+}
+
+fn hex(node: &Node) -> Result<Vec<u1>> {
+    let text = node.text()?;
+    if text.len() % 2 != 0 || !text.chars().all(|c| c.is_ascii_hexdigit()) {
+        return node.error("expected an even number of hex digits".to_string());
+    }
+    Ok((0..text.len()).step_by(2).map(|i| u1::from_str_radix(&text[i..i + 2], 16).unwrap()).collect())
+}
+
+fn members(node: &Node, out: &mut Vec<u1>) -> Result<()> {
+    let members = node.items()?;
+    push_u2(out, count(node, members.len())?);
+    for member in members {
+        push_u2(out, member.integer("access_flags")?);
+        push_u2(out, member.integer("name_index")?);
+        push_u2(out, member.integer("descriptor_index")?);
+        attributes(&member.field("attributes")?, out)?;
+    }
+    Ok(())
+}
+
+// The kind is told by the fields, not by the name
+fn attributes(node: &Node, out: &mut Vec<u1>) -> Result<()> {
+    let attributes = node.items()?;
+    push_u2(out, count(node, attributes.len())?);
+    for attribute in attributes {
+        push_u2(out, attribute.integer("attribute_name_index")?);
+        let mut info = Vec::new();
+        if let Some(bytes) = attribute.optional("info") {
+            info = hex(&bytes)?;
+        } else if attribute.optional("code").is_some() {
+            code(&attribute, &mut info)?;
+        } else if let Some(index) = attribute.optional("constantvalue_index") {
+            push_u2(&mut info, index.as_integer()?);
+        } else if let Some(index) = attribute.optional("sourcefile_index") {
+            push_u2(&mut info, index.as_integer()?);
+        } else if let Some(table) = attribute.optional("line_number_table") {
+            let entries = table.items()?;
+            push_u2(&mut info, count(&table, entries.len())?);
+            for entry in entries {
+                push_u2(&mut info, entry.integer("start_pc")?);
+                push_u2(&mut info, entry.integer("line_number")?);
+            }
+        } else if let Some(bootstrap_methods) = attribute.optional("bootstrap_methods") {
+            let methods = bootstrap_methods.items()?;
+            push_u2(&mut info, count(&bootstrap_methods, methods.len())?);
+            for method in methods {
+                push_u2(&mut info, method.integer("bootstrap_method_ref")?);
+                let arguments_node = method.field("bootstrap_arguments")?;
+                let arguments = arguments_node.items()?;
+                push_u2(&mut info, count(&arguments_node, arguments.len())?);
+                for argument in arguments {
+                    push_u2(&mut info, argument.as_integer()?);
+                }
+            }
+        } else {
+            return attribute.error("expected info or the fields of a known attribute".to_string());
+        }
+        push_u4(out, u4::try_from(info.len()).or_else(|_| attribute.error("attribute is too long".to_string()))?);
+        out.extend_from_slice(&info);
+    }
+    Ok(())
+}
+
+fn code(node: &Node, out: &mut Vec<u1>) -> Result<()> {
+    push_u2(out, node.integer("max_stack")?);
+    push_u2(out, node.integer("max_locals")?);
+    let mut code = Vec::new();
+    for item in node.field("code")?.items()? {
+        let instruction = instruction(&item, code.len() as u4)?;
+        bytecode::encode(&instruction, &mut code).or_else(|err| item.error(match err {
+            Error::OutOfBounds(_) => format!("operand of {} is out of range", instruction.mnemonic()),
+            _ => format!("invalid operand for {}", instruction.mnemonic()),
+        }))?;
+    }
+    push_u4(out, u4::try_from(code.len()).or_else(|_| node.error("code is too long".to_string()))?);
+    out.extend_from_slice(&code);
+    let exception_table = node.field("exception_table")?;
+    let exceptions = exception_table.items()?;
+    push_u2(out, count(&exception_table, exceptions.len())?);
+    for exception in exceptions {
+        push_u2(out, exception.integer("start_pc")?);
+        push_u2(out, exception.integer("end_pc")?);
+        push_u2(out, exception.integer("handler_pc")?);
+        push_u2(out, exception.field("catch_type")?.integer("index")?);
+    }
+    attributes(&node.field("attributes")?, out)
+}
+
+// `pc` is where the instruction goes, a pc in the document has to agree with it
+fn instruction(node: &Node, pc: u4) -> Result<Instruction> {
+    if let Some(expected) = node.optional("pc") {
+        let expected: u4 = expected.as_integer()?;
+        if expected != pc {
+            return node.error(format!("pc is {} but the instruction is at {}", expected, pc));
+        }
+    }
+    let mnemonic = node.field("opcode")?;
+    let opcode = match bytecode::opcode(mnemonic.text()?) {
+        Some(opcode) => opcode,
+        None => return mnemonic.error(format!("unknown instruction {}", mnemonic.text()?)),
+    };
+    let wide = match node.optional("wide").map(|wide| wide.json) {
+        None | Some(&Json::Bool(false)) => false,
+        Some(&Json::Bool(true)) => true,
+        Some(_) => return node.field("wide")?.error("expected a boolean".to_string()),
+    };
+    let operand = match bytecode::operand_kind(opcode) {
+        OperandKind::None => Operand::None,
+        OperandKind::Byte | OperandKind::Short => Operand::Int(node.integer("value")?),
+        OperandKind::Local => Operand::Local(node.integer("index")?),
+        OperandKind::Constant1 | OperandKind::Constant | OperandKind::Class | OperandKind::ConstantDynamic =>
+            Operand::Constant(node.integer("index")?),
+        OperandKind::Branch | OperandKind::BranchWide => Operand::Branch(node.integer("target")?),
+        OperandKind::Iinc => Operand::Iinc { index: node.integer("index")?, delta: node.integer("delta")? },
+        OperandKind::InvokeInterface =>
+            Operand::InvokeInterface { index: node.integer("index")?, count: node.integer("count")? },
+        OperandKind::MultiANewArray =>
+            Operand::MultiANewArray { index: node.integer("index")?, dimensions: node.integer("dimensions")? },
+        OperandKind::NewArray => {
+            let atype = node.field("atype")?;
+            Operand::NewArray(match *atype.json {
+                Json::String(ref name) => match bytecode::array_type(name) {
+                    Some(atype) => atype,
+                    None => return atype.error(format!("unknown array type {}", name)),
+                },
+                _ => atype.as_integer()?,
+            })
+        },
+        OperandKind::TableSwitch => Operand::TableSwitch {
+            default: node.integer("default")?,
+            low: node.integer("low")?,
+            high: node.integer("high")?,
+            targets: node.field("targets")?.items()?.iter()
+                .map(|target| target.as_integer())
+                .collect::<Result<Vec<_>>>()?
+                .into_boxed_slice(),
+        },
+        OperandKind::LookupSwitch => {
+            let mut pairs = Vec::new();
+            for pair in node.field("pairs")?.items()? {
+                match pair.items()?.as_slice() {
+                    [key, target] => pairs.push((key.as_integer()?, target.as_integer()?)),
+                    _ => return pair.error("expected [key, target]".to_string()),
+                }
+            }
+            Operand::LookupSwitch { default: node.integer("default")?, pairs: pairs.into_boxed_slice() }
+        },
+    };
+    Ok(Instruction { pc, opcode, wide, operand })
+}
@@ -0,0 +1,73 @@
+/*
+* JSON form of a class file, for diffing and for tools that don't read class files. The layout
+* follows the JVMS structures, schema version 1:
+*
+*   {
+*     "schema": 1,
+*     "minor_version": 0, "major_version": 52,
+*     "constant_pool": [constant...],
+*     "access_flags": 33, "flags": ["ACC_PUBLIC", "ACC_SUPER"],
+*     "this_class": class, "super_class": class, "interfaces": [class...],
+*     "fields": [member...], "methods": [member...], "attributes": [attribute...]
+*   }
+*
+*   class       {"index": 2, "name": "Foo"}, super_class is {"index": 0} for java/lang/Object
+*   constant    {"index": 1, "tag": "Utf8", "value": "Foo"}, one per entry in index order. The other
+*               fields are named after the JVMS: Class has name_index, Fieldref has class_index and
+*               name_and_type_index and so on, MethodHandle has reference_kind ("REF_invokeStatic")
+*               and reference_index. Integer and Long have value, Float and Double have value as a
+*               number, "Infinity", "-Infinity" or the bits of a NaN as "0x7fc00000".
+*   member      {"access_flags": 9, "flags": [...], "name_index": 5, "name": "main",
+*                "descriptor_index": 6, "descriptor": "([Ljava/lang/String;)V", "attributes": [...]}
+*   attribute   {"attribute_name_index": 7, "name": "Code", ...} and then by kind
+*                 ConstantValue     constantvalue_index
+*                 SourceFile        sourcefile_index, sourcefile
+*                 Code              max_stack, max_locals, code: [instruction...],
+*                                   exception_table: [{start_pc, end_pc, handler_pc, catch_type: class}],
+*                                   attributes
+*                 LineNumberTable   line_number_table: [{start_pc, line_number}]
+*                 BootstrapMethods  bootstrap_methods: [{bootstrap_method_ref, bootstrap_arguments: [...]}]
+*                 anything else     info, the bytes as hex
+*   instruction {"pc": 3, "opcode": "invokevirtual", "index": 4, "constant": {...}}, "wide": true
+*               for a wide prefix and the operand by kind: value (bipush, sipush), index (locals and
+*               constants), target (branches, absolute pc), index and delta (iinc), index and count
+*               (invokeinterface), index and dimensions (multianewarray), atype ("int", newarray),
+*               default, low, high and targets (tableswitch), default and pairs: [[key, target]...]
+*               (lookupswitch)
+*
+* Strings that only repeat what an index refers to (name, descriptor, class, flags, constant, the
+* value of a String...) are there for readers, the import reads indexes and numbers only. So a
+* rename is an edit of the Utf8 constant, and since pcs and branch targets are absolute, inserting
+* code is easier in the jasm form. A Utf8 constant that isn't text, like one with an unpaired
+* surrogate, has its bytes as hex instead of value: {"index": 3, "tag": "Utf8", "bytes": "eda080"}.
+*/
+use std::io::Write;
+
+use class_file::class_file::ClassFile;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use result::Result;
+use types::u1;
+
+mod export;
+mod import;
+mod value;
+
+pub const SCHEMA: u64 = 1;
+
+pub fn export<W: Write>(out: &mut W, class_file: &ClassFile) -> Result<()> {
+    let json = export::class(class_file)?;
+    writeln!(out, "{}", json)?;
+    Ok(())
+}
+
+// Bytes of the .class file
+pub fn import(text: &str) -> Result<Vec<u1>> {
+    let json = value::parse(text)?;
+    import::class(&json)
+}
+
+pub fn import_class(text: &str) -> Result<ClassFile> {
+    let bytes = import(text)?;
+    ClassFile::from_bytes(&PoSlice::new(&bytes))
+}
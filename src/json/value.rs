@@ -0,0 +1,310 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use result::{Result, Error};
+
+// Numbers keep their text so that Long values survive without going through f64, objects keep
+// their key order so that the output is stable
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+pub fn number<T: fmt::Display>(value: T) -> Json {
+    Json::Number(value.to_string())
+}
+
+pub fn string(text: &str) -> Json {
+    Json::String(text.to_string())
+}
+
+pub struct Object(Vec<(String, Json)>);
+
+impl Object {
+    pub fn new() -> Self {
+        Object(Vec::new())
+    }
+
+    pub fn with(mut self, key: &str, value: Json) -> Self {
+        self.0.push((key.to_string(), value));
+        self
+    }
+
+    // Left out when None, for the strings that can't be resolved in a broken pool
+    pub fn with_option(self, key: &str, value: Option<Json>) -> Self {
+        match value {
+            Some(value) => self.with(key, value),
+            None => self,
+        }
+    }
+
+    // Keys of `other` after the ones already there
+    pub fn extend(mut self, other: Object) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+impl From<Object> for Json {
+    fn from(object: Object) -> Self {
+        Json::Object(object.0)
+    }
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match *self {
+            Json::Object(ref fields) => fields.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    // Printed on one line: scalars, arrays of scalars and objects of those
+    fn is_flat(&self) -> bool {
+        match *self {
+            Json::Array(ref items) => items.iter().all(|item| !matches!(*item, Json::Array(_) | Json::Object(_))),
+            Json::Object(ref fields) => fields.iter().all(|(_, value)| value.is_flat()),
+            _ => true,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        let flat = self.is_flat();
+        let (separator, inner, close) = if flat {
+            (", ".to_string(), String::new(), String::new())
+        } else {
+            (",\n".to_string(), " ".repeat(indent + 2), format!("\n{}", " ".repeat(indent)))
+        };
+        match *self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(ref text) => f.write_str(text),
+            Json::String(ref text) => write_string(f, text),
+            Json::Array(ref items) if items.is_empty() => f.write_str("[]"),
+            Json::Array(ref items) => {
+                f.write_str(if flat { "[" } else { "[\n" })?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(&separator)?;
+                    }
+                    f.write_str(&inner)?;
+                    item.write(f, indent + 2)?;
+                }
+                write!(f, "{}]", close)
+            },
+            Json::Object(ref fields) if fields.is_empty() => f.write_str("{}"),
+            Json::Object(ref fields) => {
+                f.write_str(if flat { "{" } else { "{\n" })?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(&separator)?;
+                    }
+                    f.write_str(&inner)?;
+                    write_string(f, key)?;
+                    f.write_str(": ")?;
+                    value.write(f, indent + 2)?;
+                }
+                write!(f, "{}}}", close)
+            },
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+pub fn parse(text: &str) -> Result<Json> {
+    let mut parser = Parser { chars: text.chars().peekable(), line: 1 };
+    let json = parser.value().map_err(|message| Error::Syntax(parser.line, message))?;
+    parser.skip_whitespace();
+    match parser.chars.next() {
+        None => Ok(json),
+        Some(c) => Err(Error::Syntax(parser.line, format!("unexpected {:?} after the document", c))),
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                '\n' => self.line += 1,
+                ' ' | '\t' | '\r' => (),
+                _ => break,
+            }
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> ::std::result::Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected {:?}, found {:?}", expected, c)),
+            None => Err(format!("expected {:?}, found the end", expected)),
+        }
+    }
+
+    fn value(&mut self) -> ::std::result::Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.peek().cloned() {
+            Some('{') => {
+                self.chars.next();
+                let mut fields = Vec::new();
+                if !self.close('}') {
+                    loop {
+                        self.skip_whitespace();
+                        if self.chars.peek() != Some(&'"') {
+                            return Err("expected a key".to_string());
+                        }
+                        let key = self.string()?;
+                        self.expect(':')?;
+                        fields.push((key, self.value()?));
+                        if self.close('}') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Json::Object(fields))
+            },
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                if !self.close(']') {
+                    loop {
+                        items.push(self.value()?);
+                        if self.close(']') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Json::Array(items))
+            },
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut text = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if !(c.is_ascii_digit() || "+-.eE".contains(c)) {
+                        break;
+                    }
+                    text.push(c);
+                    self.chars.next();
+                }
+                match text.parse::<f64>() {
+                    Ok(_) if !text.starts_with('+') => Ok(Json::Number(text)),
+                    _ => Err(format!("invalid number {}", text)),
+                }
+            },
+            Some(c) if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if !c.is_ascii_alphabetic() {
+                        break;
+                    }
+                    word.push(c);
+                    self.chars.next();
+                }
+                match word.as_str() {
+                    "null" => Ok(Json::Null),
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    _ => Err(format!("unexpected {}", word)),
+                }
+            },
+            Some(c) => Err(format!("unexpected {:?}", c)),
+            None => Err("unexpected end of the document".to_string()),
+        }
+    }
+
+    // Consumes `close` if it is next
+    fn close(&mut self, close: char) -> bool {
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&close) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn string(&mut self) -> ::std::result::Result<String, String> {
+        self.chars.next();
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.chars.next() {
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    Some('/') => text.push('/'),
+                    Some('b') => text.push('\u{8}'),
+                    Some('f') => text.push('\u{c}'),
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some('u') => {
+                        let unit = self.unit()?;
+                        let c = if (0xD800..0xDC00).contains(&unit) {
+                            if self.chars.next() != Some('\\') || self.chars.next() != Some('u') {
+                                return Err("unpaired surrogate in \\u escape".to_string());
+                            }
+                            let low = self.unit()?;
+                            if !(0xDC00..0xE000).contains(&low) {
+                                return Err("unpaired surrogate in \\u escape".to_string());
+                            }
+                            0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+                        } else {
+                            unit
+                        };
+                        text.push(::std::char::from_u32(c).ok_or("unpaired surrogate in \\u escape")?);
+                    },
+                    c => return Err(format!("invalid escape {:?}", c)),
+                },
+                Some('\n') | None => return Err("unterminated string".to_string()),
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    // Four hex digits of a \u escape
+    fn unit(&mut self) -> ::std::result::Result<u32, String> {
+        let digits: String = self.chars.by_ref().take(4).collect();
+        match u32::from_str_radix(&digits, 16) {
+            Ok(unit) if digits.len() == 4 && digits.chars().all(|c| c.is_ascii_hexdigit()) => Ok(unit),
+            _ => Err(format!("invalid \\u escape {}", digits)),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+/*
+* The class file parser, its tools (javap, jasm, JSON) and the class path. The jvm_rs binary in
+* main.rs and the fuzz targets in fuzz/ are both built on this.
+*/
+#[macro_use]
+extern crate enum_primitive_derive;
+extern crate num_traits;
+extern crate byteorder;
+
+#[macro_use]
+pub mod trace;
+pub mod class_file;
+pub mod types;
+pub mod result;
+pub mod jdk;
+pub mod class_path;
+pub mod javap;
+pub mod jasm;
+pub mod json;
+pub mod bench;
+pub mod vm;
+mod tests;
@@ -1,16 +1,197 @@
-#[macro_use]
-extern crate enum_primitive_derive;
-extern crate num_traits;
-extern crate byteorder;
+extern crate jvm_rs;
 
-#[allow(dead_code)]
-mod class_file;
-mod types;
-mod tests;
-mod result;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process;
 
-#[allow(dead_code)]
+use jvm_rs::{bench, jasm, javap, json, trace};
+use jvm_rs::class_file::class_file::ClassFile;
+use jvm_rs::class_path::{ClassPath, Directory};
+use jvm_rs::class_path::jimage::JImage;
+use jvm_rs::jdk::{ClassLibrary, Jdk};
+use jvm_rs::result::Result;
+use jvm_rs::vm::Vm;
+
+const USAGE: &str = "Usage: jvm_rs javap [-c] [-v] [-p] [-l] <classfile>...
+  -c    disassemble the code
+  -v    print everything: version, flags, constant pool, attributes
+  -p    show private members too
+  -l    print line number tables
+       jvm_rs jasm <source> <classfile>
+       jvm_rs jdis <classfile>
+       jvm_rs json <classfile>
+       jvm_rs unjson <json> <classfile>
+       jvm_rs bench [<lib/modules>]
+       jvm_rs run [-jdk <java home>] [-cp <dir>[:<dir>...]] <class> [<arg>...]
+  -jdk  boot on the class library of a JDK 17 or later instead of the bundled runtime
+  -cp   directories to load classes from, the current directory by default
+JVM_RS_TRACE=reads,counts,pool,members,attributes or all traces parsing to stderr";
 
 fn main() {
-    unimplemented!();
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Ok(names) = env::var("JVM_RS_TRACE") {
+        for name in trace::enable_names(&names) {
+            eprintln!("unknown trace category: {}", name);
+        }
+    }
+    let code = match args.first().map(String::as_str) {
+        Some("javap") => javap(&args[1..]),
+        Some("jasm") if args.len() == 3 => report(&args[1], assemble_file(&args[1], &args[2])),
+        Some("jdis") if args.len() == 2 => {
+            let stdout = io::stdout();
+            report(&args[1], jdis_file(&mut stdout.lock(), &args[1]))
+        },
+        Some("json") if args.len() == 2 => {
+            let stdout = io::stdout();
+            report(&args[1], json_file(&mut stdout.lock(), &args[1]))
+        },
+        Some("unjson") if args.len() == 3 => report(&args[1], unjson_file(&args[1], &args[2])),
+        Some("bench") if args.len() <= 2 => bench(args.get(1)),
+        Some("run") => run(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        },
+    };
+    process::exit(code);
+}
+
+fn javap(args: &[String]) -> i32 {
+    let mut options = javap::Options::default();
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "-c" => options.code = true,
+            "-v" | "-verbose" => options.verbose = true,
+            "-p" | "-private" => options.private = true,
+            "-l" => options.lines = true,
+            flag if flag.starts_with('-') => {
+                eprintln!("unknown option: {}\n{}", flag, USAGE);
+                return 2;
+            },
+            path => paths.push(path),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{}", USAGE);
+        return 2;
+    }
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut code = 0;
+    for path in paths {
+        if let Err(err) = disassemble_file(&mut out, path, &options) {
+            eprintln!("error: {}: {}", path, err);
+            code = 1;
+        }
+    }
+    code
+}
+
+fn bench(path: Option<&String>) -> i32 {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => match Jdk::locate().map(|jdk| jdk.class_library().clone()) {
+            Some(ClassLibrary::Modules(path)) => path,
+            _ => {
+                eprintln!("error: no JDK 9+ found, pass the path of a lib/modules file");
+                return 1;
+            },
+        },
+    };
+    let stdout = io::stdout();
+    let result = JImage::open(&path).and_then(|image| bench::run(&mut stdout.lock(), &image));
+    report(&path.to_string_lossy(), result)
+}
+
+// Runs main on the interpreter with the mini runtime or a JDK's, the exit status is the program's
+fn run(args: &[String]) -> i32 {
+    let (jdk, args) = match args.first().map(String::as_str) {
+        Some("-jdk") if args.len() > 1 => match Jdk::from_home(&args[1]) {
+            Some(jdk) => (Some(jdk), &args[2..]),
+            None => {
+                eprintln!("error: {}: no lib/modules or rt.jar", args[1]);
+                return 1;
+            },
+        },
+        _ => (None, args),
+    };
+    let (directories, args) = match args.first().map(String::as_str) {
+        Some("-cp") | Some("-classpath") if args.len() > 1 => (args[1].as_str(), &args[2..]),
+        _ => (".", args),
+    };
+    let class_name = match args.first() {
+        Some(class_name) if !class_name.starts_with('-') => class_name.replace('.', "/"),
+        _ => {
+            eprintln!("{}", USAGE);
+            return 2;
+        },
+    };
+    let mut class_path = ClassPath::new();
+    for directory in directories.split(':') {
+        class_path.push(Directory::new(directory));
+    }
+    let mut vm = match jdk {
+        Some(jdk) => match Vm::boot_from(&jdk, class_path) {
+            Ok(vm) => vm,
+            Err(err) => {
+                eprintln!("error: {}: {}", jdk.home().display(), err);
+                return 1;
+            },
+        },
+        None => Vm::new(class_path),
+    };
+    match vm.run_main(&class_name, &args[1..]) {
+        Ok(status) => status,
+        Err(err) => {
+            eprintln!("error: {}: {}", args[0], err);
+            1
+        },
+    }
+}
+
+fn report(path: &str, result: Result<()>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("error: {}: {}", path, err);
+            1
+        },
+    }
+}
+
+fn assemble_file(source: &str, output: &str) -> Result<()> {
+    let mut text = String::new();
+    File::open(source)?.read_to_string(&mut text)?;
+    let bytes = jasm::assemble(&text)?;
+    File::create(output)?.write_all(&bytes)?;
+    Ok(())
+}
+
+fn jdis_file<W: Write>(out: &mut W, path: &str) -> Result<()> {
+    let class_file = ClassFile::read_from(BufReader::new(File::open(path)?))?;
+    jasm::disassemble(out, &class_file)
+}
+
+fn json_file<W: Write>(out: &mut W, path: &str) -> Result<()> {
+    let class_file = ClassFile::read_from(BufReader::new(File::open(path)?))?;
+    json::export(out, &class_file)
+}
+
+fn unjson_file(source: &str, output: &str) -> Result<()> {
+    let mut text = String::new();
+    File::open(source)?.read_to_string(&mut text)?;
+    let bytes = json::import(&text)?;
+    File::create(output)?.write_all(&bytes)?;
+    Ok(())
+}
+
+fn disassemble_file<W: Write>(out: &mut W, path: &str, options: &javap::Options) -> Result<()> {
+    let class_file = ClassFile::read_from(BufReader::new(File::open(path)?))?;
+    if options.verbose {
+        writeln!(out, "Classfile {}", path)?;
+    }
+    javap::disassemble(out, &class_file, options)
 }
@@ -1,19 +1,182 @@
-use std::result;
-
-use class_file::constant_pool::Tag;
-use types::{u1, u2};
-
-pub type Result<T> = result::Result<T, Error>; 
-
-#[derive(Debug)]
-pub enum Error {
-    CPTag(u1),
-    Index(usize),
-    WrongTag(Tag),
-    WrongKind(u1),
-    EmptyCPInfo(u2),
-    OutOfBounds(usize),
-    NotUtf8,    // TODO: better error
-    InvalidUtf8,
-    MainNotFound,
-}
\ No newline at end of file
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+use class_file::constant_pool::{RefKind, Tag};
+use types::{u1, u2, u4};
+
+pub type Result<T> = result::Result<T, Error>; 
+
+#[derive(Debug)]
+pub enum Error {
+    CPTag(u1),
+    // constant pool index past the end of the pool
+    Index(usize),
+    // constant pool index, expected tags, actual tag
+    WrongTag(usize, &'static [Tag], Tag),
+    WrongKind(u1),
+    InvalidMethodHandle(RefKind),
+    EmptyCPInfo(u2),
+    OutOfBounds(usize),
+    // a read past the end of the structure being decoded, up to this offset
+    PastEnd(usize),
+    // attribute_length, bytes the attribute decodes to, at least that many when it is more
+    AttributeLength(u4, usize),
+    InvalidUtf8,
+    InvalidDescriptor(String),
+    InvalidSignature(String),
+    InvalidOpcode(u1),
+    // bytecode the interpreter can't execute, it doesn't verify classes before running them
+    Verify(String),
+    ClassNotFound(String),
+    // class of an exception thrown while the VM sets up a program, before Java code can catch it
+    Uncaught(String),
+    MainNotFound,
+    // every thread waits for another one and none has a timeout, the JDK would hang
+    Deadlock,
+    Magic(u4),
+    Unsupported(&'static str),
+    // line, message
+    Syntax(usize, String),
+    // path in a JSON document, message
+    Json(String, String),
+    Io(io::Error),
+    // where in the class file one of the above happened
+    Context(Box<Context>),
+}
+
+#[derive(Debug)]
+pub struct Context {
+    offset: Option<usize>,
+    // innermost first, the order the decoders add them in
+    path: Vec<String>,
+    cause: Error,
+}
+
+impl Error {
+    // Adds the structure that was being decoded, only the innermost offset is kept
+    pub fn within<S: Into<String>>(self, offset: usize, segment: S) -> Self {
+        let mut context = self.into_context();
+        context.offset = context.offset.or(Some(offset));
+        context.path.push(segment.into());
+        Error::Context(context)
+    }
+
+    // Offset for errors raised outside of any structure, like in the class header
+    pub fn at(self, offset: usize) -> Self {
+        let mut context = self.into_context();
+        context.offset = context.offset.or(Some(offset));
+        Error::Context(context)
+    }
+
+    // Byte offset in the class file where decoding stopped
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            Error::Context(ref context) => context.offset,
+            _ => None,
+        }
+    }
+
+    // Like `method main([Ljava/lang/String;)V → Code → exception_table[2]`
+    pub fn path(&self) -> Option<String> {
+        let context = match *self {
+            Error::Context(ref context) if !context.path.is_empty() => context,
+            _ => return None,
+        };
+        let mut path = String::new();
+        for segment in context.path.iter().rev() {
+            if !path.is_empty() && !segment.starts_with('[') {
+                path.push_str(" → ");
+            }
+            path.push_str(segment);
+        }
+        Some(path)
+    }
+
+    // The error without its context
+    pub fn cause(&self) -> &Error {
+        match *self {
+            Error::Context(ref context) => &context.cause,
+            ref error => error,
+        }
+    }
+
+    fn into_context(self) -> Box<Context> {
+        match self {
+            Error::Context(context) => context,
+            cause => Box::new(Context { offset: None, path: Vec::new(), cause }),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::CPTag(tag) => write!(f, "unknown constant pool tag {}", tag),
+            Error::Index(index) => write!(f, "constant pool index #{} is out of range", index),
+            Error::WrongTag(index, expected, actual) => {
+                write!(f, "constant #{} is {}, expected ", index, actual.name())?;
+                for (i, tag) in expected.iter().enumerate() {
+                    match i {
+                        0 => (),
+                        _ if i + 1 == expected.len() => write!(f, " or ")?,
+                        _ => write!(f, ", ")?,
+                    }
+                    write!(f, "{}", tag.name())?;
+                }
+                Ok(())
+            },
+            Error::WrongKind(kind) => write!(f, "unknown method handle kind {}", kind),
+            Error::InvalidMethodHandle(kind) => write!(f, "invalid {} method handle", kind.name()),
+            Error::EmptyCPInfo(index) => write!(f, "constant #{} is an unusable slot", index),
+            Error::OutOfBounds(pos) => write!(f, "{} is out of bounds", pos),
+            Error::PastEnd(end) => write!(f, "reads up to {}, past the end of the structure", end),
+            Error::AttributeLength(length, decoded) if decoded < length as usize =>
+                write!(f, "attribute_length is {} but only {} bytes are decoded", length, decoded),
+            Error::AttributeLength(length, decoded) =>
+                write!(f, "attribute_length is {} but decoding needs at least {} bytes", length, decoded),
+            Error::InvalidUtf8 => write!(f, "invalid modified UTF-8"),
+            Error::InvalidDescriptor(ref descriptor) => write!(f, "invalid descriptor {:?}", descriptor),
+            Error::InvalidSignature(ref signature) => write!(f, "invalid signature {:?}", signature),
+            Error::InvalidOpcode(opcode) => write!(f, "invalid opcode 0x{:02x}", opcode),
+            Error::Verify(ref message) => write!(f, "verify error: {}", message),
+            Error::ClassNotFound(ref name) => write!(f, "class {} not found", name),
+            Error::Uncaught(ref name) => write!(f, "uncaught {}", name),
+            Error::MainNotFound => write!(f, "no main([Ljava/lang/String;)V method"),
+            Error::Deadlock => write!(f, "deadlock, every thread is blocked"),
+            Error::Magic(magic) => write!(f, "bad magic number 0x{:08x}", magic),
+            Error::Unsupported(what) => write!(f, "unsupported {}", what),
+            Error::Syntax(line, ref message) => write!(f, "line {}: {}", line, message),
+            Error::Json(ref path, ref message) => write!(f, "{}: {}", path, message),
+            Error::Io(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            Error::Io(ref err) => write!(f, "{}", err),
+            Error::Context(ref context) => {
+                match (context.offset, self.path()) {
+                    (Some(offset), Some(path)) => write!(f, "at offset {} in {}: ", offset, path)?,
+                    (Some(offset), None) => write!(f, "at offset {}: ", offset)?,
+                    (None, Some(path)) => write!(f, "in {}: ", path)?,
+                    (None, None) => (),
+                }
+                write!(f, "{}", context.cause)
+            },
+        }
+    }
+}
+
+// Context already prints its cause, the chain continues below it
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Context(ref context) => context.cause.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -1,49 +1,233 @@
-use class_file::pos_slice::PoSlice;
-use class_file::constant_pool::{ConstantClass, ConstantDouble, ConstantMethodref, ConstantInvokeDynamic};
-
-#[test]
-fn pos_slice_works() {
-    let bytes = [4, 6, 2, 7, 1, 2, 9, 6, 8, 0];
-    let poslice = PoSlice::new(&bytes);
-    assert_eq!(poslice.read_u1().unwrap(), 4);
-    assert_eq!(poslice.peek_u2().unwrap(), 1538);
-    assert_eq!(poslice.read_u2().unwrap(), 1538);
-    assert_eq!(poslice.peek_u2().unwrap(), 1793);
-    assert_eq!(poslice.read_u4().unwrap(), 117_506_569);
-    assert_eq!(poslice.read_slice(2).unwrap(), &[6, 8]);
-    assert_eq!(poslice.read_u1().unwrap(), 0);
-}
-
-use std::io::Read;
-use std::fs::File;
-
-use class_file::class_file::ClassFile;
-use class_file::from_bytes::FromBytes;
-
-#[test]
-fn class_file_works() {
-    let mut file = File::open("/home/zer0/Projects/jvm_rs/src/tests/source_files/Test.class")
-        .unwrap();
-    let mut bytes = Vec::new();
-    file.read_to_end(&mut bytes).unwrap();
-    let len = bytes.len();
-    println!("len: {}", len);
-    let slice = PoSlice::new(&bytes);
-    let class_file = ClassFile::from_bytes(&slice).unwrap();
-    assert_eq!(len, slice.pos());
-    let _main = class_file.get_main_method().unwrap();
-}
-
-#[test]
-fn constant_pool_works() {
-    let bytes = [0u8; 30];
-    let pos_slice = PoSlice::new(&bytes);
-    let _ = ConstantClass::read(&pos_slice).unwrap();
-    assert_eq!(pos_slice.pos(), 2);
-    let _ = ConstantDouble::read(&pos_slice).unwrap();
-    assert_eq!(pos_slice.pos(), 10);
-    let _ = ConstantMethodref::read(&pos_slice).unwrap();
-    assert_eq!(pos_slice.pos(), 14);
-    let _ = ConstantInvokeDynamic::read(&pos_slice).unwrap();
-    assert_eq!(pos_slice.pos(), 18);
-}
\ No newline at end of file
+use class_file::pos_slice::PoSlice;
+use result::Error;
+use class_file::constant_pool::{ConstantClass, ConstantDouble, ConstantMethodref, ConstantInvokeDynamic, LoadableConstant, RefKind};
+
+#[test]
+fn pos_slice_works() {
+    let bytes = [4, 6, 2, 7, 1, 2, 9, 6, 8, 0];
+    let poslice = PoSlice::new(&bytes);
+    assert_eq!(poslice.read_u1().unwrap(), 4);
+    assert_eq!(poslice.peek_u2().unwrap(), 1538);
+    assert_eq!(poslice.read_u2().unwrap(), 1538);
+    assert_eq!(poslice.peek_u2().unwrap(), 1793);
+    assert_eq!(poslice.read_u4().unwrap(), 117_506_569);
+    assert_eq!(poslice.read_slice(2).unwrap(), &[6, 8]);
+    assert_eq!(poslice.read_u1().unwrap(), 0);
+}
+
+#[test]
+fn pos_slice_bounds_work() {
+    let empty = PoSlice::new(&[]);
+    assert_eq!(empty.read_slice(0).unwrap(), &[] as &[u8]);
+    assert!(matches!(empty.read_u1(), Err(Error::OutOfBounds(0))));
+
+    let bytes = [1, 2, 3, 4, 5, 6];
+    let poslice = PoSlice::new(&bytes);
+    assert_eq!(poslice.skip(6).unwrap(), 6);
+    assert_eq!(poslice.remaining(), 0);
+    assert!(poslice.peek_slice(0).unwrap().is_empty());
+    assert!(poslice.read_u2().is_err());
+
+    // Lengths from the file can't wrap the arithmetic around
+    let poslice = PoSlice::new(&bytes);
+    poslice.read_u1().unwrap();
+    assert!(matches!(poslice.read_slice(usize::MAX), Err(Error::OutOfBounds(1))));
+    assert!(poslice.skip(usize::MAX - 1).is_err());
+    assert_eq!(poslice.pos(), 1);
+
+    // Children keep offsets into the whole input and stop at their end
+    let child = poslice.sub_slice(3).unwrap();
+    assert_eq!(poslice.pos(), 4);
+    assert_eq!(child.pos(), 1);
+    assert_eq!(child.read_u2().unwrap(), 0x0203);
+    assert!(matches!(child.read_u2(), Err(Error::PastEnd(5))));
+    assert_eq!(child.remaining(), 1);
+    assert_eq!(child.read_slice(1).unwrap(), &[4]);
+    assert!(poslice.sub_slice(3).is_err());
+    assert_eq!(poslice.read_u2().unwrap(), 0x0506);
+}
+
+use std::sync::Arc;
+use std::thread;
+
+use class_file::attribute_info::{CodeAttribute, SourceFileAttribute};
+use class_file::class_file::{ClassFile, MemberInfo};
+use class_file::from_bytes::FromBytes;
+use class_file::descriptor::FieldType;
+use tests::read_fixture;
+
+#[test]
+fn class_file_works() {
+    let bytes = read_fixture("Test.class");
+    let len = bytes.len();
+    let slice = PoSlice::new(&bytes);
+    let class_file = ClassFile::from_bytes(&slice).unwrap();
+    assert_eq!(len, slice.pos());
+    let _main = class_file.get_main_method().unwrap();
+}
+
+#[test]
+fn constant_pool_works() {
+    let bytes = [0u8; 30];
+    let pos_slice = PoSlice::new(&bytes);
+    let _ = ConstantClass::read(&pos_slice).unwrap();
+    assert_eq!(pos_slice.pos(), 2);
+    let _ = ConstantDouble::read(&pos_slice).unwrap();
+    assert_eq!(pos_slice.pos(), 10);
+    let _ = ConstantMethodref::read(&pos_slice).unwrap();
+    assert_eq!(pos_slice.pos(), 14);
+    let _ = ConstantInvokeDynamic::read(&pos_slice).unwrap();
+    assert_eq!(pos_slice.pos(), 18);
+}
+#[test]
+fn exception_table_works() {
+    let bytes = read_fixture("Exceptions.class");
+    let slice = PoSlice::new(&bytes);
+    let class_file = ClassFile::from_bytes(&slice).unwrap();
+    assert_eq!(class_file.source_file().unwrap(), Some("Exceptions.java"));
+
+    let parse = class_file.find_method("parse", "(Ljava/lang/String;)I").unwrap().unwrap();
+    let code = parse.get_code_attribute().unwrap();
+    let handlers: Vec<_> = code.handlers_at(4).collect();
+    assert_eq!(handlers.len(), 2);
+    assert_eq!(handlers[0].handler_pc(), 15);
+    assert_eq!(code.catch_type_name(handlers[0]).unwrap(), Some("java/lang/NumberFormatException"));
+    assert_eq!(code.catch_type_name(handlers[1]).unwrap(), None);
+    assert_eq!(code.handlers_at(5).count(), 0);
+
+    assert_eq!(code.line_number(0), Some(6));
+    assert_eq!(code.line_number(14), Some(6));
+    assert_eq!(code.line_number(38), Some(11));
+}
+
+#[test]
+fn field_ref_works() {
+    let bytes = read_fixture("Exceptions.class");
+    let slice = PoSlice::new(&bytes);
+    let class_file = ClassFile::from_bytes(&slice).unwrap();
+
+    let constant_pool = class_file.constant_pool();
+    let field_ref = constant_pool.field_ref(13).unwrap();
+    assert_eq!((field_ref.class_name, field_ref.name, field_ref.descriptor), ("Exceptions", "count", "I"));
+    assert!(constant_pool.method_ref(13).is_err());
+
+    let count = class_file.find_field(field_ref.name, field_ref.descriptor).unwrap().unwrap();
+    assert!(count.access_flags().is_static());
+    assert!(!count.access_flags().is_final());
+    assert_eq!(count.get_field_type().unwrap(), FieldType::Int);
+    assert!(class_file.find_field("count", "J").unwrap().is_none());
+}
+
+#[test]
+fn field_flags_work() {
+    let bytes = read_fixture("Flags.class");
+    let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    let running = class_file.find_field("running", "Z").unwrap().unwrap();
+    assert!(running.field_flags().is_volatile());
+    assert!(!running.field_flags().is_transient());
+    assert_eq!(running.field_flags().names(), vec!["ACC_VOLATILE"]);
+    let cached = class_file.find_field("cached", "I").unwrap().unwrap();
+    assert!(cached.field_flags().is_transient());
+    assert!(!cached.field_flags().is_volatile());
+    assert_eq!(cached.field_flags().names(), vec!["ACC_TRANSIENT"]);
+}
+
+#[test]
+fn method_flags_work() {
+    let bytes = read_fixture("Flags.class");
+    let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    // The same bit as ACC_SYNCHRONIZED, set by every javac
+    assert!(class_file.access_flags().is_super());
+
+    let increment = class_file.find_method("increment", "()V").unwrap().unwrap();
+    assert!(increment.method_flags().is_synchronized());
+    assert_eq!(increment.method_flags().names(), vec!["ACC_SYNCHRONIZED"]);
+    let log = class_file.find_method("log", "([Ljava/lang/String;)V").unwrap().unwrap();
+    assert!(!log.method_flags().is_synchronized());
+    assert!(log.method_flags().is_varargs());
+}
+
+#[test]
+fn loadable_constants_work() {
+    let bytes = read_fixture("Constants.class");
+    let slice = PoSlice::new(&bytes);
+    let class_file = ClassFile::from_bytes(&slice).unwrap();
+    let constant_pool = class_file.constant_pool();
+
+    assert_eq!(constant_pool.loadable(7).unwrap(), LoadableConstant::String("nul\0"));
+    assert_eq!(constant_pool.loadable(9).unwrap(), LoadableConstant::String("snow\u{2603}man"));
+    assert_eq!(constant_pool.loadable(11).unwrap(), LoadableConstant::String("\u{1F600}"));
+    assert_eq!(constant_pool.loadable(13).unwrap(), LoadableConstant::Class("java/lang/String"));
+    assert_eq!(constant_pool.loadable(15).unwrap(), LoadableConstant::Class("[I"));
+    assert_eq!(constant_pool.loadable(22).unwrap(), LoadableConstant::Long(0x12_3456_789A));
+    assert_eq!(constant_pool.loadable(26).unwrap(), LoadableConstant::Double(3.5));
+    assert_eq!(constant_pool.loadable(30).unwrap(), LoadableConstant::Float(0.5));
+    assert_eq!(constant_pool.loadable(33).unwrap(), LoadableConstant::Integer(100_000));
+    assert!(constant_pool.loadable(1).is_err());
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn class_file_is_thread_safe() {
+    assert_send_sync::<ClassFile>();
+    assert_send_sync::<MemberInfo>();
+    assert_send_sync::<CodeAttribute>();
+    assert_send_sync::<SourceFileAttribute>();
+
+    let handles: Vec<_> = ["Exceptions.class", "Constants.class"].iter()
+        .map(|name| thread::spawn(move || {
+            let bytes = read_fixture(name);
+            ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap()
+        }))
+        .collect();
+    let class_files: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+    let class_files = Arc::new(class_files);
+    let shared = class_files.clone();
+    let name = thread::spawn(move || shared[1].name().unwrap().to_string()).join().unwrap();
+    assert_eq!(name, "Constants");
+    assert_eq!(class_files[0].name().unwrap(), "Exceptions");
+}
+
+#[test]
+fn bootstrap_methods_work() {
+    let bytes = read_fixture("Lambdas.class");
+    let slice = PoSlice::new(&bytes);
+    let class_file = ClassFile::from_bytes(&slice).unwrap();
+    let constant_pool = class_file.constant_pool();
+
+    let call_site = constant_pool.dynamic(18).unwrap();
+    assert_eq!(call_site.name, "makeConcatWithConstants");
+    assert_eq!(call_site.descriptor, "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;");
+
+    let call_site = constant_pool.dynamic(7).unwrap();
+    assert_eq!((call_site.name, call_site.descriptor), ("get", "()Ljava/util/function/Supplier;"));
+    let bootstrap_method = &class_file.bootstrap_methods()[call_site.bootstrap_method_attr_index as usize];
+    match constant_pool.loadable(bootstrap_method.bootstrap_method_ref() as usize).unwrap() {
+        LoadableConstant::MethodHandle(kind, method) => {
+            assert_eq!(kind, RefKind::InvokeStatic);
+            assert_eq!((method.class_name, method.name), ("java/lang/invoke/LambdaMetafactory", "metafactory"));
+        },
+        _ => panic!("bootstrap method is not a method handle"),
+    }
+    assert_eq!(bootstrap_method.bootstrap_arguments().len(), 3);
+    assert!(constant_pool.dynamic(8).is_err());
+}
+
+#[test]
+fn method_handles_work() {
+    let bytes = read_fixture("Lambdas.class");
+    let slice = PoSlice::new(&bytes);
+    let class_file = ClassFile::from_bytes(&slice).unwrap();
+    let constant_pool = class_file.constant_pool();
+
+    // LambdaMetafactory.metafactory(samMethodType, implMethod, instantiatedMethodType)
+    let arguments = class_file.bootstrap_methods()[0].bootstrap_arguments();
+    let sam_method_type = constant_pool.method_type(arguments[0] as usize).unwrap();
+    assert_eq!(sam_method_type.return_type(), Some(&FieldType::Object("java/lang/Object".to_string())));
+    let (kind, implementation) = constant_pool.method_handle(arguments[1] as usize).unwrap();
+    assert_eq!(kind.name(), "REF_invokeStatic");
+    assert!(!kind.is_field());
+    assert_eq!((implementation.class_name, implementation.name), ("Lambdas", "lambda$greet$0"));
+    assert!(constant_pool.method_handle(arguments[0] as usize).is_err());
+}
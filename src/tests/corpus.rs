@@ -0,0 +1,125 @@
+/*
+* The checked in classes in src/tests/corpus, build.sh there says how they are made. Every class
+* parses to its last byte, prints exactly what the JDK's javap printed into its .javap file and round
+* trips through jasm and JSON. Together they cover every constant pool tag, every attribute of
+* JVMS §4.7 and every version from Java 8 to 21.
+*/
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use class_file::attribute_info::AttributeInfo;
+use class_file::class_file::ClassFile;
+use class_file::constant_pool::Tag;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use class_file::view::ClassView;
+use javap::{self, Options};
+use jasm;
+use json;
+
+const ATTRIBUTES: &[&str] = &[
+    "ConstantValue", "Code", "StackMapTable", "BootstrapMethods", "NestHost", "NestMembers",
+    "PermittedSubclasses", "Exceptions", "InnerClasses", "EnclosingMethod", "Synthetic", "Signature",
+    "Record", "SourceFile", "LineNumberTable", "LocalVariableTable", "LocalVariableTypeTable",
+    "SourceDebugExtension", "Deprecated", "RuntimeVisibleAnnotations", "RuntimeInvisibleAnnotations",
+    "RuntimeVisibleParameterAnnotations", "RuntimeInvisibleParameterAnnotations",
+    "RuntimeVisibleTypeAnnotations", "RuntimeInvisibleTypeAnnotations", "AnnotationDefault",
+    "MethodParameters", "Module", "ModulePackages", "ModuleMainClass",
+];
+
+fn corpus() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/corpus")
+}
+
+fn find_classes(dir: &Path, classes: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            find_classes(&path, classes);
+        } else if path.extension().is_some_and(|extension| extension == "class") {
+            classes.push(path);
+        }
+    }
+}
+
+// Paths relative to the corpus, the way build.sh passes them to javap
+fn classes() -> Vec<(String, Vec<u8>)> {
+    let corpus = corpus();
+    let mut paths = Vec::new();
+    find_classes(&corpus.join("classes"), &mut paths);
+    paths.sort();
+    paths.into_iter()
+        .map(|path| {
+            let name = path.strip_prefix(&corpus).unwrap().iter()
+                .map(|part| part.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            (name, fs::read(&path).unwrap())
+        })
+        .collect()
+}
+
+fn attribute_names<'a>(attributes: &'a [AttributeInfo], names: &mut BTreeSet<&'a str>) {
+    for attribute in attributes {
+        names.insert(attribute.name());
+        if let AttributeInfo::Code(ref code) = *attribute {
+            attribute_names(code.attributes(), names);
+        }
+    }
+}
+
+#[test]
+fn corpus_matches_expected_output() {
+    let classes = classes();
+    assert!(classes.len() >= 35, "only {} classes in the corpus", classes.len());
+    for (name, bytes) in classes {
+        let slice = PoSlice::new(&bytes);
+        let class_file = ClassFile::from_bytes(&slice).unwrap_or_else(|err| panic!("{}: {}", name, err));
+        assert_eq!(slice.pos(), bytes.len(), "{} has trailing bytes", name);
+
+        let mut out = Vec::new();
+        javap::disassemble(&mut out, &class_file, &Options { verbose: true, private: true, ..Options::default() }).unwrap();
+        let expected = fs::read_to_string(corpus().join(name.replace(".class", ".javap"))).unwrap();
+        assert!(String::from_utf8(out).unwrap() == expected,
+            "{} doesn't print like the JDK's javap -v -p", name);
+
+        let mut source = Vec::new();
+        jasm::disassemble(&mut source, &class_file).unwrap();
+        assert!(jasm::assemble(&String::from_utf8(source).unwrap()).unwrap() == bytes, "{} doesn't round trip through jasm", name);
+        let mut text = Vec::new();
+        json::export(&mut text, &class_file).unwrap();
+        assert!(json::import(&String::from_utf8(text).unwrap()).unwrap() == bytes, "{} doesn't round trip through JSON", name);
+        assert_eq!(ClassView::parse(&bytes).unwrap().name().unwrap(), class_file.name().unwrap());
+    }
+}
+
+#[test]
+fn corpus_covers_class_files() {
+    let classes = classes();
+    let mut tags = BTreeSet::new();
+    let mut versions = BTreeSet::new();
+    let class_files: Vec<_> = classes.iter()
+        .map(|(_, bytes)| ClassFile::from_bytes(&PoSlice::new(bytes)).unwrap())
+        .collect();
+    let mut attributes = BTreeSet::new();
+    for class_file in &class_files {
+        versions.insert(class_file.major_version());
+        tags.extend(class_file.constant_pool().iter().map(|(_, cp_info)| cp_info.tag().name()));
+        attribute_names(class_file.attributes(), &mut attributes);
+        for member in class_file.fields().iter().chain(class_file.methods()) {
+            attribute_names(member.attributes(), &mut attributes);
+        }
+    }
+
+    for tag in (0..=u8::MAX).filter_map(|val| Tag::new(val).ok()) {
+        assert!(tags.contains(tag.name()), "no {} constant in the corpus", tag.name());
+    }
+    for name in ATTRIBUTES {
+        assert!(attributes.contains(name), "no {} attribute in the corpus", name);
+    }
+    // Java 8 is 52, 21 is 65
+    for version in 52..=65 {
+        assert!(versions.contains(&version), "no version {} class in the corpus", version);
+    }
+}
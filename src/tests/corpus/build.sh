@@ -0,0 +1,51 @@
+#!/bin/sh
+# Rebuilds classes/ from java/ and jasm/ and writes what the JDK's javap -v -p prints for every class
+# next to it, less the first three lines (path, modification time and checksum). Needs a JDK 17 on
+# the PATH and is only run by hand: the tests read what is checked in, so they need neither javac nor
+# this script. Hello for 18 to 21 is compiled by javac 21 when JDK21_HOME is set and assembled from
+# jasm/versions otherwise. `build.sh expected` only rewrites the expected output.
+set -e
+cd "$(dirname "$0")"
+jvm_rs() {
+    cargo run --quiet --manifest-path ../../../Cargo.toml -- "$@"
+}
+
+if [ "$1" != expected ]; then
+    rm -rf classes
+    tmp=$(mktemp -d)
+    trap 'rm -rf "$tmp"' EXIT
+
+    javac --release 8 -g -parameters -d classes java/release8/*.java
+    javac --release 11 -g -d classes java/release11/*.java
+    javac --release 17 -d classes java/release17/Expr.java
+
+    # jar adds ModulePackages and ModuleMainClass to what javac writes
+    javac --release 17 -d "$tmp/module" java/release17/module-info.java java/release17/corpus/Main.java
+    jar --create --file "$tmp/corpus.jar" --main-class corpus.Main -C "$tmp/module" .
+    (cd "$tmp" && mkdir unpacked && cd unpacked && jar --extract --file ../corpus.jar)
+    cp "$tmp/unpacked/module-info.class" classes/
+    mkdir -p classes/corpus
+    cp "$tmp/unpacked/corpus/Main.class" classes/corpus/
+
+    mkdir -p classes/versions
+    for release in 8 9 10 11 12 13 14 15 16 17; do
+        javac --release $release -d "$tmp/$release" java/versions/Hello.java
+        cp "$tmp/$release/Hello.class" classes/versions/Hello-$release.class
+    done
+    if [ -n "$JDK21_HOME" ]; then
+        for release in 18 19 20 21; do
+            "$JDK21_HOME/bin/javac" --release $release -d "$tmp/$release" java/versions/Hello.java
+            cp "$tmp/$release/Hello.class" classes/versions/Hello-$release.class
+        done
+    else
+        for release in 18 19 20 21; do
+            jvm_rs jasm jasm/versions/Hello-$release.jasm classes/versions/Hello-$release.class
+        done
+    fi
+
+    jvm_rs jasm jasm/Condy.jasm classes/Condy.class
+fi
+
+find classes -name '*.class' | while read -r class; do
+    javap -J-Dfile.encoding=UTF-8 -J-Dsun.stdout.encoding=UTF-8 -v -p "$class" | tail -n +4 > "${class%.class}.javap"
+done
@@ -0,0 +1,27 @@
+import java.util.List;
+
+// Nestmates reach each other's private members without bridge methods
+class Nest {
+    private int secret = 42;
+
+    class Inner {
+        private String reveal() {
+            var prefix = "secret: ";
+            return prefix + secret + '!';
+        }
+    }
+
+    static class Nested {
+        private static long twice(Nest nest) {
+            return nest.secret * 2L;
+        }
+    }
+
+    String both() {
+        return new Inner().reveal() + Nested.twice(this);
+    }
+
+    static int sum(List<Integer> values) {
+        return values.stream().mapToInt(Integer::intValue).sum();
+    }
+}
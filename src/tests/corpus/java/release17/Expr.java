@@ -0,0 +1,39 @@
+sealed interface Expr permits Expr.Num, Expr.Add, Expr.Neg {
+    record Num(int value) implements Expr {
+    }
+
+    record Add(Expr left, Expr right) implements Expr {
+        public Add {
+            if (left == null || right == null) {
+                throw new IllegalArgumentException("missing operand");
+            }
+        }
+    }
+
+    final class Neg implements Expr {
+        final Expr operand;
+
+        Neg(Expr operand) {
+            this.operand = operand;
+        }
+    }
+
+    static int eval(Expr expr) {
+        if (expr instanceof Num num) {
+            return num.value();
+        } else if (expr instanceof Add add) {
+            return eval(add.left()) + eval(add.right());
+        } else if (expr instanceof Neg neg) {
+            return -eval(neg.operand);
+        }
+        throw new IllegalStateException();
+    }
+
+    static String show(Expr expr) {
+        return switch (expr.getClass().getSimpleName()) {
+            case "Num" -> "number";
+            case "Add" -> "sum";
+            default -> "other";
+        };
+    }
+}
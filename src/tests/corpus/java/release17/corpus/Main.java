@@ -0,0 +1,12 @@
+package corpus;
+
+public class Main implements Runnable {
+    @Override
+    public void run() {
+        System.out.println("corpus");
+    }
+
+    public static void main(String[] args) {
+        new Main().run();
+    }
+}
@@ -0,0 +1,10 @@
+module corpus {
+    requires java.base;
+    requires static java.logging;
+
+    exports corpus;
+    opens corpus to java.base;
+
+    uses java.lang.Runnable;
+    provides java.lang.Runnable with corpus.Main;
+}
@@ -0,0 +1,13 @@
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Retention(RetentionPolicy.RUNTIME)
+@interface Label {
+    String value() default "none";
+
+    int priority() default 1;
+
+    Class<?>[] types() default {};
+
+    RetentionPolicy policy() default RetentionPolicy.RUNTIME;
+}
@@ -0,0 +1,7 @@
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Target;
+
+// Class retention, so it ends up in the Invisible annotation attributes
+@Target({ ElementType.TYPE, ElementType.METHOD, ElementType.PARAMETER, ElementType.TYPE_USE })
+@interface Marker {
+}
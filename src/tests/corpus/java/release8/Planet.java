@@ -0,0 +1,28 @@
+enum Planet {
+    MERCURY(3.303e+23, 2.4397e6),
+    EARTH(5.976e+24, 6.37814e6),
+    JUPITER(1.9e+27, 7.1492e7);
+
+    static final double G = 6.67300E-11;
+
+    private final double mass;
+    private final double radius;
+
+    Planet(double mass, double radius) {
+        this.mass = mass;
+        this.radius = radius;
+    }
+
+    double surfaceGravity() {
+        return G * mass / (radius * radius);
+    }
+
+    static String kind(Planet planet) {
+        switch (planet) {
+            case JUPITER:
+                return "gas giant";
+            default:
+                return "rocky";
+        }
+    }
+}
@@ -0,0 +1,13 @@
+import java.io.Serializable;
+
+interface Shape extends Serializable {
+    double area();
+
+    default String describe() {
+        return getClass().getSimpleName() + " " + area();
+    }
+
+    static Shape unit() {
+        return new Shapes.Circle(1);
+    }
+}
@@ -0,0 +1,109 @@
+import java.io.IOException;
+import java.io.Reader;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.function.Function;
+import java.util.function.Supplier;
+
+@Marker
+@Label(value = "shapes", priority = 2, types = { Shape.class })
+class Shapes<T extends Shape> {
+    static final int SIDES = 4;
+    static final long BIG = 1L << 40;
+    static final float RATIO = 1.5f;
+    static final double TAU = 6.283185307179586;
+    static final String NAME = "shapes";
+
+    private final List<@Tag("element") T> shapes = new ArrayList<>();
+    private int changes;
+
+    static class Circle implements Shape {
+        final double radius;
+
+        Circle(double radius) {
+            this.radius = radius;
+        }
+
+        @Override
+        public double area() {
+            return Math.PI * radius * radius;
+        }
+    }
+
+    // Inner, reads the outer instance
+    class Counter {
+        int count() {
+            return shapes.size() + changes;
+        }
+    }
+
+    void add(@Label("shape") T shape, @Marker int times) {
+        for (int i = 0; i < times; i++) {
+            shapes.add(shape);
+            changes++;
+        }
+    }
+
+    double total() {
+        double total = 0;
+        for (T shape : shapes) {
+            total += shape.area();
+        }
+        return total;
+    }
+
+    static <S extends Comparable<S>> S max(List<S> items) {
+        S max = null;
+        for (S item : items) {
+            if (max == null || item.compareTo(max) > 0) {
+                max = item;
+            }
+        }
+        return max;
+    }
+
+    @Deprecated
+    static int read(Reader reader) throws IOException {
+        return reader.read();
+    }
+
+    @Marker
+    static Object constants() {
+        int big = 100000;
+        float third = 0.33f;
+        return new Object[] { big, third, 3L, 0.25, "text", Shapes.class, int[][].class };
+    }
+
+    static List<Supplier<Shape>> factories() {
+        // Local and anonymous classes get an EnclosingMethod attribute
+        class Unit implements Supplier<Shape> {
+            @Override
+            public Shape get() {
+                return Shape.unit();
+            }
+        }
+        List<Supplier<Shape>> factories = new ArrayList<>();
+        factories.add(new Unit());
+        factories.add(new Supplier<Shape>() {
+            @Override
+            public Shape get() {
+                return new Circle(2);
+            }
+        });
+        factories.add(Shape::unit);
+        factories.add(() -> new Circle(3));
+        return factories;
+    }
+
+    static Function<Double, Shape> circles() {
+        return Circle::new;
+    }
+
+    static String describe(Shape shape) {
+        return shape.describe();
+    }
+
+    static @Tag("result") String cast(Object object) {
+        return (@Marker String) object;
+    }
+}
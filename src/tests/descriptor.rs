@@ -0,0 +1,56 @@
+use class_file::descriptor::{java_name, FieldType, MethodDescriptor};
+use class_file::signature::{ClassSignature, MethodSignature, TypeSignature};
+
+#[test]
+fn method_descriptor_works() {
+    let descriptor = MethodDescriptor::parse("(IDLjava/lang/Thread;[[J)Ljava/lang/Object;").unwrap();
+    assert_eq!(descriptor.params(), &[
+        FieldType::Int,
+        FieldType::Double,
+        FieldType::Object("java/lang/Thread".to_string()),
+        FieldType::Array(Box::new(FieldType::Array(Box::new(FieldType::Long)))),
+    ]);
+    assert_eq!(descriptor.arg_slots(), 5);
+    assert_eq!(descriptor.return_type(), Some(&FieldType::Object("java/lang/Object".to_string())));
+    assert_eq!(descriptor.return_slots(), 1);
+
+    let descriptor = MethodDescriptor::parse("()V").unwrap();
+    assert_eq!(descriptor.arg_slots(), 0);
+    assert_eq!(descriptor.return_type(), None);
+
+    assert!(MethodDescriptor::parse("(I").is_err());
+    assert!(MethodDescriptor::parse("(L;)V").is_err());
+    assert!(MethodDescriptor::parse("()VI").is_err());
+    assert!(FieldType::parse("Q").is_err());
+    assert!(FieldType::parse("Ljava/lang/Object").is_err());
+}
+
+#[test]
+fn type_name_works() {
+    assert_eq!(FieldType::parse("I").unwrap().type_name(), "int");
+    assert_eq!(FieldType::parse("[[Ljava/lang/String;").unwrap().type_name(), "java.lang.String[][]");
+    assert_eq!(java_name("java/util/Map$Entry"), "java.util.Map$Entry");
+    assert_eq!(java_name("[Ljava/lang/Object;"), "[Ljava.lang.Object;");
+}
+
+#[test]
+fn signature_works() {
+    let signature = ClassSignature::parse("<K:Ljava/lang/Object;V::Ljava/lang/Comparable<-TV;>;>Ljava/util/AbstractMap<TK;TV;>;Ljava/io/Serializable;").unwrap();
+    assert_eq!(signature.type_params().len(), 2);
+    assert!(signature.type_params()[0].class_bound().is_some());
+    assert!(signature.type_params()[1].class_bound().is_none());
+    assert_eq!(signature.type_params()[1].interface_bounds()[0].type_name(), "java.lang.Comparable<? super V>");
+    assert_eq!(signature.super_class().type_name(), "java.util.AbstractMap<K, V>");
+    assert_eq!(signature.interfaces()[0].type_name(), "java.io.Serializable");
+
+    let signature = MethodSignature::parse("<T:Ljava/lang/Throwable;>([Ljava/util/Map<*+TT;>.Entry;I)V^TT;").unwrap();
+    assert_eq!(signature.params()[0].type_name(), "java.util.Map<?, ? extends T>.Entry[]");
+    assert_eq!(signature.params()[1].type_name(), "int");
+    assert_eq!(signature.return_type(), None);
+    assert_eq!(signature.throws(), &[TypeSignature::Variable("T".to_string())]);
+
+    assert!(TypeSignature::parse("Ljava/util/List<>;").is_err());
+    assert!(TypeSignature::parse("TT").is_err());
+    assert!(MethodSignature::parse("()V^I").is_err());
+    assert!(ClassSignature::parse("<>Ljava/lang/Object;").is_err());
+}
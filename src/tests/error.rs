@@ -0,0 +1,122 @@
+use std::error::Error as StdError;
+use std::io;
+
+use class_file::class_file::ClassFile;
+use class_file::constant_pool::Tag;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use class_file::view::ClassView;
+use result::Error;
+use tests::read_fixture;
+
+#[test]
+fn error_context_works() {
+    let bytes = read_fixture("Exceptions.class");
+    let view = ClassView::parse(&bytes).unwrap();
+    let method = view.find_method("parse", "(Ljava/lang/String;)I").unwrap().unwrap();
+    let code = method.code(view.constant_pool()).unwrap().unwrap().code();
+    let code_start = code.as_ptr() as usize - bytes.as_ptr() as usize;
+
+    // exception_table_length, then 8 bytes per entry, cut in the middle of end_pc of the third
+    let entry = code_start + code.len() + 2 + 2 * 8;
+    let err = ClassFile::from_bytes(&PoSlice::new(&bytes[..entry + 3])).err().unwrap();
+    assert_eq!(err.offset(), Some(entry + 2));
+    assert_eq!(err.path().unwrap(), "method parse(Ljava/lang/String;)I → Code → exception_table[2]");
+    assert!(matches!(*err.cause(), Error::OutOfBounds(_)));
+    assert_eq!(err.to_string(), format!(
+        "at offset {} in method parse(Ljava/lang/String;)I → Code → exception_table[2]: {} is out of bounds",
+        entry + 2, entry + 3));
+
+    // attribute_name_index of the Code attribute pointing at this_class
+    let mut corrupt = bytes.clone();
+    let name_index = code_start - 8 - 6;
+    let this_class = view.this_class();
+    corrupt[name_index..name_index + 2].copy_from_slice(&this_class.to_be_bytes());
+    let err = ClassFile::from_bytes(&PoSlice::new(&corrupt)).err().unwrap();
+    assert_eq!(err.offset(), Some(name_index + 2));
+    assert!(matches!(*err.cause(), Error::WrongTag(index, &[Tag::UTF8], Tag::CLASS) if index == this_class as usize));
+    assert_eq!(err.to_string(), format!(
+        "at offset {} in method parse(Ljava/lang/String;)I → attribute_name_index: constant #{} is Class, expected Utf8",
+        name_index + 2, this_class));
+
+    // Nothing to point at in the header but the offset
+    let err = ClassFile::from_bytes(&PoSlice::new(&bytes[..9])).err().unwrap();
+    assert_eq!(err.path(), None);
+    assert_eq!(err.to_string(), "at offset 8: 9 is out of bounds");
+
+    let mut corrupt = bytes.clone();
+    corrupt[3] = 0xbf;
+    for err in [ClassFile::from_bytes(&PoSlice::new(&corrupt)).err().unwrap(), ClassView::parse(&corrupt).err().unwrap()] {
+        assert_eq!(err.offset(), Some(0));
+        assert!(matches!(*err.cause(), Error::Magic(0xcafe_babf)));
+        assert_eq!(err.to_string(), "at offset 0: bad magic number 0xcafebabf");
+    }
+
+    let mut corrupt = bytes.clone();
+    corrupt[10] = 0;
+    let err = ClassFile::from_bytes(&PoSlice::new(&corrupt)).err().unwrap();
+    assert_eq!(err.to_string(), "at offset 11 in constant_pool[1]: unknown constant pool tag 0");
+}
+
+#[test]
+fn error_messages_work() {
+    let bytes = read_fixture("Exceptions.class");
+    let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    let constant_pool = class_file.constant_pool();
+    let (utf8, _) = constant_pool.iter().find(|&(_, cp_info)| cp_info.tag() == Tag::UTF8).unwrap();
+    assert_eq!(constant_pool.method_ref(utf8).err().unwrap().to_string(),
+        format!("constant #{} is Utf8, expected Methodref or InterfaceMethodref", utf8));
+    assert!(matches!(constant_pool.get(constant_pool.len()), Err(Error::Index(_))));
+    assert_eq!(Error::InvalidOpcode(0xcb).to_string(), "invalid opcode 0xcb");
+
+    // The io::Error under the context is the source
+    let err = ClassFile::read_from(&bytes[..bytes.len() / 2]).err().unwrap();
+    let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::UnexpectedEof);
+    assert!(err.to_string().starts_with(&format!("at offset {} in ", err.offset().unwrap())));
+}
+
+#[test]
+fn attribute_length_is_checked() {
+    let bytes = read_fixture("Exceptions.class");
+    let view = ClassView::parse(&bytes).unwrap();
+    let method = view.find_method("parse", "(Ljava/lang/String;)I").unwrap().unwrap();
+    let code = method.code(view.constant_pool()).unwrap().unwrap().code();
+    // attribute_length, max_stack, max_locals, code_length, code
+    let length_at = code.as_ptr() as usize - bytes.as_ptr() as usize - 12;
+    let length = u32::from_be_bytes([bytes[length_at], bytes[length_at + 1], bytes[length_at + 2], bytes[length_at + 3]]);
+
+    let mut longer = bytes.clone();
+    longer[length_at..length_at + 4].copy_from_slice(&(length + 1).to_be_bytes());
+    let err = ClassFile::from_bytes(&PoSlice::new(&longer)).err().unwrap();
+    assert!(matches!(*err.cause(), Error::AttributeLength(declared, decoded) if declared == length + 1 && decoded == length as usize));
+    assert_eq!(err.path().unwrap(), "method parse(Ljava/lang/String;)I → Code");
+    assert!(err.to_string().ends_with(&format!("attribute_length is {} but only {} bytes are decoded", length + 1, length)));
+
+    // The last attribute inside Code no longer fits, the stream reader stops at the same place
+    let mut shorter = bytes.clone();
+    shorter[length_at..length_at + 4].copy_from_slice(&(length - 1).to_be_bytes());
+    for err in [
+        ClassFile::from_bytes(&PoSlice::new(&shorter)).err().unwrap(),
+        ClassFile::read_from(&shorter[..]).err().unwrap(),
+    ] {
+        assert!(matches!(*err.cause(), Error::AttributeLength(declared, decoded) if declared == length - 1 && decoded == length as usize));
+        assert_eq!(err.path().unwrap(), "method parse(Ljava/lang/String;)I → Code");
+    }
+
+    // No attributes inside Code leaves the rest unread, the view sees it once Code is decoded
+    let exceptions_at = length_at + 12 + code.len();
+    let exceptions = u16::from_be_bytes([bytes[exceptions_at], bytes[exceptions_at + 1]]) as usize;
+    let count_at = exceptions_at + 2 + exceptions * 8;
+    let mut unread = bytes.clone();
+    unread[count_at..count_at + 2].copy_from_slice(&[0, 0]);
+    let decoded = count_at + 2 - (length_at + 4);
+    let view = ClassView::parse(&unread).unwrap();
+    let method = view.find_method("parse", "(Ljava/lang/String;)I").unwrap().unwrap();
+    for err in [
+        method.code(view.constant_pool()).err().unwrap(),
+        ClassFile::from_bytes(&PoSlice::new(&unread)).err().unwrap(),
+    ] {
+        assert!(matches!(*err.cause(), Error::AttributeLength(declared, actual) if declared == length && actual == decoded));
+    }
+}
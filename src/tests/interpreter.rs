@@ -0,0 +1,658 @@
+/*
+* The interpreter on classes assembled from jasm sources. FIXTURES are the least of java.lang the
+* tests need, the mini runtime's classes of the same name are loaded in their place.
+*/
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use class_file::class_file::ClassFile;
+use class_path::{ClassPath, ClassSource};
+use jasm;
+use result::Result;
+use types::u1;
+use vm::Vm;
+
+const FIXTURES: &[&str] = &[r#"
+.class public super java/lang/Object
+
+.method public <init> ()V
+    .code stack 0 locals 1
+        return
+    .end code
+.end method
+"#, r#"
+.class public final super java/lang/String
+.super java/lang/Object
+
+.field private final value [C
+.end field
+
+.method public native intern ()Ljava/lang/String;
+.end method
+"#, r#"
+.class public final super java/lang/Class
+.super java/lang/Object
+"#, r#"
+.class public final super java/lang/System
+.super java/lang/Object
+
+.method public static native exit (I)V
+.end method
+"#, r#"
+.class public interface abstract java/lang/Cloneable
+.super java/lang/Object
+"#, r#"
+.class public interface abstract java/io/Serializable
+.super java/lang/Object
+"#, r#"
+.class public super java/lang/Throwable
+.super java/lang/Object
+
+.field private detailMessage Ljava/lang/String;
+.end field
+
+.field private cause Ljava/lang/Throwable;
+.end field
+
+.field private causeSet Z
+.end field
+
+.field private stackTrace [Ljava/lang/StackTraceElement;
+.end field
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method java/lang/Object <init> ()V
+        aload_0
+        invokevirtual Method java/lang/Throwable fillInStackTrace ()Ljava/lang/Throwable;
+        pop
+        return
+    .end code
+.end method
+
+.method public native fillInStackTrace ()Ljava/lang/Throwable;
+.end method
+
+.method public getMessage ()Ljava/lang/String;
+    .code stack 1 locals 1
+        aload_0
+        getfield Field java/lang/Throwable detailMessage Ljava/lang/String;
+        areturn
+    .end code
+.end method
+
+.method public printStackTrace ()V
+    .code stack 0 locals 1
+        return
+    .end code
+.end method
+"#, r#"
+.class public final super java/lang/StackTraceElement
+.super java/lang/Object
+
+.field private declaringClass Ljava/lang/String;
+.end field
+
+.field private methodName Ljava/lang/String;
+.end field
+
+.field private fileName Ljava/lang/String;
+.end field
+
+.field private lineNumber I
+.end field
+"#];
+
+// Every throwable the tests and the interpreter throw, with its superclass
+const THROWABLES: &[(&str, &str)] = &[
+    ("java/lang/Exception", "java/lang/Throwable"),
+    ("java/lang/RuntimeException", "java/lang/Exception"),
+    ("java/lang/ArithmeticException", "java/lang/RuntimeException"),
+    ("java/lang/NullPointerException", "java/lang/RuntimeException"),
+    ("java/lang/Error", "java/lang/Throwable"),
+    ("java/lang/LinkageError", "java/lang/Error"),
+    ("java/lang/NoClassDefFoundError", "java/lang/LinkageError"),
+    ("java/lang/ExceptionInInitializerError", "java/lang/LinkageError"),
+    ("java/lang/IncompatibleClassChangeError", "java/lang/LinkageError"),
+    ("java/lang/NoSuchMethodError", "java/lang/IncompatibleClassChangeError"),
+    ("java/lang/NoSuchFieldError", "java/lang/IncompatibleClassChangeError"),
+    ("java/lang/AbstractMethodError", "java/lang/IncompatibleClassChangeError"),
+];
+
+fn throwable(name: &str, super_name: &str) -> String {
+    format!(r#"
+.class public super {0}
+.super {1}
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method {1} <init> ()V
+        return
+    .end code
+.end method
+"#, name, super_name)
+}
+
+// A class whose main runs `code`
+fn main_class(name: &str, code: &str) -> String {
+    format!(r#"
+.class public super {}
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 4 locals 4
+{}
+    .end code
+.end method
+"#, name, code)
+}
+
+// main exits with 3 when `code` throws `exception` and with 0 when it doesn't
+fn catching(name: &str, exception: &str, code: &str) -> String {
+    main_class(name, &format!(r#"
+    Try:
+{}
+        iconst_0
+        invokestatic Method java/lang/System exit (I)V
+        return
+    Catch:
+        iconst_3
+        invokestatic Method java/lang/System exit (I)V
+        return
+        .catch {} from Try to Catch using Catch
+"#, code, exception))
+}
+
+struct Fixtures(Vec<Vec<u1>>);
+
+impl ClassSource for Fixtures {
+    fn find_class(&self, name: &str) -> Result<Option<Vec<u1>>> {
+        for bytes in self.0.iter() {
+            if ClassFile::read_from(&bytes[..])?.name()? == name {
+                return Ok(Some(bytes.clone()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// The exit status and stderr of main of the class
+fn run(main: &str, sources: &[&str]) -> (i32, String) {
+    let throwables: Vec<String> = THROWABLES.iter().map(|&(name, super_name)| throwable(name, super_name)).collect();
+    let classes = FIXTURES.iter().cloned()
+        .chain(throwables.iter().map(String::as_str))
+        .chain(sources.iter().cloned())
+        .map(|source| jasm::assemble(source).unwrap())
+        .collect();
+    let mut class_path = ClassPath::new();
+    class_path.push(Fixtures(classes));
+    let mut vm = Vm::new(class_path);
+    let stderr = Output::default();
+    vm.set_stderr(stderr.clone());
+    let status = vm.run_main(main, &[]).unwrap();
+    let stderr = String::from_utf8(stderr.0.borrow().clone()).unwrap();
+    (status, stderr)
+}
+
+fn exit_status(main: &str, sources: &[&str]) -> i32 {
+    run(main, sources).0
+}
+
+// Virtual dispatch, a super call, a default method and a long argument taking two locals
+const CALLS: &[&str] = &[r#"
+.class public interface abstract Named
+.super java/lang/Object
+
+.method public id ()I
+    .code stack 1 locals 1
+        iconst_1
+        ireturn
+    .end code
+.end method
+"#, r#"
+.class public super Base
+.super java/lang/Object
+.implements Named
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method java/lang/Object <init> ()V
+        return
+    .end code
+.end method
+
+.method public value ()I
+    .code stack 1 locals 1
+        bipush 10
+        ireturn
+    .end code
+.end method
+
+.method public static twice (I)I
+    .code stack 2 locals 1
+        iload_0
+        iconst_2
+        imul
+        ireturn
+    .end code
+.end method
+"#, r#"
+.class public super Derived
+.super Base
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method Base <init> ()V
+        return
+    .end code
+.end method
+
+.method public value ()I
+    .code stack 2 locals 1
+        aload_0
+        invokespecial Method Base value ()I
+        iconst_5
+        iadd
+        ireturn
+    .end code
+.end method
+"#, r#"
+.class public super Calls
+.super java/lang/Object
+
+.method static add (JJ)J
+    .code stack 4 locals 4
+        lload_0
+        lload_2
+        ladd
+        lreturn
+    .end code
+.end method
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 4 locals 2
+        new Derived
+        dup
+        invokespecial Method Derived <init> ()V
+        astore_1
+        aload_1
+        invokevirtual Method Base value ()I
+        aload_1
+        invokeinterface InterfaceMethod Named id ()I 1
+        iadd
+        invokestatic Method Base twice (I)I
+        i2l
+        ldc2_w Long 10
+        invokestatic Method Calls add (JJ)J
+        l2i
+        invokestatic Method java/lang/System exit (I)V
+        return
+    .end code
+.end method
+"#];
+
+#[test]
+fn invocation_works() {
+    assert_eq!(exit_status("Calls", CALLS), 42);
+}
+
+const ABSTRACT: &str = r#"
+.class public interface abstract Runner
+.super java/lang/Object
+
+.method public abstract run ()V
+.end method
+"#;
+
+const LAZY: &str = r#"
+.class public super Lazy
+.super java/lang/Object
+.implements Runner
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method java/lang/Object <init> ()V
+        return
+    .end code
+.end method
+"#;
+
+#[test]
+fn bad_calls_throw() {
+    let missing = catching("Missing", "java/lang/NoSuchMethodError", "
+        invokestatic Method Missing missing ()V");
+    assert_eq!(exit_status("Missing", &[&missing]), 3);
+    let unimplemented = catching("Unimplemented", "java/lang/AbstractMethodError", "
+        new Lazy
+        dup
+        invokespecial Method Lazy <init> ()V
+        invokeinterface InterfaceMethod Runner run ()V 1");
+    assert_eq!(exit_status("Unimplemented", &[&unimplemented, ABSTRACT, LAZY]), 3);
+    let not_static = catching("NotStatic", "java/lang/IncompatibleClassChangeError", "
+        invokestatic Method Base value ()I");
+    assert_eq!(exit_status("NotStatic", &[&not_static, CALLS[0], CALLS[1]]), 3);
+}
+
+const FAILURE: &str = r#"
+.class public super Failure
+.super java/lang/RuntimeException
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method java/lang/RuntimeException <init> ()V
+        return
+    .end code
+.end method
+
+.method public static fail ()V
+    .code stack 2 locals 0
+        new Failure
+        dup
+        invokespecial Method Failure <init> ()V
+        athrow
+    .end code
+.end method
+"#;
+
+// The first handler doesn't match, the second one does. Then an exception the interpreter throws
+const THROWS: &str = r#"
+    Call:
+        invokestatic Method Failure fail ()V
+    Called:
+        iconst_2
+        invokestatic Method java/lang/System exit (I)V
+        return
+    NotError:
+        iconst_4
+        invokestatic Method java/lang/System exit (I)V
+        return
+    Caught:
+        pop
+    Divide:
+        iconst_1
+        iconst_0
+        idiv
+    Divided:
+        iconst_5
+        invokestatic Method java/lang/System exit (I)V
+        return
+    DividedByZero:
+        pop
+        bipush 7
+        invokestatic Method java/lang/System exit (I)V
+        return
+        .catch java/lang/Error from Call to Called using NotError
+        .catch java/lang/Exception from Call to Called using Caught
+        .catch java/lang/ArithmeticException from Divide to Divided using DividedByZero
+"#;
+
+#[test]
+fn exceptions_are_caught() {
+    assert_eq!(exit_status("Throws", &[&main_class("Throws", THROWS), FAILURE]), 7);
+}
+
+#[test]
+fn uncaught_exceptions_end_main() {
+    let uncaught = main_class("Uncaught", "
+        invokestatic Method Failure fail ()V
+        return");
+    let (status, stderr) = run("Uncaught", &[&uncaught, FAILURE]);
+    assert_eq!(status, 1);
+    assert!(stderr.starts_with("Exception in thread \"main\" "), "{:?}", stderr);
+}
+
+const BROKEN: &str = r#"
+.class public super Broken
+.super java/lang/Object
+
+.method static <clinit> ()V
+    .code stack 2 locals 0
+        iconst_1
+        iconst_0
+        idiv
+        pop
+        return
+    .end code
+.end method
+
+.method public static touch ()V
+    .code stack 0 locals 0
+        return
+    .end code
+.end method
+"#;
+
+// The first use wraps the ArithmeticException, the class can't be used after that
+const INITIALIZES: &str = r#"
+    First:
+        invokestatic Method Broken touch ()V
+    Firsted:
+        iconst_2
+        invokestatic Method java/lang/System exit (I)V
+        return
+    Wrapped:
+        pop
+    Second:
+        invokestatic Method Broken touch ()V
+    Seconded:
+        iconst_4
+        invokestatic Method java/lang/System exit (I)V
+        return
+    Erroneous:
+        pop
+        bipush 9
+        invokestatic Method java/lang/System exit (I)V
+        return
+        .catch java/lang/ExceptionInInitializerError from First to Firsted using Wrapped
+        .catch java/lang/NoClassDefFoundError from Second to Seconded using Erroneous
+"#;
+
+#[test]
+fn failing_initializers_throw() {
+    assert_eq!(exit_status("Initializes", &[&main_class("Initializes", INITIALIZES), BROKEN]), 9);
+}
+
+const HOLDERS: &[&str] = &[r#"
+.class public interface abstract Constants
+.super java/lang/Object
+
+.field public static final BASE I
+    .constantvalue Integer 5
+.end field
+"#, r#"
+.class public super Counter
+.super java/lang/Object
+
+.field public static count I
+.end field
+
+.method static <clinit> ()V
+    .code stack 1 locals 0
+        bipush 10
+        putstatic Field Counter count I
+        return
+    .end code
+.end method
+"#, r#"
+.class public super Holder
+.super java/lang/Object
+
+.field public x I
+.end field
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method java/lang/Object <init> ()V
+        return
+    .end code
+.end method
+"#, r#"
+.class public super SubHolder
+.super Holder
+.implements Constants
+
+.field public y J
+.end field
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method Holder <init> ()V
+        return
+    .end code
+.end method
+"#];
+
+// BASE through the implementing class, count after <clinit> set it and x through the subclass
+const FIELDS: &str = r#"
+        new SubHolder
+        dup
+        invokespecial Method SubHolder <init> ()V
+        astore_1
+        aload_1
+        iconst_3
+        putfield Field SubHolder x I
+        aload_1
+        ldc2_w Long 4
+        putfield Field SubHolder y J
+        getstatic Field Counter count I
+        iconst_1
+        iadd
+        putstatic Field Counter count I
+        getstatic Field SubHolder BASE I
+        getstatic Field Counter count I
+        iadd
+        aload_1
+        getfield Field Holder x I
+        iadd
+        aload_1
+        getfield Field SubHolder y J
+        l2i
+        iadd
+        invokestatic Method java/lang/System exit (I)V
+        return
+"#;
+
+#[test]
+fn fields_work() {
+    let mut sources = vec![main_class("Fields", FIELDS)];
+    sources.extend(HOLDERS.iter().map(|source| source.to_string()));
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    assert_eq!(exit_status("Fields", &sources), 23);
+}
+
+#[test]
+fn bad_field_refs_throw() {
+    let missing = catching("MissingField", "java/lang/NoSuchFieldError", "
+        getstatic Field Counter missing I
+        pop");
+    assert_eq!(exit_status("MissingField", &[&missing, HOLDERS[1]]), 3);
+    let not_static = catching("NotStaticField", "java/lang/IncompatibleClassChangeError", "
+        getstatic Field Holder x I
+        pop");
+    assert_eq!(exit_status("NotStaticField", &[&not_static, HOLDERS[2]]), 3);
+}
+
+const GREETER: &str = r#"
+.class public super Greeter
+.super java/lang/Object
+
+.field public static final GREETING Ljava/lang/String;
+    .constantvalue String "hello"
+.end field
+
+.method public static greeting ()Ljava/lang/String;
+    .code stack 1 locals 0
+        ldc String "hello"
+        areturn
+    .end code
+.end method
+
+.method public static mirror ()Ljava/lang/Class;
+    .code stack 1 locals 0
+        ldc Class Greeter
+        areturn
+    .end code
+.end method
+"#;
+
+// Literals and ConstantValue strings are interned, a message the interpreter made is only equal
+// to the literal once interned. Each failed check exits with its own status
+const CONSTANTS: &str = r#"
+        ldc String "hello"
+        invokestatic Method Greeter greeting ()Ljava/lang/String;
+        if_acmpne Literal
+        ldc String "hello"
+        getstatic Field Greeter GREETING Ljava/lang/String;
+        if_acmpne ConstantValue
+        ldc Class Greeter
+        invokestatic Method Greeter mirror ()Ljava/lang/Class;
+        if_acmpne Mirror
+        ldc Class "[I"
+        ldc Class Greeter
+        if_acmpeq Mirror
+    Divide:
+        iconst_1
+        iconst_0
+        idiv
+        pop
+    Divided:
+        return
+    DividedByZero:
+        invokevirtual Method java/lang/Throwable getMessage ()Ljava/lang/String;
+        astore_1
+        aload_1
+        ldc String "/ by zero"
+        if_acmpeq Interned
+        aload_1
+        invokevirtual Method java/lang/String intern ()Ljava/lang/String;
+        ldc String "/ by zero"
+        if_acmpne Interned
+        bipush 100
+        invokestatic Method java/lang/System exit (I)V
+        return
+    Literal:
+        iconst_2
+        invokestatic Method java/lang/System exit (I)V
+        return
+    ConstantValue:
+        iconst_3
+        invokestatic Method java/lang/System exit (I)V
+        return
+    Mirror:
+        iconst_4
+        invokestatic Method java/lang/System exit (I)V
+        return
+    Interned:
+        iconst_5
+        invokestatic Method java/lang/System exit (I)V
+        return
+        .catch java/lang/ArithmeticException from Divide to Divided using DividedByZero
+"#;
+
+#[test]
+fn constants_are_loaded() {
+    assert_eq!(exit_status("Constants", &[&main_class("Constants", CONSTANTS), GREETER]), 100);
+}
@@ -0,0 +1,182 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::process::Command;
+
+use class_file::bytecode::{self, Operand};
+use class_file::class_file::ClassFile;
+use class_file::constant_pool::CPInfoRep;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use jasm;
+use jdk::Jdk;
+use result::Error;
+use tests::read_fixture;
+
+// Version 49 so the JVM verifies it without a StackMapTable
+const SWITCHES: &str = r#"
+.version 49 0
+.class public super Switches
+.super java/lang/Object
+.implements java/lang/Runnable
+
+.method public <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method java/lang/Object <init> ()V
+        return
+    .end code
+.end method
+
+.method public run ()V
+    .code stack 2 locals 1
+        getstatic Field java/lang/System out Ljava/io/PrintStream;
+        ldc String "run"
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/String;)V
+        return
+    .end code
+.end method
+
+// 1 -> 10, 2 -> 20, 100 -> 1000, anything else -> -1
+.method static pick (I)I
+    .code stack 1 locals 1
+        iload_0
+        tableswitch 1 one two default other
+    one:
+        bipush 10
+        ireturn
+    two:
+        bipush 20
+        ireturn
+    other:
+        iload_0
+        lookupswitch 100 hundred default none
+    hundred:
+        sipush 1000
+        ireturn
+    none:
+        iconst_m1
+        ireturn
+    .end code
+.end method
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 3 locals 301
+        new Switches
+        dup
+        invokespecial Method Switches <init> ()V
+        invokeinterface InterfaceMethod java/lang/Runnable run ()V
+        iconst_0
+        wide istore 300
+        wide iinc 300 100
+        getstatic Field java/lang/System out Ljava/io/PrintStream;
+        wide iload 300
+        invokestatic Method Switches pick (I)I
+        invokevirtual Method java/io/PrintStream println (I)V
+    start:
+        aconst_null
+        athrow
+    end:
+        pop
+        getstatic Field java/lang/System out Ljava/io/PrintStream;
+        iconst_2
+        invokestatic Method Switches pick (I)I
+        invokevirtual Method java/io/PrintStream println (I)V
+        return
+        .catch java/lang/NullPointerException from start to end using end
+    .end code
+.end method
+"#;
+
+fn disassemble(class_file: &ClassFile) -> String {
+    let mut out = Vec::new();
+    jasm::disassemble(&mut out, class_file).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn round_trip_works() {
+    for name in &["Exceptions.class", "Constants.class", "Lambdas.class"] {
+        let bytes = read_fixture(name);
+        let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+        let source = disassemble(&class_file);
+        assert!(jasm::assemble(&source).unwrap() == bytes, "{} doesn't round trip", name);
+    }
+}
+
+// An unpaired surrogate, a supplementary character in standard UTF-8 and a text that looks like bytes
+const LOSSY: &str = ".class Lossy\n.const #1 = Utf8 0xeda080\n.const #2 = Utf8 0xf09f9880\n.const #3 = Utf8 \"0x41\"\n";
+
+#[test]
+fn lossy_utf8_round_trips() {
+    let bytes = jasm::assemble(LOSSY).unwrap();
+    let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    let utf8 = |index| match *class_file.constant_pool().get(index).unwrap() {
+        CPInfoRep::Utf8(ref utf8) => (utf8.as_str().to_string(), utf8.lossy_bytes().map(<[u8]>::to_vec)),
+        _ => panic!("#{} is not Utf8", index),
+    };
+    assert_eq!(utf8(1), ("\u{fffd}".to_string(), Some(vec![0xed, 0xa0, 0x80])));
+    assert_eq!(utf8(2), ("\u{1f600}".to_string(), Some(vec![0xf0, 0x9f, 0x98, 0x80])));
+    assert_eq!(utf8(3), ("0x41".to_string(), None));
+
+    let source = disassemble(&class_file);
+    assert!(source.contains(".const #1 = Utf8 0xeda080\n"));
+    assert!(source.contains(".const #3 = Utf8 \"0x41\"\n"));
+    assert_eq!(jasm::assemble(&source).unwrap(), bytes);
+}
+
+#[test]
+fn assembler_works() {
+    let bytes = jasm::assemble(SWITCHES).unwrap();
+    let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    assert_eq!(class_file.name().unwrap(), "Switches");
+    assert_eq!(class_file.interface_names().unwrap(), vec!["java/lang/Runnable"]);
+    // Symbolic references share their entries
+    let print_streams = class_file.constant_pool().iter()
+        .filter(|&(_, cp_info)| match cp_info {
+            CPInfoRep::Utf8(utf8) => utf8.as_str() == "java/io/PrintStream",
+            _ => false,
+        })
+        .count();
+    assert_eq!(print_streams, 1);
+
+    let pick = class_file.find_method("pick", "(I)I").unwrap().unwrap();
+    let instructions = bytecode::decode(pick.get_code_attribute().unwrap().code()).unwrap();
+    assert_eq!(instructions[1].operand, Operand::TableSwitch {
+        default: 30,
+        low: 1,
+        high: 2,
+        targets: vec![24, 27].into_boxed_slice(),
+    });
+    let main = class_file.get_main_method().unwrap();
+    let instructions = bytecode::decode(main.get_code_attribute().unwrap().code()).unwrap();
+    match instructions[3].operand {
+        Operand::InvokeInterface { count, .. } => assert_eq!(count, 1),
+        ref operand => panic!("{:?}", operand),
+    }
+    assert!(instructions[5].wide);
+    assert_eq!(jasm::assemble(&disassemble(&class_file)).unwrap(), bytes);
+
+    match jasm::assemble(".class Broken\n.method m ()V\n.code stack 0 locals 0\ngoto nowhere\n.end code\n.end method") {
+        Err(Error::Syntax(4, ref message)) => assert_eq!(message, "undefined label nowhere"),
+        result => panic!("{:?}", result.map(|_| ())),
+    }
+    match jasm::assemble(".class Broken\n.const #1 = Long 1\n.const #2 = Integer 2") {
+        Err(Error::Syntax(3, _)) => (),
+        result => panic!("{:?}", result.map(|_| ())),
+    }
+
+    let java = match Jdk::locate() {
+        Some(jdk) => jdk.home().join("bin").join("java"),
+        None => {
+            eprintln!("no JDK found, skipping running the assembled class");
+            return;
+        },
+    };
+    let dir = env::temp_dir().join(format!("jvm_rs_jasm_{}", ::std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    File::create(dir.join("Switches.class")).unwrap().write_all(&bytes).unwrap();
+    let output = Command::new(java).arg("-cp").arg(&dir).arg("Switches").output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "run\n1000\n20\n");
+}
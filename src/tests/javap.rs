@@ -0,0 +1,69 @@
+use class_file::bytecode::{self, Instruction, Operand};
+use class_file::class_file::ClassFile;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use javap::{self, Options};
+use result::Error;
+use tests::read_fixture;
+
+fn disassemble(name: &str, options: &Options) -> String {
+    let bytes = read_fixture(name);
+    let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    let mut out = Vec::new();
+    javap::disassemble(&mut out, &class_file, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn bytecode_works() {
+    // iload_0, tableswitch 1..2, wide iinc 300 -1, goto 0
+    let code = [
+        0x1a,
+        0xaa, 0, 0,
+        0, 0, 0, 29,
+        0, 0, 0, 1,
+        0, 0, 0, 2,
+        0, 0, 0, 23,
+        0, 0, 0, 23,
+        0xc4, 0x84, 0x01, 0x2c, 0xff, 0xff,
+        0xa7, 0xff, 0xe2,
+    ];
+    let instructions = bytecode::decode(&code).unwrap();
+    assert_eq!(instructions.len(), 4);
+    assert_eq!(instructions[1].operand, Operand::TableSwitch {
+        default: 30,
+        low: 1,
+        high: 2,
+        targets: vec![24, 24].into_boxed_slice(),
+    });
+    assert_eq!(instructions[2], Instruction {
+        pc: 24,
+        opcode: 0x84,
+        wide: true,
+        operand: Operand::Iinc { index: 300, delta: -1 },
+    });
+    assert_eq!(instructions[3].mnemonic(), "goto");
+    assert_eq!(instructions[3].operand, Operand::Branch(0));
+
+    match bytecode::decode(&[0x1a, 0xcb]) {
+        Err(Error::InvalidOpcode(0xcb)) => (),
+        result => panic!("{:?}", result),
+    }
+    assert!(bytecode::decode(&[0x11, 0x00]).is_err());
+}
+
+#[test]
+fn javap_works() {
+    let output = disassemble("Exceptions.class", &Options { code: true, ..Options::default() });
+    assert!(output.starts_with("Compiled from \"Exceptions.java\"\nclass Exceptions {\n"));
+    assert!(output.contains("  static int parse(java.lang.String);\n"));
+    assert!(output.contains("       1: invokestatic  #7                  // Method java/lang/Integer.parseInt:(Ljava/lang/String;)I\n"));
+    assert!(output.contains("           0     5    15   Class java/lang/NumberFormatException\n"));
+    assert!(output.contains("          15    18    28   any\n"));
+
+    let output = disassemble("Lambdas.class", &Options { verbose: true, private: true, ..Options::default() });
+    assert!(output.contains("  major version: 55\n"));
+    assert!(output.contains("   #7 = InvokeDynamic      #0:#8          // #0:get:()Ljava/util/function/Supplier;\n"));
+    assert!(output.contains("  private static java.lang.String lambda$greet$0();\n"));
+    assert!(output.contains("      #43 REF_invokeStatic Lambdas.lambda$greet$0:()Ljava/lang/String;\n"));
+}
@@ -0,0 +1,31 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+
+use jdk::{ClassLibrary, Jdk};
+
+#[test]
+fn jdk_works() {
+    let home = env::temp_dir().join(format!("jvm_rs_fake_jdk_{}", std::process::id()));
+    fs::create_dir_all(home.join("lib")).unwrap();
+    assert!(Jdk::from_home(&home).is_none());
+
+    File::create(home.join("lib").join("modules")).unwrap();
+    File::create(home.join("release")).unwrap()
+        .write_all(b"IMPLEMENTOR=\"Test\"\nJAVA_VERSION=\"17.0.15\"\n").unwrap();
+    let jdk = Jdk::from_home(&home).unwrap();
+    assert_eq!(jdk.class_library(), &ClassLibrary::Modules(home.join("lib").join("modules")));
+    assert_eq!(jdk.version(), Some("17.0.15"));
+    assert_eq!(jdk.feature_version(), Some(17));
+
+    fs::remove_file(home.join("lib").join("modules")).unwrap();
+    fs::create_dir_all(home.join("jre").join("lib")).unwrap();
+    File::create(home.join("jre").join("lib").join("rt.jar")).unwrap();
+    File::create(home.join("release")).unwrap()
+        .write_all(b"JAVA_VERSION=\"1.8.0_292\"\n").unwrap();
+    let jdk = Jdk::from_home(&home).unwrap();
+    assert_eq!(jdk.class_library(), &ClassLibrary::RtJar(home.join("jre").join("lib").join("rt.jar")));
+    assert_eq!(jdk.feature_version(), Some(8));
+
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,98 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use class_file::class_file::ClassFile;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use class_path::{ClassPath, ClassSource, Directory};
+use class_path::jimage::JImage;
+use jdk::{ClassLibrary, Jdk};
+use result::Error;
+use tests::fixtures;
+
+fn modules() -> Option<JImage> {
+    match Jdk::locate().as_ref().map(Jdk::class_library) {
+        Some(ClassLibrary::Modules(path)) => Some(JImage::open(path).unwrap()),
+        _ => {
+            eprintln!("no JDK 9+ found, skipping jimage test");
+            None
+        },
+    }
+}
+
+#[test]
+fn jimage_works() {
+    let image = match modules() {
+        Some(image) => image,
+        None => return,
+    };
+    let entry = image.find_in_module("java.base", "java/lang/Object.class").unwrap().unwrap();
+    assert_eq!(entry.name(), "/java.base/java/lang/Object.class");
+    assert!(image.find_in_module("java.base", "java/lang/NoSuchClass.class").unwrap().is_none());
+    assert!(image.entries().unwrap().contains(&entry));
+
+    let bytes = image.read(&entry).unwrap();
+    let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    assert_eq!(class_file.name().unwrap(), "java/lang/Object");
+
+    let module_info = image.find_in_module("java.base", "module-info.class").unwrap().unwrap();
+    let bytes = image.read(&module_info).unwrap();
+    ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+
+    assert_eq!(image.module_of("java/sql/Connection").unwrap(), Some("java.sql".to_string()));
+}
+
+#[test]
+fn class_path_works() {
+    let mut class_path = ClassPath::new();
+    class_path.push(Directory::new(fixtures()));
+    if let Some(image) = modules() {
+        class_path.push(image);
+        let bytes = class_path.find_class("java/lang/String").unwrap().unwrap();
+        let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+        assert_eq!(class_file.super_name().unwrap(), "java/lang/Object");
+    }
+    assert!(class_path.find_class("Exceptions").unwrap().is_some());
+    assert!(class_path.find_class("NoSuchClass").unwrap().is_none());
+}
+
+
+// A little endian image with one slot: header, redirect, offset, locations and strings
+fn write_image(name: &str, table_length: u32, redirect: i32, locations_size: u32, strings_size: u32) -> PathBuf {
+    let mut bytes = Vec::new();
+    for value in &[0xCAFE_DADA, 1 << 16, 0, 1, table_length, locations_size, strings_size] {
+        bytes.extend_from_slice(&u32::to_le_bytes(*value));
+    }
+    bytes.extend_from_slice(&redirect.to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    // A location without attributes and the empty string
+    bytes.extend_from_slice(&[0, 0]);
+    let path = env::temp_dir().join(format!("jvm_rs_{}_{}.jimage", name, ::std::process::id()));
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn broken_jimage_fails_cleanly() {
+    let open = |name, table_length, redirect, locations_size, strings_size| {
+        let path = write_image(name, table_length, redirect, locations_size, strings_size);
+        let image = JImage::open(&path);
+        fs::remove_file(&path).unwrap();
+        image
+    };
+    // Sizes past the end of the file aren't allocated
+    assert!(matches!(open("strings", 1, 0, 1, u32::MAX), Err(Error::OutOfBounds(_))));
+    assert!(matches!(open("table", u32::MAX, 0, 1, 1), Err(Error::OutOfBounds(_))));
+
+    let image = open("valid", 1, 0, 1, 1).unwrap();
+    assert!(image.find("/a/b.class").unwrap().is_none());
+    assert_eq!(image.entries().unwrap()[0].name(), "");
+    // Redirects to a slot that isn't there
+    for &redirect in &[-2, i32::MIN] {
+        let image = open("redirect", 1, redirect, 1, 1).unwrap();
+        assert!(matches!(image.find("/a/b.class"), Err(Error::OutOfBounds(_))));
+    }
+    let image = open("redirect", 1, -1, 1, 1).unwrap();
+    assert!(image.find("/a/b.class").unwrap().is_none());
+}
@@ -0,0 +1,81 @@
+use class_file::class_file::ClassFile;
+use class_file::constant_pool::CPInfoRep;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use jasm;
+use json;
+use result::Error;
+use tests::read_fixture;
+
+fn export(class_file: &ClassFile) -> String {
+    let mut out = Vec::new();
+    json::export(&mut out, class_file).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn export_works() {
+    for name in &["Exceptions.class", "Constants.class", "Lambdas.class", "Test.class"] {
+        let bytes = read_fixture(name);
+        let text = export(&ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap());
+        assert!(json::import(&text).unwrap() == bytes, "{} doesn't round trip", name);
+    }
+
+    let text = export(&ClassFile::from_bytes(&PoSlice::new(&read_fixture("Constants.class"))).unwrap());
+    assert!(text.contains(r#"{"index": 7, "tag": "String", "string_index": 8, "value": "nul\u0000"},"#));
+    assert!(text.contains(r#"{"index": 22, "tag": "Long", "value": 78187493530},"#));
+    assert!(text.contains(r#"{"index": 30, "tag": "Float", "value": 0.5},"#));
+    assert!(text.contains(r#""this_class": {"index": 17, "name": "Constants"},"#));
+
+    // JSON has no NaN or infinities
+    let source = ".class Floats\n.const #1 = Float 0x7fc00001\n.const #2 = Double -inf\n";
+    let bytes = jasm::assemble(source).unwrap();
+    let text = export(&ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap());
+    assert!(text.contains(r#"{"index": 1, "tag": "Float", "value": "0x7fc00001"},"#));
+    assert!(text.contains(r#"{"index": 2, "tag": "Double", "value": "-Infinity"},"#));
+    assert_eq!(json::import(&text).unwrap(), bytes);
+}
+
+#[test]
+fn lossy_utf8_round_trips() {
+    let bytes = jasm::assemble(".class Lossy\n.const #1 = Utf8 0xeda080\n").unwrap();
+    let text = export(&ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap());
+    assert!(text.contains(r#"{"index": 1, "tag": "Utf8", "bytes": "eda080"},"#));
+    assert_eq!(json::import(&text).unwrap(), bytes);
+}
+
+#[test]
+fn import_works() {
+    let bytes = read_fixture("Constants.class");
+    let text = export(&ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap());
+
+    // Only the indexes and values are read, the resolved names are left alone
+    let edited = text
+        .replace(r#""tag": "Integer", "value": 100000"#, r#""tag": "Integer", "value": 7"#)
+        .replace(r#""this_class": {"index": 17, "name": "Constants"}"#, r#""this_class": {"index": 17}"#);
+    let class_file = json::import_class(&edited).unwrap();
+    assert_eq!(class_file.name().unwrap(), "Constants");
+    match *class_file.constant_pool().get(33).unwrap() {
+        CPInfoRep::Integer(integer) => assert_eq!(integer.value(), 7),
+        _ => panic!("expected an Integer"),
+    }
+
+    match json::import(&text.replacen(r#""max_stack": "#, r#""stack": "#, 1)) {
+        Err(Error::Json(ref path, ref message)) => {
+            assert_eq!(path, "$.methods[0].attributes[0]");
+            assert_eq!(message, "missing max_stack");
+        },
+        result => panic!("{:?}", result.map(|_| ())),
+    }
+    match json::import(&text.replace(r#""value": 100000"#, r#""value": 1e5"#)) {
+        Err(Error::Json(ref path, ref message)) => {
+            assert_eq!(path, "$.constant_pool[30].value");
+            assert_eq!(message, "expected an integer");
+        },
+        result => panic!("{:?}", result.map(|_| ())),
+    }
+    match json::import("{\n  \"schema\": 1,\n  \"minor_version\": 0 \"major_version\": 52\n}") {
+        Err(Error::Syntax(3, _)) => (),
+        result => panic!("{:?}", result.map(|_| ())),
+    }
+}
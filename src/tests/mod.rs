@@ -1,3 +1,46 @@
-#[cfg(test)]
-
-mod class_file;
\ No newline at end of file
+#[cfg(test)]
+mod class_file;
+#[cfg(test)]
+mod descriptor;
+#[cfg(test)]
+mod jdk;
+#[cfg(test)]
+mod jimage;
+#[cfg(test)]
+mod javap;
+#[cfg(test)]
+mod jasm;
+#[cfg(test)]
+mod json;
+#[cfg(test)]
+mod view;
+#[cfg(test)]
+mod stream;
+#[cfg(test)]
+mod error;
+#[cfg(test)]
+mod trace;
+#[cfg(test)]
+mod property;
+#[cfg(test)]
+mod corpus;
+#[cfg(test)]
+mod interpreter;
+#[cfg(test)]
+mod vm;
+
+#[cfg(test)]
+use std::fs;
+#[cfg(test)]
+use std::path::PathBuf;
+
+// Directory of the checked in .java and .class files the tests read
+#[cfg(test)]
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests/source_files")
+}
+
+#[cfg(test)]
+fn read_fixture(name: &str) -> Vec<u8> {
+    fs::read(fixtures().join(name)).unwrap()
+}
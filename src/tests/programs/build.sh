@@ -0,0 +1,23 @@
+#!/bin/sh
+# Rebuilds classes/ from java/ and records what the JDK's java prints for every program: stdout in
+# expected/<Program>.out, stderr in .err and the exit status in .status. Needs a JDK 17 on the PATH and
+# is only run by hand, the tests read what is checked in. The programs are compiled for Java 8, those in
+# CURRENT for the JDK's own release.
+set -e
+cd "$(dirname "$0")"
+rm -rf classes expected
+mkdir expected
+CURRENT="Nestmates Lambdas Concat Concurrency"
+for source in java/*.java; do
+    case " $CURRENT " in
+        *" $(basename "$source" .java) "*) javac -encoding UTF-8 -d classes "$source" ;;
+        *) javac -encoding UTF-8 --release 8 -d classes "$source" ;;
+    esac
+done
+for source in java/*.java; do
+    program=$(basename "$source" .java)
+    status=0
+    java -Dfile.encoding=UTF-8 -Dstdout.encoding=UTF-8 -Dstderr.encoding=UTF-8 -cp classes "$program" \
+        > "expected/$program.out" 2> "expected/$program.err" || status=$?
+    echo $status > "expected/$program.status"
+done
@@ -0,0 +1,55 @@
+public class Basics {
+    static int fib(int n) {
+        return n < 2 ? n : fib(n - 1) + fib(n - 2);
+    }
+
+    static String day(int day) {
+        switch (day) {
+            case 0: return "Sunday";
+            case 6: return "Saturday";
+            case 1000: return "far";
+            default: return "weekday";
+        }
+    }
+
+    static String fruit(String name) {
+        switch (name) {
+            case "apple": return "red";
+            case "banana": return "yellow";
+            default: return "unknown";
+        }
+    }
+
+    public static void main(String[] args) {
+        System.out.println("Hello, " + (args.length > 0 ? args[0] : "world") + "!");
+        System.out.println(fib(20));
+        System.out.println(day(0) + " " + day(3) + " " + day(1000) + " " + fruit("banana") + " " + fruit("kiwi"));
+        int sum = 0;
+        for (int i = 0; i < 1000; i++) {
+            if (i % 3 == 0 || i % 5 == 0) {
+                sum += i;
+            }
+        }
+        System.out.println(sum);
+        int[] counts = new int[5];
+        for (char c : "abracadabra".toCharArray()) {
+            counts[c - 'a' < 5 ? c - 'a' : 4]++;
+        }
+        for (int i = 0; i < counts.length; i++) {
+            System.out.print(counts[i] + (i < counts.length - 1 ? "," : "\n"));
+        }
+        long[][] grid = new long[3][4];
+        grid[2][3] = 42L;
+        int[][][] cube = new int[2][3][];
+        System.out.println(grid.length + " " + grid[2].length + " " + grid[2][3] + " " + cube[1].length + " " + cube[1][2]);
+        boolean flag = true;
+        char letter = 'x';
+        byte small = (byte) 200;
+        short medium = (short) 70000;
+        System.out.println(flag + " " + letter + " " + small + " " + medium + " " + (char) (letter + 1));
+        synchronized (Basics.class) {
+            System.out.println("synchronized");
+        }
+        System.err.println("to stderr");
+    }
+}
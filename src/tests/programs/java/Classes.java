@@ -0,0 +1,123 @@
+import java.util.Arrays;
+
+public class Classes {
+    interface Shape {
+        double area();
+
+        default String describe() {
+            return getClass().getSimpleName() + " of area " + area();
+        }
+    }
+
+    static abstract class Base implements Shape, Comparable<Base> {
+        static int created;
+        final int id;
+
+        Base() {
+            id = ++created;
+        }
+
+        public int compareTo(Base other) {
+            return Double.compare(area(), other.area());
+        }
+
+        public String toString() {
+            return "#" + id + " " + describe();
+        }
+    }
+
+    static class Square extends Base {
+        private final double side;
+
+        Square(double side) {
+            this.side = side;
+        }
+
+        public double area() {
+            return side * side;
+        }
+    }
+
+    static class Circle extends Base implements Cloneable {
+        double radius;
+
+        Circle(double radius) {
+            this.radius = radius;
+        }
+
+        public double area() {
+            return 3 * radius * radius;
+        }
+
+        public String describe() {
+            return "round " + super.describe();
+        }
+
+        public Circle clone() {
+            try {
+                return (Circle) super.clone();
+            } catch (CloneNotSupportedException e) {
+                throw new AssertionError(e);
+            }
+        }
+    }
+
+    enum Planet {
+        MERCURY(3.303e+23), EARTH(5.976e+24);
+
+        final double mass;
+
+        Planet(double mass) {
+            this.mass = mass;
+        }
+    }
+
+    static class Counter {
+        static final String PREFIX = "count";
+        static int value = init();
+
+        static int init() {
+            System.out.println("Counter initialized");
+            return 10;
+        }
+    }
+
+    class Inner {
+        int outer() {
+            return field;
+        }
+    }
+
+    int field = 7;
+
+    public static void main(String[] args) {
+        Base[] shapes = { new Square(3), new Circle(1), new Square(1) };
+        Arrays.sort(shapes);
+        for (Shape shape : shapes) {
+            System.out.println(shape);
+        }
+        Circle circle = new Circle(2);
+        Circle copy = circle.clone();
+        copy.radius = 5;
+        System.out.println(circle.radius + " " + copy.radius + " " + (copy.id == circle.id) + " " + Base.created);
+        System.out.println(Planet.EARTH + " " + Planet.valueOf("MERCURY").ordinal() + " " + Planet.values().length + " " + Planet.EARTH.mass + " " + Planet.EARTH.compareTo(Planet.MERCURY));
+        System.out.println(Counter.PREFIX);
+        System.out.println("before " + Counter.value);
+        System.out.println(new Classes().new Inner().outer());
+        Object o = circle;
+        System.out.println((o instanceof Shape) + " " + (o instanceof Square) + " " + (o instanceof Cloneable) + " " + o.getClass().getName() + " " + o.getClass().getSuperclass().getName());
+        System.out.println(int[].class.getName() + " " + String[][].class.getName() + " " + int.class + " " + Runnable.class + " " + new Object[0].getClass().getComponentType());
+        System.out.println(Shape.class.isInstance(o) + " " + Base.class.isAssignableFrom(Square.class) + " " + Integer.TYPE + " " + Planet.class.isEnum());
+        int[] numbers = {5, 3, 1};
+        int[] cloned = numbers.clone();
+        cloned[0] = 9;
+        System.out.println(Arrays.toString(numbers) + " " + Arrays.toString(cloned) + " " + Arrays.equals(numbers, cloned) + " " + Arrays.hashCode(numbers));
+        Runnable anonymous = new Runnable() {
+            public void run() {
+                System.out.println("anonymous " + getClass().getName());
+            }
+        };
+        anonymous.run();
+        System.out.println(new Object().equals(null) + " " + (new Object().hashCode() == new Object().hashCode()));
+    }
+}
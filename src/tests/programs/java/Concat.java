@@ -0,0 +1,48 @@
+// String concatenation of every type, linked by invokedynamic through
+// StringConcatFactory.makeConcatWithConstants. Compiled for the JDK's own release
+public class Concat {
+    static class Point {
+        final int x;
+        final int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+
+        public String toString() {
+            return "(" + x + ", " + y + ")";
+        }
+    }
+
+    public static void main(String[] args) {
+        boolean flag = true;
+        byte b = -8;
+        short s = 300;
+        char c = 'c';
+        int i = 42;
+        long l = 1L << 40;
+        float f = 1.5f;
+        double d = 0.1 + 0.2;
+        String text = "text";
+        String none = null;
+        Object object = new Point(1, 2);
+        Object nothing = null;
+
+        System.out.println("flag=" + flag + " b=" + b + " s=" + s + " c=" + c + " i=" + i);
+        System.out.println("l=" + l + " f=" + f + " d=" + d);
+        System.out.println(text + none + object + nothing);
+        System.out.println(i + l + " adds first, then " + i + l);
+        System.out.println("" + c + c + (char) (c + 1));
+        System.out.println(f + "" + d);
+        System.out.println("tags \u0001 and \u0002 are constants: " + i);
+        String accumulated = "";
+        for (int n = 0; n < 5; n++) {
+            accumulated += n;
+        }
+        System.out.println(accumulated);
+        accumulated += '!';
+        System.out.println(accumulated + Double.NaN + Float.NEGATIVE_INFINITY + Long.MIN_VALUE);
+        System.out.println("nested " + ("inner " + i) + " done");
+    }
+}
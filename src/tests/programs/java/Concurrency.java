@@ -0,0 +1,303 @@
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.VarHandle;
+import java.util.concurrent.atomic.AtomicBoolean;
+import java.util.concurrent.atomic.AtomicInteger;
+import java.util.concurrent.atomic.AtomicLong;
+import java.util.concurrent.atomic.AtomicReference;
+import java.util.concurrent.locks.Condition;
+import java.util.concurrent.locks.LockSupport;
+import java.util.concurrent.locks.ReentrantLock;
+
+public class Concurrency {
+    static final AtomicInteger counter = new AtomicInteger();
+    static final AtomicLong total = new AtomicLong(10);
+    static final AtomicBoolean claimed = new AtomicBoolean();
+    static final AtomicInteger winners = new AtomicInteger();
+    static final AtomicReference<Node> stack = new AtomicReference<Node>();
+    static final ReentrantLock lock = new ReentrantLock();
+    static int guarded;
+
+    static class Node {
+        final int value;
+        final Node next;
+
+        Node(int value, Node next) {
+            this.value = value;
+            this.next = next;
+        }
+    }
+
+    static class Worker extends Thread {
+        final int id;
+
+        Worker(int id) {
+            super("worker-" + id);
+            this.id = id;
+        }
+
+        public void run() {
+            for (int i = 0; i < 2000; i++) {
+                counter.incrementAndGet();
+                total.addAndGet(i);
+                lock.lock();
+                try {
+                    guarded++;
+                    if (i % 500 == 0) {
+                        Thread.yield();
+                    }
+                } finally {
+                    lock.unlock();
+                }
+                if (i % 100 == 0) {
+                    Node head;
+                    do {
+                        head = stack.get();
+                    } while (!stack.compareAndSet(head, new Node(id * 10000 + i, head)));
+                }
+            }
+            if (claimed.compareAndSet(false, true)) {
+                winners.incrementAndGet();
+            }
+        }
+    }
+
+    // A buffer of two slots between a producer and a consumer
+    static class Buffer {
+        final ReentrantLock lock = new ReentrantLock();
+        final Condition notFull = lock.newCondition();
+        final Condition notEmpty = lock.newCondition();
+        final int[] items = new int[2];
+        int count;
+        int put;
+        int take;
+
+        void put(int item) throws InterruptedException {
+            lock.lock();
+            try {
+                while (count == items.length) {
+                    notFull.await();
+                }
+                items[put] = item;
+                put = (put + 1) % items.length;
+                count++;
+                notEmpty.signal();
+            } finally {
+                lock.unlock();
+            }
+        }
+
+        int take() throws InterruptedException {
+            lock.lock();
+            try {
+                while (count == 0) {
+                    notEmpty.await();
+                }
+                int item = items[take];
+                take = (take + 1) % items.length;
+                count--;
+                notFull.signal();
+                return item;
+            } finally {
+                lock.unlock();
+            }
+        }
+    }
+
+    static class Point {
+        volatile int x;
+        long y;
+        final String name = "p";
+        static short z;
+    }
+
+    static long consumed;
+
+    public static void main(String[] args) throws Exception {
+        Thread[] workers = new Thread[4];
+        for (int i = 0; i < workers.length; i++) {
+            workers[i] = new Worker(i);
+            workers[i].start();
+        }
+        for (Thread worker : workers) {
+            worker.join();
+        }
+        int nodes = 0;
+        long values = 0;
+        for (Node node = stack.get(); node != null; node = node.next) {
+            nodes++;
+            values += node.value;
+        }
+        System.out.println(counter + " " + total + " " + guarded + " " + claimed + " " + winners + " " + nodes + " " + values);
+        System.out.println(counter.getAndSet(5) + " " + counter.compareAndSet(4, 1) + " " + counter.compareAndSet(5, 1) + " " + counter.decrementAndGet()
+            + " " + counter.getAndAdd(3) + " " + counter.longValue() + " " + total.getAndIncrement() + " " + total.doubleValue() + " " + claimed.getAndSet(false));
+
+        lock.lock();
+        lock.lock();
+        System.out.println(lock.getHoldCount() + " " + lock.isLocked() + " " + lock.isHeldByCurrentThread() + " " + lock.isFair()
+            + " " + lock.toString().endsWith("[Locked by thread main]"));
+        final boolean[] tried = new boolean[2];
+        Thread other = new Thread(new Runnable() {
+            public void run() {
+                tried[0] = lock.tryLock();
+                tried[1] = lock.isHeldByCurrentThread();
+                try {
+                    lock.unlock();
+                } catch (IllegalMonitorStateException e) {
+                    System.out.println("unlock by another thread: " + e.getClass().getName());
+                }
+            }
+        });
+        other.start();
+        other.join();
+        lock.unlock();
+        lock.unlock();
+        System.out.println(tried[0] + " " + tried[1] + " " + lock.getHoldCount() + " " + lock.isLocked() + " " + lock.toString().endsWith("[Unlocked]")
+            + " " + lock.hasQueuedThreads());
+        try {
+            lock.unlock();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println("unlock without holding: " + e.getClass().getName());
+        }
+
+        final Buffer buffer = new Buffer();
+        Thread consumer = new Thread(new Runnable() {
+            public void run() {
+                try {
+                    for (int i = 0; i < 100; i++) {
+                        consumed += buffer.take();
+                    }
+                } catch (InterruptedException e) {
+                    System.out.println("consumer interrupted");
+                }
+            }
+        });
+        consumer.start();
+        for (int i = 1; i <= 100; i++) {
+            buffer.put(i);
+        }
+        consumer.join();
+        System.out.println("consumed " + consumed + " " + buffer.count);
+
+        final ReentrantLock waitLock = new ReentrantLock();
+        final Condition never = waitLock.newCondition();
+        waitLock.lock();
+        try {
+            System.out.println("awaitNanos " + (never.awaitNanos(1000000) <= 0) + " " + waitLock.getHoldCount());
+            Thread.currentThread().interrupt();
+            never.await();
+        } catch (InterruptedException e) {
+            System.out.println("await interrupted " + waitLock.isHeldByCurrentThread() + " " + Thread.currentThread().isInterrupted());
+        } finally {
+            waitLock.unlock();
+        }
+        try {
+            never.signal();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println("signal without holding: " + e.getClass().getName());
+        }
+        Thread waiter = new Thread(new Runnable() {
+            public void run() {
+                waitLock.lock();
+                try {
+                    never.await();
+                    System.out.println("waiter signalled");
+                } catch (InterruptedException e) {
+                    System.out.println("waiter interrupted");
+                } finally {
+                    waitLock.unlock();
+                }
+            }
+        });
+        waiter.start();
+        waiter.interrupt();
+        waiter.join();
+        Thread locker = new Thread(new Runnable() {
+            public void run() {
+                try {
+                    waitLock.lockInterruptibly();
+                    System.out.println("locked");
+                } catch (InterruptedException e) {
+                    System.out.println("lockInterruptibly interrupted " + Thread.currentThread().isInterrupted());
+                }
+            }
+        });
+        waitLock.lock();
+        locker.start();
+        locker.interrupt();
+        locker.join();
+        waitLock.unlock();
+
+        LockSupport.unpark(Thread.currentThread());
+        LockSupport.park();
+        System.out.println("unpark before park");
+        LockSupport.parkNanos(1000000);
+        System.out.println("parkNanos");
+        LockSupport.parkUntil(System.currentTimeMillis() - 1);
+        System.out.println("parkUntil");
+        final boolean[] woken = new boolean[1];
+        Thread parker = new Thread(new Runnable() {
+            public void run() {
+                while (!woken[0]) {
+                    LockSupport.park();
+                }
+                System.out.println("unparked");
+            }
+        });
+        parker.start();
+        Thread.sleep(10);
+        woken[0] = true;
+        LockSupport.unpark(parker);
+        parker.join();
+        Thread interrupted = new Thread(new Runnable() {
+            public void run() {
+                LockSupport.park();
+                System.out.println("park interrupted " + Thread.currentThread().isInterrupted());
+            }
+        });
+        interrupted.start();
+        interrupted.interrupt();
+        interrupted.join();
+
+        MethodHandles.Lookup lookup = MethodHandles.lookup();
+        VarHandle x = lookup.findVarHandle(Point.class, "x", int.class);
+        VarHandle y = lookup.findVarHandle(Point.class, "y", long.class);
+        VarHandle z = lookup.findStaticVarHandle(Point.class, "z", short.class);
+        VarHandle name = lookup.findVarHandle(Point.class, "name", String.class);
+        VarHandle longs = MethodHandles.arrayElementVarHandle(long[].class);
+        Point p = new Point();
+        x.set(p, 5);
+        boolean swapped = x.compareAndSet(p, 5, 7);
+        boolean missed = x.compareAndSet(p, 5, 9);
+        int added = (int) x.getAndAdd(p, 3);
+        int ored = (int) x.getAndBitwiseOr(p, 0x100);
+        int witness = (int) x.compareAndExchange(p, 1, 2);
+        System.out.println(swapped + " " + missed + " " + added + " " + ored + " " + witness + " " + (int) x.getVolatile(p) + " " + p.x);
+        y.setRelease(p, 40L);
+        System.out.println((long) y.getAndAdd(p, 2L) + " " + (long) y.getAcquire(p) + " " + (long) y.getAndSet(p, -1L) + " " + p.y);
+        z.set((short) 32767);
+        System.out.println((short) z.getAndAdd((short) 1) + " " + (short) z.get() + " " + Point.z);
+        System.out.println((String) name.get(p) + " " + x.varType() + " " + x + " " + z + " " + longs);
+        try {
+            name.set(p, "q");
+        } catch (UnsupportedOperationException e) {
+            System.out.println("set final: " + e.getClass().getName());
+        }
+        long[] array = new long[3];
+        longs.set(array, 1, 10L);
+        System.out.println((long) longs.getAndAdd(array, 1, 5L) + " " + longs.compareAndSet(array, 2, 0L, 4L) + " " + array[0] + " " + array[1] + " " + array[2]);
+        try {
+            longs.get(array, 3);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.out.println(e);
+        }
+        try {
+            MethodHandles.arrayElementVarHandle(int.class);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e);
+        }
+        VarHandle.fullFence();
+        VarHandle.acquireFence();
+        VarHandle.releaseFence();
+        System.out.println("fenced");
+    }
+}
@@ -0,0 +1,51 @@
+// Default methods, the most specific one of the superinterfaces runs, JVMS §5.4.3.3 and §5.4.6
+public class Defaults {
+    interface J {
+        default String m() { return "J"; }
+    }
+
+    interface K extends J {
+        default String m() { return "K"; }
+    }
+
+    interface L extends J {
+    }
+
+    interface Named {
+        String name();
+
+        default String greet() { return "hello " + name(); }
+    }
+
+    static class C implements J, K {
+    }
+
+    static class D extends C implements L {
+    }
+
+    static class E implements K {
+        public String m() { return "E then " + K.super.m(); }
+    }
+
+    static class Base {
+        public String m() { return "Base"; }
+    }
+
+    static class F extends Base implements K {
+    }
+
+    static class G implements Named {
+        public String name() { return "G"; }
+    }
+
+    public static void main(String[] args) {
+        System.out.println(new C().m());
+        System.out.println(new D().m());
+        System.out.println(new E().m());
+        System.out.println(new F().m());
+        J j = new D();
+        System.out.println(j.m());
+        Named named = new G();
+        System.out.println(named.greet());
+    }
+}
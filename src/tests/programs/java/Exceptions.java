@@ -0,0 +1,94 @@
+public class Exceptions {
+    static int depth;
+
+    static void recurse() {
+        depth++;
+        recurse();
+    }
+
+    static class Broken {
+        static int value = 1 / (depth - depth);
+    }
+
+    static class Resource implements AutoCloseable {
+        public void close() {
+            throw new IllegalStateException("close");
+        }
+    }
+
+    static int finallyReturns() {
+        try {
+            throw new RuntimeException("lost");
+        } finally {
+            return 3;
+        }
+    }
+
+    static void nested(int n) {
+        if (n == 0) {
+            throw new UnsupportedOperationException("deep");
+        }
+        nested(n - 1);
+    }
+
+    public static void main(String[] args) {
+        try {
+            recurse();
+        } catch (StackOverflowError e) {
+            System.out.println("StackOverflowError " + (depth > 1000));
+        }
+        try {
+            System.out.println(Broken.value);
+        } catch (ExceptionInInitializerError e) {
+            System.out.println(e + " caused by " + e.getCause());
+        }
+        try {
+            System.out.println(Broken.value);
+        } catch (NoClassDefFoundError e) {
+            System.out.println(e);
+        }
+        String nothing = null;
+        try {
+            nothing.length();
+        } catch (NullPointerException e) {
+            System.out.println("NullPointerException");
+        }
+        Object[] strings = new String[1];
+        try {
+            strings[0] = 1;
+        } catch (ArrayStoreException e) {
+            System.out.println(e);
+        }
+        try {
+            int[] array = new int[2];
+            array[2] = 1;
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            int[] array = new int[-1];
+        } catch (NegativeArraySizeException e) {
+            System.out.println(e);
+        }
+        try {
+            "abc".substring(5);
+        } catch (IndexOutOfBoundsException e) {
+            System.out.println(e.getClass().getName());
+        }
+        try (Resource resource = new Resource()) {
+            throw new RuntimeException("body");
+        } catch (RuntimeException e) {
+            System.out.println(e.getMessage() + " suppressed " + e.getSuppressed()[0].getMessage());
+        }
+        System.out.println(finallyReturns());
+        try {
+            nested(2);
+        } catch (RuntimeException e) {
+            StackTraceElement[] trace = e.getStackTrace();
+            System.out.println(trace.length + " " + trace[0] + " " + trace[trace.length - 1].getMethodName());
+        }
+        Exception wrapped = new RuntimeException("outer", new IllegalArgumentException("inner"));
+        wrapped.printStackTrace(System.out);
+        throw new IllegalStateException("uncaught", wrapped);
+    }
+}
@@ -0,0 +1,7 @@
+public class Exit {
+    public static void main(String[] args) {
+        System.out.print("exiting");
+        System.exit(args.length + 3);
+        System.out.println("unreachable");
+    }
+}
@@ -0,0 +1,290 @@
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.MethodType;
+import java.lang.invoke.WrongMethodTypeException;
+
+// Method handles of every kind of member, signature polymorphic invokeExact and invoke, and the
+// combinators of MethodHandles
+public class Handles {
+    private int count;
+    static String label = "start";
+    private final String name;
+
+    public Handles() {
+        this("default");
+    }
+
+    Handles(String name) {
+        this.name = name;
+    }
+
+    static int twice(int x) {
+        return 2 * x;
+    }
+
+    String greet(String who) {
+        return name + " greets " + who;
+    }
+
+    private int secret() {
+        return 42;
+    }
+
+    static String join(String... parts) {
+        StringBuilder b = new StringBuilder();
+        for (String part : parts) {
+            b.append(b.length() == 0 ? "" : "+").append(part);
+        }
+        return b.toString();
+    }
+
+    static String fail(String message) {
+        throw new IllegalStateException(message);
+    }
+
+    static String recover(IllegalStateException e, String message) {
+        return "recovered from " + e.getMessage();
+    }
+
+    static boolean isEven(int x) {
+        return x % 2 == 0;
+    }
+
+    static String even(int x) {
+        return x + " is even";
+    }
+
+    static String odd(int x) {
+        return x + " is odd";
+    }
+
+    static String describe(String what, int times, double scale) {
+        return what + " x" + times + " @" + scale;
+    }
+
+    static void log(String message) {
+        System.out.println("log: " + message);
+    }
+
+    interface Shape {
+        double area();
+    }
+
+    static class Square implements Shape {
+        private final double side;
+
+        Square(double side) {
+            this.side = side;
+        }
+
+        public double area() {
+            return side * side;
+        }
+    }
+
+    static class Base {
+        String who() {
+            return "base";
+        }
+    }
+
+    static class Derived extends Base {
+        String who() {
+            return "derived";
+        }
+
+        String superWho() throws Throwable {
+            MethodHandle special = MethodHandles.lookup().findSpecial(Base.class, "who", MethodType.methodType(String.class), Derived.class);
+            return (String) special.invoke(this);
+        }
+    }
+
+    static class Lazy {
+        static int value = init();
+
+        static int init() {
+            System.out.println("Lazy initialized");
+            return 7;
+        }
+    }
+
+    public static void main(String[] args) throws Throwable {
+        MethodHandles.Lookup lookup = MethodHandles.lookup();
+        System.out.println(lookup);
+        System.out.println(MethodHandles.publicLookup());
+
+        MethodHandle twice = lookup.findStatic(Handles.class, "twice", MethodType.methodType(int.class, int.class));
+        System.out.println(twice);
+        System.out.println((int) twice.invokeExact(21));
+        try {
+            long wrong = (long) twice.invokeExact(21);
+            System.out.println(wrong);
+        } catch (WrongMethodTypeException e) {
+            System.out.println(e.getMessage());
+        }
+        System.out.println((long) twice.invoke(7));
+        Object boxed = twice.invoke(Integer.valueOf(5));
+        System.out.println(boxed);
+        System.out.println(twice.invokeWithArguments(8));
+        try {
+            twice.asType(MethodType.methodType(void.class, String.class));
+        } catch (WrongMethodTypeException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            twice.invoke("five");
+        } catch (WrongMethodTypeException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            Object nothing = null;
+            twice.invoke(nothing);
+        } catch (NullPointerException e) {
+            System.out.println("NullPointerException");
+        }
+
+        MethodHandle constructor = lookup.findConstructor(Handles.class, MethodType.methodType(void.class));
+        System.out.println(constructor);
+        Handles handles = (Handles) constructor.invokeExact();
+        MethodHandle named = lookup.findConstructor(Handles.class, MethodType.methodType(void.class, String.class));
+        Handles alice = (Handles) named.invoke("alice");
+        MethodHandle greet = lookup.findVirtual(Handles.class, "greet", MethodType.methodType(String.class, String.class));
+        System.out.println(greet);
+        System.out.println((String) greet.invokeExact(handles, "bob"));
+        MethodHandle bound = greet.bindTo(alice);
+        System.out.println(bound);
+        System.out.println((String) bound.invokeExact("carol"));
+        System.out.println(lookup.bind(alice, "greet", MethodType.methodType(String.class, String.class)).invoke("dave"));
+        System.out.println((int) lookup.findVirtual(Handles.class, "secret", MethodType.methodType(int.class)).invokeExact(handles));
+
+        MethodHandle getCount = lookup.findGetter(Handles.class, "count", int.class);
+        MethodHandle setCount = lookup.findSetter(Handles.class, "count", int.class);
+        System.out.println(getCount);
+        System.out.println(setCount);
+        setCount.invokeExact(handles, 5);
+        setCount.invoke(handles, Integer.valueOf(6));
+        System.out.println((int) getCount.invokeExact(handles));
+        System.out.println(getCount.invoke(handles));
+        System.out.println((String) lookup.findGetter(Handles.class, "name", String.class).invokeExact(alice));
+        MethodHandle getLabel = lookup.findStaticGetter(Handles.class, "label", String.class);
+        MethodHandle setLabel = lookup.findStaticSetter(Handles.class, "label", String.class);
+        setLabel.invokeExact("changed");
+        System.out.println((String) getLabel.invokeExact());
+        MethodHandle lazy = lookup.findStaticGetter(Lazy.class, "value", int.class);
+        System.out.println("found Lazy.value");
+        System.out.println((int) lazy.invokeExact());
+
+        MethodHandle area = lookup.findVirtual(Shape.class, "area", MethodType.methodType(double.class));
+        System.out.println(area);
+        System.out.println((double) area.invokeExact((Shape) new Square(3)));
+        System.out.println(area.invoke(new Square(1.5)));
+        System.out.println(new Derived().superWho());
+        MethodHandle length = lookup.findVirtual(String.class, "length", MethodType.methodType(int.class));
+        System.out.println((int) length.invokeExact("four"));
+
+        try {
+            lookup.findStatic(Handles.class, "nope", MethodType.methodType(void.class));
+        } catch (NoSuchMethodException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            lookup.findGetter(Handles.class, "missing", int.class);
+        } catch (NoSuchFieldException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            lookup.findStatic(Handles.class, "greet", MethodType.methodType(String.class, String.class));
+        } catch (IllegalAccessException e) {
+            System.out.println("IllegalAccessException");
+        }
+        try {
+            MethodHandles.publicLookup().findVirtual(Handles.class, "secret", MethodType.methodType(int.class));
+        } catch (IllegalAccessException e) {
+            System.out.println("IllegalAccessException");
+        }
+        try {
+            lookup.findStaticSetter(Lazy.class, "missing", int.class);
+        } catch (NoSuchFieldException e) {
+            System.out.println(e.getMessage());
+        }
+
+        MethodHandle describe = lookup.findStatic(Handles.class, "describe",
+            MethodType.methodType(String.class, String.class, int.class, double.class));
+        MethodHandle inserted = MethodHandles.insertArguments(describe, 1, 3, 0.5);
+        System.out.println(inserted);
+        System.out.println((String) inserted.invokeExact("cake"));
+        MethodHandle dropped = MethodHandles.dropArguments(twice, 0, String.class, long.class);
+        System.out.println(dropped);
+        System.out.println((int) dropped.invokeExact("ignored", 1L, 4));
+        MethodHandle join = lookup.findStatic(Handles.class, "join", MethodType.methodType(String.class, String[].class));
+        MethodHandle upper = lookup.findVirtual(String.class, "toUpperCase", MethodType.methodType(String.class));
+        MethodHandle filtered = MethodHandles.filterArguments(describe, 0, upper);
+        System.out.println((String) filtered.invokeExact("pie", 2, 1.0));
+        MethodHandle toText = lookup.findStatic(String.class, "valueOf", MethodType.methodType(String.class, int.class));
+        MethodHandle returned = MethodHandles.filterReturnValue(twice, toText);
+        System.out.println(returned);
+        System.out.println((String) returned.invokeExact(50));
+        MethodHandle logged = MethodHandles.foldArguments(
+            MethodHandles.dropArguments(toText, 0, String.class).asType(MethodType.methodType(String.class, String.class, int.class)),
+            MethodHandles.dropArguments(lookup.findStatic(Handles.class, "log", MethodType.methodType(void.class, String.class)), 1, int.class));
+        System.out.println(logged);
+        System.out.println((String) logged.invokeExact("folding", 9));
+        MethodHandle parity = MethodHandles.guardWithTest(
+            lookup.findStatic(Handles.class, "isEven", MethodType.methodType(boolean.class, int.class)),
+            lookup.findStatic(Handles.class, "even", MethodType.methodType(String.class, int.class)),
+            lookup.findStatic(Handles.class, "odd", MethodType.methodType(String.class, int.class)));
+        System.out.println((String) parity.invokeExact(4));
+        System.out.println((String) parity.invokeExact(7));
+        MethodHandle failing = lookup.findStatic(Handles.class, "fail", MethodType.methodType(String.class, String.class));
+        MethodHandle caught = MethodHandles.catchException(failing, IllegalStateException.class,
+            lookup.findStatic(Handles.class, "recover", MethodType.methodType(String.class, IllegalStateException.class, String.class)));
+        System.out.println((String) caught.invokeExact("trouble"));
+        try {
+            String never = (String) failing.invokeExact("uncaught");
+            System.out.println(never);
+        } catch (IllegalStateException e) {
+            System.out.println("caught " + e.getMessage());
+        }
+        MethodHandle thrower = MethodHandles.throwException(int.class, IllegalArgumentException.class);
+        System.out.println(thrower);
+        try {
+            int never = (int) thrower.invokeExact(new IllegalArgumentException("thrown"));
+            System.out.println(never);
+        } catch (IllegalArgumentException e) {
+            System.out.println("caught " + e.getMessage());
+        }
+        MethodHandle constant = MethodHandles.constant(long.class, 12);
+        System.out.println(constant);
+        System.out.println((long) constant.invokeExact());
+        MethodHandle identity = MethodHandles.identity(String.class);
+        System.out.println((String) identity.invokeExact("same"));
+        MethodHandle swapped = MethodHandles.permuteArguments(describe,
+            MethodType.methodType(String.class, double.class, int.class, String.class), 2, 1, 0);
+        System.out.println(swapped);
+        System.out.println((String) swapped.invokeExact(2.5, 1, "swapped"));
+        MethodHandle spread = describe.asSpreader(Object[].class, 2);
+        System.out.println(spread);
+        System.out.println((String) spread.invokeExact("spread", new Object[] { 4, 0.25 }));
+        MethodHandle collect = join.asCollector(String[].class, 3);
+        System.out.println(collect);
+        System.out.println((String) collect.invokeExact("a", "b", "c"));
+        MethodHandle varargs = join.asVarargsCollector(String[].class);
+        System.out.println(varargs.isVarargsCollector());
+        System.out.println((String) varargs.invoke("x", "y"));
+        System.out.println((String) varargs.invoke());
+        System.out.println((String) varargs.invoke(new String[] { "array", "passed" }));
+        System.out.println(varargs.invokeWithArguments("p", "q", "r"));
+        MethodHandle exact = MethodHandles.exactInvoker(MethodType.methodType(int.class, int.class));
+        System.out.println(exact);
+        System.out.println((int) exact.invokeExact(twice, 100));
+        MethodHandle loose = MethodHandles.invoker(MethodType.methodType(Object.class, Object.class));
+        System.out.println(loose.invoke(twice, 11));
+
+        MethodType type = MethodType.methodType(int.class, String.class, long.class);
+        System.out.println(type.toMethodDescriptorString());
+        System.out.println(type.wrap());
+        System.out.println(type.erase());
+        System.out.println(type.generic());
+        System.out.println(type.equals(MethodType.methodType(int.class, String.class, long.class)));
+    }
+}
@@ -0,0 +1,167 @@
+import java.io.Serializable;
+
+// Lambdas and method references, linked by invokedynamic through LambdaMetafactory. Compiled for
+// the JDK's own release
+public class Lambdas {
+    interface IntOp {
+        int apply(int a, int b);
+    }
+
+    interface Fn<T, R> {
+        R apply(T t);
+
+        default <V> Fn<T, V> andThen(Fn<? super R, ? extends V> after) {
+            return t -> after.apply(apply(t));
+        }
+
+        static <T> Fn<T, T> identity() {
+            return t -> t;
+        }
+    }
+
+    // apply(String) and the bridge apply(Object)
+    interface StringFn extends Fn<String, String> {
+        String apply(String s);
+    }
+
+    interface Supplier<T> {
+        T get();
+    }
+
+    interface ToLong {
+        long apply(int x);
+    }
+
+    interface ToDouble {
+        double apply(Integer x);
+    }
+
+    interface Maker {
+        int[] make(int length);
+    }
+
+    interface Joiner {
+        String join(String format, Object a, Object b);
+    }
+
+    static class Base {
+        String name() {
+            return "base";
+        }
+    }
+
+    static class Derived extends Base {
+        String name() {
+            return "derived";
+        }
+
+        Supplier<String> superName() {
+            return super::name;
+        }
+    }
+
+    private final String prefix;
+    private int calls;
+
+    Lambdas(String prefix) {
+        this.prefix = prefix;
+    }
+
+    private String tag(String s) {
+        calls++;
+        return prefix + s;
+    }
+
+    Fn<String, String> tagger() {
+        return this::tag;
+    }
+
+    Fn<String, String> counting() {
+        return s -> tag(s) + "#" + calls;
+    }
+
+    static int square(long x) {
+        return (int) (x * x);
+    }
+
+    static Runnable make() {
+        return () -> System.out.println("same lambda");
+    }
+
+    public static void main(String[] args) {
+        Runnable hello = () -> System.out.println("hello from a lambda");
+        hello.run();
+        System.out.println(make() == make());
+
+        IntOp add = (a, b) -> a + b;
+        IntOp max = Math::max;
+        System.out.println(add.apply(2, 3) + " " + max.apply(7, -1));
+
+        int base = 10;
+        String label = "captured";
+        IntOp offset = (a, b) -> base + a * b;
+        Supplier<String> supplier = () -> label + " " + base;
+        System.out.println(offset.apply(3, 4) + " " + supplier.get());
+
+        Fn<String, Integer> length = String::length;
+        Fn<String, Integer> parse = Integer::parseInt;
+        Fn<Integer, Integer> twice = x -> x * 2;
+        System.out.println(length.apply("four") + parse.apply("38") + twice.apply(21));
+        System.out.println(length.andThen(twice).andThen(Object::toString).apply("abc"));
+        System.out.println(Fn.<String>identity().apply("identity"));
+
+        String greeting = "hi ";
+        Fn<String, String> bound = greeting::concat;
+        System.out.println(bound.apply("there"));
+
+        Supplier<StringBuilder> builder = StringBuilder::new;
+        Fn<String, StringBuilder> fromString = StringBuilder::new;
+        System.out.println(builder.get().append("built").append(fromString.apply(" from string")));
+        Maker maker = int[]::new;
+        System.out.println(maker.make(4).length);
+
+        ToLong widened = Lambdas::square;
+        ToDouble unboxed = x -> x;
+        System.out.println(widened.apply(12) + " " + unboxed.apply(5));
+
+        Lambdas lambdas = new Lambdas("<");
+        Fn<String, String> tagger = lambdas.tagger();
+        Fn<String, String> counting = lambdas.counting();
+        System.out.println(tagger.apply("a") + " " + counting.apply("b") + " " + counting.apply("c"));
+        System.out.println(new Derived().superName().get());
+
+        StringFn upper = s -> s.toUpperCase();
+        Fn<String, String> generic = upper;
+        System.out.println(upper.apply("bridged") + " " + generic.apply("too"));
+
+        Runnable serializable = (Runnable & Serializable) () -> System.out.println("serializable lambda");
+        serializable.run();
+        System.out.println(serializable instanceof Serializable);
+        System.out.println(hello instanceof Serializable);
+
+        Joiner joiner = String::format;
+        System.out.println(joiner.join("%s and %s", "varargs", 2));
+
+        Supplier<Supplier<String>> nested = () -> () -> label + " twice";
+        System.out.println(nested.get().get());
+
+        Runnable[] counters = new Runnable[3];
+        for (int i = 0; i < counters.length; i++) {
+            int n = i;
+            counters[i] = () -> System.out.print(n + " ");
+        }
+        for (Runnable counter : counters) {
+            counter.run();
+        }
+        System.out.println();
+
+        Fn<String, Integer> failing = s -> Integer.parseInt(s);
+        try {
+            failing.apply("nope");
+        } catch (NumberFormatException e) {
+            System.out.println(e.getMessage());
+        }
+        Fn<Object, String> text = String::valueOf;
+        System.out.println(text.apply(null));
+    }
+}
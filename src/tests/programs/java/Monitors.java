@@ -0,0 +1,209 @@
+public class Monitors {
+    static class Counter {
+        private int count;
+
+        synchronized void increment() {
+            count++;
+        }
+
+        synchronized int get() {
+            return count;
+        }
+
+        // Reentrant: the thread is in the monitor already
+        synchronized int incrementTwice() {
+            increment();
+            synchronized (this) {
+                increment();
+            }
+            return count;
+        }
+    }
+
+    // A one element queue
+    static class Slot {
+        private Integer value;
+
+        synchronized void put(int v) throws InterruptedException {
+            while (value != null) {
+                wait();
+            }
+            value = v;
+            notifyAll();
+        }
+
+        synchronized int take() throws InterruptedException {
+            while (value == null) {
+                wait();
+            }
+            int v = value;
+            value = null;
+            notifyAll();
+            return v;
+        }
+    }
+
+    static int staticCount;
+
+    static synchronized void staticIncrement() {
+        staticCount++;
+    }
+
+    static void throwInside(Object lock) {
+        synchronized (lock) {
+            throw new IllegalStateException("inside");
+        }
+    }
+
+    public static void main(String[] args) throws InterruptedException {
+        // The JDK hangs, the VM gives up
+        if (args.length > 0) {
+            final Object lock = new Object();
+            Thread other = new Thread(new Runnable() {
+                public void run() {
+                    synchronized (lock) {
+                        System.out.println("unreachable");
+                    }
+                }
+            });
+            synchronized (lock) {
+                other.start();
+                other.join();
+            }
+        }
+
+        final Counter counter = new Counter();
+        Thread[] threads = new Thread[4];
+        for (int i = 0; i < threads.length; i++) {
+            threads[i] = new Thread(new Runnable() {
+                public void run() {
+                    for (int j = 0; j < 5000; j++) {
+                        counter.increment();
+                        staticIncrement();
+                    }
+                }
+            });
+            threads[i].start();
+        }
+        for (Thread thread : threads) {
+            thread.join();
+        }
+        System.out.println("counted " + counter.get() + " " + staticCount);
+        System.out.println("reentrant " + counter.incrementTwice());
+
+        Object lock = new Object();
+        System.out.println("holds " + Thread.holdsLock(lock));
+        synchronized (lock) {
+            synchronized (lock) {
+                System.out.println("holds " + Thread.holdsLock(lock));
+            }
+            System.out.println("holds " + Thread.holdsLock(lock));
+        }
+        System.out.println("holds " + Thread.holdsLock(lock));
+
+        try {
+            throwInside(lock);
+        } catch (IllegalStateException e) {
+            System.out.println("released " + !Thread.holdsLock(lock));
+        }
+
+        final Slot slot = new Slot();
+        Thread producer = new Thread(new Runnable() {
+            public void run() {
+                try {
+                    for (int i = 1; i <= 5; i++) {
+                        slot.put(i * i);
+                    }
+                    slot.put(-1);
+                } catch (InterruptedException e) {
+                    System.out.println("producer interrupted");
+                }
+            }
+        });
+        producer.start();
+        StringBuilder taken = new StringBuilder();
+        int v;
+        while ((v = slot.take()) >= 0) {
+            taken.append(v).append(' ');
+        }
+        producer.join();
+        System.out.println("taken " + taken);
+
+        final Object gate = new Object();
+        final int[] woken = new int[1];
+        Thread[] waiters = new Thread[3];
+        for (int i = 0; i < waiters.length; i++) {
+            waiters[i] = new Thread(new Runnable() {
+                public void run() {
+                    synchronized (gate) {
+                        try {
+                            gate.wait();
+                            woken[0]++;
+                        } catch (InterruptedException e) {
+                            System.out.println("waiter interrupted");
+                        }
+                    }
+                }
+            });
+            waiters[i].start();
+        }
+        Thread.sleep(20);
+        synchronized (gate) {
+            gate.notifyAll();
+        }
+        for (Thread waiter : waiters) {
+            waiter.join();
+        }
+        System.out.println("woken " + woken[0]);
+
+        final Object waited = new Object();
+        Thread interrupted = new Thread(new Runnable() {
+            public void run() {
+                synchronized (waited) {
+                    try {
+                        waited.wait();
+                    } catch (InterruptedException e) {
+                        System.out.println(e + " holds " + Thread.holdsLock(waited));
+                    }
+                }
+            }
+        });
+        interrupted.start();
+        Thread.sleep(20);
+        interrupted.interrupt();
+        interrupted.join();
+
+        synchronized (lock) {
+            long start = System.currentTimeMillis();
+            lock.wait(20);
+            System.out.println("timed out " + (System.currentTimeMillis() - start >= 20) + " holds " + Thread.holdsLock(lock));
+            lock.notify();
+        }
+
+        try {
+            lock.notify();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println(e);
+        }
+        try {
+            lock.wait();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println(e);
+        }
+        try {
+            synchronized (lock) {
+                lock.wait(-1);
+            }
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            Object nothing = null;
+            synchronized (nothing) {
+                System.out.println("unreachable");
+            }
+        } catch (NullPointerException e) {
+            System.out.println("null monitor");
+        }
+    }
+}
@@ -0,0 +1,18 @@
+// twice is registered by the test that passes an argument, the JDK has no library for either method
+public class Natives {
+    static native int twice(int value);
+
+    static native String[] missing(String name, int[][] values, long count);
+
+    public static void main(String[] args) {
+        try {
+            missing("x", null, 1);
+        } catch (UnsatisfiedLinkError e) {
+            System.out.println(e);
+        }
+        if (args.length > 0) {
+            System.out.println(twice(21));
+        }
+        twice(1);
+    }
+}
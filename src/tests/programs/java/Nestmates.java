@@ -0,0 +1,52 @@
+// Compiled for the JDK's own release, inner classes use each other's private fields directly. No string
+// concatenation, that is invokedynamic there
+public class Nestmates {
+    private int count;
+    private static String name = "outer";
+
+    class Inner {
+        private int seen;
+
+        void bump() {
+            count++;
+            seen = count;
+        }
+    }
+
+    static class Nested {
+        private static int created;
+
+        Nested() {
+            created++;
+            name = name.concat("!");
+        }
+    }
+
+    static class Base {
+        static int shared = 1;
+
+        static {
+            System.out.println("Base initialized");
+        }
+    }
+
+    static class Derived extends Base {
+        static {
+            System.out.println("Derived initialized");
+        }
+    }
+
+    public static void main(String[] args) {
+        Nestmates outer = new Nestmates();
+        Inner inner = outer.new Inner();
+        inner.bump();
+        inner.bump();
+        System.out.println(outer.count);
+        System.out.println(inner.seen);
+        new Nested();
+        new Nested();
+        System.out.println(Nested.created);
+        System.out.println(name);
+        System.out.println(Derived.shared);
+    }
+}
@@ -0,0 +1,37 @@
+public class Numbers {
+    public static void main(String[] args) {
+        long big = 1L << 40;
+        System.out.println(big + " " + (int) big + " " + (big >>> 3) + " " + (-big >> 3) + " " + (-1 >>> 28));
+        System.out.println((-17 % 5) + " " + (-17 / 5) + " " + (Integer.MIN_VALUE / -1) + " " + (Long.MIN_VALUE % -1));
+        System.out.println(Integer.MAX_VALUE + 1 + " " + (Long.MAX_VALUE + 1) + " " + (byte) 130 + " " + (char) 65);
+        System.out.println(0.1 + 0.2 + " " + (float) (0.1 + 0.2) + " " + 1.0f / 3 + " " + 100.0 + " " + 1e7 + " " + 1.0E-5 + " " + 123456789.0f);
+        System.out.println(0.0 / 0.0 + " " + 1 / 0.0 + " " + -1 / 0.0 + " " + -0.0 + " " + Double.MIN_VALUE + " " + Float.MAX_VALUE);
+        System.out.println((int) Double.NaN + " " + (int) 1e20 + " " + (long) -1e30 + " " + (int) -2.7 + " " + (long) 2.7f);
+        System.out.println(5.5 % 2 + " " + Math.sqrt(2) + " " + Math.pow(2, 10) + " " + Math.abs(-2.5) + " " + Math.round(2.5) + " " + Math.round(-2.5));
+        System.out.println(Math.floorMod(-7, 3) + " " + Math.floorDiv(-7, 3) + " " + Math.max(3, 7) + " " + Math.min(-0.0, 0.0) + " " + Math.ceil(-0.5));
+        System.out.println(Integer.parseInt("-123") + " " + Integer.parseInt("ff", 16) + " " + Long.parseLong("9223372036854775807") + " " + Double.parseDouble(" 2.5e3 "));
+        System.out.println(Integer.toHexString(-1) + " " + Integer.toBinaryString(10) + " " + Long.toHexString(255L) + " " + Integer.toString(-255, 16));
+        System.out.println(Integer.bitCount(255) + " " + Integer.reverse(1) + " " + Long.numberOfTrailingZeros(64) + " " + Integer.highestOneBit(100));
+        System.out.println(Double.compare(0.0, -0.0) + " " + Double.isNaN(0.0 / 0.0) + " " + Float.floatToIntBits(1.0f) + " " + Double.doubleToLongBits(-2.0));
+        Integer boxed = 127;
+        Integer other = 127;
+        Long wide = 5L;
+        System.out.println((boxed == other) + " " + boxed.equals(127) + " " + wide.hashCode() + " " + Character.valueOf('c') + " " + Boolean.valueOf("TRUE"));
+        System.out.println(Integer.compare(3, 5) + " " + Integer.valueOf(42).compareTo(7) + " " + Short.parseShort("-5") + " " + Byte.MAX_VALUE);
+        System.out.println(String.format("%d|%5d|%-5d|%05d|%,d|%x|%X|%o", 42, 42, 42, -42, 1234567, 255, 255, 8));
+        System.out.println(String.format("%.2f|%.1f|%.0f|%10.3f|%-8.2f|%e|%.3e", 1.005, 0.25, 2.5, Math.PI, -1.5, 12345.678, 0.0));
+        System.out.println(String.format("%s|%10s|%-6s|%c|%b|%%|%n", "str", "right", "left", 'c', null).trim());
+        for (String bad : new String[] {"12x", "", "99999999999"}) {
+            try {
+                Integer.parseInt(bad);
+            } catch (NumberFormatException e) {
+                System.out.println(e.getMessage());
+            }
+        }
+        try {
+            System.out.println(1 / (args.length));
+        } catch (ArithmeticException e) {
+            System.out.println(e);
+        }
+    }
+}
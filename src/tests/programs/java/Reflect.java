@@ -0,0 +1,277 @@
+import java.lang.reflect.Constructor;
+import java.lang.reflect.Field;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
+import java.util.Arrays;
+
+// Core reflection: members of a class, Method.invoke, Field.get and set, Constructor.newInstance
+// and Class.newInstance, with boxing, widening, access checks and the JDK's messages
+public class Reflect {
+    interface Shape {
+        int SIDES = 0;
+
+        double area();
+
+        String name();
+    }
+
+    static abstract class Base implements Shape {
+        public String name() {
+            return getClass().getSimpleName();
+        }
+
+        protected int hidden() {
+            return 1;
+        }
+    }
+
+    static final class Square extends Base {
+        public static int made;
+        public final double side;
+        private long secret = 7;
+        final int fixed = 3;
+        private static final String CONSTANT = "constant";
+        byte small;
+        char letter = 'a';
+
+        public Square() {
+            this(1);
+        }
+
+        public Square(double side) {
+            this.side = side;
+            made++;
+        }
+
+        private Square(String side) throws NumberFormatException {
+            this(Double.parseDouble(side));
+        }
+
+        public double area() {
+            return side * side;
+        }
+
+        public static long scale(long value, int factor) {
+            return value * factor;
+        }
+
+        private String whisper(String s) {
+            return s.toLowerCase() + secret;
+        }
+
+        public void fail(String message) throws IllegalStateException {
+            throw new IllegalStateException(message);
+        }
+
+        public static int sum(int... values) {
+            int sum = 0;
+            for (int value : values) {
+                sum += value;
+            }
+            return sum;
+        }
+    }
+
+    static class Failing {
+        Failing() {
+            throw new UnsupportedOperationException("no instances");
+        }
+    }
+
+    enum Color {
+        RED
+    }
+
+    static String names(Method[] methods) {
+        String[] names = new String[methods.length];
+        for (int i = 0; i < methods.length; i++) {
+            names[i] = methods[i].getName() + "/" + methods[i].getParameterTypes().length;
+        }
+        Arrays.sort(names);
+        return Arrays.toString(names);
+    }
+
+    static String names(Field[] fields) {
+        String[] names = new String[fields.length];
+        for (int i = 0; i < fields.length; i++) {
+            names[i] = fields[i].getName() + ":" + fields[i].getType().getName();
+        }
+        Arrays.sort(names);
+        return Arrays.toString(names);
+    }
+
+    static void fails(String what, Runnable action) {
+        try {
+            action.run();
+            System.out.println(what + ": no exception");
+        } catch (RuntimeException e) {
+            Throwable cause = e.getCause();
+            System.out.println(what + ": " + cause.getClass().getName() + ": " + cause.getMessage());
+        }
+    }
+
+    static Object invoke(Method method, Object receiver, Object... args) {
+        try {
+            return method.invoke(receiver, args);
+        } catch (Exception e) {
+            throw new RuntimeException(e);
+        }
+    }
+
+    static void set(Field field, Object receiver, Object value) {
+        try {
+            field.set(receiver, value);
+        } catch (Exception e) {
+            throw new RuntimeException(e);
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        Class<Square> square = Square.class;
+        System.out.println(names(square.getDeclaredMethods()));
+        System.out.println(names(square.getDeclaredFields()));
+        System.out.println(names(square.getFields()));
+        System.out.println(names(Shape.class.getMethods()));
+        System.out.println(square.getDeclaredConstructors().length + " " + square.getConstructors().length);
+        System.out.println(Modifier.toString(square.getModifiers()) + " | " + Modifier.toString(Base.class.getModifiers())
+            + " | " + Modifier.toString(Shape.class.getModifiers()) + " | " + Modifier.toString(Reflect.class.getModifiers()));
+        System.out.println(Modifier.toString(int.class.getModifiers()) + " | " + Modifier.toString(Square[].class.getModifiers())
+            + " | " + Modifier.toString(void.class.getModifiers()));
+        System.out.println(Arrays.toString(square.getInterfaces()) + " " + Arrays.toString(Base.class.getInterfaces())
+            + " " + int[].class.getInterfaces().length);
+
+        Method area = square.getMethod("area");
+        Method scale = square.getMethod("scale", long.class, int.class);
+        Method whisper = square.getDeclaredMethod("whisper", String.class);
+        Method fail = square.getMethod("fail", String.class);
+        Method sum = square.getMethod("sum", int[].class);
+        Method name = square.getMethod("name");
+        System.out.println(area);
+        System.out.println(scale);
+        System.out.println(whisper);
+        System.out.println(fail);
+        System.out.println(sum + " " + sum.isVarArgs());
+        System.out.println(name + " " + name.getDeclaringClass().getSimpleName());
+        System.out.println(Shape.class.getMethod("area"));
+
+        Square three = new Square(3);
+        System.out.println(area.invoke(three) + " " + name.invoke(three));
+        System.out.println(scale.invoke(null, 5, (short) 4));
+        System.out.println(scale.invoke(null, 'a', (byte) 2));
+        System.out.println(sum.invoke(null, new Object[] {new int[] {1, 2, 3}}));
+        System.out.println(Shape.class.getMethod("area").invoke(three));
+        fails("wrong count", () -> invoke(area, three, 1));
+        fails("mismatch", () -> invoke(scale, null, 1.5, 2));
+        fails("unboxing null", () -> invoke(scale, null, null, 2));
+        fails("receiver", () -> invoke(area, "not a square"));
+        try {
+            area.invoke(null);
+        } catch (NullPointerException e) {
+            System.out.println("null receiver: NullPointerException");
+        }
+        fails("private", () -> invoke(whisper, three, "X"));
+        fails("thrown", () -> invoke(fail, three, "boom"));
+        try {
+            fail.invoke(three, "target");
+        } catch (InvocationTargetException e) {
+            System.out.println(e.getTargetException() + " " + (e.getCause() == e.getTargetException()));
+        }
+        System.out.println(whisper.isAccessible());
+        whisper.setAccessible(true);
+        System.out.println(whisper.invoke(three, "QUIET") + " " + whisper.isAccessible());
+        System.out.println(Base.class.getDeclaredMethod("hidden").invoke(three));
+
+        Field side = square.getField("side");
+        Field made = square.getField("made");
+        Field secret = square.getDeclaredField("secret");
+        Field fixed = square.getDeclaredField("fixed");
+        Field constant = square.getDeclaredField("CONSTANT");
+        Field small = square.getDeclaredField("small");
+        Field letter = square.getDeclaredField("letter");
+        System.out.println(side + " | " + made + " | " + secret + " | " + constant);
+        System.out.println(side.get(three) + " " + side.getDouble(three) + " " + made.getInt(null) + " " + made.getLong(null));
+        System.out.println(letter.getChar(three) + " " + letter.getInt(three) + " " + small.getByte(three));
+        made.set(null, 10);
+        made.setShort(null, (short) 12);
+        System.out.println(Square.made);
+        small.setByte(three, (byte) -3);
+        letter.set(three, 'z');
+        System.out.println(three.small + " " + three.letter);
+        fails("narrowing", () -> set(small, three, 300));
+        fails("wrong type", () -> set(made, null, "ten"));
+        fails("final", () -> set(side, three, 4.0));
+        fails("static final", () -> set(constant, null, "changed"));
+        fails("private field", () -> set(secret, three, 1L));
+        try {
+            letter.getBoolean(three);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        secret.setAccessible(true);
+        secret.setLong(three, 99);
+        System.out.println(secret.get(three) + " " + whisper.invoke(three, "A"));
+        fixed.setAccessible(true);
+        fixed.setInt(three, 30);
+        System.out.println(fixed.getInt(three));
+        constant.setAccessible(true);
+        fails("static final accessible", () -> set(constant, null, "changed"));
+
+        Constructor<Square> nullary = square.getConstructor();
+        Constructor<Square> fromDouble = square.getConstructor(double.class);
+        Constructor<Square> fromString = square.getDeclaredConstructor(String.class);
+        System.out.println(nullary + " | " + fromDouble + " | " + fromString);
+        System.out.println(fromDouble.newInstance(2).area() + " " + nullary.newInstance().side);
+        try {
+            fromString.newInstance("4");
+        } catch (IllegalAccessException e) {
+            System.out.println(e.getMessage());
+        }
+        fromString.setAccessible(true);
+        System.out.println(fromString.newInstance("4").area());
+        try {
+            fromString.newInstance("four");
+        } catch (InvocationTargetException e) {
+            System.out.println(e.getCause());
+        }
+        System.out.println(square.newInstance().side + " " + Square.made);
+        try {
+            Base.class.newInstance();
+        } catch (InstantiationException e) {
+            System.out.println("InstantiationException " + e.getMessage());
+        }
+        try {
+            Failing.class.newInstance();
+        } catch (UnsupportedOperationException e) {
+            System.out.println("unwrapped " + e.getMessage());
+        }
+        try {
+            Integer.class.newInstance();
+        } catch (InstantiationException e) {
+            System.out.println("InstantiationException " + e.getMessage());
+        }
+        try {
+            Constructor<?> color = Color.class.getDeclaredConstructors()[0];
+            color.setAccessible(true);
+            color.newInstance("BLUE", 1);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            square.getMethod("missing", int.class, String.class);
+        } catch (NoSuchMethodException e) {
+            System.out.println("NoSuchMethodException " + e.getMessage());
+        }
+        try {
+            square.getField("secret");
+        } catch (NoSuchFieldException e) {
+            System.out.println("NoSuchFieldException " + e.getMessage());
+        }
+
+        System.out.println(int.class + " " + int.class.isPrimitive() + " " + void.class.getName() + " " + Integer.TYPE);
+        System.out.println(int.class == Integer.TYPE && double.class == Double.TYPE && boolean.class != Boolean.class);
+        System.out.println(Shape.class.getField("SIDES").get(null) + " " + Shape.class.getField("SIDES").getModifiers());
+        System.out.println(area.equals(square.getMethod("area")) + " " + (area == square.getMethod("area"))
+            + " " + area.equals(Shape.class.getMethod("area")));
+    }
+}
@@ -0,0 +1,27 @@
+import java.util.Arrays;
+
+public class Strings {
+    public static void main(String[] args) {
+        String s = "Hello, World";
+        System.out.println(s.length() + " " + s.charAt(4) + " " + s.indexOf("World") + " " + s.lastIndexOf('o') + " " + s.substring(7) + " " + s.substring(0, 5));
+        System.out.println(s.toUpperCase() + " " + s.toLowerCase() + " " + s.replace('l', 'L') + " " + s.replace("World", "There") + " " + s.contains("lo, "));
+        System.out.println("  padded  ".trim() + "|" + "a,b,,c,,".split(",").length + "|" + Arrays.toString("a.b.c".split("\\.")) + "|" + String.join("-", "x", "y", "z"));
+        System.out.println("abc".compareTo("abd") + " " + "b".compareTo("a") + " " + "ABC".equalsIgnoreCase("abc") + " " + "abc".hashCode() + " " + "".isEmpty());
+        System.out.println("héllo wörld ✓".toUpperCase() + " " + "héllo".getBytes().length + " " + new String("héllo".getBytes()) + " " + "😀".length());
+        StringBuilder sb = new StringBuilder("abc");
+        sb.append(1).append('x').append(2.5).append(true).append((Object) null).insert(0, "[").append(']');
+        System.out.println(sb + " " + sb.length() + " " + sb.indexOf("x") + " " + sb.charAt(1));
+        sb.setLength(4);
+        System.out.println(sb.reverse() + " " + new StringBuilder("racecar").reverse() + " " + sb.deleteCharAt(0) + " " + sb.delete(0, 1));
+        String a = "interned";
+        String b = new String("interned");
+        System.out.println((a == b) + " " + (a == b.intern()) + " " + a.equals(b) + " " + (a == "intern" + "ed"));
+        char[] chars = "sorting".toCharArray();
+        Arrays.sort(chars);
+        System.out.println(new String(chars) + " " + String.valueOf(chars, 1, 3) + " " + String.valueOf(3.0f) + " " + String.valueOf((Object) null));
+        System.out.println(Character.isDigit('7') + " " + Character.isLetter('é') + " " + Character.isWhitespace('\t') + " " + Character.toUpperCase('ß') + " " + (int) Character.toLowerCase('Ä'));
+        System.out.println("a-b".startsWith("a") + " " + "a-b".endsWith("-b") + " " + "a-b".startsWith("-", 1) + " " + "abcabc".indexOf("c", 3));
+        Object[] things = {1, 'c', 2L, 3.5f, "s", null, new int[0].length};
+        System.out.println(Arrays.toString(things) + " " + Arrays.toString(Arrays.copyOf(chars, 2)));
+    }
+}
@@ -0,0 +1,132 @@
+public class Threads {
+    static class Worker extends Thread {
+        Worker(String name) {
+            super(name);
+        }
+
+        public void run() {
+            throw new IllegalStateException("boom in " + getName());
+        }
+    }
+
+    static class Sleeper implements Runnable {
+        public void run() {
+            try {
+                Thread.sleep(10000);
+                System.out.println("slept");
+            } catch (InterruptedException e) {
+                System.out.println(Thread.currentThread().getName() + ": " + e.getMessage() + " " + Thread.currentThread().isInterrupted());
+            }
+        }
+    }
+
+    static long sum;
+
+    public static void main(String[] args) throws InterruptedException {
+        // The JDK hangs, the VM gives up
+        if (args.length > 0) {
+            Thread.currentThread().join();
+        }
+        Thread main = Thread.currentThread();
+        System.out.println(main.getName() + " " + main.isDaemon() + " " + main.getPriority() + " " + main + " " + main.isAlive());
+
+        Thread adder = new Thread(new Runnable() {
+            public void run() {
+                for (int i = 1; i <= 100000; i++) {
+                    sum += i;
+                }
+            }
+        }, "adder");
+        System.out.println(adder.isAlive());
+        adder.start();
+        adder.join();
+        System.out.println(sum + " " + adder.isAlive() + " " + adder);
+        try {
+            adder.start();
+        } catch (IllegalThreadStateException e) {
+            System.out.println(e);
+        }
+
+        Thread sleeper = new Thread(new Sleeper(), "sleeper");
+        sleeper.start();
+        try {
+            sleeper.setDaemon(true);
+        } catch (IllegalThreadStateException e) {
+            System.out.println("setDaemon: " + e);
+        }
+        sleeper.join(20);
+        System.out.println("still sleeping " + sleeper.isAlive());
+        sleeper.interrupt();
+        sleeper.join();
+        System.out.println("joined " + sleeper.isAlive());
+
+        main.interrupt();
+        System.out.println(main.isInterrupted() + " " + Thread.interrupted() + " " + Thread.interrupted());
+        main.interrupt();
+        try {
+            Thread.sleep(10);
+        } catch (InterruptedException e) {
+            System.out.println("main: " + e.getMessage());
+        }
+        try {
+            Thread.sleep(-1);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+
+        long start = System.currentTimeMillis();
+        Thread.sleep(20);
+        Thread.yield();
+        System.out.println("slept 20: " + (System.currentTimeMillis() - start >= 20));
+
+        Thread handled = new Worker("handled");
+        handled.setUncaughtExceptionHandler(new Thread.UncaughtExceptionHandler() {
+            public void uncaughtException(Thread t, Throwable e) {
+                System.out.println("handler: " + t.getName() + " threw " + e.getMessage());
+            }
+        });
+        handled.start();
+        handled.join();
+
+        Thread unhandled = new Worker("unhandled");
+        unhandled.start();
+        unhandled.join();
+
+        Thread.setDefaultUncaughtExceptionHandler(new Thread.UncaughtExceptionHandler() {
+            public void uncaughtException(Thread t, Throwable e) {
+                System.out.println("default handler: " + e.getMessage());
+            }
+        });
+        Thread defaulted = new Worker("defaulted");
+        defaulted.start();
+        defaulted.join();
+        Thread.setDefaultUncaughtExceptionHandler(null);
+
+        Thread daemon = new Thread(new Runnable() {
+            public void run() {
+                while (true) {
+                    try {
+                        Thread.sleep(1000);
+                    } catch (InterruptedException e) {
+                        return;
+                    }
+                }
+            }
+        });
+        daemon.setDaemon(true);
+        daemon.start();
+
+        Thread last = new Thread(new Runnable() {
+            public void run() {
+                try {
+                    Thread.sleep(30);
+                } catch (InterruptedException e) {
+                    return;
+                }
+                System.out.println("last thread ends after main");
+            }
+        });
+        last.start();
+        System.out.println("main ends");
+    }
+}
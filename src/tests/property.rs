@@ -0,0 +1,509 @@
+/*
+* Random classes written as jasm source, assembled and parsed back. Every case checks that the
+* parsed class is the one that was generated, that the jasm and JSON writers give back the same
+* bytes, that the three parsers agree, and that truncated or corrupted bytes are an error and
+* never a panic. Seeds are fixed, a failing case is reproduced by its number.
+*/
+use std::fmt::Write;
+
+use class_file::attribute_info::AttributeInfo;
+use class_file::bytecode::{self, Operand};
+use class_file::class_file::{ClassFile, MemberInfo};
+use class_file::constant_pool::{ConstantPoolRep, LoadableConstant, MemberRef};
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use class_file::view::ClassView;
+use jasm;
+use json;
+use types::{u1, u2};
+
+const CASES: u64 = 300;
+
+// Pieces of names and strings, with what modified UTF-8 encodes in 2, 3 and 6 bytes
+const PIECES: &[&str] = &["a", "Foo", "bar_", "$", "x1", "é", "ß", "λ", "中文", "😀", "\u{7ff}", "\u{800}", "\u{ffff}"];
+const TEXT: &[&str] = &[" ", "\"", "\\", "\0", "\n", "\r", "\t", "//", "#1", "any", "default"];
+const TYPES: &[&str] = &["Z", "B", "C", "S", "I", "J", "F", "D", "[I", "[[Ljava/lang/Object;", "Ljava/lang/String;"];
+
+// xorshift64*
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Integer(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+}
+
+impl Value {
+    fn source(&self) -> String {
+        match *self {
+            Value::Integer(value) => format!("Integer {}", value),
+            Value::Long(value) => format!("Long {}", value),
+            Value::Float(value) => format!("Float {}", value),
+            Value::Double(value) => format!("Double {}", value),
+            Value::String(ref text) => format!("String {}", quote(text)),
+        }
+    }
+
+    fn matches(&self, loadable: LoadableConstant) -> bool {
+        match (self, loadable) {
+            (&Value::Integer(value), LoadableConstant::Integer(actual)) => value == actual,
+            (&Value::Long(value), LoadableConstant::Long(actual)) => value == actual,
+            (&Value::Float(value), LoadableConstant::Float(actual)) => value.to_bits() == actual.to_bits(),
+            (&Value::Double(value), LoadableConstant::Double(actual)) => value.to_bits() == actual.to_bits(),
+            (Value::String(text), LoadableConstant::String(actual)) => text == actual,
+            _ => false,
+        }
+    }
+}
+
+// Branch targets are instruction numbers, the pcs are only known after assembling
+#[derive(Debug)]
+enum Op {
+    None,
+    Int(i32),
+    Local(u2),
+    Iinc(u2, i16),
+    Loadable(Value),
+    Field(String, String, String),
+    Method(String, String, String),
+    Class(String),
+    Branch(usize),
+    TableSwitch(i32, Vec<usize>, usize),
+    LookupSwitch(Vec<(i32, usize)>, usize),
+}
+
+struct Instruction {
+    mnemonic: &'static str,
+    wide: bool,
+    op: Op,
+}
+
+struct Code {
+    max_stack: u2,
+    max_locals: u2,
+    instructions: Vec<Instruction>,
+    // start, end, handler as instruction numbers
+    catches: Vec<(usize, usize, usize, Option<String>)>,
+    lines: Vec<(usize, u2)>,
+}
+
+struct Member {
+    flags: u2,
+    name: String,
+    descriptor: String,
+    constant: Option<Value>,
+    code: Option<Code>,
+}
+
+struct Class {
+    version: (u2, u2),
+    flags: u2,
+    name: String,
+    super_name: String,
+    interfaces: Vec<String>,
+    fields: Vec<Member>,
+    methods: Vec<Member>,
+    source_file: Option<String>,
+}
+
+// Every character as is but what the lexer needs escaped
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\0' => quoted.push_str("\\u0000"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn name(rng: &mut Rng) -> String {
+    (0..1 + rng.below(3)).map(|_| *rng.pick(PIECES)).collect()
+}
+
+fn class_name(rng: &mut Rng) -> String {
+    match rng.below(3) {
+        0 => format!("{}/{}", name(rng), name(rng)),
+        _ => name(rng),
+    }
+}
+
+fn text(rng: &mut Rng) -> String {
+    (0..rng.below(6)).map(|_| if rng.below(2) == 0 { *rng.pick(PIECES) } else { *rng.pick(TEXT) }).collect()
+}
+
+fn value(rng: &mut Rng, wide: bool) -> Value {
+    match (wide, rng.below(3)) {
+        (false, 0) => Value::Integer(rng.next() as i32),
+        (false, 1) => Value::Float(rng.next() as i16 as f32 / 8.0),
+        (false, _) => Value::String(text(rng)),
+        (true, 0) => Value::Double(rng.next() as i32 as f64 / 16.0),
+        (true, _) => Value::Long(rng.next() as i64),
+    }
+}
+
+fn instruction(rng: &mut Rng, count: usize) -> Instruction {
+    let (mnemonic, wide, op) = match rng.below(13) {
+        0 => (*rng.pick(&["nop", "pop", "dup", "aconst_null", "iadd", "athrow"]), false, Op::None),
+        1 => (*rng.pick(&["iconst_m1", "iconst_0", "iconst_5", "lconst_1", "fconst_2", "dconst_0"]), false, Op::None),
+        2 => ("bipush", false, Op::Int(rng.next() as i8 as i32)),
+        3 => ("sipush", false, Op::Int(rng.next() as i16 as i32)),
+        4 => {
+            let mnemonic = *rng.pick(&["iload", "lstore", "aload", "ret"]);
+            match rng.below(2) {
+                0 => (mnemonic, false, Op::Local(rng.next() as u8 as u2)),
+                _ => (mnemonic, true, Op::Local(rng.next() as u2)),
+            }
+        },
+        5 => match rng.below(2) {
+            0 => ("iinc", false, Op::Iinc(rng.next() as u8 as u2, rng.next() as i8 as i16)),
+            _ => ("iinc", true, Op::Iinc(rng.next() as u2, rng.next() as i16)),
+        },
+        6 => ("ldc_w", false, Op::Loadable(value(rng, false))),
+        7 => ("ldc2_w", false, Op::Loadable(value(rng, true))),
+        8 => (*rng.pick(&["getstatic", "putfield"]), false,
+            Op::Field(class_name(rng), name(rng), rng.pick(TYPES).to_string())),
+        9 => (*rng.pick(&["invokestatic", "invokevirtual"]), false,
+            Op::Method(class_name(rng), name(rng), method_descriptor(rng))),
+        10 => (*rng.pick(&["new", "checkcast", "anewarray"]), false, Op::Class(class_name(rng))),
+        11 => (*rng.pick(&["goto", "ifeq", "ifnull", "goto_w", "jsr"]), false, Op::Branch(rng.below(count))),
+        _ => match rng.below(2) {
+            0 => {
+                let targets = (0..1 + rng.below(4)).map(|_| rng.below(count)).collect();
+                ("tableswitch", false, Op::TableSwitch(rng.next() as i16 as i32, targets, rng.below(count)))
+            },
+            _ => {
+                let mut key = rng.next() as i16 as i32;
+                let pairs = (0..rng.below(4))
+                    .map(|_| {
+                        key += 1 + rng.below(1000) as i32;
+                        (key, rng.below(count))
+                    })
+                    .collect();
+                ("lookupswitch", false, Op::LookupSwitch(pairs, rng.below(count)))
+            },
+        },
+    };
+    Instruction { mnemonic, wide, op }
+}
+
+fn method_descriptor(rng: &mut Rng) -> String {
+    let parameters: String = (0..rng.below(4)).map(|_| *rng.pick(TYPES)).collect();
+    let result = if rng.below(2) == 0 { "V" } else { *rng.pick(TYPES) };
+    format!("({}){}", parameters, result)
+}
+
+fn code(rng: &mut Rng) -> Code {
+    let count = 2 + rng.below(24);
+    let mut instructions: Vec<_> = (0..count - 1).map(|_| instruction(rng, count)).collect();
+    instructions.push(Instruction { mnemonic: "return", wide: false, op: Op::None });
+    let catches = (0..rng.below(3))
+        .map(|_| {
+            let start = rng.below(count - 1);
+            let end = start + 1 + rng.below(count - 1 - start);
+            let catch_type = if rng.below(2) == 0 { None } else { Some(class_name(rng)) };
+            (start, end, rng.below(count), catch_type)
+        })
+        .collect();
+    let lines = (0..rng.below(4)).map(|_| (rng.below(count), rng.next() as u2)).collect();
+    Code { max_stack: rng.next() as u2, max_locals: rng.next() as u2, instructions, catches, lines }
+}
+
+fn generate(case: u64) -> Class {
+    let mut rng = Rng::new(case);
+    let fields = (0..rng.below(5))
+        .map(|_| {
+            let (descriptor, constant) = match rng.below(6) {
+                0 => ("I", Some(Value::Integer(rng.next() as i32))),
+                1 => ("J", Some(Value::Long(rng.next() as i64))),
+                2 => ("F", Some(Value::Float(rng.next() as i16 as f32 / 4.0))),
+                3 => ("D", Some(Value::Double(rng.next() as i32 as f64 / 32.0))),
+                4 => ("Ljava/lang/String;", Some(Value::String(text(&mut rng)))),
+                _ => (*rng.pick(TYPES), None),
+            };
+            Member { flags: rng.next() as u2, name: name(&mut rng), descriptor: descriptor.to_string(), constant, code: None }
+        })
+        .collect();
+    let methods = (0..rng.below(4))
+        .map(|_| Member {
+            flags: rng.next() as u2,
+            name: name(&mut rng),
+            descriptor: method_descriptor(&mut rng),
+            constant: None,
+            code: if rng.below(4) == 0 { None } else { Some(code(&mut rng)) },
+        })
+        .collect();
+    Class {
+        version: (45 + rng.below(21) as u2, rng.below(4) as u2),
+        flags: rng.next() as u2,
+        name: class_name(&mut rng),
+        super_name: class_name(&mut rng),
+        interfaces: (0..rng.below(3)).map(|_| class_name(&mut rng)).collect(),
+        fields,
+        methods,
+        source_file: if rng.below(2) == 0 { None } else { Some(name(&mut rng)) },
+    }
+}
+
+fn operand_source(op: &Op) -> String {
+    match *op {
+        Op::None => String::new(),
+        Op::Int(value) => value.to_string(),
+        Op::Local(index) => index.to_string(),
+        Op::Iinc(index, delta) => format!("{} {}", index, delta),
+        Op::Loadable(ref value) => value.source(),
+        Op::Field(ref class, ref name, ref descriptor) => format!("Field {} {} {}", quote(class), quote(name), descriptor),
+        Op::Method(ref class, ref name, ref descriptor) => format!("Method {} {} {}", quote(class), quote(name), descriptor),
+        Op::Class(ref class) => quote(class),
+        Op::Branch(target) => format!("L{}", target),
+        Op::TableSwitch(low, ref targets, default) => {
+            let targets: Vec<_> = targets.iter().map(|target| format!("L{}", target)).collect();
+            format!("{} {} default L{}", low, targets.join(" "), default)
+        },
+        Op::LookupSwitch(ref pairs, default) => {
+            let pairs: Vec<_> = pairs.iter().map(|&(key, target)| format!("{} L{}", key, target)).collect();
+            format!("{} default L{}", pairs.join(" "), default)
+        },
+    }
+}
+
+fn source(class: &Class) -> String {
+    let mut out = String::new();
+    writeln!(out, ".version {} {}", class.version.0, class.version.1).unwrap();
+    writeln!(out, ".class 0x{:x} {}", class.flags, quote(&class.name)).unwrap();
+    writeln!(out, ".super {}", quote(&class.super_name)).unwrap();
+    for interface in &class.interfaces {
+        writeln!(out, ".implements {}", quote(interface)).unwrap();
+    }
+    for field in &class.fields {
+        writeln!(out, ".field 0x{:x} {} {}", field.flags, quote(&field.name), field.descriptor).unwrap();
+        if let Some(ref constant) = field.constant {
+            writeln!(out, "    .constantvalue {}", constant.source()).unwrap();
+        }
+        writeln!(out, ".end field").unwrap();
+    }
+    for method in &class.methods {
+        writeln!(out, ".method 0x{:x} {} {}", method.flags, quote(&method.name), method.descriptor).unwrap();
+        if let Some(ref code) = method.code {
+            writeln!(out, "    .code stack {} locals {}", code.max_stack, code.max_locals).unwrap();
+            for (i, instruction) in code.instructions.iter().enumerate() {
+                writeln!(out, "    L{}:", i).unwrap();
+                let wide = if instruction.wide { "wide " } else { "" };
+                writeln!(out, "        {}{} {}", wide, instruction.mnemonic, operand_source(&instruction.op)).unwrap();
+            }
+            for &(start, end, handler, ref catch_type) in &code.catches {
+                let catch_type = catch_type.as_ref().map_or("any".to_string(), |class| quote(class));
+                writeln!(out, "        .catch {} from L{} to L{} using L{}", catch_type, start, end, handler).unwrap();
+            }
+            if !code.lines.is_empty() {
+                writeln!(out, "        .linenumbertable").unwrap();
+                for &(start, line) in &code.lines {
+                    writeln!(out, "            L{} {}", start, line).unwrap();
+                }
+                writeln!(out, "        .end linenumbertable").unwrap();
+            }
+            writeln!(out, "    .end code").unwrap();
+        }
+        writeln!(out, ".end method").unwrap();
+    }
+    if let Some(ref source_file) = class.source_file {
+        writeln!(out, ".sourcefile {}", quote(source_file)).unwrap();
+    }
+    out
+}
+
+fn member_ref<'a>(class: &'a str, name: &'a str, descriptor: &'a str) -> MemberRef<'a> {
+    MemberRef { class_name: class, name, descriptor }
+}
+
+fn check_code(code: &Code, member: &MemberInfo, pool: &ConstantPoolRep) {
+    let attribute = member.get_code_attribute().unwrap();
+    assert_eq!((attribute.max_stack(), attribute.max_locals()), (code.max_stack, code.max_locals));
+    let decoded = bytecode::decode(attribute.code()).unwrap();
+    assert_eq!(decoded.len(), code.instructions.len());
+    let pcs: Vec<_> = decoded.iter().map(|instruction| instruction.pc).collect();
+    for (expected, actual) in code.instructions.iter().zip(&decoded) {
+        assert_eq!((actual.mnemonic(), actual.wide), (expected.mnemonic, expected.wide));
+        match (&expected.op, &actual.operand) {
+            (Op::None, Operand::None) => (),
+            (Op::Int(value), Operand::Int(actual)) => assert_eq!(actual, value),
+            (Op::Local(index), Operand::Local(actual)) => assert_eq!(actual, index),
+            (Op::Iinc(index, delta), Operand::Iinc { index: actual, delta: actual_delta }) =>
+                assert_eq!((actual, actual_delta), (index, delta)),
+            (Op::Loadable(value), Operand::Constant(index)) =>
+                assert!(value.matches(pool.loadable(*index as usize).unwrap()), "{:?}", value),
+            (Op::Field(class, name, descriptor), Operand::Constant(index)) =>
+                assert_eq!(pool.field_ref(*index as usize).unwrap(), member_ref(class, name, descriptor)),
+            (Op::Method(class, name, descriptor), Operand::Constant(index)) =>
+                assert_eq!(pool.method_ref(*index as usize).unwrap().1, member_ref(class, name, descriptor)),
+            (Op::Class(class), Operand::Constant(index)) =>
+                assert_eq!(pool.class_name(*index as usize).unwrap(), class),
+            (Op::Branch(target), Operand::Branch(pc)) => assert_eq!(*pc, pcs[*target]),
+            (Op::TableSwitch(low, targets, default), Operand::TableSwitch { default: pc, low: actual, targets: actual_targets, .. }) => {
+                assert_eq!((*actual, *pc), (*low, pcs[*default]));
+                assert_eq!(actual_targets.to_vec(), targets.iter().map(|&target| pcs[target]).collect::<Vec<_>>());
+            },
+            (Op::LookupSwitch(pairs, default), Operand::LookupSwitch { default: pc, pairs: actual }) => {
+                assert_eq!(*pc, pcs[*default]);
+                assert_eq!(actual.to_vec(), pairs.iter().map(|&(key, target)| (key, pcs[target])).collect::<Vec<_>>());
+            },
+            (expected, actual) => panic!("{:?} decoded as {:?}", expected, actual),
+        }
+    }
+
+    assert_eq!(attribute.exception_table().len(), code.catches.len());
+    for (&(start, end, handler, ref catch_type), exception) in code.catches.iter().zip(attribute.exception_table()) {
+        assert_eq!(exception.start_pc() as u32, pcs[start]);
+        assert_eq!(exception.end_pc() as u32, pcs[end]);
+        assert_eq!(exception.handler_pc() as u32, pcs[handler]);
+        assert_eq!(attribute.catch_type_name(exception).unwrap(), catch_type.as_deref());
+    }
+    let lines: Vec<_> = attribute.attributes().iter()
+        .filter_map(|attribute| match *attribute {
+            AttributeInfo::LineNumberTable(ref table) => Some(table.line_number_table()),
+            _ => None,
+        })
+        .flatten()
+        .map(|line| (line.start_pc() as u32, line.line_number()))
+        .collect();
+    assert_eq!(lines, code.lines.iter().map(|&(start, line)| (pcs[start], line)).collect::<Vec<_>>());
+}
+
+fn check_members(expected: &[Member], members: &[MemberInfo], pool: &ConstantPoolRep) {
+    assert_eq!(members.len(), expected.len());
+    for (expected, member) in expected.iter().zip(members) {
+        assert_eq!(member.access_flags().bits(), expected.flags);
+        assert_eq!(member.get_name().unwrap(), expected.name);
+        assert_eq!(member.get_descriptor().unwrap(), expected.descriptor);
+        match (&expected.constant, member.get_constant_value()) {
+            (Some(value), Some(constant)) =>
+                assert!(value.matches(pool.loadable(constant.constant_value_index() as usize).unwrap()), "{:?}", value),
+            (&None, None) => (),
+            (value, _) => panic!("{} constant value should be {:?}", expected.name, value),
+        }
+        match expected.code {
+            Some(ref code) => check_code(code, member, pool),
+            None => assert!(member.get_code_attribute().is_none()),
+        }
+    }
+}
+
+fn check_class(class: &Class, class_file: &ClassFile) {
+    assert_eq!((class_file.major_version(), class_file.minor_version()), class.version);
+    assert_eq!(class_file.access_flags().bits(), class.flags);
+    assert_eq!(class_file.name().unwrap(), class.name);
+    assert_eq!(class_file.super_name().unwrap(), class.super_name);
+    assert_eq!(class_file.interface_names().unwrap(), class.interfaces);
+    assert_eq!(class_file.source_file().unwrap(), class.source_file.as_deref());
+    check_members(&class.fields, class_file.fields(), class_file.constant_pool());
+    check_members(&class.methods, class_file.methods(), class_file.constant_pool());
+}
+
+fn disassemble(class_file: &ClassFile) -> String {
+    let mut out = Vec::new();
+    jasm::disassemble(&mut out, class_file).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn export(class_file: &ClassFile) -> String {
+    let mut out = Vec::new();
+    json::export(&mut out, class_file).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// Whatever the bytes, parsing ends in a value or an error
+fn parse_all(bytes: &[u1]) -> bool {
+    let eager = ClassFile::from_bytes(&PoSlice::new(bytes));
+    if let Ok(ref class_file) = eager {
+        for method in class_file.methods() {
+            if let Some(code) = method.get_code_attribute() {
+                let _ = bytecode::decode(code.code());
+            }
+        }
+    }
+    let stream = ClassFile::read_from(bytes);
+    assert_eq!(stream.is_ok(), eager.is_ok());
+    if let Ok(view) = ClassView::parse(bytes) {
+        for method in view.methods() {
+            let _ = method.code(view.constant_pool());
+            for attribute in method.attributes().flatten() {
+                let _ = attribute.decode(view.constant_pool());
+            }
+        }
+    }
+    eager.is_ok()
+}
+
+#[test]
+fn generated_classes_parse() {
+    for case in 0..CASES {
+        let class = generate(case);
+        let source = source(&class);
+        let bytes = jasm::assemble(&source).unwrap_or_else(|err| panic!("case {}: {}\n{}", case, err, source));
+        let slice = PoSlice::new(&bytes);
+        let class_file = ClassFile::from_bytes(&slice).unwrap();
+        assert_eq!(slice.pos(), bytes.len());
+        check_class(&class, &class_file);
+
+        let text = export(&class_file);
+        assert!(jasm::assemble(&disassemble(&class_file)).unwrap() == bytes, "case {} doesn't round trip through jasm", case);
+        assert!(json::import(&text).unwrap() == bytes, "case {} doesn't round trip through JSON", case);
+        assert_eq!(export(&ClassFile::read_from(&bytes[..]).unwrap()), text);
+
+        let view = ClassView::parse(&bytes).unwrap();
+        assert_eq!(view.name().unwrap(), class.name);
+        for (method, member) in view.methods().iter().zip(class_file.methods()) {
+            let code = method.code(view.constant_pool()).unwrap();
+            assert_eq!(code.map(|code| code.code()), member.get_code_attribute().map(|code| code.code()));
+        }
+    }
+}
+
+#[test]
+fn damaged_classes_fail_cleanly() {
+    for case in 0..CASES {
+        let bytes = jasm::assemble(&source(&generate(case))).unwrap();
+        let mut rng = Rng::new(!case);
+        for _ in 0..16 {
+            let len = rng.below(bytes.len());
+            assert!(!parse_all(&bytes[..len]), "case {} parses with only {} bytes", case, len);
+        }
+        // Most flips still parse, what matters is that none panics
+        for _ in 0..32 {
+            let mut damaged = bytes.clone();
+            let i = rng.below(damaged.len());
+            damaged[i] ^= 1 << rng.below(8);
+            parse_all(&damaged);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+class Constants {
+    static final long BIG = 0x123456789AL;
+    static final double PI = 3.5;
+    static final float HALF = 0.5f;
+    static final int MAX = 100000;
+
+    static Object[] constants() {
+        return new Object[] { "nul\0", "snow\u2603man", "\uD83D\uDE00", String.class, int[].class };
+    }
+}
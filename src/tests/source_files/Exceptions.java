@@ -0,0 +1,13 @@
+class Exceptions {
+    static int count;
+
+    static int parse(String s) {
+        try {
+            return Integer.parseInt(s);
+        } catch (NumberFormatException e) {
+            return -1;
+        } finally {
+            count++;
+        }
+    }
+}
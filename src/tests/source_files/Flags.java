@@ -0,0 +1,17 @@
+class Flags {
+    volatile boolean running;
+    transient int cached;
+    int count;
+
+    synchronized void increment() {
+        count++;
+    }
+
+    static native long nanos();
+
+    void log(String... messages) {
+        for (String message : messages) {
+            System.out.println(message);
+        }
+    }
+}
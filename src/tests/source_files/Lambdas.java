@@ -0,0 +1,8 @@
+import java.util.function.Supplier;
+
+class Lambdas {
+    static String greet(String name) {
+        Supplier<String> supplier = () -> "Hello";
+        return supplier.get() + ", " + name;
+    }
+}
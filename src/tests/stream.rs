@@ -0,0 +1,66 @@
+use std::cmp;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+use class_file::class_file::ClassFile;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use json;
+use result::Error;
+use tests::{fixtures, read_fixture};
+
+fn export(class_file: &ClassFile) -> String {
+    let mut out = Vec::new();
+    json::export(&mut out, class_file).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// Hands out at most one byte per call like a slow socket
+struct Trickle<'a>(&'a [u8]);
+
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = cmp::min(1, cmp::min(buf.len(), self.0.len()));
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
+}
+
+#[test]
+fn read_from_works() {
+    for name in &["Exceptions.class", "Constants.class", "Lambdas.class", "Test.class"] {
+        let bytes = read_fixture(name);
+        let expected = export(&ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap());
+        let file = BufReader::new(File::open(fixtures().join(name)).unwrap());
+        assert_eq!(export(&ClassFile::read_from(file).unwrap()), expected);
+        assert_eq!(export(&ClassFile::read_from(Trickle(&bytes)).unwrap()), expected);
+    }
+
+    // Nothing past the end of a class is consumed
+    let mut bytes = read_fixture("Test.class");
+    bytes.extend(read_fixture("Constants.class"));
+    let mut input = &bytes[..];
+    assert_eq!(ClassFile::read_from(&mut input).unwrap().name().unwrap(), "Test");
+    assert_eq!(ClassFile::read_from(&mut input).unwrap().name().unwrap(), "Constants");
+    assert!(input.is_empty());
+}
+
+#[test]
+fn read_from_reports_offsets() {
+    let bytes = read_fixture("Constants.class");
+    let err = ClassFile::read_from(&bytes[..9]).err().unwrap();
+    assert_eq!(err.offset(), Some(8));
+    match *err.cause() {
+        Error::Io(ref err) => assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof),
+        ref cause => panic!("{:?}", cause),
+    }
+    let err = ClassFile::read_from(Trickle(&bytes[..bytes.len() - 1])).err().unwrap();
+    assert!(err.offset().unwrap() < bytes.len() - 1);
+
+    let mut corrupt = bytes.clone();
+    corrupt[10] = 0;
+    let err = ClassFile::read_from(&corrupt[..]).err().unwrap();
+    assert_eq!(err.offset(), Some(11));
+    assert!(matches!(*err.cause(), Error::CPTag(0)));
+}
@@ -0,0 +1,41 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use class_file::class_file::ClassFile;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use trace::{self, Category};
+use tests::read_fixture;
+
+#[derive(Clone)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+#[test]
+fn trace_works() {
+    let bytes = read_fixture("Exceptions.class");
+
+    // Other tests parse at the same time, only what has to be there is checked
+    let buffer = Buffer(Arc::new(Mutex::new(Vec::new())));
+    trace::set_sink(Some(Box::new(buffer.clone())));
+    assert_eq!(trace::enable_names("pool, members,nope"), vec!["nope".to_string()]);
+    assert!(trace::enabled(Category::Pool) && !trace::enabled(Category::Reads));
+    ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+    trace::disable(Category::Pool);
+    trace::disable(Category::Members);
+    trace::set_sink(None);
+
+    let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    assert!(text.contains("pool: #1 Methodref at 10\n"));
+    assert!(text.contains("members: field count I at 315\n"));
+    assert!(text.contains("members: method parse(Ljava/lang/String;)I at 368\n"));
+    assert!(!text.contains("reads: "));
+}
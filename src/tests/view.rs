@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use class_file::class_file::ClassFile;
+use class_file::constant_pool::CPInfoRep;
+use class_file::from_bytes::FromBytes;
+use class_file::pos_slice::PoSlice;
+use class_file::view::{Attribute, ClassView};
+use tests::read_fixture;
+
+fn borrows_from(text: Cow<str>, bytes: &[u8]) -> bool {
+    match text {
+        Cow::Borrowed(text) => bytes.as_ptr_range().contains(&text.as_ptr()),
+        Cow::Owned(_) => false,
+    }
+}
+
+#[test]
+fn class_view_works() {
+    for name in &["Exceptions.class", "Constants.class", "Lambdas.class", "Test.class"] {
+        let bytes = read_fixture(name);
+        let class_file = ClassFile::from_bytes(&PoSlice::new(&bytes)).unwrap();
+        let view = ClassView::parse(&bytes).unwrap();
+        let pool = view.constant_pool();
+        assert_eq!(view.major_version(), class_file.major_version());
+        assert_eq!(view.access_flags().bits(), class_file.access_flags().bits());
+        assert_eq!(view.name().unwrap(), class_file.name().unwrap());
+        assert_eq!(view.interfaces().collect::<Vec<_>>(), class_file.interfaces());
+        assert_eq!(pool.len(), class_file.constant_pool().len());
+        for (index, cp_info) in class_file.constant_pool().iter() {
+            assert_eq!(pool.tag(index as u16).unwrap(), cp_info.tag());
+            if let CPInfoRep::Utf8(ref utf8) = *cp_info {
+                assert_eq!(pool.utf8(index as u16).unwrap(), utf8.as_str());
+            }
+        }
+
+        assert_eq!(view.methods().len(), class_file.methods().len());
+        for (method, member) in view.methods().iter().zip(class_file.methods()) {
+            let method_name = pool.utf8(method.name_index()).unwrap();
+            assert!(borrows_from(method_name.clone(), &bytes));
+            assert_eq!(method_name, member.get_name().unwrap());
+            assert_eq!(method.attributes().len(), member.attributes().len());
+            let (code, eager) = match (method.code(pool).unwrap(), member.get_code_attribute()) {
+                (Some(code), Some(eager)) => (code, eager),
+                (None, None) => continue,
+                _ => panic!("{} has code in one parser only", method_name),
+            };
+            assert_eq!(code.code(), eager.code());
+            assert!(bytes.as_ptr_range().contains(&code.code().as_ptr()));
+            assert_eq!(code.max_stack(), eager.max_stack());
+            let exceptions: Vec<_> = code.exception_table().map(|e| (e.start_pc(), e.handler_pc(), e.catch_type())).collect();
+            let expected: Vec<_> = eager.exception_table().iter()
+                .map(|e| (e.start_pc(), e.handler_pc(), e.catch_type()))
+                .collect();
+            assert_eq!(exceptions, expected);
+            for attribute in code.attributes() {
+                if let Attribute::LineNumberTable(lines) = attribute.unwrap().decode(pool).unwrap() {
+                    for line in lines {
+                        assert_eq!(eager.line_number(line.start_pc()), Some(line.line_number()));
+                    }
+                }
+            }
+        }
+    }
+
+    // Only what plain UTF-8 can't hold is copied
+    let bytes = read_fixture("Constants.class");
+    let view = ClassView::parse(&bytes).unwrap();
+    assert!(matches!(view.constant_pool().utf8(8).unwrap(), Cow::Owned(ref text) if text == "nul\0"));
+    assert!(borrows_from(view.constant_pool().utf8(10).unwrap(), &bytes));
+    let constants = view.find_method("constants", "()[Ljava/lang/Object;").unwrap().unwrap();
+    assert!(constants.code(view.constant_pool()).unwrap().is_some());
+    assert!(ClassView::parse(&bytes[..bytes.len() - 1]).is_err());
+}
@@ -0,0 +1,737 @@
+/*
+* The programs in src/tests/programs run on the interpreter and print exactly what the JDK's java
+* printed for them, build.sh there says how both are made.
+*/
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use class_file::class_file::ClassFile;
+use class_path::{ClassPath, ClassSource, Directory};
+use jasm;
+use jdk::Jdk;
+use result::{Error, Result};
+use types::u1;
+use vm::runtime::CLASSES;
+use vm::value::Value;
+use vm::{Flow, Vm, MIN_JDK_VERSION};
+
+// A writer the test keeps a handle to after the VM took it
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Output {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn programs() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/programs")
+}
+
+// stdout, stderr and the exit status
+fn run(program: &str, args: &[&str]) -> (String, String, i32) {
+    let mut class_path = ClassPath::new();
+    class_path.push(Directory::new(programs().join("classes")));
+    run_on(Vm::new(class_path), program, args)
+}
+
+fn run_on(mut vm: Vm, program: &str, args: &[&str]) -> (String, String, i32) {
+    let (stdout, stderr) = (Output::default(), Output::default());
+    vm.set_stdout(stdout.clone());
+    vm.set_stderr(stderr.clone());
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let status = vm.run_main(program, &args).unwrap();
+    (stdout.text(), stderr.text(), status)
+}
+
+fn expected(program: &str) -> (String, String, i32) {
+    let read = |extension| fs::read_to_string(programs().join("expected").join(format!("{}.{}", program, extension))).unwrap();
+    (read("out"), read("err"), read("status").trim().parse().unwrap())
+}
+
+fn assert_runs_like_java(program: &str) {
+    let (stdout, stderr, status) = run(program, &[]);
+    let (expected_stdout, expected_stderr, expected_status) = expected(program);
+    assert_eq!(stdout, expected_stdout, "stdout of {}", program);
+    assert_eq!(stderr, expected_stderr, "stderr of {}", program);
+    assert_eq!(status, expected_status, "exit status of {}", program);
+}
+
+#[test]
+fn basics_work() {
+    assert_runs_like_java("Basics");
+}
+
+#[test]
+fn numbers_work() {
+    assert_runs_like_java("Numbers");
+}
+
+#[test]
+fn strings_work() {
+    assert_runs_like_java("Strings");
+}
+
+#[test]
+fn classes_work() {
+    assert_runs_like_java("Classes");
+}
+
+#[test]
+fn exceptions_work() {
+    assert_runs_like_java("Exceptions");
+}
+
+#[test]
+fn nestmates_work() {
+    assert_runs_like_java("Nestmates");
+}
+
+#[test]
+fn exit_works() {
+    assert_runs_like_java("Exit");
+}
+
+#[test]
+fn args_work() {
+    let (stdout, _, status) = run("Basics", &["args"]);
+    assert!(stdout.starts_with("Hello, args!\n"));
+    assert_eq!(status, 0);
+    let (stdout, _, status) = run("Exit", &["one", "two"]);
+    assert_eq!(stdout, "exiting");
+    assert_eq!(status, 5);
+}
+
+#[test]
+fn missing_classes_are_errors() {
+    let mut vm = Vm::new(ClassPath::new());
+    match vm.run_main("Missing", &[]) {
+        Err(Error::ClassNotFound(name)) => assert_eq!(name, "Missing"),
+        other => panic!("expected ClassNotFound, got {:?}", other),
+    }
+    let mut vm = Vm::new(ClassPath::new());
+    match vm.run_main("java/lang/Object", &[]) {
+        Err(Error::MainNotFound) => (),
+        other => panic!("expected MainNotFound, got {:?}", other),
+    }
+}
+
+// Every class build.sh compiled is bundled, under the name it declares
+#[test]
+fn runtime_is_bundled() {
+    let classes = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/vm/runtime/classes");
+    let mut on_disk = Vec::new();
+    for package in &["java/io", "java/lang", "java/lang/invoke", "java/lang/reflect", "java/util",
+        "java/util/concurrent/atomic", "java/util/concurrent/locks", "jdk/internal/misc", "jdk/internal/reflect"] {
+        for entry in fs::read_dir(classes.join(package)).unwrap() {
+            let entry = entry.unwrap();
+            if entry.file_type().unwrap().is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            on_disk.push(format!("{}/{}", package, name.trim_end_matches(".class")));
+        }
+    }
+    on_disk.sort();
+    let mut bundled: Vec<String> = CLASSES.iter().map(|&(name, _)| name.to_string()).collect();
+    bundled.sort();
+    assert_eq!(bundled, on_disk);
+    for &(name, bytes) in CLASSES {
+        let class_file = ClassFile::read_from(bytes).unwrap();
+        assert_eq!(class_file.name().unwrap(), name);
+    }
+}
+
+fn twice(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Int(args[0].as_int()? * 2)))
+}
+
+#[test]
+fn natives_work() {
+    assert_runs_like_java("Natives");
+    let mut class_path = ClassPath::new();
+    class_path.push(Directory::new(programs().join("classes")));
+    let mut vm = Vm::new(class_path);
+    let stdout = Output::default();
+    vm.set_stdout(stdout.clone());
+    vm.register_native("Natives", "twice", "(I)I", twice);
+    assert_eq!(vm.run_main("Natives", &["twice".to_string()]).unwrap(), 0);
+    assert!(stdout.text().ends_with("\n42\n"));
+}
+
+// The runtime's own natives are ACC_NATIVE in the class files
+#[test]
+fn runtime_natives_are_native() {
+    let &(_, bytes) = CLASSES.iter().find(|&&(name, _)| name == "java/lang/Object").unwrap();
+    let class_file = ClassFile::read_from(bytes).unwrap();
+    let method = |name: &str| class_file.methods().iter().find(|method| method.get_name().unwrap() == name).unwrap();
+    assert!(method("hashCode").method_flags().is_native());
+    assert!(method("getClass").method_flags().is_native());
+    assert!(!method("toString").method_flags().is_native());
+}
+
+#[test]
+fn threads_work() {
+    assert_runs_like_java("Threads");
+    let mut class_path = ClassPath::new();
+    class_path.push(Directory::new(programs().join("classes")));
+    let mut vm = Vm::new(class_path);
+    match vm.run_main("Threads", &["deadlock".to_string()]) {
+        Err(Error::Deadlock) => (),
+        other => panic!("expected Deadlock, got {:?}", other),
+    }
+}
+
+#[test]
+fn monitors_work() {
+    assert_runs_like_java("Monitors");
+    let mut class_path = ClassPath::new();
+    class_path.push(Directory::new(programs().join("classes")));
+    let mut vm = Vm::new(class_path);
+    match vm.run_main("Monitors", &["deadlock".to_string()]) {
+        Err(Error::Deadlock) => (),
+        other => panic!("expected Deadlock, got {:?}", other),
+    }
+}
+
+// javac never leaves a monitor it didn't enter
+const UNBALANCED: &str = r#"
+.version 49 0
+.class public super Unbalanced
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 2 locals 1
+        new java/lang/Object
+        dup
+        invokespecial Method java/lang/Object <init> ()V
+        monitorexit
+        return
+    .end code
+.end method
+"#;
+
+// Classes assembled from jasm sources
+struct Assembled(Vec<Vec<u1>>);
+
+impl ClassSource for Assembled {
+    fn find_class(&self, name: &str) -> Result<Option<Vec<u1>>> {
+        for bytes in self.0.iter() {
+            if ClassFile::read_from(&bytes[..])?.name()? == name {
+                return Ok(Some(bytes.clone()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// The exit status and stderr of the main class of the assembled sources
+fn run_assembled(main: &str, sources: &[&str]) -> (i32, String) {
+    let mut class_path = ClassPath::new();
+    class_path.push(Assembled(sources.iter().map(|source| jasm::assemble(source).unwrap()).collect()));
+    let mut vm = Vm::new(class_path);
+    let stderr = Output::default();
+    vm.set_stderr(stderr.clone());
+    (vm.run_main(main, &[]).unwrap(), stderr.text())
+}
+
+#[test]
+fn unbalanced_monitorexit_throws() {
+    assert_eq!(run_assembled("Unbalanced", &[UNBALANCED]),
+        (1, "Exception in thread \"main\" java.lang.IllegalMonitorStateException\n\tat Unbalanced.main(Unknown Source)\n".to_string()));
+}
+
+#[test]
+fn defaults_work() {
+    assert_runs_like_java("Defaults");
+}
+
+#[test]
+fn method_handles_work() {
+    assert_runs_like_java("Handles");
+}
+
+// Two unrelated interfaces with a default m(), javac only lets this happen across separate compilations
+const CONFLICTING: &[&str] = &[r#"
+.version 52 0
+.class interface abstract P
+.super java/lang/Object
+
+.method public m ()V
+    .code stack 0 locals 1
+        return
+    .end code
+.end method
+"#, r#"
+.version 52 0
+.class interface abstract Q
+.super java/lang/Object
+
+.method public m ()V
+    .code stack 0 locals 1
+        return
+    .end code
+.end method
+"#, r#"
+.version 52 0
+.class public super Conflicting
+.super java/lang/Object
+.implements P
+.implements Q
+
+.method <init> ()V
+    .code stack 1 locals 1
+        aload_0
+        invokespecial Method java/lang/Object <init> ()V
+        return
+    .end code
+.end method
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 2 locals 1
+        new Conflicting
+        dup
+        invokespecial Method Conflicting <init> ()V
+        invokevirtual Method Conflicting m ()V
+        return
+    .end code
+.end method
+"#];
+
+#[test]
+fn conflicting_defaults_throw() {
+    assert_eq!(run_assembled("Conflicting", CONFLICTING),
+        (1, "Exception in thread \"main\" java.lang.IncompatibleClassChangeError: Conflicting default methods: P.m Q.m\n\tat Conflicting.main(Unknown Source)\n".to_string()));
+}
+
+// An InterfaceMethodref naming a class and a Methodref naming an interface, JVMS §5.4.3.3 and §5.4.3.4
+const INTERFACE_REF: &str = r#"
+.version 52 0
+.class public super InterfaceRef
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 1 locals 1
+        iconst_0
+        invokestatic InterfaceMethod java/lang/Integer valueOf (I)Ljava/lang/Integer;
+        return
+    .end code
+.end method
+"#;
+
+const METHOD_REF: &str = r#"
+.version 52 0
+.class public super MethodRef
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 1 locals 1
+        aconst_null
+        invokevirtual Method java/lang/Runnable run ()V
+        return
+    .end code
+.end method
+"#;
+
+#[test]
+fn wrong_method_ref_kinds_throw() {
+    assert_eq!(run_assembled("InterfaceRef", &[INTERFACE_REF]),
+        (1, "Exception in thread \"main\" java.lang.IncompatibleClassChangeError: Found class java.lang.Integer, but interface was expected\n\tat InterfaceRef.main(Unknown Source)\n".to_string()));
+    assert_eq!(run_assembled("MethodRef", &[METHOD_REF]),
+        (1, "Exception in thread \"main\" java.lang.IncompatibleClassChangeError: Found interface java.lang.Runnable, but class was expected\n\tat MethodRef.main(Unknown Source)\n".to_string()));
+}
+
+// A private field and two final ones the other classes reach for
+const SECRET: &str = r#"
+.version 52 0
+.class super Secret
+.super java/lang/Object
+
+.field private static value I
+.end field
+
+.field final count I
+.end field
+
+.field static final LOCK Ljava/lang/Object;
+.end field
+
+.method <init> ()V
+    .code stack 2 locals 1
+        aload_0
+        invokespecial Method java/lang/Object <init> ()V
+        aload_0
+        iconst_1
+        putfield Field Secret count I
+        return
+    .end code
+.end method
+
+.method static reset ()V
+    .code stack 1 locals 0
+        aconst_null
+        putstatic Field Secret LOCK Ljava/lang/Object;
+        return
+    .end code
+.end method
+"#;
+
+const SNOOP: &str = r#"
+.version 52 0
+.class public super Snoop
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 1 locals 1
+        getstatic Field Secret value I
+        pop
+        return
+    .end code
+.end method
+"#;
+
+const OVERWRITE: &str = r#"
+.version 52 0
+.class public super Overwrite
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 2 locals 1
+        new Secret
+        dup
+        invokespecial Method Secret <init> ()V
+        iconst_2
+        putfield Field Secret count I
+        return
+    .end code
+.end method
+"#;
+
+const RESET: &str = r#"
+.version 52 0
+.class public super Reset
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 0 locals 1
+        invokestatic Method Secret reset ()V
+        return
+    .end code
+.end method
+"#;
+
+#[test]
+fn field_access_is_checked() {
+    assert_eq!(run_assembled("Snoop", &[SECRET, SNOOP]),
+        (1, "Exception in thread \"main\" java.lang.IllegalAccessError: class Snoop tried to access private field Secret.value\n\tat Snoop.main(Unknown Source)\n".to_string()));
+    assert_eq!(run_assembled("Overwrite", &[SECRET, OVERWRITE]),
+        (1, "Exception in thread \"main\" java.lang.IllegalAccessError: Update to non-static final field Secret.count attempted from a different class (Overwrite) than the field's declaring class\n\tat Overwrite.main(Unknown Source)\n".to_string()));
+    assert_eq!(run_assembled("Reset", &[SECRET, RESET]),
+        (1, "Exception in thread \"main\" java.lang.IllegalAccessError: Update to static final field Secret.LOCK attempted from a different method (reset) than the initializer method <clinit>\n\tat Secret.reset(Unknown Source)\n\tat Reset.main(Unknown Source)\n".to_string()));
+}
+
+// ldc of MethodType and MethodHandle constants, javac only writes them as bootstrap arguments
+const CONSTANTS: &str = r#"
+.version 52 0
+.class public super Constants
+.super java/lang/Object
+
+.field count I
+.end field
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 3 locals 1
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc MethodType "(I[Ljava/lang/String;)V"
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc MethodHandle REF_invokeStatic Method Constants main ([Ljava/lang/String;)V
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc MethodHandle REF_getField Field Constants count I
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc MethodHandle REF_newInvokeSpecial Method java/lang/StringBuilder <init> (Ljava/lang/String;)V
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc MethodHandle REF_invokeInterface InterfaceMethod java/lang/CharSequence charAt (I)C
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+        ldc MethodType "()V"
+        ldc MethodType "()V"
+        if_acmpne Fail
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc MethodHandle REF_getStatic Field Secret value I
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+    Fail:
+        return
+    .end code
+.end method
+"#;
+
+const MISSING_TYPE: &str = r#"
+.version 52 0
+.class public super MissingType
+.super java/lang/Object
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 1 locals 1
+        ldc MethodType "(LMissing;)V"
+        pop
+        return
+    .end code
+.end method
+"#;
+
+#[test]
+fn method_handle_constants_work() {
+    assert_eq!(run_assembled("Constants", &[CONSTANTS, SECRET]), (1, "\
+(int,String[])void
+MethodHandle(String[])void
+MethodHandle(Constants)int
+MethodHandle(String)StringBuilder
+MethodHandle(CharSequence,int)char
+Exception in thread \"main\" java.lang.IllegalAccessError: class Constants tried to access Secret.value
+\tat Constants.main(Unknown Source)
+".to_string()));
+    assert_eq!(run_assembled("MissingType", &[MISSING_TYPE]),
+        (1, "Exception in thread \"main\" java.lang.NoClassDefFoundError: Missing\n\tat MissingType.main(Unknown Source)\n".to_string()));
+}
+
+// Bootstrap methods of its own: a constant call site linked once per instruction, a mutable one
+// whose target changes, Dynamic constants with a Dynamic argument and bootstrap methods that fail
+const INDY: &str = r#"
+.version 55 0
+.class public super Indy
+.super java/lang/Object
+
+.field static site Ljava/lang/invoke/MutableCallSite;
+.end field
+
+.method static hello ()V
+    .code stack 2 locals 0
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc String "hello"
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/String;)V
+        return
+    .end code
+.end method
+
+.method static add (II)I
+    .code stack 2 locals 2
+        iload 0
+        iload 1
+        iadd
+        ireturn
+    .end code
+.end method
+
+.method static first ()V
+    .code stack 5 locals 0
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc String "first"
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/String;)V
+        getstatic Field Indy site Ljava/lang/invoke/MutableCallSite;
+        invokestatic Method java/lang/invoke/MethodHandles lookup ()Ljava/lang/invoke/MethodHandles$Lookup;
+        ldc Class Indy
+        ldc String "second"
+        ldc MethodType "()V"
+        invokevirtual Method java/lang/invoke/MethodHandles$Lookup findStatic (Ljava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/MethodHandle;
+        invokevirtual Method java/lang/invoke/MutableCallSite setTarget (Ljava/lang/invoke/MethodHandle;)V
+        return
+    .end code
+.end method
+
+.method static second ()V
+    .code stack 2 locals 0
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc String "second"
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/String;)V
+        return
+    .end code
+.end method
+
+.method static constant (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;
+    .code stack 6 locals 3
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        aload 1
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/String;)V
+        new java/lang/invoke/ConstantCallSite
+        dup
+        aload 0
+        ldc Class Indy
+        aload 1
+        aload 2
+        invokevirtual Method java/lang/invoke/MethodHandles$Lookup findStatic (Ljava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/MethodHandle;
+        invokespecial Method java/lang/invoke/ConstantCallSite <init> (Ljava/lang/invoke/MethodHandle;)V
+        areturn
+    .end code
+.end method
+
+.method static mutable (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;
+    .code stack 6 locals 3
+        new java/lang/invoke/MutableCallSite
+        dup
+        aload 0
+        ldc Class Indy
+        ldc String "first"
+        aload 2
+        invokevirtual Method java/lang/invoke/MethodHandles$Lookup findStatic (Ljava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/MethodHandle;
+        invokespecial Method java/lang/invoke/MutableCallSite <init> (Ljava/lang/invoke/MethodHandle;)V
+        dup
+        putstatic Field Indy site Ljava/lang/invoke/MutableCallSite;
+        areturn
+    .end code
+.end method
+
+.method static failing (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;
+    .code stack 3 locals 3
+        new java/lang/IllegalStateException
+        dup
+        ldc String "no call site"
+        invokespecial Method java/lang/IllegalStateException <init> (Ljava/lang/String;)V
+        athrow
+    .end code
+.end method
+
+.method static notASite (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/Object;
+    .code stack 1 locals 3
+        aload 1
+        areturn
+    .end code
+.end method
+
+.method static print (Ljava/lang/Throwable;)V
+    .code stack 2 locals 1
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        aload 0
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        aload 0
+        invokevirtual Method java/lang/Throwable getCause ()Ljava/lang/Throwable;
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/Object;)V
+        return
+    .end code
+.end method
+
+.method public static main ([Ljava/lang/String;)V
+    .code stack 3 locals 2
+        iconst_0
+        istore 1
+    Loop:
+        invokedynamic InvokeDynamic 0 hello ()V
+        invokedynamic InvokeDynamic 5 swap ()V
+        iinc 1 1
+        iload 1
+        iconst_2
+        if_icmplt Loop
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        iconst_3
+        iconst_4
+        invokedynamic InvokeDynamic 0 add (II)I
+        invokevirtual Method java/io/PrintStream println (I)V
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc Dynamic 2 answer I
+        invokevirtual Method java/io/PrintStream println (I)V
+        getstatic Field java/lang/System err Ljava/io/PrintStream;
+        ldc Dynamic 3 text Ljava/lang/String;
+        invokevirtual Method java/io/PrintStream println (Ljava/lang/String;)V
+    Failing:
+        invokedynamic InvokeDynamic 1 hello ()V
+    FailingEnd:
+        goto NotASite
+    FailingHandler:
+        invokestatic Method Indy print (Ljava/lang/Throwable;)V
+    NotASite:
+        invokedynamic InvokeDynamic 4 hello ()V
+    NotASiteEnd:
+        return
+    NotASiteHandler:
+        invokestatic Method Indy print (Ljava/lang/Throwable;)V
+        return
+    .catch java/lang/BootstrapMethodError from Failing to FailingEnd using FailingHandler
+    .catch java/lang/BootstrapMethodError from NotASite to NotASiteEnd using NotASiteHandler
+    .end code
+.end method
+
+.bootstrapmethods
+    MethodHandle REF_invokeStatic Method Indy constant (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;
+    MethodHandle REF_invokeStatic Method Indy failing (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;
+    MethodHandle REF_invokeStatic Method java/lang/invoke/ConstantBootstraps invoke (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/Class;Ljava/lang/invoke/MethodHandle;[Ljava/lang/Object;)Ljava/lang/Object; MethodHandle REF_invokeStatic Method Indy add (II)I Integer 40 Integer 2
+    MethodHandle REF_invokeStatic Method java/lang/invoke/ConstantBootstraps invoke (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/Class;Ljava/lang/invoke/MethodHandle;[Ljava/lang/Object;)Ljava/lang/Object; MethodHandle REF_invokeStatic Method java/lang/String valueOf (Ljava/lang/Object;)Ljava/lang/String; Dynamic 2 answer I
+    MethodHandle REF_invokeStatic Method Indy notASite (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/Object;
+    MethodHandle REF_invokeStatic Method Indy mutable (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;
+.end bootstrapmethods
+"#;
+
+#[test]
+fn bootstrap_methods_work() {
+    assert_eq!(run_assembled("Indy", &[INDY]), (0, "\
+hello
+hello
+first
+hello
+second
+add
+7
+42
+42
+java.lang.BootstrapMethodError: bootstrap method initialization exception
+java.lang.IllegalStateException: no call site
+java.lang.BootstrapMethodError: call site initialization exception
+java.lang.ClassCastException: java.lang.String is not a CallSite
+".to_string()));
+}
+
+#[test]
+fn lambdas_work() {
+    assert_runs_like_java("Lambdas");
+}
+
+#[test]
+fn string_concatenation_works() {
+    assert_runs_like_java("Concat");
+}
+
+#[test]
+fn reflection_works() {
+    assert_runs_like_java("Reflect");
+}
+
+#[test]
+fn concurrency_works() {
+    assert_runs_like_java("Concurrency");
+}
+
+// The programs that don't need java.lang.invoke or the JDK's reflection run on the JDK's own
+// java.base too
+#[test]
+fn jdk_boots() {
+    let jdk = match Jdk::locate() {
+        Some(ref jdk) if jdk.feature_version().unwrap_or(0) >= MIN_JDK_VERSION => jdk.clone(),
+        _ => {
+            eprintln!("no JDK {} or later found, skipping booting it", MIN_JDK_VERSION);
+            return;
+        },
+    };
+    for program in &["Basics", "Strings", "Exceptions", "Nestmates", "Exit", "Natives", "Threads", "Monitors", "Defaults"] {
+        let mut class_path = ClassPath::new();
+        class_path.push(Directory::new(programs().join("classes")));
+        let vm = Vm::boot_from(&jdk, class_path).unwrap();
+        assert_eq!(run_on(vm, program, &[]), expected(program), "{} on {}", program, jdk.home().display());
+    }
+}
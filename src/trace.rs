@@ -0,0 +1,90 @@
+/*
+* Parse events by category, all off by default. `JVM_RS_TRACE=pool,attributes jvm_rs javap X.class`
+* turns categories on, `all` turns on every one. Events go to stderr unless a sink is set so they
+* never mix with what the CLI writes to stdout.
+*/
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Category {
+    // Every u1, u2, u4 and slice with its offset, very loud
+    Reads = 1,
+    // Lengths in front of tables
+    Counts = 2,
+    Pool = 4,
+    Members = 8,
+    Attributes = 16,
+}
+
+const CATEGORIES: [Category; 5] = [Category::Reads, Category::Counts, Category::Pool, Category::Members, Category::Attributes];
+
+static ENABLED: AtomicU8 = AtomicU8::new(0);
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+impl Category {
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Reads => "reads",
+            Category::Counts => "counts",
+            Category::Pool => "pool",
+            Category::Members => "members",
+            Category::Attributes => "attributes",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        CATEGORIES.iter().cloned().find(|category| category.name() == name)
+    }
+}
+
+pub fn enable(category: Category) {
+    ENABLED.fetch_or(category as u8, Ordering::Relaxed);
+}
+
+pub fn disable(category: Category) {
+    ENABLED.fetch_and(!(category as u8), Ordering::Relaxed);
+}
+
+#[inline]
+pub fn enabled(category: Category) -> bool {
+    ENABLED.load(Ordering::Relaxed) & category as u8 != 0
+}
+
+// Comma separated names like `pool,attributes` or `all`, returns the names it doesn't know
+pub fn enable_names(names: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        match Category::from_name(name) {
+            Some(category) => enable(category),
+            None if name == "all" => CATEGORIES.iter().cloned().for_each(enable),
+            None => unknown.push(name.to_string()),
+        }
+    }
+    unknown
+}
+
+// None goes back to stderr
+pub fn set_sink(sink: Option<Box<dyn Write + Send>>) {
+    *SINK.lock().unwrap_or_else(|err| err.into_inner()) = sink;
+}
+
+// Use trace! instead, it doesn't format anything when the category is off
+pub fn log(category: Category, args: fmt::Arguments) {
+    let mut sink = SINK.lock().unwrap_or_else(|err| err.into_inner());
+    // A trace that can't be written is dropped, parsing goes on
+    let _ = match *sink {
+        Some(ref mut sink) => writeln!(sink, "{}: {}", category.name(), args),
+        None => writeln!(io::stderr(), "{}: {}", category.name(), args),
+    };
+}
+
+macro_rules! trace {
+    ($category:expr, $($arg:tt)*) => {
+        if $crate::trace::enabled($category) {
+            $crate::trace::log($category, format_args!($($arg)*));
+        }
+    };
+}
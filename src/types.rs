@@ -1,6 +1,6 @@
-#[allow(non_camel_case_types)]
-pub type u1 = u8;
-#[allow(non_camel_case_types)]
-pub type u2 = u16;
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types)]
+pub type u1 = u8;
+#[allow(non_camel_case_types)]
+pub type u2 = u16;
+#[allow(non_camel_case_types)]
 pub type u4 = u32;
\ No newline at end of file
@@ -0,0 +1,276 @@
+/*
+* What Unsafe and VarHandle do to fields and array elements. Threads are green and switch between
+* instructions, so every access is atomic and every thread sees every write in order: plain,
+* opaque, acquire/release and volatile accesses are all sequentially consistent and the fences
+* have nothing to do. Unsafe's offsets are field slots and array indexes
+*/
+use class_file::descriptor::{FieldType, MethodDescriptor};
+use result::Error;
+use vm::class::ClassId;
+use vm::heap::{Body, ObjectRef};
+use vm::interpreter::Action;
+use vm::invoke::wrapper_of;
+use vm::value::Value;
+use vm::{Flow, Vm};
+
+// Where a value lives
+#[derive(Clone, Copy)]
+pub(super) enum Location {
+    Static(ClassId, usize),
+    Field(ObjectRef, usize),
+    Element(ObjectRef, usize),
+}
+
+// The VarHandle access modes by what they take after the coordinates and what they do
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Get,
+    Set,
+    CompareAndSet,
+    CompareAndExchange,
+    GetAndSet,
+    GetAndAdd,
+    GetAndBitwise(Bitwise),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Bitwise {
+    Or,
+    And,
+    Xor,
+}
+
+impl Bitwise {
+    fn apply(self, a: i64, b: i64) -> i64 {
+        match self {
+            Bitwise::Or => a | b,
+            Bitwise::And => a & b,
+            Bitwise::Xor => a ^ b,
+        }
+    }
+}
+
+impl Mode {
+    fn of(name: &str) -> Option<Self> {
+        let name = ["Acquire", "Release", "Plain"].iter()
+            .find(|suffix| name.ends_with(*suffix))
+            .map_or(name, |suffix| &name[..name.len() - suffix.len()]);
+        Some(match name {
+            "get" | "getVolatile" | "getOpaque" => Mode::Get,
+            "set" | "setVolatile" | "setOpaque" => Mode::Set,
+            "compareAndSet" | "weakCompareAndSet" => Mode::CompareAndSet,
+            "compareAndExchange" => Mode::CompareAndExchange,
+            "getAndSet" => Mode::GetAndSet,
+            "getAndAdd" => Mode::GetAndAdd,
+            "getAndBitwiseOr" => Mode::GetAndBitwise(Bitwise::Or),
+            "getAndBitwiseAnd" => Mode::GetAndBitwise(Bitwise::And),
+            "getAndBitwiseXor" => Mode::GetAndBitwise(Bitwise::Xor),
+            _ => return None,
+        })
+    }
+
+    // Values after the coordinates
+    fn operands(self) -> usize {
+        match self {
+            Mode::Get => 0,
+            Mode::CompareAndSet | Mode::CompareAndExchange => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl Vm {
+    pub(super) fn load_at(&self, location: Location) -> Flow<Value> {
+        Ok(match location {
+            Location::Static(class, slot) => self.class(class).statics[slot],
+            Location::Field(object, slot) => self.field(object, slot)?,
+            Location::Element(array, index) => self.array(array)?.get(index)
+                .ok_or_else(|| Error::Verify(format!("no element {}", index)))?,
+        })
+    }
+
+    pub(super) fn store_at(&mut self, location: Location, value: Value) -> Flow<()> {
+        match location {
+            Location::Static(class, slot) => self.class_mut(class).statics[slot] = value,
+            Location::Field(object, slot) => self.set_field(object, slot, value)?,
+            Location::Element(array, index) => if !self.array_mut(array)?.set(index, value) {
+                return Err(Error::Verify(format!("can't store {:?} in element {}", value, index)).into());
+            },
+        }
+        Ok(())
+    }
+
+    // Unsafe's object and offset, a field slot of an object or an index of an array
+    pub(super) fn location(&mut self, object: ObjectRef, offset: i64) -> Flow<Location> {
+        let (is_array, length) = match *self.heap.get(object).body() {
+            Body::Fields(ref fields) => (false, fields.len()),
+            Body::Array(ref array) => (true, array.len()),
+        };
+        if offset < 0 || offset as usize >= length {
+            return Err(self.exception("java/lang/InternalError", Some(format!("bad offset {}", offset))));
+        }
+        Ok(if is_array { Location::Element(object, offset as usize) } else { Location::Field(object, offset as usize) })
+    }
+
+    // Stores `value` if the location holds `expected`, floating point values compare by bits
+    // like the JDK's
+    pub(super) fn compare_and_set(&mut self, location: Location, expected: Value, value: Value) -> Flow<(bool, Value)> {
+        let witness = self.load_at(location)?;
+        let same = match (witness, expected) {
+            (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            (Value::Double(a), Value::Double(b)) => a.to_bits() == b.to_bits(),
+            (a, b) => a == b,
+        };
+        if same {
+            self.store_at(location, value)?;
+        }
+        Ok((same, witness))
+    }
+
+    // invokevirtual of a VarHandle access mode with the call site's descriptor. The handle, the
+    // coordinates (none for a static field, the object for a field, the array and the index for
+    // an element) and the operands are on the operand stack. Values convert like asType does
+    pub(super) fn access_var(&mut self, name: &str, descriptor: &str) -> Flow<Action> {
+        let site_type = MethodDescriptor::parse(descriptor)?;
+        let count = site_type.params().len();
+        let handle = self.receiver(count + 1)?;
+        let mode = Mode::of(name).ok_or_else(|| Error::Verify(format!("no VarHandle access mode {}", name)))?;
+        let field = self.named_field(handle, "field")?.as_ref()?;
+        let var_class = match self.named_field(handle, "varType")?.as_ref()? {
+            Some(mirror) => self.class_of_mirror(mirror).ok_or_else(|| Error::Verify("Class object without a class".to_string()))?,
+            None => return Err(Error::Verify("VarHandle without a type".to_string()).into()),
+        };
+        let var_type = self.field_type_of(var_class)?.ok_or_else(|| Error::Verify("VarHandle of void".to_string()))?;
+        // The declaring class, the slot and whether the field is static and final, None for elements
+        let field = match field {
+            Some(getter) => {
+                let (class, slot, is_static) = self.field_handle(getter)?;
+                let is_final = self.class(class).fields().iter()
+                    .any(|field| field.slot == slot && field.flags().is_static() == is_static && field.flags().is_final());
+                if is_static && !self.is_initialized(class) {
+                    return Ok(Action::Initialize(class));
+                }
+                Some((class, slot, is_static, is_final))
+            },
+            None => None,
+        };
+        let coordinates = match field {
+            Some((_, _, true, _)) => 0,
+            Some(_) => 1,
+            None => 2,
+        };
+        if count != coordinates + mode.operands() {
+            let message = format!("expected {} arguments for {} but got {}", coordinates + mode.operands(), name, descriptor);
+            return Err(self.exception("java/lang/invoke/WrongMethodTypeException", Some(message)));
+        }
+        let below = self.frame().stack.len() - count;
+        let args: Vec<Value> = self.frame_mut().stack.drain(below..).collect();
+        self.frame_mut().pop()?;
+        let location = match field {
+            Some((class, slot, true, _)) => Location::Static(class, slot),
+            Some((class, slot, false, _)) => {
+                let object = self.non_null(args[0].as_ref()?)?;
+                let object_class = self.heap.get(object).class();
+                if !self.is_assignable(object_class, class) {
+                    return Err(self.cast_error(object_class, class));
+                }
+                Location::Field(object, slot)
+            },
+            None => {
+                let array = self.non_null(args[0].as_ref()?)?;
+                let array_class = self.heap.get(array).class();
+                let handle_class = match self.named_field(handle, "arrayClass")?.as_ref()? {
+                    Some(mirror) => self.class_of_mirror(mirror).ok_or_else(|| Error::Verify("Class object without a class".to_string()))?,
+                    None => return Err(Error::Verify("VarHandle without a field or an array class".to_string()).into()),
+                };
+                if !self.is_assignable(array_class, handle_class) {
+                    return Err(self.cast_error(array_class, handle_class));
+                }
+                let index = args[1].as_int()?;
+                let length = self.array(array)?.len();
+                if index < 0 || index as usize >= length {
+                    let message = format!("Index {} out of bounds for length {}", index, length);
+                    return Err(self.exception("java/lang/ArrayIndexOutOfBoundsException", Some(message)));
+                }
+                Location::Element(array, index as usize)
+            },
+        };
+        let is_final = field.is_some_and(|(_, _, _, is_final)| is_final);
+        if is_final && mode != Mode::Get {
+            return Err(self.exception("java/lang/UnsupportedOperationException", None));
+        }
+        let mut operands = Vec::with_capacity(mode.operands());
+        for (i, &arg) in args.iter().enumerate().skip(coordinates) {
+            operands.push(self.coerce(arg, &site_type.params()[i], &var_type)?);
+        }
+        let (result, result_type) = match mode {
+            Mode::Get => (Some(self.load_at(location)?), &var_type),
+            Mode::Set => {
+                self.store_at(location, operands[0])?;
+                (None, &var_type)
+            },
+            Mode::CompareAndSet => (Some(Value::Int(self.compare_and_set(location, operands[0], operands[1])?.0 as i32)), &FieldType::Boolean),
+            Mode::CompareAndExchange => (Some(self.compare_and_set(location, operands[0], operands[1])?.1), &var_type),
+            Mode::GetAndSet => {
+                let old = self.load_at(location)?;
+                self.store_at(location, operands[0])?;
+                (Some(old), &var_type)
+            },
+            Mode::GetAndAdd | Mode::GetAndBitwise(_) => {
+                let old = self.load_at(location)?;
+                let new = match (mode, old, operands[0]) {
+                    (Mode::GetAndAdd, Value::Float(a), Value::Float(b)) => Some(Value::Float(a + b)),
+                    (Mode::GetAndAdd, Value::Double(a), Value::Double(b)) => Some(Value::Double(a + b)),
+                    (Mode::GetAndAdd, Value::Int(a), Value::Int(b)) if var_type != FieldType::Boolean => Some(Value::Int(a.wrapping_add(b))),
+                    (Mode::GetAndAdd, Value::Long(a), Value::Long(b)) => Some(Value::Long(a.wrapping_add(b))),
+                    (Mode::GetAndBitwise(op), Value::Int(a), Value::Int(b)) => Some(Value::Int(op.apply(a as i64, b as i64) as i32)),
+                    (Mode::GetAndBitwise(op), Value::Long(a), Value::Long(b)) => Some(Value::Long(op.apply(a, b))),
+                    _ => None,
+                };
+                match new {
+                    Some(new) => self.store_at(location, narrow(new, &var_type))?,
+                    None => return Err(self.exception("java/lang/UnsupportedOperationException", None)),
+                }
+                (Some(old), &var_type)
+            },
+        };
+        if let (Some(result), Some(return_type)) = (result, site_type.return_type()) {
+            let result = self.coerce(result, result_type, return_type)?;
+            self.frame_mut().push(result);
+        }
+        Ok(Action::Next)
+    }
+
+    // A value of one type as another, with the conversions of asType
+    fn coerce(&mut self, value: Value, from: &FieldType, to: &FieldType) -> Flow<Value> {
+        if from == to {
+            return Ok(value);
+        }
+        let boxed = self.box_value(value, from)?;
+        let from_class = self.class_of_type(Some(from))?;
+        let to_class = self.class_of_type(Some(to))?;
+        let converted = self.convert(boxed, from_class, to_class)?;
+        if wrapper_of(to).is_none() {
+            return Ok(converted);
+        }
+        match converted.as_ref()? {
+            Some(boxed) => match self.unbox_value(boxed)? {
+                Some((_, unboxed)) => Ok(unboxed),
+                None => Err(Error::Verify(format!("{:?} isn't wrapped", to)).into()),
+            },
+            None => Err(self.exception("java/lang/NullPointerException", None)),
+        }
+    }
+
+}
+
+// Ints stored in a byte, short, char or boolean wrap like the JVM's narrowing conversions
+fn narrow(value: Value, field_type: &FieldType) -> Value {
+    match (value, field_type) {
+        (Value::Int(v), &FieldType::Byte) => Value::Int(v as i8 as i32),
+        (Value::Int(v), &FieldType::Short) => Value::Int(v as i16 as i32),
+        (Value::Int(v), &FieldType::Char) => Value::Int(v as u16 as i32),
+        (Value::Int(v), &FieldType::Boolean) => Value::Int(v & 1),
+        (value, _) => value,
+    }
+}
@@ -0,0 +1,188 @@
+/*
+* Booting on a JDK's own java.base instead of the mini runtime, in the order HotSpot's
+* Threads::create_vm does it: the core classes are initialized, the main thread and its thread
+* groups are made, System.initPhase1 sets up the system properties and System.out, initPhase2 the
+* boot module layer and initPhase3 the security manager and system class loader before main runs.
+* JDK 17 is the one this follows, the natives java.base needs are natives::JDK
+*/
+use class_file::descriptor::java_name;
+use class_path::ClassPath;
+use class_path::jimage::JImage;
+use jdk::{ClassLibrary, Jdk};
+use result::{Result, Error};
+use vm::class::{ClassId, InitState};
+use vm::heap::ObjectRef;
+use vm::natives::Natives;
+use vm::thread::{Frame, FrameKind, Thread, ThreadId, ThreadState};
+use vm::value::Value;
+use vm::{Vm, THREAD_RUNNABLE};
+
+// The oldest JDK whose java.base the VM boots, older ones have other natives and an older String
+pub const MIN_JDK_VERSION: u32 = 17;
+
+// Initialized before the main thread and its thread groups exist, like
+// initialize_java_lang_classes
+const INITIALIZED_FIRST: &[&str] = &[
+    "java/lang/String",
+    "java/lang/System",
+    "java/lang/Class",
+    "java/lang/ThreadGroup",
+];
+
+// Initialized once it does
+const INITIALIZED_NEXT: &[&str] = &[
+    "java/lang/Module",
+    "java/lang/reflect/Method",
+    "java/lang/ref/Finalizer",
+];
+
+impl Vm {
+    // A VM on the lib/modules of the JDK, which has to be JDK 17 or later
+    pub fn boot_from(jdk: &Jdk, class_path: ClassPath) -> Result<Self> {
+        match jdk.feature_version() {
+            Some(version) if version >= MIN_JDK_VERSION => (),
+            _ => return Err(Error::Unsupported("JDK, booting needs JDK 17 or later")),
+        }
+        match *jdk.class_library() {
+            ClassLibrary::Modules(ref modules) =>
+                Ok(Vm::with_sources(JImage::open(modules)?, class_path, Natives::jdk(), Some(jdk.home().to_path_buf()))),
+            ClassLibrary::RtJar(_) => Err(Error::Unsupported("rt.jar class library, booting needs lib/modules")),
+        }
+    }
+
+    // Returns the main thread, ended until main is pushed on it
+    pub(super) fn boot(&mut self) -> Result<ThreadId> {
+        // Thread's constructor asks for the current thread, so the object exists first
+        let thread_class = self.load_boot_class("java/lang/Thread")?;
+        let thread = self.alloc_instance(thread_class);
+        self.set_named_field(thread, "priority", Value::Int(5))?;
+        self.threads.push(Thread::new(thread, false));
+        let main = ThreadId(self.threads.len() as u32 - 1);
+        self.threads[main.index()].booting = true;
+        self.set_named_field(thread, "eetop", Value::Long(main.index() as i64 + 1))?;
+        self.set_thread_status(thread, THREAD_RUNNABLE)?;
+        self.current = main;
+
+        self.inject_unsafe_constants()?;
+        self.initialize_boot_classes(main, INITIALIZED_FIRST)?;
+        let group_class = self.load_boot_class("java/lang/ThreadGroup")?;
+        let system = self.alloc_instance(group_class);
+        self.call_boot(main, "java/lang/ThreadGroup", "<init>", "()V", &[Value::Ref(system)])?;
+        let main_group = self.alloc_instance(group_class);
+        let name = self.new_string("main").map_err(|throw| self.fatal(throw))?;
+        self.call_boot(main, "java/lang/ThreadGroup", "<init>", "(Ljava/lang/ThreadGroup;Ljava/lang/String;)V",
+            &[Value::Ref(main_group), Value::Ref(system), Value::Ref(name)])?;
+        self.initialize_boot_classes(main, &["java/lang/Thread"])?;
+        self.call_boot(main, "java/lang/Thread", "<init>", "(Ljava/lang/ThreadGroup;Ljava/lang/String;)V",
+            &[Value::Ref(thread), Value::Ref(main_group), Value::Ref(name)])?;
+        self.initialize_boot_classes(main, INITIALIZED_NEXT)?;
+
+        self.call_boot(main, "java/lang/System", "initPhase1", "()V", &[])?;
+        // initPhase2 prints what went wrong with the module system itself and returns an error
+        // code, VM.initLevel tells whether it got through
+        self.call_boot(main, "java/lang/System", "initPhase2", "(ZZ)I", &[Value::Int(1), Value::Int(1)])?;
+        let vm_class = self.load_boot_class("jdk/internal/misc/VM")?;
+        if self.class(vm_class).statics[self.static_slot(vm_class, "initLevel")?].as_int()? < 2 {
+            return Err(Error::Unsupported("JDK, its boot layer failed to initialize"));
+        }
+        self.call_boot(main, "java/lang/System", "initPhase3", "()V", &[])?;
+        self.threads[main.index()].booting = false;
+        Ok(main)
+    }
+
+    // What DestroyJavaVM does once every thread that isn't a daemon has ended: Shutdown runs the
+    // shutdown hooks
+    pub(super) fn shut_down(&mut self, main: ThreadId) -> Result<()> {
+        self.call_boot(main, "java/lang/Shutdown", "shutdown", "()V", &[])
+    }
+
+    fn initialize_boot_classes(&mut self, main: ThreadId, names: &[&str]) -> Result<()> {
+        for name in names {
+            let class = self.load_boot_class(name)?;
+            self.current = main;
+            self.threads[main.index()].state = ThreadState::Runnable;
+            if !self.initialize(class).map_err(|throw| self.fatal(throw))? {
+                self.run_boot(main)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn load_boot_class(&mut self, name: &str) -> Result<ClassId> {
+        self.load_class(name).map_err(|throw| self.fatal(throw))
+    }
+
+    // UnsafeConstants is initialized without its <clinit>, the VM sets what it would have zeroed
+    fn inject_unsafe_constants(&mut self) -> Result<()> {
+        let class = self.load_boot_class("jdk/internal/misc/UnsafeConstants")?;
+        let constants = [
+            ("ADDRESS_SIZE0", Value::Int(8)),
+            ("PAGE_SIZE", Value::Int(4096)),
+            ("BIG_ENDIAN", Value::Int(0)),
+            ("UNALIGNED_ACCESS", Value::Int(1)),
+            ("DATA_CACHE_LINE_FLUSH_SIZE", Value::Int(0)),
+        ];
+        for &(name, value) in &constants {
+            let slot = self.static_slot(class, name)?;
+            self.class_mut(class).statics[slot] = value;
+        }
+        self.class_mut(class).state = InitState::Initialized;
+        Ok(())
+    }
+
+    fn static_slot(&self, class: ClassId, name: &str) -> Result<usize> {
+        self.class(class).fields().iter()
+            .find(|field| field.name() == name && field.flags().is_static())
+            .map(|field| field.slot)
+            .ok_or_else(|| Error::Verify(format!("no static field {} in {}", name, self.class(class).name)))
+    }
+
+    // Runs the method on the main thread until it returns
+    fn call_boot(&mut self, main: ThreadId, class_name: &str, name: &str, descriptor: &str, args: &[Value]) -> Result<()> {
+        let class = self.load_boot_class(class_name)?;
+        let method = self.class(class).find_method(name, descriptor).cloned()
+            .ok_or_else(|| Error::Verify(format!("no {}.{}{}", class_name, name, descriptor)))?;
+        let thread = &mut self.threads[main.index()];
+        thread.frames.push(Frame::new(method, args, FrameKind::Normal));
+        thread.state = ThreadState::Runnable;
+        self.current = main;
+        self.run_boot(main)
+    }
+
+    // Runs every thread until the main one has no frames left. An exception that gets out stops
+    // the boot, there is no System.err to print it to yet
+    fn run_boot(&mut self, main: ThreadId) -> Result<()> {
+        while !self.threads[main.index()].frames.is_empty() && self.schedule()? {
+            self.run_slice()?;
+        }
+        if let Some(exception) = self.threads[main.index()].uncaught.take() {
+            return Err(Error::Uncaught(self.describe(exception)));
+        }
+        Ok(())
+    }
+
+    // "java.lang.NullPointerException: message at Foo.bar(Foo.java:12)", what there is of it,
+    // and the same of the cause
+    fn describe(&self, exception: ObjectRef) -> String {
+        let class = self.heap.get(exception).class();
+        let mut description = java_name(&self.class(class).name);
+        if let Ok(Some(message)) = self.named_field(exception, "detailMessage").and_then(|message| message.as_ref()) {
+            if let Ok(message) = self.rust_string(message) {
+                description = format!("{}: {}", description, message);
+            }
+        }
+        let top = self.named_field(exception, "backtrace").and_then(|trace| trace.as_ref()).ok().flatten()
+            .and_then(|trace| self.array(trace).ok()?.get(0)?.as_ref().ok().flatten());
+        if let Some(element) = top {
+            let field = |name| self.named_field(element, name).and_then(|value| value.as_ref()).ok().flatten()
+                .and_then(|string| self.rust_string(string).ok())
+                .unwrap_or_default();
+            let line = self.named_field(element, "lineNumber").and_then(|line| line.as_int()).unwrap_or(-1);
+            description = format!("{} at {}.{}({}:{})", description, field("declaringClass"), field("methodName"), field("fileName"), line);
+        }
+        match self.named_field(exception, "cause").and_then(|cause| cause.as_ref()) {
+            Ok(Some(cause)) if cause != exception => format!("{}, caused by {}", description, self.describe(cause)),
+            _ => description,
+        }
+    }
+}
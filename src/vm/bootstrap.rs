@@ -0,0 +1,175 @@
+/*
+* invokedynamic and Dynamic constants, JVMS §5.4.3.6. The bootstrap method runs in a frame of
+* MethodHandleNatives.linkCallSite or linkDynamicConstant, what that returns is kept and the
+* instruction that needed it runs again. Each invokedynamic instruction is linked once, from then
+* on it invokes the target of its call site exactly
+*/
+use std::sync::Arc;
+
+use class_file::attribute_info::BootstrapMethod;
+use class_file::constant_pool::{DynamicRef, LoadableConstant};
+use class_file::descriptor::{FieldType, MethodDescriptor};
+use result::{Result, Error};
+use types::u2;
+use vm::class::{ClassId, Method, Resolved};
+use vm::heap::{Array, Body};
+use vm::invoke::wrapper_of;
+use vm::interpreter::Action;
+use vm::thread::{Frame, FrameKind};
+use vm::value::Value;
+use vm::{Flow, Vm, MAX_FRAMES};
+
+impl Vm {
+    // The invokedynamic at `pc` of the method runs the target of its call site, linking it first
+    pub(super) fn invoke_dynamic(&mut self, method: &Arc<Method>, pc: u32, index: u2) -> Flow<Action> {
+        let class = method.class();
+        let key = (Arc::as_ptr(method) as usize, pc);
+        let site = match self.call_sites.get(&key) {
+            Some(&site) => site,
+            None => {
+                let class_file = self.class_file(class)?;
+                let dynamic = class_file.constant_pool().dynamic(index as usize)?;
+                if let Some(action) = self.unlinked_arguments(class, &dynamic, 0)? {
+                    return Ok(action);
+                }
+                let method_type = self.method_type(dynamic.descriptor)?;
+                return self.bootstrap(class, &dynamic, Value::Ref(method_type), FrameKind::CallSite(key.0, key.1));
+            },
+        };
+        let class_file = self.class_file(class)?;
+        let descriptor = class_file.constant_pool().dynamic(index as usize)?.descriptor;
+        let target = self.named_field(site, "target")?.as_ref()?
+            .ok_or_else(|| Error::Verify("call site without a target".to_string()))?;
+        let count = MethodDescriptor::parse(descriptor)?.params().len();
+        let below = self.frame().stack.len().checked_sub(count)
+            .ok_or_else(|| Error::Verify("operand stack underflow".to_string()))?;
+        self.frame_mut().stack.insert(below, Value::Ref(target));
+        let action = self.invoke_handle(descriptor, true)?;
+        // The instruction runs again once the class is initialized, and pushes the target again
+        if let Action::Initialize(_) = action {
+            self.frame_mut().stack.remove(below);
+        }
+        Ok(action)
+    }
+
+    // What links the Dynamic constant at the index, or the first of its static arguments that
+    // isn't linked yet. None when there is nothing to link
+    pub(super) fn unlinked_constant(&mut self, class: ClassId, index: u2, depth: usize) -> Flow<Option<Action>> {
+        if self.resolved.contains_key(&(class, index)) {
+            return Ok(None);
+        }
+        let class_file = self.class_file(class)?;
+        let dynamic = match class_file.constant_pool().loadable(index as usize)? {
+            LoadableConstant::Dynamic(dynamic) => dynamic,
+            _ => return Ok(None),
+        };
+        // A constant that is its own argument links forever, HotSpot runs out of stack too
+        if depth == MAX_FRAMES {
+            return Err(self.exception("java/lang/StackOverflowError", None));
+        }
+        if let Some(action) = self.unlinked_arguments(class, &dynamic, depth + 1)? {
+            return Ok(Some(action));
+        }
+        let field_type = FieldType::parse(dynamic.descriptor)?;
+        let type_class = self.class_of_type(Some(&field_type))?;
+        let mirror = self.mirror(type_class)?;
+        Ok(Some(self.bootstrap(class, &dynamic, Value::Ref(mirror), FrameKind::Constant(class, index))?))
+    }
+
+    fn unlinked_arguments(&mut self, class: ClassId, dynamic: &DynamicRef, depth: usize) -> Flow<Option<Action>> {
+        let class_file = self.class_file(class)?;
+        for &argument in bootstrap_arguments(class_file.bootstrap_methods(), dynamic)?.1 {
+            if let Some(action) = self.unlinked_constant(class, argument, depth)? {
+                return Ok(Some(action));
+            }
+        }
+        Ok(None)
+    }
+
+    // The value of a linked Dynamic constant, linkDynamicConstant boxed it for a primitive type
+    pub(super) fn dynamic_constant(&self, class: ClassId, index: u2, descriptor: &str) -> Flow<Value> {
+        let value = match self.resolved.get(&(class, index)) {
+            Some(&Resolved::Constant(value)) => value,
+            _ => return Err(Error::Verify(format!("dynamic constant {} used before it is linked", index)).into()),
+        };
+        if wrapper_of(&FieldType::parse(descriptor)?).is_none() {
+            return Ok(value);
+        }
+        match value.as_ref()? {
+            Some(boxed) => match self.unbox_value(boxed)? {
+                Some((_, unboxed)) => Ok(unboxed),
+                None => Err(Error::Verify(format!("dynamic constant {} isn't boxed", index)).into()),
+            },
+            None => Err(Error::Verify(format!("dynamic constant {} is null", index)).into()),
+        }
+    }
+
+    // A frame of MethodHandleNatives.linkCallSite or linkDynamicConstant with the class, the
+    // bootstrap method, the name, the type and the static arguments
+    fn bootstrap(&mut self, class: ClassId, dynamic: &DynamicRef, type_value: Value, kind: FrameKind) -> Flow<Action> {
+        let natives = self.load_class("java/lang/invoke/MethodHandleNatives")?;
+        if !self.is_initialized(natives) {
+            return Ok(Action::Initialize(natives));
+        }
+        let class_file = self.class_file(class)?;
+        let (bootstrap_method, arguments) = bootstrap_arguments(class_file.bootstrap_methods(), dynamic)?;
+        let bootstrap_method = self.load_constant(class, bootstrap_method)?;
+        let mut values = Vec::with_capacity(arguments.len());
+        for &argument in arguments {
+            values.push(self.bootstrap_argument(class, argument)?);
+        }
+        let array_class = self.load_class("[Ljava/lang/Object;")?;
+        let values = self.heap.alloc(array_class, Body::Array(Array::Reference(values)));
+        let (name, descriptor) = match kind {
+            FrameKind::CallSite(..) => ("linkCallSite",
+                "(Ljava/lang/Class;Ljava/lang/invoke/MethodHandle;Ljava/lang/String;Ljava/lang/invoke/MethodType;[Ljava/lang/Object;)Ljava/lang/invoke/CallSite;"),
+            _ => ("linkDynamicConstant",
+                "(Ljava/lang/Class;Ljava/lang/invoke/MethodHandle;Ljava/lang/String;Ljava/lang/Class;[Ljava/lang/Object;)Ljava/lang/Object;"),
+        };
+        let link = self.class(natives).find_method(name, descriptor).cloned()
+            .ok_or_else(|| Error::Verify(format!("MethodHandleNatives without {}", name)))?;
+        let caller = self.mirror(class)?;
+        let dynamic_name = self.intern(dynamic.name)?;
+        let args = [Value::Ref(caller), bootstrap_method, Value::Ref(dynamic_name), type_value, Value::Ref(values)];
+        Ok(Action::Link(Frame::new(link, &args, kind)))
+    }
+
+    // A static argument as the bootstrap method gets it, numbers boxed
+    fn bootstrap_argument(&mut self, class: ClassId, index: u2) -> Flow<Value> {
+        let class_file = self.class_file(class)?;
+        let (value, field_type) = match class_file.constant_pool().loadable(index as usize)? {
+            LoadableConstant::Integer(value) => (Value::Int(value), FieldType::Int),
+            LoadableConstant::Float(value) => (Value::Float(value), FieldType::Float),
+            LoadableConstant::Long(value) => (Value::Long(value), FieldType::Long),
+            LoadableConstant::Double(value) => (Value::Double(value), FieldType::Double),
+            LoadableConstant::Dynamic(_) => match self.resolved.get(&(class, index)) {
+                Some(&Resolved::Constant(value)) => return Ok(value),
+                _ => return Err(Error::Verify(format!("dynamic constant {} used before it is linked", index)).into()),
+            },
+            _ => return self.load_constant(class, index),
+        };
+        self.box_value(value, &field_type)
+    }
+
+    // Kept once MethodHandleNatives returns, see return_from
+    pub(super) fn linked(&mut self, kind: FrameKind, value: Option<Value>) -> Result<()> {
+        match (kind, value) {
+            (FrameKind::CallSite(method, pc), Some(Value::Ref(site))) => {
+                self.call_sites.insert((method, pc), site);
+            },
+            (FrameKind::Constant(class, index), Some(value)) => {
+                self.resolved.insert((class, index), Resolved::Constant(value));
+            },
+            _ => return Err(Error::Verify("MethodHandleNatives returned nothing to link".to_string())),
+        }
+        Ok(())
+    }
+}
+
+// The bootstrap method and the static arguments of an invokedynamic or Dynamic constant
+fn bootstrap_arguments<'a>(bootstrap_methods: &'a [BootstrapMethod], dynamic: &DynamicRef) -> Result<(u2, &'a [u2])> {
+    match bootstrap_methods.get(dynamic.bootstrap_method_attr_index as usize) {
+        Some(bootstrap_method) => Ok((bootstrap_method.bootstrap_method_ref(), bootstrap_method.bootstrap_arguments())),
+        None => Err(Error::Verify(format!("no bootstrap method {}", dynamic.bootstrap_method_attr_index))),
+    }
+}
@@ -0,0 +1,823 @@
+use std::sync::{Arc, OnceLock};
+
+use class_path::ClassSource;
+use class_file::access_flags::{ClassFlags, FieldFlags, MethodFlags, ACC_ABSTRACT, ACC_FINAL, ACC_PRIVATE, ACC_PROTECTED, ACC_PUBLIC};
+use class_file::attribute_info::CodeAttribute;
+use class_file::bytecode::{self, Instruction};
+use class_file::class_file::ClassFile;
+use class_file::constant_pool::{LoadableConstant, Tag};
+use class_file::descriptor::{java_name, FieldType, MethodDescriptor};
+use result::{Result, Error};
+use types::{u1, u2};
+use vm::heap::ObjectRef;
+use vm::natives::NativeMethod;
+use vm::thread::ThreadId;
+use vm::value::Value;
+use vm::{Flow, Vm};
+
+// Index into the VM's class table, classes are never unloaded
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClassId(u32);
+
+impl ClassId {
+    pub fn index(self) -> usize { self.0 as usize }
+}
+
+pub struct Class {
+    // Internal binary name, "[I" for arrays and "int" for primitive types
+    pub(super) name: String,
+    pub(super) kind: ClassKind,
+    pub(super) flags: ClassFlags,
+    pub(super) super_class: Option<ClassId>,
+    pub(super) interfaces: Vec<ClassId>,
+    pub(super) class_file: Option<Arc<ClassFile>>,
+    // Declared fields only, static ones index statics and instance ones the object's fields
+    pub(super) fields: Vec<Field>,
+    // Types of every instance field slot, inherited ones first
+    pub(super) instance_fields: Vec<FieldType>,
+    pub(super) statics: Vec<Value>,
+    pub(super) methods: Vec<Arc<Method>>,
+    pub(super) state: InitState,
+    // The java/lang/Class object, made the first time it is asked for
+    pub(super) mirror: Option<ObjectRef>,
+    pub(super) source_file: Option<String>,
+    // The class a class the VM spun acts for, see Vm::spin
+    pub(super) host: Option<ClassId>,
+}
+
+impl Class {
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn kind(&self) -> &ClassKind { &self.kind }
+
+    pub fn flags(&self) -> ClassFlags { self.flags }
+
+    pub fn super_class(&self) -> Option<ClassId> { self.super_class }
+
+    pub fn interfaces(&self) -> &[ClassId] { &self.interfaces }
+
+    pub fn fields(&self) -> &[Field] { &self.fields }
+
+    pub fn methods(&self) -> &[Arc<Method>] { &self.methods }
+
+    pub fn state(&self) -> InitState { self.state }
+
+    pub fn is_interface(&self) -> bool { self.flags.is_interface() }
+
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<&Arc<Method>> {
+        self.methods.iter().find(|method| method.name == name && method.descriptor == descriptor)
+    }
+
+    fn find_field(&self, name: &str, descriptor: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.name == name && field.descriptor == descriptor)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClassKind {
+    Instance,
+    // The class of the elements
+    Array(ClassId),
+    Primitive(FieldType),
+    // void.class, for method types
+    Void,
+}
+
+pub struct Field {
+    pub(super) name: String,
+    pub(super) descriptor: String,
+    pub(super) field_type: FieldType,
+    pub(super) flags: FieldFlags,
+    pub(super) slot: usize,
+}
+
+impl Field {
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn descriptor(&self) -> &str { &self.descriptor }
+
+    pub fn field_type(&self) -> &FieldType { &self.field_type }
+
+    pub fn flags(&self) -> FieldFlags { self.flags }
+}
+
+// JVMS §5.5, loading and linking happen together so a class starts out linked
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InitState {
+    Linked,
+    Initializing(ThreadId),
+    Initialized,
+    // <clinit> threw, using the class again throws NoClassDefFoundError
+    Erroneous,
+}
+
+pub struct Method {
+    pub(super) class: ClassId,
+    pub(super) name: String,
+    pub(super) descriptor: String,
+    pub(super) method_type: MethodDescriptor,
+    pub(super) flags: MethodFlags,
+    // None for native and abstract methods
+    pub(super) code: Option<Code>,
+    // Looked up in the registry on the first call
+    pub(super) native: OnceLock<Option<NativeMethod>>,
+}
+
+impl Method {
+    pub fn class(&self) -> ClassId { self.class }
+
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn descriptor(&self) -> &str { &self.descriptor }
+
+    pub fn method_type(&self) -> &MethodDescriptor { &self.method_type }
+
+    pub fn flags(&self) -> MethodFlags { self.flags }
+
+    // Operand stack entries the arguments take, `this` included
+    pub(super) fn arg_count(&self) -> usize {
+        self.method_type.params().len() + if self.flags.is_static() { 0 } else { 1 }
+    }
+}
+
+pub struct Code {
+    pub(super) attribute: CodeAttribute,
+    pub(super) instructions: Box<[Instruction]>,
+}
+
+impl Code {
+    fn new(attribute: &CodeAttribute) -> Result<Self> {
+        Ok(Code {
+            attribute: attribute.clone(),
+            instructions: bytecode::decode(attribute.code())?.into_boxed_slice(),
+        })
+    }
+
+    // Index of the instruction at `pc`, branch targets are pcs
+    pub(super) fn index_of(&self, pc: u32) -> Result<usize> {
+        self.instructions.binary_search_by_key(&pc, |instruction| instruction.pc)
+            .map_err(|_| Error::Verify(format!("no instruction at pc {}", pc)))
+    }
+}
+
+// What a constant pool entry resolved to, cached per class and index
+#[derive(Clone)]
+pub(super) enum Resolved {
+    Class(ClassId),
+    Field { class: ClassId, slot: usize, flags: FieldFlags },
+    Method(Arc<Method>),
+    String(ObjectRef),
+    // The object of a MethodType or MethodHandle constant
+    Constant(Value),
+}
+
+const PRIMITIVES: &[(&str, FieldType)] = &[
+    ("boolean", FieldType::Boolean), ("byte", FieldType::Byte), ("char", FieldType::Char),
+    ("short", FieldType::Short), ("int", FieldType::Int), ("long", FieldType::Long),
+    ("float", FieldType::Float), ("double", FieldType::Double),
+];
+
+impl Vm {
+    pub fn class(&self, id: ClassId) -> &Class {
+        &self.classes[id.index()]
+    }
+
+    pub(super) fn class_mut(&mut self, id: ClassId) -> &mut Class {
+        &mut self.classes[id.index()]
+    }
+
+    // Loads and links the class and its superclasses, JVMS §5.3. Failures are Java exceptions
+    pub fn load_class(&mut self, name: &str) -> Flow<ClassId> {
+        if let Some(&id) = self.class_ids.get(name) {
+            return Ok(id);
+        }
+        if name.starts_with('[') {
+            return self.load_array_class(name);
+        }
+        if name == "void" || PRIMITIVES.iter().any(|&(primitive, _)| primitive == name) {
+            return self.primitive_class(name);
+        }
+        let bytes = match self.class_path.find_class(name)? {
+            Some(bytes) => bytes,
+            None => return Err(self.exception("java/lang/NoClassDefFoundError", Some(name.to_string()))),
+        };
+        let class_file = match ClassFile::read_from(&bytes[..]) {
+            Ok(class_file) => class_file,
+            Err(err) => return Err(self.exception("java/lang/ClassFormatError", Some(format!("{}: {}", name, err)))),
+        };
+        let actual = class_file.name()?.to_string();
+        if actual != name {
+            let message = format!("{} (wrong name: {})", name, actual);
+            return Err(self.exception("java/lang/NoClassDefFoundError", Some(message)));
+        }
+        if !self.loading.insert(name.to_string()) {
+            return Err(self.exception("java/lang/ClassCircularityError", Some(name.to_string())));
+        }
+        let supers = self.load_supers(&class_file);
+        self.loading.remove(name);
+        let (super_class, interfaces) = supers?;
+        self.define_class(class_file, super_class, interfaces)
+    }
+
+    pub(super) fn load_supers(&mut self, class_file: &ClassFile) -> Flow<(Option<ClassId>, Vec<ClassId>)> {
+        let super_class = match class_file.super_class() {
+            0 => None,
+            _ => {
+                let super_class = self.load_class(class_file.super_name()?)?;
+                if self.class(super_class).is_interface() {
+                    let message = format!("class {} has interface {} as super class", class_file.name()?, self.class(super_class).name);
+                    return Err(self.exception("java/lang/IncompatibleClassChangeError", Some(message)));
+                }
+                Some(super_class)
+            },
+        };
+        let mut interfaces = Vec::new();
+        for name in class_file.interface_names()? {
+            let interface = self.load_class(name)?;
+            if !self.class(interface).is_interface() {
+                let message = format!("class {} can not implement {}, because it is not an interface", class_file.name()?, name);
+                return Err(self.exception("java/lang/IncompatibleClassChangeError", Some(message)));
+            }
+            interfaces.push(interface);
+        }
+        Ok((super_class, interfaces))
+    }
+
+    pub(super) fn define_class(&mut self, class_file: ClassFile, super_class: Option<ClassId>, interfaces: Vec<ClassId>) -> Flow<ClassId> {
+        let id = ClassId(self.classes.len() as u32);
+        let mut instance_fields = super_class.map_or_else(Vec::new, |super_class| self.class(super_class).instance_fields.clone());
+        let mut statics = Vec::new();
+        let mut fields = Vec::new();
+        for field in class_file.fields() {
+            let field_type = field.get_field_type()?;
+            let flags = field.field_flags();
+            let slot = if flags.is_static() {
+                // String constants need the heap, they are set when the class is initialized
+                let value = match field.get_constant_value() {
+                    Some(constant) => match class_file.constant_pool().loadable(constant.constant_value_index() as usize)? {
+                        LoadableConstant::Integer(value) => Value::Int(value),
+                        LoadableConstant::Long(value) => Value::Long(value),
+                        LoadableConstant::Float(value) => Value::Float(value),
+                        LoadableConstant::Double(value) => Value::Double(value),
+                        _ => Value::zero(&field_type),
+                    },
+                    None => Value::zero(&field_type),
+                };
+                statics.push(value);
+                statics.len() - 1
+            } else {
+                instance_fields.push(field_type.clone());
+                instance_fields.len() - 1
+            };
+            fields.push(Field {
+                name: field.get_name()?.to_string(),
+                descriptor: field.get_descriptor()?.to_string(),
+                field_type,
+                flags,
+                slot,
+            });
+        }
+        let mut methods = Vec::new();
+        for method in class_file.methods() {
+            let code = match method.get_code_attribute() {
+                Some(attribute) => match Code::new(attribute) {
+                    Ok(code) => Some(code),
+                    Err(err) => {
+                        let message = format!("{}.{}: {}", class_file.name()?, method.get_name()?, err);
+                        return Err(self.exception("java/lang/ClassFormatError", Some(message)));
+                    },
+                },
+                None => None,
+            };
+            methods.push(Arc::new(Method {
+                class: id,
+                name: method.get_name()?.to_string(),
+                descriptor: method.get_descriptor()?.to_string(),
+                method_type: method.get_method_descriptor()?,
+                flags: method.method_flags(),
+                code,
+                native: OnceLock::new(),
+            }));
+        }
+        let name = class_file.name()?.to_string();
+        self.class_ids.insert(name.clone(), id);
+        self.classes.push(Class {
+            name,
+            kind: ClassKind::Instance,
+            flags: class_file.access_flags(),
+            super_class,
+            interfaces,
+            fields,
+            instance_fields,
+            statics,
+            methods,
+            state: InitState::Linked,
+            mirror: None,
+            source_file: class_file.source_file()?.map(str::to_string),
+            class_file: Some(Arc::new(class_file)),
+            host: None,
+        });
+        Ok(id)
+    }
+
+    // Array classes are made by the VM, JVMS §5.3.3
+    fn load_array_class(&mut self, name: &str) -> Flow<ClassId> {
+        let component = match FieldType::parse(name)? {
+            FieldType::Array(component) => component,
+            _ => return Err(Error::InvalidDescriptor(name.to_string()).into()),
+        };
+        let component = match *component {
+            FieldType::Object(ref class_name) => self.load_class(class_name)?,
+            FieldType::Array(_) => self.load_class(&name[1..])?,
+            ref primitive => self.primitive_class(&primitive.type_name())?,
+        };
+        let object = self.load_class("java/lang/Object")?;
+        let interfaces = vec![self.load_class("java/lang/Cloneable")?, self.load_class("java/io/Serializable")?];
+        Ok(self.add_class(name, ClassKind::Array(component), Some(object), interfaces))
+    }
+
+    fn primitive_class(&mut self, name: &str) -> Flow<ClassId> {
+        if let Some(&id) = self.class_ids.get(name) {
+            return Ok(id);
+        }
+        if name == "void" {
+            return Ok(self.add_class(name, ClassKind::Void, None, Vec::new()));
+        }
+        let field_type = PRIMITIVES.iter()
+            .find(|&&(primitive, _)| primitive == name)
+            .map(|(_, field_type)| field_type.clone())
+            .ok_or_else(|| Error::InvalidDescriptor(name.to_string()))?;
+        Ok(self.add_class(name, ClassKind::Primitive(field_type), None, Vec::new()))
+    }
+
+    fn add_class(&mut self, name: &str, kind: ClassKind, super_class: Option<ClassId>, interfaces: Vec<ClassId>) -> ClassId {
+        let id = ClassId(self.classes.len() as u32);
+        self.class_ids.insert(name.to_string(), id);
+        self.classes.push(Class {
+            name: name.to_string(),
+            kind,
+            flags: ClassFlags::new(ACC_PUBLIC | ACC_FINAL | ACC_ABSTRACT),
+            super_class,
+            interfaces,
+            class_file: None,
+            fields: Vec::new(),
+            instance_fields: Vec::new(),
+            statics: Vec::new(),
+            methods: Vec::new(),
+            state: InitState::Initialized,
+            mirror: None,
+            source_file: None,
+            host: None,
+        });
+        id
+    }
+
+    // The class of an array with elements of `component`
+    pub(super) fn array_class_of(&mut self, component: ClassId) -> Flow<ClassId> {
+        let name = match self.class(component).kind {
+            ClassKind::Primitive(ref field_type) => format!("[{}", descriptor_of(field_type)),
+            ClassKind::Array(_) => format!("[{}", self.class(component).name),
+            ClassKind::Instance => format!("[L{};", self.class(component).name),
+            ClassKind::Void => return Err(Error::Verify("array of void".to_string()).into()),
+        };
+        self.load_class(&name)
+    }
+
+    // JVMS §5.4.3.2, the class that declares the field and the field
+    pub(super) fn find_field(&self, class: ClassId, name: &str, descriptor: &str) -> Option<(ClassId, &Field)> {
+        if let Some(field) = self.class(class).find_field(name, descriptor) {
+            return Some((class, field));
+        }
+        for &interface in self.class(class).interfaces.iter() {
+            if let Some(found) = self.find_field(interface, name, descriptor) {
+                return Some(found);
+            }
+        }
+        self.class(class).super_class.and_then(|super_class| self.find_field(super_class, name, descriptor))
+    }
+
+    // Instance field slot by name alone, for the fields natives read and write
+    pub(super) fn field_slot(&self, class: ClassId, name: &str) -> Result<usize> {
+        let mut current = Some(class);
+        while let Some(class) = current {
+            let class = self.class(class);
+            if let Some(field) = class.fields.iter().find(|field| field.name == name && !field.flags.is_static()) {
+                return Ok(field.slot);
+            }
+            current = class.super_class;
+        }
+        Err(Error::Verify(format!("no field {} in {}", name, self.class(class).name)))
+    }
+
+    // JVMS §5.4.3.3 and §5.4.3.4, superclasses first, then the maximally-specific superinterface
+    // methods where a single method with a body wins over abstract ones
+    pub(super) fn find_method(&mut self, class: ClassId, name: &str, descriptor: &str) -> Flow<Option<Arc<Method>>> {
+        let mut current = Some(class);
+        while let Some(class) = current {
+            if let Some(method) = self.class(class).find_method(name, descriptor) {
+                return Ok(Some(method.clone()));
+            }
+            current = self.class(class).super_class;
+        }
+        let candidates = self.maximally_specific(class, name, descriptor);
+        match self.default_method(&candidates)? {
+            Some(method) => Ok(Some(method)),
+            None => Ok(candidates.into_iter().next()),
+        }
+    }
+
+    // The superinterface methods of `class` no subinterface of their declaring interface
+    // overrides, JVMS §5.4.3.3
+    fn maximally_specific(&self, class: ClassId, name: &str, descriptor: &str) -> Vec<Arc<Method>> {
+        let candidates: Vec<Arc<Method>> = self.superinterfaces(class).into_iter()
+            .filter_map(|interface| self.class(interface).find_method(name, descriptor).cloned())
+            .filter(|method| !method.flags.is_private() && !method.flags.is_static())
+            .collect();
+        candidates.iter()
+            .filter(|method| !candidates.iter().any(|other| other.class != method.class && self.is_assignable(other.class, method.class)))
+            .cloned()
+            .collect()
+    }
+
+    // The one maximally-specific method with a body, two of them are an
+    // IncompatibleClassChangeError
+    fn default_method(&mut self, candidates: &[Arc<Method>]) -> Flow<Option<Arc<Method>>> {
+        let defaults: Vec<&Arc<Method>> = candidates.iter().filter(|method| !method.flags.is_abstract()).collect();
+        match defaults[..] {
+            [] => Ok(None),
+            [method] => Ok(Some(method.clone())),
+            _ => {
+                let message = format!("Conflicting default methods: {}", defaults.iter()
+                    .map(|method| format!("{}.{}", java_name(&self.class(method.class).name), method.name))
+                    .collect::<Vec<_>>()
+                    .join(" "));
+                Err(self.exception("java/lang/IncompatibleClassChangeError", Some(message)))
+            },
+        }
+    }
+
+    // Every interface the class implements, directly or not, nearest first
+    pub(super) fn superinterfaces(&self, class: ClassId) -> Vec<ClassId> {
+        let mut interfaces = Vec::new();
+        let mut current = Some(class);
+        while let Some(class) = current {
+            let mut pending = self.class(class).interfaces.clone();
+            while !pending.is_empty() {
+                let interface = pending.remove(0);
+                if !interfaces.contains(&interface) {
+                    interfaces.push(interface);
+                    pending.extend(self.class(interface).interfaces.iter().cloned());
+                }
+            }
+            current = self.class(class).super_class;
+        }
+        interfaces
+    }
+
+    // JVMS §5.4.6, the method invokevirtual and invokeinterface run for a receiver of `class`
+    pub(super) fn select_method(&mut self, class: ClassId, resolved: &Arc<Method>) -> Flow<Arc<Method>> {
+        if resolved.flags.is_private() {
+            return Ok(resolved.clone());
+        }
+        let key = (class, Arc::as_ptr(resolved) as usize);
+        if let Some(method) = self.selected.get(&key) {
+            return Ok(method.clone());
+        }
+        let mut selected = None;
+        let mut current = Some(class);
+        while let Some(class) = current {
+            if let Some(method) = self.class(class).find_method(&resolved.name, &resolved.descriptor) {
+                if !method.flags.is_static() && !method.flags.is_private() {
+                    selected = Some(method.clone());
+                    break;
+                }
+            }
+            current = self.class(class).super_class;
+        }
+        if selected.is_none() {
+            let candidates = self.maximally_specific(class, &resolved.name, &resolved.descriptor);
+            selected = self.default_method(&candidates)?;
+        }
+        match selected {
+            Some(ref method) if !method.flags.is_abstract() => {
+                self.selected.insert(key, method.clone());
+                Ok(method.clone())
+            },
+            _ => {
+                let message = format!("{}.{}{}", self.class(class).name, resolved.name, resolved.descriptor);
+                Err(self.exception("java/lang/AbstractMethodError", Some(message)))
+            },
+        }
+    }
+
+    // Whether a value of class `from` can be stored where `to` is expected, JVMS §6.5 checkcast
+    pub fn is_assignable(&self, from: ClassId, to: ClassId) -> bool {
+        if from == to {
+            return true;
+        }
+        let (from_class, to_class) = (self.class(from), self.class(to));
+        match (&from_class.kind, &to_class.kind) {
+            (&ClassKind::Array(from_component), &ClassKind::Array(to_component)) => {
+                match (&self.class(from_component).kind, &self.class(to_component).kind) {
+                    (&ClassKind::Primitive(_), _) | (_, &ClassKind::Primitive(_)) => false,
+                    _ => self.is_assignable(from_component, to_component),
+                }
+            },
+            (&ClassKind::Primitive(_), _) | (_, &ClassKind::Primitive(_)) |
+            (&ClassKind::Void, _) | (_, &ClassKind::Void) => false,
+            _ if to_class.is_interface() => self.superinterfaces(from).contains(&to),
+            _ => {
+                let mut current = from_class.super_class;
+                while let Some(class) = current {
+                    if class == to {
+                        return true;
+                    }
+                    current = self.class(class).super_class;
+                }
+                false
+            },
+        }
+    }
+
+    pub(super) fn is_subclass_of(&mut self, class: ClassId, name: &str) -> Flow<bool> {
+        let other = self.load_class(name)?;
+        Ok(self.is_assignable(class, other))
+    }
+
+    // Resolves a Class constant of the class `class`
+    pub(super) fn resolve_class(&mut self, class: ClassId, index: u2) -> Flow<ClassId> {
+        if let Some(&Resolved::Class(resolved)) = self.resolved.get(&(class, index)) {
+            return Ok(resolved);
+        }
+        let class_file = self.class_file(class)?;
+        let resolved = self.load_class(class_file.constant_pool().class_name(index as usize)?)?;
+        self.resolved.insert((class, index), Resolved::Class(resolved));
+        Ok(resolved)
+    }
+
+    // JVMS §5.4.3.2 and the access and final field rules of getfield, putfield, getstatic and
+    // putstatic for code in `method`
+    pub(super) fn resolve_field(&mut self, method: &Method, index: u2, opcode: u1) -> Flow<(ClassId, usize)> {
+        let class = method.class;
+        let is_static = opcode == 0xb2 || opcode == 0xb3;
+        let (declaring, slot, flags) = match self.resolved.get(&(class, index)) {
+            Some(&Resolved::Field { class, slot, flags }) => (class, slot, flags),
+            _ => {
+                let class_file = self.class_file(class)?;
+                let field_ref = class_file.constant_pool().field_ref(index as usize)?;
+                let owner = self.load_class(field_ref.class_name)?;
+                let (declaring, slot, flags) = match self.find_field(owner, field_ref.name, field_ref.descriptor) {
+                    Some((declaring, field)) => (declaring, field.slot, field.flags),
+                    None => {
+                        let message = format!("{}.{}", field_ref.class_name, field_ref.name);
+                        return Err(self.exception("java/lang/NoSuchFieldError", Some(message)));
+                    },
+                };
+                if !self.is_accessible(class, declaring, flags.bits())? {
+                    let message = format!("class {} tried to access {}field {}.{}", java_name(&self.class(class).name),
+                        if flags.is_private() { "private " } else if flags.is_protected() { "protected " } else { "" },
+                        java_name(&self.class(declaring).name), field_ref.name);
+                    return Err(self.exception("java/lang/IllegalAccessError", Some(message)));
+                }
+                self.resolved.insert((class, index), Resolved::Field { class: declaring, slot, flags });
+                (declaring, slot, flags)
+            },
+        };
+        let kind = if flags.is_static() { "static" } else { "non-static" };
+        if flags.is_static() != is_static {
+            let message = format!("Expected {} field {}", if is_static { "static" } else { "non-static" }, self.field_name(class, index)?);
+            return Err(self.exception("java/lang/IncompatibleClassChangeError", Some(message)));
+        }
+        // Final fields are only set by the initializer of their own class
+        if flags.is_final() && (opcode == 0xb3 || opcode == 0xb5) {
+            let initializer = if is_static { "<clinit>" } else { "<init>" };
+            let message = if declaring != class {
+                Some(format!("Update to {} final field {} attempted from a different class ({}) than the field's declaring class",
+                    kind, self.field_name(class, index)?, java_name(&self.class(class).name)))
+            } else if method.name != initializer {
+                Some(format!("Update to {} final field {} attempted from a different method ({}) than the initializer method {}",
+                    kind, self.field_name(class, index)?, method.name, initializer))
+            } else {
+                None
+            };
+            if message.is_some() {
+                return Err(self.exception("java/lang/IllegalAccessError", message));
+            }
+        }
+        Ok((declaring, slot))
+    }
+
+    // "java.lang.Foo.bar" for the Fieldref at `index`
+    fn field_name(&self, class: ClassId, index: u2) -> Result<String> {
+        let class_file = self.class_file(class)?;
+        let field_ref = class_file.constant_pool().field_ref(index as usize)?;
+        Ok(format!("{}.{}", java_name(field_ref.class_name), field_ref.name))
+    }
+
+    // JVMS §5.4.4, whether code in `accessor` may use a member of `declaring` with the access
+    // flags `bits`. Every class has the same loader, a runtime package is a package name. A spun
+    // class has the access of its host besides its own members
+    pub(super) fn is_accessible(&mut self, accessor: ClassId, declaring: ClassId, bits: u2) -> Flow<bool> {
+        if accessor == declaring {
+            return Ok(true);
+        }
+        let accessor = self.class(accessor).host.unwrap_or(accessor);
+        let same_package = package(&self.class(accessor).name) == package(&self.class(declaring).name);
+        Ok(if bits & ACC_PUBLIC != 0 {
+            true
+        } else if bits & ACC_PRIVATE != 0 {
+            accessor == declaring || self.nest_host(accessor)? == self.nest_host(declaring)?
+        } else if bits & ACC_PROTECTED != 0 {
+            same_package || self.is_assignable(accessor, declaring)
+        } else {
+            same_package
+        })
+    }
+
+    // JVMS §5.4.1, whether code in `accessor` may use the class at all
+    pub(super) fn is_class_accessible(&self, accessor: ClassId, class: ClassId) -> bool {
+        let accessor = self.class(accessor).host.unwrap_or(accessor);
+        self.class(class).flags.is_public() || package(&self.class(accessor).name) == package(&self.class(class).name)
+    }
+
+    // JVMS §5.4.4, a host that doesn't list the class or is in another package leaves the class
+    // the host of its own nest
+    fn nest_host(&mut self, class: ClassId) -> Flow<ClassId> {
+        let host_name = match self.class(class).class_file {
+            Some(ref class_file) => match class_file.nest_host()? {
+                Some(host) => host.to_string(),
+                None => return Ok(class),
+            },
+            None => return Ok(class),
+        };
+        let host = self.load_class(&host_name)?;
+        let name = self.class(class).name.clone();
+        let is_member = package(&host_name) == package(&name) && match self.class(host).class_file {
+            Some(ref class_file) => class_file.nest_members()?.contains(&&name[..]),
+            None => false,
+        };
+        Ok(if is_member { host } else { class })
+    }
+
+    pub(super) fn resolve_method(&mut self, class: ClassId, index: u2) -> Flow<Arc<Method>> {
+        if let Some(Resolved::Method(method)) = self.resolved.get(&(class, index)) {
+            return Ok(method.clone());
+        }
+        let class_file = self.class_file(class)?;
+        let (tag, method_ref) = class_file.constant_pool().method_ref(index as usize)?;
+        let owner = self.load_class(method_ref.class_name)?;
+        // Step 1 of both, a Methodref names a class and an InterfaceMethodref an interface
+        let is_interface = self.class(owner).is_interface();
+        if is_interface != (tag == Tag::INTERFACE_METHOD_REF) {
+            let message = format!("Found {} {}, but {} was expected", if is_interface { "interface" } else { "class" },
+                java_name(method_ref.class_name), if is_interface { "class" } else { "interface" });
+            return Err(self.exception("java/lang/IncompatibleClassChangeError", Some(message)));
+        }
+        let method = self.lookup_method(owner, method_ref.name, method_ref.descriptor)?;
+        self.resolved.insert((class, index), Resolved::Method(method.clone()));
+        Ok(method)
+    }
+
+    // The method a Methodref or InterfaceMethodref of `owner` resolves to, NoSuchMethodError if
+    // there is none
+    pub(super) fn lookup_method(&mut self, owner: ClassId, name: &str, descriptor: &str) -> Flow<Arc<Method>> {
+        let mut method = self.find_method(owner, name, descriptor)?;
+        // Any descriptor names a signature polymorphic method, JVMS §5.4.3.3
+        if method.is_none() {
+            method = self.class(owner).methods.iter()
+                .find(|method| method.name == name && self.is_signature_polymorphic(method))
+                .cloned();
+        }
+        // Methods of interfaces also include the public ones of Object, JVMS §5.4.3.4
+        if method.is_none() && self.class(owner).is_interface() {
+            let object = self.load_class("java/lang/Object")?;
+            method = self.class(object).find_method(name, descriptor)
+                .filter(|method| method.flags.is_public())
+                .cloned();
+        }
+        match method {
+            Some(method) => Ok(method),
+            None => {
+                let message = format!("{}.{}{}", self.class(owner).name, name, descriptor);
+                Err(self.exception("java/lang/NoSuchMethodError", Some(message)))
+            },
+        }
+    }
+
+    // JVMS §2.9.3, invokeExact and invoke of MethodHandle and the access modes of VarHandle
+    pub(super) fn is_signature_polymorphic(&self, method: &Method) -> bool {
+        let class_name = &self.class(method.class).name;
+        (class_name == "java/lang/invoke/MethodHandle" || class_name == "java/lang/invoke/VarHandle")
+            && method.flags.is_native() && method.flags.is_varargs()
+            && method.method_type.params().len() == 1
+    }
+
+    pub(super) fn resolve_string(&mut self, class: ClassId, index: u2) -> Flow<ObjectRef> {
+        if let Some(&Resolved::String(string)) = self.resolved.get(&(class, index)) {
+            return Ok(string);
+        }
+        let class_file = self.class_file(class)?;
+        let string = self.intern(class_file.constant_pool().string(index as usize)?)?;
+        self.resolved.insert((class, index), Resolved::String(string));
+        Ok(string)
+    }
+
+    pub(super) fn class_file(&self, class: ClassId) -> Result<Arc<ClassFile>> {
+        self.class(class).class_file.clone()
+            .ok_or_else(|| Error::Verify(format!("{} has no class file", self.class(class).name)))
+    }
+
+    // JVMS §5.5. Ok(false) when <clinit> frames were pushed, the instruction that needed the
+    // class runs again once they return
+    pub(super) fn initialize(&mut self, class: ClassId) -> Flow<bool> {
+        match self.class(class).state {
+            InitState::Initialized => return Ok(true),
+            InitState::Initializing(thread) if thread == self.current => return Ok(true),
+            // Another thread runs <clinit>, this one tries again after its turn
+            InitState::Initializing(_) => {
+                self.yielded = true;
+                return Ok(false);
+            },
+            InitState::Erroneous => {
+                let message = format!("Could not initialize class {}", self.class(class).name.replace('/', "."));
+                return Err(self.exception("java/lang/NoClassDefFoundError", Some(message)));
+            },
+            InitState::Linked => (),
+        }
+        if let Some(super_class) = self.class(class).super_class {
+            if !self.initialize(super_class)? {
+                return Ok(false);
+            }
+        }
+        self.class_mut(class).state = InitState::Initializing(self.current);
+        let class_file = self.class_file(class)?;
+        for field in class_file.fields().iter().filter(|field| field.field_flags().is_static()) {
+            if let Some(constant) = field.get_constant_value() {
+                if let LoadableConstant::String(string) = class_file.constant_pool().loadable(constant.constant_value_index() as usize)? {
+                    let string = self.intern(string)?;
+                    let slot = self.class(class).find_field(field.get_name()?, field.get_descriptor()?).map(|field| field.slot);
+                    if let Some(slot) = slot {
+                        self.class_mut(class).statics[slot] = Value::Ref(string);
+                    }
+                }
+            }
+        }
+        match self.class(class).find_method("<clinit>", "()V").cloned() {
+            Some(clinit) => {
+                self.push_initializer(clinit)?;
+                Ok(false)
+            },
+            None => {
+                self.class_mut(class).state = InitState::Initialized;
+                Ok(true)
+            },
+        }
+    }
+
+    // The java/lang/Class object of the class
+    pub fn mirror(&mut self, class: ClassId) -> Flow<ObjectRef> {
+        if let Some(mirror) = self.class(class).mirror {
+            return Ok(mirror);
+        }
+        let class_class = self.load_class("java/lang/Class")?;
+        let mirror = self.alloc_instance(class_class);
+        self.class_mut(class).mirror = Some(mirror);
+        self.mirrors.insert(mirror, class);
+        // The JDK's Class keeps what its natives would otherwise ask the VM for
+        if self.jdk.is_some() {
+            if let ClassKind::Array(component) = self.class(class).kind {
+                let component = self.mirror(component)?;
+                self.set_named_field(mirror, "componentType", Value::Ref(component))?;
+            }
+            if let Some(module) = self.module_of(class) {
+                self.set_named_field(mirror, "module", Value::Ref(module))?;
+            }
+        }
+        Ok(mirror)
+    }
+
+    // The class a java/lang/Class object stands for
+    pub fn class_of_mirror(&self, mirror: ObjectRef) -> Option<ClassId> {
+        self.mirrors.get(&mirror).cloned()
+    }
+}
+
+pub(super) fn descriptor_of(field_type: &FieldType) -> String {
+    match *field_type {
+        FieldType::Byte => "B".to_string(),
+        FieldType::Char => "C".to_string(),
+        FieldType::Double => "D".to_string(),
+        FieldType::Float => "F".to_string(),
+        FieldType::Int => "I".to_string(),
+        FieldType::Long => "J".to_string(),
+        FieldType::Short => "S".to_string(),
+        FieldType::Boolean => "Z".to_string(),
+        FieldType::Object(ref class_name) => format!("L{};", class_name),
+        FieldType::Array(ref component) => format!("[{}", descriptor_of(component)),
+    }
+}
+
+// "java/lang" for "java/lang/Object", "" for the unnamed package
+pub(super) fn package(name: &str) -> &str {
+    name.rfind('/').map_or("", |end| &name[..end])
+}
@@ -0,0 +1,179 @@
+use vm::class::ClassId;
+use vm::thread::ThreadId;
+use vm::value::Value;
+
+// Index of an object in the heap. Nothing is ever freed, there is no garbage collector
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObjectRef(u32);
+
+impl ObjectRef {
+    pub fn index(self) -> usize { self.0 as usize }
+
+    // What Object.hashCode and System.identityHashCode return, scrambled so consecutive objects
+    // don't hash to consecutive numbers
+    pub fn identity_hash(self) -> i32 {
+        (self.0.wrapping_add(1).wrapping_mul(0x9e37_79b9) >> 1) as i32
+    }
+}
+
+pub struct Object {
+    pub(super) class: ClassId,
+    pub(super) lock: Lock,
+    pub(super) body: Body,
+}
+
+impl Object {
+    pub fn class(&self) -> ClassId { self.class }
+
+    pub fn lock(&self) -> Lock { self.lock }
+
+    pub fn body(&self) -> &Body { &self.body }
+
+    pub fn body_mut(&mut self) -> &mut Body { &mut self.body }
+}
+
+// The lock word of the object header: the thread in the object's monitor and how many times it
+// entered it, monitors are reentrant
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Lock {
+    pub(super) owner: Option<ThreadId>,
+    pub(super) count: u32,
+}
+
+impl Lock {
+    pub fn owner(&self) -> Option<ThreadId> { self.owner }
+
+    pub fn count(&self) -> u32 { self.count }
+}
+
+pub enum Body {
+    // Instance fields in slot order, superclass fields first
+    Fields(Box<[Value]>),
+    Array(Array),
+}
+
+// boolean[] shares Byte with byte[], the class tells them apart
+#[derive(Clone)]
+pub enum Array {
+    Byte(Vec<i8>),
+    Char(Vec<u16>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    Reference(Vec<Value>),
+}
+
+impl Array {
+    pub fn len(&self) -> usize {
+        match *self {
+            Array::Byte(ref elements) => elements.len(),
+            Array::Char(ref elements) => elements.len(),
+            Array::Short(ref elements) => elements.len(),
+            Array::Int(ref elements) => elements.len(),
+            Array::Long(ref elements) => elements.len(),
+            Array::Float(ref elements) => elements.len(),
+            Array::Double(ref elements) => elements.len(),
+            Array::Reference(ref elements) => elements.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // None past the end
+    pub fn get(&self, index: usize) -> Option<Value> {
+        match *self {
+            Array::Byte(ref elements) => elements.get(index).map(|&e| Value::Int(e as i32)),
+            Array::Char(ref elements) => elements.get(index).map(|&e| Value::Int(e as i32)),
+            Array::Short(ref elements) => elements.get(index).map(|&e| Value::Int(e as i32)),
+            Array::Int(ref elements) => elements.get(index).map(|&e| Value::Int(e)),
+            Array::Long(ref elements) => elements.get(index).map(|&e| Value::Long(e)),
+            Array::Float(ref elements) => elements.get(index).map(|&e| Value::Float(e)),
+            Array::Double(ref elements) => elements.get(index).map(|&e| Value::Double(e)),
+            Array::Reference(ref elements) => elements.get(index).cloned(),
+        }
+    }
+
+    // Ints are narrowed to the element type, false when index is past the end or the value
+    // doesn't fit the array
+    pub fn set(&mut self, index: usize, value: Value) -> bool {
+        if index >= self.len() {
+            return false;
+        }
+        match (self, value) {
+            (Array::Byte(ref mut elements), Value::Int(v)) => elements[index] = v as i8,
+            (Array::Char(ref mut elements), Value::Int(v)) => elements[index] = v as u16,
+            (Array::Short(ref mut elements), Value::Int(v)) => elements[index] = v as i16,
+            (Array::Int(ref mut elements), Value::Int(v)) => elements[index] = v,
+            (Array::Long(ref mut elements), Value::Long(v)) => elements[index] = v,
+            (Array::Float(ref mut elements), Value::Float(v)) => elements[index] = v,
+            (Array::Double(ref mut elements), Value::Double(v)) => elements[index] = v,
+            (Array::Reference(ref mut elements), v @ Value::Null) |
+            (Array::Reference(ref mut elements), v @ Value::Ref(_)) => elements[index] = v,
+            _ => return false,
+        }
+        true
+    }
+
+    // A new array of the same kind, the range is checked by the caller
+    pub fn slice(&self, start: usize, length: usize) -> Array {
+        macro_rules! slice {
+            ($($kind:ident),*) => {
+                match *self {
+                    $(Array::$kind(ref elements) => Array::$kind(elements[start..start + length].to_vec()),)*
+                }
+            };
+        }
+        slice!(Byte, Char, Short, Int, Long, Float, Double, Reference)
+    }
+
+    // Copies length elements between two arrays of the same kind, ranges are checked by the caller
+    pub fn copy_from(&mut self, dest_pos: usize, src: &Array, src_pos: usize, length: usize) -> bool {
+        macro_rules! copy {
+            ($($kind:ident),*) => {
+                match (self, src) {
+                    $((Array::$kind(ref mut dest), Array::$kind(ref src)) =>
+                        dest[dest_pos..dest_pos + length].copy_from_slice(&src[src_pos..src_pos + length]),)*
+                    _ => return false,
+                }
+            };
+        }
+        copy!(Byte, Char, Short, Int, Long, Float, Double, Reference);
+        true
+    }
+}
+
+#[derive(Default)]
+pub struct Heap {
+    objects: Vec<Object>,
+}
+
+impl Heap {
+    pub fn new() -> Self {
+        Heap::default()
+    }
+
+    pub fn alloc(&mut self, class: ClassId, body: Body) -> ObjectRef {
+        self.objects.push(Object { class, lock: Lock::default(), body });
+        ObjectRef(self.objects.len() as u32 - 1)
+    }
+
+    pub fn get(&self, reference: ObjectRef) -> &Object {
+        &self.objects[reference.index()]
+    }
+
+    pub fn get_mut(&mut self, reference: ObjectRef) -> &mut Object {
+        &mut self.objects[reference.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
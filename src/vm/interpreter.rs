@@ -0,0 +1,1100 @@
+use std::cmp::Ordering;
+use std::mem;
+use std::sync::Arc;
+
+use class_file::bytecode::{self, Instruction, Operand};
+use class_file::constant_pool::LoadableConstant;
+use class_file::descriptor::{java_name, MethodDescriptor};
+use result::{Result, Error};
+use types::u2;
+use vm::class::{ClassId, ClassKind, InitState, Method, Resolved};
+use vm::heap::ObjectRef;
+use vm::natives::NativeMethod;
+use vm::thread::{Frame, FrameKind, ThreadState};
+use vm::value::Value;
+use vm::{Flow, Throw, Vm, MAX_FRAMES};
+
+// What the driver does once an instruction ran
+pub(super) enum Action {
+    Next,
+    // To the instruction at the pc
+    Jump(u32),
+    // The arguments are still on the caller's operand stack
+    Invoke(Arc<Method>),
+    Return(Option<Value>),
+    // The class gets initialized and the instruction runs again
+    Initialize(ClassId),
+    // A bootstrap method runs in the frame, then the instruction runs again
+    Link(Frame),
+}
+
+impl Vm {
+    pub(super) fn frame(&self) -> &Frame {
+        self.thread().frames.last().expect("a running thread has a frame")
+    }
+
+    pub(super) fn frame_mut(&mut self) -> &mut Frame {
+        self.thread_mut().frames.last_mut().expect("a running thread has a frame")
+    }
+
+    pub(super) fn push(&mut self, value: Value) {
+        self.frame_mut().push(value);
+    }
+
+    pub(super) fn pop(&mut self) -> Result<Value> {
+        self.frame_mut().pop()
+    }
+
+    fn pop_int(&mut self) -> Result<i32> {
+        self.frame_mut().pop_int()
+    }
+
+    fn pop_long(&mut self) -> Result<i64> {
+        self.frame_mut().pop_long()
+    }
+
+    fn pop_float(&mut self) -> Result<f32> {
+        self.frame_mut().pop_float()
+    }
+
+    fn pop_double(&mut self) -> Result<f64> {
+        self.frame_mut().pop_double()
+    }
+
+    pub(super) fn pop_ref(&mut self) -> Result<Option<ObjectRef>> {
+        self.frame_mut().pop_ref()
+    }
+
+    // The two operands of a binary instruction, in the order they were pushed
+    fn pop_ints(&mut self) -> Result<(i32, i32)> {
+        let b = self.pop_int()?;
+        Ok((self.pop_int()?, b))
+    }
+
+    fn pop_longs(&mut self) -> Result<(i64, i64)> {
+        let b = self.pop_long()?;
+        Ok((self.pop_long()?, b))
+    }
+
+    fn pop_floats(&mut self) -> Result<(f32, f32)> {
+        let b = self.pop_float()?;
+        Ok((self.pop_float()?, b))
+    }
+
+    fn pop_doubles(&mut self) -> Result<(f64, f64)> {
+        let b = self.pop_double()?;
+        Ok((self.pop_double()?, b))
+    }
+
+    pub(super) fn non_null(&mut self, reference: Option<ObjectRef>) -> Flow<ObjectRef> {
+        match reference {
+            Some(reference) => Ok(reference),
+            None => Err(self.exception("java/lang/NullPointerException", None)),
+        }
+    }
+
+    // The receiver of a call taking `count` stack entries, left on the stack
+    pub(super) fn receiver(&mut self, count: usize) -> Flow<ObjectRef> {
+        let receiver = {
+            let stack = &self.frame().stack;
+            match stack.len().checked_sub(count) {
+                Some(index) => stack[index],
+                None => return Err(Error::Verify("operand stack underflow".to_string()).into()),
+            }
+        };
+        let receiver = receiver.as_ref()?;
+        self.non_null(receiver)
+    }
+
+    fn array_index(&mut self, array: ObjectRef, index: i32) -> Flow<usize> {
+        let length = self.array(array)?.len();
+        if index < 0 || index as usize >= length {
+            let message = format!("Index {} out of bounds for length {}", index, length);
+            return Err(self.exception("java/lang/ArrayIndexOutOfBoundsException", Some(message)));
+        }
+        Ok(index as usize)
+    }
+
+    pub(super) fn is_initialized(&self, class: ClassId) -> bool {
+        match self.class(class).state {
+            InitState::Initialized => true,
+            InitState::Initializing(thread) => thread == self.current,
+            _ => false,
+        }
+    }
+
+    fn push_frame(&mut self, frame: Frame) -> Flow<()> {
+        if self.thread().frames.len() >= MAX_FRAMES {
+            return Err(self.exception("java/lang/StackOverflowError", None));
+        }
+        self.thread_mut().frames.push(frame);
+        Ok(())
+    }
+
+    pub(super) fn push_initializer(&mut self, clinit: Arc<Method>) -> Flow<()> {
+        let class = clinit.class();
+        self.push_frame(Frame::new(clinit, &[], FrameKind::Initializer(class)))
+    }
+
+    // Runs one instruction of the current thread
+    pub(super) fn step(&mut self) -> Result<()> {
+        let method = self.frame().method.clone();
+        let code = match method.code {
+            Some(ref code) => code,
+            None => return Err(Error::Verify(format!("{}.{} has no code", self.class(method.class()).name, method.name()))),
+        };
+        let ip = self.frame().ip;
+        let instruction = code.instructions.get(ip)
+            .ok_or_else(|| Error::Verify(format!("execution falls off the end of {}.{}", self.class(method.class()).name, method.name())))?;
+        let flow = match self.execute(&method, instruction) {
+            Ok(Action::Next) => {
+                self.frame_mut().ip += 1;
+                Ok(())
+            },
+            Ok(Action::Jump(pc)) => {
+                self.frame_mut().ip = code.index_of(pc)?;
+                Ok(())
+            },
+            Ok(Action::Invoke(callee)) => self.invoke(callee),
+            // Returning from a synchronized method that already left its monitor throws
+            Ok(Action::Return(value)) => {
+                let monitor = self.frame_mut().monitor.take();
+                monitor.map_or(Ok(()), |monitor| self.monitor_exit(monitor))
+                    .and_then(|()| Ok(self.return_from(value)?))
+            },
+            Ok(Action::Initialize(class)) => self.initialize(class).map(|_| ()),
+            Ok(Action::Link(frame)) => self.push_frame(frame),
+            Err(throw) => Err(throw),
+        };
+        match flow {
+            Ok(()) => Ok(()),
+            Err(Throw::Exception(exception)) => self.throw(exception),
+            Err(Throw::Fatal(err)) => Err(err),
+        }
+    }
+
+    // UnsatisfiedLinkError names the method the way HotSpot does, "'int Foo.bar(java.lang.String)'"
+    fn native(&mut self, method: &Method) -> Flow<NativeMethod> {
+        let class_name = &self.class(method.class()).name;
+        if let Some(native) = *method.native.get_or_init(|| self.natives.get(class_name, method.name(), method.descriptor())) {
+            return Ok(native);
+        }
+        let descriptor = MethodDescriptor::parse(method.descriptor())?;
+        let return_type = descriptor.return_type().map_or("void".to_string(), |return_type| return_type.type_name());
+        let params: Vec<String> = descriptor.params().iter().map(|param| param.type_name()).collect();
+        let message = format!("'{} {}.{}({})'", return_type, java_name(class_name), method.name(), params.join(", "));
+        Err(self.exception("java/lang/UnsatisfiedLinkError", Some(message)))
+    }
+
+    fn invoke(&mut self, method: Arc<Method>) -> Flow<()> {
+        let count = method.arg_count();
+        let args = {
+            let stack = &mut self.frame_mut().stack;
+            match stack.len().checked_sub(count) {
+                Some(start) => stack.split_off(start),
+                None => return Err(Error::Verify("operand stack underflow".to_string()).into()),
+            }
+        };
+        // A native gets a frame of its own for the time it runs, so stack traces show it
+        if method.flags().is_native() {
+            let depth = self.thread().frames.len();
+            self.push_frame(Frame::new(method.clone(), &[], FrameKind::Normal))?;
+            let result = self.native(&method).and_then(|native| native(self, &args));
+            self.thread_mut().frames.remove(depth);
+            // The arguments go back for the invoke instruction to run again, after the
+            // <clinit> the native pushed if there is one
+            if mem::replace(&mut self.rerun, false) {
+                result?;
+                self.thread_mut().frames[depth - 1].stack.extend(args);
+                return Ok(());
+            }
+            if let Some(value) = result? {
+                self.push(value);
+            }
+            self.frame_mut().ip += 1;
+            return Ok(());
+        }
+        if method.code.is_none() {
+            let message = format!("{}.{}{}", java_name(&self.class(method.class()).name), method.name(), method.descriptor());
+            return Err(self.exception("java/lang/AbstractMethodError", Some(message)));
+        }
+        // A synchronized method locks `this`, or the class for a static one, once its frame is
+        // pushed. Synchronized natives don't lock
+        let monitor = match method.flags().is_synchronized() {
+            true if method.flags().is_static() => Some(self.mirror(method.class())?),
+            true => Some(self.non_null(args.first().map_or(Ok(None), |this| this.as_ref())?)?),
+            false => None,
+        };
+        let mut frame = Frame::new(method, &args, FrameKind::Normal);
+        frame.monitor = monitor;
+        self.push_frame(frame)?;
+        if let Some(monitor) = monitor {
+            self.monitor_enter(monitor);
+        }
+        Ok(())
+    }
+
+    // JVMS §6.5 monitorenter. When another thread holds the monitor the current one stops running
+    // until the scheduler hands it over
+    pub(super) fn monitor_enter(&mut self, object: ObjectRef) {
+        let current = self.current;
+        let lock = &mut self.heap.get_mut(object).lock;
+        match lock.owner {
+            None => {
+                lock.owner = Some(current);
+                lock.count = 1;
+            },
+            Some(owner) if owner == current => lock.count += 1,
+            Some(_) => self.thread_mut().state = ThreadState::Entering(object, 1),
+        }
+    }
+
+    // IllegalMonitorStateException when the current thread isn't in the monitor
+    pub(super) fn monitor_exit(&mut self, object: ObjectRef) -> Flow<()> {
+        if !self.holds_lock(object) {
+            return Err(self.exception("java/lang/IllegalMonitorStateException", None));
+        }
+        self.release(object);
+        Ok(())
+    }
+
+    fn release(&mut self, object: ObjectRef) {
+        let lock = &mut self.heap.get_mut(object).lock;
+        lock.count -= 1;
+        if lock.count == 0 {
+            lock.owner = None;
+        }
+    }
+
+    pub(super) fn holds_lock(&self, object: ObjectRef) -> bool {
+        self.heap.get(object).lock.owner == Some(self.current)
+    }
+
+    fn return_from(&mut self, value: Option<Value>) -> Result<()> {
+        let frame = self.thread_mut().frames.pop().expect("a running thread has a frame");
+        match frame.kind {
+            FrameKind::Normal => {
+                if let Some(caller) = self.thread_mut().frames.last_mut() {
+                    if let Some(value) = value {
+                        caller.push(value);
+                    }
+                    caller.ip += 1;
+                }
+            },
+            FrameKind::Initializer(class) => self.class_mut(class).state = InitState::Initialized,
+            kind @ FrameKind::CallSite(..) | kind @ FrameKind::Constant(..) => self.linked(kind, value)?,
+            FrameKind::Detached => (),
+        }
+        Ok(())
+    }
+
+    // Unwinds to the nearest handler of the exception, JVMS §2.10
+    fn throw(&mut self, mut exception: ObjectRef) -> Result<()> {
+        loop {
+            let (method, ip) = match self.thread().frames.last() {
+                Some(frame) => (frame.method.clone(), frame.ip),
+                None => return self.uncaught(exception),
+            };
+            let code = method.code.as_ref().ok_or_else(|| Error::Verify("frame without code".to_string()))?;
+            let pc = code.instructions.get(ip).map_or(0, |instruction| instruction.pc) as u2;
+            let exception_class = self.heap.get(exception).class();
+            let mut handler = None;
+            for entry in code.attribute.handlers_at(pc) {
+                // A catch type that can't be loaded catches nothing
+                let matches = match code.attribute.catch_type_name(entry)? {
+                    None => true,
+                    Some(name) => match self.load_class(name) {
+                        Ok(catch_type) => self.is_assignable(exception_class, catch_type),
+                        Err(Throw::Exception(_)) => false,
+                        Err(Throw::Fatal(err)) => return Err(err),
+                    },
+                };
+                if matches {
+                    handler = Some(entry.handler_pc());
+                    break;
+                }
+            }
+            if let Some(handler_pc) = handler {
+                let handler = code.index_of(handler_pc as u32)?;
+                let frame = self.frame_mut();
+                frame.stack.clear();
+                frame.push(Value::Ref(exception));
+                frame.ip = handler;
+                return Ok(());
+            }
+            let frame = self.thread_mut().frames.pop().expect("a running thread has a frame");
+            if let Some(monitor) = frame.monitor {
+                if self.holds_lock(monitor) {
+                    self.release(monitor);
+                }
+            }
+            match frame.kind {
+                FrameKind::Normal | FrameKind::CallSite(..) | FrameKind::Constant(..) => (),
+                FrameKind::Initializer(class) => {
+                    self.class_mut(class).state = InitState::Erroneous;
+                    match self.initializer_error(exception) {
+                        Ok(error) => exception = error,
+                        Err(Throw::Exception(other)) => exception = other,
+                        Err(Throw::Fatal(err)) => return Err(err),
+                    }
+                },
+                // Nothing called it, the exception goes nowhere
+                FrameKind::Detached => return Ok(()),
+            }
+        }
+    }
+
+    // Errors escape <clinit> as they are, anything else is wrapped, JVMS §5.5
+    fn initializer_error(&mut self, exception: ObjectRef) -> Flow<ObjectRef> {
+        let exception_class = self.heap.get(exception).class();
+        if self.is_subclass_of(exception_class, "java/lang/Error")? {
+            return Ok(exception);
+        }
+        let class = self.load_class("java/lang/ExceptionInInitializerError")?;
+        let error = self.alloc_instance(class);
+        self.set_named_field(error, "cause", Value::Ref(exception))?;
+        // The JDK's Throwable tells an unset cause by it being the exception itself
+        if self.jdk.is_none() {
+            self.set_named_field(error, "causeSet", Value::Int(1))?;
+        }
+        self.fill_in_stack_trace(error)?;
+        Ok(error)
+    }
+
+    // Hands the exception to Thread.dispatchUncaughtException, which prints it unless a handler
+    // was set, and lets the thread end
+    fn uncaught(&mut self, exception: ObjectRef) -> Result<()> {
+        self.thread_mut().uncaught = Some(exception);
+        let thread = self.thread().object;
+        let class = self.heap.get(thread).class();
+        let dispatch = match self.find_method(class, "dispatchUncaughtException", "(Ljava/lang/Throwable;)V") {
+            Ok(Some(method)) => method,
+            _ => return Err(Error::Verify("thread without dispatchUncaughtException".to_string())),
+        };
+        match self.push_frame(Frame::new(dispatch, &[Value::Ref(thread), Value::Ref(exception)], FrameKind::Detached)) {
+            Ok(()) | Err(Throw::Exception(_)) => Ok(()),
+            Err(Throw::Fatal(err)) => Err(err),
+        }
+    }
+
+    pub(super) fn load_constant(&mut self, class: ClassId, index: u2) -> Flow<Value> {
+        let class_file = self.class_file(class)?;
+        Ok(match class_file.constant_pool().loadable(index as usize)? {
+            LoadableConstant::Integer(value) => Value::Int(value),
+            LoadableConstant::Float(value) => Value::Float(value),
+            LoadableConstant::Long(value) => Value::Long(value),
+            LoadableConstant::Double(value) => Value::Double(value),
+            LoadableConstant::String(_) => Value::Ref(self.resolve_string(class, index)?),
+            LoadableConstant::Class(_) => {
+                let resolved = self.resolve_class(class, index)?;
+                Value::Ref(self.mirror(resolved)?)
+            },
+            LoadableConstant::MethodType(descriptor) => {
+                if let Some(&Resolved::Constant(value)) = self.resolved.get(&(class, index)) {
+                    return Ok(value);
+                }
+                let value = Value::Ref(self.method_type(descriptor)?);
+                self.resolved.insert((class, index), Resolved::Constant(value));
+                value
+            },
+            LoadableConstant::MethodHandle(kind, reference) => {
+                if let Some(&Resolved::Constant(value)) = self.resolved.get(&(class, index)) {
+                    return Ok(value);
+                }
+                let handle = self.direct_handle(class, kind, &reference)?;
+                let value = Value::Ref(self.varargs_collector(handle)?);
+                self.resolved.insert((class, index), Resolved::Constant(value));
+                value
+            },
+            LoadableConstant::Dynamic(dynamic) => self.dynamic_constant(class, index, dynamic.descriptor)?,
+        })
+    }
+
+    fn multi_array(&mut self, class: ClassId, counts: &[i32]) -> Flow<ObjectRef> {
+        let array = self.alloc_array(class, counts[0] as usize)?;
+        if counts.len() > 1 {
+            let component = match self.class(class).kind {
+                ClassKind::Array(component) => component,
+                _ => return Err(Error::Verify("multianewarray with too many dimensions".to_string()).into()),
+            };
+            for i in 0..counts[0] as usize {
+                let element = self.multi_array(component, &counts[1..])?;
+                self.array_mut(array)?.set(i, Value::Ref(element));
+            }
+        }
+        Ok(array)
+    }
+
+    fn class_cast(&mut self, object: ObjectRef, target: ClassId) -> Throw {
+        let object_class = self.heap.get(object).class();
+        let message = format!("class {} cannot be cast to class {}",
+            java_name(&self.class(object_class).name), java_name(&self.class(target).name));
+        self.exception("java/lang/ClassCastException", Some(message))
+    }
+
+    fn execute(&mut self, method: &Arc<Method>, instruction: &Instruction) -> Flow<Action> {
+        let class = method.class();
+        let opcode = instruction.opcode;
+        match opcode {
+            // nop
+            0x00 => (),
+            // aconst_null
+            0x01 => self.push(Value::Null),
+            // iconst_m1 to iconst_5
+            0x02..=0x08 => self.push(Value::Int(opcode as i32 - 0x03)),
+            // lconst_0, lconst_1
+            0x09 | 0x0a => self.push(Value::Long(opcode as i64 - 0x09)),
+            // fconst_0 to fconst_2
+            0x0b..=0x0d => self.push(Value::Float((opcode - 0x0b) as f32)),
+            // dconst_0, dconst_1
+            0x0e | 0x0f => self.push(Value::Double((opcode - 0x0e) as f64)),
+            // bipush, sipush
+            0x10 | 0x11 => self.push(Value::Int(int_operand(instruction)?)),
+            // ldc, ldc_w, ldc2_w
+            0x12..=0x14 => {
+                let index = constant(instruction)?;
+                if let Some(action) = self.unlinked_constant(class, index, 0)? {
+                    return Ok(action);
+                }
+                let value = self.load_constant(class, index)?;
+                self.push(value);
+            },
+            // iload, lload, fload, dload, aload
+            0x15..=0x19 => {
+                let value = self.frame().local(local(instruction)?)?;
+                self.push(value);
+            },
+            // iload_0 to aload_3
+            0x1a..=0x2d => {
+                let value = self.frame().local(((opcode - 0x1a) % 4) as usize)?;
+                self.push(value);
+            },
+            // iaload, laload, faload, daload, aaload, baload, caload, saload
+            0x2e..=0x35 => {
+                let index = self.pop_int()?;
+                let array = self.pop_ref()?;
+                let array = self.non_null(array)?;
+                let index = self.array_index(array, index)?;
+                let value = self.array(array)?.get(index).expect("index is checked");
+                self.push(value);
+            },
+            // istore, lstore, fstore, dstore, astore
+            0x36..=0x3a => {
+                let value = self.pop()?;
+                self.frame_mut().set_local(local(instruction)?, value)?;
+            },
+            // istore_0 to astore_3
+            0x3b..=0x4e => {
+                let value = self.pop()?;
+                self.frame_mut().set_local(((opcode - 0x3b) % 4) as usize, value)?;
+            },
+            // iastore, lastore, fastore, dastore, aastore, bastore, castore, sastore
+            0x4f..=0x56 => {
+                let value = self.pop()?;
+                let index = self.pop_int()?;
+                let array = self.pop_ref()?;
+                let array = self.non_null(array)?;
+                let index = self.array_index(array, index)?;
+                if let Value::Ref(object) = value {
+                    let array_class = self.heap.get(array).class();
+                    let object_class = self.heap.get(object).class();
+                    if let ClassKind::Array(component) = self.class(array_class).kind {
+                        if !self.is_assignable(object_class, component) {
+                            let message = java_name(&self.class(object_class).name);
+                            return Err(self.exception("java/lang/ArrayStoreException", Some(message)));
+                        }
+                    }
+                }
+                if !self.array_mut(array)?.set(index, value) {
+                    return Err(Error::Verify(format!("{:?} stored in the wrong kind of array", value)).into());
+                }
+            },
+            // pop
+            0x57 => {
+                self.pop()?;
+            },
+            // pop2
+            0x58 => {
+                if !self.pop()?.is_wide() {
+                    self.pop()?;
+                }
+            },
+            // dup
+            0x59 => {
+                let value = self.pop()?;
+                self.push(value);
+                self.push(value);
+            },
+            // dup_x1
+            0x5a => {
+                let (v1, v2) = (self.pop()?, self.pop()?);
+                self.push_all(&[v1, v2, v1]);
+            },
+            // dup_x2
+            0x5b => {
+                let (v1, v2) = (self.pop()?, self.pop()?);
+                if v2.is_wide() {
+                    self.push_all(&[v1, v2, v1]);
+                } else {
+                    let v3 = self.pop()?;
+                    self.push_all(&[v1, v3, v2, v1]);
+                }
+            },
+            // dup2
+            0x5c => {
+                let v1 = self.pop()?;
+                if v1.is_wide() {
+                    self.push_all(&[v1, v1]);
+                } else {
+                    let v2 = self.pop()?;
+                    self.push_all(&[v2, v1, v2, v1]);
+                }
+            },
+            // dup2_x1
+            0x5d => {
+                let (v1, v2) = (self.pop()?, self.pop()?);
+                if v1.is_wide() {
+                    self.push_all(&[v1, v2, v1]);
+                } else {
+                    let v3 = self.pop()?;
+                    self.push_all(&[v2, v1, v3, v2, v1]);
+                }
+            },
+            // dup2_x2
+            0x5e => {
+                let (v1, v2) = (self.pop()?, self.pop()?);
+                match (v1.is_wide(), v2.is_wide()) {
+                    (true, true) => self.push_all(&[v1, v2, v1]),
+                    (true, false) => {
+                        let v3 = self.pop()?;
+                        self.push_all(&[v1, v3, v2, v1]);
+                    },
+                    (false, _) => {
+                        let v3 = self.pop()?;
+                        if v3.is_wide() {
+                            self.push_all(&[v2, v1, v3, v2, v1]);
+                        } else {
+                            let v4 = self.pop()?;
+                            self.push_all(&[v2, v1, v4, v3, v2, v1]);
+                        }
+                    },
+                }
+            },
+            // swap
+            0x5f => {
+                let (v1, v2) = (self.pop()?, self.pop()?);
+                self.push_all(&[v1, v2]);
+            },
+            // iadd, ladd, fadd, dadd
+            0x60 => self.int_op(i32::wrapping_add)?,
+            0x61 => self.long_op(i64::wrapping_add)?,
+            0x62 => self.float_op(|a, b| a + b)?,
+            0x63 => self.double_op(|a, b| a + b)?,
+            // isub, lsub, fsub, dsub
+            0x64 => self.int_op(i32::wrapping_sub)?,
+            0x65 => self.long_op(i64::wrapping_sub)?,
+            0x66 => self.float_op(|a, b| a - b)?,
+            0x67 => self.double_op(|a, b| a - b)?,
+            // imul, lmul, fmul, dmul
+            0x68 => self.int_op(i32::wrapping_mul)?,
+            0x69 => self.long_op(i64::wrapping_mul)?,
+            0x6a => self.float_op(|a, b| a * b)?,
+            0x6b => self.double_op(|a, b| a * b)?,
+            // idiv, irem
+            0x6c | 0x70 => {
+                let (a, b) = self.pop_ints()?;
+                if b == 0 {
+                    return Err(self.exception("java/lang/ArithmeticException", Some("/ by zero".to_string())));
+                }
+                self.push(Value::Int(if opcode == 0x6c { a.wrapping_div(b) } else { a.wrapping_rem(b) }));
+            },
+            // ldiv, lrem
+            0x6d | 0x71 => {
+                let (a, b) = self.pop_longs()?;
+                if b == 0 {
+                    return Err(self.exception("java/lang/ArithmeticException", Some("/ by zero".to_string())));
+                }
+                self.push(Value::Long(if opcode == 0x6d { a.wrapping_div(b) } else { a.wrapping_rem(b) }));
+            },
+            // fdiv, ddiv
+            0x6e => self.float_op(|a, b| a / b)?,
+            0x6f => self.double_op(|a, b| a / b)?,
+            // frem, drem
+            0x72 => self.float_op(|a, b| a % b)?,
+            0x73 => self.double_op(|a, b| a % b)?,
+            // ineg, lneg, fneg, dneg
+            0x74 => {
+                let value = self.pop_int()?;
+                self.push(Value::Int(value.wrapping_neg()));
+            },
+            0x75 => {
+                let value = self.pop_long()?;
+                self.push(Value::Long(value.wrapping_neg()));
+            },
+            0x76 => {
+                let value = self.pop_float()?;
+                self.push(Value::Float(-value));
+            },
+            0x77 => {
+                let value = self.pop_double()?;
+                self.push(Value::Double(-value));
+            },
+            // ishl, ishr, iushr, the shift distance is masked by wrapping_shl
+            0x78 => self.int_op(|a, b| a.wrapping_shl(b as u32))?,
+            0x7a => self.int_op(|a, b| a.wrapping_shr(b as u32))?,
+            0x7c => self.int_op(|a, b| (a as u32).wrapping_shr(b as u32) as i32)?,
+            // lshl, lshr, lushr take an int distance
+            0x79 | 0x7b | 0x7d => {
+                let distance = self.pop_int()? as u32;
+                let value = self.pop_long()?;
+                self.push(Value::Long(match opcode {
+                    0x79 => value.wrapping_shl(distance),
+                    0x7b => value.wrapping_shr(distance),
+                    _ => (value as u64).wrapping_shr(distance) as i64,
+                }));
+            },
+            // iand, land, ior, lor, ixor, lxor
+            0x7e => self.int_op(|a, b| a & b)?,
+            0x7f => self.long_op(|a, b| a & b)?,
+            0x80 => self.int_op(|a, b| a | b)?,
+            0x81 => self.long_op(|a, b| a | b)?,
+            0x82 => self.int_op(|a, b| a ^ b)?,
+            0x83 => self.long_op(|a, b| a ^ b)?,
+            // iinc
+            0x84 => match instruction.operand {
+                Operand::Iinc { index, delta } => {
+                    let value = self.frame().local(index as usize)?.as_int()?;
+                    self.frame_mut().set_local(index as usize, Value::Int(value.wrapping_add(delta as i32)))?;
+                },
+                _ => return Err(bad_operand(instruction)),
+            },
+            // i2l, i2f, i2d
+            0x85 => {
+                let value = self.pop_int()?;
+                self.push(Value::Long(value as i64));
+            },
+            0x86 => {
+                let value = self.pop_int()?;
+                self.push(Value::Float(value as f32));
+            },
+            0x87 => {
+                let value = self.pop_int()?;
+                self.push(Value::Double(value as f64));
+            },
+            // l2i, l2f, l2d
+            0x88 => {
+                let value = self.pop_long()?;
+                self.push(Value::Int(value as i32));
+            },
+            0x89 => {
+                let value = self.pop_long()?;
+                self.push(Value::Float(value as f32));
+            },
+            0x8a => {
+                let value = self.pop_long()?;
+                self.push(Value::Double(value as f64));
+            },
+            // f2i, f2l, f2d, Rust's casts saturate and turn NaN into 0 like Java's
+            0x8b => {
+                let value = self.pop_float()?;
+                self.push(Value::Int(value as i32));
+            },
+            0x8c => {
+                let value = self.pop_float()?;
+                self.push(Value::Long(value as i64));
+            },
+            0x8d => {
+                let value = self.pop_float()?;
+                self.push(Value::Double(value as f64));
+            },
+            // d2i, d2l, d2f
+            0x8e => {
+                let value = self.pop_double()?;
+                self.push(Value::Int(value as i32));
+            },
+            0x8f => {
+                let value = self.pop_double()?;
+                self.push(Value::Long(value as i64));
+            },
+            0x90 => {
+                let value = self.pop_double()?;
+                self.push(Value::Float(value as f32));
+            },
+            // i2b, i2c, i2s
+            0x91 => {
+                let value = self.pop_int()?;
+                self.push(Value::Int(value as i8 as i32));
+            },
+            0x92 => {
+                let value = self.pop_int()?;
+                self.push(Value::Int(value as u16 as i32));
+            },
+            0x93 => {
+                let value = self.pop_int()?;
+                self.push(Value::Int(value as i16 as i32));
+            },
+            // lcmp
+            0x94 => {
+                let (a, b) = self.pop_longs()?;
+                self.push(Value::Int(a.cmp(&b) as i32));
+            },
+            // fcmpl, fcmpg, NaN compares as -1 for the l forms and 1 for the g forms
+            0x95 | 0x96 => {
+                let (a, b) = self.pop_floats()?;
+                let nan = if opcode == 0x95 { -1 } else { 1 };
+                self.push(Value::Int(a.partial_cmp(&b).map_or(nan, |ordering| ordering as i32)));
+            },
+            // dcmpl, dcmpg
+            0x97 | 0x98 => {
+                let (a, b) = self.pop_doubles()?;
+                let nan = if opcode == 0x97 { -1 } else { 1 };
+                self.push(Value::Int(a.partial_cmp(&b).map_or(nan, |ordering| ordering as i32)));
+            },
+            // ifeq, ifne, iflt, ifge, ifgt, ifle
+            0x99..=0x9e => {
+                let value = self.pop_int()?;
+                return branch(instruction, condition(opcode - 0x99, value.cmp(&0)));
+            },
+            // if_icmpeq, if_icmpne, if_icmplt, if_icmpge, if_icmpgt, if_icmple
+            0x9f..=0xa4 => {
+                let (a, b) = self.pop_ints()?;
+                return branch(instruction, condition(opcode - 0x9f, a.cmp(&b)));
+            },
+            // if_acmpeq, if_acmpne
+            0xa5 | 0xa6 => {
+                let (b, a) = (self.pop()?.as_ref()?, self.pop()?.as_ref()?);
+                return branch(instruction, (a == b) == (opcode == 0xa5));
+            },
+            // goto, goto_w
+            0xa7 | 0xc8 => return branch(instruction, true),
+            // jsr, jsr_w
+            0xa8 | 0xc9 => {
+                let next = method.code.as_ref()
+                    .and_then(|code| code.instructions.get(self.frame().ip + 1))
+                    .ok_or_else(|| Error::Verify("jsr at the end of the code".to_string()))?
+                    .pc;
+                self.push(Value::ReturnAddress(next));
+                return branch(instruction, true);
+            },
+            // ret
+            0xa9 => match self.frame().local(local(instruction)?)? {
+                Value::ReturnAddress(pc) => return Ok(Action::Jump(pc)),
+                other => return Err(Error::Verify(format!("ret to {:?}", other)).into()),
+            },
+            // tableswitch
+            0xaa => match instruction.operand {
+                Operand::TableSwitch { default, low, high, ref targets } => {
+                    let index = self.pop_int()?;
+                    if index < low || index > high {
+                        return Ok(Action::Jump(default));
+                    }
+                    return Ok(Action::Jump(targets[(index as i64 - low as i64) as usize]));
+                },
+                _ => return Err(bad_operand(instruction)),
+            },
+            // lookupswitch
+            0xab => match instruction.operand {
+                Operand::LookupSwitch { default, ref pairs } => {
+                    let key = self.pop_int()?;
+                    let target = pairs.iter().find(|&&(value, _)| value == key).map_or(default, |&(_, target)| target);
+                    return Ok(Action::Jump(target));
+                },
+                _ => return Err(bad_operand(instruction)),
+            },
+            // ireturn, lreturn, freturn, dreturn, areturn
+            0xac..=0xb0 => {
+                let value = self.pop()?;
+                return Ok(Action::Return(Some(value)));
+            },
+            // return
+            0xb1 => return Ok(Action::Return(None)),
+            // getstatic
+            0xb2 => {
+                let (owner, slot) = self.resolve_field(method, constant(instruction)?, opcode)?;
+                if !self.is_initialized(owner) {
+                    return Ok(Action::Initialize(owner));
+                }
+                let value = self.class(owner).statics[slot];
+                self.push(value);
+            },
+            // putstatic
+            0xb3 => {
+                let (owner, slot) = self.resolve_field(method, constant(instruction)?, opcode)?;
+                if !self.is_initialized(owner) {
+                    return Ok(Action::Initialize(owner));
+                }
+                let value = self.pop()?;
+                self.class_mut(owner).statics[slot] = value;
+            },
+            // getfield
+            0xb4 => {
+                let (_, slot) = self.resolve_field(method, constant(instruction)?, opcode)?;
+                let object = self.pop_ref()?;
+                let object = self.non_null(object)?;
+                let value = self.field(object, slot)?;
+                self.push(value);
+            },
+            // putfield
+            0xb5 => {
+                let (_, slot) = self.resolve_field(method, constant(instruction)?, opcode)?;
+                let value = self.pop()?;
+                let object = self.pop_ref()?;
+                let object = self.non_null(object)?;
+                self.set_field(object, slot, value)?;
+            },
+            // invokevirtual, invokeinterface
+            0xb6 | 0xb9 => {
+                let index = constant(instruction)?;
+                let resolved = self.resolve_method(class, index)?;
+                // The handle runs with the descriptor of the call site
+                if opcode == 0xb6 && self.is_signature_polymorphic(&resolved) {
+                    let class_file = self.class_file(class)?;
+                    let (_, method_ref) = class_file.constant_pool().method_ref(index as usize)?;
+                    if self.class(resolved.class()).name == "java/lang/invoke/VarHandle" {
+                        return self.access_var(resolved.name(), method_ref.descriptor);
+                    }
+                    return self.invoke_handle(method_ref.descriptor, resolved.name() == "invokeExact");
+                }
+                if resolved.flags().is_static() {
+                    return Err(self.incompatible_call("non-static", &resolved));
+                }
+                let receiver = self.receiver(resolved.arg_count())?;
+                let receiver_class = self.heap.get(receiver).class();
+                if opcode == 0xb9 && !self.is_assignable(receiver_class, resolved.class()) {
+                    let message = format!("Class {} does not implement the requested interface {}",
+                        java_name(&self.class(receiver_class).name), java_name(&self.class(resolved.class()).name));
+                    return Err(self.exception("java/lang/IncompatibleClassChangeError", Some(message)));
+                }
+                return Ok(Action::Invoke(self.select_method(receiver_class, &resolved)?));
+            },
+            // invokespecial
+            0xb7 => {
+                let resolved = self.resolve_method(class, constant(instruction)?)?;
+                if resolved.flags().is_static() {
+                    return Err(self.incompatible_call("non-static", &resolved));
+                }
+                self.receiver(resolved.arg_count())?;
+                // super.m() runs the superclass's method even when the resolved one is further
+                // up, JVMS §6.5 invokespecial with ACC_SUPER
+                let owner = resolved.class();
+                let is_super_call = resolved.name() != "<init>" && owner != class
+                    && !self.class(owner).is_interface() && self.is_assignable(class, owner);
+                let method = match self.class(class).super_class {
+                    Some(super_class) if is_super_call =>
+                        self.find_method(super_class, resolved.name(), resolved.descriptor())?.unwrap_or(resolved),
+                    _ => resolved,
+                };
+                return Ok(Action::Invoke(method));
+            },
+            // invokestatic
+            0xb8 => {
+                let resolved = self.resolve_method(class, constant(instruction)?)?;
+                if !resolved.flags().is_static() {
+                    return Err(self.incompatible_call("static", &resolved));
+                }
+                if !self.is_initialized(resolved.class()) {
+                    return Ok(Action::Initialize(resolved.class()));
+                }
+                return Ok(Action::Invoke(resolved));
+            },
+            // invokedynamic
+            0xba => return self.invoke_dynamic(method, instruction.pc, constant(instruction)?),
+            // new
+            0xbb => {
+                let resolved = self.resolve_class(class, constant(instruction)?)?;
+                if self.class(resolved).is_interface() || self.class(resolved).flags().is_abstract() {
+                    let message = java_name(&self.class(resolved).name);
+                    return Err(self.exception("java/lang/InstantiationError", Some(message)));
+                }
+                if !self.is_initialized(resolved) {
+                    return Ok(Action::Initialize(resolved));
+                }
+                let object = self.alloc_instance(resolved);
+                self.push(Value::Ref(object));
+            },
+            // newarray
+            0xbc => {
+                let type_name = match instruction.operand {
+                    Operand::NewArray(atype) => bytecode::array_type_name(atype)
+                        .ok_or_else(|| Error::Verify(format!("newarray of type {}", atype)))?,
+                    _ => return Err(bad_operand(instruction)),
+                };
+                let component = self.load_class(type_name)?;
+                let array_class = self.array_class_of(component)?;
+                let count = self.array_count()?;
+                let array = self.alloc_array(array_class, count)?;
+                self.push(Value::Ref(array));
+            },
+            // anewarray
+            0xbd => {
+                let component = self.resolve_class(class, constant(instruction)?)?;
+                let array_class = self.array_class_of(component)?;
+                let count = self.array_count()?;
+                let array = self.alloc_array(array_class, count)?;
+                self.push(Value::Ref(array));
+            },
+            // arraylength
+            0xbe => {
+                let array = self.pop_ref()?;
+                let array = self.non_null(array)?;
+                let length = self.array(array)?.len();
+                self.push(Value::Int(length as i32));
+            },
+            // athrow
+            0xbf => {
+                let exception = self.pop_ref()?;
+                let exception = self.non_null(exception)?;
+                return Err(Throw::Exception(exception));
+            },
+            // checkcast
+            0xc0 => {
+                let target = self.resolve_class(class, constant(instruction)?)?;
+                let object = self.pop_ref()?;
+                if let Some(object) = object {
+                    if !self.is_assignable(self.heap.get(object).class(), target) {
+                        return Err(self.class_cast(object, target));
+                    }
+                }
+                self.push(Value::from_ref(object));
+            },
+            // instanceof
+            0xc1 => {
+                let target = self.resolve_class(class, constant(instruction)?)?;
+                let object = self.pop_ref()?;
+                let is_instance = object.is_some_and(|object| self.is_assignable(self.heap.get(object).class(), target));
+                self.push(Value::Int(is_instance as i32));
+            },
+            // monitorenter
+            0xc2 => {
+                let object = self.pop_ref()?;
+                let object = self.non_null(object)?;
+                self.monitor_enter(object);
+            },
+            // monitorexit
+            0xc3 => {
+                let object = self.pop_ref()?;
+                let object = self.non_null(object)?;
+                self.monitor_exit(object)?;
+            },
+            // multianewarray
+            0xc5 => {
+                let (index, dimensions) = match instruction.operand {
+                    Operand::MultiANewArray { index, dimensions } => (index, dimensions as usize),
+                    _ => return Err(bad_operand(instruction)),
+                };
+                let array_class = self.resolve_class(class, index)?;
+                let mut counts = vec![0; dimensions];
+                for i in (0..dimensions).rev() {
+                    counts[i] = self.pop_int()?;
+                }
+                if let Some(&count) = counts.iter().find(|&&count| count < 0) {
+                    return Err(self.exception("java/lang/NegativeArraySizeException", Some(count.to_string())));
+                }
+                if counts.is_empty() {
+                    return Err(Error::Verify("multianewarray with no dimensions".to_string()).into());
+                }
+                let array = self.multi_array(array_class, &counts)?;
+                self.push(Value::Ref(array));
+            },
+            // ifnull, ifnonnull
+            0xc6 | 0xc7 => {
+                let object = self.pop_ref()?;
+                return branch(instruction, object.is_none() == (opcode == 0xc6));
+            },
+            _ => return Err(Error::InvalidOpcode(opcode).into()),
+        }
+        Ok(Action::Next)
+    }
+
+    // Bottom to top, the way JVMS writes the stack
+    fn push_all(&mut self, values: &[Value]) {
+        for &value in values {
+            self.push(value);
+        }
+    }
+
+    fn int_op<F: Fn(i32, i32) -> i32>(&mut self, op: F) -> Result<()> {
+        let (a, b) = self.pop_ints()?;
+        self.push(Value::Int(op(a, b)));
+        Ok(())
+    }
+
+    fn long_op<F: Fn(i64, i64) -> i64>(&mut self, op: F) -> Result<()> {
+        let (a, b) = self.pop_longs()?;
+        self.push(Value::Long(op(a, b)));
+        Ok(())
+    }
+
+    fn float_op<F: Fn(f32, f32) -> f32>(&mut self, op: F) -> Result<()> {
+        let (a, b) = self.pop_floats()?;
+        self.push(Value::Float(op(a, b)));
+        Ok(())
+    }
+
+    fn double_op<F: Fn(f64, f64) -> f64>(&mut self, op: F) -> Result<()> {
+        let (a, b) = self.pop_doubles()?;
+        self.push(Value::Double(op(a, b)));
+        Ok(())
+    }
+
+    fn array_count(&mut self) -> Flow<usize> {
+        let count = self.pop_int()?;
+        if count < 0 {
+            return Err(self.exception("java/lang/NegativeArraySizeException", Some(count.to_string())));
+        }
+        Ok(count as usize)
+    }
+
+    fn incompatible_call(&mut self, expected: &str, method: &Method) -> Throw {
+        let message = format!("Expected {} method {}.{}{}", expected,
+            java_name(&self.class(method.class()).name), method.name(), method.descriptor());
+        self.exception("java/lang/IncompatibleClassChangeError", Some(message))
+    }
+}
+
+// Operands are checked by bytecode::decode, a mismatch is a bug there
+fn bad_operand(instruction: &Instruction) -> Throw {
+    Error::Verify(format!("{} with operand {:?}", instruction.mnemonic(), instruction.operand)).into()
+}
+
+fn int_operand(instruction: &Instruction) -> Flow<i32> {
+    match instruction.operand {
+        Operand::Int(value) => Ok(value),
+        _ => Err(bad_operand(instruction)),
+    }
+}
+
+fn constant(instruction: &Instruction) -> Flow<u2> {
+    match instruction.operand {
+        Operand::Constant(index) => Ok(index),
+        Operand::InvokeInterface { index, .. } => Ok(index),
+        _ => Err(bad_operand(instruction)),
+    }
+}
+
+fn local(instruction: &Instruction) -> Flow<usize> {
+    match instruction.operand {
+        Operand::Local(index) => Ok(index as usize),
+        _ => Err(bad_operand(instruction)),
+    }
+}
+
+fn branch(instruction: &Instruction, taken: bool) -> Flow<Action> {
+    match instruction.operand {
+        Operand::Branch(target) if taken => Ok(Action::Jump(target)),
+        Operand::Branch(_) => Ok(Action::Next),
+        _ => Err(bad_operand(instruction)),
+    }
+}
+
+// eq, ne, lt, ge, gt, le in the order of the if<cond> opcodes
+fn condition(kind: u8, ordering: Ordering) -> bool {
+    match kind {
+        0 => ordering == Ordering::Equal,
+        1 => ordering != Ordering::Equal,
+        2 => ordering == Ordering::Less,
+        3 => ordering != Ordering::Less,
+        4 => ordering == Ordering::Greater,
+        _ => ordering != Ordering::Greater,
+    }
+}
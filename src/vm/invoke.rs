@@ -0,0 +1,602 @@
+/*
+* java.lang.invoke objects the VM makes itself: the MethodType of a descriptor and the direct
+* method handles of MethodHandle constants and lookups, JVMS §5.4.3.5. invokeExact and invoke
+* of a direct handle of the call site's type use the member right away, other calls go through
+* an adapter that boxes the arguments for MethodHandle.invokeBoxed
+*/
+use std::sync::Arc;
+
+use class_file::access_flags::{ACC_FINAL, ACC_PRIVATE, ACC_PUBLIC, ACC_VARARGS};
+use class_file::constant_pool::{MemberRef, RefKind};
+use class_file::descriptor::{java_name, FieldType, MethodDescriptor};
+use jasm;
+use result::{Result, Error};
+use types::u2;
+use vm::class::{descriptor_of, ClassId, ClassKind, Method};
+use vm::heap::{Array, Body, ObjectRef};
+use vm::interpreter::Action;
+use vm::spin;
+use vm::value::Value;
+use vm::{Flow, Vm};
+
+// What a DirectMethodHandle the VM made stands for
+pub(super) struct DirectHandle {
+    pub(super) kind: RefKind,
+    // The symbolic reference, the invoker and lambda classes use it
+    pub(super) owner: String,
+    pub(super) name: String,
+    pub(super) descriptor: String,
+    pub(super) is_interface: bool,
+    // The access flags of the member
+    bits: u2,
+    // Of the handle's MethodType
+    pub(super) type_descriptor: String,
+    member: Member,
+}
+
+#[derive(Clone)]
+enum Member {
+    // The declaring class and the slot in its statics or in the object's fields
+    Field(ClassId, usize),
+    Method(Arc<Method>),
+}
+
+// Lookup.allowedModes bits
+const PRIVATE: i32 = 0x02;
+const PROTECTED: i32 = 0x04;
+const PACKAGE: i32 = 0x08;
+
+// A FULL lookup, MethodHandles.lookup() makes them
+const FULL_ACCESS: i32 = 0x5f;
+
+const WRAPPERS: &[(FieldType, &str)] = &[
+    (FieldType::Boolean, "java/lang/Boolean"), (FieldType::Byte, "java/lang/Byte"),
+    (FieldType::Char, "java/lang/Character"), (FieldType::Short, "java/lang/Short"),
+    (FieldType::Int, "java/lang/Integer"), (FieldType::Long, "java/lang/Long"),
+    (FieldType::Float, "java/lang/Float"), (FieldType::Double, "java/lang/Double"),
+];
+
+// The wrapper class of a primitive type
+pub(super) fn wrapper_of(field_type: &FieldType) -> Option<&'static str> {
+    WRAPPERS.iter().find(|(primitive, _)| primitive == field_type).map(|&(_, wrapper)| wrapper)
+}
+
+// The primitive type of a wrapper class
+pub(super) fn unwrapped(field_type: &FieldType) -> Option<&'static FieldType> {
+    match *field_type {
+        FieldType::Object(ref class_name) => WRAPPERS.iter().find(|&&(_, wrapper)| wrapper == class_name).map(|(primitive, _)| primitive),
+        _ => None,
+    }
+}
+
+impl Vm {
+    // A java/lang/invoke/MethodType of the classes in the method descriptor, loading them.
+    // Each descriptor gets one object
+    pub(super) fn method_type(&mut self, descriptor: &str) -> Flow<ObjectRef> {
+        if let Some(&method_type) = self.method_types.get(descriptor) {
+            return Ok(method_type);
+        }
+        let parsed = MethodDescriptor::parse(descriptor)?;
+        let mut ptypes = Vec::new();
+        for param in parsed.params() {
+            let class = self.class_of_type(Some(param))?;
+            ptypes.push(Value::Ref(self.mirror(class)?));
+        }
+        let rtype = self.class_of_type(parsed.return_type())?;
+        let rtype = self.mirror(rtype)?;
+        let array_class = self.load_class("[Ljava/lang/Class;")?;
+        let ptypes = self.heap.alloc(array_class, Body::Array(Array::Reference(ptypes)));
+        let class = self.load_class("java/lang/invoke/MethodType")?;
+        let method_type = self.alloc_instance(class);
+        self.set_named_field(method_type, "rtype", Value::Ref(rtype))?;
+        self.set_named_field(method_type, "ptypes", Value::Ref(ptypes))?;
+        self.method_types.insert(descriptor.to_string(), method_type);
+        Ok(method_type)
+    }
+
+    // The class of a field or return type, None is void
+    pub(super) fn class_of_type(&mut self, field_type: Option<&FieldType>) -> Flow<ClassId> {
+        match field_type {
+            None => self.load_class("void"),
+            Some(FieldType::Object(name)) => self.load_class(name),
+            Some(array @ &FieldType::Array(_)) => self.load_class(&descriptor_of(array)),
+            Some(primitive) => self.load_class(&primitive.type_name()),
+        }
+    }
+
+    // Resolves the member for code in `caller` and makes the method handle, with the access and
+    // kind checks of the matching field or invoke instruction
+    pub(super) fn direct_handle(&mut self, caller: ClassId, kind: RefKind, reference: &MemberRef) -> Flow<ObjectRef> {
+        let owner = self.load_class(reference.class_name)?;
+        let (declaring, bits, is_static) = if kind.is_field() {
+            match self.find_field(owner, reference.name, reference.descriptor) {
+                Some((declaring, field)) => (declaring, field.flags.bits(), field.flags.is_static()),
+                None => {
+                    let message = format!("{}.{}", reference.class_name, reference.name);
+                    return Err(self.exception("java/lang/NoSuchFieldError", Some(message)));
+                },
+            }
+        } else {
+            let method = self.lookup_method(owner, reference.name, reference.descriptor)?;
+            // Constructors aren't inherited
+            if kind == RefKind::NewInvokeSpecial && method.class != owner {
+                let message = format!("{}.{}{}", reference.class_name, reference.name, reference.descriptor);
+                return Err(self.exception("java/lang/NoSuchMethodError", Some(message)));
+            }
+            (method.class, method.flags.bits(), method.flags.is_static())
+        };
+        let name = format!("{}.{}", java_name(reference.class_name), reference.name);
+        let wants_static = kind == RefKind::GetStatic || kind == RefKind::PutStatic || kind == RefKind::InvokeStatic;
+        if is_static != wants_static {
+            let message = format!("Expected {} {} {}", if wants_static { "static" } else { "non-static" },
+                if kind.is_field() { "field" } else { "method" }, name);
+            return Err(self.exception("java/lang/IncompatibleClassChangeError", Some(message)));
+        }
+        if !self.is_accessible(caller, declaring, bits)? {
+            let message = format!("class {} tried to access {}", java_name(&self.class(caller).name), name);
+            return Err(self.exception("java/lang/IllegalAccessError", Some(message)));
+        }
+        let is_final = bits & ACC_FINAL != 0;
+        if is_final && (kind == RefKind::PutField || kind == RefKind::PutStatic) {
+            let message = format!("{} is final", name);
+            return Err(self.exception("java/lang/IllegalAccessError", Some(message)));
+        }
+        let this = match reference.class_name.starts_with('[') {
+            true => reference.class_name.to_string(),
+            false => format!("L{};", reference.class_name),
+        };
+        let descriptor = match kind {
+            RefKind::GetField => format!("({}){}", this, reference.descriptor),
+            RefKind::GetStatic => format!("(){}", reference.descriptor),
+            RefKind::PutField => format!("({}{})V", this, reference.descriptor),
+            RefKind::PutStatic => format!("({})V", reference.descriptor),
+            RefKind::InvokeStatic => reference.descriptor.to_string(),
+            RefKind::NewInvokeSpecial => {
+                let end = reference.descriptor.rfind(')').unwrap_or(0);
+                format!("{}){}", &reference.descriptor[..end], this)
+            },
+            RefKind::InvokeVirtual | RefKind::InvokeSpecial | RefKind::InvokeInterface =>
+                format!("({}{}", this, &reference.descriptor[1..]),
+        };
+        let method_type = self.method_type(&descriptor)?;
+        let class = self.load_class("java/lang/invoke/DirectMethodHandle")?;
+        let handle = self.alloc_instance(class);
+        self.set_named_field(handle, "type", Value::Ref(method_type))?;
+        self.set_named_field(handle, "kind", Value::Int(kind as i32))?;
+        let member = match self.find_field(owner, reference.name, reference.descriptor) {
+            Some((declaring, field)) if kind.is_field() => Member::Field(declaring, field.slot),
+            _ => Member::Method(self.lookup_method(owner, reference.name, reference.descriptor)?),
+        };
+        self.direct_handles.insert(handle, DirectHandle {
+            kind,
+            owner: reference.class_name.to_string(),
+            name: reference.name.to_string(),
+            descriptor: reference.descriptor.to_string(),
+            is_interface: self.class(owner).is_interface(),
+            bits,
+            type_descriptor: descriptor,
+            member,
+        });
+        Ok(handle)
+    }
+
+    // MethodHandleNatives.resolve, a direct handle for a Lookup of `caller` with the access
+    // `modes`. Without private access private members can't be found, a lookup with neither
+    // package nor private access finds public members only
+    pub(super) fn resolve_handle(&mut self, caller: ClassId, modes: i32, kind: RefKind, refc: ClassId, name: &str, descriptor: &str)
+        -> Flow<ObjectRef> {
+        let class_name = self.class(refc).name.clone();
+        let reference = MemberRef { class_name: &class_name, name, descriptor };
+        let handle = self.direct_handle(caller, kind, &reference)?;
+        let bits = self.direct_handles[&handle].bits;
+        let denied = if modes & PRIVATE == 0 && bits & ACC_PRIVATE != 0 {
+            Some("member is private")
+        } else if modes & (PRIVATE | PACKAGE | PROTECTED) == 0 && bits & ACC_PUBLIC == 0 {
+            Some("member is not public")
+        } else {
+            None
+        };
+        if let Some(denied) = denied {
+            let message = format!("{}: {}.{}", denied, java_name(&class_name), name);
+            return Err(self.exception("java/lang/IllegalAccessError", Some(message)));
+        }
+        self.varargs_collector(handle)
+    }
+
+    // The handle of a varargs method is a MethodHandles$VarargsCollector like in the JDK, so
+    // invoke and bootstrap methods collect trailing arguments. asFixedArity gives the direct
+    // handle back
+    pub(super) fn varargs_collector(&mut self, handle: ObjectRef) -> Flow<ObjectRef> {
+        match self.direct_handles.get(&handle) {
+            Some(direct) if !direct.kind.is_field() && direct.bits & ACC_VARARGS != 0 => (),
+            _ => return Ok(handle),
+        }
+        let class = self.load_class("java/lang/invoke/MethodHandles$VarargsCollector")?;
+        let collector = self.alloc_instance(class);
+        let method_type = self.named_field(handle, "type")?;
+        self.set_named_field(collector, "type", method_type)?;
+        self.set_named_field(collector, "target", Value::Ref(handle))?;
+        Ok(collector)
+    }
+
+    // MethodHandles.lookup(), full access to the class that calls it
+    pub(super) fn lookup(&mut self, caller: ClassId) -> Flow<ObjectRef> {
+        let caller = self.class(caller).host.unwrap_or(caller);
+        let class = self.load_class("java/lang/invoke/MethodHandles$Lookup")?;
+        let lookup = self.alloc_instance(class);
+        let mirror = self.mirror(caller)?;
+        self.set_named_field(lookup, "lookupClass", Value::Ref(mirror))?;
+        self.set_named_field(lookup, "allowedModes", Value::Int(FULL_ACCESS))?;
+        Ok(lookup)
+    }
+
+    // The descriptor of a MethodType, or of a field's Class
+    pub(super) fn type_descriptor(&mut self, method_type: ObjectRef) -> Result<String> {
+        if let Some(class) = self.class_of_mirror(method_type) {
+            return Ok(self.class_descriptor(class));
+        }
+        let rtype = self.named_field(method_type, "rtype")?.as_ref()?;
+        let ptypes = self.named_field(method_type, "ptypes")?.as_ref()?;
+        let mut descriptor = "(".to_string();
+        if let Some(ptypes) = ptypes {
+            let ptypes: Vec<Value> = match *self.array(ptypes)? {
+                Array::Reference(ref ptypes) => ptypes.clone(),
+                _ => return Err(Error::Verify("MethodType.ptypes is not a Class[]".to_string())),
+            };
+            for ptype in ptypes {
+                descriptor.push_str(&self.mirror_descriptor(ptype.as_ref()?)?);
+            }
+        }
+        descriptor.push(')');
+        descriptor.push_str(&self.mirror_descriptor(rtype)?);
+        Ok(descriptor)
+    }
+
+    fn mirror_descriptor(&self, mirror: Option<ObjectRef>) -> Result<String> {
+        match mirror.and_then(|mirror| self.class_of_mirror(mirror)) {
+            Some(class) => Ok(self.class_descriptor(class)),
+            None => Err(Error::Verify("MethodType with a null type".to_string())),
+        }
+    }
+
+    // "Ljava/lang/String;", "[I", "I" or "V"
+    pub(super) fn class_descriptor(&self, class: ClassId) -> String {
+        let class = self.class(class);
+        match class.kind {
+            ClassKind::Instance => format!("L{};", class.name),
+            ClassKind::Array(_) => class.name.clone(),
+            ClassKind::Primitive(ref field_type) => descriptor_of(field_type),
+            ClassKind::Void => "V".to_string(),
+        }
+    }
+
+    // invokeExact and invoke with the call site's descriptor, the handle and the arguments are
+    // on the operand stack
+    pub(super) fn invoke_handle(&mut self, descriptor: &str, exact: bool) -> Flow<Action> {
+        let count = MethodDescriptor::parse(descriptor)?.params().len();
+        let handle = self.receiver(count + 1)?;
+        let (kind, member) = match self.direct_handles.get(&handle) {
+            Some(direct) if direct.type_descriptor == descriptor => (direct.kind, direct.member.clone()),
+            _ => return Ok(Action::Invoke(self.adapter(descriptor, exact)?)),
+        };
+        match member {
+            Member::Field(class, slot) => self.access_field(kind, class, slot),
+            Member::Method(method) => self.invoke_member(kind, method, count),
+        }
+    }
+
+    // The field of a getter or setter the VM made: the declaring class, the slot and whether
+    // it is static
+    pub(super) fn field_handle(&self, handle: ObjectRef) -> Result<(ClassId, usize, bool)> {
+        match self.direct_handles.get(&handle) {
+            Some(&DirectHandle { kind, member: Member::Field(class, slot), .. }) =>
+                Ok((class, slot, kind == RefKind::GetStatic || kind == RefKind::PutStatic)),
+            _ => Err(Error::Verify("not a field handle the VM made".to_string())),
+        }
+    }
+
+    // What getfield, getstatic, putfield and putstatic do, the handle is under the operands
+    fn access_field(&mut self, kind: RefKind, class: ClassId, slot: usize) -> Flow<Action> {
+        let is_static = kind == RefKind::GetStatic || kind == RefKind::PutStatic;
+        if is_static && !self.is_initialized(class) {
+            return Ok(Action::Initialize(class));
+        }
+        let value = if kind == RefKind::PutField || kind == RefKind::PutStatic { Some(self.pop()?) } else { None };
+        let object = if is_static { None } else { Some(self.pop_ref()?) };
+        self.pop()?;
+        let object = match object {
+            Some(object) => Some(self.non_null(object)?),
+            None => None,
+        };
+        match (object, value) {
+            (Some(object), Some(value)) => self.set_field(object, slot, value)?,
+            (Some(object), None) => {
+                let value = self.field(object, slot)?;
+                self.push(value);
+            },
+            (None, Some(value)) => self.class_mut(class).statics[slot] = value,
+            (None, None) => {
+                let value = self.class(class).statics[slot];
+                self.push(value);
+            },
+        }
+        Ok(Action::Next)
+    }
+
+    // What the invoke instructions and new do, the handle under the `count` arguments goes
+    fn invoke_member(&mut self, kind: RefKind, method: Arc<Method>, count: usize) -> Flow<Action> {
+        let below = self.frame().stack.len() - count - 1;
+        let method = match kind {
+            RefKind::InvokeStatic | RefKind::NewInvokeSpecial if !self.is_initialized(method.class) =>
+                return Ok(Action::Initialize(method.class)),
+            RefKind::InvokeVirtual | RefKind::InvokeInterface => {
+                let receiver = self.receiver(count)?;
+                let receiver_class = self.heap.get(receiver).class();
+                self.select_method(receiver_class, &method)?
+            },
+            RefKind::InvokeSpecial => {
+                self.receiver(count)?;
+                method
+            },
+            _ => method,
+        };
+        if kind == RefKind::NewInvokeSpecial {
+            // The new object is what the handle returns, <init> gets it too
+            let object = Value::Ref(self.alloc_instance(method.class));
+            let stack = &mut self.frame_mut().stack;
+            stack[below] = object;
+            stack.insert(below + 1, object);
+        } else {
+            self.frame_mut().stack.remove(below);
+        }
+        Ok(Action::Invoke(method))
+    }
+
+    // A static invoke(MethodHandle, params...) of the call site's descriptor that boxes the
+    // arguments, calls invokeBoxed of the handle asCallSiteType returns and unboxes the result
+    fn adapter(&mut self, descriptor: &str, exact: bool) -> Flow<Arc<Method>> {
+        let key = (descriptor.to_string(), exact);
+        if let Some(adapter) = self.adapters.get(&key) {
+            return Ok(adapter.clone());
+        }
+        let parsed = MethodDescriptor::parse(descriptor)?;
+        let mut code = String::new();
+        let mut slot = 1;
+        for (i, param) in parsed.params().iter().enumerate() {
+            code.push_str(&format!("dup\nldc Integer {}\n{}\n{}\naastore\n", i, spin::load(param, slot), spin::box_value(Some(param))));
+            slot += param.slots();
+        }
+        let end = descriptor.rfind(')').unwrap_or(0);
+        let name = self.spun_name("java/lang/invoke/Adapter");
+        let source = format!("
+.version 52 0
+.class final super {name}
+.super java/lang/Object
+
+.method static invoke (Ljava/lang/invoke/MethodHandle;{params}){result}
+    .code stack 8 locals {locals}
+        aload 0
+        ldc MethodType {descriptor}
+        iconst_{exact}
+        invokevirtual Method java/lang/invoke/MethodHandle asCallSiteType (Ljava/lang/invoke/MethodType;Z)Ljava/lang/invoke/MethodHandle;
+        ldc Integer {count}
+        anewarray java/lang/Object
+        {code}
+        invokevirtual Method java/lang/invoke/MethodHandle invokeBoxed ([Ljava/lang/Object;)Ljava/lang/Object;
+        {unbox}
+        {return}
+    .end code
+.end method
+",
+            name = name, params = &descriptor[1..end], result = &descriptor[end + 1..], locals = slot,
+            descriptor = jasm::quote(descriptor), exact = exact as u8, count = parsed.params().len(), code = code,
+            unbox = spin::unbox_value(parsed.return_type()), return = spin::return_value(parsed.return_type()));
+        let host = self.load_class("java/lang/invoke/MethodHandle")?;
+        let class = self.spin(host, &source)?;
+        let adapter = self.class(class).find_method("invoke", &format!("(Ljava/lang/invoke/MethodHandle;{}", &descriptor[1..]))
+            .cloned()
+            .ok_or_else(|| Error::Verify("adapter without invoke".to_string()))?;
+        self.adapters.insert(key, adapter.clone());
+        Ok(adapter)
+    }
+
+    // DirectMethodHandle.makeInvoker, a DirectMethodHandle$Invoker that unboxes the arguments,
+    // uses the member with the instruction of the handle's kind and boxes the result
+    pub(super) fn invoker(&mut self, handle: ObjectRef) -> Flow<ObjectRef> {
+        let direct = self.direct_handles.get(&handle)
+            .ok_or_else(|| Error::Verify("DirectMethodHandle the VM didn't make".to_string()))?;
+        let key = format!("{} {}.{}{}", direct.kind as u8, direct.owner, direct.name, direct.descriptor);
+        if let Some(&invoker) = self.invokers.get(&key) {
+            return Ok(invoker);
+        }
+        let host = match direct.member {
+            Member::Field(class, _) => class,
+            Member::Method(ref method) => method.class,
+        };
+        let handle_type = MethodDescriptor::parse(&direct.type_descriptor)?;
+        let mut code = String::new();
+        if direct.kind == RefKind::NewInvokeSpecial {
+            code.push_str(&format!("new {}\n", jasm::word(&direct.owner)));
+            code.push_str("dup\n");
+        }
+        for (i, param) in handle_type.params().iter().enumerate() {
+            code.push_str(&format!("aload 1\nldc Integer {}\naaload\n{}\n", i, spin::unbox_value(Some(param))));
+        }
+        code.push_str(&direct.instruction());
+        code.push('\n');
+        code.push_str(&spin::box_value(handle_type.return_type()));
+        let name = self.spun_name(&format!("{}$$Invoker", self.class(host).name));
+        let source = format!("
+.version 52 0
+.class final super {name}
+.super java/lang/Object
+.implements java/lang/invoke/DirectMethodHandle$Invoker
+
+.method public invoke ([Ljava/lang/Object;)Ljava/lang/Object;
+    .code stack {stack} locals 2
+        {code}
+        areturn
+    .end code
+.end method
+", name = jasm::word(&name), stack = handle_type.arg_slots() + 5, code = code);
+        let class = self.spin(host, &source)?;
+        let invoker = self.alloc_instance(class);
+        self.invokers.insert(key, invoker);
+        Ok(invoker)
+    }
+
+    // The type of the values of the class, None for void
+    pub(super) fn field_type_of(&self, class: ClassId) -> Result<Option<FieldType>> {
+        let class = self.class(class);
+        Ok(match class.kind {
+            ClassKind::Instance => Some(FieldType::Object(class.name.clone())),
+            ClassKind::Array(_) => Some(FieldType::parse(&class.name)?),
+            ClassKind::Primitive(ref field_type) => Some(field_type.clone()),
+            ClassKind::Void => None,
+        })
+    }
+
+    // A primitive value in a new object of its wrapper class, references stay as they are
+    pub(super) fn box_value(&mut self, value: Value, field_type: &FieldType) -> Flow<Value> {
+        match wrapper_of(field_type) {
+            Some(wrapper) => {
+                let class = self.load_class(wrapper)?;
+                let boxed = self.alloc_instance(class);
+                self.set_named_field(boxed, "value", value)?;
+                Ok(Value::Ref(boxed))
+            },
+            None => Ok(value),
+        }
+    }
+
+    // The primitive type and value of a wrapper object, None for other objects
+    pub(super) fn unbox_value(&self, object: ObjectRef) -> Result<Option<(FieldType, Value)>> {
+        let class_name = &self.class(self.heap.get(object).class()).name;
+        match WRAPPERS.iter().find(|&&(_, wrapper)| wrapper == class_name) {
+            Some((field_type, _)) => Ok(Some((field_type.clone(), self.named_field(object, "value")?))),
+            None => Ok(None),
+        }
+    }
+
+    // Wrapper.canConvert, whether asType can turn values of `from` into `to`: primitive widening,
+    // boxing, unboxing and reference casts. Void goes to and comes from anything
+    pub(super) fn can_convert(&mut self, from: ClassId, to: ClassId) -> Flow<bool> {
+        let (from_type, to_type) = (self.field_type_of(from)?, self.field_type_of(to)?);
+        Ok(match (from_type, to_type) {
+            _ if from == to => true,
+            (None, _) | (_, None) => true,
+            (Some(from_type), Some(to_type)) => match (wrapper_of(&from_type), wrapper_of(&to_type)) {
+                (Some(_), Some(_)) => widen(Value::zero(&from_type), &from_type, &to_type).is_some(),
+                (Some(wrapper), None) => {
+                    let wrapper = self.load_class(wrapper)?;
+                    self.is_assignable(wrapper, to)
+                },
+                (None, Some(wrapper)) => match unwrapped(&from_type) {
+                    Some(unboxed) => widen(Value::zero(unboxed), unboxed, &to_type).is_some(),
+                    None => {
+                        let wrapper = self.load_class(wrapper)?;
+                        self.is_assignable(wrapper, from)
+                    },
+                },
+                (None, None) => true,
+            },
+        })
+    }
+
+    // Wrapper.convert, `value` of `from`, boxed when primitive, as a `to`. Casts throw
+    // ClassCastException and unboxing null throws NullPointerException
+    pub(super) fn convert(&mut self, value: Value, from: ClassId, to: ClassId) -> Flow<Value> {
+        let to_type = match self.field_type_of(to)? {
+            Some(to_type) => to_type,
+            None => return Ok(Value::Null),
+        };
+        let is_primitive = wrapper_of(&to_type).is_some();
+        if self.field_type_of(from)?.is_none() {
+            return if is_primitive { self.box_value(Value::zero(&to_type), &to_type) } else { Ok(Value::Null) };
+        }
+        let object = match value.as_ref()? {
+            Some(object) => object,
+            None if is_primitive => return Err(self.exception("java/lang/NullPointerException", None)),
+            None => return Ok(Value::Null),
+        };
+        let class = self.heap.get(object).class();
+        if !is_primitive {
+            if self.is_assignable(class, to) {
+                return Ok(value);
+            }
+            return Err(self.cast_error(class, to));
+        }
+        // From a primitive type or a wrapper the value widens, from other references the
+        // wrapper has to match
+        let widens = match self.field_type_of(from)? {
+            Some(ref from_type) => wrapper_of(from_type).is_some() || unwrapped(from_type).is_some(),
+            None => false,
+        };
+        let converted = match self.unbox_value(object)? {
+            Some((field_type, unboxed)) if widens => widen(unboxed, &field_type, &to_type),
+            Some((field_type, unboxed)) if field_type == to_type => Some(unboxed),
+            _ => None,
+        };
+        match converted {
+            Some(converted) => self.box_value(converted, &to_type),
+            None => {
+                let wrapper = self.load_class(wrapper_of(&to_type).unwrap_or("java/lang/Object"))?;
+                Err(self.cast_error(class, wrapper))
+            },
+        }
+    }
+
+    pub(super) fn cast_error(&mut self, from: ClassId, to: ClassId) -> ::vm::Throw {
+        let message = format!("Cannot cast {} to {}", java_name(&self.class(from).name), java_name(&self.class(to).name));
+        self.exception("java/lang/ClassCastException", Some(message))
+    }
+}
+
+impl DirectHandle {
+    // The jasm instruction that uses the member, after `new` and `dup` for a constructor
+    pub(super) fn instruction(&self) -> String {
+        let instruction = match self.kind {
+            RefKind::GetField => "getfield",
+            RefKind::GetStatic => "getstatic",
+            RefKind::PutField => "putfield",
+            RefKind::PutStatic => "putstatic",
+            RefKind::InvokeVirtual => "invokevirtual",
+            RefKind::InvokeStatic => "invokestatic",
+            RefKind::InvokeSpecial | RefKind::NewInvokeSpecial => "invokespecial",
+            RefKind::InvokeInterface => "invokeinterface",
+        };
+        let reference = if self.kind.is_field() { "Field" } else if self.is_interface { "InterfaceMethod" } else { "Method" };
+        format!("{} {} {} {} {}", instruction, reference, jasm::word(&self.owner), jasm::word(&self.name), jasm::word(&self.descriptor))
+    }
+}
+
+// JLS §5.1.2 widening primitive conversion, or the value itself for the same type. None when
+// `from` doesn't widen to `to`
+fn widen(value: Value, from: &FieldType, to: &FieldType) -> Option<Value> {
+    if from == to {
+        return Some(value);
+    }
+    let rank = |field_type: &FieldType| match *field_type {
+        FieldType::Byte => Some(0),
+        FieldType::Short | FieldType::Char => Some(1),
+        FieldType::Int => Some(2),
+        FieldType::Long => Some(3),
+        FieldType::Float => Some(4),
+        FieldType::Double => Some(5),
+        _ => None,
+    };
+    match (rank(from), rank(to)) {
+        // char and short don't widen to each other, byte doesn't widen to char
+        (Some(from_rank), Some(to_rank)) if from_rank < to_rank && *to != FieldType::Char => (),
+        _ => return None,
+    }
+    Some(match (value, to) {
+        (Value::Int(value), &FieldType::Long) => Value::Long(value as i64),
+        (Value::Int(value), &FieldType::Float) => Value::Float(value as f32),
+        (Value::Long(value), &FieldType::Float) => Value::Float(value as f32),
+        (Value::Int(value), &FieldType::Double) => Value::Double(value as f64),
+        (Value::Long(value), &FieldType::Double) => Value::Double(value as f64),
+        (Value::Float(value), &FieldType::Double) => Value::Double(value as f64),
+        (value, _) => value,
+    })
+}
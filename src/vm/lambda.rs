@@ -0,0 +1,180 @@
+/*
+* The classes behind the bootstrap methods javac uses. LambdaMetafactory gets a class that
+* implements the functional interface, its fields hold the captured arguments and its interface
+* method calls the implementation. StringConcatFactory gets a static method that appends to a
+* StringBuilder. The call site's target is a direct handle of a static method of the spun class
+*/
+use class_file::constant_pool::{MemberRef, RefKind};
+use class_file::descriptor::{FieldType, MethodDescriptor};
+use jasm;
+use result::Error;
+use vm::class::{descriptor_of, ClassId};
+use vm::heap::ObjectRef;
+use vm::spin;
+use vm::{Flow, Vm};
+
+impl Vm {
+    // LambdaMetafactory.spinLambda, a static get$Lambda of the factory type that makes instances.
+    // The class has a method of the name for each descriptor in `methods`, the interface method
+    // first and then the bridges. A lambda that captures nothing has a single instance
+    pub(super) fn spin_lambda(&mut self, caller: ClassId, factory: &str, interfaces: &[ClassId], method_name: &str,
+        methods: &[String], implementation: ObjectRef) -> Flow<ObjectRef> {
+        let (kind, owner, instruction, implementation_type) = {
+            let direct = self.direct_handles.get(&implementation)
+                .ok_or_else(|| Error::Verify("DirectMethodHandle the VM didn't make".to_string()))?;
+            (direct.kind, jasm::word(&direct.owner), direct.instruction(), MethodDescriptor::parse(&direct.type_descriptor)?)
+        };
+        let factory_type = MethodDescriptor::parse(factory)?;
+        let captured = factory_type.params();
+        let name = self.spun_name(&format!("{}$$Lambda", self.class(caller).name));
+        let this = jasm::word(&name);
+        let mut source = format!(".version 52 0\n.class final super {}\n.super java/lang/Object\n", this);
+        for &interface in interfaces {
+            source.push_str(&format!(".implements {}\n", jasm::word(&self.class(interface).name)));
+        }
+        let mut fields = String::new();
+        let mut slot = 1;
+        for (i, field_type) in captured.iter().enumerate() {
+            let descriptor = jasm::word(&descriptor_of(field_type));
+            source.push_str(&format!(".field private final arg${} {}\n.end field\n", i + 1, descriptor));
+            fields.push_str(&format!("aload 0\n{}\nputfield Field {} arg${} {}\n", spin::load(field_type, slot), this, i + 1, descriptor));
+            slot += field_type.slots();
+        }
+        let end = factory.rfind(')').unwrap_or(0);
+        let constructor = jasm::word(&format!("({})V", &factory[1..end]));
+        source.push_str(&format!("
+.method private <init> {constructor}
+    .code stack 3 locals {locals}
+        aload 0
+        invokespecial Method java/lang/Object <init> ()V
+        {fields}
+        return
+    .end code
+.end method
+", constructor = constructor, locals = slot, fields = fields));
+        if captured.is_empty() {
+            source.push_str(&format!("
+.field private static final INSTANCE {instance}
+.end field
+
+.method static <clinit> ()V
+    .code stack 2 locals 0
+        new {this}
+        dup
+        invokespecial Method {this} <init> ()V
+        putstatic Field {this} INSTANCE {instance}
+        return
+    .end code
+.end method
+
+.method static get$Lambda {factory}
+    .code stack 1 locals 0
+        getstatic Field {this} INSTANCE {instance}
+        areturn
+    .end code
+.end method
+", this = this, instance = jasm::word(&format!("L{};", name)), factory = jasm::word(factory)));
+        } else {
+            let mut code = String::new();
+            let mut slot = 0;
+            for field_type in captured {
+                code.push_str(&spin::load(field_type, slot));
+                code.push('\n');
+                slot += field_type.slots();
+            }
+            source.push_str(&format!("
+.method static get$Lambda {factory}
+    .code stack {stack} locals {locals}
+        new {this}
+        dup
+        {code}
+        invokespecial Method {this} <init> {constructor}
+        areturn
+    .end code
+.end method
+", this = this, factory = jasm::word(factory), stack = slot + 2, locals = slot, code = code, constructor = constructor));
+        }
+        let implementation_params = implementation_type.params();
+        for descriptor in methods {
+            let method_type = MethodDescriptor::parse(descriptor)?;
+            let mut code = String::new();
+            if kind == RefKind::NewInvokeSpecial {
+                code.push_str(&format!("new {}\ndup\n", owner));
+            }
+            for (i, field_type) in captured.iter().enumerate() {
+                code.push_str(&format!("aload 0\ngetfield Field {} arg${} {}\n{}\n", this, i + 1, jasm::word(&descriptor_of(field_type)),
+                    spin::convert(Some(field_type), implementation_params.get(i))));
+            }
+            let mut slot = 1;
+            for (i, param) in method_type.params().iter().enumerate() {
+                code.push_str(&format!("{}\n{}\n", spin::load(param, slot),
+                    spin::convert(Some(param), implementation_params.get(captured.len() + i))));
+                slot += param.slots();
+            }
+            code.push_str(&instruction);
+            code.push('\n');
+            code.push_str(&spin::convert(implementation_type.return_type(), method_type.return_type()));
+            source.push_str(&format!("
+.method public {name} {descriptor}
+    .code stack {stack} locals {locals}
+        {code}
+        {return}
+    .end code
+.end method
+", name = jasm::word(method_name), descriptor = jasm::word(descriptor), stack = implementation_type.arg_slots() + 4, locals = slot,
+                code = code, return = spin::return_value(method_type.return_type())));
+        }
+        let class = self.spin(caller, &source)?;
+        self.direct_handle(class, RefKind::InvokeStatic, &MemberRef { class_name: &name, name: "get$Lambda", descriptor: factory })
+    }
+
+    // StringConcatFactory.spinConcat, a static concat of the concat type. literals[i] goes before
+    // argument i, the last one after the arguments
+    pub(super) fn spin_concat(&mut self, caller: ClassId, descriptor: &str, literals: &[String]) -> Flow<ObjectRef> {
+        let concat_type = MethodDescriptor::parse(descriptor)?;
+        let append = |code: &mut String, descriptor: &str| code.push_str(&format!(
+            "invokevirtual Method java/lang/StringBuilder append ({})Ljava/lang/StringBuilder;\n", descriptor));
+        let mut code = String::new();
+        let mut slot = 0;
+        for (i, literal) in literals.iter().enumerate() {
+            if !literal.is_empty() {
+                code.push_str(&format!("ldc String {}\n", jasm::quote(literal)));
+                append(&mut code, "Ljava/lang/String;");
+            }
+            if let Some(param) = concat_type.params().get(i) {
+                code.push_str(&spin::load(param, slot));
+                code.push('\n');
+                append(&mut code, match *param {
+                    FieldType::Boolean => "Z",
+                    FieldType::Char => "C",
+                    FieldType::Byte | FieldType::Short | FieldType::Int => "I",
+                    FieldType::Long => "J",
+                    FieldType::Float => "F",
+                    FieldType::Double => "D",
+                    FieldType::Object(ref class_name) if class_name == "java/lang/String" => "Ljava/lang/String;",
+                    _ => "Ljava/lang/Object;",
+                });
+                slot += param.slots();
+            }
+        }
+        let name = self.spun_name(&format!("{}$$StringConcat", self.class(caller).name));
+        let source = format!("
+.version 52 0
+.class final super {name}
+.super java/lang/Object
+
+.method static concat {descriptor}
+    .code stack 4 locals {locals}
+        new java/lang/StringBuilder
+        dup
+        invokespecial Method java/lang/StringBuilder <init> ()V
+        {code}
+        invokevirtual Method java/lang/StringBuilder toString ()Ljava/lang/String;
+        areturn
+    .end code
+.end method
+", name = jasm::word(&name), descriptor = jasm::word(descriptor), locals = slot, code = code);
+        let class = self.spin(caller, &source)?;
+        self.direct_handle(class, RefKind::InvokeStatic, &MemberRef { class_name: &name, name: "concat", descriptor })
+    }
+}
@@ -0,0 +1,587 @@
+/*
+* An interpreter for class files. The classes of java.lang and friends come from the mini runtime in
+* runtime/, Java sources compiled to class files that are bundled into the crate, so programs run
+* without a JDK, or from a JDK's own class library (see boot.rs). There is no garbage collector and
+* no verifier: objects live as long as the VM and malformed bytecode stops the VM with Error::Verify
+* instead of throwing VerifyError.
+*/
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::result;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::Instant;
+
+use class_path::{ClassPath, ClassSource};
+use class_file::descriptor::{java_name, FieldType};
+use result::{Result, Error};
+use types::u2;
+
+pub mod class;
+pub mod heap;
+pub mod natives;
+pub mod runtime;
+pub mod thread;
+pub mod value;
+mod atomic;
+mod boot;
+mod bootstrap;
+mod interpreter;
+mod invoke;
+mod lambda;
+mod modules;
+mod reflect;
+mod spin;
+
+pub use self::boot::MIN_JDK_VERSION;
+
+use self::class::{ClassId, ClassKind, Method, Resolved};
+use self::heap::{Array, Body, Heap, Lock, ObjectRef};
+use self::invoke::DirectHandle;
+use self::natives::{NativeMethod, Natives};
+use self::runtime::Runtime;
+use self::thread::{Frame, FrameKind, Thread, ThreadId, ThreadState};
+use self::value::Value;
+
+// Java frames one thread can have before StackOverflowError
+const MAX_FRAMES: usize = 4096;
+
+// Instructions a thread runs before the scheduler switches to the next one
+const TIME_SLICE: usize = 1000;
+
+// The threadStatus of a started thread that runs and of one that ended, JVMTI's ALIVE | RUNNABLE
+// and TERMINATED
+const THREAD_RUNNABLE: i32 = 0x5;
+const THREAD_TERMINATED: i32 = 0x2;
+
+// Why a method didn't run to the next instruction
+#[derive(Debug)]
+pub enum Throw {
+    // A java/lang/Throwable on its way to a handler
+    Exception(ObjectRef),
+    // The VM can't go on, like for a broken class file
+    Fatal(Error),
+}
+
+impl From<Error> for Throw {
+    fn from(err: Error) -> Self {
+        Throw::Fatal(err)
+    }
+}
+
+pub type Flow<T> = result::Result<T, Throw>;
+
+pub struct Vm {
+    class_path: ClassPath,
+    classes: Vec<class::Class>,
+    class_ids: HashMap<String, ClassId>,
+    // Classes whose superclasses are being loaded, to catch circularity
+    loading: HashSet<String>,
+    resolved: HashMap<(ClassId, u2), Resolved>,
+    // Receiver class and resolved method to the method that runs, see select_method
+    selected: HashMap<(ClassId, usize), Arc<Method>>,
+    mirrors: HashMap<ObjectRef, ClassId>,
+    interned: HashMap<Vec<u16>, ObjectRef>,
+    // java/lang/invoke/MethodType objects by descriptor
+    method_types: HashMap<String, ObjectRef>,
+    // The members of the DirectMethodHandles the VM made
+    direct_handles: HashMap<ObjectRef, DirectHandle>,
+    // Spun classes: adapters by call site descriptor and whether it is invokeExact, and
+    // DirectMethodHandle$Invoker objects by member
+    adapters: HashMap<(String, bool), Arc<Method>>,
+    invokers: HashMap<String, ObjectRef>,
+    spun: u32,
+    // The CallSite of each linked invokedynamic, by the address of the method and the pc
+    call_sites: HashMap<(usize, u32), ObjectRef>,
+    heap: Heap,
+    natives: Natives,
+    // The home of the JDK whose java.base runs instead of the mini runtime, booted before main
+    jdk: Option<PathBuf>,
+    // The modules a JDK's packages are in and the boot loader's unnamed module, see modules.rs
+    packages: HashMap<String, ObjectRef>,
+    unnamed_module: Option<ObjectRef>,
+    threads: Vec<Thread>,
+    current: ThreadId,
+    stdout: Box<dyn Write>,
+    stderr: Box<dyn Write>,
+    exit_status: Option<i32>,
+    // Thread.yield was called, the current thread's time slice ends
+    yielded: bool,
+    // The native that just ran needs a class initialized and runs again after that
+    rerun: bool,
+    started: Instant,
+}
+
+impl Vm {
+    // The mini runtime comes first, classes of the same name in `class_path` are never loaded
+    pub fn new(class_path: ClassPath) -> Self {
+        Vm::with_sources(Runtime, class_path, Natives::new(), None)
+    }
+
+    fn with_sources<S: ClassSource + 'static>(library: S, class_path: ClassPath, natives: Natives, jdk: Option<PathBuf>) -> Self {
+        let mut sources = ClassPath::new();
+        sources.push(library);
+        sources.push(class_path);
+        Vm {
+            class_path: sources,
+            classes: Vec::new(),
+            class_ids: HashMap::new(),
+            loading: HashSet::new(),
+            resolved: HashMap::new(),
+            selected: HashMap::new(),
+            mirrors: HashMap::new(),
+            interned: HashMap::new(),
+            method_types: HashMap::new(),
+            direct_handles: HashMap::new(),
+            adapters: HashMap::new(),
+            invokers: HashMap::new(),
+            spun: 0,
+            call_sites: HashMap::new(),
+            heap: Heap::new(),
+            natives,
+            jdk,
+            packages: HashMap::new(),
+            unnamed_module: None,
+            threads: Vec::new(),
+            current: ThreadId(0),
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
+            exit_status: None,
+            yielded: false,
+            rerun: false,
+            started: Instant::now(),
+        }
+    }
+
+    // Where System.out writes, the process's stdout by default
+    pub fn set_stdout<W: Write + 'static>(&mut self, stdout: W) {
+        self.stdout = Box::new(stdout);
+    }
+
+    pub fn set_stderr<W: Write + 'static>(&mut self, stderr: W) {
+        self.stderr = Box::new(stderr);
+    }
+
+    // Implements a native method of a user class, `class_name` in internal form like
+    // "com/example/Foo". A method is linked the first time it runs, so natives have to be
+    // registered before that or it throws UnsatisfiedLinkError
+    pub fn register_native(&mut self, class_name: &str, name: &str, descriptor: &str, method: NativeMethod) {
+        self.natives.register(class_name, name, descriptor, method);
+    }
+
+    pub fn heap(&self) -> &Heap { &self.heap }
+
+    pub fn heap_mut(&mut self) -> &mut Heap { &mut self.heap }
+
+    // Runs `public static void main(String[])` of the class until every thread that isn't a
+    // daemon has ended and returns the exit status: what System.exit was called with, 1 if main
+    // threw and 0 otherwise
+    pub fn run_main(&mut self, class_name: &str, args: &[String]) -> Result<i32> {
+        let class = match self.load_class(class_name) {
+            Ok(class) => class,
+            Err(Throw::Exception(_)) => return Err(Error::ClassNotFound(java_name(class_name))),
+            Err(Throw::Fatal(err)) => return Err(err),
+        };
+        let main = self.class(class).find_method("main", "([Ljava/lang/String;)V")
+            .filter(|method| method.flags().is_static())
+            .cloned()
+            .ok_or(Error::MainNotFound)?;
+        let main_thread = if self.jdk.is_some() {
+            self.boot()?
+        } else {
+            let main_thread = self.main_thread().map_err(|throw| self.fatal(throw))?;
+            self.start_thread(main_thread, None)?
+        };
+        let args = self.string_array(args).map_err(|throw| self.fatal(throw))?;
+        let thread = &mut self.threads[main_thread.index()];
+        thread.frames.push(Frame::new(main, &[Value::Ref(args)], FrameKind::Normal));
+        thread.state = ThreadState::Runnable;
+        self.current = main_thread;
+        while self.exit_status.is_none() && self.schedule()? {
+            self.run_slice()?;
+        }
+        let uncaught = self.threads[main_thread.index()].uncaught.take().is_some();
+        if self.jdk.is_some() && self.exit_status.is_none() {
+            self.shut_down(main_thread)?;
+        }
+        self.stdout.flush()?;
+        self.stderr.flush()?;
+        Ok(self.exit_status.unwrap_or(if uncaught { 1 } else { 0 }))
+    }
+
+    // The Thread object of the thread that runs main, made without running a constructor since
+    // the constructors ask for the current thread
+    fn main_thread(&mut self) -> Flow<ObjectRef> {
+        let class = self.load_class("java/lang/Thread")?;
+        let thread = self.alloc_instance(class);
+        let name = self.new_string("main")?;
+        self.set_named_field(thread, "name", Value::Ref(name))?;
+        self.set_named_field(thread, "priority", Value::Int(5))?;
+        self.set_named_field(thread, "tid", Value::Long(1))?;
+        Ok(thread)
+    }
+
+    // A new thread that runs `frame` once the scheduler gets to it, without a frame it has ended
+    // until one is pushed
+    pub(super) fn start_thread(&mut self, object: ObjectRef, frame: Option<Frame>) -> Result<ThreadId> {
+        let daemon = self.named_field(object, "daemon")?.as_int()? != 0;
+        let mut thread = Thread::new(object, daemon);
+        match frame {
+            Some(frame) => thread.frames.push(frame),
+            None => thread.state = ThreadState::Terminated,
+        }
+        self.threads.push(thread);
+        let id = ThreadId(self.threads.len() as u32 - 1);
+        self.set_named_field(object, "eetop", Value::Long(id.index() as i64 + 1))?;
+        self.set_thread_status(object, THREAD_RUNNABLE)?;
+        Ok(id)
+    }
+
+    // A JDK's Thread keeps its JVMTI state in threadStatus, which start() checks to only start it
+    // once and getState reads
+    pub(super) fn set_thread_status(&mut self, object: ObjectRef, status: i32) -> Result<()> {
+        if self.jdk.is_none() {
+            return Ok(());
+        }
+        self.set_named_field(object, "threadStatus", Value::Int(status))
+    }
+
+    // The VM's thread of a java/lang/Thread, None before it was started
+    pub(super) fn thread_of(&self, object: ObjectRef) -> Result<Option<ThreadId>> {
+        let eetop = self.named_field(object, "eetop")?.as_long()?;
+        Ok(if eetop == 0 { None } else { Some(ThreadId(eetop as u32 - 1)) })
+    }
+
+    // Makes the next runnable thread after the current one current, sleeping until one wakes up if
+    // none can run. false once every thread that isn't a daemon has ended
+    fn schedule(&mut self) -> Result<bool> {
+        loop {
+            let now = Instant::now();
+            let mut wake_up: Option<Instant> = None;
+            for i in 0..self.threads.len() {
+                let mut state = match self.threads[i].state {
+                    ThreadState::Sleeping(until) if until <= now => ThreadState::Runnable,
+                    ThreadState::Joining(thread, _) if !self.threads[thread.index()].is_alive() => ThreadState::Runnable,
+                    ThreadState::Joining(_, Some(until)) if until <= now => ThreadState::Runnable,
+                    ThreadState::Waiting(object, count, Some(until)) if until <= now => ThreadState::Entering(object, count),
+                    ThreadState::Parked(Some(until)) if until <= now => ThreadState::Runnable,
+                    state => state,
+                };
+                if let ThreadState::Entering(object, count) = state {
+                    let lock = &mut self.heap.get_mut(object).lock;
+                    if lock.owner.is_none() {
+                        *lock = Lock { owner: Some(ThreadId(i as u32)), count };
+                        state = ThreadState::Runnable;
+                    }
+                }
+                if let ThreadState::Sleeping(until) | ThreadState::Joining(_, Some(until)) | ThreadState::Waiting(_, _, Some(until))
+                    | ThreadState::Parked(Some(until)) = state {
+                    wake_up = Some(wake_up.map_or(until, |wake_up| wake_up.min(until)));
+                }
+                self.threads[i].state = state;
+            }
+            if !self.threads.iter().any(|thread| !thread.daemon && thread.is_alive()) {
+                return Ok(false);
+            }
+            let count = self.threads.len();
+            for offset in 1..=count {
+                let thread = (self.current.index() + offset) % count;
+                if self.threads[thread].state == ThreadState::Runnable {
+                    self.current = ThreadId(thread as u32);
+                    return Ok(true);
+                }
+            }
+            match wake_up {
+                Some(wake_up) => sleep(wake_up.saturating_duration_since(now)),
+                None => return Err(Error::Deadlock),
+            }
+        }
+    }
+
+    // Runs the current thread until its time slice is over or it stops being runnable
+    fn run_slice(&mut self) -> Result<()> {
+        self.yielded = false;
+        for _ in 0..TIME_SLICE {
+            if self.exit_status.is_some() || self.yielded || self.thread().state != ThreadState::Runnable {
+                break;
+            }
+            self.step()?;
+            if self.thread().frames.is_empty() {
+                self.end_thread()?;
+            }
+        }
+        Ok(())
+    }
+
+    // A JDK's Thread.exit runs first, like in HotSpot's JavaThread::exit. Then Thread.isAlive is
+    // false and the threads joining it, which wait on the Thread, are notified
+    fn end_thread(&mut self) -> Result<()> {
+        let object = self.thread().object;
+        if self.jdk.is_none() || self.thread().booting {
+            self.thread_mut().state = ThreadState::Terminated;
+            return Ok(());
+        }
+        if !self.thread().exiting {
+            self.thread_mut().exiting = true;
+            let class = self.heap.get(object).class();
+            if let Some(exit) = self.find_method(class, "exit", "()V").map_err(|throw| self.fatal(throw))? {
+                self.thread_mut().frames.push(Frame::new(exit, &[Value::Ref(object)], FrameKind::Detached));
+                return Ok(());
+            }
+        }
+        self.thread_mut().state = ThreadState::Terminated;
+        self.set_named_field(object, "eetop", Value::Long(0))?;
+        self.set_thread_status(object, THREAD_TERMINATED)?;
+        for thread in &mut self.threads {
+            if let ThreadState::Waiting(waited, count, _) = thread.state {
+                if waited == object {
+                    thread.state = ThreadState::Entering(waited, count);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn string_array(&mut self, strings: &[String]) -> Flow<ObjectRef> {
+        let class = self.load_class("[Ljava/lang/String;")?;
+        let array = self.alloc_array(class, strings.len())?;
+        for (i, string) in strings.iter().enumerate() {
+            let string = self.new_string(string)?;
+            self.array_mut(array)?.set(i, Value::Ref(string));
+        }
+        Ok(array)
+    }
+
+    pub(super) fn thread(&self) -> &Thread {
+        &self.threads[self.current.index()]
+    }
+
+    pub(super) fn thread_mut(&mut self) -> &mut Thread {
+        &mut self.threads[self.current.index()]
+    }
+
+    // An exception the VM can't hand to Java code, while starting up
+    fn fatal(&self, throw: Throw) -> Error {
+        match throw {
+            Throw::Exception(exception) => {
+                let class = self.heap.get(exception).class();
+                Error::Uncaught(java_name(&self.class(class).name))
+            },
+            Throw::Fatal(err) => err,
+        }
+    }
+
+    pub fn alloc_instance(&mut self, class: ClassId) -> ObjectRef {
+        let fields = self.class(class).instance_fields.iter().map(Value::zero).collect();
+        self.heap.alloc(class, Body::Fields(fields))
+    }
+
+    // `class` is the array class, the elements are zeroed
+    pub fn alloc_array(&mut self, class: ClassId, length: usize) -> Flow<ObjectRef> {
+        let component = match self.class(class).kind {
+            ClassKind::Array(component) => component,
+            _ => return Err(Error::Verify(format!("{} is not an array class", self.class(class).name)).into()),
+        };
+        let array = match self.class(component).kind {
+            ClassKind::Primitive(ref field_type) => match *field_type {
+                FieldType::Boolean |
+                FieldType::Byte => Array::Byte(vec![0; length]),
+                FieldType::Char => Array::Char(vec![0; length]),
+                FieldType::Short => Array::Short(vec![0; length]),
+                FieldType::Int => Array::Int(vec![0; length]),
+                FieldType::Long => Array::Long(vec![0; length]),
+                FieldType::Float => Array::Float(vec![0.0; length]),
+                FieldType::Double => Array::Double(vec![0.0; length]),
+                _ => Array::Reference(vec![Value::Null; length]),
+            },
+            _ => Array::Reference(vec![Value::Null; length]),
+        };
+        Ok(self.heap.alloc(class, Body::Array(array)))
+    }
+
+    pub fn array(&self, reference: ObjectRef) -> Result<&Array> {
+        match *self.heap.get(reference).body() {
+            Body::Array(ref array) => Ok(array),
+            Body::Fields(_) => Err(Error::Verify("expected an array".to_string())),
+        }
+    }
+
+    pub fn array_mut(&mut self, reference: ObjectRef) -> Result<&mut Array> {
+        match *self.heap.get_mut(reference).body_mut() {
+            Body::Array(ref mut array) => Ok(array),
+            Body::Fields(_) => Err(Error::Verify("expected an array".to_string())),
+        }
+    }
+
+    pub fn field(&self, object: ObjectRef, slot: usize) -> Result<Value> {
+        match *self.heap.get(object).body() {
+            Body::Fields(ref fields) if slot < fields.len() => Ok(fields[slot]),
+            _ => Err(Error::Verify(format!("no field slot {}", slot))),
+        }
+    }
+
+    pub fn set_field(&mut self, object: ObjectRef, slot: usize, value: Value) -> Result<()> {
+        match *self.heap.get_mut(object).body_mut() {
+            Body::Fields(ref mut fields) if slot < fields.len() => {
+                fields[slot] = value;
+                Ok(())
+            },
+            _ => Err(Error::Verify(format!("no field slot {}", slot))),
+        }
+    }
+
+    // Instance fields by name, for natives that read and write the runtime classes' fields
+    pub fn named_field(&self, object: ObjectRef, name: &str) -> Result<Value> {
+        let slot = self.field_slot(self.heap.get(object).class(), name)?;
+        self.field(object, slot)
+    }
+
+    pub fn set_named_field(&mut self, object: ObjectRef, name: &str, value: Value) -> Result<()> {
+        let slot = self.field_slot(self.heap.get(object).class(), name)?;
+        self.set_field(object, slot, value)
+    }
+
+    pub fn new_string(&mut self, string: &str) -> Flow<ObjectRef> {
+        self.new_string_utf16(string.encode_utf16().collect())
+    }
+
+    // The mini runtime's strings are a char[]. The JDK's are a byte[] with a coder, LATIN1 (0)
+    // for one byte per char when every char fits and UTF16 (1) for two in the byte order of
+    // StringUTF16.isBigEndian, little endian here
+    pub fn new_string_utf16(&mut self, chars: Vec<u16>) -> Flow<ObjectRef> {
+        let string_class = self.load_class("java/lang/String")?;
+        let string = self.alloc_instance(string_class);
+        let value = if self.jdk.is_some() {
+            let byte_array = self.load_class("[B")?;
+            let latin1 = chars.iter().all(|&c| c < 0x100);
+            let bytes = if latin1 {
+                chars.iter().map(|&c| c as i8).collect()
+            } else {
+                chars.iter().flat_map(|&c| c.to_le_bytes()).map(|b| b as i8).collect()
+            };
+            self.set_named_field(string, "coder", Value::Int(!latin1 as i32))?;
+            self.heap.alloc(byte_array, Body::Array(Array::Byte(bytes)))
+        } else {
+            let char_array = self.load_class("[C")?;
+            self.heap.alloc(char_array, Body::Array(Array::Char(chars)))
+        };
+        self.set_named_field(string, "value", Value::Ref(value))?;
+        Ok(string)
+    }
+
+    // The UTF-16 code units of a java/lang/String
+    pub fn string_chars(&self, string: ObjectRef) -> Result<Vec<u16>> {
+        let value = self.named_field(string, "value")?.as_ref()?
+            .ok_or_else(|| Error::Verify("String.value is null".to_string()))?;
+        match *self.array(value)? {
+            Array::Char(ref chars) => Ok(chars.clone()),
+            Array::Byte(ref bytes) if self.named_field(string, "coder")?.as_int()? == 0 =>
+                Ok(bytes.iter().map(|&b| b as u8 as u16).collect()),
+            Array::Byte(ref bytes) => Ok(bytes.chunks(2).map(|pair| u16::from_le_bytes([pair[0] as u8, pair[1] as u8])).collect()),
+            _ => Err(Error::Verify("String.value is not a char[] or byte[]".to_string())),
+        }
+    }
+
+    // Lone surrogates become U+FFFD
+    pub fn rust_string(&self, string: ObjectRef) -> Result<String> {
+        Ok(String::from_utf16_lossy(&self.string_chars(string)?))
+    }
+
+    pub fn intern(&mut self, string: &str) -> Flow<ObjectRef> {
+        self.intern_utf16(string.encode_utf16().collect())
+    }
+
+    pub(super) fn intern_utf16(&mut self, chars: Vec<u16>) -> Flow<ObjectRef> {
+        if let Some(&string) = self.interned.get(&chars) {
+            return Ok(string);
+        }
+        let string = self.new_string_utf16(chars.clone())?;
+        self.interned.insert(chars, string);
+        Ok(string)
+    }
+
+    // A new exception of the class as the VM throws them: detailMessage is set directly and no
+    // constructor runs, so the runtime's exception classes have no other state
+    pub fn exception(&mut self, class_name: &str, message: Option<String>) -> Throw {
+        match self.new_exception(class_name, message) {
+            Ok(exception) => Throw::Exception(exception),
+            Err(throw) => throw,
+        }
+    }
+
+    fn new_exception(&mut self, class_name: &str, message: Option<String>) -> Flow<ObjectRef> {
+        let class = self.load_class(class_name)?;
+        let exception = self.alloc_instance(class);
+        if let Some(message) = message {
+            let message = self.new_string(&message)?;
+            self.set_named_field(exception, "detailMessage", Value::Ref(message))?;
+        }
+        if self.jdk.is_some() {
+            self.set_named_field(exception, "cause", Value::Ref(exception))?;
+        }
+        self.fill_in_stack_trace(exception)?;
+        Ok(exception)
+    }
+
+    // Throwable.stackTrace from the frames of the current thread. The frames of fillInStackTrace,
+    // the constructors of the exception and spun classes are left out, like the JDK does
+    pub(super) fn fill_in_stack_trace(&mut self, exception: ObjectRef) -> Flow<()> {
+        let throwable = self.load_class("java/lang/Throwable")?;
+        let frames = self.threads.get(self.current.index()).map_or(0, |thread| thread.frames.len());
+        let mut skip = 0;
+        while skip < frames {
+            let method = &self.thread().frames[frames - 1 - skip].method;
+            let hidden = method.name() == "<init>" || method.name() == "fillInStackTrace";
+            if !hidden || !self.is_assignable(method.class(), throwable) {
+                break;
+            }
+            skip += 1;
+        }
+        let mut elements = Vec::new();
+        for i in (0..frames - skip).rev() {
+            let (class, method_name, line) = {
+                let frame = &self.thread().frames[i];
+                if self.class(frame.method.class()).host.is_some() {
+                    continue;
+                }
+                let line = match frame.line_number() {
+                    _ if frame.method.flags().is_native() => -2,
+                    Some(line) => line as i32,
+                    None => -1,
+                };
+                (frame.method.class(), frame.method.name().to_string(), line)
+            };
+            let element_class = self.load_class("java/lang/StackTraceElement")?;
+            let element = self.alloc_instance(element_class);
+            let declaring_class = java_name(&self.class(class).name);
+            let declaring_class = self.new_string(&declaring_class)?;
+            let method_name = self.new_string(&method_name)?;
+            let file_name = match self.class(class).source_file.clone() {
+                Some(file_name) => Value::Ref(self.new_string(&file_name)?),
+                None => Value::Null,
+            };
+            self.set_named_field(element, "declaringClass", Value::Ref(declaring_class))?;
+            self.set_named_field(element, "methodName", Value::Ref(method_name))?;
+            self.set_named_field(element, "fileName", file_name)?;
+            self.set_named_field(element, "lineNumber", Value::Int(line))?;
+            if self.jdk.is_some() {
+                let mirror = self.mirror(class)?;
+                self.set_named_field(element, "declaringClassObject", Value::Ref(mirror))?;
+            }
+            elements.push(Value::Ref(element));
+        }
+        let depth = elements.len();
+        let array_class = self.load_class("[Ljava/lang/StackTraceElement;")?;
+        let array = self.heap.alloc(array_class, Body::Array(Array::Reference(elements)));
+        // The JDK's Throwable makes its stackTrace out of the backtrace once it is asked for it,
+        // see StackTraceElement.initStackTraceElements
+        if self.jdk.is_some() {
+            self.set_named_field(exception, "backtrace", Value::Ref(array))?;
+            self.set_named_field(exception, "depth", Value::Int(depth as i32))?;
+        } else {
+            self.set_named_field(exception, "stackTrace", Value::Ref(array))?;
+        }
+        Ok(())
+    }
+}
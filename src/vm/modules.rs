@@ -0,0 +1,54 @@
+/*
+* The modules of a JDK's class library. ModuleBootstrap defines them with their packages, the VM
+* keeps the module of each package to give every Class its module. Reads and exports are left to
+* the JDK's own Java code, the VM checks no access between modules
+*/
+use vm::class::{package, ClassId, ClassKind};
+use vm::heap::ObjectRef;
+use vm::value::Value;
+use vm::{Flow, Vm};
+
+impl Vm {
+    // Module.defineModule0, the packages are like "java.lang"
+    pub(super) fn define_module(&mut self, module: ObjectRef, packages: &[String]) -> Flow<()> {
+        for name in packages {
+            self.packages.insert(name.replace('.', "/"), module);
+        }
+        self.fix_up_modules()
+    }
+
+    // The unnamed module of the boot loader, for the classes of packages no module has
+    pub(super) fn set_unnamed_module(&mut self, module: ObjectRef) -> Flow<()> {
+        self.unnamed_module = Some(module);
+        self.fix_up_modules()
+    }
+
+    // None until java.base is defined, primitive types and arrays of them are in java.base
+    pub(super) fn module_of(&self, class: ClassId) -> Option<ObjectRef> {
+        if !self.packages.contains_key("java/lang") {
+            return None;
+        }
+        match *self.class(class).kind() {
+            ClassKind::Array(component) => self.module_of(component),
+            ClassKind::Primitive(_) | ClassKind::Void => self.packages.get("java/lang").cloned(),
+            ClassKind::Instance => {
+                let class = self.class(class).host.unwrap_or(class);
+                self.packages.get(package(&self.class(class).name)).cloned().or(self.unnamed_module)
+            },
+        }
+    }
+
+    // Classes get a module once it is defined, like HotSpot's fixup list for the classes loaded
+    // before java.base
+    fn fix_up_modules(&mut self) -> Flow<()> {
+        let mirrors: Vec<(ObjectRef, ClassId)> = self.mirrors.iter().map(|(&mirror, &class)| (mirror, class)).collect();
+        for (mirror, class) in mirrors {
+            if self.named_field(mirror, "module")?.as_ref()?.is_none() {
+                if let Some(module) = self.module_of(class) {
+                    self.set_named_field(mirror, "module", Value::Ref(module))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
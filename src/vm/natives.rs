@@ -0,0 +1,1642 @@
+use std::char;
+use std::collections::HashMap;
+use std::env;
+use std::mem;
+use std::io::{self, Read, Write};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use class_file::constant_pool::RefKind;
+use class_file::descriptor::java_name;
+use result::{Result, Error};
+use vm::atomic::Location;
+use vm::class::ClassKind;
+use vm::heap::{Array, Body, Lock, ObjectRef};
+use vm::thread::{Frame, FrameKind, ThreadState};
+use vm::value::Value;
+use vm::{Flow, Throw, Vm};
+
+// A native method gets its arguments the way they were on the operand stack, `this` first for
+// instance methods and longs and doubles as one value. None for void methods
+pub type NativeMethod = fn(&mut Vm, &[Value]) -> Flow<Option<Value>>;
+
+// Native methods by "class.name(descriptor)", like "java/lang/Object.hashCode()I"
+pub struct Natives {
+    methods: HashMap<String, NativeMethod>,
+}
+
+impl Natives {
+    // The natives of the mini runtime
+    pub fn new() -> Self {
+        let mut natives = Natives { methods: HashMap::new() };
+        natives.extend(RUNTIME);
+        natives
+    }
+
+    // The natives of a JDK's java.base, those the runtime shares with it included
+    pub fn jdk() -> Self {
+        let mut natives = Natives::new();
+        natives.extend(JDK);
+        natives
+    }
+
+    fn extend(&mut self, table: &[(&str, NativeMethod)]) {
+        for &(key, method) in table {
+            self.methods.insert(key.to_string(), method);
+        }
+    }
+
+    // Replaces a native of the same class, name and descriptor, the runtime's included
+    pub fn register(&mut self, class_name: &str, name: &str, descriptor: &str, method: NativeMethod) {
+        self.methods.insert(format!("{}.{}{}", class_name, name, descriptor), method);
+    }
+
+    pub fn get(&self, class_name: &str, name: &str, descriptor: &str) -> Option<NativeMethod> {
+        self.methods.get(&format!("{}.{}{}", class_name, name, descriptor)).cloned()
+    }
+}
+
+impl Default for Natives {
+    fn default() -> Self {
+        Natives::new()
+    }
+}
+
+const RUNTIME: &[(&str, NativeMethod)] = &[
+    ("java/lang/Object.getClass()Ljava/lang/Class;", object_get_class),
+    ("java/lang/Object.hashCode()I", identity_hash_code),
+    ("java/lang/Object.clone()Ljava/lang/Object;", object_clone),
+    ("java/lang/Object.wait0(J)V", object_wait),
+    ("java/lang/Object.notify()V", object_notify),
+    ("java/lang/Object.notifyAll()V", object_notify_all),
+    ("java/lang/Class.getName0()Ljava/lang/String;", class_get_name),
+    ("java/lang/Class.isInstance(Ljava/lang/Object;)Z", class_is_instance),
+    ("java/lang/Class.isAssignableFrom(Ljava/lang/Class;)Z", class_is_assignable_from),
+    ("java/lang/Class.isInterface()Z", class_is_interface),
+    ("java/lang/Class.isArray()Z", class_is_array),
+    ("java/lang/Class.isPrimitive()Z", class_is_primitive),
+    ("java/lang/Class.getSuperclass()Ljava/lang/Class;", class_get_superclass),
+    ("java/lang/Class.getComponentType()Ljava/lang/Class;", class_get_component_type),
+    ("java/lang/Class.getEnumConstants0()[Ljava/lang/Object;", class_get_enum_constants),
+    ("java/lang/Class.getPrimitiveClass(Ljava/lang/String;)Ljava/lang/Class;", class_get_primitive_class),
+    ("java/lang/Class.getInterfaces()[Ljava/lang/Class;", class_get_interfaces),
+    ("java/lang/Class.getModifiers()I", class_get_modifiers),
+    ("java/lang/Class.getDeclaredFields0(Z)[Ljava/lang/reflect/Field;", class_get_declared_fields),
+    ("java/lang/Class.getDeclaredMethods0(Z)[Ljava/lang/reflect/Method;", |vm, args| class_get_declared_methods(vm, args, false)),
+    ("java/lang/Class.getDeclaredConstructors0(Z)[Ljava/lang/reflect/Constructor;", |vm, args| class_get_declared_methods(vm, args, true)),
+    ("java/lang/Throwable.fillInStackTrace()Ljava/lang/Throwable;", throwable_fill_in_stack_trace),
+    ("java/lang/String.decodeUtf8([B)[C", string_decode_utf8),
+    ("java/lang/String.encodeUtf8([C)[B", string_encode_utf8),
+    ("java/lang/String.intern()Ljava/lang/String;", string_intern),
+    ("java/lang/System.currentTimeMillis()J", system_current_time_millis),
+    ("java/lang/System.nanoTime()J", system_nano_time),
+    ("java/lang/System.arraycopy(Ljava/lang/Object;ILjava/lang/Object;II)V", system_arraycopy),
+    ("java/lang/System.identityHashCode(Ljava/lang/Object;)I", identity_hash_code),
+    ("java/lang/System.exit(I)V", system_exit),
+    ("java/lang/System$StandardStream.write0(I[BII)V", standard_stream_write),
+    ("java/lang/System$StandardStream.flush0(I)V", standard_stream_flush),
+    ("java/lang/Thread.currentThread()Ljava/lang/Thread;", thread_current_thread),
+    ("java/lang/Thread.yield()V", thread_yield),
+    ("java/lang/Thread.sleep0(J)V", thread_sleep),
+    ("java/lang/Thread.start0()V", thread_start),
+    ("java/lang/Thread.isAlive()Z", thread_is_alive),
+    ("java/lang/Thread.join0(J)V", thread_join),
+    ("java/lang/Thread.interrupt0()V", thread_interrupt),
+    ("java/lang/Thread.holdsLock(Ljava/lang/Object;)Z", thread_holds_lock),
+    ("java/lang/Character.isDigit0(I)Z", character_is_digit),
+    ("java/lang/Character.isLetter0(I)Z", character_is_letter),
+    ("java/lang/Character.isLowerCase0(I)Z", character_is_lower_case),
+    ("java/lang/Character.isUpperCase0(I)Z", character_is_upper_case),
+    ("java/lang/Character.isWhitespace0(I)Z", character_is_whitespace),
+    ("java/lang/Character.toLowerCase0(I)I", character_to_lower_case),
+    ("java/lang/Character.toUpperCase0(I)I", character_to_upper_case),
+    ("java/lang/Float.parseFloat(Ljava/lang/String;)F", float_parse),
+    ("java/lang/Float.toString(F)Ljava/lang/String;", float_to_string),
+    ("java/lang/Float.floatToIntBits(F)I", float_to_int_bits),
+    ("java/lang/Float.floatToRawIntBits(F)I", float_to_raw_int_bits),
+    ("java/lang/Float.intBitsToFloat(I)F", int_bits_to_float),
+    ("java/lang/Double.parseDouble(Ljava/lang/String;)D", double_parse),
+    ("java/lang/Double.toString(D)Ljava/lang/String;", double_to_string),
+    ("java/lang/Double.doubleToLongBits(D)J", double_to_long_bits),
+    ("java/lang/Double.doubleToRawLongBits(D)J", double_to_raw_long_bits),
+    ("java/lang/Double.longBitsToDouble(J)D", long_bits_to_double),
+    ("java/lang/Math.sin(D)D", |_, args| math(args, f64::sin)),
+    ("java/lang/Math.cos(D)D", |_, args| math(args, f64::cos)),
+    ("java/lang/Math.tan(D)D", |_, args| math(args, f64::tan)),
+    ("java/lang/Math.asin(D)D", |_, args| math(args, f64::asin)),
+    ("java/lang/Math.acos(D)D", |_, args| math(args, f64::acos)),
+    ("java/lang/Math.atan(D)D", |_, args| math(args, f64::atan)),
+    ("java/lang/Math.atan2(DD)D", |_, args| math2(args, f64::atan2)),
+    ("java/lang/Math.sinh(D)D", |_, args| math(args, f64::sinh)),
+    ("java/lang/Math.cosh(D)D", |_, args| math(args, f64::cosh)),
+    ("java/lang/Math.tanh(D)D", |_, args| math(args, f64::tanh)),
+    ("java/lang/Math.exp(D)D", |_, args| math(args, f64::exp)),
+    ("java/lang/Math.log(D)D", |_, args| math(args, f64::ln)),
+    ("java/lang/Math.log10(D)D", |_, args| math(args, f64::log10)),
+    ("java/lang/Math.sqrt(D)D", |_, args| math(args, f64::sqrt)),
+    ("java/lang/Math.cbrt(D)D", |_, args| math(args, f64::cbrt)),
+    ("java/lang/Math.pow(DD)D", |_, args| math2(args, f64::powf)),
+    ("java/lang/Math.hypot(DD)D", |_, args| math2(args, f64::hypot)),
+    ("java/lang/Math.floor(D)D", |_, args| math(args, f64::floor)),
+    ("java/lang/Math.ceil(D)D", |_, args| math(args, f64::ceil)),
+    ("java/lang/Math.rint(D)D", |_, args| math(args, f64::round_ties_even)),
+    ("java/lang/invoke/MethodHandle.invokeExact([Ljava/lang/Object;)Ljava/lang/Object;", method_handle_invoke),
+    ("java/lang/invoke/MethodHandle.invoke([Ljava/lang/Object;)Ljava/lang/Object;", method_handle_invoke),
+    ("java/lang/invoke/DirectMethodHandle.makeInvoker()Ljava/lang/invoke/DirectMethodHandle$Invoker;", direct_method_handle_make_invoker),
+    ("java/lang/invoke/MethodHandleNatives.resolve(Ljava/lang/Class;IILjava/lang/Class;Ljava/lang/String;Ljava/lang/Object;)Ljava/lang/invoke/MethodHandle;",
+        method_handle_natives_resolve),
+    ("java/lang/invoke/MethodHandles.lookup()Ljava/lang/invoke/MethodHandles$Lookup;", method_handles_lookup),
+    ("java/lang/invoke/LambdaMetafactory.spinLambda(Ljava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodHandle;[Ljava/lang/Class;[Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/MethodHandle;",
+        lambda_metafactory_spin_lambda),
+    ("java/lang/invoke/StringConcatFactory.spinConcat(Ljava/lang/Class;Ljava/lang/invoke/MethodType;[Ljava/lang/String;)Ljava/lang/invoke/MethodHandle;",
+        string_concat_factory_spin_concat),
+    ("java/lang/invoke/Wrapper.canConvert(Ljava/lang/Class;Ljava/lang/Class;)Z", wrapper_can_convert),
+    ("java/lang/invoke/Wrapper.convert(Ljava/lang/Object;Ljava/lang/Class;Ljava/lang/Class;)Ljava/lang/Object;", wrapper_convert),
+    ("java/lang/reflect/Array.newArray(Ljava/lang/Class;I)Ljava/lang/Object;", array_new_array),
+    ("java/lang/reflect/Array.getLength(Ljava/lang/Object;)I", array_get_length),
+    ("java/lang/reflect/Array.get(Ljava/lang/Object;I)Ljava/lang/Object;", array_get),
+    ("java/lang/reflect/Array.set(Ljava/lang/Object;ILjava/lang/Object;)V", array_set),
+    ("java/lang/reflect/Field.setFinal(Ljava/lang/Object;Ljava/lang/Object;)V", field_set_final),
+    ("jdk/internal/reflect/Reflection.getCallerClass()Ljava/lang/Class;", reflection_get_caller_class),
+    ("jdk/internal/reflect/Reflection.verifyMemberAccess(Ljava/lang/Class;Ljava/lang/Class;I)Z", reflection_verify_member_access),
+    ("jdk/internal/misc/Unsafe.objectFieldOffset1(Ljava/lang/Class;Ljava/lang/String;)J", unsafe_object_field_offset),
+    ("jdk/internal/misc/Unsafe.getIntVolatile(Ljava/lang/Object;J)I", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getLongVolatile(Ljava/lang/Object;J)J", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getReferenceVolatile(Ljava/lang/Object;J)Ljava/lang/Object;", unsafe_get),
+    ("jdk/internal/misc/Unsafe.putIntVolatile(Ljava/lang/Object;JI)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putLongVolatile(Ljava/lang/Object;JJ)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putReferenceVolatile(Ljava/lang/Object;JLjava/lang/Object;)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.compareAndSetInt(Ljava/lang/Object;JII)Z", unsafe_compare_and_set),
+    ("jdk/internal/misc/Unsafe.compareAndSetLong(Ljava/lang/Object;JJJ)Z", unsafe_compare_and_set),
+    ("jdk/internal/misc/Unsafe.compareAndSetReference(Ljava/lang/Object;JLjava/lang/Object;Ljava/lang/Object;)Z", unsafe_compare_and_set),
+    ("jdk/internal/misc/Unsafe.park(ZJ)V", unsafe_park),
+    ("jdk/internal/misc/Unsafe.unpark(Ljava/lang/Object;)V", unsafe_unpark),
+    ("jdk/internal/misc/Unsafe.fullFence()V", fence),
+    ("jdk/internal/misc/Unsafe.loadFence()V", fence),
+    ("jdk/internal/misc/Unsafe.storeFence()V", fence),
+    ("java/lang/invoke/VarHandle.fullFence()V", fence),
+    ("java/lang/invoke/VarHandle.acquireFence()V", fence),
+    ("java/lang/invoke/VarHandle.releaseFence()V", fence),
+    ("java/lang/invoke/VarHandle.loadLoadFence()V", fence),
+    ("java/lang/invoke/VarHandle.storeStoreFence()V", fence),
+    ("java/util/Formatter.fixed(DI)Ljava/lang/String;", formatter_fixed),
+    ("java/util/Formatter.scientific(DI)Ljava/lang/String;", formatter_scientific),
+];
+
+// What java.base of JDK 17 needs on top of the runtime's
+const JDK: &[(&str, NativeMethod)] = &[
+    ("java/lang/Class.registerNatives()V", nothing),
+    ("java/lang/System.registerNatives()V", nothing),
+    ("java/lang/Thread.registerNatives()V", nothing),
+    ("jdk/internal/misc/Unsafe.registerNatives()V", nothing),
+    ("jdk/internal/misc/ScopedMemoryAccess.registerNatives()V", nothing),
+    ("java/lang/ClassLoader.registerNatives()V", nothing),
+    ("java/lang/Object.wait(J)V", object_wait_interruptibly),
+    ("java/lang/Class.initClassName()Ljava/lang/String;", class_init_class_name),
+    ("java/lang/Class.getInterfaces0()[Ljava/lang/Class;", class_get_interfaces),
+    ("java/lang/Class.desiredAssertionStatus0(Ljava/lang/Class;)Z", |_, _| boolean(false)),
+    ("java/lang/Class.isHidden()Z", class_is_hidden),
+    ("java/lang/Class.forName0(Ljava/lang/String;ZLjava/lang/ClassLoader;Ljava/lang/Class;)Ljava/lang/Class;", class_for_name),
+    ("java/lang/Class.getDeclaringClass0()Ljava/lang/Class;", class_get_declaring_class),
+    ("java/lang/Class.getSimpleBinaryName0()Ljava/lang/String;", class_get_simple_binary_name),
+    ("java/lang/Class.getEnclosingMethod0()[Ljava/lang/Object;", class_get_enclosing_method),
+    ("java/lang/Throwable.fillInStackTrace(I)Ljava/lang/Throwable;", throwable_fill_in_stack_trace),
+    ("java/lang/StackTraceElement.initStackTraceElements([Ljava/lang/StackTraceElement;Ljava/lang/Throwable;)V", stack_trace_element_init),
+    ("java/lang/System.setIn0(Ljava/io/InputStream;)V", |vm, args| set_system_stream(vm, args, "in")),
+    ("java/lang/System.setOut0(Ljava/io/PrintStream;)V", |vm, args| set_system_stream(vm, args, "out")),
+    ("java/lang/System.setErr0(Ljava/io/PrintStream;)V", |vm, args| set_system_stream(vm, args, "err")),
+    ("java/lang/Thread.sleep(J)V", thread_sleep_interruptibly),
+    ("java/lang/Shutdown.beforeHalt()V", nothing),
+    ("java/lang/Shutdown.halt0(I)V", system_exit),
+    ("java/lang/Thread.setPriority0(I)V", nothing),
+    ("java/lang/Thread.clearInterruptEvent()V", nothing),
+    ("java/lang/Thread.setNativeName(Ljava/lang/String;)V", nothing),
+    ("java/lang/Runtime.availableProcessors()I", |_, _| Ok(Some(Value::Int(1)))),
+    // The heap has no limit and nothing is ever collected
+    ("java/lang/Runtime.maxMemory()J", |_, _| Ok(Some(Value::Long(i64::MAX)))),
+    ("java/lang/Runtime.totalMemory()J", |_, _| Ok(Some(Value::Long(i64::MAX)))),
+    ("java/lang/Runtime.freeMemory()J", |_, _| Ok(Some(Value::Long(i64::MAX)))),
+    ("java/lang/Runtime.gc()V", nothing),
+    ("java/lang/StringUTF16.isBigEndian()Z", |_, _| boolean(false)),
+    ("java/lang/StrictMath.sin(D)D", |_, args| math(args, f64::sin)),
+    ("java/lang/StrictMath.cos(D)D", |_, args| math(args, f64::cos)),
+    ("java/lang/StrictMath.tan(D)D", |_, args| math(args, f64::tan)),
+    ("java/lang/StrictMath.asin(D)D", |_, args| math(args, f64::asin)),
+    ("java/lang/StrictMath.acos(D)D", |_, args| math(args, f64::acos)),
+    ("java/lang/StrictMath.atan(D)D", |_, args| math(args, f64::atan)),
+    ("java/lang/StrictMath.atan2(DD)D", |_, args| math2(args, f64::atan2)),
+    ("java/lang/StrictMath.sinh(D)D", |_, args| math(args, f64::sinh)),
+    ("java/lang/StrictMath.cosh(D)D", |_, args| math(args, f64::cosh)),
+    ("java/lang/StrictMath.tanh(D)D", |_, args| math(args, f64::tanh)),
+    ("java/lang/StrictMath.log(D)D", |_, args| math(args, f64::ln)),
+    ("java/lang/StrictMath.log10(D)D", |_, args| math(args, f64::log10)),
+    ("java/lang/StrictMath.sqrt(D)D", |_, args| math(args, f64::sqrt)),
+    ("java/lang/StrictMath.expm1(D)D", |_, args| math(args, f64::exp_m1)),
+    ("java/lang/StrictMath.log1p(D)D", |_, args| math(args, f64::ln_1p)),
+    ("java/lang/StrictMath.IEEEremainder(DD)D", |_, args| math2(args, ieee_remainder)),
+    ("java/lang/System.mapLibraryName(Ljava/lang/String;)Ljava/lang/String;", system_map_library_name),
+    ("jdk/internal/misc/VM.initialize()V", nothing),
+    ("jdk/internal/misc/CDS.isDumpingClassList0()Z", |_, _| boolean(false)),
+    ("jdk/internal/misc/CDS.isDumpingArchive0()Z", |_, _| boolean(false)),
+    ("jdk/internal/misc/CDS.isSharingEnabled0()Z", |_, _| boolean(false)),
+    ("jdk/internal/misc/CDS.getRandomSeedForDumping()J", |_, _| Ok(Some(Value::Long(0)))),
+    ("jdk/internal/misc/CDS.initializeFromArchive(Ljava/lang/Class;)V", nothing),
+    ("jdk/internal/misc/Unsafe.arrayBaseOffset0(Ljava/lang/Class;)I", |_, _| Ok(Some(Value::Int(0)))),
+    ("jdk/internal/misc/Unsafe.arrayIndexScale0(Ljava/lang/Class;)I", |_, _| Ok(Some(Value::Int(1)))),
+    ("jdk/internal/misc/Unsafe.getInt(Ljava/lang/Object;J)I", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getLong(Ljava/lang/Object;J)J", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getReference(Ljava/lang/Object;J)Ljava/lang/Object;", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getBoolean(Ljava/lang/Object;J)Z", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getByte(Ljava/lang/Object;J)B", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getShort(Ljava/lang/Object;J)S", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getChar(Ljava/lang/Object;J)C", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getFloat(Ljava/lang/Object;J)F", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getDouble(Ljava/lang/Object;J)D", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getBooleanVolatile(Ljava/lang/Object;J)Z", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getByteVolatile(Ljava/lang/Object;J)B", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getShortVolatile(Ljava/lang/Object;J)S", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getCharVolatile(Ljava/lang/Object;J)C", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getFloatVolatile(Ljava/lang/Object;J)F", unsafe_get),
+    ("jdk/internal/misc/Unsafe.getDoubleVolatile(Ljava/lang/Object;J)D", unsafe_get),
+    ("jdk/internal/misc/Unsafe.putInt(Ljava/lang/Object;JI)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putLong(Ljava/lang/Object;JJ)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putReference(Ljava/lang/Object;JLjava/lang/Object;)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putBoolean(Ljava/lang/Object;JZ)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putByte(Ljava/lang/Object;JB)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putShort(Ljava/lang/Object;JS)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putChar(Ljava/lang/Object;JC)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putFloat(Ljava/lang/Object;JF)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putDouble(Ljava/lang/Object;JD)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putBooleanVolatile(Ljava/lang/Object;JZ)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putByteVolatile(Ljava/lang/Object;JB)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putShortVolatile(Ljava/lang/Object;JS)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putCharVolatile(Ljava/lang/Object;JC)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putFloatVolatile(Ljava/lang/Object;JF)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.putDoubleVolatile(Ljava/lang/Object;JD)V", unsafe_put),
+    ("jdk/internal/misc/Unsafe.compareAndExchangeInt(Ljava/lang/Object;JII)I", unsafe_compare_and_exchange),
+    ("jdk/internal/misc/Unsafe.compareAndExchangeLong(Ljava/lang/Object;JJJ)J", unsafe_compare_and_exchange),
+    ("jdk/internal/misc/Unsafe.compareAndExchangeReference(Ljava/lang/Object;JLjava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+        unsafe_compare_and_exchange),
+    ("jdk/internal/misc/Unsafe.shouldBeInitialized0(Ljava/lang/Class;)Z", unsafe_should_be_initialized),
+    ("jdk/internal/misc/Unsafe.ensureClassInitialized0(Ljava/lang/Class;)V", unsafe_ensure_class_initialized),
+    ("jdk/internal/reflect/Reflection.getClassAccessFlags(Ljava/lang/Class;)I", reflection_get_class_access_flags),
+    // Signals are never raised, the handlers the JDK installs stay unused
+    ("jdk/internal/misc/Signal.findSignal0(Ljava/lang/String;)I", signal_find_signal),
+    ("jdk/internal/misc/Signal.handle0(IJ)J", |_, _| Ok(Some(Value::Long(0)))),
+    // Nothing is ever collected, no reference gets cleared or enqueued by the VM
+    ("java/lang/ref/Reference.refersTo0(Ljava/lang/Object;)Z", reference_refers_to),
+    ("java/lang/ref/PhantomReference.refersTo0(Ljava/lang/Object;)Z", reference_refers_to),
+    ("java/lang/ref/Reference.clear0()V", reference_clear),
+    ("java/lang/ref/Reference.getAndClearReferencePendingList()Ljava/lang/ref/Reference;", |_, _| Ok(Some(Value::Null))),
+    ("java/lang/ref/Reference.hasReferencePendingList()Z", |_, _| boolean(false)),
+    ("java/lang/ref/Reference.waitForReferencePendingList()V", reference_wait_for_pending_list),
+    ("java/lang/Module.defineModule0(Ljava/lang/Module;ZLjava/lang/String;Ljava/lang/String;[Ljava/lang/Object;)V", module_define),
+    ("java/lang/Module.addReads0(Ljava/lang/Module;Ljava/lang/Module;)V", nothing),
+    ("java/lang/Module.addExports0(Ljava/lang/Module;Ljava/lang/String;Ljava/lang/Module;)V", nothing),
+    ("java/lang/Module.addExportsToAll0(Ljava/lang/Module;Ljava/lang/String;)V", nothing),
+    ("java/lang/Module.addExportsToAllUnnamed0(Ljava/lang/Module;Ljava/lang/String;)V", nothing),
+    ("jdk/internal/loader/BootLoader.setBootLoaderUnnamedModule0(Ljava/lang/Module;)V", boot_loader_set_unnamed_module),
+    ("java/io/UnixFileSystem.initIDs()V", nothing),
+    ("java/io/UnixFileSystem.getBooleanAttributes0(Ljava/io/File;)I", file_system_get_boolean_attributes),
+    ("java/io/UnixFileSystem.canonicalize0(Ljava/lang/String;)Ljava/lang/String;", file_system_canonicalize),
+    ("java/io/FileDescriptor.initIDs()V", nothing),
+    ("java/io/FileDescriptor.getHandle(I)J", |_, _| Ok(Some(Value::Long(-1)))),
+    ("java/io/FileDescriptor.getAppend(I)Z", |_, _| boolean(false)),
+    ("java/io/FileInputStream.initIDs()V", nothing),
+    ("java/io/FileInputStream.readBytes([BII)I", file_input_stream_read_bytes),
+    ("java/io/FileInputStream.available0()I", |_, _| Ok(Some(Value::Int(0)))),
+    ("java/io/FileOutputStream.initIDs()V", nothing),
+    ("java/io/FileOutputStream.writeBytes([BIIZ)V", file_output_stream_write_bytes),
+    ("jdk/internal/util/SystemProps$Raw.platformProperties()[Ljava/lang/String;", system_props_platform_properties),
+    ("jdk/internal/util/SystemProps$Raw.vmProperties()[Ljava/lang/String;", system_props_vm_properties),
+    ("java/security/AccessController.getStackAccessControlContext()Ljava/security/AccessControlContext;", |_, _| Ok(Some(Value::Null))),
+    ("java/security/AccessController.getInheritedAccessControlContext()Ljava/security/AccessControlContext;", |_, _| Ok(Some(Value::Null))),
+    ("java/security/AccessController.getProtectionDomain(Ljava/lang/Class;)Ljava/security/ProtectionDomain;", |_, _| Ok(Some(Value::Null))),
+    ("java/security/AccessController.ensureMaterializedForStackWalk(Ljava/lang/Object;)V", nothing),
+];
+
+fn arg(args: &[Value], index: usize) -> Result<Value> {
+    args.get(index).cloned().ok_or_else(|| Error::Verify(format!("missing native argument {}", index)))
+}
+
+// `this` and other arguments that can't be null
+fn non_null(vm: &mut Vm, args: &[Value], index: usize) -> Flow<ObjectRef> {
+    match arg(args, index)?.as_ref()? {
+        Some(reference) => Ok(reference),
+        None => Err(vm.exception("java/lang/NullPointerException", None)),
+    }
+}
+
+fn string_arg(vm: &mut Vm, args: &[Value], index: usize) -> Flow<String> {
+    let string = non_null(vm, args, index)?;
+    Ok(vm.rust_string(string)?)
+}
+
+fn new_string(vm: &mut Vm, string: &str) -> Flow<Option<Value>> {
+    Ok(Some(Value::Ref(vm.new_string(string)?)))
+}
+
+fn boolean(value: bool) -> Flow<Option<Value>> {
+    Ok(Some(Value::Int(value as i32)))
+}
+
+fn object_get_class(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let class = vm.heap.get(this).class();
+    Ok(Some(Value::Ref(vm.mirror(class)?)))
+}
+
+fn identity_hash_code(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Int(arg(args, 0)?.as_ref()?.map_or(0, ObjectRef::identity_hash))))
+}
+
+// Shallow, arrays are always cloneable
+fn object_clone(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let class = vm.heap.get(this).class();
+    let body = match *vm.heap.get(this).body() {
+        Body::Array(ref array) => Body::Array(array.clone()),
+        Body::Fields(ref fields) => Body::Fields(fields.clone()),
+    };
+    if let Body::Fields(_) = body {
+        if !vm.is_subclass_of(class, "java/lang/Cloneable")? {
+            let message = java_name(vm.class(class).name());
+            return Err(vm.exception("java/lang/CloneNotSupportedException", Some(message)));
+        }
+    }
+    Ok(Some(Value::Ref(vm.heap.alloc(class, body))))
+}
+
+// wait and notify need the monitor of `this`
+fn owned_monitor(vm: &mut Vm, args: &[Value]) -> Flow<ObjectRef> {
+    let this = non_null(vm, args, 0)?;
+    if !vm.holds_lock(this) {
+        return Err(vm.exception("java/lang/IllegalMonitorStateException", Some("current thread is not owner".to_string())));
+    }
+    Ok(this)
+}
+
+// Leaves the monitor for as long as the thread waits, unless it was interrupted already
+fn object_wait(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = owned_monitor(vm, args)?;
+    let millis = arg(args, 1)?.as_long()?;
+    if !interrupted(vm)? {
+        let count = vm.heap.get(this).lock.count;
+        vm.heap.get_mut(this).lock = Lock::default();
+        let until = if millis == 0 { None } else { Some(deadline(millis)) };
+        vm.thread_mut().state = ThreadState::Waiting(this, count, until);
+    }
+    Ok(None)
+}
+
+// Wakes the first waiting thread in the order threads were started, the JDK promises no order
+fn object_notify(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    notify(vm, args, false)
+}
+
+fn object_notify_all(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    notify(vm, args, true)
+}
+
+fn notify(vm: &mut Vm, args: &[Value], all: bool) -> Flow<Option<Value>> {
+    let this = owned_monitor(vm, args)?;
+    for thread in &mut vm.threads {
+        if let ThreadState::Waiting(object, count, _) = thread.state {
+            if object == this {
+                thread.state = ThreadState::Entering(object, count);
+                if !all {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn this_class(vm: &mut Vm, args: &[Value]) -> Flow<::vm::class::ClassId> {
+    class_arg(vm, args, 0)
+}
+
+fn class_arg(vm: &mut Vm, args: &[Value], index: usize) -> Flow<::vm::class::ClassId> {
+    let mirror = non_null(vm, args, index)?;
+    Ok(vm.class_of_mirror(mirror).ok_or_else(|| Error::Verify("Class object without a class".to_string()))?)
+}
+
+fn class_value(vm: &mut Vm, class: Option<::vm::class::ClassId>) -> Flow<Option<Value>> {
+    match class {
+        Some(class) => Ok(Some(Value::Ref(vm.mirror(class)?))),
+        None => Ok(Some(Value::Null)),
+    }
+}
+
+fn class_get_name(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let name = java_name(vm.class(class).name());
+    let name = vm.intern(&name)?;
+    Ok(Some(Value::Ref(name)))
+}
+
+fn class_is_instance(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    match arg(args, 1)?.as_ref()? {
+        Some(object) => {
+            let object_class = vm.heap.get(object).class();
+            boolean(vm.is_assignable(object_class, class))
+        },
+        None => boolean(false),
+    }
+}
+
+fn class_is_assignable_from(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let other = non_null(vm, args, 1)?;
+    let other = vm.class_of_mirror(other).ok_or_else(|| Error::Verify("Class object without a class".to_string()))?;
+    boolean(vm.is_assignable(other, class))
+}
+
+fn class_is_interface(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    boolean(vm.class(class).kind() == &ClassKind::Instance && vm.class(class).is_interface())
+}
+
+fn class_is_array(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    boolean(matches!(*vm.class(class).kind(), ClassKind::Array(_)))
+}
+
+fn class_is_primitive(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    boolean(matches!(*vm.class(class).kind(), ClassKind::Primitive(_) | ClassKind::Void))
+}
+
+// Null for interfaces and primitive types
+fn class_get_superclass(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let super_class = match vm.class(class).is_interface() && vm.class(class).kind() == &ClassKind::Instance {
+        true => None,
+        false => vm.class(class).super_class(),
+    };
+    class_value(vm, super_class)
+}
+
+fn class_get_component_type(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let component = match *vm.class(class).kind() {
+        ClassKind::Array(component) => Some(component),
+        _ => None,
+    };
+    class_value(vm, component)
+}
+
+// The enum's class is initialized by then, Enum.valueOf and values() run in it
+fn class_get_enum_constants(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    if !vm.class(class).flags().is_enum() {
+        return Ok(Some(Value::Null));
+    }
+    let constants: Vec<Value> = vm.class(class).fields().iter()
+        .filter(|field| field.flags().is_enum() && field.flags().is_static())
+        .map(|field| vm.class(class).statics[field.slot])
+        .collect();
+    let array_class = vm.load_class("[Ljava/lang/Object;")?;
+    Ok(Some(Value::Ref(vm.heap.alloc(array_class, Body::Array(Array::Reference(constants))))))
+}
+
+fn class_get_primitive_class(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let name = string_arg(vm, args, 0)?;
+    let class = vm.load_class(&name)?;
+    class_value(vm, Some(class))
+}
+
+fn class_get_interfaces(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    Ok(Some(Value::Ref(vm.interface_mirrors(class)?)))
+}
+
+fn class_get_modifiers(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    Ok(Some(Value::Int(vm.class_modifiers(class)?)))
+}
+
+fn class_get_declared_fields(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let public_only = arg(args, 1)?.as_int()? != 0;
+    Ok(Some(Value::Ref(vm.declared_fields(class, public_only)?)))
+}
+
+fn class_get_declared_methods(vm: &mut Vm, args: &[Value], constructors: bool) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let public_only = arg(args, 1)?.as_int()? != 0;
+    Ok(Some(Value::Ref(vm.declared_methods(class, public_only, constructors)?)))
+}
+
+fn throwable_fill_in_stack_trace(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    vm.fill_in_stack_trace(this)?;
+    Ok(Some(Value::Ref(this)))
+}
+
+// Malformed input becomes U+FFFD
+fn string_decode_utf8(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let bytes = non_null(vm, args, 0)?;
+    let chars: Vec<u16> = match *vm.array(bytes)? {
+        Array::Byte(ref bytes) => {
+            let bytes: Vec<u8> = bytes.iter().map(|&b| b as u8).collect();
+            String::from_utf8_lossy(&bytes).encode_utf16().collect()
+        },
+        _ => return Err(Error::Verify("decodeUtf8 of a non-byte array".to_string()).into()),
+    };
+    let class = vm.load_class("[C")?;
+    Ok(Some(Value::Ref(vm.heap.alloc(class, Body::Array(Array::Char(chars))))))
+}
+
+// Lone surrogates become '?', like String.getBytes
+fn string_encode_utf8(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let chars = non_null(vm, args, 0)?;
+    let string: String = match *vm.array(chars)? {
+        Array::Char(ref chars) => char::decode_utf16(chars.iter().cloned()).map(|c| c.unwrap_or('?')).collect(),
+        _ => return Err(Error::Verify("encodeUtf8 of a non-char array".to_string()).into()),
+    };
+    let bytes = string.bytes().map(|b| b as i8).collect();
+    let class = vm.load_class("[B")?;
+    Ok(Some(Value::Ref(vm.heap.alloc(class, Body::Array(Array::Byte(bytes))))))
+}
+
+// The string itself goes into the pool when no equal string is there yet
+fn string_intern(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let chars = vm.string_chars(this)?;
+    let interned = *vm.interned.entry(chars).or_insert(this);
+    Ok(Some(Value::Ref(interned)))
+}
+
+fn system_current_time_millis(_: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as i64);
+    Ok(Some(Value::Long(millis)))
+}
+
+fn system_nano_time(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Long(vm.started.elapsed().as_nanos() as i64)))
+}
+
+fn system_arraycopy(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let src = non_null(vm, args, 0)?;
+    let src_pos = arg(args, 1)?.as_int()?;
+    let dest = non_null(vm, args, 2)?;
+    let dest_pos = arg(args, 3)?.as_int()?;
+    let length = arg(args, 4)?.as_int()?;
+    let (src_class, dest_class) = (vm.heap.get(src).class(), vm.heap.get(dest).class());
+    let (src_component, dest_component) = match (vm.class(src_class).kind().clone(), vm.class(dest_class).kind().clone()) {
+        (ClassKind::Array(src_component), ClassKind::Array(dest_component)) => (src_component, dest_component),
+        (ClassKind::Array(_), _) => {
+            let message = format!("arraycopy: destination type {} is not an array", java_name(vm.class(dest_class).name()));
+            return Err(vm.exception("java/lang/ArrayStoreException", Some(message)));
+        },
+        _ => {
+            let message = format!("arraycopy: source type {} is not an array", java_name(vm.class(src_class).name()));
+            return Err(vm.exception("java/lang/ArrayStoreException", Some(message)));
+        },
+    };
+    let primitive = |vm: &Vm, class| matches!(*vm.class(class).kind(), ClassKind::Primitive(_));
+    if (primitive(vm, src_component) || primitive(vm, dest_component)) && src_component != dest_component {
+        let message = format!("arraycopy: type mismatch: can not copy {}[] into {}[]",
+            java_name(vm.class(src_component).name()), java_name(vm.class(dest_component).name()));
+        return Err(vm.exception("java/lang/ArrayStoreException", Some(message)));
+    }
+    let (src_length, dest_length) = (vm.array(src)?.len() as i64, vm.array(dest)?.len() as i64);
+    let message = if src_pos < 0 {
+        Some(format!("arraycopy: source index {} out of bounds for length {}", src_pos, src_length))
+    } else if dest_pos < 0 {
+        Some(format!("arraycopy: destination index {} out of bounds for length {}", dest_pos, dest_length))
+    } else if length < 0 {
+        Some(format!("arraycopy: length {} is negative", length))
+    } else if src_pos as i64 + length as i64 > src_length {
+        Some(format!("arraycopy: last source index {} out of bounds for length {}", src_pos as i64 + length as i64, src_length))
+    } else if dest_pos as i64 + length as i64 > dest_length {
+        Some(format!("arraycopy: last destination index {} out of bounds for length {}", dest_pos as i64 + length as i64, dest_length))
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        return Err(vm.exception("java/lang/ArrayIndexOutOfBoundsException", Some(message)));
+    }
+    let (src_pos, dest_pos, length) = (src_pos as usize, dest_pos as usize, length as usize);
+    let mut elements = vm.array(src)?.slice(src_pos, length);
+    // Elements are stored up to the first one the destination can't hold
+    let mut stored = length;
+    if !vm.is_assignable(src_component, dest_component) {
+        if let Array::Reference(ref references) = elements {
+            stored = references.iter()
+                .position(|element| match *element {
+                    Value::Ref(object) => !vm.is_assignable(vm.heap.get(object).class(), dest_component),
+                    _ => false,
+                })
+                .unwrap_or(length);
+        }
+        elements = elements.slice(0, stored);
+    }
+    vm.array_mut(dest)?.copy_from(dest_pos, &elements, 0, stored);
+    if stored < length {
+        let element = vm.array(src)?.get(src_pos + stored).and_then(|element| element.as_ref().ok()).and_then(|element| element);
+        let element_class = element.map_or(String::new(), |element| java_name(vm.class(vm.heap.get(element).class()).name()));
+        let message = format!("arraycopy: element type mismatch: can not cast one of the elements of {} to the type of the destination array, {}",
+            element_class, java_name(vm.class(dest_component).name()));
+        return Err(vm.exception("java/lang/ArrayStoreException", Some(message)));
+    }
+    Ok(None)
+}
+
+// Stops the VM once the native returns
+fn system_exit(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    vm.exit_status = Some(arg(args, 0)?.as_int()?);
+    Ok(None)
+}
+
+fn standard_stream(vm: &mut Vm, fd: i32) -> &mut dyn Write {
+    match fd {
+        2 => &mut *vm.stderr,
+        _ => &mut *vm.stdout,
+    }
+}
+
+fn standard_stream_write(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let fd = arg(args, 0)?.as_int()?;
+    let bytes = non_null(vm, args, 1)?;
+    let (off, len) = (arg(args, 2)?.as_int()? as usize, arg(args, 3)?.as_int()? as usize);
+    let bytes: Vec<u8> = match *vm.array(bytes)? {
+        Array::Byte(ref bytes) => bytes[off..off + len].iter().map(|&b| b as u8).collect(),
+        _ => return Err(Error::Verify("write0 of a non-byte array".to_string()).into()),
+    };
+    standard_stream(vm, fd).write_all(&bytes).map_err(Error::from)?;
+    Ok(None)
+}
+
+fn standard_stream_flush(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let fd = arg(args, 0)?.as_int()?;
+    standard_stream(vm, fd).flush().map_err(Error::from)?;
+    Ok(None)
+}
+
+fn thread_current_thread(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Ref(vm.thread().object)))
+}
+
+fn thread_yield(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    vm.yielded = true;
+    Ok(None)
+}
+
+// The Java code after the blocking natives checks the flag once the thread runs again
+fn interrupted(vm: &Vm) -> Result<bool> {
+    Ok(vm.named_field(vm.thread().object, "interrupted")?.as_int()? != 0)
+}
+
+// Long.MAX_VALUE millis is past what an Instant holds, a year is as good as forever
+fn deadline(millis: i64) -> Instant {
+    Instant::now() + Duration::from_millis(millis.min(365 * 24 * 60 * 60 * 1000) as u64)
+}
+
+fn thread_sleep(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let millis = arg(args, 0)?.as_long()?;
+    if !interrupted(vm)? {
+        vm.thread_mut().state = ThreadState::Sleeping(deadline(millis));
+    }
+    Ok(None)
+}
+
+// The new thread runs the run() of `this`, its uncaught exceptions go to the thread's handler
+fn thread_start(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let class = vm.heap.get(this).class();
+    let run = vm.find_method(class, "run", "()V")?
+        .ok_or_else(|| Error::Verify("thread without run()".to_string()))?;
+    vm.start_thread(this, Some(Frame::new(run, &[Value::Ref(this)], FrameKind::Normal)))?;
+    Ok(None)
+}
+
+fn thread_is_alive(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let alive = vm.thread_of(this)?.is_some_and(|thread| vm.threads[thread.index()].is_alive());
+    Ok(Some(Value::Int(alive as i32)))
+}
+
+// A timeout of 0 waits for as long as the thread runs
+fn thread_join(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let millis = arg(args, 1)?.as_long()?;
+    if let Some(thread) = vm.thread_of(this)? {
+        if vm.threads[thread.index()].is_alive() && !interrupted(vm)? {
+            let until = if millis == 0 { None } else { Some(deadline(millis)) };
+            vm.thread_mut().state = ThreadState::Joining(thread, until);
+        }
+    }
+    Ok(None)
+}
+
+fn thread_interrupt(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    if let Some(thread) = vm.thread_of(this)? {
+        let thread = &mut vm.threads[thread.index()];
+        thread.state = match thread.state {
+            ThreadState::Sleeping(_) | ThreadState::Joining(..) | ThreadState::Parked(_) => ThreadState::Runnable,
+            ThreadState::Waiting(object, count, _) => ThreadState::Entering(object, count),
+            state => state,
+        };
+    }
+    Ok(None)
+}
+
+fn thread_holds_lock(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let object = non_null(vm, args, 0)?;
+    Ok(Some(Value::Int(vm.holds_lock(object) as i32)))
+}
+
+fn code_point(args: &[Value]) -> Result<Option<char>> {
+    Ok(char::from_u32(arg(args, 0)?.as_int()? as u32))
+}
+
+fn character_is_digit(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    boolean(code_point(args)?.is_some_and(char::is_numeric))
+}
+
+fn character_is_letter(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    boolean(code_point(args)?.is_some_and(char::is_alphabetic))
+}
+
+fn character_is_lower_case(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    boolean(code_point(args)?.is_some_and(char::is_lowercase))
+}
+
+fn character_is_upper_case(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    boolean(code_point(args)?.is_some_and(char::is_uppercase))
+}
+
+// No-break spaces aren't whitespace to Java
+fn character_is_whitespace(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    boolean(code_point(args)?.is_some_and(|c| c.is_whitespace() && !matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')))
+}
+
+// Mappings to more than one char leave the code point as it is
+fn map_case<I: Iterator<Item = char>>(args: &[Value], map: fn(char) -> I) -> Flow<Option<Value>> {
+    let code_point = arg(args, 0)?.as_int()?;
+    let mapped = char::from_u32(code_point as u32).and_then(|c| {
+        let mut mapped = map(c);
+        match (mapped.next(), mapped.next()) {
+            (Some(single), None) => Some(single as i32),
+            _ => None,
+        }
+    });
+    Ok(Some(Value::Int(mapped.unwrap_or(code_point))))
+}
+
+fn character_to_lower_case(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    map_case(args, char::to_lowercase)
+}
+
+fn character_to_upper_case(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    map_case(args, char::to_uppercase)
+}
+
+// Double.parseDouble's grammar without hexadecimal floats: surrounding whitespace and a d or f
+// suffix are allowed, Rust's "inf" and "nan" spellings aren't
+fn parse_floating(vm: &mut Vm, args: &[Value]) -> Flow<f64> {
+    let string = string_arg(vm, args, 0)?;
+    let trimmed = string.trim_matches(|c: char| c <= ' ');
+    let number = trimmed.strip_suffix(|c| matches!(c, 'd' | 'D' | 'f' | 'F')).unwrap_or(trimmed);
+    let unsigned = number.strip_prefix(|c| c == '+' || c == '-').unwrap_or(number);
+    let parsed = if unsigned == "Infinity" || unsigned == "NaN" {
+        number.replace("Infinity", "inf").parse::<f64>().ok()
+    } else if unsigned.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) {
+        number.parse::<f64>().ok()
+    } else {
+        None
+    };
+    match parsed {
+        Some(value) => Ok(value),
+        None if trimmed.is_empty() => Err(vm.exception("java/lang/NumberFormatException", Some("empty String".to_string()))),
+        _ => Err(vm.exception("java/lang/NumberFormatException", Some(format!("For input string: \"{}\"", string)))),
+    }
+}
+
+fn float_parse(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Float(parse_floating(vm, args)? as f32)))
+}
+
+fn double_parse(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Double(parse_floating(vm, args)?)))
+}
+
+// Double.toString: shortest digits, plain between 10^-3 and 10^7 and computerized scientific
+// notation outside
+fn java_floating<T: Copy + Into<f64> + ::std::fmt::Display + ::std::fmt::LowerExp>(value: T) -> String {
+    let magnitude = value.into().abs();
+    if magnitude.is_nan() {
+        return "NaN".to_string();
+    }
+    if magnitude.is_infinite() {
+        return if value.into() > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if magnitude == 0.0 || (1e-3..1e7).contains(&magnitude) {
+        let plain = value.to_string();
+        return if plain.contains('.') { plain } else { plain + ".0" };
+    }
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap_or(scientific.len()));
+    let point = if mantissa.contains('.') { "" } else { ".0" };
+    format!("{}{}E{}", mantissa, point, &exponent[1..])
+}
+
+fn float_to_string(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    new_string(vm, &java_floating(arg(args, 0)?.as_float()?))
+}
+
+fn double_to_string(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    new_string(vm, &java_floating(arg(args, 0)?.as_double()?))
+}
+
+fn float_to_int_bits(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let value = arg(args, 0)?.as_float()?;
+    Ok(Some(Value::Int(if value.is_nan() { 0x7fc0_0000 } else { value.to_bits() as i32 })))
+}
+
+fn float_to_raw_int_bits(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Int(arg(args, 0)?.as_float()?.to_bits() as i32)))
+}
+
+fn int_bits_to_float(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Float(f32::from_bits(arg(args, 0)?.as_int()? as u32))))
+}
+
+fn double_to_long_bits(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let value = arg(args, 0)?.as_double()?;
+    Ok(Some(Value::Long(if value.is_nan() { 0x7ff8_0000_0000_0000 } else { value.to_bits() as i64 })))
+}
+
+fn double_to_raw_long_bits(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Long(arg(args, 0)?.as_double()?.to_bits() as i64)))
+}
+
+fn long_bits_to_double(_: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    Ok(Some(Value::Double(f64::from_bits(arg(args, 0)?.as_long()? as u64))))
+}
+
+fn math(args: &[Value], function: fn(f64) -> f64) -> Flow<Option<Value>> {
+    Ok(Some(Value::Double(function(arg(args, 0)?.as_double()?))))
+}
+
+fn math2(args: &[Value], function: fn(f64, f64) -> f64) -> Flow<Option<Value>> {
+    Ok(Some(Value::Double(function(arg(args, 0)?.as_double()?, arg(args, 1)?.as_double()?))))
+}
+
+// Calls of invokeExact and invoke are linked to the handle, only reflection gets here
+fn method_handle_invoke(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    Err(vm.exception("java/lang/UnsupportedOperationException", Some("cannot reflectively invoke MethodHandle".to_string())))
+}
+
+fn direct_method_handle_make_invoker(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    Ok(Some(Value::Ref(vm.invoker(this)?)))
+}
+
+fn method_handle_natives_resolve(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let caller = class_arg(vm, args, 0)?;
+    let modes = arg(args, 1)?.as_int()?;
+    let kind = RefKind::read(arg(args, 2)?.as_int()? as u8)?;
+    let refc = class_arg(vm, args, 3)?;
+    let name = string_arg(vm, args, 4)?;
+    let method_type = non_null(vm, args, 5)?;
+    let descriptor = vm.type_descriptor(method_type)?;
+    Ok(Some(Value::Ref(vm.resolve_handle(caller, modes, kind, refc, &name, &descriptor)?)))
+}
+
+fn method_handles_lookup(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    let frames = &vm.thread().frames;
+    let caller = frames[frames.len() - 2].method.class();
+    Ok(Some(Value::Ref(vm.lookup(caller)?)))
+}
+
+// The elements of an Object[] argument
+fn references_arg(vm: &mut Vm, args: &[Value], index: usize) -> Flow<Vec<ObjectRef>> {
+    let array = non_null(vm, args, index)?;
+    let elements = match *vm.array(array)? {
+        Array::Reference(ref elements) => elements.clone(),
+        _ => return Err(Error::Verify("expected an array of references".to_string()).into()),
+    };
+    let mut references = Vec::with_capacity(elements.len());
+    for element in elements {
+        match element.as_ref()? {
+            Some(reference) => references.push(reference),
+            None => return Err(vm.exception("java/lang/NullPointerException", None)),
+        }
+    }
+    Ok(references)
+}
+
+fn lambda_metafactory_spin_lambda(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let caller = class_arg(vm, args, 0)?;
+    let name = string_arg(vm, args, 1)?;
+    let factory_type = non_null(vm, args, 2)?;
+    let factory = vm.type_descriptor(factory_type)?;
+    let interface_type = non_null(vm, args, 3)?;
+    let mut methods = vec![vm.type_descriptor(interface_type)?];
+    let implementation = non_null(vm, args, 4)?;
+    let mut interfaces = Vec::new();
+    for mirror in references_arg(vm, args, 5)? {
+        interfaces.push(vm.class_of_mirror(mirror).ok_or_else(|| Error::Verify("interface without a class".to_string()))?);
+    }
+    for bridge in references_arg(vm, args, 6)? {
+        let bridge = vm.type_descriptor(bridge)?;
+        if !methods.contains(&bridge) {
+            methods.push(bridge);
+        }
+    }
+    Ok(Some(Value::Ref(vm.spin_lambda(caller, &factory, &interfaces, &name, &methods, implementation)?)))
+}
+
+fn string_concat_factory_spin_concat(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let caller = class_arg(vm, args, 0)?;
+    let concat_type = non_null(vm, args, 1)?;
+    let descriptor = vm.type_descriptor(concat_type)?;
+    let mut literals = Vec::new();
+    for literal in references_arg(vm, args, 2)? {
+        literals.push(vm.rust_string(literal)?);
+    }
+    Ok(Some(Value::Ref(vm.spin_concat(caller, &descriptor, &literals)?)))
+}
+
+fn wrapper_can_convert(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let from = class_arg(vm, args, 0)?;
+    let to = class_arg(vm, args, 1)?;
+    boolean(vm.can_convert(from, to)?)
+}
+
+fn wrapper_convert(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let from = class_arg(vm, args, 1)?;
+    let to = class_arg(vm, args, 2)?;
+    Ok(Some(vm.convert(arg(args, 0)?, from, to)?))
+}
+
+fn array_new_array(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let component = class_arg(vm, args, 0)?;
+    let length = arg(args, 1)?.as_int()?;
+    if *vm.class(component).kind() == ClassKind::Void {
+        return Err(vm.exception("java/lang/IllegalArgumentException", None));
+    }
+    if length < 0 {
+        return Err(vm.exception("java/lang/NegativeArraySizeException", Some(length.to_string())));
+    }
+    let array_class = vm.array_class_of(component)?;
+    Ok(Some(Value::Ref(vm.alloc_array(array_class, length as usize)?)))
+}
+
+// The array and its component type, IllegalArgumentException for other objects
+fn array_arg(vm: &mut Vm, args: &[Value]) -> Flow<(ObjectRef, ::vm::class::ClassId)> {
+    let array = non_null(vm, args, 0)?;
+    match *vm.class(vm.heap.get(array).class()).kind() {
+        ClassKind::Array(component) => Ok((array, component)),
+        _ => Err(vm.exception("java/lang/IllegalArgumentException", Some("Argument is not an array".to_string()))),
+    }
+}
+
+fn array_element(vm: &mut Vm, array: ObjectRef, index: i32) -> Flow<usize> {
+    let length = vm.array(array)?.len();
+    if index < 0 || index as usize >= length {
+        let message = format!("Index {} out of bounds for length {}", index, length);
+        return Err(vm.exception("java/lang/ArrayIndexOutOfBoundsException", Some(message)));
+    }
+    Ok(index as usize)
+}
+
+fn array_get_length(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let (array, _) = array_arg(vm, args)?;
+    Ok(Some(Value::Int(vm.array(array)?.len() as i32)))
+}
+
+// Primitive elements come boxed
+fn array_get(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let (array, component) = array_arg(vm, args)?;
+    let index = array_element(vm, array, arg(args, 1)?.as_int()?)?;
+    let element = vm.array(array)?.get(index).unwrap_or(Value::Null);
+    match vm.field_type_of(component)? {
+        Some(field_type) => Ok(Some(vm.box_value(element, &field_type)?)),
+        None => Ok(Some(element)),
+    }
+}
+
+// Primitive elements are unboxed and widened to the component type
+fn array_set(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let (array, component) = array_arg(vm, args)?;
+    let index = array_element(vm, array, arg(args, 1)?.as_int()?)?;
+    let value = arg(args, 2)?;
+    let from = match value.as_ref()? {
+        Some(object) => vm.heap.get(object).class(),
+        None => component,
+    };
+    let matches = match *vm.class(component).kind() {
+        ClassKind::Primitive(_) if value.as_ref()?.is_none() => false,
+        ClassKind::Primitive(_) => vm.can_convert(from, component)?,
+        _ => vm.is_assignable(from, component),
+    };
+    if !matches {
+        return Err(vm.exception("java/lang/IllegalArgumentException", Some("array element type mismatch".to_string())));
+    }
+    let element = match *vm.class(component).kind() {
+        ClassKind::Primitive(_) => match vm.convert(value, from, component)?.as_ref()? {
+            Some(boxed) => vm.unbox_value(boxed)?.map_or(Value::Null, |(_, unboxed)| unboxed),
+            None => Value::Null,
+        },
+        _ => value,
+    };
+    vm.array_mut(array)?.set(index, element);
+    Ok(None)
+}
+
+// The shortest decimal digits of a finite value and where the point goes in them, 0.25 is
+// ([2, 5], 0) and 120.0 is ([1, 2], 3)
+fn field_set_final(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let object = non_null(vm, args, 1)?;
+    vm.set_final_field(this, object, arg(args, 2)?)?;
+    Ok(None)
+}
+
+// The caller of the method that calls getCallerClass, the frame below that method's
+fn reflection_get_caller_class(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    let frames = &vm.thread().frames;
+    let caller = match frames.len().checked_sub(3) {
+        Some(index) => frames[index].method.class(),
+        None => return Ok(Some(Value::Null)),
+    };
+    let caller = vm.class(caller).host.unwrap_or(caller);
+    class_value(vm, Some(caller))
+}
+
+fn reflection_verify_member_access(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let caller = class_arg(vm, args, 0)?;
+    let declaring = class_arg(vm, args, 1)?;
+    let modifiers = arg(args, 2)?.as_int()? as u16;
+    let accessible = vm.is_class_accessible(caller, declaring) && vm.is_accessible(caller, declaring, modifiers)?;
+    boolean(accessible || caller == declaring)
+}
+
+// The slot of the instance field, InternalError when there is none like the JDK's
+fn unsafe_object_field_offset(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = class_arg(vm, args, 1)?;
+    let name = string_arg(vm, args, 2)?;
+    match vm.field_slot(class, &name) {
+        Ok(slot) => Ok(Some(Value::Long(slot as i64))),
+        Err(_) => Err(vm.exception("java/lang/InternalError", Some(name))),
+    }
+}
+
+fn unsafe_location(vm: &mut Vm, args: &[Value]) -> Flow<Location> {
+    let object = non_null(vm, args, 1)?;
+    let offset = arg(args, 2)?.as_long()?;
+    vm.location(object, offset)
+}
+
+fn unsafe_get(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let location = unsafe_location(vm, args)?;
+    Ok(Some(vm.load_at(location)?))
+}
+
+fn unsafe_put(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let location = unsafe_location(vm, args)?;
+    vm.store_at(location, arg(args, 3)?)?;
+    Ok(None)
+}
+
+fn unsafe_compare_and_set(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let location = unsafe_location(vm, args)?;
+    let (set, _) = vm.compare_and_set(location, arg(args, 3)?, arg(args, 4)?)?;
+    boolean(set)
+}
+
+// Takes the permit or parks the thread once the native returns, unless it is interrupted. The
+// time is a relative number of nanoseconds, 0 for no deadline, or an absolute one in
+// milliseconds since the epoch
+fn unsafe_park(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let is_absolute = arg(args, 1)?.as_int()? != 0;
+    let time = arg(args, 2)?.as_long()?;
+    if vm.thread().permit {
+        vm.thread_mut().permit = false;
+        return Ok(None);
+    }
+    if time < 0 || is_absolute && time == 0 || interrupted(vm)? {
+        return Ok(None);
+    }
+    let until = if is_absolute {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_millis() as i64);
+        Some(deadline((time - now).max(0)))
+    } else if time == 0 {
+        None
+    } else {
+        Some(deadline(time / 1_000_000) + Duration::from_nanos((time % 1_000_000) as u64))
+    };
+    vm.thread_mut().state = ThreadState::Parked(until);
+    Ok(None)
+}
+
+// Wakes the thread up if it is parked, gives it the permit otherwise. Threads that weren't
+// started get nothing
+fn unsafe_unpark(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let object = non_null(vm, args, 1)?;
+    if let Some(thread) = vm.thread_of(object)? {
+        let thread = &mut vm.threads[thread.index()];
+        match thread.state {
+            ThreadState::Parked(_) => thread.state = ThreadState::Runnable,
+            _ => thread.permit = true,
+        }
+    }
+    Ok(None)
+}
+
+// registerNatives and the like, the VM finds natives by name
+fn nothing(_: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    Ok(None)
+}
+
+// Threads switch between instructions, every thread already sees every write in order
+fn fence(_: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    Ok(None)
+}
+
+// Natives only a JDK's java.base has
+
+// A native that needs a class initialized runs again once <clinit> has returned, or once another
+// thread initializing it is done
+fn initialized(vm: &mut Vm, class: ::vm::class::ClassId) -> Flow<bool> {
+    if vm.initialize(class)? {
+        return Ok(true);
+    }
+    vm.rerun = true;
+    Ok(false)
+}
+
+// Sets `name`, which getName caches
+fn class_init_class_name(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let name = class_get_name(vm, args)?.unwrap_or(Value::Null);
+    vm.set_named_field(this, "name", name)?;
+    Ok(Some(name))
+}
+
+// Spun classes stand for hidden classes
+fn class_is_hidden(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    boolean(vm.class(class).host.is_some())
+}
+
+// Every class is the boot loader's, the loader and the caller don't matter. Names are binary
+// names like "java.lang.String" and "[Ljava.lang.String;"
+fn class_for_name(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let name = string_arg(vm, args, 0)?;
+    let initialize = arg(args, 1)?.as_int()? != 0;
+    if name.contains('/') {
+        return Err(vm.exception("java/lang/ClassNotFoundException", Some(name)));
+    }
+    let class = match vm.load_class(&name.replace('.', "/")) {
+        Ok(class) => class,
+        Err(Throw::Exception(_)) => return Err(vm.exception("java/lang/ClassNotFoundException", Some(name))),
+        Err(throw) => return Err(throw),
+    };
+    if initialize && !initialized(vm, class)? {
+        return Ok(None);
+    }
+    class_value(vm, Some(class))
+}
+
+// The outer class InnerClasses gives a member class, null for local and anonymous ones
+fn class_get_declaring_class(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let outer = match vm.class(class).class_file {
+        Some(ref class_file) => class_file.inner_class(&vm.class(class).name)?
+            .and_then(|inner| inner.outer_class.map(str::to_string)),
+        None => None,
+    };
+    match outer {
+        Some(outer) => {
+            let outer = vm.load_class(&outer)?;
+            class_value(vm, Some(outer))
+        },
+        None => Ok(Some(Value::Null)),
+    }
+}
+
+// The simple name InnerClasses gives a nested class, null for an anonymous one
+fn class_get_simple_binary_name(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let name = match vm.class(class).class_file {
+        Some(ref class_file) => class_file.inner_class(&vm.class(class).name)?
+            .and_then(|inner| inner.inner_name.map(str::to_string)),
+        None => None,
+    };
+    match name {
+        Some(name) => Ok(Some(Value::Ref(vm.intern(&name)?))),
+        None => Ok(Some(Value::Null)),
+    }
+}
+
+// { enclosing class, method name, method descriptor } from the EnclosingMethod attribute, the
+// name and descriptor are null for a class in an initializer
+fn class_get_enclosing_method(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = this_class(vm, args)?;
+    let enclosing = match vm.class(class).class_file {
+        Some(ref class_file) => class_file.enclosing_method()?
+            .map(|enclosing| (enclosing.class.to_string(),
+                enclosing.method.map(|(name, descriptor)| (name.to_string(), descriptor.to_string())))),
+        None => None,
+    };
+    let (enclosing, method) = match enclosing {
+        Some(enclosing) => enclosing,
+        None => return Ok(Some(Value::Null)),
+    };
+    let enclosing = vm.load_class(&enclosing)?;
+    let mut info = vec![Value::Ref(vm.mirror(enclosing)?), Value::Null, Value::Null];
+    if let Some((name, descriptor)) = method {
+        info[1] = Value::Ref(vm.intern(&name)?);
+        info[2] = Value::Ref(vm.intern(&descriptor)?);
+    }
+    let array_class = vm.load_class("[Ljava/lang/Object;")?;
+    Ok(Some(Value::Ref(vm.heap.alloc(array_class, Body::Array(Array::Reference(info))))))
+}
+
+// The backtrace fill_in_stack_trace made is StackTraceElements already, they are copied into the
+// ones of the array
+fn stack_trace_element_init(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let elements = non_null(vm, args, 0)?;
+    let throwable = non_null(vm, args, 1)?;
+    let backtrace = match vm.named_field(throwable, "backtrace")?.as_ref()? {
+        Some(backtrace) => vm.array(backtrace)?.clone(),
+        None => return Err(vm.exception("java/lang/InternalError", Some("no backtrace".to_string()))),
+    };
+    let elements = vm.array(elements)?.clone();
+    for i in 0..elements.len().min(backtrace.len()) {
+        if let (Some(Value::Ref(element)), Some(Value::Ref(filled))) = (elements.get(i), backtrace.get(i)) {
+            if let Body::Fields(ref fields) = *vm.heap.get(filled).body() {
+                let fields = Body::Fields(fields.clone());
+                *vm.heap.get_mut(element).body_mut() = fields;
+            }
+        }
+    }
+    Ok(None)
+}
+
+// The JDK's sleep and wait are natives that throw InterruptedException themselves. They block
+// and run again when the thread wakes up, to throw if it was interrupted meanwhile
+fn blocking(vm: &mut Vm, args: &[Value], block: NativeMethod, message: Option<&str>) -> Flow<Option<Value>> {
+    let resumed = mem::replace(&mut vm.thread_mut().resumed, false);
+    if interrupted(vm)? {
+        let thread = vm.thread().object;
+        vm.set_named_field(thread, "interrupted", Value::Int(0))?;
+        return Err(vm.exception("java/lang/InterruptedException", message.map(str::to_string)));
+    }
+    if !resumed {
+        block(vm, args)?;
+        vm.thread_mut().resumed = true;
+        vm.rerun = true;
+    }
+    Ok(None)
+}
+
+fn thread_sleep_interruptibly(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    if arg(args, 0)?.as_long()? < 0 {
+        return Err(vm.exception("java/lang/IllegalArgumentException", Some("timeout value is negative".to_string())));
+    }
+    blocking(vm, args, thread_sleep, Some("sleep interrupted"))
+}
+
+fn object_wait_interruptibly(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    owned_monitor(vm, args)?;
+    if arg(args, 1)?.as_long()? < 0 {
+        return Err(vm.exception("java/lang/IllegalArgumentException", Some("timeout value is negative".to_string())));
+    }
+    blocking(vm, args, object_wait, None)
+}
+
+// x - n * y for the integer n nearest to x / y, the even one of two
+fn ieee_remainder(x: f64, y: f64) -> f64 {
+    let remainder = x % y;
+    let half = y.abs() / 2.0;
+    if remainder.abs() > half || remainder.abs() == half && ((x - remainder) / y).rem_euclid(2.0) != 0.0 {
+        remainder - y.abs().copysign(remainder)
+    } else {
+        remainder
+    }
+}
+
+fn system_map_library_name(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let name = string_arg(vm, args, 0)?;
+    new_string(vm, &format!("{}{}{}", env::consts::DLL_PREFIX, name, env::consts::DLL_SUFFIX))
+}
+
+fn set_system_stream(vm: &mut Vm, args: &[Value], name: &str) -> Flow<Option<Value>> {
+    let system = vm.load_class("java/lang/System")?;
+    let slot = vm.class(system).fields().iter()
+        .find(|field| field.name() == name && field.flags().is_static())
+        .map(|field| field.slot)
+        .ok_or_else(|| Error::Verify(format!("no System.{}", name)))?;
+    vm.class_mut(system).statics[slot] = arg(args, 0)?;
+    Ok(None)
+}
+
+// The file descriptor number of a FileInputStream or FileOutputStream. Only the standard streams
+// are open, others throw IOException
+fn standard_fd(vm: &mut Vm, stream: ObjectRef) -> Flow<i32> {
+    let fd = match vm.named_field(stream, "fd")?.as_ref()? {
+        Some(descriptor) => vm.named_field(descriptor, "fd")?.as_int()?,
+        None => -1,
+    };
+    match fd {
+        0..=2 => Ok(fd),
+        _ => Err(vm.exception("java/io/IOException", Some("Stream Closed".to_string()))),
+    }
+}
+
+fn file_output_stream_write_bytes(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let fd = standard_fd(vm, this)?;
+    standard_stream_write(vm, &[Value::Int(fd), arg(args, 1)?, arg(args, 2)?, arg(args, 3)?])?;
+    standard_stream(vm, fd).flush().map_err(Error::from)?;
+    Ok(None)
+}
+
+// Blocks the whole VM until stdin has something, -1 at its end
+fn file_input_stream_read_bytes(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    if standard_fd(vm, this)? != 0 {
+        return Err(vm.exception("java/io/IOException", Some("Bad file descriptor".to_string())));
+    }
+    let bytes = non_null(vm, args, 1)?;
+    let (off, len) = (arg(args, 2)?.as_int()? as usize, arg(args, 3)?.as_int()? as usize);
+    let mut buffer = vec![0; len];
+    let read = io::stdin().read(&mut buffer).map_err(Error::from)?;
+    if let Array::Byte(ref mut bytes) = *vm.array_mut(bytes)? {
+        for (i, &byte) in buffer[..read].iter().enumerate() {
+            bytes[off + i] = byte as i8;
+        }
+    }
+    Ok(Some(Value::Int(if read == 0 && len > 0 { -1 } else { read as i32 })))
+}
+
+// The POSIX numbers, -1 for signals there aren't
+fn signal_find_signal(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let number = match string_arg(vm, args, 0)?.as_str() {
+        "HUP" => 1,
+        "INT" => 2,
+        "QUIT" => 3,
+        "KILL" => 9,
+        "TERM" => 15,
+        _ => -1,
+    };
+    Ok(Some(Value::Int(number)))
+}
+
+fn reference_refers_to(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    let referent = vm.named_field(this, "referent")?;
+    boolean(referent == arg(args, 1)?)
+}
+
+fn reference_clear(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let this = non_null(vm, args, 0)?;
+    vm.set_named_field(this, "referent", Value::Null)?;
+    Ok(None)
+}
+
+// The Reference Handler thread parks for good, the pending list stays empty
+fn reference_wait_for_pending_list(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    vm.thread_mut().state = ThreadState::Parked(None);
+    Ok(None)
+}
+
+fn module_define(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let module = non_null(vm, args, 0)?;
+    let packages = non_null(vm, args, 4)?;
+    let packages = vm.array(packages)?.clone();
+    let mut names = Vec::with_capacity(packages.len());
+    for i in 0..packages.len() {
+        if let Some(Value::Ref(name)) = packages.get(i) {
+            names.push(vm.rust_string(name)?);
+        }
+    }
+    vm.define_module(module, &names)?;
+    Ok(None)
+}
+
+fn boot_loader_set_unnamed_module(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let module = non_null(vm, args, 0)?;
+    vm.set_unnamed_module(module)?;
+    Ok(None)
+}
+
+fn file_path(vm: &mut Vm, args: &[Value], index: usize) -> Flow<PathBuf> {
+    let file = non_null(vm, args, index)?;
+    let path = match vm.named_field(file, "path")?.as_ref()? {
+        Some(path) => vm.rust_string(path)?,
+        None => return Err(vm.exception("java/lang/NullPointerException", None)),
+    };
+    Ok(PathBuf::from(path))
+}
+
+// BA_EXISTS, BA_REGULAR, BA_DIRECTORY and BA_HIDDEN of java.io.FileSystem
+fn file_system_get_boolean_attributes(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let path = file_path(vm, args, 1)?;
+    let attributes = match fs::metadata(&path) {
+        Ok(metadata) => {
+            let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            0x01 | if metadata.is_file() { 0x02 } else { 0 } | if metadata.is_dir() { 0x04 } else { 0 } | if hidden { 0x08 } else { 0 }
+        },
+        Err(_) => 0,
+    };
+    Ok(Some(Value::Int(attributes)))
+}
+
+// Paths that don't exist stay as they are
+fn file_system_canonicalize(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let path = string_arg(vm, args, 1)?;
+    let canonical = fs::canonicalize(&path).map_or(path, |canonical| canonical.to_string_lossy().into_owned());
+    new_string(vm, &canonical)
+}
+
+fn string_array(vm: &mut Vm, strings: &[Option<String>]) -> Flow<Option<Value>> {
+    let mut values = Vec::with_capacity(strings.len());
+    for string in strings {
+        values.push(match *string {
+            Some(ref string) => Value::Ref(vm.new_string(string)?),
+            None => Value::Null,
+        });
+    }
+    let array_class = vm.load_class("[Ljava/lang/String;")?;
+    Ok(Some(Value::Ref(vm.heap.alloc(array_class, Body::Array(Array::Reference(values))))))
+}
+
+// The properties of the platform by the indexes of SystemProps$Raw, null where there is nothing
+// better than SystemProps' default. The encodings are UTF-8, whatever the locale says
+fn system_props_platform_properties(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    let (os_name, separator, path_separator, line_separator) = match env::consts::OS {
+        "windows" => ("Windows", "\\", ";", "\r\n"),
+        "macos" => ("Mac OS X", "/", ":", "\n"),
+        "linux" => ("Linux", "/", ":", "\n"),
+        os => (os, "/", ":", "\n"),
+    };
+    let os_arch = match env::consts::ARCH {
+        "x86_64" => "amd64",
+        "x86" => "i386",
+        arch => arch,
+    };
+    // "?" when there is no telling, like the JDK when the user has no passwd entry
+    let home = env::var("HOME").unwrap_or_else(|_| "?".to_string());
+    let user = env::var("USER").unwrap_or_else(|_| "?".to_string());
+    let dir = env::current_dir().ok().map(|dir| dir.to_string_lossy().into_owned());
+    let tmpdir = env::temp_dir().to_string_lossy().into_owned();
+    let mut props: Vec<Option<String>> = vec![None; 39];
+    props[36] = dir;
+    let mut set = |index: usize, value: &str| props[index] = Some(value.to_string());
+    set(4, "UTF-8");
+    set(5, separator);
+    set(18, &tmpdir);
+    set(19, line_separator);
+    set(20, os_arch);
+    set(21, os_name);
+    set(22, "");
+    set(23, path_separator);
+    set(28, if cfg!(target_pointer_width = "64") { "64" } else { "32" });
+    set(29, if cfg!(target_endian = "big") { "big" } else { "little" });
+    set(31, if cfg!(target_endian = "big") { "UnicodeBig" } else { "UnicodeLittle" });
+    set(32, "UTF-8");
+    set(37, &home);
+    set(38, &user);
+    string_array(vm, &props)
+}
+
+// Pairs of keys and values, what the VM itself says about the JDK it runs
+fn system_props_vm_properties(vm: &mut Vm, _: &[Value]) -> Flow<Option<Value>> {
+    let home = vm.jdk.as_ref().map_or(String::new(), |home| home.to_string_lossy().into_owned());
+    let library_path = Path::new(&home).join("lib").to_string_lossy().into_owned();
+    let props = [
+        ("java.home", home.as_str()),
+        ("sun.boot.library.path", &library_path),
+        ("java.library.path", ""),
+        ("java.vm.specification.name", "Java Virtual Machine Specification"),
+        ("java.vm.specification.vendor", "Oracle Corporation"),
+        ("java.vm.specification.version", "17"),
+        ("java.vm.name", "jvm_rs"),
+        ("java.vm.vendor", "jvm_rs"),
+        ("java.vm.version", env!("CARGO_PKG_VERSION")),
+        ("java.vm.info", "interpreted mode"),
+        ("jdk.debug", "release"),
+    ];
+    let strings: Vec<Option<String>> = props.iter()
+        .flat_map(|&(key, value)| vec![Some(key.to_string()), Some(value.to_string())])
+        .collect();
+    string_array(vm, &strings)
+}
+
+fn unsafe_compare_and_exchange(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let location = unsafe_location(vm, args)?;
+    let (_, witness) = vm.compare_and_set(location, arg(args, 3)?, arg(args, 4)?)?;
+    Ok(Some(witness))
+}
+
+fn unsafe_should_be_initialized(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = class_arg(vm, args, 1)?;
+    boolean(!vm.is_initialized(class))
+}
+
+fn unsafe_ensure_class_initialized(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = class_arg(vm, args, 1)?;
+    initialized(vm, class)?;
+    Ok(None)
+}
+
+fn reflection_get_class_access_flags(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let class = class_arg(vm, args, 0)?;
+    Ok(Some(Value::Int(vm.class(class).flags().bits() as i32)))
+}
+
+fn decimal_digits(value: f64) -> (Vec<u8>, isize) {
+    if value == 0.0 {
+        return (vec![0], 1);
+    }
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap_or(scientific.len()));
+    let digits = mantissa.bytes().filter(u8::is_ascii_digit).map(|digit| digit - b'0').collect();
+    (digits, exponent[1..].parse::<isize>().unwrap_or(0) + 1)
+}
+
+// Keeps `count` digits rounding half up, a carry out of the first digit moves the point
+fn round_half_up(digits: &mut Vec<u8>, point: &mut isize, count: isize) {
+    if count < 0 {
+        digits.clear();
+        return;
+    }
+    let count = count as usize;
+    if digits.len() <= count {
+        return;
+    }
+    let round_up = digits[count] >= 5;
+    digits.truncate(count);
+    if round_up {
+        let mut i = count;
+        loop {
+            if i == 0 {
+                digits.insert(0, 1);
+                *point += 1;
+                break;
+            }
+            i -= 1;
+            if digits[i] == 9 {
+                digits[i] = 0;
+            } else {
+                digits[i] += 1;
+                break;
+            }
+        }
+    }
+}
+
+// %.nf, finite values only, NaN and the infinities are formatted in Java
+fn formatter_fixed(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let value = arg(args, 0)?.as_double()?;
+    let precision = arg(args, 1)?.as_int()?.max(0) as isize;
+    let (mut digits, mut point) = decimal_digits(value);
+    let count = point + precision;
+    round_half_up(&mut digits, &mut point, count);
+    let digit = |i: isize| if i >= 0 && (i as usize) < digits.len() { (b'0' + digits[i as usize]) as char } else { '0' };
+    let mut formatted = String::new();
+    if value.is_sign_negative() {
+        formatted.push('-');
+    }
+    if point <= 0 {
+        formatted.push('0');
+    }
+    formatted.extend((0..point).map(digit));
+    if precision > 0 {
+        formatted.push('.');
+        formatted.extend((point..point + precision).map(digit));
+    }
+    new_string(vm, &formatted)
+}
+
+// %.ne, with at least two exponent digits like 1.500000e+01
+fn formatter_scientific(vm: &mut Vm, args: &[Value]) -> Flow<Option<Value>> {
+    let value = arg(args, 0)?.as_double()?;
+    let precision = arg(args, 1)?.as_int()?.max(0) as usize;
+    let (mut digits, mut point) = decimal_digits(value);
+    round_half_up(&mut digits, &mut point, precision as isize + 1);
+    digits.resize(precision + 1, 0);
+    let exponent = if value == 0.0 { 0 } else { point - 1 };
+    let mut formatted = String::new();
+    if value.is_sign_negative() {
+        formatted.push('-');
+    }
+    formatted.push((b'0' + digits[0]) as char);
+    if precision > 0 {
+        formatted.push('.');
+        formatted.extend(digits[1..].iter().map(|&digit| (b'0' + digit) as char));
+    }
+    formatted.push_str(&format!("e{}{:02}", if exponent < 0 { '-' } else { '+' }, exponent.abs()));
+    new_string(vm, &formatted)
+}
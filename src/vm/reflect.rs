@@ -0,0 +1,146 @@
+/*
+* The java.lang.reflect objects of a class's members, made anew each time they are asked for like
+* the JDK's copies. The VM gives them the declaring class, the name, the types and the access
+* flags, Field, Method and Constructor use their members through method handles
+*/
+use class_file::access_flags::{ACC_ABSTRACT, ACC_FINAL, ACC_PRIVATE, ACC_PROTECTED, ACC_PUBLIC, ACC_SUPER};
+use result::Error;
+use vm::class::{ClassId, ClassKind};
+use vm::heap::{Array, Body, ObjectRef};
+use vm::value::Value;
+use vm::{Flow, Vm};
+
+impl Vm {
+    // Class.getModifiers: the InnerClasses flags of a nested class, the access flags of its
+    // element type for an array
+    pub(super) fn class_modifiers(&self, class: ClassId) -> Flow<i32> {
+        let flags = match *self.class(class).kind() {
+            ClassKind::Array(component) => {
+                let access = self.class_modifiers(component)? as u16 & (ACC_PUBLIC | ACC_PRIVATE | ACC_PROTECTED);
+                access | ACC_ABSTRACT | ACC_FINAL
+            },
+            ClassKind::Primitive(_) | ClassKind::Void => ACC_PUBLIC | ACC_ABSTRACT | ACC_FINAL,
+            ClassKind::Instance => {
+                let class = self.class(class);
+                let inner = match class.class_file {
+                    Some(ref class_file) => class_file.inner_class_flags(&class.name)?,
+                    None => None,
+                };
+                inner.unwrap_or_else(|| class.flags().bits()) & !ACC_SUPER
+            },
+        };
+        Ok(flags as i32)
+    }
+
+    // Class.getDeclaredFields0, in the order the class file declares them
+    pub(super) fn declared_fields(&mut self, class: ClassId, public_only: bool) -> Flow<ObjectRef> {
+        let fields: Vec<_> = self.class(class).fields().iter()
+            .filter(|field| !public_only || field.flags().is_public())
+            .map(|field| (field.name().to_string(), field.field_type().clone(), field.flags().bits()))
+            .collect();
+        let field_class = self.load_class("java/lang/reflect/Field")?;
+        let mirror = self.mirror(class)?;
+        let mut objects = Vec::with_capacity(fields.len());
+        for (name, field_type, bits) in fields {
+            let field = self.alloc_instance(field_class);
+            let name = self.intern(&name)?;
+            let type_class = self.class_of_type(Some(&field_type))?;
+            let type_mirror = self.mirror(type_class)?;
+            self.set_named_field(field, "clazz", Value::Ref(mirror))?;
+            self.set_named_field(field, "name", Value::Ref(name))?;
+            self.set_named_field(field, "type", Value::Ref(type_mirror))?;
+            self.set_named_field(field, "modifiers", Value::Int(bits as i32))?;
+            objects.push(Value::Ref(field));
+        }
+        let array_class = self.load_class("[Ljava/lang/reflect/Field;")?;
+        Ok(self.heap.alloc(array_class, Body::Array(Array::Reference(objects))))
+    }
+
+    // Class.getDeclaredMethods0 and getDeclaredConstructors0. Methods leave out <init> and
+    // <clinit>, constructors are the <init> methods
+    pub(super) fn declared_methods(&mut self, class: ClassId, public_only: bool, constructors: bool) -> Flow<ObjectRef> {
+        let methods: Vec<_> = self.class(class).methods().iter()
+            .filter(|method| (method.name() == "<init>") == constructors && method.name() != "<clinit>")
+            .filter(|method| !public_only || method.flags().is_public())
+            .cloned()
+            .collect();
+        let class_name = if constructors { "java/lang/reflect/Constructor" } else { "java/lang/reflect/Method" };
+        let method_class = self.load_class(class_name)?;
+        let mirror = self.mirror(class)?;
+        let class_file = self.class(class).class_file.clone();
+        let mut objects = Vec::with_capacity(methods.len());
+        for method in methods {
+            let object = self.alloc_instance(method_class);
+            let mut parameter_types = Vec::new();
+            for param in method.method_type().params() {
+                let param_class = self.class_of_type(Some(param))?;
+                parameter_types.push(Value::Ref(self.mirror(param_class)?));
+            }
+            let mut exception_types = Vec::new();
+            let member_info = match class_file {
+                Some(ref class_file) => class_file.find_method(method.name(), method.descriptor())?,
+                None => None,
+            };
+            if let Some(member_info) = member_info {
+                for exception in member_info.exceptions()? {
+                    let exception = self.load_class(exception)?;
+                    exception_types.push(Value::Ref(self.mirror(exception)?));
+                }
+            }
+            let parameter_types = self.class_array(parameter_types)?;
+            let exception_types = self.class_array(exception_types)?;
+            self.set_named_field(object, "clazz", Value::Ref(mirror))?;
+            self.set_named_field(object, "parameterTypes", Value::Ref(parameter_types))?;
+            self.set_named_field(object, "exceptionTypes", Value::Ref(exception_types))?;
+            self.set_named_field(object, "modifiers", Value::Int(method.flags().bits() as i32))?;
+            if !constructors {
+                let name = self.intern(method.name())?;
+                let return_class = self.class_of_type(method.method_type().return_type())?;
+                let return_type = self.mirror(return_class)?;
+                self.set_named_field(object, "name", Value::Ref(name))?;
+                self.set_named_field(object, "returnType", Value::Ref(return_type))?;
+            }
+            objects.push(Value::Ref(object));
+        }
+        let array_class = self.load_class(&format!("[L{};", class_name))?;
+        Ok(self.heap.alloc(array_class, Body::Array(Array::Reference(objects))))
+    }
+
+    fn class_array(&mut self, mirrors: Vec<Value>) -> Flow<ObjectRef> {
+        let array_class = self.load_class("[Ljava/lang/Class;")?;
+        Ok(self.heap.alloc(array_class, Body::Array(Array::Reference(mirrors))))
+    }
+
+    // Field.setFinal, the value already has the field's type with primitives wrapped
+    pub(super) fn set_final_field(&mut self, field: ObjectRef, object: ObjectRef, value: Value) -> Flow<()> {
+        let mirror = self.named_field(field, "clazz")?.as_ref()?
+            .ok_or_else(|| Error::Verify("Field without a class".to_string()))?;
+        let class = self.class_of_mirror(mirror).ok_or_else(|| Error::Verify("Class object without a class".to_string()))?;
+        let name = match self.named_field(field, "name")?.as_ref()? {
+            Some(name) => self.rust_string(name)?,
+            None => return Err(Error::Verify("Field without a name".to_string()).into()),
+        };
+        let (slot, is_primitive) = match self.class(class).fields().iter().find(|field| field.name() == name && !field.flags().is_static()) {
+            Some(field) => (field.slot, !field.field_type().is_reference()),
+            None => return Err(Error::Verify(format!("no final field {}", name)).into()),
+        };
+        let value = match value.as_ref()? {
+            Some(boxed) if is_primitive => match self.unbox_value(boxed)? {
+                Some((_, unboxed)) => unboxed,
+                None => return Err(Error::Verify(format!("{} isn't wrapped", name)).into()),
+            },
+            _ => value,
+        };
+        Ok(self.set_field(object, slot, value)?)
+    }
+
+    // Class.getInterfaces, Cloneable and Serializable for arrays
+    pub(super) fn interface_mirrors(&mut self, class: ClassId) -> Flow<ObjectRef> {
+        let interfaces = self.class(class).interfaces().to_vec();
+        let mut mirrors = Vec::with_capacity(interfaces.len());
+        for interface in interfaces {
+            mirrors.push(Value::Ref(self.mirror(interface)?));
+        }
+        self.class_array(mirrors)
+    }
+}
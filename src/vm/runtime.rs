@@ -0,0 +1,163 @@
+use class_path::ClassSource;
+use result::Result;
+use types::u1;
+
+// The class files of the mini runtime, compiled from runtime/src by runtime/build.sh
+pub struct Runtime;
+
+macro_rules! classes {
+    ($($name:literal),* $(,)*) => {
+        pub const CLASSES: &[(&str, &[u1])] = &[
+            $(($name, include_bytes!(concat!("runtime/classes/", $name, ".class")))),*
+        ];
+    };
+}
+
+classes!(
+    "java/io/ByteArrayOutputStream",
+    "java/io/OutputStream",
+    "java/io/PrintStream",
+    "java/io/Serializable",
+    "java/lang/AbstractMethodError",
+    "java/lang/ArithmeticException",
+    "java/lang/ArrayIndexOutOfBoundsException",
+    "java/lang/ArrayStoreException",
+    "java/lang/AssertionError",
+    "java/lang/AutoCloseable",
+    "java/lang/Boolean",
+    "java/lang/BootstrapMethodError",
+    "java/lang/Byte",
+    "java/lang/CharSequence",
+    "java/lang/Character",
+    "java/lang/Class",
+    "java/lang/ClassCastException",
+    "java/lang/ClassCircularityError",
+    "java/lang/ClassFormatError",
+    "java/lang/CloneNotSupportedException",
+    "java/lang/Cloneable",
+    "java/lang/Comparable",
+    "java/lang/Double",
+    "java/lang/Enum",
+    "java/lang/Error",
+    "java/lang/Exception",
+    "java/lang/ExceptionInInitializerError",
+    "java/lang/Float",
+    "java/lang/IllegalAccessError",
+    "java/lang/IllegalAccessException",
+    "java/lang/IllegalArgumentException",
+    "java/lang/IllegalMonitorStateException",
+    "java/lang/IllegalStateException",
+    "java/lang/IllegalThreadStateException",
+    "java/lang/IncompatibleClassChangeError",
+    "java/lang/IndexOutOfBoundsException",
+    "java/lang/InstantiationError",
+    "java/lang/InstantiationException",
+    "java/lang/Integer",
+    "java/lang/InternalError",
+    "java/lang/InterruptedException",
+    "java/lang/Iterable",
+    "java/lang/LinkageError",
+    "java/lang/Long",
+    "java/lang/Math",
+    "java/lang/NegativeArraySizeException",
+    "java/lang/NoClassDefFoundError",
+    "java/lang/NoSuchFieldError",
+    "java/lang/NoSuchFieldException",
+    "java/lang/NoSuchMethodError",
+    "java/lang/NoSuchMethodException",
+    "java/lang/NullPointerException",
+    "java/lang/Number",
+    "java/lang/NumberFormatException",
+    "java/lang/Object",
+    "java/lang/OutOfMemoryError",
+    "java/lang/ReflectiveOperationException",
+    "java/lang/Runnable",
+    "java/lang/RuntimeException",
+    "java/lang/Short",
+    "java/lang/StackOverflowError",
+    "java/lang/StackTraceElement",
+    "java/lang/String",
+    "java/lang/StringBuilder",
+    "java/lang/StringIndexOutOfBoundsException",
+    "java/lang/System$StandardStream",
+    "java/lang/System",
+    "java/lang/Thread$UncaughtExceptionHandler",
+    "java/lang/Thread",
+    "java/lang/Throwable",
+    "java/lang/UnsatisfiedLinkError",
+    "java/lang/UnsupportedOperationException",
+    "java/lang/VirtualMachineError",
+    "java/lang/Void",
+    "java/lang/invoke/CallSite$DynamicInvoker",
+    "java/lang/invoke/CallSite$Uninitialized",
+    "java/lang/invoke/CallSite",
+    "java/lang/invoke/ConstantBootstraps",
+    "java/lang/invoke/ConstantCallSite",
+    "java/lang/invoke/DirectMethodHandle$Invoker",
+    "java/lang/invoke/DirectMethodHandle",
+    "java/lang/invoke/LambdaConversionException",
+    "java/lang/invoke/LambdaMetafactory",
+    "java/lang/invoke/MethodHandle",
+    "java/lang/invoke/MethodHandleInfo",
+    "java/lang/invoke/MethodHandleNatives",
+    "java/lang/invoke/MethodHandles$Caught",
+    "java/lang/invoke/MethodHandles$Collector",
+    "java/lang/invoke/MethodHandles$Constant",
+    "java/lang/invoke/MethodHandles$Converted",
+    "java/lang/invoke/MethodHandles$Dropped",
+    "java/lang/invoke/MethodHandles$Filtered",
+    "java/lang/invoke/MethodHandles$Folded",
+    "java/lang/invoke/MethodHandles$Guarded",
+    "java/lang/invoke/MethodHandles$Inserted",
+    "java/lang/invoke/MethodHandles$Invoker",
+    "java/lang/invoke/MethodHandles$Lookup",
+    "java/lang/invoke/MethodHandles$Permuted",
+    "java/lang/invoke/MethodHandles$ReturnFiltered",
+    "java/lang/invoke/MethodHandles$Spreader",
+    "java/lang/invoke/MethodHandles$Thrower",
+    "java/lang/invoke/MethodHandles$VarargsCollector",
+    "java/lang/invoke/MethodHandles",
+    "java/lang/invoke/MethodType",
+    "java/lang/invoke/MutableCallSite",
+    "java/lang/invoke/StringConcatException",
+    "java/lang/invoke/StringConcatFactory",
+    "java/lang/invoke/VarHandle",
+    "java/lang/invoke/VolatileCallSite",
+    "java/lang/invoke/Wrapper",
+    "java/lang/invoke/WrongMethodTypeException",
+    "java/lang/reflect/AccessibleObject",
+    "java/lang/reflect/Array",
+    "java/lang/reflect/Constructor",
+    "java/lang/reflect/Executable",
+    "java/lang/reflect/Field",
+    "java/lang/reflect/InvocationTargetException",
+    "java/lang/reflect/Member",
+    "java/lang/reflect/Method",
+    "java/lang/reflect/Modifier",
+    "java/lang/reflect/Primitives",
+    "java/util/Arrays",
+    "java/util/Formatter",
+    "java/util/Iterator",
+    "java/util/Objects",
+    "java/util/concurrent/atomic/AtomicBoolean",
+    "java/util/concurrent/atomic/AtomicInteger",
+    "java/util/concurrent/atomic/AtomicLong",
+    "java/util/concurrent/atomic/AtomicReference",
+    "java/util/concurrent/locks/AbstractOwnableSynchronizer",
+    "java/util/concurrent/locks/AbstractQueuedSynchronizer$ConditionObject",
+    "java/util/concurrent/locks/AbstractQueuedSynchronizer$Node",
+    "java/util/concurrent/locks/AbstractQueuedSynchronizer",
+    "java/util/concurrent/locks/Condition",
+    "java/util/concurrent/locks/Lock",
+    "java/util/concurrent/locks/LockSupport",
+    "java/util/concurrent/locks/ReentrantLock$Sync",
+    "java/util/concurrent/locks/ReentrantLock",
+    "jdk/internal/misc/Unsafe",
+    "jdk/internal/reflect/Reflection",
+);
+
+impl ClassSource for Runtime {
+    fn find_class(&self, name: &str) -> Result<Option<Vec<u1>>> {
+        Ok(CLASSES.iter().find(|&&(class, _)| class == name).map(|&(_, bytes)| bytes.to_vec()))
+    }
+}
@@ -0,0 +1,9 @@
+#!/bin/sh
+# Rebuilds classes/ from src/, the class files the VM bundles as its runtime. Needs a JDK 9+ on the
+# PATH and is only run by hand: the crate includes what is checked in. The sources are compiled
+# against themselves, not the JDK, so they only call what the runtime has.
+set -e
+cd "$(dirname "$0")"
+rm -rf classes
+mkdir classes
+javac -encoding UTF-8 -source 8 -target 8 -nowarn -Xlint:-options -XDsuppressNotes -bootclasspath classes -sourcepath src -d classes $(find src -name '*.java' | sort)
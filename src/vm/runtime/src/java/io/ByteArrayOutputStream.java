@@ -0,0 +1,51 @@
+package java.io;
+
+public class ByteArrayOutputStream extends OutputStream {
+    protected byte[] buf;
+    protected int count;
+
+    public ByteArrayOutputStream() {
+        this(32);
+    }
+
+    public ByteArrayOutputStream(int size) {
+        buf = new byte[size];
+    }
+
+    public void write(int b) {
+        ensureCapacity(count + 1);
+        buf[count++] = (byte) b;
+    }
+
+    public void write(byte[] b, int off, int len) {
+        ensureCapacity(count + len);
+        System.arraycopy(b, off, buf, count, len);
+        count += len;
+    }
+
+    public void reset() {
+        count = 0;
+    }
+
+    public int size() {
+        return count;
+    }
+
+    public byte[] toByteArray() {
+        byte[] bytes = new byte[count];
+        System.arraycopy(buf, 0, bytes, 0, count);
+        return bytes;
+    }
+
+    public String toString() {
+        return new String(toByteArray());
+    }
+
+    private void ensureCapacity(int minCapacity) {
+        if (minCapacity > buf.length) {
+            byte[] grown = new byte[Math.max(minCapacity, buf.length * 2)];
+            System.arraycopy(buf, 0, grown, 0, count);
+            buf = grown;
+        }
+    }
+}
@@ -0,0 +1,21 @@
+package java.io;
+
+public abstract class OutputStream implements AutoCloseable {
+    public abstract void write(int b);
+
+    public void write(byte[] b) {
+        write(b, 0, b.length);
+    }
+
+    public void write(byte[] b, int off, int len) {
+        for (int i = 0; i < len; i++) {
+            write(b[off + i]);
+        }
+    }
+
+    public void flush() {
+    }
+
+    public void close() {
+    }
+}
@@ -0,0 +1,148 @@
+package java.io;
+
+// Encodes to UTF-8, IOExceptions don't exist in the runtime so checkError is always false
+public class PrintStream extends OutputStream {
+    private final OutputStream out;
+    private final boolean autoFlush;
+
+    public PrintStream(OutputStream out) {
+        this(out, false);
+    }
+
+    public PrintStream(OutputStream out, boolean autoFlush) {
+        if (out == null) {
+            throw new NullPointerException("Null output stream");
+        }
+        this.out = out;
+        this.autoFlush = autoFlush;
+    }
+
+    public void write(int b) {
+        out.write(b);
+        if (autoFlush && b == '\n') {
+            out.flush();
+        }
+    }
+
+    public void write(byte[] buf, int off, int len) {
+        out.write(buf, off, len);
+        if (autoFlush) {
+            out.flush();
+        }
+    }
+
+    public void flush() {
+        out.flush();
+    }
+
+    public void close() {
+        out.close();
+    }
+
+    public boolean checkError() {
+        return false;
+    }
+
+    private void writeString(String s) {
+        byte[] bytes = s.getBytes();
+        out.write(bytes, 0, bytes.length);
+        if (autoFlush && s.indexOf('\n') >= 0) {
+            out.flush();
+        }
+    }
+
+    public void print(boolean b) {
+        writeString(String.valueOf(b));
+    }
+
+    public void print(char c) {
+        writeString(String.valueOf(c));
+    }
+
+    public void print(int i) {
+        writeString(String.valueOf(i));
+    }
+
+    public void print(long l) {
+        writeString(String.valueOf(l));
+    }
+
+    public void print(float f) {
+        writeString(String.valueOf(f));
+    }
+
+    public void print(double d) {
+        writeString(String.valueOf(d));
+    }
+
+    public void print(char[] s) {
+        writeString(new String(s));
+    }
+
+    public void print(String s) {
+        writeString(String.valueOf(s));
+    }
+
+    public void print(Object obj) {
+        writeString(String.valueOf(obj));
+    }
+
+    public void println() {
+        writeString("\n");
+    }
+
+    public void println(boolean x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public void println(char x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public void println(int x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public void println(long x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public void println(float x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public void println(double x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public void println(char[] x) {
+        writeString(new String(x) + "\n");
+    }
+
+    public void println(String x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public void println(Object x) {
+        writeString(String.valueOf(x) + "\n");
+    }
+
+    public PrintStream printf(String format, Object... args) {
+        return format(format, args);
+    }
+
+    public PrintStream format(String format, Object... args) {
+        writeString(String.format(format, args));
+        return this;
+    }
+
+    public PrintStream append(CharSequence csq) {
+        print(String.valueOf(csq));
+        return this;
+    }
+
+    public PrintStream append(char c) {
+        print(c);
+        return this;
+    }
+}
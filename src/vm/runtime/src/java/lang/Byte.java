@@ -0,0 +1,84 @@
+package java.lang;
+
+public final class Byte extends Number implements Comparable<Byte> {
+    public static final byte MIN_VALUE = -128;
+    public static final byte MAX_VALUE = 127;
+    public static final int SIZE = 8;
+    public static final Class<Byte> TYPE = (Class<Byte>) Class.getPrimitiveClass("byte");
+
+    private static final Byte[] cache = new Byte[256];
+
+    private final byte value;
+
+    public Byte(byte value) {
+        this.value = value;
+    }
+
+    public static Byte valueOf(byte value) {
+        if (value >= -128 && value <= 127) {
+            Byte cached = cache[value + 128];
+            if (cached == null) {
+                cached = new Byte(value);
+                cache[value + 128] = cached;
+            }
+            return cached;
+        }
+        return new Byte(value);
+    }
+
+    public static Byte valueOf(String s) {
+        return valueOf(parseByte(s));
+    }
+
+    public static byte parseByte(String s) {
+        return parseByte(s, 10);
+    }
+
+    public static byte parseByte(String s, int radix) {
+        return (byte) Long.parse(s, radix, MIN_VALUE, MAX_VALUE);
+    }
+
+    public static String toString(byte value) {
+        return Integer.toString(value);
+    }
+
+    public static int compare(byte x, byte y) {
+        return x - y;
+    }
+
+    public static int hashCode(byte value) {
+        return value;
+    }
+
+    public int intValue() {
+        return value;
+    }
+
+    public long longValue() {
+        return value;
+    }
+
+    public float floatValue() {
+        return value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public int compareTo(Byte another) {
+        return compare(value, another.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Byte && value == ((Byte) obj).value;
+    }
+
+    public int hashCode() {
+        return value;
+    }
+
+    public String toString() {
+        return Integer.toString(value);
+    }
+}
@@ -0,0 +1,223 @@
+package java.lang;
+
+// ASCII is handled here, the rest of Unicode by the VM
+public final class Character implements java.io.Serializable, Comparable<Character> {
+    public static final char MIN_VALUE = '\u0000';
+    public static final char MAX_VALUE = '\uffff';
+    public static final int MIN_RADIX = 2;
+    public static final int MAX_RADIX = 36;
+    public static final char MIN_HIGH_SURROGATE = '\ud800';
+    public static final char MAX_HIGH_SURROGATE = '\udbff';
+    public static final char MIN_LOW_SURROGATE = '\udc00';
+    public static final char MAX_LOW_SURROGATE = '\udfff';
+    public static final int MIN_SUPPLEMENTARY_CODE_POINT = 0x010000;
+    public static final int MAX_CODE_POINT = 0x10ffff;
+    public static final int SIZE = 16;
+    public static final Class<Character> TYPE = (Class<Character>) Class.getPrimitiveClass("char");
+
+    private static final Character[] cache = new Character[128];
+
+    private final char value;
+
+    public Character(char value) {
+        this.value = value;
+    }
+
+    public static Character valueOf(char c) {
+        if (c < 128) {
+            Character cached = cache[c];
+            if (cached == null) {
+                cached = new Character(c);
+                cache[c] = cached;
+            }
+            return cached;
+        }
+        return new Character(c);
+    }
+
+    public static boolean isDigit(char ch) {
+        return isDigit((int) ch);
+    }
+
+    public static boolean isDigit(int codePoint) {
+        return codePoint < 128 ? codePoint >= '0' && codePoint <= '9' : isDigit0(codePoint);
+    }
+
+    public static boolean isLetter(char ch) {
+        return isLetter((int) ch);
+    }
+
+    public static boolean isLetter(int codePoint) {
+        return codePoint < 128 ? isLowerCase(codePoint) || isUpperCase(codePoint) : isLetter0(codePoint);
+    }
+
+    public static boolean isLetterOrDigit(char ch) {
+        return isLetter(ch) || isDigit(ch);
+    }
+
+    public static boolean isLetterOrDigit(int codePoint) {
+        return isLetter(codePoint) || isDigit(codePoint);
+    }
+
+    public static boolean isAlphabetic(int codePoint) {
+        return isLetter(codePoint);
+    }
+
+    public static boolean isLowerCase(char ch) {
+        return isLowerCase((int) ch);
+    }
+
+    public static boolean isLowerCase(int codePoint) {
+        return codePoint < 128 ? codePoint >= 'a' && codePoint <= 'z' : isLowerCase0(codePoint);
+    }
+
+    public static boolean isUpperCase(char ch) {
+        return isUpperCase((int) ch);
+    }
+
+    public static boolean isUpperCase(int codePoint) {
+        return codePoint < 128 ? codePoint >= 'A' && codePoint <= 'Z' : isUpperCase0(codePoint);
+    }
+
+    public static boolean isWhitespace(char ch) {
+        return isWhitespace((int) ch);
+    }
+
+    public static boolean isWhitespace(int codePoint) {
+        if (codePoint < 128) {
+            return codePoint == ' ' || (codePoint >= '\t' && codePoint <= '\r') || (codePoint >= 0x1c && codePoint <= 0x1f);
+        }
+        // No-break spaces aren't whitespace to Java
+        return codePoint != 0x00a0 && codePoint != 0x2007 && codePoint != 0x202f && isWhitespace0(codePoint);
+    }
+
+    public static boolean isSpaceChar(char ch) {
+        return ch == ' ' || ch == 0x00a0 || (ch >= 128 && isWhitespace0(ch));
+    }
+
+    public static char toLowerCase(char ch) {
+        return (char) toLowerCase((int) ch);
+    }
+
+    public static int toLowerCase(int codePoint) {
+        if (codePoint < 128) {
+            return codePoint >= 'A' && codePoint <= 'Z' ? codePoint + 32 : codePoint;
+        }
+        return toLowerCase0(codePoint);
+    }
+
+    public static char toUpperCase(char ch) {
+        return (char) toUpperCase((int) ch);
+    }
+
+    public static int toUpperCase(int codePoint) {
+        if (codePoint < 128) {
+            return codePoint >= 'a' && codePoint <= 'z' ? codePoint - 32 : codePoint;
+        }
+        return toUpperCase0(codePoint);
+    }
+
+    private static native boolean isDigit0(int codePoint);
+
+    private static native boolean isLetter0(int codePoint);
+
+    private static native boolean isLowerCase0(int codePoint);
+
+    private static native boolean isUpperCase0(int codePoint);
+
+    private static native boolean isWhitespace0(int codePoint);
+
+    private static native int toLowerCase0(int codePoint);
+
+    private static native int toUpperCase0(int codePoint);
+
+    public static int digit(char ch, int radix) {
+        return digit((int) ch, radix);
+    }
+
+    public static int digit(int codePoint, int radix) {
+        int digit = -1;
+        if (codePoint >= '0' && codePoint <= '9') {
+            digit = codePoint - '0';
+        } else if (codePoint >= 'a' && codePoint <= 'z') {
+            digit = codePoint - 'a' + 10;
+        } else if (codePoint >= 'A' && codePoint <= 'Z') {
+            digit = codePoint - 'A' + 10;
+        }
+        return digit < radix && radix >= MIN_RADIX && radix <= MAX_RADIX ? digit : -1;
+    }
+
+    public static int getNumericValue(char ch) {
+        return digit(ch, MAX_RADIX);
+    }
+
+    public static char forDigit(int digit, int radix) {
+        if (digit < 0 || digit >= radix || radix < MIN_RADIX || radix > MAX_RADIX) {
+            return '\0';
+        }
+        return (char) (digit < 10 ? '0' + digit : 'a' - 10 + digit);
+    }
+
+    public static boolean isHighSurrogate(char ch) {
+        return ch >= MIN_HIGH_SURROGATE && ch <= MAX_HIGH_SURROGATE;
+    }
+
+    public static boolean isLowSurrogate(char ch) {
+        return ch >= MIN_LOW_SURROGATE && ch <= MAX_LOW_SURROGATE;
+    }
+
+    public static boolean isSurrogate(char ch) {
+        return ch >= MIN_HIGH_SURROGATE && ch <= MAX_LOW_SURROGATE;
+    }
+
+    public static int toCodePoint(char high, char low) {
+        return ((high - MIN_HIGH_SURROGATE) << 10) + (low - MIN_LOW_SURROGATE) + MIN_SUPPLEMENTARY_CODE_POINT;
+    }
+
+    public static int charCount(int codePoint) {
+        return codePoint >= MIN_SUPPLEMENTARY_CODE_POINT ? 2 : 1;
+    }
+
+    public static char[] toChars(int codePoint) {
+        if (codePoint < 0 || codePoint > MAX_CODE_POINT) {
+            throw new IllegalArgumentException("Not a valid Unicode code point: 0x" + Integer.toHexString(codePoint).toUpperCase());
+        }
+        if (codePoint < MIN_SUPPLEMENTARY_CODE_POINT) {
+            return new char[] { (char) codePoint };
+        }
+        int offset = codePoint - MIN_SUPPLEMENTARY_CODE_POINT;
+        return new char[] { (char) ((offset >>> 10) + MIN_HIGH_SURROGATE), (char) ((offset & 0x3ff) + MIN_LOW_SURROGATE) };
+    }
+
+    public static String toString(char c) {
+        return String.valueOf(c);
+    }
+
+    public static int compare(char x, char y) {
+        return x - y;
+    }
+
+    public static int hashCode(char value) {
+        return value;
+    }
+
+    public char charValue() {
+        return value;
+    }
+
+    public int compareTo(Character anotherCharacter) {
+        return compare(value, anotherCharacter.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Character && value == ((Character) obj).value;
+    }
+
+    public int hashCode() {
+        return value;
+    }
+
+    public String toString() {
+        return String.valueOf(value);
+    }
+}
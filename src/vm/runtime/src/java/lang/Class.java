@@ -0,0 +1,252 @@
+package java.lang;
+
+import java.lang.reflect.Constructor;
+import java.lang.reflect.Field;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Member;
+import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
+import java.util.Arrays;
+import jdk.internal.reflect.Reflection;
+
+// Instances are made by the VM only, one per loaded class, array class and primitive type
+public final class Class<T> implements java.io.Serializable {
+    private String name;
+
+    private Class() {
+    }
+
+    public String getName() {
+        if (name == null) {
+            name = getName0();
+        }
+        return name;
+    }
+
+    private native String getName0();
+
+    public String getSimpleName() {
+        if (isArray()) {
+            return getComponentType().getSimpleName() + "[]";
+        }
+        String name = getName();
+        return name.substring(Math.max(name.lastIndexOf('.'), name.lastIndexOf('$')) + 1);
+    }
+
+    public String getTypeName() {
+        return isArray() ? getComponentType().getTypeName() + "[]" : getName();
+    }
+
+    public String toString() {
+        return (isInterface() ? "interface " : (isPrimitive() ? "" : "class ")) + getName();
+    }
+
+    public native boolean isInstance(Object obj);
+
+    public native boolean isAssignableFrom(Class<?> cls);
+
+    public native boolean isInterface();
+
+    public native boolean isArray();
+
+    public native boolean isPrimitive();
+
+    public boolean isEnum() {
+        return getSuperclass() == Enum.class && getEnumConstants0() != null;
+    }
+
+    public native Class<? super T> getSuperclass();
+
+    public native Class<?> getComponentType();
+
+    public native Class<?>[] getInterfaces();
+
+    // The InnerClasses flags for a nested class, without ACC_SUPER
+    public native int getModifiers();
+
+    public Field[] getDeclaredFields() {
+        return getDeclaredFields0(false);
+    }
+
+    public Method[] getDeclaredMethods() {
+        return getDeclaredMethods0(false);
+    }
+
+    public Constructor<?>[] getDeclaredConstructors() {
+        return getDeclaredConstructors0(false);
+    }
+
+    public Constructor<?>[] getConstructors() {
+        return getDeclaredConstructors0(true);
+    }
+
+    // The public fields of the class, then of its superinterfaces and then of its superclass
+    public Field[] getFields() {
+        Field[] fields = getDeclaredFields0(true);
+        for (Class<?> interfaceClass : getInterfaces()) {
+            fields = concat(fields, interfaceClass.getFields());
+        }
+        Class<?> superClass = getSuperclass();
+        return superClass == null ? fields : concat(fields, superClass.getFields());
+    }
+
+    // The public methods of the class and the ones it inherits, an interface's static methods
+    // aren't inherited
+    public Method[] getMethods() {
+        Method[] methods = getDeclaredMethods0(true);
+        Class<?> superClass = getSuperclass();
+        if (superClass != null) {
+            methods = inherit(methods, superClass.getMethods(), false);
+        }
+        for (Class<?> interfaceClass : getInterfaces()) {
+            methods = inherit(methods, interfaceClass.getMethods(), true);
+        }
+        return methods;
+    }
+
+    public Field getDeclaredField(String name) throws NoSuchFieldException {
+        return searchFields(getDeclaredFields0(false), name);
+    }
+
+    public Field getField(String name) throws NoSuchFieldException {
+        return searchFields(getFields(), name);
+    }
+
+    public Method getDeclaredMethod(String name, Class<?>... parameterTypes) throws NoSuchMethodException {
+        return searchMethods(getDeclaredMethods0(false), name, parameterTypes);
+    }
+
+    public Method getMethod(String name, Class<?>... parameterTypes) throws NoSuchMethodException {
+        return searchMethods(getMethods(), name, parameterTypes);
+    }
+
+    public Constructor<T> getDeclaredConstructor(Class<?>... parameterTypes) throws NoSuchMethodException {
+        return searchConstructors(getDeclaredConstructors0(false), parameterTypes);
+    }
+
+    public Constructor<T> getConstructor(Class<?>... parameterTypes) throws NoSuchMethodException {
+        return searchConstructors(getDeclaredConstructors0(true), parameterTypes);
+    }
+
+    // Runs the nullary constructor, what it throws passes through unwrapped
+    public T newInstance() throws InstantiationException, IllegalAccessException {
+        Class<?> caller = Reflection.getCallerClass();
+        if (this == Class.class) {
+            throw new IllegalAccessException("Can not call newInstance() on the Class for java.lang.Class");
+        }
+        Constructor<T> constructor;
+        try {
+            constructor = getDeclaredConstructor();
+        } catch (NoSuchMethodException e) {
+            throw (InstantiationException) new InstantiationException(getName()).initCause(e);
+        }
+        Reflection.ensureMemberAccess(caller, this, constructor.getModifiers());
+        constructor.setAccessible(true);
+        try {
+            return constructor.newInstance();
+        } catch (InvocationTargetException e) {
+            Class.<RuntimeException>rethrow(e.getTargetException());
+            return null;
+        }
+    }
+
+    private static <E extends Throwable> void rethrow(Throwable t) throws E {
+        throw (E) t;
+    }
+
+    private native Field[] getDeclaredFields0(boolean publicOnly);
+
+    private native Method[] getDeclaredMethods0(boolean publicOnly);
+
+    private native Constructor<T>[] getDeclaredConstructors0(boolean publicOnly);
+
+    private static Field searchFields(Field[] fields, String name) throws NoSuchFieldException {
+        for (Field field : fields) {
+            if (field.getName().equals(name)) {
+                return field;
+            }
+        }
+        throw new NoSuchFieldException(name);
+    }
+
+    // Of the methods with the name and parameter types, the one with the most specific return
+    // type
+    private Method searchMethods(Method[] methods, String name, Class<?>[] parameterTypes) throws NoSuchMethodException {
+        Class<?>[] types = parameterTypes == null ? new Class<?>[0] : parameterTypes;
+        Method found = null;
+        for (Method method : methods) {
+            if (method.getName().equals(name) && Arrays.equals(method.getParameterTypes(), types)
+                    && (found == null || found.getReturnType().isAssignableFrom(method.getReturnType()))) {
+                found = method;
+            }
+        }
+        if (found == null) {
+            throw new NoSuchMethodException(methodToString(name, types));
+        }
+        return found;
+    }
+
+    private Constructor<T> searchConstructors(Constructor<T>[] constructors, Class<?>[] parameterTypes) throws NoSuchMethodException {
+        Class<?>[] types = parameterTypes == null ? new Class<?>[0] : parameterTypes;
+        for (Constructor<T> constructor : constructors) {
+            if (Arrays.equals(constructor.getParameterTypes(), types)) {
+                return constructor;
+            }
+        }
+        throw new NoSuchMethodException(methodToString("<init>", types));
+    }
+
+    // "Foo.bar(int,java.lang.String)"
+    private String methodToString(String name, Class<?>[] parameterTypes) {
+        StringBuilder sb = new StringBuilder(getName()).append('.').append(name).append('(');
+        for (int i = 0; i < parameterTypes.length; i++) {
+            sb.append(i == 0 ? "" : ",").append(parameterTypes[i] == null ? "null" : parameterTypes[i].getName());
+        }
+        return sb.append(')').toString();
+    }
+
+    // The inherited methods the declared ones don't override
+    private static Method[] inherit(Method[] methods, Method[] inherited, boolean fromInterface) {
+        Method[] result = Arrays.copyOf(methods, methods.length + inherited.length);
+        int count = methods.length;
+        for (Method method : inherited) {
+            boolean overridden = fromInterface && Modifier.isStatic(method.getModifiers());
+            for (int i = 0; !overridden && i < count; i++) {
+                overridden = result[i].getName().equals(method.getName())
+                    && Arrays.equals(result[i].getParameterTypes(), method.getParameterTypes());
+            }
+            if (!overridden) {
+                result[count++] = method;
+            }
+        }
+        return Arrays.copyOf(result, count);
+    }
+
+    private static Field[] concat(Field[] a, Field[] b) {
+        Field[] result = Arrays.copyOf(a, a.length + b.length);
+        System.arraycopy(b, 0, result, a.length, b.length);
+        return result;
+    }
+
+    public T cast(Object obj) {
+        if (obj != null && !isInstance(obj)) {
+            throw new ClassCastException("Cannot cast " + obj.getClass().getName() + " to " + getName());
+        }
+        return (T) obj;
+    }
+
+    // Assertions are always off
+    public boolean desiredAssertionStatus() {
+        return false;
+    }
+
+    public T[] getEnumConstants() {
+        T[] constants = (T[]) getEnumConstants0();
+        return constants == null ? null : constants.clone();
+    }
+
+    // The values of the ACC_ENUM fields, null if this isn't an enum class
+    native Object[] getEnumConstants0();
+
+    static native Class<?> getPrimitiveClass(String name);
+}
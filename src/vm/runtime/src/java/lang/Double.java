@@ -0,0 +1,118 @@
+package java.lang;
+
+public final class Double extends Number implements Comparable<Double> {
+    public static final double POSITIVE_INFINITY = 1.0d / 0.0d;
+    public static final double NEGATIVE_INFINITY = -1.0d / 0.0d;
+    public static final double NaN = 0.0d / 0.0d;
+    public static final double MAX_VALUE = 0x1.fffffffffffffP+1023;
+    public static final double MIN_VALUE = 0x0.0000000000001P-1022;
+    public static final double MIN_NORMAL = 0x1.0p-1022;
+    public static final Class<Double> TYPE = (Class<Double>) Class.getPrimitiveClass("double");
+
+    private final double value;
+
+    public Double(double value) {
+        this.value = value;
+    }
+
+    public static Double valueOf(double value) {
+        return new Double(value);
+    }
+
+    public static Double valueOf(String s) {
+        return new Double(parseDouble(s));
+    }
+
+    // Decimal and the special values, not hexadecimal floating point
+    public static native double parseDouble(String s);
+
+    // The shortest decimal that rounds to the value, in Java's layout
+    public static native String toString(double value);
+
+    public static native long doubleToLongBits(double value);
+
+    public static native long doubleToRawLongBits(double value);
+
+    public static native double longBitsToDouble(long bits);
+
+    public static boolean isNaN(double v) {
+        return v != v;
+    }
+
+    public static boolean isInfinite(double v) {
+        return v == POSITIVE_INFINITY || v == NEGATIVE_INFINITY;
+    }
+
+    public static boolean isFinite(double v) {
+        return Math.abs(v) <= MAX_VALUE;
+    }
+
+    public static int compare(double x, double y) {
+        if (x < y) {
+            return -1;
+        }
+        if (x > y) {
+            return 1;
+        }
+        long xBits = doubleToLongBits(x);
+        long yBits = doubleToLongBits(y);
+        return xBits == yBits ? 0 : (xBits < yBits ? -1 : 1);
+    }
+
+    public static double sum(double a, double b) {
+        return a + b;
+    }
+
+    public static double max(double a, double b) {
+        return Math.max(a, b);
+    }
+
+    public static double min(double a, double b) {
+        return Math.min(a, b);
+    }
+
+    public boolean isNaN() {
+        return isNaN(value);
+    }
+
+    public boolean isInfinite() {
+        return isInfinite(value);
+    }
+
+    public int intValue() {
+        return (int) value;
+    }
+
+    public long longValue() {
+        return (long) value;
+    }
+
+    public float floatValue() {
+        return (float) value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public int compareTo(Double anotherDouble) {
+        return compare(value, anotherDouble.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Double && doubleToLongBits(((Double) obj).value) == doubleToLongBits(value);
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+
+    public static int hashCode(double value) {
+        long bits = doubleToLongBits(value);
+        return (int) (bits ^ (bits >>> 32));
+    }
+
+    public int hashCode() {
+        return hashCode(value);
+    }
+}
@@ -0,0 +1,65 @@
+package java.lang;
+
+public abstract class Enum<E extends Enum<E>> implements Comparable<E>, java.io.Serializable {
+    private final String name;
+    private final int ordinal;
+
+    protected Enum(String name, int ordinal) {
+        this.name = name;
+        this.ordinal = ordinal;
+    }
+
+    public final String name() {
+        return name;
+    }
+
+    public final int ordinal() {
+        return ordinal;
+    }
+
+    public String toString() {
+        return name;
+    }
+
+    public final boolean equals(Object other) {
+        return this == other;
+    }
+
+    public final int hashCode() {
+        return super.hashCode();
+    }
+
+    protected final Object clone() throws CloneNotSupportedException {
+        throw new CloneNotSupportedException();
+    }
+
+    public final int compareTo(E o) {
+        Enum<?> other = o;
+        if (getDeclaringClass() != other.getDeclaringClass()) {
+            throw new ClassCastException();
+        }
+        return ordinal - other.ordinal;
+    }
+
+    public final Class<E> getDeclaringClass() {
+        Class<?> clazz = getClass();
+        Class<?> zuper = clazz.getSuperclass();
+        return (Class<E>) (zuper == Enum.class ? clazz : zuper);
+    }
+
+    public static <T extends Enum<T>> T valueOf(Class<T> enumType, String name) {
+        Object[] constants = enumType.getEnumConstants0();
+        if (constants == null) {
+            throw new IllegalArgumentException(enumType.getName() + " is not an enum class");
+        }
+        if (name == null) {
+            throw new NullPointerException("Name is null");
+        }
+        for (int i = 0; i < constants.length; i++) {
+            if (((Enum<?>) constants[i]).name.equals(name)) {
+                return (T) constants[i];
+            }
+        }
+        throw new IllegalArgumentException("No enum constant " + enumType.getName().replace('$', '.') + "." + name);
+    }
+}
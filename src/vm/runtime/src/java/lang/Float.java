@@ -0,0 +1,117 @@
+package java.lang;
+
+public final class Float extends Number implements Comparable<Float> {
+    public static final float POSITIVE_INFINITY = 1.0f / 0.0f;
+    public static final float NEGATIVE_INFINITY = -1.0f / 0.0f;
+    public static final float NaN = 0.0f / 0.0f;
+    public static final float MAX_VALUE = 0x1.fffffeP+127f;
+    public static final float MIN_VALUE = 0x0.000002P-126f;
+    public static final float MIN_NORMAL = 0x1.0p-126f;
+    public static final Class<Float> TYPE = (Class<Float>) Class.getPrimitiveClass("float");
+
+    private final float value;
+
+    public Float(float value) {
+        this.value = value;
+    }
+
+    public static Float valueOf(float value) {
+        return new Float(value);
+    }
+
+    public static Float valueOf(String s) {
+        return new Float(parseFloat(s));
+    }
+
+    // Decimal and the special values, not hexadecimal floating point
+    public static native float parseFloat(String s);
+
+    // The shortest decimal that rounds to the value, in Java's layout
+    public static native String toString(float value);
+
+    public static native int floatToIntBits(float value);
+
+    public static native int floatToRawIntBits(float value);
+
+    public static native float intBitsToFloat(int bits);
+
+    public static boolean isNaN(float v) {
+        return v != v;
+    }
+
+    public static boolean isInfinite(float v) {
+        return v == POSITIVE_INFINITY || v == NEGATIVE_INFINITY;
+    }
+
+    public static boolean isFinite(float v) {
+        return Math.abs(v) <= MAX_VALUE;
+    }
+
+    public static int compare(float x, float y) {
+        if (x < y) {
+            return -1;
+        }
+        if (x > y) {
+            return 1;
+        }
+        int xBits = floatToIntBits(x);
+        int yBits = floatToIntBits(y);
+        return xBits == yBits ? 0 : (xBits < yBits ? -1 : 1);
+    }
+
+    public static float sum(float a, float b) {
+        return a + b;
+    }
+
+    public static float max(float a, float b) {
+        return Math.max(a, b);
+    }
+
+    public static float min(float a, float b) {
+        return Math.min(a, b);
+    }
+
+    public boolean isNaN() {
+        return isNaN(value);
+    }
+
+    public boolean isInfinite() {
+        return isInfinite(value);
+    }
+
+    public int intValue() {
+        return (int) value;
+    }
+
+    public long longValue() {
+        return (long) value;
+    }
+
+    public float floatValue() {
+        return (float) value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public int compareTo(Float anotherFloat) {
+        return compare(value, anotherFloat.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Float && floatToIntBits(((Float) obj).value) == floatToIntBits(value);
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+
+    public static int hashCode(float value) {
+        return floatToIntBits(value);
+    }
+
+    public int hashCode() {
+        return hashCode(value);
+    }
+}
@@ -0,0 +1,206 @@
+package java.lang;
+
+public final class Long extends Number implements Comparable<Long> {
+    public static final long MIN_VALUE = 0x8000000000000000L;
+    public static final long MAX_VALUE = 0x7fffffffffffffffL;
+    public static final int SIZE = 64;
+    public static final int BYTES = 8;
+    public static final Class<Long> TYPE = (Class<Long>) Class.getPrimitiveClass("long");
+
+    private static final Long[] cache = new Long[256];
+
+    private final long value;
+
+    public Long(long value) {
+        this.value = value;
+    }
+
+    public Long(String s) {
+        this(parseLong(s));
+    }
+
+    public static Long valueOf(long l) {
+        if (l >= -128 && l <= 127) {
+            Long cached = cache[(int) l + 128];
+            if (cached == null) {
+                cached = new Long(l);
+                cache[(int) l + 128] = cached;
+            }
+            return cached;
+        }
+        return new Long(l);
+    }
+
+    public static Long valueOf(String s) {
+        return valueOf(parseLong(s));
+    }
+
+    public static long parseLong(String s) {
+        return parseLong(s, 10);
+    }
+
+    public static long parseLong(String s, int radix) {
+        return parse(s, radix, MIN_VALUE, MAX_VALUE);
+    }
+
+    // The parser of every integral type, throws unless min <= value <= max
+    static long parse(String s, int radix, long min, long max) {
+        if (s == null) {
+            throw new NumberFormatException("Cannot parse null string: null");
+        }
+        if (radix < 2 || radix > 36) {
+            throw new NumberFormatException("radix " + radix + " out of range");
+        }
+        int i = 0;
+        boolean negative = false;
+        if (s.length() > 0 && (s.charAt(0) == '-' || s.charAt(0) == '+')) {
+            negative = s.charAt(0) == '-';
+            i++;
+        }
+        if (i == s.length()) {
+            throw forInputString(s);
+        }
+        // Accumulated negatively so MIN_VALUE doesn't overflow
+        long limit = negative ? min : -max;
+        long multmin = limit / radix;
+        long result = 0;
+        for (; i < s.length(); i++) {
+            int digit = Character.digit(s.charAt(i), radix);
+            if (digit < 0 || result < multmin) {
+                throw forInputString(s);
+            }
+            result *= radix;
+            if (result < limit + digit) {
+                throw forInputString(s);
+            }
+            result -= digit;
+        }
+        return negative ? result : -result;
+    }
+
+    private static NumberFormatException forInputString(String s) {
+        return new NumberFormatException("For input string: \"" + s + "\"");
+    }
+
+    public static String toString(long i) {
+        return toString(i, 10);
+    }
+
+    public static String toString(long i, int radix) {
+        if (radix < 2 || radix > 36) {
+            radix = 10;
+        }
+        if (i == 0) {
+            return "0";
+        }
+        char[] buf = new char[65];
+        int pos = buf.length;
+        boolean negative = i < 0;
+        // Negative digits so MIN_VALUE doesn't overflow
+        if (!negative) {
+            i = -i;
+        }
+        while (i != 0) {
+            buf[--pos] = Character.forDigit((int) -(i % radix), radix);
+            i /= radix;
+        }
+        if (negative) {
+            buf[--pos] = '-';
+        }
+        return new String(buf, pos, buf.length - pos);
+    }
+
+    // shift is the number of bits per digit, 4 for hex
+    static String toUnsignedString(long i, int shift) {
+        char[] buf = new char[64];
+        int pos = buf.length;
+        int mask = (1 << shift) - 1;
+        do {
+            buf[--pos] = Character.forDigit((int) i & mask, 1 << shift);
+            i >>>= shift;
+        } while (i != 0);
+        return new String(buf, pos, buf.length - pos);
+    }
+
+    public static String toHexString(long i) {
+        return toUnsignedString(i, 4);
+    }
+
+    public static String toOctalString(long i) {
+        return toUnsignedString(i, 3);
+    }
+
+    public static String toBinaryString(long i) {
+        return toUnsignedString(i, 1);
+    }
+
+    public static int compare(long x, long y) {
+        return x < y ? -1 : (x == y ? 0 : 1);
+    }
+
+    public static int signum(long i) {
+        return (int) ((i >> 63) | (-i >>> 63));
+    }
+
+    public static long sum(long a, long b) {
+        return a + b;
+    }
+
+    public static long max(long a, long b) {
+        return Math.max(a, b);
+    }
+
+    public static long min(long a, long b) {
+        return Math.min(a, b);
+    }
+
+    public static int hashCode(long value) {
+        return (int) (value ^ (value >>> 32));
+    }
+
+    public static int bitCount(long i) {
+        return Integer.bitCount((int) i) + Integer.bitCount((int) (i >>> 32));
+    }
+
+    public static int numberOfLeadingZeros(long i) {
+        int high = (int) (i >>> 32);
+        return high != 0 ? Integer.numberOfLeadingZeros(high) : 32 + Integer.numberOfLeadingZeros((int) i);
+    }
+
+    public static int numberOfTrailingZeros(long i) {
+        int low = (int) i;
+        return low != 0 ? Integer.numberOfTrailingZeros(low) : 32 + Integer.numberOfTrailingZeros((int) (i >>> 32));
+    }
+
+    public int intValue() {
+        return (int) value;
+    }
+
+    public long longValue() {
+        return value;
+    }
+
+    public float floatValue() {
+        return value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public int compareTo(Long anotherLong) {
+        return compare(value, anotherLong.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Long && value == ((Long) obj).value;
+    }
+
+    public int hashCode() {
+        return hashCode(value);
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+}
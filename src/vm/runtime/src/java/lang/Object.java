@@ -0,0 +1,60 @@
+package java.lang;
+
+public class Object {
+    public Object() {
+    }
+
+    public final native Class<?> getClass();
+
+    public native int hashCode();
+
+    public boolean equals(Object obj) {
+        return this == obj;
+    }
+
+    // Arrays and Cloneable objects only, a shallow copy
+    protected native Object clone() throws CloneNotSupportedException;
+
+    public String toString() {
+        return getClass().getName() + "@" + Integer.toHexString(hashCode());
+    }
+
+    public final native void notify();
+
+    public final native void notifyAll();
+
+    public final void wait() throws InterruptedException {
+        wait(0);
+    }
+
+    // 0 waits until notify
+    public final void wait(long timeoutMillis) throws InterruptedException {
+        if (timeoutMillis < 0) {
+            throw new IllegalArgumentException("timeout value is negative");
+        }
+        wait0(timeoutMillis);
+        if (Thread.interrupted()) {
+            throw new InterruptedException();
+        }
+    }
+
+    public final void wait(long timeoutMillis, int nanos) throws InterruptedException {
+        if (timeoutMillis < 0) {
+            throw new IllegalArgumentException("timeout value is negative");
+        }
+        if (nanos < 0 || nanos > 999999) {
+            throw new IllegalArgumentException("nanosecond timeout value out of range");
+        }
+        if (nanos > 0 && timeoutMillis < Long.MAX_VALUE) {
+            timeoutMillis++;
+        }
+        wait(timeoutMillis);
+    }
+
+    // Leaves the monitor and puts the current thread to wait once the native returns, it is back
+    // in the monitor by the time it runs again
+    private final native void wait0(long timeoutMillis);
+
+    protected void finalize() throws Throwable {
+    }
+}
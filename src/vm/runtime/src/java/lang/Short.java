@@ -0,0 +1,84 @@
+package java.lang;
+
+public final class Short extends Number implements Comparable<Short> {
+    public static final short MIN_VALUE = -32768;
+    public static final short MAX_VALUE = 32767;
+    public static final int SIZE = 16;
+    public static final Class<Short> TYPE = (Class<Short>) Class.getPrimitiveClass("short");
+
+    private static final Short[] cache = new Short[256];
+
+    private final short value;
+
+    public Short(short value) {
+        this.value = value;
+    }
+
+    public static Short valueOf(short value) {
+        if (value >= -128 && value <= 127) {
+            Short cached = cache[value + 128];
+            if (cached == null) {
+                cached = new Short(value);
+                cache[value + 128] = cached;
+            }
+            return cached;
+        }
+        return new Short(value);
+    }
+
+    public static Short valueOf(String s) {
+        return valueOf(parseShort(s));
+    }
+
+    public static short parseShort(String s) {
+        return parseShort(s, 10);
+    }
+
+    public static short parseShort(String s, int radix) {
+        return (short) Long.parse(s, radix, MIN_VALUE, MAX_VALUE);
+    }
+
+    public static String toString(short value) {
+        return Integer.toString(value);
+    }
+
+    public static int compare(short x, short y) {
+        return x - y;
+    }
+
+    public static int hashCode(short value) {
+        return value;
+    }
+
+    public int intValue() {
+        return value;
+    }
+
+    public long longValue() {
+        return value;
+    }
+
+    public float floatValue() {
+        return value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public int compareTo(Short another) {
+        return compare(value, another.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Short && value == ((Short) obj).value;
+    }
+
+    public int hashCode() {
+        return value;
+    }
+
+    public String toString() {
+        return Integer.toString(value);
+    }
+}
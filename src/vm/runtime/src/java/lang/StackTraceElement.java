@@ -0,0 +1,64 @@
+package java.lang;
+
+public final class StackTraceElement implements java.io.Serializable {
+    private String declaringClass;
+    private String methodName;
+    private String fileName;
+    // -1 when unknown, -2 for a native method
+    private int lineNumber;
+
+    public StackTraceElement(String declaringClass, String methodName, String fileName, int lineNumber) {
+        this.declaringClass = declaringClass;
+        this.methodName = methodName;
+        this.fileName = fileName;
+        this.lineNumber = lineNumber;
+    }
+
+    public String getClassName() {
+        return declaringClass;
+    }
+
+    public String getMethodName() {
+        return methodName;
+    }
+
+    public String getFileName() {
+        return fileName;
+    }
+
+    public int getLineNumber() {
+        return lineNumber;
+    }
+
+    public boolean isNativeMethod() {
+        return lineNumber == -2;
+    }
+
+    public boolean equals(Object obj) {
+        if (!(obj instanceof StackTraceElement)) {
+            return false;
+        }
+        StackTraceElement other = (StackTraceElement) obj;
+        return declaringClass.equals(other.declaringClass) && methodName.equals(other.methodName)
+            && (fileName == null ? other.fileName == null : fileName.equals(other.fileName))
+            && lineNumber == other.lineNumber;
+    }
+
+    public int hashCode() {
+        return 31 * declaringClass.hashCode() + methodName.hashCode() + lineNumber;
+    }
+
+    public String toString() {
+        String location;
+        if (isNativeMethod()) {
+            location = "Native Method";
+        } else if (fileName == null) {
+            location = "Unknown Source";
+        } else if (lineNumber >= 0) {
+            location = fileName + ":" + lineNumber;
+        } else {
+            location = fileName;
+        }
+        return declaringClass + "." + methodName + "(" + location + ")";
+    }
+}
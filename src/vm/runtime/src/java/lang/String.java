@@ -0,0 +1,431 @@
+package java.lang;
+
+// UTF-16 in a char[], the Java 8 layout
+public final class String implements java.io.Serializable, Comparable<String>, CharSequence {
+    private final char[] value;
+    private int hash;
+
+    public String() {
+        value = new char[0];
+    }
+
+    public String(String original) {
+        value = original.value;
+        hash = original.hash;
+    }
+
+    public String(char[] value) {
+        this(value, 0, value.length);
+    }
+
+    public String(char[] value, int offset, int count) {
+        if (offset < 0 || count < 0 || offset > value.length - count) {
+            throw new StringIndexOutOfBoundsException("offset " + offset + ", count " + count + ", length " + value.length);
+        }
+        this.value = new char[count];
+        System.arraycopy(value, offset, this.value, 0, count);
+    }
+
+    public String(StringBuilder builder) {
+        this(builder.getValue(), 0, builder.length());
+    }
+
+    // Decodes UTF-8, the only charset the runtime has
+    public String(byte[] bytes) {
+        value = decodeUtf8(bytes);
+    }
+
+    private static native char[] decodeUtf8(byte[] bytes);
+
+    public byte[] getBytes() {
+        return encodeUtf8(value);
+    }
+
+    private static native byte[] encodeUtf8(char[] value);
+
+    public int length() {
+        return value.length;
+    }
+
+    public boolean isEmpty() {
+        return value.length == 0;
+    }
+
+    public char charAt(int index) {
+        if (index < 0 || index >= value.length) {
+            throw new StringIndexOutOfBoundsException("index " + index + ", length " + value.length);
+        }
+        return value[index];
+    }
+
+    public int codePointAt(int index) {
+        char high = charAt(index);
+        if (Character.isHighSurrogate(high) && index + 1 < value.length && Character.isLowSurrogate(value[index + 1])) {
+            return Character.toCodePoint(high, value[index + 1]);
+        }
+        return high;
+    }
+
+    public void getChars(int srcBegin, int srcEnd, char[] dst, int dstBegin) {
+        checkRange(srcBegin, srcEnd);
+        System.arraycopy(value, srcBegin, dst, dstBegin, srcEnd - srcBegin);
+    }
+
+    public char[] toCharArray() {
+        char[] chars = new char[value.length];
+        System.arraycopy(value, 0, chars, 0, value.length);
+        return chars;
+    }
+
+    public boolean equals(Object anObject) {
+        if (this == anObject) {
+            return true;
+        }
+        if (!(anObject instanceof String)) {
+            return false;
+        }
+        char[] other = ((String) anObject).value;
+        if (other.length != value.length) {
+            return false;
+        }
+        for (int i = 0; i < value.length; i++) {
+            if (value[i] != other[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public boolean equalsIgnoreCase(String anotherString) {
+        return anotherString != null && anotherString.length() == length() && compareToIgnoreCase(anotherString) == 0;
+    }
+
+    public boolean contentEquals(CharSequence cs) {
+        return equals(cs.toString());
+    }
+
+    public int hashCode() {
+        int h = hash;
+        if (h == 0) {
+            for (int i = 0; i < value.length; i++) {
+                h = 31 * h + value[i];
+            }
+            hash = h;
+        }
+        return h;
+    }
+
+    public int compareTo(String anotherString) {
+        int limit = Math.min(value.length, anotherString.value.length);
+        for (int i = 0; i < limit; i++) {
+            if (value[i] != anotherString.value[i]) {
+                return value[i] - anotherString.value[i];
+            }
+        }
+        return value.length - anotherString.value.length;
+    }
+
+    public int compareToIgnoreCase(String str) {
+        int limit = Math.min(value.length, str.value.length);
+        for (int i = 0; i < limit; i++) {
+            char c1 = Character.toLowerCase(Character.toUpperCase(value[i]));
+            char c2 = Character.toLowerCase(Character.toUpperCase(str.value[i]));
+            if (c1 != c2) {
+                return c1 - c2;
+            }
+        }
+        return value.length - str.value.length;
+    }
+
+    public boolean startsWith(String prefix, int toffset) {
+        if (toffset < 0 || toffset > value.length - prefix.value.length) {
+            return false;
+        }
+        for (int i = 0; i < prefix.value.length; i++) {
+            if (value[toffset + i] != prefix.value[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public boolean startsWith(String prefix) {
+        return startsWith(prefix, 0);
+    }
+
+    public boolean endsWith(String suffix) {
+        return startsWith(suffix, value.length - suffix.value.length);
+    }
+
+    public int indexOf(int ch) {
+        return indexOf(ch, 0);
+    }
+
+    public int indexOf(int ch, int fromIndex) {
+        for (int i = Math.max(fromIndex, 0); i < value.length; i++) {
+            if (value[i] == ch) {
+                return i;
+            }
+        }
+        return -1;
+    }
+
+    public int lastIndexOf(int ch) {
+        return lastIndexOf(ch, value.length - 1);
+    }
+
+    public int lastIndexOf(int ch, int fromIndex) {
+        for (int i = Math.min(fromIndex, value.length - 1); i >= 0; i--) {
+            if (value[i] == ch) {
+                return i;
+            }
+        }
+        return -1;
+    }
+
+    public int indexOf(String str) {
+        return indexOf(str, 0);
+    }
+
+    public int indexOf(String str, int fromIndex) {
+        for (int i = Math.max(fromIndex, 0); i <= value.length - str.value.length; i++) {
+            if (startsWith(str, i)) {
+                return i;
+            }
+        }
+        return -1;
+    }
+
+    public int lastIndexOf(String str) {
+        for (int i = value.length - str.value.length; i >= 0; i--) {
+            if (startsWith(str, i)) {
+                return i;
+            }
+        }
+        return -1;
+    }
+
+    public boolean contains(CharSequence s) {
+        return indexOf(s.toString()) >= 0;
+    }
+
+    public String substring(int beginIndex) {
+        return substring(beginIndex, value.length);
+    }
+
+    public String substring(int beginIndex, int endIndex) {
+        checkRange(beginIndex, endIndex);
+        if (beginIndex == 0 && endIndex == value.length) {
+            return this;
+        }
+        return new String(value, beginIndex, endIndex - beginIndex);
+    }
+
+    public CharSequence subSequence(int beginIndex, int endIndex) {
+        return substring(beginIndex, endIndex);
+    }
+
+    public String concat(String str) {
+        if (str.isEmpty()) {
+            return this;
+        }
+        char[] chars = new char[value.length + str.value.length];
+        System.arraycopy(value, 0, chars, 0, value.length);
+        System.arraycopy(str.value, 0, chars, value.length, str.value.length);
+        return new String(chars);
+    }
+
+    public String replace(char oldChar, char newChar) {
+        char[] chars = toCharArray();
+        for (int i = 0; i < chars.length; i++) {
+            if (chars[i] == oldChar) {
+                chars[i] = newChar;
+            }
+        }
+        return new String(chars);
+    }
+
+    public String replace(CharSequence target, CharSequence replacement) {
+        String from = target.toString();
+        String to = replacement.toString();
+        StringBuilder sb = new StringBuilder();
+        int start = 0;
+        if (from.isEmpty()) {
+            for (int i = 0; i < value.length; i++) {
+                sb.append(to).append(value[i]);
+            }
+            return sb.append(to).toString();
+        }
+        int index;
+        while ((index = indexOf(from, start)) >= 0) {
+            sb.append(value, start, index - start).append(to);
+            start = index + from.length();
+        }
+        return sb.append(value, start, value.length - start).toString();
+    }
+
+    // Only literal separators, or a metacharacter escaped with a backslash, regular expressions
+    // aren't supported
+    public String[] split(String regex) {
+        String separator = regex;
+        if (regex.length() == 2 && regex.charAt(0) == '\\' && !Character.isLetterOrDigit(regex.charAt(1))) {
+            separator = regex.substring(1);
+        } else {
+            for (int i = 0; i < regex.length(); i++) {
+                if (".$|()[{^?*+\\".indexOf(regex.charAt(i)) >= 0) {
+                    throw new UnsupportedOperationException("regular expression " + regex);
+                }
+            }
+        }
+        if (value.length == 0) {
+            return new String[] { this };
+        }
+        String[] parts = new String[value.length + 1];
+        int count = 0;
+        if (separator.isEmpty()) {
+            for (int i = 0; i < value.length; i++) {
+                parts[count++] = substring(i, i + 1);
+            }
+        } else {
+            int start = 0;
+            int index;
+            while ((index = indexOf(separator, start)) >= 0) {
+                parts[count++] = substring(start, index);
+                start = index + separator.length();
+            }
+            parts[count++] = substring(start);
+        }
+        while (count > 0 && parts[count - 1].isEmpty()) {
+            count--;
+        }
+        String[] result = new String[count];
+        System.arraycopy(parts, 0, result, 0, count);
+        return result;
+    }
+
+    public String trim() {
+        int begin = 0;
+        int end = value.length;
+        while (begin < end && value[begin] <= ' ') {
+            begin++;
+        }
+        while (end > begin && value[end - 1] <= ' ') {
+            end--;
+        }
+        return substring(begin, end);
+    }
+
+    public String strip() {
+        int begin = 0;
+        int end = value.length;
+        while (begin < end && Character.isWhitespace(value[begin])) {
+            begin++;
+        }
+        while (end > begin && Character.isWhitespace(value[end - 1])) {
+            end--;
+        }
+        return substring(begin, end);
+    }
+
+    public boolean isBlank() {
+        return strip().isEmpty();
+    }
+
+    public String toLowerCase() {
+        char[] chars = toCharArray();
+        for (int i = 0; i < chars.length; i++) {
+            chars[i] = Character.toLowerCase(chars[i]);
+        }
+        return new String(chars);
+    }
+
+    public String toUpperCase() {
+        char[] chars = toCharArray();
+        for (int i = 0; i < chars.length; i++) {
+            chars[i] = Character.toUpperCase(chars[i]);
+        }
+        return new String(chars);
+    }
+
+    public String repeat(int count) {
+        if (count < 0) {
+            throw new IllegalArgumentException("count is negative: " + count);
+        }
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < count; i++) {
+            sb.append(this);
+        }
+        return sb.toString();
+    }
+
+    public String toString() {
+        return this;
+    }
+
+    public native String intern();
+
+    public static String join(CharSequence delimiter, CharSequence... elements) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < elements.length; i++) {
+            if (i > 0) {
+                sb.append(delimiter);
+            }
+            sb.append(elements[i]);
+        }
+        return sb.toString();
+    }
+
+    public static String format(String format, Object... args) {
+        return new java.util.Formatter().format(format, args).toString();
+    }
+
+    public static String valueOf(Object obj) {
+        return obj == null ? "null" : obj.toString();
+    }
+
+    public static String valueOf(char[] data) {
+        return new String(data);
+    }
+
+    public static String valueOf(char[] data, int offset, int count) {
+        return new String(data, offset, count);
+    }
+
+    public static String copyValueOf(char[] data) {
+        return new String(data);
+    }
+
+    public static String copyValueOf(char[] data, int offset, int count) {
+        return new String(data, offset, count);
+    }
+
+    public static String valueOf(boolean b) {
+        return b ? "true" : "false";
+    }
+
+    public static String valueOf(char c) {
+        return new String(new char[] { c });
+    }
+
+    public static String valueOf(int i) {
+        return Integer.toString(i);
+    }
+
+    public static String valueOf(long l) {
+        return Long.toString(l);
+    }
+
+    public static String valueOf(float f) {
+        return Float.toString(f);
+    }
+
+    public static String valueOf(double d) {
+        return Double.toString(d);
+    }
+
+    private void checkRange(int beginIndex, int endIndex) {
+        if (beginIndex < 0 || beginIndex > endIndex || endIndex > value.length) {
+            throw new StringIndexOutOfBoundsException("begin " + beginIndex + ", end " + endIndex + ", length " + value.length);
+        }
+    }
+}
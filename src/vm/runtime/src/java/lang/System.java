@@ -0,0 +1,73 @@
+package java.lang;
+
+import java.io.OutputStream;
+import java.io.PrintStream;
+
+public final class System {
+    public static final PrintStream out = new PrintStream(new StandardStream(1), true);
+    public static final PrintStream err = new PrintStream(new StandardStream(2), true);
+
+    private System() {
+    }
+
+    public static native long currentTimeMillis();
+
+    public static native long nanoTime();
+
+    public static native void arraycopy(Object src, int srcPos, Object dest, int destPos, int length);
+
+    public static native int identityHashCode(Object x);
+
+    // Stops every thread, the status is what the VM returns
+    public static native void exit(int status);
+
+    public static String lineSeparator() {
+        return "\n";
+    }
+
+    public static String getProperty(String key) {
+        if (key.equals("line.separator")) {
+            return "\n";
+        }
+        if (key.equals("file.separator")) {
+            return "/";
+        }
+        if (key.equals("path.separator")) {
+            return ":";
+        }
+        return null;
+    }
+
+    public static String getProperty(String key, String def) {
+        String value = getProperty(key);
+        return value == null ? def : value;
+    }
+
+    // Writes to the VM's stdout or stderr
+    private static final class StandardStream extends OutputStream {
+        private final int fd;
+
+        StandardStream(int fd) {
+            this.fd = fd;
+        }
+
+        public void write(int b) {
+            write(new byte[] { (byte) b }, 0, 1);
+        }
+
+        public void write(byte[] b, int off, int len) {
+            if (off < 0 || len < 0 || off > b.length - len) {
+                throw new IndexOutOfBoundsException();
+            }
+            write0(fd, b, off, len);
+        }
+
+        public void flush() {
+            flush0(fd);
+        }
+
+        private static native void write0(int fd, byte[] b, int off, int len);
+
+        private static native void flush0(int fd);
+    }
+}
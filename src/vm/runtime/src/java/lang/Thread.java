@@ -0,0 +1,200 @@
+package java.lang;
+
+// Green threads: the VM switches between them on one OS thread, a time slice at a time. There are no
+// thread groups, every thread is in "main"
+public class Thread implements Runnable {
+    public static final int MIN_PRIORITY = 1;
+    public static final int NORM_PRIORITY = 5;
+    public static final int MAX_PRIORITY = 10;
+
+    private static int threadInitNumber;
+    private static long threadSeqNumber = 1;
+    private static volatile UncaughtExceptionHandler defaultUncaughtExceptionHandler;
+
+    private volatile String name;
+    private int priority;
+    private boolean daemon;
+    private final Runnable target;
+    private final long tid;
+    // Index of the VM's thread plus one, 0 until the thread is started
+    private long eetop;
+    private volatile boolean interrupted;
+    private volatile UncaughtExceptionHandler uncaughtExceptionHandler;
+
+    public Thread() {
+        this(null, "Thread-" + threadInitNumber++);
+    }
+
+    public Thread(Runnable target) {
+        this(target, "Thread-" + threadInitNumber++);
+    }
+
+    public Thread(String name) {
+        this(null, name);
+    }
+
+    public Thread(Runnable target, String name) {
+        if (name == null) {
+            throw new NullPointerException("name cannot be null");
+        }
+        Thread parent = currentThread();
+        this.name = name;
+        this.target = target;
+        this.priority = parent.getPriority();
+        this.daemon = parent.isDaemon();
+        this.tid = ++threadSeqNumber;
+    }
+
+    public static native Thread currentThread();
+
+    public static native void yield();
+
+    public static native boolean holdsLock(Object obj);
+
+    public static void sleep(long millis) throws InterruptedException {
+        if (millis < 0) {
+            throw new IllegalArgumentException("timeout value is negative");
+        }
+        sleep0(millis);
+        if (interrupted()) {
+            throw new InterruptedException("sleep interrupted");
+        }
+    }
+
+    // Puts the current thread to sleep once the native returns, unless it is interrupted
+    private static native void sleep0(long millis);
+
+    public void start() {
+        if (eetop != 0) {
+            throw new IllegalThreadStateException();
+        }
+        start0();
+    }
+
+    private native void start0();
+
+    public void run() {
+        if (target != null) {
+            target.run();
+        }
+    }
+
+    public final native boolean isAlive();
+
+    public final void join() throws InterruptedException {
+        join(0);
+    }
+
+    // 0 waits for as long as the thread runs
+    public final void join(long millis) throws InterruptedException {
+        if (millis < 0) {
+            throw new IllegalArgumentException("timeout value is negative");
+        }
+        join0(millis);
+        if (interrupted()) {
+            throw new InterruptedException();
+        }
+    }
+
+    // Blocks the current thread until this one ends, unless it is interrupted
+    private native void join0(long millis);
+
+    public void interrupt() {
+        interrupted = true;
+        interrupt0();
+    }
+
+    // Wakes the thread up if it sleeps, joins, waits or is parked
+    private native void interrupt0();
+
+    public boolean isInterrupted() {
+        return interrupted;
+    }
+
+    public static boolean interrupted() {
+        Thread thread = currentThread();
+        boolean interrupted = thread.interrupted;
+        thread.interrupted = false;
+        return interrupted;
+    }
+
+    public final String getName() {
+        return name;
+    }
+
+    public final void setName(String name) {
+        if (name == null) {
+            throw new NullPointerException("name cannot be null");
+        }
+        this.name = name;
+    }
+
+    public long getId() {
+        return tid;
+    }
+
+    public final int getPriority() {
+        return priority;
+    }
+
+    // Only recorded, the scheduler gives every thread the same slice
+    public final void setPriority(int newPriority) {
+        if (newPriority > MAX_PRIORITY || newPriority < MIN_PRIORITY) {
+            throw new IllegalArgumentException();
+        }
+        priority = newPriority;
+    }
+
+    public final boolean isDaemon() {
+        return daemon;
+    }
+
+    // The VM exits once every thread that isn't a daemon has ended
+    public final void setDaemon(boolean on) {
+        if (isAlive()) {
+            throw new IllegalThreadStateException();
+        }
+        daemon = on;
+    }
+
+    // null when none was set, there is no thread group to fall back on
+    public UncaughtExceptionHandler getUncaughtExceptionHandler() {
+        return uncaughtExceptionHandler;
+    }
+
+    public void setUncaughtExceptionHandler(UncaughtExceptionHandler handler) {
+        uncaughtExceptionHandler = handler;
+    }
+
+    public static UncaughtExceptionHandler getDefaultUncaughtExceptionHandler() {
+        return defaultUncaughtExceptionHandler;
+    }
+
+    public static void setDefaultUncaughtExceptionHandler(UncaughtExceptionHandler handler) {
+        defaultUncaughtExceptionHandler = handler;
+    }
+
+    // Called by the VM with what the thread threw, what ThreadGroup.uncaughtException does in the JDK
+    private void dispatchUncaughtException(Throwable e) {
+        UncaughtExceptionHandler handler = uncaughtExceptionHandler;
+        if (handler == null) {
+            handler = defaultUncaughtExceptionHandler;
+        }
+        if (handler != null) {
+            handler.uncaughtException(this, e);
+        } else {
+            System.err.print("Exception in thread \"" + name + "\" ");
+            e.printStackTrace();
+        }
+    }
+
+    public String toString() {
+        // The JDK drops a thread from its group when it ends
+        String group = eetop != 0 && !isAlive() ? "" : "main";
+        return "Thread[" + name + "," + priority + "," + group + "]";
+    }
+
+    public interface UncaughtExceptionHandler {
+        void uncaughtException(Thread t, Throwable e);
+    }
+}
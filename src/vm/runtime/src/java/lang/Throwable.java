@@ -0,0 +1,143 @@
+package java.lang;
+
+import java.io.PrintStream;
+
+public class Throwable implements java.io.Serializable {
+    private String detailMessage;
+    // Set once, by a constructor or initCause
+    private Throwable cause;
+    private boolean causeSet;
+    // Filled by the VM, innermost frame first
+    private StackTraceElement[] stackTrace;
+    private Throwable[] suppressed;
+
+    public Throwable() {
+        fillInStackTrace();
+    }
+
+    public Throwable(String message) {
+        fillInStackTrace();
+        detailMessage = message;
+    }
+
+    public Throwable(String message, Throwable cause) {
+        fillInStackTrace();
+        detailMessage = message;
+        this.cause = cause;
+        causeSet = true;
+    }
+
+    public Throwable(Throwable cause) {
+        this(cause == null ? null : cause.toString(), cause);
+    }
+
+    protected Throwable(String message, Throwable cause, boolean enableSuppression, boolean writableStackTrace) {
+        this(message, cause);
+    }
+
+    public String getMessage() {
+        return detailMessage;
+    }
+
+    public String getLocalizedMessage() {
+        return getMessage();
+    }
+
+    public Throwable getCause() {
+        return cause;
+    }
+
+    public Throwable initCause(Throwable cause) {
+        if (causeSet) {
+            throw new IllegalStateException("Can't overwrite cause with " + cause, this);
+        }
+        if (cause == this) {
+            throw new IllegalArgumentException("Self-causation not permitted", this);
+        }
+        this.cause = cause;
+        causeSet = true;
+        return this;
+    }
+
+    public native Throwable fillInStackTrace();
+
+    public StackTraceElement[] getStackTrace() {
+        return stackTrace == null ? new StackTraceElement[0] : stackTrace.clone();
+    }
+
+    public void setStackTrace(StackTraceElement[] stackTrace) {
+        this.stackTrace = stackTrace.clone();
+    }
+
+    public final void addSuppressed(Throwable exception) {
+        if (exception == this) {
+            throw new IllegalArgumentException("Self-suppression not permitted", exception);
+        }
+        if (exception == null) {
+            throw new NullPointerException("Cannot suppress a null exception.");
+        }
+        Throwable[] grown = new Throwable[suppressed == null ? 1 : suppressed.length + 1];
+        for (int i = 0; i < grown.length - 1; i++) {
+            grown[i] = suppressed[i];
+        }
+        grown[grown.length - 1] = exception;
+        suppressed = grown;
+    }
+
+    public final Throwable[] getSuppressed() {
+        return suppressed == null ? new Throwable[0] : suppressed.clone();
+    }
+
+    public String toString() {
+        String name = getClass().getName();
+        String message = getLocalizedMessage();
+        return message != null ? name + ": " + message : name;
+    }
+
+    public void printStackTrace() {
+        printStackTrace(System.err);
+    }
+
+    public void printStackTrace(PrintStream s) {
+        s.println(this);
+        StackTraceElement[] trace = getStackTrace();
+        for (int i = 0; i < trace.length; i++) {
+            s.println("\tat " + trace[i]);
+        }
+        Throwable[] suppressed = getSuppressed();
+        for (int i = 0; i < suppressed.length; i++) {
+            suppressed[i].printEnclosedStackTrace(s, trace, "Suppressed: ", "\t");
+        }
+        Throwable cause = getCause();
+        if (cause != null) {
+            cause.printEnclosedStackTrace(s, trace, "Caused by: ", "");
+        }
+    }
+
+    // Frames in common with the enclosing trace are left out, like the JDK does
+    private void printEnclosedStackTrace(PrintStream s, StackTraceElement[] enclosingTrace, String caption, String prefix) {
+        StackTraceElement[] trace = getStackTrace();
+        int m = trace.length - 1;
+        int n = enclosingTrace.length - 1;
+        while (m >= 0 && n >= 0 && trace[m].equals(enclosingTrace[n])) {
+            m--;
+            n--;
+        }
+        int framesInCommon = trace.length - 1 - m;
+        s.println(prefix + caption + this);
+        for (int i = 0; i <= m; i++) {
+            s.println(prefix + "\tat " + trace[i]);
+        }
+        if (framesInCommon != 0) {
+            s.println(prefix + "\t... " + framesInCommon + " more");
+        }
+        Throwable[] suppressed = getSuppressed();
+        for (int i = 0; i < suppressed.length; i++) {
+            suppressed[i].printEnclosedStackTrace(s, trace, "Suppressed: ", prefix + "\t");
+        }
+        Throwable cause = getCause();
+        if (cause != null) {
+            cause.printEnclosedStackTrace(s, trace, "Caused by: ", prefix);
+        }
+    }
+}
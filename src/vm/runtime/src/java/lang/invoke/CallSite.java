@@ -0,0 +1,56 @@
+package java.lang.invoke;
+
+// The target of an invokedynamic instruction. The VM links the instruction to the call site the
+// bootstrap method returns and from then on runs whatever `target` is with invokeExact
+public abstract class CallSite {
+    MethodHandle target;
+
+    CallSite(MethodType type) {
+        target = new Uninitialized(type);
+    }
+
+    CallSite(MethodHandle target) {
+        target.type(); // null check
+        this.target = target;
+    }
+
+    public MethodType type() {
+        return target.type();
+    }
+
+    public abstract MethodHandle getTarget();
+
+    public abstract void setTarget(MethodHandle newTarget);
+
+    public abstract MethodHandle dynamicInvoker();
+
+    final void checkTargetType(MethodHandle newTarget) {
+        if (!newTarget.type().equals(type())) {
+            throw new WrongMethodTypeException(newTarget + " should be of type " + type());
+        }
+    }
+
+    // Runs the target the call site has when it's called
+    static final class DynamicInvoker extends MethodHandle {
+        private final CallSite site;
+
+        DynamicInvoker(CallSite site) {
+            super(site.type());
+            this.site = site;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            return site.getTarget().invokeBoxed(args);
+        }
+    }
+
+    static final class Uninitialized extends MethodHandle {
+        Uninitialized(MethodType type) {
+            super(type);
+        }
+
+        Object invokeBoxed(Object[] args) {
+            throw new IllegalStateException("uninitialized call site");
+        }
+    }
+}
@@ -0,0 +1,32 @@
+package java.lang.invoke;
+
+// Bootstrap methods for Dynamic constants
+public final class ConstantBootstraps {
+    private ConstantBootstraps() {
+    }
+
+    public static Object nullConstant(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        if (type.isPrimitive()) {
+            throw new IllegalArgumentException(type + " is a primitive class");
+        }
+        return null;
+    }
+
+    // `name` is the descriptor of the primitive type
+    public static Class<?> primitiveClass(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        Class<?> primitive = name.length() == 1 ? Wrapper.forDescriptor(name.charAt(0)) : null;
+        if (primitive == null) {
+            throw new IllegalArgumentException("not primitive: " + name);
+        }
+        return primitive;
+    }
+
+    // What the handle returns for the arguments, as a `type`
+    public static Object invoke(MethodHandles.Lookup lookup, String name, Class<?> type, MethodHandle handle, Object... args)
+            throws Throwable {
+        if (type != handle.type().returnType()) {
+            handle = handle.asType(handle.type().changeReturnType(type));
+        }
+        return handle.invokeWithArguments(args);
+    }
+}
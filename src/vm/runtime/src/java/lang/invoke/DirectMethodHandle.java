@@ -0,0 +1,28 @@
+package java.lang.invoke;
+
+// A handle of a field, method or constructor, made by the VM. The VM keeps which member it is
+final class DirectMethodHandle extends MethodHandle {
+    // One of the MethodHandleInfo.REF_ kinds
+    private final int kind;
+    private Invoker invoker;
+
+    private DirectMethodHandle(MethodType type, int kind) {
+        super(type);
+        this.kind = kind;
+    }
+
+    // A class the VM makes for the member, it unboxes the arguments, uses the member and boxes
+    // the result
+    interface Invoker {
+        Object invoke(Object[] args) throws Throwable;
+    }
+
+    Object invokeBoxed(Object[] args) throws Throwable {
+        if (invoker == null) {
+            invoker = makeInvoker();
+        }
+        return invoker.invoke(args);
+    }
+
+    private native Invoker makeInvoker();
+}
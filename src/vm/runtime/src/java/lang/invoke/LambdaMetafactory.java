@@ -0,0 +1,93 @@
+package java.lang.invoke;
+
+import java.io.Serializable;
+
+// The bootstrap methods of lambdas and method references. The VM spins a class implementing the
+// functional interface whose method calls the implementation with the captured arguments first,
+// the call site makes instances of it. Lambdas that capture nothing get the same instance each time
+public final class LambdaMetafactory {
+    public static final int FLAG_SERIALIZABLE = 1 << 0;
+    public static final int FLAG_MARKERS = 1 << 1;
+    public static final int FLAG_BRIDGES = 1 << 2;
+
+    private static final Class<?>[] NO_CLASSES = new Class<?>[0];
+    private static final MethodType[] NO_TYPES = new MethodType[0];
+
+    private LambdaMetafactory() {
+    }
+
+    public static CallSite metafactory(MethodHandles.Lookup caller, String interfaceMethodName, MethodType factoryType,
+            MethodType interfaceMethodType, MethodHandle implementation, MethodType dynamicMethodType) throws LambdaConversionException {
+        return spin(caller, interfaceMethodName, factoryType, interfaceMethodType, implementation, dynamicMethodType, NO_CLASSES, NO_TYPES);
+    }
+
+    // `args` has the interface method type, the implementation, the dynamic method type and the
+    // flags, then the marker interfaces and the bridge method types, each after their count
+    public static CallSite altMetafactory(MethodHandles.Lookup caller, String interfaceMethodName, MethodType factoryType,
+            Object... args) throws LambdaConversionException {
+        int flags = (Integer) args[3];
+        int next = 4;
+        Class<?>[] markers = NO_CLASSES;
+        if ((flags & FLAG_MARKERS) != 0) {
+            markers = new Class<?>[(Integer) args[next++]];
+            for (int i = 0; i < markers.length; i++) {
+                markers[i] = (Class<?>) args[next++];
+            }
+        }
+        if ((flags & FLAG_SERIALIZABLE) != 0) {
+            Class<?>[] serializable = new Class<?>[markers.length + 1];
+            System.arraycopy(markers, 0, serializable, 0, markers.length);
+            serializable[markers.length] = Serializable.class;
+            markers = serializable;
+        }
+        MethodType[] bridges = NO_TYPES;
+        if ((flags & FLAG_BRIDGES) != 0) {
+            bridges = new MethodType[(Integer) args[next++]];
+            for (int i = 0; i < bridges.length; i++) {
+                bridges[i] = (MethodType) args[next++];
+            }
+        }
+        return spin(caller, interfaceMethodName, factoryType, (MethodType) args[0], (MethodHandle) args[1], (MethodType) args[2],
+            markers, bridges);
+    }
+
+    private static CallSite spin(MethodHandles.Lookup caller, String interfaceMethodName, MethodType factoryType,
+            MethodType interfaceMethodType, MethodHandle implementation, MethodType dynamicMethodType,
+            Class<?>[] markers, MethodType[] bridges) throws LambdaConversionException {
+        Class<?> functionalInterface = factoryType.returnType();
+        if (!functionalInterface.isInterface()) {
+            throw new LambdaConversionException("Functional interface " + functionalInterface.getName() + " is not an interface");
+        }
+        for (Class<?> marker : markers) {
+            if (!marker.isInterface()) {
+                throw new LambdaConversionException("Marker interface " + marker.getName() + " is not an interface");
+            }
+        }
+        MethodHandle direct = implementation.asFixedArity();
+        if (!(direct instanceof DirectMethodHandle)) {
+            throw new LambdaConversionException("implementation is not direct or cannot be cracked: " + implementation);
+        }
+        int capturedArity = factoryType.parameterCount();
+        int samArity = interfaceMethodType.parameterCount();
+        int implArity = direct.type().parameterCount();
+        if (implArity != capturedArity + samArity || dynamicMethodType.parameterCount() != samArity) {
+            throw new LambdaConversionException("Incorrect number of parameters for " + implementation + "; " + capturedArity
+                + " captured parameters, " + samArity + " functional interface method parameters, " + implArity
+                + " implementation parameters");
+        }
+        if (direct.type().returnType() == void.class && interfaceMethodType.returnType() != void.class) {
+            throw new LambdaConversionException("Type mismatch for lambda expected return: void is not convertible to "
+                + interfaceMethodType.returnType());
+        }
+        Class<?>[] interfaces = new Class<?>[markers.length + 1];
+        interfaces[0] = functionalInterface;
+        System.arraycopy(markers, 0, interfaces, 1, markers.length);
+        return new ConstantCallSite(spinLambda(caller.lookupClass(), interfaceMethodName, factoryType, interfaceMethodType,
+            direct, interfaces, bridges));
+    }
+
+    // A static method of a new class for `caller` that makes the instances, it takes the captured
+    // arguments. The class implements the interfaces, it has the interface method and the bridges
+    private static native MethodHandle spinLambda(Class<?> caller, String interfaceMethodName, MethodType factoryType,
+        MethodType interfaceMethodType, MethodHandle implementation, Class<?>[] interfaces, MethodType[] bridges);
+}
@@ -0,0 +1,101 @@
+package java.lang.invoke;
+
+// A typed reference to a field, method or constructor, or a transformation of other handles.
+// invokeExact and invoke are signature polymorphic: the VM calls them with the descriptor of the
+// call site and runs a direct handle of that exact type itself. Every other call boxes the
+// arguments into invokeBoxed
+public abstract class MethodHandle {
+    private final MethodType type;
+
+    MethodHandle(MethodType type) {
+        this.type = type;
+    }
+
+    public MethodType type() {
+        return type;
+    }
+
+    public final native Object invokeExact(Object... args) throws Throwable;
+
+    public final native Object invoke(Object... args) throws Throwable;
+
+    // The arguments and the result boxed, null for void
+    abstract Object invokeBoxed(Object[] args) throws Throwable;
+
+    // The handle a call site of the type runs, WrongMethodTypeException for invokeExact
+    final MethodHandle asCallSiteType(MethodType callSiteType, boolean exact) {
+        if (callSiteType.equals(type)) {
+            return this;
+        }
+        if (exact) {
+            throw new WrongMethodTypeException("expected " + type + " but found " + callSiteType);
+        }
+        return asType(callSiteType);
+    }
+
+    public Object invokeWithArguments(Object... arguments) throws Throwable {
+        return asType(MethodType.genericMethodType(arguments.length)).invokeBoxed(arguments);
+    }
+
+    public MethodHandle asType(MethodType newType) {
+        if (newType.equals(type)) {
+            return this;
+        }
+        boolean convertible = newType.parameterCount() == type.parameterCount()
+            && Wrapper.canConvert(type.returnType(), newType.returnType());
+        for (int i = 0; convertible && i < type.parameterCount(); i++) {
+            convertible = Wrapper.canConvert(newType.parameterType(i), type.parameterType(i));
+        }
+        if (!convertible) {
+            throw new WrongMethodTypeException("cannot convert " + this + " to " + newType);
+        }
+        return new MethodHandles.Converted(this, newType);
+    }
+
+    public MethodHandle bindTo(Object x) {
+        if (type.parameterCount() == 0 || type.parameterType(0).isPrimitive()) {
+            throw new IllegalArgumentException("no leading reference parameter: " + x);
+        }
+        return MethodHandles.insertArguments(this, 0, x);
+    }
+
+    public MethodHandle asSpreader(Class<?> arrayType, int arrayLength) {
+        int start = type.parameterCount() - arrayLength;
+        if (!arrayType.isArray() || arrayLength < 0 || start < 0) {
+            throw new IllegalArgumentException("bad spread array type " + arrayType.getName());
+        }
+        return new MethodHandles.Spreader(this, arrayType, start);
+    }
+
+    public MethodHandle asCollector(Class<?> arrayType, int arrayLength) {
+        int last = type.parameterCount() - 1;
+        if (last < 0 || !type.parameterType(last).isAssignableFrom(arrayType) || !arrayType.isArray() || arrayLength < 0) {
+            throw new IllegalArgumentException("array type not assignable to trailing argument: " + this + ", " + arrayType.getSimpleName());
+        }
+        return new MethodHandles.Collector(this, arrayType, arrayLength);
+    }
+
+    public MethodHandle asVarargsCollector(Class<?> arrayType) {
+        asCollector(arrayType, 0);
+        return isVarargsCollector() ? this : new MethodHandles.VarargsCollector(this);
+    }
+
+    public MethodHandle withVarargs(boolean makeVarargs) {
+        if (!makeVarargs) {
+            return asFixedArity();
+        }
+        return asVarargsCollector(type.lastParameterType());
+    }
+
+    public boolean isVarargsCollector() {
+        return false;
+    }
+
+    public MethodHandle asFixedArity() {
+        return this;
+    }
+
+    public String toString() {
+        return "MethodHandle" + type;
+    }
+}
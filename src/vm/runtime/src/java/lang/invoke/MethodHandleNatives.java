@@ -0,0 +1,54 @@
+package java.lang.invoke;
+
+// What the Java side of method handles asks the VM for, and what the VM calls to link
+// invokedynamic instructions and Dynamic constants, JVMS §5.4.3.6
+final class MethodHandleNatives {
+    private MethodHandleNatives() {
+    }
+
+    // A direct handle of the member for code in `caller` with the lookup modes. `type` is a
+    // MethodType, or the Class of a field. Throws the LinkageError the matching instruction
+    // would throw
+    static native MethodHandle resolve(Class<?> caller, int modes, int kind, Class<?> refc, String name, Object type);
+
+    // The call site of an invokedynamic in `caller`, it has to have the instruction's type
+    static CallSite linkCallSite(Class<?> caller, MethodHandle bootstrapMethod, String name, MethodType type, Object[] staticArguments) {
+        Object site = bootstrap(caller, bootstrapMethod, name, type, staticArguments);
+        if (!(site instanceof CallSite)) {
+            String message = site == null ? "null" : site.getClass().getName();
+            throw new BootstrapMethodError("call site initialization exception", new ClassCastException(message + " is not a CallSite"));
+        }
+        CallSite callSite = (CallSite) site;
+        if (!callSite.type().equals(type)) {
+            throw new BootstrapMethodError("call site initialization exception",
+                new WrongMethodTypeException("expected " + type + " but found " + callSite.type()));
+        }
+        return callSite;
+    }
+
+    // The value of a Dynamic constant in `caller`, boxed when `type` is primitive
+    static Object linkDynamicConstant(Class<?> caller, MethodHandle bootstrapMethod, String name, Class<?> type, Object[] staticArguments) {
+        Object value = bootstrap(caller, bootstrapMethod, name, type, staticArguments);
+        try {
+            return Wrapper.convertValue(value, type);
+        } catch (ClassCastException | NullPointerException e) {
+            throw new BootstrapMethodError("bootstrap method initialization exception", e);
+        }
+    }
+
+    // Errors the bootstrap method throws pass as they are, other exceptions are wrapped
+    private static Object bootstrap(Class<?> caller, MethodHandle bootstrapMethod, String name, Object type, Object[] staticArguments) {
+        Object[] arguments = new Object[3 + staticArguments.length];
+        arguments[0] = new MethodHandles.Lookup(caller, MethodHandles.Lookup.FULL);
+        arguments[1] = name;
+        arguments[2] = type;
+        System.arraycopy(staticArguments, 0, arguments, 3, staticArguments.length);
+        try {
+            return bootstrapMethod.invokeWithArguments(arguments);
+        } catch (Error e) {
+            throw e;
+        } catch (Throwable t) {
+            throw new BootstrapMethodError("bootstrap method initialization exception", t);
+        }
+    }
+}
@@ -0,0 +1,611 @@
+package java.lang.invoke;
+
+import java.lang.reflect.Array;
+
+// Lookups that make direct handles, and the combinators that build handles out of others. Each
+// combinator is a handle class below whose invokeBoxed does the transformation on boxed values
+public class MethodHandles {
+    private MethodHandles() {
+    }
+
+    // A lookup with the full access of the class that calls it
+    public static native Lookup lookup();
+
+    public static Lookup publicLookup() {
+        return new Lookup(Object.class, Lookup.UNCONDITIONAL);
+    }
+
+    public static Lookup privateLookupIn(Class<?> targetClass, Lookup caller) throws IllegalAccessException {
+        return new Lookup(targetClass, Lookup.FULL);
+    }
+
+    public static final class Lookup {
+        public static final int PUBLIC = 0x01;
+        public static final int PRIVATE = 0x02;
+        public static final int PROTECTED = 0x04;
+        public static final int PACKAGE = 0x08;
+        public static final int MODULE = 0x10;
+        public static final int UNCONDITIONAL = 0x20;
+        public static final int ORIGINAL = 0x40;
+        static final int FULL = PUBLIC | PRIVATE | PROTECTED | PACKAGE | MODULE | ORIGINAL;
+
+        private final Class<?> lookupClass;
+        private final int allowedModes;
+
+        Lookup(Class<?> lookupClass, int allowedModes) {
+            this.lookupClass = lookupClass;
+            this.allowedModes = allowedModes;
+        }
+
+        public Class<?> lookupClass() {
+            return lookupClass;
+        }
+
+        public int lookupModes() {
+            return allowedModes;
+        }
+
+        // Private access stays with the class the lookup was made for
+        public Lookup in(Class<?> requestedLookupClass) {
+            if (requestedLookupClass == lookupClass) {
+                return this;
+            }
+            return new Lookup(requestedLookupClass, allowedModes & ~(PRIVATE | PROTECTED | ORIGINAL));
+        }
+
+        public MethodHandle findStatic(Class<?> refc, String name, MethodType type) throws NoSuchMethodException, IllegalAccessException {
+            return resolveMethod(MethodHandleInfo.REF_invokeStatic, refc, name, type);
+        }
+
+        public MethodHandle findVirtual(Class<?> refc, String name, MethodType type) throws NoSuchMethodException, IllegalAccessException {
+            int kind = refc.isInterface() ? MethodHandleInfo.REF_invokeInterface : MethodHandleInfo.REF_invokeVirtual;
+            return resolveMethod(kind, refc, name, type);
+        }
+
+        public MethodHandle findSpecial(Class<?> refc, String name, MethodType type, Class<?> specialCaller) throws NoSuchMethodException, IllegalAccessException {
+            if (specialCaller != lookupClass || (allowedModes & PRIVATE) == 0) {
+                throw new IllegalAccessException("no private access for invokespecial: " + specialCaller + ", from " + this);
+            }
+            return resolveMethod(MethodHandleInfo.REF_invokeSpecial, refc, name, type);
+        }
+
+        public MethodHandle findConstructor(Class<?> refc, MethodType type) throws NoSuchMethodException, IllegalAccessException {
+            return resolveMethod(MethodHandleInfo.REF_newInvokeSpecial, refc, "<init>", type);
+        }
+
+        public MethodHandle findGetter(Class<?> refc, String name, Class<?> type) throws NoSuchFieldException, IllegalAccessException {
+            return resolveField(MethodHandleInfo.REF_getField, refc, name, type);
+        }
+
+        public MethodHandle findSetter(Class<?> refc, String name, Class<?> type) throws NoSuchFieldException, IllegalAccessException {
+            return resolveField(MethodHandleInfo.REF_putField, refc, name, type);
+        }
+
+        public MethodHandle findStaticGetter(Class<?> refc, String name, Class<?> type) throws NoSuchFieldException, IllegalAccessException {
+            return resolveField(MethodHandleInfo.REF_getStatic, refc, name, type);
+        }
+
+        public MethodHandle findStaticSetter(Class<?> refc, String name, Class<?> type) throws NoSuchFieldException, IllegalAccessException {
+            return resolveField(MethodHandleInfo.REF_putStatic, refc, name, type);
+        }
+
+        // The getter checks the field and the access, the handle accesses the field it reads
+        public VarHandle findVarHandle(Class<?> recv, String name, Class<?> type) throws NoSuchFieldException, IllegalAccessException {
+            return new VarHandle(type, findGetter(recv, name, type), null);
+        }
+
+        public VarHandle findStaticVarHandle(Class<?> decl, String name, Class<?> type) throws NoSuchFieldException, IllegalAccessException {
+            return new VarHandle(type, findStaticGetter(decl, name, type), null);
+        }
+
+        public MethodHandle bind(Object receiver, String name, MethodType type) throws NoSuchMethodException, IllegalAccessException {
+            return findVirtual(receiver.getClass(), name, type).bindTo(receiver);
+        }
+
+        private MethodHandle resolveMethod(int kind, Class<?> refc, String name, MethodType type) throws NoSuchMethodException, IllegalAccessException {
+            try {
+                return MethodHandleNatives.resolve(lookupClass, allowedModes, kind, refc, name, type);
+            } catch (NoSuchMethodError e) {
+                throw new NoSuchMethodException("no such " + (kind == MethodHandleInfo.REF_newInvokeSpecial ? "constructor" : "method")
+                    + ": " + refc.getName() + "." + name + type + "/" + MethodHandleInfo.referenceKindToString(kind));
+            } catch (IncompatibleClassChangeError e) {
+                throw new IllegalAccessException(e.getMessage() + ", from " + this);
+            }
+        }
+
+        private MethodHandle resolveField(int kind, Class<?> refc, String name, Class<?> type) throws NoSuchFieldException, IllegalAccessException {
+            try {
+                return MethodHandleNatives.resolve(lookupClass, allowedModes, kind, refc, name, type);
+            } catch (NoSuchFieldError e) {
+                throw new NoSuchFieldException("no such field: " + refc.getName() + "." + name + "/" + type.getName()
+                    + "/" + MethodHandleInfo.referenceKindToString(kind));
+            } catch (IncompatibleClassChangeError e) {
+                throw new IllegalAccessException(e.getMessage() + ", from " + this);
+            }
+        }
+
+        public String toString() {
+            String name = lookupClass.getName();
+            return allowedModes == UNCONDITIONAL ? name + "/publicLookup" : name;
+        }
+    }
+
+    public static MethodHandle insertArguments(MethodHandle target, int pos, Object... values) {
+        MethodType type = target.type();
+        if (pos < 0 || pos + values.length > type.parameterCount()) {
+            throw new IllegalArgumentException("too many values to insert");
+        }
+        Object[] converted = new Object[values.length];
+        for (int i = 0; i < values.length; i++) {
+            converted[i] = Wrapper.convertValue(values[i], type.parameterType(pos + i));
+        }
+        return new Inserted(target, type.dropParameterTypes(pos, pos + values.length), pos, converted);
+    }
+
+    public static MethodHandle dropArguments(MethodHandle target, int pos, Class<?>... valueTypes) {
+        return new Dropped(target, target.type().insertParameterTypes(pos, valueTypes), pos, valueTypes.length);
+    }
+
+    public static MethodHandle filterArguments(MethodHandle target, int pos, MethodHandle... filters) {
+        MethodType type = target.type();
+        for (int i = 0; i < filters.length; i++) {
+            MethodHandle filter = filters[i];
+            if (filter == null) {
+                continue;
+            }
+            MethodType filterType = filter.type();
+            if (filterType.parameterCount() != 1 || filterType.returnType() != type.parameterType(pos + i)) {
+                throw new IllegalArgumentException("target and filter types do not match: " + type + ", " + filterType);
+            }
+            type = type.changeParameterType(pos + i, filterType.parameterType(0));
+        }
+        return new Filtered(target, type, pos, filters.clone());
+    }
+
+    public static MethodHandle filterReturnValue(MethodHandle target, MethodHandle filter) {
+        MethodType type = target.type();
+        MethodType filterType = filter.type();
+        boolean matches = type.returnType() == void.class
+            ? filterType.parameterCount() == 0
+            : filterType.parameterCount() == 1 && filterType.parameterType(0) == type.returnType();
+        if (!matches) {
+            throw new IllegalArgumentException("target and filter types do not match: " + type + ", " + filterType);
+        }
+        return new ReturnFiltered(target, filter);
+    }
+
+    public static MethodHandle foldArguments(MethodHandle target, MethodHandle combiner) {
+        MethodType type = target.type();
+        MethodType combinerType = combiner.type();
+        int folded = combinerType.returnType() == void.class ? 0 : 1;
+        boolean matches = combinerType.parameterCount() + folded <= type.parameterCount()
+            && (folded == 0 || type.parameterType(0) == combinerType.returnType());
+        for (int i = 0; matches && i < combinerType.parameterCount(); i++) {
+            matches = combinerType.parameterType(i) == type.parameterType(folded + i);
+        }
+        if (!matches) {
+            throw new IllegalArgumentException("target and combiner types do not match: " + type + " != " + combinerType);
+        }
+        return new Folded(target, type.dropParameterTypes(0, folded), combiner);
+    }
+
+    public static MethodHandle guardWithTest(MethodHandle test, MethodHandle target, MethodHandle fallback) {
+        MethodType type = target.type();
+        MethodType testType = test.type();
+        boolean matches = testType.returnType() == boolean.class && type.equals(fallback.type())
+            && testType.parameterCount() <= type.parameterCount();
+        for (int i = 0; matches && i < testType.parameterCount(); i++) {
+            matches = testType.parameterType(i) == type.parameterType(i);
+        }
+        if (!matches) {
+            throw new IllegalArgumentException("target, test and fallback types do not match: " + type + ", " + testType + ", " + fallback.type());
+        }
+        return new Guarded(test, target, fallback);
+    }
+
+    public static MethodHandle catchException(MethodHandle target, Class<? extends Throwable> exType, MethodHandle handler) {
+        MethodType type = target.type();
+        MethodType handlerType = handler.type();
+        boolean matches = handlerType.returnType() == type.returnType() && handlerType.parameterCount() >= 1
+            && handlerType.parameterType(0).isAssignableFrom(exType)
+            && handlerType.parameterCount() - 1 <= type.parameterCount();
+        for (int i = 1; matches && i < handlerType.parameterCount(); i++) {
+            matches = handlerType.parameterType(i) == type.parameterType(i - 1);
+        }
+        if (!matches) {
+            throw new IllegalArgumentException("target and handler types do not match: " + type + ", " + handlerType);
+        }
+        return new Caught(target, exType, handler);
+    }
+
+    public static MethodHandle throwException(Class<?> returnType, Class<? extends Throwable> exType) {
+        return new Thrower(MethodType.methodType(returnType, exType));
+    }
+
+    public static MethodHandle constant(Class<?> type, Object value) {
+        if (type == void.class) {
+            throw new IllegalArgumentException("void type");
+        }
+        return new Constant(MethodType.methodType(type), Wrapper.convertValue(value, type));
+    }
+
+    public static MethodHandle identity(Class<?> type) {
+        if (type == void.class) {
+            throw new IllegalArgumentException("void type");
+        }
+        return new Permuted(null, MethodType.methodType(type, type), new int[] { 0 });
+    }
+
+    public static MethodHandle zero(Class<?> type) {
+        return type == void.class ? empty(MethodType.methodType(void.class)) : constant(type, Wrapper.convert(null, void.class, type));
+    }
+
+    public static MethodHandle empty(MethodType type) {
+        return dropArguments(new Constant(MethodType.methodType(type.returnType()), Wrapper.convert(null, void.class, type.returnType())),
+            0, type.parameterArray());
+    }
+
+    public static MethodHandle permuteArguments(MethodHandle target, MethodType newType, int... reorder) {
+        MethodType type = target.type();
+        boolean matches = reorder.length == type.parameterCount() && newType.returnType() == type.returnType();
+        for (int i = 0; matches && i < reorder.length; i++) {
+            matches = reorder[i] >= 0 && reorder[i] < newType.parameterCount()
+                && newType.parameterType(reorder[i]) == type.parameterType(i);
+        }
+        if (!matches) {
+            throw new IllegalArgumentException("bad reorder array: " + java.util.Arrays.toString(reorder));
+        }
+        return new Permuted(target, newType, reorder.clone());
+    }
+
+    // (MethodHandle,A...)R handles that call their first argument
+    public static MethodHandle exactInvoker(MethodType type) {
+        return new Invoker(type, true);
+    }
+
+    public static MethodHandle invoker(MethodType type) {
+        return new Invoker(type, false);
+    }
+
+    public static MethodHandle spreadInvoker(MethodType type, int leadingArgCount) {
+        return invoker(type).asSpreader(Object[].class, type.parameterCount() - leadingArgCount);
+    }
+
+    public static VarHandle arrayElementVarHandle(Class<?> arrayClass) {
+        if (!arrayClass.isArray()) {
+            throw new IllegalArgumentException("not an array: " + arrayClass.getName());
+        }
+        return new VarHandle(arrayClass.getComponentType(), null, arrayClass);
+    }
+
+    private static Object[] remove(Object[] args, int pos, int count) {
+        Object[] removed = new Object[args.length - count];
+        System.arraycopy(args, 0, removed, 0, pos);
+        System.arraycopy(args, pos + count, removed, pos, args.length - pos - count);
+        return removed;
+    }
+
+    private static Object[] insert(Object[] args, int pos, Object[] values) {
+        Object[] inserted = new Object[args.length + values.length];
+        System.arraycopy(args, 0, inserted, 0, pos);
+        System.arraycopy(values, 0, inserted, pos, values.length);
+        System.arraycopy(args, pos, inserted, pos + values.length, args.length - pos);
+        return inserted;
+    }
+
+    private static Object[] prefix(Object[] args, int count) {
+        Object[] prefix = new Object[count];
+        System.arraycopy(args, 0, prefix, 0, count);
+        return prefix;
+    }
+
+    static final class Converted extends MethodHandle {
+        private final MethodHandle target;
+
+        Converted(MethodHandle target, MethodType type) {
+            super(type);
+            this.target = target;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            MethodType from = type();
+            MethodType to = target.type();
+            Object[] converted = new Object[args.length];
+            for (int i = 0; i < args.length; i++) {
+                converted[i] = Wrapper.convert(args[i], from.parameterType(i), to.parameterType(i));
+            }
+            return Wrapper.convert(target.invokeBoxed(converted), to.returnType(), from.returnType());
+        }
+    }
+
+    static final class Inserted extends MethodHandle {
+        private final MethodHandle target;
+        private final int pos;
+        private final Object[] values;
+
+        Inserted(MethodHandle target, MethodType type, int pos, Object[] values) {
+            super(type);
+            this.target = target;
+            this.pos = pos;
+            this.values = values;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            return target.invokeBoxed(insert(args, pos, values));
+        }
+    }
+
+    static final class Dropped extends MethodHandle {
+        private final MethodHandle target;
+        private final int pos;
+        private final int count;
+
+        Dropped(MethodHandle target, MethodType type, int pos, int count) {
+            super(type);
+            this.target = target;
+            this.pos = pos;
+            this.count = count;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            return target.invokeBoxed(remove(args, pos, count));
+        }
+    }
+
+    static final class Filtered extends MethodHandle {
+        private final MethodHandle target;
+        private final int pos;
+        private final MethodHandle[] filters;
+
+        Filtered(MethodHandle target, MethodType type, int pos, MethodHandle[] filters) {
+            super(type);
+            this.target = target;
+            this.pos = pos;
+            this.filters = filters;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            Object[] filtered = args.clone();
+            for (int i = 0; i < filters.length; i++) {
+                if (filters[i] != null) {
+                    filtered[pos + i] = filters[i].invokeBoxed(new Object[] { args[pos + i] });
+                }
+            }
+            return target.invokeBoxed(filtered);
+        }
+    }
+
+    static final class ReturnFiltered extends MethodHandle {
+        private final MethodHandle target;
+        private final MethodHandle filter;
+
+        ReturnFiltered(MethodHandle target, MethodHandle filter) {
+            super(target.type().changeReturnType(filter.type().returnType()));
+            this.target = target;
+            this.filter = filter;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            Object result = target.invokeBoxed(args);
+            return filter.invokeBoxed(target.type().returnType() == void.class ? new Object[0] : new Object[] { result });
+        }
+    }
+
+    static final class Folded extends MethodHandle {
+        private final MethodHandle target;
+        private final MethodHandle combiner;
+
+        Folded(MethodHandle target, MethodType type, MethodHandle combiner) {
+            super(type);
+            this.target = target;
+            this.combiner = combiner;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            Object folded = combiner.invokeBoxed(prefix(args, combiner.type().parameterCount()));
+            if (combiner.type().returnType() == void.class) {
+                return target.invokeBoxed(args);
+            }
+            return target.invokeBoxed(insert(args, 0, new Object[] { folded }));
+        }
+    }
+
+    static final class Guarded extends MethodHandle {
+        private final MethodHandle test;
+        private final MethodHandle target;
+        private final MethodHandle fallback;
+
+        Guarded(MethodHandle test, MethodHandle target, MethodHandle fallback) {
+            super(target.type());
+            this.test = test;
+            this.target = target;
+            this.fallback = fallback;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            boolean passed = (Boolean) test.invokeBoxed(prefix(args, test.type().parameterCount()));
+            return (passed ? target : fallback).invokeBoxed(args);
+        }
+    }
+
+    static final class Caught extends MethodHandle {
+        private final MethodHandle target;
+        private final Class<? extends Throwable> exType;
+        private final MethodHandle handler;
+
+        Caught(MethodHandle target, Class<? extends Throwable> exType, MethodHandle handler) {
+            super(target.type());
+            this.target = target;
+            this.exType = exType;
+            this.handler = handler;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            try {
+                return target.invokeBoxed(args);
+            } catch (Throwable t) {
+                if (!exType.isInstance(t)) {
+                    throw t;
+                }
+                return handler.invokeBoxed(insert(prefix(args, handler.type().parameterCount() - 1), 0, new Object[] { t }));
+            }
+        }
+    }
+
+    static final class Thrower extends MethodHandle {
+        Thrower(MethodType type) {
+            super(type);
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            throw (Throwable) args[0];
+        }
+    }
+
+    static final class Constant extends MethodHandle {
+        private final Object value;
+
+        Constant(MethodType type, Object value) {
+            super(type);
+            this.value = value;
+        }
+
+        Object invokeBoxed(Object[] args) {
+            return value;
+        }
+    }
+
+    // The target gets the arguments at the positions of `reorder`, no target returns the first
+    static final class Permuted extends MethodHandle {
+        private final MethodHandle target;
+        private final int[] reorder;
+
+        Permuted(MethodHandle target, MethodType type, int[] reorder) {
+            super(type);
+            this.target = target;
+            this.reorder = reorder;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            if (target == null) {
+                return args[reorder[0]];
+            }
+            Object[] permuted = new Object[reorder.length];
+            for (int i = 0; i < reorder.length; i++) {
+                permuted[i] = args[reorder[i]];
+            }
+            return target.invokeBoxed(permuted);
+        }
+    }
+
+    static final class Invoker extends MethodHandle {
+        private final MethodType targetType;
+        private final boolean exact;
+
+        Invoker(MethodType targetType, boolean exact) {
+            super(targetType.insertParameterTypes(0, MethodHandle.class));
+            this.targetType = targetType;
+            this.exact = exact;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            MethodHandle target = (MethodHandle) args[0];
+            return target.asCallSiteType(targetType, exact).invokeBoxed(remove(args, 0, 1));
+        }
+    }
+
+    // The trailing array argument spread over `arrayLength` parameters
+    static final class Spreader extends MethodHandle {
+        private final MethodHandle target;
+        private final int start;
+
+        Spreader(MethodHandle target, Class<?> arrayType, int start) {
+            super(target.type().dropParameterTypes(start, target.type().parameterCount()).appendParameterTypes(arrayType));
+            this.target = target;
+            this.start = start;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            MethodType targetType = target.type();
+            Object array = args[start];
+            int length = targetType.parameterCount() - start;
+            if (array == null ? length != 0 : Array.getLength(array) != length) {
+                throw new IllegalArgumentException("array is not of length " + length);
+            }
+            Class<?> component = type().parameterType(start).getComponentType();
+            Object[] spread = new Object[targetType.parameterCount()];
+            System.arraycopy(args, 0, spread, 0, start);
+            for (int i = 0; i < length; i++) {
+                spread[start + i] = Wrapper.convert(Array.get(array, i), component, targetType.parameterType(start + i));
+            }
+            return target.invokeBoxed(spread);
+        }
+    }
+
+    // `arrayLength` trailing parameters collected into an array for the target
+    static final class Collector extends MethodHandle {
+        private final MethodHandle target;
+        private final int start;
+
+        Collector(MethodHandle target, Class<?> arrayType, int arrayLength) {
+            super(collectedType(target.type(), arrayType, arrayLength));
+            this.target = target;
+            this.start = target.type().parameterCount() - 1;
+        }
+
+        private static MethodType collectedType(MethodType type, Class<?> arrayType, int arrayLength) {
+            Class<?>[] collected = new Class<?>[arrayLength];
+            for (int i = 0; i < arrayLength; i++) {
+                collected[i] = arrayType.getComponentType();
+            }
+            return type.dropParameterTypes(type.parameterCount() - 1, type.parameterCount()).appendParameterTypes(collected);
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            Class<?> component = target.type().parameterType(start).getComponentType();
+            Object array = Array.newInstance(component, args.length - start);
+            for (int i = start; i < args.length; i++) {
+                Array.set(array, i - start, args[i]);
+            }
+            Object[] collected = new Object[start + 1];
+            System.arraycopy(args, 0, collected, 0, start);
+            collected[start] = array;
+            return target.invokeBoxed(collected);
+        }
+    }
+
+    // Calls with the exact type pass the array on, others collect the trailing arguments
+    static final class VarargsCollector extends MethodHandle {
+        private final MethodHandle target;
+
+        VarargsCollector(MethodHandle target) {
+            super(target.type());
+            this.target = target;
+        }
+
+        public MethodHandle asType(MethodType newType) {
+            MethodType type = type();
+            int last = type.parameterCount() - 1;
+            if (newType.parameterCount() == type.parameterCount()
+                    && type.parameterType(last).isAssignableFrom(newType.parameterType(last))) {
+                return target.asType(newType);
+            }
+            if (newType.parameterCount() < last) {
+                throw new WrongMethodTypeException("cannot convert " + this + " to " + newType);
+            }
+            return target.asCollector(type.parameterType(last), newType.parameterCount() - last).asType(newType);
+        }
+
+        public boolean isVarargsCollector() {
+            return true;
+        }
+
+        public MethodHandle asFixedArity() {
+            return target;
+        }
+
+        Object invokeBoxed(Object[] args) throws Throwable {
+            return target.invokeBoxed(args);
+        }
+    }
+}
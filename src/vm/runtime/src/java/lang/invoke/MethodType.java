@@ -0,0 +1,207 @@
+package java.lang.invoke;
+
+// The VM makes these for ldc and for the descriptors of invokedynamic and signature polymorphic
+// call sites. Equal types aren't always the same object
+public final class MethodType implements java.io.Serializable {
+    private static final Class<?>[] NO_PTYPES = new Class<?>[0];
+
+    private final Class<?> rtype;
+    private final Class<?>[] ptypes;
+
+    private MethodType(Class<?> rtype, Class<?>[] ptypes) {
+        if (rtype == null) {
+            throw new NullPointerException();
+        }
+        for (Class<?> ptype : ptypes) {
+            if (ptype == null) {
+                throw new NullPointerException();
+            }
+            if (ptype == void.class) {
+                throw new IllegalArgumentException("parameter type cannot be void");
+            }
+        }
+        this.rtype = rtype;
+        this.ptypes = ptypes;
+    }
+
+    public static MethodType methodType(Class<?> rtype, Class<?>[] ptypes) {
+        return new MethodType(rtype, ptypes.clone());
+    }
+
+    public static MethodType methodType(Class<?> rtype, Class<?> ptype0, Class<?>... ptypes) {
+        Class<?>[] all = new Class<?>[ptypes.length + 1];
+        all[0] = ptype0;
+        System.arraycopy(ptypes, 0, all, 1, ptypes.length);
+        return new MethodType(rtype, all);
+    }
+
+    public static MethodType methodType(Class<?> rtype) {
+        return new MethodType(rtype, NO_PTYPES);
+    }
+
+    public static MethodType methodType(Class<?> rtype, Class<?> ptype0) {
+        return new MethodType(rtype, new Class<?>[] { ptype0 });
+    }
+
+    public static MethodType methodType(Class<?> rtype, MethodType ptypes) {
+        return new MethodType(rtype, ptypes.ptypes);
+    }
+
+    public static MethodType genericMethodType(int objectArgCount) {
+        Class<?>[] ptypes = new Class<?>[objectArgCount];
+        for (int i = 0; i < objectArgCount; i++) {
+            ptypes[i] = Object.class;
+        }
+        return new MethodType(Object.class, ptypes);
+    }
+
+    public Class<?> returnType() {
+        return rtype;
+    }
+
+    public Class<?> parameterType(int num) {
+        return ptypes[num];
+    }
+
+    public int parameterCount() {
+        return ptypes.length;
+    }
+
+    public Class<?>[] parameterArray() {
+        return ptypes.clone();
+    }
+
+    public Class<?> lastParameterType() {
+        return ptypes.length == 0 ? void.class : ptypes[ptypes.length - 1];
+    }
+
+    public MethodType changeParameterType(int num, Class<?> nptype) {
+        Class<?>[] changed = ptypes.clone();
+        changed[num] = nptype;
+        return new MethodType(rtype, changed);
+    }
+
+    public MethodType insertParameterTypes(int num, Class<?>... ptypesToInsert) {
+        if (num < 0 || num > ptypes.length) {
+            throw new IndexOutOfBoundsException(Integer.toString(num));
+        }
+        Class<?>[] inserted = new Class<?>[ptypes.length + ptypesToInsert.length];
+        System.arraycopy(ptypes, 0, inserted, 0, num);
+        System.arraycopy(ptypesToInsert, 0, inserted, num, ptypesToInsert.length);
+        System.arraycopy(ptypes, num, inserted, num + ptypesToInsert.length, ptypes.length - num);
+        return new MethodType(rtype, inserted);
+    }
+
+    public MethodType appendParameterTypes(Class<?>... ptypesToInsert) {
+        return insertParameterTypes(ptypes.length, ptypesToInsert);
+    }
+
+    public MethodType dropParameterTypes(int start, int end) {
+        if (start < 0 || start > end || end > ptypes.length) {
+            throw new IndexOutOfBoundsException("start=" + start + " end=" + end);
+        }
+        Class<?>[] dropped = new Class<?>[ptypes.length - (end - start)];
+        System.arraycopy(ptypes, 0, dropped, 0, start);
+        System.arraycopy(ptypes, end, dropped, start, ptypes.length - end);
+        return new MethodType(rtype, dropped);
+    }
+
+    public MethodType changeReturnType(Class<?> nrtype) {
+        return new MethodType(nrtype, ptypes);
+    }
+
+    public boolean hasPrimitives() {
+        if (rtype.isPrimitive()) {
+            return true;
+        }
+        for (Class<?> ptype : ptypes) {
+            if (ptype.isPrimitive()) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    public boolean hasWrappers() {
+        return unwrap() != this;
+    }
+
+    // Every reference type becomes Object
+    public MethodType erase() {
+        Class<?>[] erased = new Class<?>[ptypes.length];
+        for (int i = 0; i < ptypes.length; i++) {
+            erased[i] = ptypes[i].isPrimitive() ? ptypes[i] : Object.class;
+        }
+        return new MethodType(rtype.isPrimitive() ? rtype : Object.class, erased);
+    }
+
+    public MethodType generic() {
+        return genericMethodType(ptypes.length);
+    }
+
+    // Primitive types become their wrappers, void becomes Void
+    public MethodType wrap() {
+        Class<?>[] wrapped = new Class<?>[ptypes.length];
+        for (int i = 0; i < ptypes.length; i++) {
+            wrapped[i] = Wrapper.wrap(ptypes[i]);
+        }
+        return new MethodType(Wrapper.wrap(rtype), wrapped);
+    }
+
+    public MethodType unwrap() {
+        boolean changed = false;
+        Class<?>[] unwrapped = new Class<?>[ptypes.length];
+        for (int i = 0; i < ptypes.length; i++) {
+            unwrapped[i] = Wrapper.unwrap(ptypes[i]);
+            changed |= unwrapped[i] != ptypes[i];
+        }
+        Class<?> unwrappedReturn = Wrapper.unwrap(rtype);
+        return changed || unwrappedReturn != rtype ? new MethodType(unwrappedReturn, unwrapped) : this;
+    }
+
+    public boolean equals(Object x) {
+        if (!(x instanceof MethodType)) {
+            return false;
+        }
+        MethodType that = (MethodType) x;
+        if (rtype != that.rtype || ptypes.length != that.ptypes.length) {
+            return false;
+        }
+        for (int i = 0; i < ptypes.length; i++) {
+            if (ptypes[i] != that.ptypes[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public int hashCode() {
+        int hashCode = 31 + rtype.hashCode();
+        for (Class<?> ptype : ptypes) {
+            hashCode = 31 * hashCode + ptype.hashCode();
+        }
+        return hashCode;
+    }
+
+    // (int,String)void
+    public String toString() {
+        StringBuilder b = new StringBuilder("(");
+        for (int i = 0; i < ptypes.length; i++) {
+            b.append(i == 0 ? "" : ",").append(ptypes[i].getSimpleName());
+        }
+        return b.append(')').append(rtype.getSimpleName()).toString();
+    }
+
+    public String toMethodDescriptorString() {
+        return descriptorString();
+    }
+
+    // (ILjava/lang/String;)V
+    public String descriptorString() {
+        StringBuilder b = new StringBuilder("(");
+        for (Class<?> ptype : ptypes) {
+            b.append(Wrapper.descriptor(ptype));
+        }
+        return b.append(')').append(Wrapper.descriptor(rtype)).toString();
+    }
+}
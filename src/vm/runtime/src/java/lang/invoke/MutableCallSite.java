@@ -0,0 +1,24 @@
+package java.lang.invoke;
+
+public class MutableCallSite extends CallSite {
+    public MutableCallSite(MethodType type) {
+        super(type);
+    }
+
+    public MutableCallSite(MethodHandle target) {
+        super(target);
+    }
+
+    public final MethodHandle getTarget() {
+        return target;
+    }
+
+    public void setTarget(MethodHandle newTarget) {
+        checkTargetType(newTarget);
+        target = newTarget;
+    }
+
+    public final MethodHandle dynamicInvoker() {
+        return new DynamicInvoker(this);
+    }
+}
@@ -0,0 +1,60 @@
+package java.lang.invoke;
+
+// The bootstrap methods of string concatenation since Java 9. The VM spins a static method that
+// appends the literal text and the arguments to a StringBuilder
+public final class StringConcatFactory {
+    private static final char TAG_ARG = '\u0001';
+    private static final char TAG_CONST = '\u0002';
+
+    private StringConcatFactory() {
+    }
+
+    public static CallSite makeConcat(MethodHandles.Lookup lookup, String name, MethodType concatType) throws StringConcatException {
+        StringBuilder recipe = new StringBuilder();
+        for (int i = 0; i < concatType.parameterCount(); i++) {
+            recipe.append(TAG_ARG);
+        }
+        return makeConcatWithConstants(lookup, name, concatType, recipe.toString());
+    }
+
+    // In the recipe \1 stands for the next argument and \2 for the next constant
+    public static CallSite makeConcatWithConstants(MethodHandles.Lookup lookup, String name, MethodType concatType,
+            String recipe, Object... constants) throws StringConcatException {
+        if (concatType.returnType() != String.class) {
+            throw new StringConcatException("The return type should be compatible with String, but it is " + concatType.returnType());
+        }
+        int count = concatType.parameterCount();
+        String[] literals = new String[count + 1];
+        StringBuilder literal = new StringBuilder();
+        int argument = 0;
+        int constant = 0;
+        for (int i = 0; i < recipe.length(); i++) {
+            char c = recipe.charAt(i);
+            if (c == TAG_ARG) {
+                if (argument < count) {
+                    literals[argument] = literal.toString();
+                    literal.setLength(0);
+                }
+                argument++;
+            } else if (c == TAG_CONST) {
+                if (constant == constants.length) {
+                    throw new StringConcatException("Mismatched number of concat constants: recipe wants " + (constant + 1)
+                        + " constants, but only " + constants.length + " are passed");
+                }
+                literal.append(String.valueOf(constants[constant++]));
+            } else {
+                literal.append(c);
+            }
+        }
+        if (argument != count) {
+            throw new StringConcatException("Mismatched number of concat arguments: recipe wants " + argument
+                + " arguments, but signature provides " + count);
+        }
+        literals[count] = literal.toString();
+        return new ConstantCallSite(spinConcat(lookup.lookupClass(), concatType, literals));
+    }
+
+    // A static method of a new class for `caller` of the concat type, it appends literals[i]
+    // before argument i and the last literal after them
+    private static native MethodHandle spinConcat(Class<?> caller, MethodType concatType, String[] literals);
+}
@@ -0,0 +1,104 @@
+package java.lang.invoke;
+
+// A typed reference to a field or to the elements of an array type. The access modes are
+// signature polymorphic: the VM runs them on the field the getter handle reads, or on the element
+// the array and index coordinates name, with the descriptor of the call site
+public final class VarHandle {
+    private final Class<?> varType;
+    // A direct getter handle for a field, null for array elements
+    private final MethodHandle field;
+    private final Class<?> arrayClass;
+
+    VarHandle(Class<?> varType, MethodHandle field, Class<?> arrayClass) {
+        this.varType = varType;
+        this.field = field;
+        this.arrayClass = arrayClass;
+    }
+
+    public Class<?> varType() {
+        return varType;
+    }
+
+    public final native Object get(Object... args);
+
+    public final native void set(Object... args);
+
+    public final native Object getVolatile(Object... args);
+
+    public final native void setVolatile(Object... args);
+
+    public final native Object getOpaque(Object... args);
+
+    public final native void setOpaque(Object... args);
+
+    public final native Object getAcquire(Object... args);
+
+    public final native void setRelease(Object... args);
+
+    public final native boolean compareAndSet(Object... args);
+
+    public final native Object compareAndExchange(Object... args);
+
+    public final native Object compareAndExchangeAcquire(Object... args);
+
+    public final native Object compareAndExchangeRelease(Object... args);
+
+    public final native boolean weakCompareAndSetPlain(Object... args);
+
+    public final native boolean weakCompareAndSet(Object... args);
+
+    public final native boolean weakCompareAndSetAcquire(Object... args);
+
+    public final native boolean weakCompareAndSetRelease(Object... args);
+
+    public final native Object getAndSet(Object... args);
+
+    public final native Object getAndSetAcquire(Object... args);
+
+    public final native Object getAndSetRelease(Object... args);
+
+    public final native Object getAndAdd(Object... args);
+
+    public final native Object getAndAddAcquire(Object... args);
+
+    public final native Object getAndAddRelease(Object... args);
+
+    public final native Object getAndBitwiseOr(Object... args);
+
+    public final native Object getAndBitwiseOrAcquire(Object... args);
+
+    public final native Object getAndBitwiseOrRelease(Object... args);
+
+    public final native Object getAndBitwiseAnd(Object... args);
+
+    public final native Object getAndBitwiseAndAcquire(Object... args);
+
+    public final native Object getAndBitwiseAndRelease(Object... args);
+
+    public final native Object getAndBitwiseXor(Object... args);
+
+    public final native Object getAndBitwiseXorAcquire(Object... args);
+
+    public final native Object getAndBitwiseXorRelease(Object... args);
+
+    public static native void fullFence();
+
+    public static native void acquireFence();
+
+    public static native void releaseFence();
+
+    public static native void loadLoadFence();
+
+    public static native void storeStoreFence();
+
+    public String toString() {
+        String coordinates;
+        if (field == null) {
+            coordinates = arrayClass + ", int";
+        } else {
+            MethodType type = field.type();
+            coordinates = type.parameterCount() == 0 ? "" : type.parameterType(0).toString();
+        }
+        return "VarHandle[varType=" + varType.getName() + ", coord=[" + coordinates + "]]";
+    }
+}
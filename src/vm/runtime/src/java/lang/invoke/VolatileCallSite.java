@@ -0,0 +1,25 @@
+package java.lang.invoke;
+
+// Threads take turns on one OS thread, every call site is as good as volatile
+public class VolatileCallSite extends CallSite {
+    public VolatileCallSite(MethodType type) {
+        super(type);
+    }
+
+    public VolatileCallSite(MethodHandle target) {
+        super(target);
+    }
+
+    public final MethodHandle getTarget() {
+        return target;
+    }
+
+    public void setTarget(MethodHandle newTarget) {
+        checkTargetType(newTarget);
+        target = newTarget;
+    }
+
+    public final MethodHandle dynamicInvoker() {
+        return new DynamicInvoker(this);
+    }
+}
@@ -0,0 +1,61 @@
+package java.lang.invoke;
+
+// The primitive types and their wrapper classes, and the conversions between them that method
+// handles do for asType and invoke
+final class Wrapper {
+    private static final Class<?>[] PRIMITIVES = {
+        boolean.class, byte.class, char.class, short.class, int.class, long.class, float.class, double.class, void.class
+    };
+    private static final Class<?>[] WRAPPERS = {
+        Boolean.class, Byte.class, Character.class, Short.class, Integer.class, Long.class, Float.class, Double.class, Void.class
+    };
+    private static final String DESCRIPTORS = "ZBCSIJFDV";
+
+    private Wrapper() {
+    }
+
+    private static int indexOf(Class<?>[] types, Class<?> type) {
+        for (int i = 0; i < types.length; i++) {
+            if (types[i] == type) {
+                return i;
+            }
+        }
+        return -1;
+    }
+
+    static Class<?> wrap(Class<?> type) {
+        int index = indexOf(PRIMITIVES, type);
+        return index < 0 ? type : WRAPPERS[index];
+    }
+
+    static Class<?> unwrap(Class<?> type) {
+        int index = indexOf(WRAPPERS, type);
+        return index < 0 ? type : PRIMITIVES[index];
+    }
+
+    // The primitive type of a descriptor character, null for other characters
+    static Class<?> forDescriptor(char descriptor) {
+        int index = DESCRIPTORS.indexOf(descriptor);
+        return index < 0 ? null : PRIMITIVES[index];
+    }
+
+    static String descriptor(Class<?> type) {
+        if (type.isPrimitive()) {
+            return String.valueOf(DESCRIPTORS.charAt(indexOf(PRIMITIVES, type)));
+        }
+        String name = type.getName().replace('.', '/');
+        return type.isArray() ? name : "L" + name + ";";
+    }
+
+    // Whether asType can turn a value of `from` into `to`: widening, boxing, unboxing and casts
+    static native boolean canConvert(Class<?> from, Class<?> to);
+
+    // The value of `from`, boxed if primitive, as a `to`. null for void and the zero value when
+    // `from` is void
+    static native Object convert(Object value, Class<?> from, Class<?> to);
+
+    // A value bound to a parameter of the type, like insertArguments and constant take them
+    static Object convertValue(Object value, Class<?> type) {
+        return convert(value, value == null ? Object.class : value.getClass(), type);
+    }
+}
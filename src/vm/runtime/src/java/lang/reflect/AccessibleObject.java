@@ -0,0 +1,29 @@
+package java.lang.reflect;
+
+// Fields, methods and constructors, whose language access checks setAccessible turns off
+public class AccessibleObject {
+    boolean override;
+
+    protected AccessibleObject() {
+    }
+
+    public static void setAccessible(AccessibleObject[] array, boolean flag) {
+        for (AccessibleObject object : array) {
+            object.setAccessible(flag);
+        }
+    }
+
+    // There are no modules, everything can be made accessible
+    public void setAccessible(boolean flag) {
+        override = flag;
+    }
+
+    public final boolean trySetAccessible() {
+        override = true;
+        return true;
+    }
+
+    public boolean isAccessible() {
+        return override;
+    }
+}
@@ -0,0 +1,23 @@
+package java.lang.reflect;
+
+// Arrays of any component type, with primitive elements boxed
+public final class Array {
+    private Array() {
+    }
+
+    public static Object newInstance(Class<?> componentType, int length) throws NegativeArraySizeException {
+        if (componentType == null) {
+            throw new NullPointerException();
+        }
+        return newArray(componentType, length);
+    }
+
+    private static native Object newArray(Class<?> componentType, int length);
+
+    public static native int getLength(Object array) throws IllegalArgumentException;
+
+    public static native Object get(Object array, int index) throws IllegalArgumentException, ArrayIndexOutOfBoundsException;
+
+    // Primitive elements take the wrapped value unboxed and widened
+    public static native void set(Object array, int index, Object value) throws IllegalArgumentException, ArrayIndexOutOfBoundsException;
+}
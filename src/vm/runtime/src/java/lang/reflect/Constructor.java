@@ -0,0 +1,64 @@
+package java.lang.reflect;
+
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.MethodType;
+import jdk.internal.reflect.Reflection;
+
+public final class Constructor<T> extends Executable {
+    private MethodHandle handle;
+
+    private Constructor() {
+    }
+
+    public Class<T> getDeclaringClass() {
+        return (Class<T>) clazz;
+    }
+
+    public String getName() {
+        return clazz.getName();
+    }
+
+    // Arguments of primitive types are unwrapped and widened
+    public T newInstance(Object... initargs) throws InstantiationException, IllegalAccessException, IllegalArgumentException,
+            InvocationTargetException {
+        if (!override) {
+            Reflection.ensureMemberAccess(Reflection.getCallerClass(), clazz, modifiers);
+        }
+        if ((clazz.getModifiers() & Modifier.ENUM) != 0) {
+            throw new IllegalArgumentException("Cannot reflectively create enum objects");
+        }
+        if (Modifier.isAbstract(clazz.getModifiers())) {
+            throw new InstantiationException();
+        }
+        return (T) invoke(handle(), arguments(false, null, initargs));
+    }
+
+    private MethodHandle handle() throws IllegalAccessException {
+        if (handle == null) {
+            MethodHandles.Lookup lookup = MethodHandles.privateLookupIn(clazz, MethodHandles.lookup());
+            try {
+                handle = lookup.findConstructor(clazz, MethodType.methodType(void.class, parameterTypes)).asFixedArity();
+            } catch (NoSuchMethodException e) {
+                throw new InternalError(e);
+            }
+        }
+        return handle;
+    }
+
+    public boolean equals(Object obj) {
+        if (!(obj instanceof Constructor)) {
+            return false;
+        }
+        Constructor<?> other = (Constructor<?>) obj;
+        return clazz == other.clazz && equalTypes(parameterTypes, other.parameterTypes);
+    }
+
+    public int hashCode() {
+        return clazz.getName().hashCode();
+    }
+
+    public String toString() {
+        return sharedToString(Modifier.constructorModifiers(), false, clazz.getTypeName());
+    }
+}
@@ -0,0 +1,121 @@
+package java.lang.reflect;
+
+import java.lang.invoke.MethodHandle;
+
+// What methods and constructors share. The VM makes them with the declaring class, the types and
+// the access flags, and they run their member through a method handle
+public abstract class Executable extends AccessibleObject implements Member {
+    Class<?> clazz;
+    Class<?>[] parameterTypes;
+    Class<?>[] exceptionTypes;
+    int modifiers;
+
+    Executable() {
+    }
+
+    public Class<?> getDeclaringClass() {
+        return clazz;
+    }
+
+    public int getModifiers() {
+        return modifiers;
+    }
+
+    public Class<?>[] getParameterTypes() {
+        return parameterTypes.clone();
+    }
+
+    public int getParameterCount() {
+        return parameterTypes.length;
+    }
+
+    public Class<?>[] getExceptionTypes() {
+        return exceptionTypes.clone();
+    }
+
+    public boolean isVarArgs() {
+        return (modifiers & Modifier.VARARGS) != 0;
+    }
+
+    public boolean isSynthetic() {
+        return (modifiers & Modifier.SYNTHETIC) != 0;
+    }
+
+    // "public static int Foo.bar(int,java.lang.String) throws java.io.IOException" for a header
+    // of "int Foo.bar"
+    String sharedToString(int modifierMask, boolean isDefault, String header) {
+        StringBuilder sb = new StringBuilder();
+        int mod = modifiers & modifierMask;
+        if (mod != 0 && !isDefault) {
+            sb.append(Modifier.toString(mod)).append(' ');
+        } else {
+            int access = mod & (Modifier.PUBLIC | Modifier.PROTECTED | Modifier.PRIVATE);
+            if (access != 0) {
+                sb.append(Modifier.toString(access)).append(' ');
+            }
+            if (isDefault) {
+                sb.append("default ");
+            }
+            if ((mod & ~access) != 0) {
+                sb.append(Modifier.toString(mod & ~access)).append(' ');
+            }
+        }
+        sb.append(header).append('(');
+        for (int i = 0; i < parameterTypes.length; i++) {
+            sb.append(i == 0 ? "" : ",").append(parameterTypes[i].getTypeName());
+        }
+        sb.append(')');
+        for (int i = 0; i < exceptionTypes.length; i++) {
+            sb.append(i == 0 ? " throws " : ",").append(exceptionTypes[i].getTypeName());
+        }
+        return sb.toString();
+    }
+
+    // The receiver, when there is one, and the arguments converted to the parameter types
+    Object[] arguments(boolean hasReceiver, Object receiver, Object[] args) {
+        int count = args == null ? 0 : args.length;
+        if (count != parameterTypes.length) {
+            throw new IllegalArgumentException("wrong number of arguments");
+        }
+        int offset = hasReceiver ? 1 : 0;
+        Object[] arguments = new Object[offset + count];
+        if (hasReceiver) {
+            arguments[0] = receiver;
+        }
+        for (int i = 0; i < count; i++) {
+            Class<?> type = parameterTypes[i];
+            Object arg = args[i];
+            // Like the JDK, null for a primitive parameter is an IllegalArgumentException without a message
+            if (type.isPrimitive() && arg == null) {
+                throw new IllegalArgumentException();
+            }
+            Object converted = type.isPrimitive() ? Primitives.widen(arg, type) : arg;
+            if (type.isPrimitive() ? converted == null : arg != null && !type.isInstance(arg)) {
+                throw new IllegalArgumentException("argument type mismatch");
+            }
+            arguments[offset + i] = converted;
+        }
+        return arguments;
+    }
+
+    // What the member throws is wrapped
+    static Object invoke(MethodHandle handle, Object[] arguments) throws InvocationTargetException {
+        try {
+            return handle.invokeWithArguments(arguments);
+        } catch (Throwable t) {
+            throw new InvocationTargetException(t);
+        }
+    }
+
+    static boolean equalTypes(Class<?>[] a, Class<?>[] b) {
+        if (a.length != b.length) {
+            return false;
+        }
+        for (int i = 0; i < a.length; i++) {
+            if (a[i] != b[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+}
@@ -0,0 +1,227 @@
+package java.lang.reflect;
+
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodHandles;
+import jdk.internal.reflect.Reflection;
+
+// The VM makes fields with the declaring class, the name, the type and the access flags. get and
+// set go through getter and setter method handles, which initialize the class of a static field
+public final class Field extends AccessibleObject implements Member {
+    private Class<?> clazz;
+    private String name;
+    private Class<?> type;
+    private int modifiers;
+    private MethodHandle getter;
+    private MethodHandle setter;
+
+    private Field() {
+    }
+
+    public Class<?> getDeclaringClass() {
+        return clazz;
+    }
+
+    public String getName() {
+        return name;
+    }
+
+    public Class<?> getType() {
+        return type;
+    }
+
+    public int getModifiers() {
+        return modifiers;
+    }
+
+    public boolean isEnumConstant() {
+        return (modifiers & Modifier.ENUM) != 0;
+    }
+
+    public boolean isSynthetic() {
+        return (modifiers & Modifier.SYNTHETIC) != 0;
+    }
+
+    // A value of a primitive type is wrapped
+    public Object get(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return get(obj, Reflection.getCallerClass());
+    }
+
+    public boolean getBoolean(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Boolean) get(obj, Reflection.getCallerClass(), boolean.class);
+    }
+
+    public byte getByte(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Byte) get(obj, Reflection.getCallerClass(), byte.class);
+    }
+
+    public char getChar(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Character) get(obj, Reflection.getCallerClass(), char.class);
+    }
+
+    public short getShort(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Short) get(obj, Reflection.getCallerClass(), short.class);
+    }
+
+    public int getInt(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Integer) get(obj, Reflection.getCallerClass(), int.class);
+    }
+
+    public long getLong(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Long) get(obj, Reflection.getCallerClass(), long.class);
+    }
+
+    public float getFloat(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Float) get(obj, Reflection.getCallerClass(), float.class);
+    }
+
+    public double getDouble(Object obj) throws IllegalArgumentException, IllegalAccessException {
+        return (Double) get(obj, Reflection.getCallerClass(), double.class);
+    }
+
+    // A value for a primitive type is unwrapped and widened. Final fields can only be set when
+    // setAccessible(true) was called and they aren't static
+    public void set(Object obj, Object value) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, value, Reflection.getCallerClass(), null);
+    }
+
+    public void setBoolean(Object obj, boolean z) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, z, Reflection.getCallerClass(), "boolean");
+    }
+
+    public void setByte(Object obj, byte b) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, b, Reflection.getCallerClass(), "byte");
+    }
+
+    public void setChar(Object obj, char c) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, c, Reflection.getCallerClass(), "char");
+    }
+
+    public void setShort(Object obj, short s) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, s, Reflection.getCallerClass(), "short");
+    }
+
+    public void setInt(Object obj, int i) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, i, Reflection.getCallerClass(), "int");
+    }
+
+    public void setLong(Object obj, long l) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, l, Reflection.getCallerClass(), "long");
+    }
+
+    public void setFloat(Object obj, float f) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, f, Reflection.getCallerClass(), "float");
+    }
+
+    public void setDouble(Object obj, double d) throws IllegalArgumentException, IllegalAccessException {
+        set(obj, d, Reflection.getCallerClass(), "double");
+    }
+
+    private Object get(Object obj, Class<?> caller) throws IllegalAccessException {
+        checkAccess(obj, caller);
+        if (getter == null) {
+            MethodHandles.Lookup lookup = MethodHandles.privateLookupIn(clazz, MethodHandles.lookup());
+            try {
+                getter = Modifier.isStatic(modifiers) ? lookup.findStaticGetter(clazz, name, type) : lookup.findGetter(clazz, name, type);
+            } catch (NoSuchFieldException e) {
+                throw new InternalError(e);
+            }
+        }
+        return invoke(getter, Modifier.isStatic(modifiers) ? new Object[0] : new Object[] { obj });
+    }
+
+    // The value widened to the primitive type
+    private Object get(Object obj, Class<?> caller, Class<?> to) throws IllegalAccessException {
+        Object value = get(obj, caller);
+        Object widened = type.isPrimitive() ? Primitives.widen(value, to) : null;
+        if (widened == null) {
+            throw new IllegalArgumentException("Attempt to get " + type.getName() + " field \"" + qualifiedName()
+                + "\" with illegal data type conversion to " + to.getName());
+        }
+        return widened;
+    }
+
+    // `primitive` names the type of a value that setInt and the like wrapped
+    private void set(Object obj, Object value, Class<?> caller, String primitive) throws IllegalAccessException {
+        checkAccess(obj, caller);
+        boolean isStatic = Modifier.isStatic(modifiers);
+        boolean isReadOnly = Modifier.isFinal(modifiers) && (isStatic || !override);
+        if (isReadOnly) {
+            throw new IllegalAccessException(setMessage(value, primitive));
+        }
+        Object converted = type.isPrimitive() ? Primitives.widen(value, type) : value;
+        if (type.isPrimitive() ? converted == null : value != null && !type.isInstance(value)) {
+            throw new IllegalArgumentException(setMessage(value, primitive));
+        }
+        if (Modifier.isFinal(modifiers)) {
+            setFinal(obj, converted);
+            return;
+        }
+        if (setter == null) {
+            MethodHandles.Lookup lookup = MethodHandles.privateLookupIn(clazz, MethodHandles.lookup());
+            try {
+                setter = isStatic ? lookup.findStaticSetter(clazz, name, type) : lookup.findSetter(clazz, name, type);
+            } catch (NoSuchFieldException e) {
+                throw new InternalError(e);
+            }
+        }
+        invoke(setter, isStatic ? new Object[] { converted } : new Object[] { obj, converted });
+    }
+
+    // Stores the converted value in the final instance field of obj
+    private native void setFinal(Object obj, Object value);
+
+    private void checkAccess(Object obj, Class<?> caller) throws IllegalAccessException {
+        if (!override) {
+            Reflection.ensureMemberAccess(caller, clazz, modifiers);
+        }
+        if (!Modifier.isStatic(modifiers) && !clazz.isInstance(obj)) {
+            if (obj == null) {
+                throw new NullPointerException();
+            }
+            throw new IllegalArgumentException(setMessage(obj, null));
+        }
+    }
+
+    // The message the JDK uses for a value that can't be stored, and for a receiver that isn't
+    // an instance of the declaring class
+    private String setMessage(Object value, String primitive) {
+        String message = "Can not set " + (Modifier.isStatic(modifiers) ? "static " : "") + (Modifier.isFinal(modifiers) ? "final " : "")
+            + type.getName() + " field " + qualifiedName() + " to ";
+        if (primitive != null) {
+            return message + "(" + primitive + ")" + value;
+        }
+        return message + (value == null ? "null value" : value.getClass().getName());
+    }
+
+    private String qualifiedName() {
+        return clazz.getName() + "." + name;
+    }
+
+    // The getter and setter only throw what initializing the class throws
+    private static Object invoke(MethodHandle handle, Object[] arguments) {
+        try {
+            return handle.invokeWithArguments(arguments);
+        } catch (RuntimeException | Error e) {
+            throw e;
+        } catch (Throwable t) {
+            throw new InternalError(t);
+        }
+    }
+
+    public boolean equals(Object obj) {
+        if (!(obj instanceof Field)) {
+            return false;
+        }
+        Field other = (Field) obj;
+        return clazz == other.clazz && name.equals(other.name) && type == other.type;
+    }
+
+    public int hashCode() {
+        return clazz.getName().hashCode() ^ name.hashCode();
+    }
+
+    public String toString() {
+        int mod = modifiers & Modifier.fieldModifiers();
+        return (mod == 0 ? "" : Modifier.toString(mod) + " ") + type.getTypeName() + " " + clazz.getTypeName() + "." + name;
+    }
+}
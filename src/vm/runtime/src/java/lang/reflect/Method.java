@@ -0,0 +1,88 @@
+package java.lang.reflect;
+
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.MethodType;
+import jdk.internal.reflect.Reflection;
+
+public final class Method extends Executable {
+    private String name;
+    private Class<?> returnType;
+    private MethodHandle handle;
+
+    private Method() {
+    }
+
+    public String getName() {
+        return name;
+    }
+
+    public Class<?> getReturnType() {
+        return returnType;
+    }
+
+    public boolean isBridge() {
+        return (modifiers & Modifier.BRIDGE) != 0;
+    }
+
+    // A public instance method of an interface with a body
+    public boolean isDefault() {
+        return (modifiers & (Modifier.ABSTRACT | Modifier.PUBLIC | Modifier.STATIC)) == Modifier.PUBLIC && clazz.isInterface();
+    }
+
+    // Arguments of primitive types are unwrapped and widened, a primitive result is wrapped and
+    // void gives null
+    public Object invoke(Object obj, Object... args) throws IllegalAccessException, IllegalArgumentException, InvocationTargetException {
+        if (!override) {
+            Reflection.ensureMemberAccess(Reflection.getCallerClass(), clazz, modifiers);
+        }
+        boolean isStatic = Modifier.isStatic(modifiers);
+        if (!isStatic) {
+            if (obj == null) {
+                throw new NullPointerException();
+            }
+            if (!clazz.isInstance(obj)) {
+                throw new IllegalArgumentException("object is not an instance of declaring class");
+            }
+        }
+        return invoke(handle(), arguments(!isStatic, obj, args));
+    }
+
+    // Private methods are invoked like invokespecial does, the others like invokevirtual
+    private MethodHandle handle() throws IllegalAccessException {
+        if (handle == null) {
+            MethodType type = MethodType.methodType(returnType, parameterTypes);
+            MethodHandles.Lookup lookup = MethodHandles.privateLookupIn(clazz, MethodHandles.lookup());
+            try {
+                if (Modifier.isStatic(modifiers)) {
+                    handle = lookup.findStatic(clazz, name, type);
+                } else if (Modifier.isPrivate(modifiers)) {
+                    handle = lookup.findSpecial(clazz, name, type, clazz);
+                } else {
+                    handle = lookup.findVirtual(clazz, name, type);
+                }
+            } catch (NoSuchMethodException e) {
+                throw new InternalError(e);
+            }
+            handle = handle.asFixedArity();
+        }
+        return handle;
+    }
+
+    public boolean equals(Object obj) {
+        if (!(obj instanceof Method)) {
+            return false;
+        }
+        Method other = (Method) obj;
+        return clazz == other.clazz && name.equals(other.name) && returnType == other.returnType
+            && equalTypes(parameterTypes, other.parameterTypes);
+    }
+
+    public int hashCode() {
+        return clazz.getName().hashCode() ^ name.hashCode();
+    }
+
+    public String toString() {
+        return sharedToString(Modifier.methodModifiers(), isDefault(), returnType.getTypeName() + " " + clazz.getTypeName() + "." + name);
+    }
+}
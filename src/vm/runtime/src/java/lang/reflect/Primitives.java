@@ -0,0 +1,80 @@
+package java.lang.reflect;
+
+// The widening primitive conversions reflection applies to wrapped values, JLS §5.1.2
+final class Primitives {
+    private Primitives() {
+    }
+
+    // The wrapped value widened to the primitive type, null when it doesn't widen
+    static Object widen(Object value, Class<?> type) {
+        if (type == boolean.class || value instanceof Boolean) {
+            return type == boolean.class && value instanceof Boolean ? value : null;
+        }
+        int from = rank(value);
+        int to = rank(type);
+        if (from < 0 || to < 0 || !widens(from, to)) {
+            return null;
+        }
+        Number number = value instanceof Character ? Integer.valueOf((Character) value) : (Number) value;
+        switch (to) {
+            case 0:
+                return Byte.valueOf(number.byteValue());
+            case 1:
+                return Short.valueOf(number.shortValue());
+            case 2:
+                return value;
+            case 3:
+                return Integer.valueOf(number.intValue());
+            case 4:
+                return Long.valueOf(number.longValue());
+            case 5:
+                return Float.valueOf(number.floatValue());
+            default:
+                return Double.valueOf(number.doubleValue());
+        }
+    }
+
+    // byte, short and char only widen to themselves and to int and the types after it, and byte
+    // to short
+    private static boolean widens(int from, int to) {
+        return from == to || (from < to && to >= 3) || (from == 0 && to == 1);
+    }
+
+    private static int rank(Object value) {
+        if (value instanceof Byte) {
+            return 0;
+        } else if (value instanceof Short) {
+            return 1;
+        } else if (value instanceof Character) {
+            return 2;
+        } else if (value instanceof Integer) {
+            return 3;
+        } else if (value instanceof Long) {
+            return 4;
+        } else if (value instanceof Float) {
+            return 5;
+        } else if (value instanceof Double) {
+            return 6;
+        }
+        return -1;
+    }
+
+    private static int rank(Class<?> type) {
+        if (type == byte.class) {
+            return 0;
+        } else if (type == short.class) {
+            return 1;
+        } else if (type == char.class) {
+            return 2;
+        } else if (type == int.class) {
+            return 3;
+        } else if (type == long.class) {
+            return 4;
+        } else if (type == float.class) {
+            return 5;
+        } else if (type == double.class) {
+            return 6;
+        }
+        return -1;
+    }
+}
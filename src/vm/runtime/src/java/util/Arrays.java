@@ -0,0 +1,262 @@
+package java.util;
+
+// The int, long, double, char and Object overloads, sort is a merge sort for every type
+public final class Arrays {
+    private Arrays() {
+    }
+
+    public static String toString(int[] a) {
+        if (a == null) {
+            return "null";
+        }
+        StringBuilder b = new StringBuilder("[");
+        for (int i = 0; i < a.length; i++) {
+            b.append(i == 0 ? "" : ", ").append(a[i]);
+        }
+        return b.append(']').toString();
+    }
+
+    public static String toString(long[] a) {
+        if (a == null) {
+            return "null";
+        }
+        StringBuilder b = new StringBuilder("[");
+        for (int i = 0; i < a.length; i++) {
+            b.append(i == 0 ? "" : ", ").append(a[i]);
+        }
+        return b.append(']').toString();
+    }
+
+    public static String toString(double[] a) {
+        if (a == null) {
+            return "null";
+        }
+        StringBuilder b = new StringBuilder("[");
+        for (int i = 0; i < a.length; i++) {
+            b.append(i == 0 ? "" : ", ").append(a[i]);
+        }
+        return b.append(']').toString();
+    }
+
+    public static String toString(char[] a) {
+        if (a == null) {
+            return "null";
+        }
+        StringBuilder b = new StringBuilder("[");
+        for (int i = 0; i < a.length; i++) {
+            b.append(i == 0 ? "" : ", ").append(a[i]);
+        }
+        return b.append(']').toString();
+    }
+
+    public static String toString(Object[] a) {
+        if (a == null) {
+            return "null";
+        }
+        StringBuilder b = new StringBuilder("[");
+        for (int i = 0; i < a.length; i++) {
+            b.append(i == 0 ? "" : ", ").append(String.valueOf(a[i]));
+        }
+        return b.append(']').toString();
+    }
+
+    public static boolean equals(int[] a, int[] a2) {
+        if (a == a2) {
+            return true;
+        }
+        if (a == null || a2 == null || a.length != a2.length) {
+            return false;
+        }
+        for (int i = 0; i < a.length; i++) {
+            if (a[i] != a2[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public static boolean equals(Object[] a, Object[] a2) {
+        if (a == a2) {
+            return true;
+        }
+        if (a == null || a2 == null || a.length != a2.length) {
+            return false;
+        }
+        for (int i = 0; i < a.length; i++) {
+            if (!Objects.equals(a[i], a2[i])) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public static int hashCode(int[] a) {
+        if (a == null) {
+            return 0;
+        }
+        int result = 1;
+        for (int i = 0; i < a.length; i++) {
+            result = 31 * result + a[i];
+        }
+        return result;
+    }
+
+    public static int hashCode(Object[] a) {
+        if (a == null) {
+            return 0;
+        }
+        int result = 1;
+        for (int i = 0; i < a.length; i++) {
+            result = 31 * result + Objects.hashCode(a[i]);
+        }
+        return result;
+    }
+
+    public static void fill(int[] a, int val) {
+        for (int i = 0; i < a.length; i++) {
+            a[i] = val;
+        }
+    }
+
+    public static void fill(long[] a, long val) {
+        for (int i = 0; i < a.length; i++) {
+            a[i] = val;
+        }
+    }
+
+    public static void fill(double[] a, double val) {
+        for (int i = 0; i < a.length; i++) {
+            a[i] = val;
+        }
+    }
+
+    public static void fill(char[] a, char val) {
+        for (int i = 0; i < a.length; i++) {
+            a[i] = val;
+        }
+    }
+
+    public static void fill(boolean[] a, boolean val) {
+        for (int i = 0; i < a.length; i++) {
+            a[i] = val;
+        }
+    }
+
+    public static void fill(Object[] a, Object val) {
+        for (int i = 0; i < a.length; i++) {
+            a[i] = val;
+        }
+    }
+
+    public static int[] copyOf(int[] original, int newLength) {
+        int[] copy = new int[newLength];
+        System.arraycopy(original, 0, copy, 0, Math.min(original.length, newLength));
+        return copy;
+    }
+
+    public static long[] copyOf(long[] original, int newLength) {
+        long[] copy = new long[newLength];
+        System.arraycopy(original, 0, copy, 0, Math.min(original.length, newLength));
+        return copy;
+    }
+
+    public static char[] copyOf(char[] original, int newLength) {
+        char[] copy = new char[newLength];
+        System.arraycopy(original, 0, copy, 0, Math.min(original.length, newLength));
+        return copy;
+    }
+
+    // The copy has the array class of the original
+    public static <T> T[] copyOf(T[] original, int newLength) {
+        T[] copy = (T[]) java.lang.reflect.Array.newInstance(original.getClass().getComponentType(), newLength);
+        System.arraycopy(original, 0, copy, 0, Math.min(original.length, newLength));
+        return copy;
+    }
+
+    public static int[] copyOfRange(int[] original, int from, int to) {
+        int[] copy = new int[to - from];
+        System.arraycopy(original, from, copy, 0, Math.min(original.length - from, to - from));
+        return copy;
+    }
+
+    public static void sort(int[] a) {
+        int[] scratch = new int[a.length];
+        for (int width = 1; width < a.length; width *= 2) {
+            for (int lo = 0; lo < a.length - width; lo += 2 * width) {
+                int mid = lo + width;
+                int hi = Math.min(lo + 2 * width, a.length);
+                int i = lo;
+                int j = mid;
+                for (int k = lo; k < hi; k++) {
+                    scratch[k] = j >= hi || (i < mid && a[i] <= a[j]) ? a[i++] : a[j++];
+                }
+                System.arraycopy(scratch, lo, a, lo, hi - lo);
+            }
+        }
+    }
+
+    public static void sort(long[] a) {
+        long[] scratch = new long[a.length];
+        for (int width = 1; width < a.length; width *= 2) {
+            for (int lo = 0; lo < a.length - width; lo += 2 * width) {
+                int mid = lo + width;
+                int hi = Math.min(lo + 2 * width, a.length);
+                int i = lo;
+                int j = mid;
+                for (int k = lo; k < hi; k++) {
+                    scratch[k] = j >= hi || (i < mid && a[i] <= a[j]) ? a[i++] : a[j++];
+                }
+                System.arraycopy(scratch, lo, a, lo, hi - lo);
+            }
+        }
+    }
+
+    public static void sort(double[] a) {
+        double[] scratch = new double[a.length];
+        for (int width = 1; width < a.length; width *= 2) {
+            for (int lo = 0; lo < a.length - width; lo += 2 * width) {
+                int mid = lo + width;
+                int hi = Math.min(lo + 2 * width, a.length);
+                int i = lo;
+                int j = mid;
+                for (int k = lo; k < hi; k++) {
+                    scratch[k] = j >= hi || (i < mid && Double.compare(a[i], a[j]) <= 0) ? a[i++] : a[j++];
+                }
+                System.arraycopy(scratch, lo, a, lo, hi - lo);
+            }
+        }
+    }
+
+    public static void sort(char[] a) {
+        char[] scratch = new char[a.length];
+        for (int width = 1; width < a.length; width *= 2) {
+            for (int lo = 0; lo < a.length - width; lo += 2 * width) {
+                int mid = lo + width;
+                int hi = Math.min(lo + 2 * width, a.length);
+                int i = lo;
+                int j = mid;
+                for (int k = lo; k < hi; k++) {
+                    scratch[k] = j >= hi || (i < mid && a[i] <= a[j]) ? a[i++] : a[j++];
+                }
+                System.arraycopy(scratch, lo, a, lo, hi - lo);
+            }
+        }
+    }
+
+    // Elements must be Comparable, like Arrays.sort(Object[]) in the JDK
+    public static void sort(Object[] a) {
+        Object[] scratch = new Object[a.length];
+        for (int width = 1; width < a.length; width *= 2) {
+            for (int lo = 0; lo < a.length - width; lo += 2 * width) {
+                int mid = lo + width;
+                int hi = Math.min(lo + 2 * width, a.length);
+                int i = lo;
+                int j = mid;
+                for (int k = lo; k < hi; k++) {
+                    scratch[k] = j >= hi || (i < mid && ((Comparable<Object>) a[i]).compareTo(a[j]) <= 0) ? a[i++] : a[j++];
+                }
+                System.arraycopy(scratch, lo, a, lo, hi - lo);
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+package java.util;
+
+// %[index$][flags][width][.precision]conversion with the b, c, d, o, x, e, f, s, n and %
+// conversions, the flags - 0 + space , and #, and no locales
+public final class Formatter {
+    private final StringBuilder out = new StringBuilder();
+
+    public Formatter format(String format, Object... args) {
+        int next = 0;
+        int i = 0;
+        while (i < format.length()) {
+            char c = format.charAt(i++);
+            if (c != '%') {
+                out.append(c);
+                continue;
+            }
+            int start = i - 1;
+            int index = -1;
+            int digits = i;
+            while (i < format.length() && Character.isDigit(format.charAt(i))) {
+                i++;
+            }
+            if (i < format.length() && format.charAt(i) == '$' && i > digits) {
+                index = Integer.parseInt(format.substring(digits, i)) - 1;
+                i++;
+            } else {
+                i = digits;
+            }
+            String flags = "";
+            while (i < format.length() && "-#+ 0,(".indexOf(format.charAt(i)) >= 0) {
+                flags += format.charAt(i++);
+            }
+            int width = -1;
+            digits = i;
+            while (i < format.length() && Character.isDigit(format.charAt(i))) {
+                i++;
+            }
+            if (i > digits) {
+                width = Integer.parseInt(format.substring(digits, i));
+            }
+            int precision = -1;
+            if (i < format.length() && format.charAt(i) == '.') {
+                digits = ++i;
+                while (i < format.length() && Character.isDigit(format.charAt(i))) {
+                    i++;
+                }
+                if (i == digits) {
+                    throw new IllegalArgumentException("Illegal format " + format.substring(start, i));
+                }
+                precision = Integer.parseInt(format.substring(digits, i));
+            }
+            if (i == format.length()) {
+                throw new IllegalArgumentException("Format specifier '" + format.substring(start) + "'");
+            }
+            char conversion = format.charAt(i++);
+            if (conversion == 'n') {
+                out.append('\n');
+                continue;
+            }
+            if (conversion == '%') {
+                pad("%", flags, width);
+                continue;
+            }
+            if (index < 0) {
+                index = next++;
+            }
+            if (args == null || index >= args.length) {
+                throw new IllegalArgumentException("Format specifier '" + format.substring(start, i) + "'");
+            }
+            String formatted = convert(conversion, args[index], flags, precision);
+            if (Character.isUpperCase(conversion)) {
+                formatted = formatted.toUpperCase();
+            }
+            pad(formatted, flags, width);
+        }
+        return this;
+    }
+
+    private static String convert(char conversion, Object arg, String flags, int precision) {
+        switch (Character.toLowerCase(conversion)) {
+        case 'b':
+            return truncate(String.valueOf(arg != null && (!(arg instanceof Boolean) || ((Boolean) arg).booleanValue())), precision);
+        case 's':
+            return truncate(String.valueOf(arg), precision);
+        case 'h':
+            return truncate(arg == null ? "null" : Integer.toHexString(arg.hashCode()), precision);
+        case 'c':
+            if (arg == null) {
+                return "null";
+            }
+            if (arg instanceof Character) {
+                return arg.toString();
+            }
+            return new String(Character.toChars(((Number) arg).intValue()));
+        case 'd':
+            if (arg == null) {
+                return "null";
+            }
+            return decimal(((Number) arg).longValue(), flags);
+        case 'o':
+        case 'x':
+            if (arg == null) {
+                return "null";
+            }
+            long value = ((Number) arg).longValue();
+            if (value < 0 && !(arg instanceof Long)) {
+                value &= arg instanceof Integer ? 0xffffffffL : (arg instanceof Short ? 0xffffL : 0xffL);
+            }
+            String digits = conversion == 'o' || conversion == 'O' ? Long.toOctalString(value) : Long.toHexString(value);
+            if (flags.indexOf('#') >= 0) {
+                digits = (conversion == 'o' ? "0" : "0x") + digits;
+            }
+            return digits;
+        case 'e':
+        case 'f':
+            if (arg == null) {
+                return "null";
+            }
+            double d = ((Number) arg).doubleValue();
+            if (d != d || Double.isInfinite(d)) {
+                return d > 0 && flags.indexOf('+') >= 0 ? "+Infinity" : Double.toString(d);
+            }
+            String number = Character.toLowerCase(conversion) == 'e'
+                ? scientific(d, precision < 0 ? 6 : precision)
+                : fixed(d, precision < 0 ? 6 : precision);
+            boolean negative = number.startsWith("-");
+            if (negative) {
+                number = number.substring(1);
+            }
+            if (flags.indexOf(',') >= 0 && Character.toLowerCase(conversion) == 'f') {
+                int point = number.indexOf('.');
+                number = group(point < 0 ? number : number.substring(0, point)) + (point < 0 ? "" : number.substring(point));
+            }
+            return sign(number, negative, flags);
+        default:
+            throw new IllegalArgumentException("Conversion = '" + conversion + "'");
+        }
+    }
+
+    private static String decimal(long value, String flags) {
+        String digits = Long.toString(value);
+        boolean negative = value < 0;
+        if (negative) {
+            digits = digits.substring(1);
+        }
+        if (flags.indexOf(',') >= 0) {
+            digits = group(digits);
+        }
+        return sign(digits, negative, flags);
+    }
+
+    private static String sign(String digits, boolean negative, String flags) {
+        if (negative) {
+            return flags.indexOf('(') >= 0 ? "(" + digits + ")" : "-" + digits;
+        }
+        if (flags.indexOf('+') >= 0) {
+            return "+" + digits;
+        }
+        if (flags.indexOf(' ') >= 0) {
+            return " " + digits;
+        }
+        return digits;
+    }
+
+    private static String group(String digits) {
+        StringBuilder grouped = new StringBuilder();
+        for (int i = 0; i < digits.length(); i++) {
+            if (i > 0 && (digits.length() - i) % 3 == 0) {
+                grouped.append(',');
+            }
+            grouped.append(digits.charAt(i));
+        }
+        return grouped.toString();
+    }
+
+    private static String truncate(String s, int precision) {
+        return precision >= 0 && precision < s.length() ? s.substring(0, precision) : s;
+    }
+
+    // Zero padding goes after the sign or the 0x prefix
+    private void pad(String s, String flags, int width) {
+        int padding = width - s.length();
+        if (padding <= 0) {
+            out.append(s);
+        } else if (flags.indexOf('-') >= 0) {
+            out.append(s);
+            for (int i = 0; i < padding; i++) {
+                out.append(' ');
+            }
+        } else if (flags.indexOf('0') >= 0) {
+            int prefix = 0;
+            if (s.startsWith("-") || s.startsWith("+") || s.startsWith(" ") || s.startsWith("(")) {
+                prefix = 1;
+            }
+            if (s.startsWith("0x", prefix) || s.startsWith("0X", prefix)) {
+                prefix += 2;
+            }
+            out.append(s, 0, prefix);
+            for (int i = 0; i < padding; i++) {
+                out.append('0');
+            }
+            out.append(s, prefix, s.length());
+        } else {
+            for (int i = 0; i < padding; i++) {
+                out.append(' ');
+            }
+            out.append(s);
+        }
+    }
+
+    // Rounded half up from the shortest decimal form of the value, like java.util.Formatter
+    private static native String fixed(double value, int precision);
+
+    private static native String scientific(double value, int precision);
+
+    public String toString() {
+        return out.toString();
+    }
+}
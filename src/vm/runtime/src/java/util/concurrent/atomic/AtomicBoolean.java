@@ -0,0 +1,46 @@
+package java.util.concurrent.atomic;
+
+import jdk.internal.misc.Unsafe;
+
+// The flag is kept as an int like the JDK does, 0 or 1
+public class AtomicBoolean implements java.io.Serializable {
+    private static final Unsafe U = Unsafe.getUnsafe();
+    private static final long VALUE = U.objectFieldOffset(AtomicBoolean.class, "value");
+
+    private volatile int value;
+
+    public AtomicBoolean(boolean initialValue) {
+        value = initialValue ? 1 : 0;
+    }
+
+    public AtomicBoolean() {
+    }
+
+    public final boolean get() {
+        return value != 0;
+    }
+
+    public final void set(boolean newValue) {
+        value = newValue ? 1 : 0;
+    }
+
+    public final void lazySet(boolean newValue) {
+        U.putIntRelease(this, VALUE, newValue ? 1 : 0);
+    }
+
+    public final boolean getAndSet(boolean newValue) {
+        return U.getAndSetInt(this, VALUE, newValue ? 1 : 0) != 0;
+    }
+
+    public final boolean compareAndSet(boolean expectedValue, boolean newValue) {
+        return U.compareAndSetInt(this, VALUE, expectedValue ? 1 : 0, newValue ? 1 : 0);
+    }
+
+    public boolean weakCompareAndSet(boolean expectedValue, boolean newValue) {
+        return U.weakCompareAndSetInt(this, VALUE, expectedValue ? 1 : 0, newValue ? 1 : 0);
+    }
+
+    public String toString() {
+        return Boolean.toString(get());
+    }
+}
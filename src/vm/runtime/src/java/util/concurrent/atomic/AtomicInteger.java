@@ -0,0 +1,86 @@
+package java.util.concurrent.atomic;
+
+import jdk.internal.misc.Unsafe;
+
+// Without the updates that take a function, there are no lambdas in java.util.function
+public class AtomicInteger extends Number implements java.io.Serializable {
+    private static final Unsafe U = Unsafe.getUnsafe();
+    private static final long VALUE = U.objectFieldOffset(AtomicInteger.class, "value");
+
+    private volatile int value;
+
+    public AtomicInteger(int initialValue) {
+        value = initialValue;
+    }
+
+    public AtomicInteger() {
+    }
+
+    public final int get() {
+        return value;
+    }
+
+    public final void set(int newValue) {
+        value = newValue;
+    }
+
+    public final void lazySet(int newValue) {
+        U.putIntRelease(this, VALUE, newValue);
+    }
+
+    public final int getAndSet(int newValue) {
+        return U.getAndSetInt(this, VALUE, newValue);
+    }
+
+    public final boolean compareAndSet(int expectedValue, int newValue) {
+        return U.compareAndSetInt(this, VALUE, expectedValue, newValue);
+    }
+
+    public final boolean weakCompareAndSet(int expectedValue, int newValue) {
+        return U.weakCompareAndSetInt(this, VALUE, expectedValue, newValue);
+    }
+
+    public final int getAndIncrement() {
+        return U.getAndAddInt(this, VALUE, 1);
+    }
+
+    public final int getAndDecrement() {
+        return U.getAndAddInt(this, VALUE, -1);
+    }
+
+    public final int getAndAdd(int delta) {
+        return U.getAndAddInt(this, VALUE, delta);
+    }
+
+    public final int incrementAndGet() {
+        return U.getAndAddInt(this, VALUE, 1) + 1;
+    }
+
+    public final int decrementAndGet() {
+        return U.getAndAddInt(this, VALUE, -1) - 1;
+    }
+
+    public final int addAndGet(int delta) {
+        return U.getAndAddInt(this, VALUE, delta) + delta;
+    }
+
+    public String toString() {
+        return Integer.toString(get());
+    }
+
+    public int intValue() {
+        return get();
+    }
+
+    public long longValue() {
+        return (long) get();
+    }
+
+    public float floatValue() {
+        return (float) get();
+    }
+
+    public double doubleValue() {
+        return (double) get();
+    }
+}
@@ -0,0 +1,85 @@
+package java.util.concurrent.atomic;
+
+import jdk.internal.misc.Unsafe;
+
+public class AtomicLong extends Number implements java.io.Serializable {
+    private static final Unsafe U = Unsafe.getUnsafe();
+    private static final long VALUE = U.objectFieldOffset(AtomicLong.class, "value");
+
+    private volatile long value;
+
+    public AtomicLong(long initialValue) {
+        value = initialValue;
+    }
+
+    public AtomicLong() {
+    }
+
+    public final long get() {
+        return value;
+    }
+
+    public final void set(long newValue) {
+        value = newValue;
+    }
+
+    public final void lazySet(long newValue) {
+        U.putLongRelease(this, VALUE, newValue);
+    }
+
+    public final long getAndSet(long newValue) {
+        return U.getAndSetLong(this, VALUE, newValue);
+    }
+
+    public final boolean compareAndSet(long expectedValue, long newValue) {
+        return U.compareAndSetLong(this, VALUE, expectedValue, newValue);
+    }
+
+    public final boolean weakCompareAndSet(long expectedValue, long newValue) {
+        return U.weakCompareAndSetLong(this, VALUE, expectedValue, newValue);
+    }
+
+    public final long getAndIncrement() {
+        return U.getAndAddLong(this, VALUE, 1L);
+    }
+
+    public final long getAndDecrement() {
+        return U.getAndAddLong(this, VALUE, -1L);
+    }
+
+    public final long getAndAdd(long delta) {
+        return U.getAndAddLong(this, VALUE, delta);
+    }
+
+    public final long incrementAndGet() {
+        return U.getAndAddLong(this, VALUE, 1L) + 1L;
+    }
+
+    public final long decrementAndGet() {
+        return U.getAndAddLong(this, VALUE, -1L) - 1L;
+    }
+
+    public final long addAndGet(long delta) {
+        return U.getAndAddLong(this, VALUE, delta) + delta;
+    }
+
+    public String toString() {
+        return Long.toString(get());
+    }
+
+    public int intValue() {
+        return (int) get();
+    }
+
+    public long longValue() {
+        return get();
+    }
+
+    public float floatValue() {
+        return (float) get();
+    }
+
+    public double doubleValue() {
+        return (double) get();
+    }
+}
@@ -0,0 +1,46 @@
+package java.util.concurrent.atomic;
+
+import jdk.internal.misc.Unsafe;
+
+// Compares by identity, like ==
+public class AtomicReference<V> implements java.io.Serializable {
+    private static final Unsafe U = Unsafe.getUnsafe();
+    private static final long VALUE = U.objectFieldOffset(AtomicReference.class, "value");
+
+    private volatile V value;
+
+    public AtomicReference(V initialValue) {
+        value = initialValue;
+    }
+
+    public AtomicReference() {
+    }
+
+    public final V get() {
+        return value;
+    }
+
+    public final void set(V newValue) {
+        value = newValue;
+    }
+
+    public final void lazySet(V newValue) {
+        U.putReferenceRelease(this, VALUE, newValue);
+    }
+
+    public final boolean compareAndSet(V expectedValue, V newValue) {
+        return U.compareAndSetReference(this, VALUE, expectedValue, newValue);
+    }
+
+    public final boolean weakCompareAndSet(V expectedValue, V newValue) {
+        return U.weakCompareAndSetReference(this, VALUE, expectedValue, newValue);
+    }
+
+    public final V getAndSet(V newValue) {
+        return (V) U.getAndSetReference(this, VALUE, newValue);
+    }
+
+    public String toString() {
+        return String.valueOf(get());
+    }
+}
@@ -0,0 +1,302 @@
+package java.util.concurrent.locks;
+
+import jdk.internal.misc.Unsafe;
+
+// Exclusive mode only. A thread that can't acquire joins the queue and parks until every node
+// before it is gone, a release unparks the first thread still waiting. Interrupted waits leave
+// their node in the queue as cancelled
+public abstract class AbstractQueuedSynchronizer extends AbstractOwnableSynchronizer {
+    private static final Unsafe U = Unsafe.getUnsafe();
+    private static final long STATE = U.objectFieldOffset(AbstractQueuedSynchronizer.class, "state");
+    private static final long HEAD = U.objectFieldOffset(AbstractQueuedSynchronizer.class, "head");
+    private static final long TAIL = U.objectFieldOffset(AbstractQueuedSynchronizer.class, "tail");
+
+    static final class Node {
+        // Set before the node is the tail, so the queue can always be walked back from the tail
+        volatile Node prev;
+        volatile Thread waiter;
+        volatile boolean cancelled;
+        // Condition queues, changed only by the thread holding the lock
+        Node nextWaiter;
+        volatile boolean signalled;
+
+        Node(Thread waiter) {
+            this.waiter = waiter;
+        }
+    }
+
+    // A node whose thread has acquired, or the node the queue started with
+    private transient volatile Node head;
+    private transient volatile Node tail;
+    private volatile int state;
+
+    protected AbstractQueuedSynchronizer() {
+    }
+
+    protected final int getState() {
+        return state;
+    }
+
+    protected final void setState(int newState) {
+        state = newState;
+    }
+
+    protected final boolean compareAndSetState(int expect, int update) {
+        return U.compareAndSetInt(this, STATE, expect, update);
+    }
+
+    protected boolean tryAcquire(int arg) {
+        throw new UnsupportedOperationException();
+    }
+
+    protected boolean tryRelease(int arg) {
+        throw new UnsupportedOperationException();
+    }
+
+    protected boolean isHeldExclusively() {
+        throw new UnsupportedOperationException();
+    }
+
+    public final void acquire(int arg) {
+        if (!tryAcquire(arg) && acquireQueued(arg, false)) {
+            Thread.currentThread().interrupt();
+        }
+    }
+
+    public final void acquireInterruptibly(int arg) throws InterruptedException {
+        if (Thread.interrupted() || !tryAcquire(arg) && acquireQueued(arg, true)) {
+            throw new InterruptedException();
+        }
+    }
+
+    public final boolean release(int arg) {
+        if (tryRelease(arg)) {
+            signalFirst();
+            return true;
+        }
+        return false;
+    }
+
+    public final boolean hasQueuedThreads() {
+        return first() != null;
+    }
+
+    public final int getQueueLength() {
+        int length = 0;
+        Node h = head;
+        for (Node p = tail; p != null && p != h; p = p.prev) {
+            if (!p.cancelled) {
+                length++;
+            }
+        }
+        return length;
+    }
+
+    // Queues the current thread and parks it until it is first and acquires. Whether it was
+    // interrupted meanwhile, an interruptible acquire gives up then
+    private boolean acquireQueued(int arg, boolean interruptible) {
+        Node node = enqueue(new Node(Thread.currentThread()));
+        boolean interrupted = false;
+        for (;;) {
+            Node h = head;
+            Node p = node.prev;
+            while (p != h && p != null && p.cancelled) {
+                p = p.prev;
+            }
+            if (p == h && tryAcquire(arg)) {
+                node.prev = null;
+                node.waiter = null;
+                head = node;
+                return interrupted;
+            }
+            if (interrupted && interruptible) {
+                node.waiter = null;
+                node.cancelled = true;
+                signalFirst();
+                return true;
+            }
+            LockSupport.park(this);
+            interrupted |= Thread.interrupted();
+        }
+    }
+
+    private Node enqueue(Node node) {
+        for (;;) {
+            Node t = tail;
+            if (t == null) {
+                if (U.compareAndSetReference(this, HEAD, null, new Node(null))) {
+                    tail = head;
+                }
+            } else {
+                node.prev = t;
+                if (U.compareAndSetReference(this, TAIL, t, node)) {
+                    return node;
+                }
+            }
+        }
+    }
+
+    // The earliest node after the head that isn't cancelled
+    private Node first() {
+        Node h = head;
+        Node first = null;
+        for (Node p = tail; p != null && p != h; p = p.prev) {
+            if (!p.cancelled) {
+                first = p;
+            }
+        }
+        return first;
+    }
+
+    private void signalFirst() {
+        Node first = first();
+        if (first != null) {
+            LockSupport.unpark(first.waiter);
+        }
+    }
+
+    // Waiting for a signal gives up the whole hold count and takes it back before returning
+    public class ConditionObject implements Condition, java.io.Serializable {
+        private transient Node firstWaiter;
+        private transient Node lastWaiter;
+
+        public ConditionObject() {
+        }
+
+        public final void await() throws InterruptedException {
+            if (Thread.interrupted()) {
+                throw new InterruptedException();
+            }
+            Node node = addWaiter();
+            int saved = fullyRelease();
+            boolean interrupted = false;
+            while (!node.signalled) {
+                LockSupport.park(this);
+                if (Thread.interrupted()) {
+                    interrupted = true;
+                    break;
+                }
+            }
+            reacquire(node, saved, interrupted, true);
+        }
+
+        public final void awaitUninterruptibly() {
+            Node node = addWaiter();
+            int saved = fullyRelease();
+            boolean interrupted = false;
+            while (!node.signalled) {
+                LockSupport.park(this);
+                interrupted |= Thread.interrupted();
+            }
+            acquire(saved);
+            if (interrupted) {
+                Thread.currentThread().interrupt();
+            }
+        }
+
+        public final long awaitNanos(long nanosTimeout) throws InterruptedException {
+            if (Thread.interrupted()) {
+                throw new InterruptedException();
+            }
+            Node node = addWaiter();
+            int saved = fullyRelease();
+            long deadline = System.nanoTime() + nanosTimeout;
+            boolean interrupted = false;
+            while (!node.signalled && nanosTimeout > 0) {
+                LockSupport.parkNanos(this, nanosTimeout);
+                if (Thread.interrupted()) {
+                    interrupted = true;
+                    break;
+                }
+                nanosTimeout = deadline - System.nanoTime();
+            }
+            reacquire(node, saved, interrupted, true);
+            long remaining = deadline - System.nanoTime();
+            return remaining <= nanosTimeout ? remaining : Long.MIN_VALUE;
+        }
+
+        public final void signal() {
+            if (!isHeldExclusively()) {
+                throw new IllegalMonitorStateException();
+            }
+            Node node = firstWaiter;
+            if (node != null) {
+                unlink(node);
+                wake(node);
+            }
+        }
+
+        public final void signalAll() {
+            if (!isHeldExclusively()) {
+                throw new IllegalMonitorStateException();
+            }
+            Node node = firstWaiter;
+            firstWaiter = lastWaiter = null;
+            while (node != null) {
+                Node next = node.nextWaiter;
+                node.nextWaiter = null;
+                wake(node);
+                node = next;
+            }
+        }
+
+        private Node addWaiter() {
+            if (!isHeldExclusively()) {
+                throw new IllegalMonitorStateException();
+            }
+            Node node = new Node(Thread.currentThread());
+            if (lastWaiter == null) {
+                firstWaiter = node;
+            } else {
+                lastWaiter.nextWaiter = node;
+            }
+            lastWaiter = node;
+            return node;
+        }
+
+        private int fullyRelease() {
+            int saved = getState();
+            if (!release(saved)) {
+                throw new IllegalMonitorStateException();
+            }
+            return saved;
+        }
+
+        private void wake(Node node) {
+            node.signalled = true;
+            LockSupport.unpark(node.waiter);
+        }
+
+        // A signal that came before the interrupt counts, the interrupt is kept for later
+        private void reacquire(Node node, int saved, boolean interrupted, boolean interruptible) throws InterruptedException {
+            acquire(saved);
+            if (!node.signalled) {
+                unlink(node);
+            } else if (interrupted) {
+                Thread.currentThread().interrupt();
+                return;
+            }
+            if (interrupted && interruptible) {
+                throw new InterruptedException();
+            }
+        }
+
+        private void unlink(Node node) {
+            Node prev = null;
+            for (Node p = firstWaiter; p != null; prev = p, p = p.nextWaiter) {
+                if (p == node) {
+                    if (prev == null) {
+                        firstWaiter = p.nextWaiter;
+                    } else {
+                        prev.nextWaiter = p.nextWaiter;
+                    }
+                    if (lastWaiter == p) {
+                        lastWaiter = prev;
+                    }
+                    p.nextWaiter = null;
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+package java.util.concurrent.locks;
+
+// Without the waits that take a TimeUnit or a Date
+public interface Condition {
+    void await() throws InterruptedException;
+
+    void awaitUninterruptibly();
+
+    long awaitNanos(long nanosTimeout) throws InterruptedException;
+
+    void signal();
+
+    void signalAll();
+}
@@ -0,0 +1,14 @@
+package java.util.concurrent.locks;
+
+// Without the timed tryLock, there is no TimeUnit
+public interface Lock {
+    void lock();
+
+    void lockInterruptibly() throws InterruptedException;
+
+    boolean tryLock();
+
+    void unlock();
+
+    Condition newCondition();
+}
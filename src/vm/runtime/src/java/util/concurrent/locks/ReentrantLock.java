@@ -0,0 +1,109 @@
+package java.util.concurrent.locks;
+
+// Always nonfair, a thread that finds the lock free takes it even with others queued
+public class ReentrantLock implements Lock, java.io.Serializable {
+    private final Sync sync;
+
+    static final class Sync extends AbstractQueuedSynchronizer {
+        protected boolean tryAcquire(int acquires) {
+            Thread current = Thread.currentThread();
+            int c = getState();
+            if (c == 0) {
+                if (compareAndSetState(0, acquires)) {
+                    setExclusiveOwnerThread(current);
+                    return true;
+                }
+            } else if (getExclusiveOwnerThread() == current) {
+                int next = c + acquires;
+                if (next < 0) {
+                    throw new Error("Maximum lock count exceeded");
+                }
+                setState(next);
+                return true;
+            }
+            return false;
+        }
+
+        protected boolean tryRelease(int releases) {
+            if (getExclusiveOwnerThread() != Thread.currentThread()) {
+                throw new IllegalMonitorStateException();
+            }
+            int c = getState() - releases;
+            boolean free = c == 0;
+            if (free) {
+                setExclusiveOwnerThread(null);
+            }
+            setState(c);
+            return free;
+        }
+
+        protected boolean isHeldExclusively() {
+            return getExclusiveOwnerThread() == Thread.currentThread();
+        }
+
+        Thread getOwner() {
+            return getState() == 0 ? null : getExclusiveOwnerThread();
+        }
+
+        int getHoldCount() {
+            return isHeldExclusively() ? getState() : 0;
+        }
+    }
+
+    public ReentrantLock() {
+        sync = new Sync();
+    }
+
+    public ReentrantLock(boolean fair) {
+        this();
+    }
+
+    public void lock() {
+        sync.acquire(1);
+    }
+
+    public void lockInterruptibly() throws InterruptedException {
+        sync.acquireInterruptibly(1);
+    }
+
+    public boolean tryLock() {
+        return sync.tryAcquire(1);
+    }
+
+    public void unlock() {
+        sync.release(1);
+    }
+
+    public Condition newCondition() {
+        return sync.new ConditionObject();
+    }
+
+    public int getHoldCount() {
+        return sync.getHoldCount();
+    }
+
+    public boolean isHeldByCurrentThread() {
+        return sync.isHeldExclusively();
+    }
+
+    public boolean isLocked() {
+        return sync.getOwner() != null;
+    }
+
+    public final boolean isFair() {
+        return false;
+    }
+
+    public final boolean hasQueuedThreads() {
+        return sync.hasQueuedThreads();
+    }
+
+    public final int getQueueLength() {
+        return sync.getQueueLength();
+    }
+
+    public String toString() {
+        Thread owner = sync.getOwner();
+        return super.toString() + (owner == null ? "[Unlocked]" : "[Locked by thread " + owner.getName() + "]");
+    }
+}
@@ -0,0 +1,162 @@
+package jdk.internal.misc;
+
+import java.lang.reflect.Field;
+
+// What java.util.concurrent uses to get at fields and array elements. An offset is a field slot or
+// an array index, there is no memory outside the heap
+public final class Unsafe {
+    private static final Unsafe theUnsafe = new Unsafe();
+
+    public static final int INVALID_FIELD_OFFSET = -1;
+
+    private Unsafe() {
+    }
+
+    public static Unsafe getUnsafe() {
+        return theUnsafe;
+    }
+
+    public long objectFieldOffset(Field f) {
+        if (f == null) {
+            throw new NullPointerException();
+        }
+        return objectFieldOffset1(f.getDeclaringClass(), f.getName());
+    }
+
+    public long objectFieldOffset(Class<?> c, String name) {
+        if (c == null || name == null) {
+            throw new NullPointerException();
+        }
+        return objectFieldOffset1(c, name);
+    }
+
+    private native long objectFieldOffset1(Class<?> c, String name);
+
+    public int arrayBaseOffset(Class<?> arrayClass) {
+        return 0;
+    }
+
+    public int arrayIndexScale(Class<?> arrayClass) {
+        return 1;
+    }
+
+    public native int getIntVolatile(Object o, long offset);
+
+    public native void putIntVolatile(Object o, long offset, int x);
+
+    public native long getLongVolatile(Object o, long offset);
+
+    public native void putLongVolatile(Object o, long offset, long x);
+
+    public native Object getReferenceVolatile(Object o, long offset);
+
+    public native void putReferenceVolatile(Object o, long offset, Object x);
+
+    // Every access is as strong as a volatile one
+    public int getInt(Object o, long offset) {
+        return getIntVolatile(o, offset);
+    }
+
+    public void putInt(Object o, long offset, int x) {
+        putIntVolatile(o, offset, x);
+    }
+
+    public long getLong(Object o, long offset) {
+        return getLongVolatile(o, offset);
+    }
+
+    public void putLong(Object o, long offset, long x) {
+        putLongVolatile(o, offset, x);
+    }
+
+    public Object getReference(Object o, long offset) {
+        return getReferenceVolatile(o, offset);
+    }
+
+    public void putReference(Object o, long offset, Object x) {
+        putReferenceVolatile(o, offset, x);
+    }
+
+    public void putIntRelease(Object o, long offset, int x) {
+        putIntVolatile(o, offset, x);
+    }
+
+    public void putLongRelease(Object o, long offset, long x) {
+        putLongVolatile(o, offset, x);
+    }
+
+    public void putReferenceRelease(Object o, long offset, Object x) {
+        putReferenceVolatile(o, offset, x);
+    }
+
+    public native boolean compareAndSetInt(Object o, long offset, int expected, int x);
+
+    public native boolean compareAndSetLong(Object o, long offset, long expected, long x);
+
+    public native boolean compareAndSetReference(Object o, long offset, Object expected, Object x);
+
+    // Never fail spuriously
+    public boolean weakCompareAndSetInt(Object o, long offset, int expected, int x) {
+        return compareAndSetInt(o, offset, expected, x);
+    }
+
+    public boolean weakCompareAndSetLong(Object o, long offset, long expected, long x) {
+        return compareAndSetLong(o, offset, expected, x);
+    }
+
+    public boolean weakCompareAndSetReference(Object o, long offset, Object expected, Object x) {
+        return compareAndSetReference(o, offset, expected, x);
+    }
+
+    public int getAndAddInt(Object o, long offset, int delta) {
+        int v;
+        do {
+            v = getIntVolatile(o, offset);
+        } while (!weakCompareAndSetInt(o, offset, v, v + delta));
+        return v;
+    }
+
+    public long getAndAddLong(Object o, long offset, long delta) {
+        long v;
+        do {
+            v = getLongVolatile(o, offset);
+        } while (!weakCompareAndSetLong(o, offset, v, v + delta));
+        return v;
+    }
+
+    public int getAndSetInt(Object o, long offset, int newValue) {
+        int v;
+        do {
+            v = getIntVolatile(o, offset);
+        } while (!weakCompareAndSetInt(o, offset, v, newValue));
+        return v;
+    }
+
+    public long getAndSetLong(Object o, long offset, long newValue) {
+        long v;
+        do {
+            v = getLongVolatile(o, offset);
+        } while (!weakCompareAndSetLong(o, offset, v, newValue));
+        return v;
+    }
+
+    public Object getAndSetReference(Object o, long offset, Object newValue) {
+        Object v;
+        do {
+            v = getReferenceVolatile(o, offset);
+        } while (!weakCompareAndSetReference(o, offset, v, newValue));
+        return v;
+    }
+
+    // Blocks the current thread until unpark, an interrupt or the deadline: relative nanoseconds,
+    // 0 for none, or absolute milliseconds since the epoch
+    public native void park(boolean isAbsolute, long time);
+
+    public native void unpark(Object thread);
+
+    public native void fullFence();
+
+    public native void loadFence();
+
+    public native void storeFence();
+}
@@ -0,0 +1,24 @@
+package jdk.internal.reflect;
+
+import java.lang.reflect.Modifier;
+
+// What reflection asks the VM about the code that uses it
+public final class Reflection {
+    private Reflection() {
+    }
+
+    // The class of the method that called the method calling this, a spun class stands for its
+    // host
+    public static native Class<?> getCallerClass();
+
+    // Whether code in `caller` may use a member of `declaring` with the modifiers, JVMS §5.4.4
+    public static native boolean verifyMemberAccess(Class<?> caller, Class<?> declaring, int modifiers);
+
+    public static void ensureMemberAccess(Class<?> caller, Class<?> declaring, int modifiers) throws IllegalAccessException {
+        if (!verifyMemberAccess(caller, declaring, modifiers)) {
+            throw new IllegalAccessException("class " + caller.getName() + " cannot access a member of class " + declaring.getName()
+                + " with modifiers \"" + Modifier.toString(modifiers & (Modifier.PUBLIC | Modifier.PRIVATE | Modifier.PROTECTED
+                | Modifier.STATIC | Modifier.FINAL)) + "\"");
+        }
+    }
+}
@@ -0,0 +1,117 @@
+/*
+* Classes the VM makes while a program runs, assembled from jasm source: the adapters of
+* signature polymorphic call sites, the invokers of direct method handles and the classes of
+* lambdas and string concatenation (vm::lambda). A spun class acts for its host class, it has
+* the host's access and its frames are left out of stack traces
+*/
+use class_file::descriptor::FieldType;
+use jasm;
+use vm::class::{descriptor_of, ClassId};
+use vm::invoke::{unwrapped, wrapper_of};
+use vm::{Flow, Vm};
+
+impl Vm {
+    pub(super) fn spin(&mut self, host: ClassId, source: &str) -> Flow<ClassId> {
+        let class_file = jasm::assemble_class(source)?;
+        let (super_class, interfaces) = self.load_supers(&class_file)?;
+        let class = self.define_class(class_file, super_class, interfaces)?;
+        self.class_mut(class).host = Some(host);
+        Ok(class)
+    }
+
+    // A class name no other class has, "java/lang/invoke/Adapter$3" for "java/lang/invoke/Adapter"
+    pub(super) fn spun_name(&mut self, prefix: &str) -> String {
+        self.spun += 1;
+        format!("{}${}", prefix, self.spun)
+    }
+}
+
+// Pushes the local of the type at `slot`
+pub(super) fn load(field_type: &FieldType, slot: usize) -> String {
+    let prefix = match *field_type {
+        FieldType::Long => "l",
+        FieldType::Float => "f",
+        FieldType::Double => "d",
+        FieldType::Object(_) | FieldType::Array(_) => "a",
+        _ => "i",
+    };
+    format!("{}load {}", prefix, slot)
+}
+
+// Returns a value of the type, None is void
+pub(super) fn return_value(field_type: Option<&FieldType>) -> String {
+    match field_type {
+        None => "return".to_string(),
+        Some(field_type) => match *field_type {
+            FieldType::Long => "lreturn".to_string(),
+            FieldType::Float => "freturn".to_string(),
+            FieldType::Double => "dreturn".to_string(),
+            FieldType::Object(_) | FieldType::Array(_) => "areturn".to_string(),
+            _ => "ireturn".to_string(),
+        },
+    }
+}
+
+// Turns the value of the type on the stack into an object, void into null
+pub(super) fn box_value(field_type: Option<&FieldType>) -> String {
+    match field_type {
+        None => "aconst_null".to_string(),
+        Some(field_type) => match wrapper_of(field_type) {
+            Some(wrapper) => format!("invokestatic Method {} valueOf ({})L{};", wrapper, descriptor_of(field_type), wrapper),
+            None => String::new(),
+        },
+    }
+}
+
+// Turns the object on the stack into a value of the type, unboxing it for primitive types. The
+// object is dropped for void
+pub(super) fn unbox_value(field_type: Option<&FieldType>) -> String {
+    match field_type {
+        None => "pop".to_string(),
+        Some(field_type) => match wrapper_of(field_type) {
+            Some(wrapper) => format!("checkcast {}\ninvokevirtual Method {} {}Value (){}",
+                wrapper, wrapper, field_type.type_name(), descriptor_of(field_type)),
+            None => match *field_type {
+                FieldType::Object(ref class_name) if class_name == "java/lang/Object" => String::new(),
+                FieldType::Object(ref class_name) => format!("checkcast {}", jasm::word(class_name)),
+                _ => format!("checkcast {}", jasm::word(&descriptor_of(field_type))),
+            },
+        },
+    }
+}
+
+// Turns the value of `from` on the stack into a `to` like a method invocation context does,
+// JLS §5.3: primitive widening, boxing, unboxing then widening, and casts. A value for void is
+// dropped
+pub(super) fn convert(from: Option<&FieldType>, to: Option<&FieldType>) -> String {
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) if from != to => (from, to),
+        (Some(from), None) if from.slots() == 2 => return "pop2".to_string(),
+        (Some(_), None) => return "pop".to_string(),
+        _ => return String::new(),
+    };
+    match (wrapper_of(from), wrapper_of(to)) {
+        (Some(_), Some(_)) => widen(from, to).to_string(),
+        (Some(_), None) => box_value(Some(from)),
+        (None, Some(_)) => match (from, unwrapped(from)) {
+            (FieldType::Object(wrapper), Some(primitive)) => format!("invokevirtual Method {} {}Value (){}\n{}",
+                wrapper, primitive.type_name(), descriptor_of(primitive), widen(primitive, to)),
+            _ => unbox_value(Some(to)),
+        },
+        (None, None) => unbox_value(Some(to)),
+    }
+}
+
+// JLS §5.1.2 widening primitive conversion
+fn widen(from: &FieldType, to: &FieldType) -> &'static str {
+    match (from, to) {
+        (&FieldType::Long, &FieldType::Float) => "l2f",
+        (&FieldType::Long, &FieldType::Double) => "l2d",
+        (&FieldType::Float, &FieldType::Double) => "f2d",
+        (&FieldType::Long, _) | (&FieldType::Float, _) | (&FieldType::Double, _) => "",
+        (_, &FieldType::Long) => "i2l",
+        (_, &FieldType::Float) => "i2f",
+        (_, &FieldType::Double) => "i2d",
+        _ => "",
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use result::{Result, Error};
+use types::u2;
+use vm::class::{ClassId, Method};
+use vm::heap::ObjectRef;
+use vm::value::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ThreadId(pub(super) u32);
+
+impl ThreadId {
+    pub fn index(self) -> usize { self.0 as usize }
+}
+
+pub struct Thread {
+    // The java/lang/Thread
+    pub(super) object: ObjectRef,
+    pub(super) daemon: bool,
+    pub(super) state: ThreadState,
+    pub(super) frames: Vec<Frame>,
+    // Set once the exception that ended the thread was handed to dispatchUncaughtException
+    pub(super) uncaught: Option<ObjectRef>,
+    // LockSupport's permit, unpark gives it and park takes it instead of blocking
+    pub(super) permit: bool,
+    // A blocking native of a JDK runs again once the thread wakes up, this tells it that it did
+    // block already
+    pub(super) resumed: bool,
+    // A JDK's Thread.exit ran, the thread ends when it returns
+    pub(super) exiting: bool,
+    // The main thread runs the steps of booting a JDK one after the other and doesn't end
+    // between them
+    pub(super) booting: bool,
+}
+
+impl Thread {
+    pub(super) fn new(object: ObjectRef, daemon: bool) -> Self {
+        Thread {
+            object,
+            daemon,
+            state: ThreadState::Runnable,
+            frames: Vec::new(),
+            uncaught: None,
+            permit: false,
+            resumed: false,
+            exiting: false,
+            booting: false,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.state != ThreadState::Terminated
+    }
+}
+
+// What the scheduler waits for before it runs the thread again. Thread.interrupt ends sleeping,
+// joining, waiting and parking early
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThreadState {
+    Runnable,
+    Sleeping(Instant),
+    // Until the thread ends or, with a timeout, the instant passes
+    Joining(ThreadId, Option<Instant>),
+    // Object.wait, until notify or the timeout. Then the thread enters the monitor again as many
+    // times as it had before
+    Waiting(ObjectRef, u32, Option<Instant>),
+    // Until the monitor is free, the scheduler then hands it to the thread
+    Entering(ObjectRef, u32),
+    // LockSupport.park, until unpark or the deadline
+    Parked(Option<Instant>),
+    Terminated,
+}
+
+pub struct Frame {
+    pub(super) method: Arc<Method>,
+    // Index of the current instruction in the method's code, not a pc. Stays on an invoke
+    // instruction until the callee returns
+    pub(super) ip: usize,
+    pub(super) locals: Vec<Value>,
+    pub(super) stack: Vec<Value>,
+    pub(super) kind: FrameKind,
+    // What a synchronized method locked, the frame leaves the monitor when it returns or throws
+    pub(super) monitor: Option<ObjectRef>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameKind {
+    Normal,
+    // <clinit> of the class, returning marks it initialized and leaves the caller as it was so
+    // the instruction that needed the class runs again
+    Initializer(ClassId),
+    // MethodHandleNatives.linkCallSite for the invokedynamic at the pc of the method (its address
+    // as a key), the call site it returns is kept and the instruction runs again
+    CallSite(usize, u32),
+    // MethodHandleNatives.linkDynamicConstant for the Dynamic constant of the class at the index,
+    // its value is kept and the instruction that needed it runs again
+    Constant(ClassId, u2),
+    // Called by the VM rather than by an instruction, what it returns goes nowhere
+    Detached,
+}
+
+impl Frame {
+    // Longs and doubles take two locals, the second is Top
+    pub(super) fn new(method: Arc<Method>, args: &[Value], kind: FrameKind) -> Self {
+        let max_locals = method.code.as_ref().map_or(0, |code| code.attribute.max_locals() as usize);
+        let mut locals = Vec::with_capacity(max_locals);
+        for &arg in args {
+            locals.push(arg);
+            if arg.is_wide() {
+                locals.push(Value::Top);
+            }
+        }
+        if locals.len() < max_locals {
+            locals.resize(max_locals, Value::Top);
+        }
+        let max_stack = method.code.as_ref().map_or(0, |code| code.attribute.max_stack() as usize);
+        Frame {
+            method,
+            ip: 0,
+            locals,
+            stack: Vec::with_capacity(max_stack),
+            kind,
+            monitor: None,
+        }
+    }
+
+    pub(super) fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    pub(super) fn pop(&mut self) -> Result<Value> {
+        self.stack.pop().ok_or_else(|| Error::Verify("operand stack underflow".to_string()))
+    }
+
+    pub(super) fn pop_int(&mut self) -> Result<i32> {
+        self.pop()?.as_int()
+    }
+
+    pub(super) fn pop_long(&mut self) -> Result<i64> {
+        self.pop()?.as_long()
+    }
+
+    pub(super) fn pop_float(&mut self) -> Result<f32> {
+        self.pop()?.as_float()
+    }
+
+    pub(super) fn pop_double(&mut self) -> Result<f64> {
+        self.pop()?.as_double()
+    }
+
+    pub(super) fn pop_ref(&mut self) -> Result<Option<ObjectRef>> {
+        self.pop()?.as_ref()
+    }
+
+    pub(super) fn local(&self, index: usize) -> Result<Value> {
+        self.locals.get(index).cloned()
+            .ok_or_else(|| Error::Verify(format!("local {} past max_locals", index)))
+    }
+
+    pub(super) fn set_local(&mut self, index: usize, value: Value) -> Result<()> {
+        let wide = value.is_wide();
+        if index + wide as usize >= self.locals.len() {
+            return Err(Error::Verify(format!("local {} past max_locals", index)));
+        }
+        self.locals[index] = value;
+        if wide {
+            self.locals[index + 1] = Value::Top;
+        }
+        Ok(())
+    }
+
+    // Source line of the current instruction
+    pub(super) fn line_number(&self) -> Option<u16> {
+        let code = self.method.code.as_ref()?;
+        let pc = code.instructions.get(self.ip)?.pc;
+        code.attribute.line_number(pc as u16)
+    }
+}
@@ -0,0 +1,82 @@
+use class_file::descriptor::FieldType;
+use result::{Result, Error};
+use vm::heap::ObjectRef;
+
+// A local variable, an operand stack entry, a field or an array element. Longs and doubles are one
+// entry on the operand stack and two local variable slots, the second of which holds Top
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Null,
+    Ref(ObjectRef),
+    // Pushed by jsr, only class files older than version 51 have it
+    ReturnAddress(u32),
+    Top,
+}
+
+impl Value {
+    // What fields and array elements start out as
+    pub fn zero(field_type: &FieldType) -> Self {
+        match *field_type {
+            FieldType::Long => Value::Long(0),
+            FieldType::Float => Value::Float(0.0),
+            FieldType::Double => Value::Double(0.0),
+            FieldType::Object(_) | FieldType::Array(_) => Value::Null,
+            _ => Value::Int(0),
+        }
+    }
+
+    pub fn from_ref(reference: Option<ObjectRef>) -> Self {
+        reference.map_or(Value::Null, Value::Ref)
+    }
+
+    // Longs and doubles, what pop2 and dup2 treat as a single value
+    pub fn is_wide(self) -> bool {
+        matches!(self, Value::Long(_) | Value::Double(_))
+    }
+
+    // The interpreter doesn't verify, a value of the wrong type means the class file is broken
+    pub fn as_int(self) -> Result<i32> {
+        match self {
+            Value::Int(value) => Ok(value),
+            other => Err(mismatch("int", other)),
+        }
+    }
+
+    pub fn as_long(self) -> Result<i64> {
+        match self {
+            Value::Long(value) => Ok(value),
+            other => Err(mismatch("long", other)),
+        }
+    }
+
+    pub fn as_float(self) -> Result<f32> {
+        match self {
+            Value::Float(value) => Ok(value),
+            other => Err(mismatch("float", other)),
+        }
+    }
+
+    pub fn as_double(self) -> Result<f64> {
+        match self {
+            Value::Double(value) => Ok(value),
+            other => Err(mismatch("double", other)),
+        }
+    }
+
+    // None for null
+    pub fn as_ref(self) -> Result<Option<ObjectRef>> {
+        match self {
+            Value::Null => Ok(None),
+            Value::Ref(reference) => Ok(Some(reference)),
+            other => Err(mismatch("reference", other)),
+        }
+    }
+}
+
+fn mismatch(expected: &str, actual: Value) -> Error {
+    Error::Verify(format!("expected {}, found {:?}", expected, actual))
+}